@@ -0,0 +1,62 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Compile-time `phf` command lookup vs the runtime `HashMap` it replaced.
+//!
+//! The old table was built on the first request; `hashmap_init` measures that
+//! one-off cost, which the phf map doesn't pay at all.
+//!
+//! Run with `cargo bench --bench command_lookup`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use bunnylol::BunnylolCommandRegistry;
+use bunnylol::bunnylol_command_registry::CommandHandler;
+use bunnylol::commands::bunnylol_command::RegisteredCommand;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+const BINDINGS: &[&str] = &["gh", "yt", "stock", "cmds", "nope"];
+
+/// The table the registry used to build on first access
+fn build_hashmap() -> HashMap<&'static str, CommandHandler> {
+    let mut map = HashMap::new();
+    for command in bunnylol::inventory::iter::<RegisteredCommand> {
+        for alias in command.bindings {
+            map.insert(*alias, command.process_args);
+        }
+    }
+    map
+}
+
+fn command_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("command_lookup");
+
+    group.bench_function("hashmap_init", |b| b.iter(|| black_box(build_hashmap())));
+
+    let map = build_hashmap();
+    group.bench_function("hashmap", |b| {
+        b.iter(|| {
+            for binding in BINDINGS {
+                black_box(map.get(black_box(binding)));
+            }
+        })
+    });
+
+    group.bench_function("phf", |b| {
+        b.iter(|| {
+            for binding in BINDINGS {
+                black_box(BunnylolCommandRegistry::get_handler(black_box(binding)));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, command_lookup);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The per-query hot path: bare commands, which return a fixed URL without
+//! allocating, commands that encode long arguments, and alias expansion.
+//!
+//! Run with `cargo bench --bench hot_path`.
+
+use std::hint::black_box;
+
+use bunnylol::{BunnylolCommandRegistry, BunnylolConfig, utils};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// Commands that need no arguments
+const BARE: &[&str] = &["gh", "yt", "cargo", "claude", "bindings"];
+
+/// Commands whose arguments get parsed and encoded
+const ARG_HEAVY: &[&str] = &[
+    "gh rust-lang/rust",
+    "r r/rust async runtime comparison tokio vs smol",
+    "amazon usb-c to usb-c cable 2m braided 240w",
+    "ddg \"exact phrase\" with some extra search terms & symbols",
+];
+
+fn process(query: &str) {
+    let command = utils::get_command_from_query_string(query);
+    black_box(BunnylolCommandRegistry::process_command(command, query).ok());
+}
+
+fn hot_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hot_path");
+
+    group.bench_function("bare_command", |b| {
+        b.iter(|| {
+            for query in BARE {
+                process(black_box(query));
+            }
+        })
+    });
+
+    group.bench_function("arg_heavy", |b| {
+        b.iter(|| {
+            for query in ARG_HEAVY {
+                process(black_box(query));
+            }
+        })
+    });
+
+    let mut config = BunnylolConfig::default();
+    config
+        .aliases
+        .insert("work".to_string(), "gh mycompany/repo".to_string());
+    config
+        .aliases
+        .insert("rr".to_string(), "r r/rust".to_string());
+    group.bench_function("alias_expansion", |b| {
+        b.iter(|| {
+            for query in ["work", "rr", "gh"] {
+                process(&config.resolve_command(black_box(query)));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, hot_path);
+criterion_main!(benches);
@@ -0,0 +1,245 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Reads every command's bindings straight out of `src/commands/*.rs`, from
+//! `#[bunnylol(bindings(...))]` attributes and hand-written `const BINDINGS`
+//! items, and:
+//!
+//! - fails the build when two commands claim the same binding, since the
+//!   registry can't see a conflicting alias until it's too late
+//! - generates the registry's binding → command lookup as a `phf::Map`, so
+//!   resolving a command needs no table built at runtime
+//!
+//! Collisions are checked across every command, but the lookup only holds
+//! the ones whose feature group (a `#[cfg(feature = ...)]` on their `mod` in
+//! `src/commands/mod.rs`) is enabled.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use syn::punctuated::Punctuated;
+use syn::{Expr, ImplItem, Item, Lit, LitStr, Meta, Token};
+
+const COMMANDS_DIR: &str = "src/commands";
+const COMMANDS_MOD: &str = "src/commands/mod.rs";
+
+/// A command's name and the file it's defined in
+struct Owner {
+    command: String,
+    file: String,
+}
+
+impl Owner {
+    /// The command's `RegisteredCommand`, as an expression in the registry module
+    fn handler(&self) -> String {
+        let module = Path::new(&self.file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        format!(
+            "RegisteredCommand::of::<crate::commands::{}::{}>()",
+            module, self.command
+        )
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", COMMANDS_DIR);
+
+    let mut files: Vec<_> = fs::read_dir(COMMANDS_DIR)
+        .expect("src/commands should exist")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+
+    let features = module_features();
+    let mut owners: BTreeMap<String, Owner> = BTreeMap::new();
+    let mut disabled: Vec<String> = Vec::new();
+    let mut collisions = Vec::new();
+    for path in &files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let enabled = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|module| features.get(module))
+            .is_none_or(|feature| feature_enabled(feature));
+        for (command, bindings) in command_bindings(path) {
+            if !enabled {
+                disabled.extend(bindings.iter().cloned());
+            }
+            for binding in bindings {
+                let owner = Owner {
+                    command: command.clone(),
+                    file: path.display().to_string(),
+                };
+                if let Some(existing) = owners.get(&binding) {
+                    collisions.push(format!(
+                        "binding '{}' is used by both {} ({}) and {} ({})",
+                        binding, existing.command, existing.file, owner.command, owner.file
+                    ));
+                } else {
+                    owners.insert(binding, owner);
+                }
+            }
+        }
+    }
+
+    if !collisions.is_empty() {
+        for collision in &collisions {
+            println!("cargo:warning={}", collision);
+        }
+        panic!(
+            "found {} binding collision(s):\n{}",
+            collisions.len(),
+            collisions.join("\n")
+        );
+    }
+
+    owners.retain(|binding, _| !disabled.contains(binding));
+    write_lookup(&owners);
+}
+
+/// The feature each command module in `src/commands/mod.rs` is gated on
+fn module_features() -> BTreeMap<String, String> {
+    let source = fs::read_to_string(COMMANDS_MOD).expect("src/commands/mod.rs should exist");
+    let file = syn::parse_file(&source).expect("src/commands/mod.rs should parse");
+
+    let mut features = BTreeMap::new();
+    for item in file.items {
+        let Item::Mod(item) = item else {
+            continue;
+        };
+        for attr in item.attrs.iter().filter(|attr| attr.path().is_ident("cfg")) {
+            let Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            let _ = list.parse_nested_meta(|meta| {
+                if meta.path.is_ident("feature") {
+                    let feature: LitStr = meta.value()?.parse()?;
+                    features.insert(item.ident.to_string(), feature.value());
+                }
+                Ok(())
+            });
+        }
+    }
+    features
+}
+
+/// Whether cargo is building with `feature`
+fn feature_enabled(feature: &str) -> bool {
+    let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    std::env::var_os(var).is_some()
+}
+
+/// Write `COMMAND_LOOKUP` to `$OUT_DIR/command_lookup.rs`, which the registry
+/// `include!`s
+fn write_lookup(owners: &BTreeMap<String, Owner>) {
+    let mut map = phf_codegen::Map::new();
+    for (binding, owner) in owners {
+        map.entry(binding.as_str(), &owner.handler());
+    }
+    let path = PathBuf::from(std::env::var("OUT_DIR").expect("cargo sets OUT_DIR"))
+        .join("command_lookup.rs");
+    let mut file = fs::File::create(&path).expect("OUT_DIR should be writable");
+    writeln!(
+        file,
+        "/// Every binding mapped to its command's handlers, generated by build.rs\n\
+         static COMMAND_LOOKUP: phf::Map<&'static str, RegisteredCommand> = {};",
+        map.build()
+    )
+    .expect("OUT_DIR should be writable");
+}
+
+/// The bindings of every command defined at the top level of `path`; test
+/// modules are skipped, since their fixtures are never registered
+fn command_bindings(path: &Path) -> Vec<(String, Vec<String>)> {
+    let source = fs::read_to_string(path).expect("command file should be readable");
+    let file = syn::parse_file(&source)
+        .unwrap_or_else(|error| panic!("failed to parse {}: {}", path.display(), error));
+
+    let mut commands = Vec::new();
+    for item in file.items {
+        match item {
+            Item::Struct(item) => {
+                if let Some(bindings) = derived_bindings(&item.attrs) {
+                    commands.push((item.ident.to_string(), bindings));
+                }
+            }
+            Item::Impl(item) => {
+                let is_command = item.trait_.as_ref().is_some_and(|(_, path, _)| {
+                    path.segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "BunnylolCommand")
+                });
+                if !is_command {
+                    continue;
+                }
+                let name = match item.self_ty.as_ref() {
+                    syn::Type::Path(ty) => ty
+                        .path
+                        .segments
+                        .last()
+                        .map(|segment| segment.ident.to_string())
+                        .unwrap_or_default(),
+                    _ => continue,
+                };
+                for impl_item in item.items {
+                    if let ImplItem::Const(item) = impl_item
+                        && item.ident == "BINDINGS"
+                    {
+                        commands.push((name.clone(), string_literals(&item.expr)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    commands
+}
+
+/// `bindings(...)` from a `#[derive(BunnylolCommand)]` struct's `#[bunnylol]`
+/// attribute, unless it's marked `unregistered`
+fn derived_bindings(attrs: &[syn::Attribute]) -> Option<Vec<String>> {
+    let mut bindings = None;
+    let mut unregistered = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bunnylol")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bindings") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let literals = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                bindings = Some(literals.iter().map(LitStr::value).collect());
+            } else if meta.path.is_ident("unregistered") {
+                unregistered = true;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<proc_macro2::TokenStream>()?;
+            }
+            Ok(())
+        });
+    }
+    bindings.filter(|_| !unregistered)
+}
+
+/// Every string literal in an expression like `&["gh", "github"]`
+fn string_literals(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::Reference(expr) => string_literals(&expr.expr),
+        Expr::Array(expr) => expr.elems.iter().flat_map(string_literals).collect(),
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(lit) => vec![lit.value()],
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
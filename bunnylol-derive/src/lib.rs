@@ -0,0 +1,257 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `#[derive(BunnylolCommand)]`: generates a command's `BINDINGS` and
+//! `get_info()` from a `#[bunnylol(...)]` attribute.
+//!
+//! ```ignore
+//! #[derive(BunnylolCommand)]
+//! #[bunnylol(
+//!     bindings("gh", "github"),
+//!     description = "Navigate to GitHub",
+//!     example = "gh facebook/react",
+//!     examples("gh @octocat", "gh rust http client"),
+//!     args = "[@<user> | <owner>/<repo> | <search terms>]",
+//!     category = Dev,
+//!     tags("git", "code", "repositories"),
+//!     usage("gh" = "Open GitHub", "gh <owner>/<repo>" = "Open a repository"),
+//!     config_keys("github.host"),
+//! )]
+//! pub struct GitHubCommand;
+//!
+//! impl GitHubCommand {
+//!     fn process_args(args: &str) -> String { ... }
+//! }
+//! ```
+//!
+//! The command still writes `process_args` itself, as an inherent method; the
+//! generated trait impl forwards to it. It may return `String`,
+//! `Cow<'static, str>` (so fixed URLs don't allocate), or, if it can reject
+//! its arguments, a `Result` of either with `CommandError`. A command that needs
+//! network I/O adds `async_handler` and also writes an inherent
+//! `async fn process_args_async(args: &str) -> Result<String, CommandError>`.
+//! A command with `subcommands` lists them in an inherent
+//! `const SUBCOMMANDS: &[Subcommand]`, which becomes the trait's and is shown
+//! on its help page; its `process_args` calls `Self::process_subcommand`
+//! first. The command is also registered with
+//! `BunnylolCommandRegistry`, unless it's generic or marked
+//! `#[bunnylol(unregistered)]` (e.g. a test fixture).
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{DeriveInput, Ident, LitStr, Token, parenthesized, parse_macro_input};
+
+#[proc_macro_derive(BunnylolCommand, attributes(bunnylol))]
+pub fn derive_bunnylol_command(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// One `"syntax" = "description"` pair in `usage(...)`
+struct Usage {
+    syntax: LitStr,
+    description: LitStr,
+}
+
+impl syn::parse::Parse for Usage {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let syntax = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let description = input.parse()?;
+        Ok(Usage {
+            syntax,
+            description,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Attributes {
+    bindings: Vec<LitStr>,
+    description: Option<LitStr>,
+    example: Option<LitStr>,
+    examples: Vec<LitStr>,
+    args: Option<LitStr>,
+    category: Option<Ident>,
+    tags: Vec<LitStr>,
+    usage: Vec<Usage>,
+    config_keys: Vec<LitStr>,
+    unregistered: bool,
+    async_handler: bool,
+    subcommands: bool,
+}
+
+fn parse_strings(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<LitStr>> {
+    let content;
+    parenthesized!(content in meta.input);
+    Ok(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+        .into_iter()
+        .collect())
+}
+
+fn parse_attributes(input: &DeriveInput) -> syn::Result<Attributes> {
+    let mut attributes = Attributes::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("bunnylol")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bindings") {
+                attributes.bindings = parse_strings(&meta)?;
+            } else if meta.path.is_ident("description") {
+                attributes.description = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("example") {
+                attributes.example = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("examples") {
+                attributes.examples = parse_strings(&meta)?;
+            } else if meta.path.is_ident("args") {
+                attributes.args = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("tags") {
+                attributes.tags = parse_strings(&meta)?;
+            } else if meta.path.is_ident("category") {
+                attributes.category = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("usage") {
+                let content;
+                parenthesized!(content in meta.input);
+                attributes.usage = Punctuated::<Usage, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            } else if meta.path.is_ident("config_keys") {
+                attributes.config_keys = parse_strings(&meta)?;
+            } else if meta.path.is_ident("unregistered") {
+                attributes.unregistered = true;
+            } else if meta.path.is_ident("async_handler") {
+                attributes.async_handler = true;
+            } else if meta.path.is_ident("subcommands") {
+                attributes.subcommands = true;
+            } else {
+                return Err(meta.error(
+                    "expected bindings, description, example, examples, args, category, tags, usage, config_keys, unregistered, async_handler, or subcommands",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attributes)
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let attributes = parse_attributes(input)?;
+    let missing = |what: &str| {
+        syn::Error::new_spanned(
+            name,
+            format!("#[derive(BunnylolCommand)] needs #[bunnylol({})]", what),
+        )
+    };
+    if attributes.bindings.is_empty() {
+        return Err(missing("bindings(\"...\")"));
+    }
+    let description = attributes
+        .description
+        .ok_or_else(|| missing("description = \"...\""))?;
+    let example = attributes
+        .example
+        .ok_or_else(|| missing("example = \"...\""))?;
+
+    let bindings = &attributes.bindings;
+    let category = attributes.category.map(|category| {
+        quote! { .with_category(::bunnylol::CommandCategory::#category) }
+    });
+    let examples = (!attributes.examples.is_empty()).then(|| {
+        let examples = &attributes.examples;
+        quote! { .with_examples(&[#(#examples),*]) }
+    });
+    let args = attributes.args.map(|args| quote! { .with_args(#args) });
+    let tags = (!attributes.tags.is_empty()).then(|| {
+        let tags = &attributes.tags;
+        quote! { .with_tags(&[#(#tags),*]) }
+    });
+    let usage = (!attributes.usage.is_empty()).then(|| {
+        let pairs = attributes.usage.iter().map(|usage| {
+            let (syntax, description) = (&usage.syntax, &usage.description);
+            quote! { (#syntax, #description) }
+        });
+        quote! { .with_usage(&[#(#pairs),*]) }
+    });
+    let config_keys = (!attributes.config_keys.is_empty()).then(|| {
+        let keys = &attributes.config_keys;
+        quote! { .with_config_keys(&[#(#keys),*]) }
+    });
+
+    let (subcommands_const, subcommands_info) = attributes
+        .subcommands
+        .then(|| {
+            (
+                quote! {
+                    const SUBCOMMANDS: &'static [::bunnylol::commands::bunnylol_command::Subcommand] =
+                        #name::SUBCOMMANDS;
+                },
+                quote! { .with_subcommands(Self::BINDINGS[0], Self::SUBCOMMANDS) },
+            )
+        })
+        .unzip();
+
+    let process_args_async = attributes.async_handler.then(|| {
+        quote! {
+            fn process_args_async(
+                args: &str,
+            ) -> ::bunnylol::commands::bunnylol_command::CommandFuture<'_> {
+                ::std::boxed::Box::pin(async move {
+                    ::bunnylol::commands::bunnylol_command::IntoCommandResult::into_command_result(
+                        #name::process_args_async(args).await,
+                    )
+                })
+            }
+        }
+    });
+
+    // A generic command has no single type to register
+    let register = (!attributes.unregistered && input.generics.params.is_empty()).then(|| {
+        quote! {
+            ::bunnylol::inventory::submit! {
+                ::bunnylol::commands::bunnylol_command::RegisteredCommand::of::<#name>()
+            }
+        }
+    });
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bunnylol::BunnylolCommand for #name #type_generics #where_clause {
+            const BINDINGS: &'static [&'static str] = &[#(#bindings),*];
+            #subcommands_const
+
+            fn process_args(
+                args: &str,
+            ) -> ::std::result::Result<
+                ::std::borrow::Cow<'static, str>,
+                ::bunnylol::CommandError,
+            > {
+                // Inherent methods take precedence, so this is the command's own
+                ::bunnylol::commands::bunnylol_command::IntoCommandResult::into_command_result(
+                    #name::process_args(args),
+                )
+            }
+
+            #process_args_async
+
+            fn get_info() -> ::bunnylol::BunnylolCommandInfo {
+                ::bunnylol::BunnylolCommandInfo::new(Self::BINDINGS, #description, #example)
+                    #examples
+                    #args
+                    #category
+                    #tags
+                    #usage
+                    #subcommands_info
+                    #config_keys
+            }
+        }
+
+        #register
+    })
+}
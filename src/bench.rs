@@ -0,0 +1,434 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `bunnylol bench`: send a running server a stream of queries from several
+//! connections at once and report how quickly it answered
+//!
+//! Queries are taken in turn from each kind (built-in commands, aliases, and
+//! fallback searches), so a slow alias index or history backend shows up in
+//! its own row. Every request goes through the server's normal path, history
+//! and middleware included.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::BunnylolCommandRegistry;
+use crate::config::BunnylolConfig;
+use crate::history_stats::percentile;
+use crate::resolver::{self, ResolutionLayer};
+use crate::utils::url_encoding::encode_url;
+
+/// How long to wait for a single response before counting it as an error
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Searches the default mix sends when nothing else claims them
+const FALLBACK_QUERIES: &[&str] = &[
+    "how to exit vim",
+    "rust borrow checker lifetimes",
+    "weather tomorrow",
+    "best pizza near me",
+];
+
+/// Which layer of the server answers a query
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QueryKind {
+    Command,
+    Alias,
+    Fallback,
+}
+
+impl QueryKind {
+    /// How `query` resolves with `config`
+    pub fn of(config: &BunnylolConfig, query: &str) -> Self {
+        match resolver::resolve(config, query).layer {
+            ResolutionLayer::Alias => QueryKind::Alias,
+            ResolutionLayer::Fallback => QueryKind::Fallback,
+            _ => QueryKind::Command,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            QueryKind::Command => "command",
+            QueryKind::Alias => "alias",
+            QueryKind::Fallback => "fallback",
+        }
+    }
+}
+
+/// The queries a benchmark sends, grouped by kind
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueryMix {
+    groups: Vec<(QueryKind, Vec<String>)>,
+}
+
+impl QueryMix {
+    /// Every built-in command's example, every alias in `config`, and a few
+    /// plain searches
+    pub fn from_config(config: &BunnylolConfig) -> Self {
+        let mut queries: Vec<String> = BunnylolCommandRegistry::get_all_commands()
+            .iter()
+            .map(|info| info.example.clone())
+            .collect();
+        let mut aliases: Vec<&String> = config.aliases.keys().collect();
+        aliases.sort();
+        queries.extend(aliases.into_iter().cloned());
+        queries.extend(FALLBACK_QUERIES.iter().map(|query| query.to_string()));
+        Self::new(config, queries)
+    }
+
+    /// `queries`, grouped by how they resolve with `config`
+    pub fn new(config: &BunnylolConfig, queries: Vec<String>) -> Self {
+        let mut groups: BTreeMap<QueryKind, Vec<String>> = BTreeMap::new();
+        for query in queries {
+            let query = query.trim().to_string();
+            if !query.is_empty() {
+                groups
+                    .entry(QueryKind::of(config, &query))
+                    .or_default()
+                    .push(query);
+            }
+        }
+        QueryMix {
+            groups: groups.into_iter().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The query to send as request `n`: kinds take turns, and each kind
+    /// cycles through its queries
+    pub fn query(&self, n: usize) -> (QueryKind, &str) {
+        let (kind, queries) = &self.groups[n % self.groups.len()];
+        (*kind, &queries[(n / self.groups.len()) % queries.len()])
+    }
+}
+
+/// One request's outcome
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub kind: QueryKind,
+    pub latency: Duration,
+    /// Why the request failed, if it did
+    pub error: Option<String>,
+}
+
+/// Send `requests` queries from `mix` to the server at `host`:`port`, with
+/// `concurrency` requests in flight at a time
+pub fn run(
+    host: &str,
+    port: u16,
+    mix: &QueryMix,
+    requests: usize,
+    concurrency: usize,
+) -> Vec<Sample> {
+    let next = AtomicUsize::new(0);
+    let samples = Mutex::new(Vec::with_capacity(requests));
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, requests.max(1)) {
+            scope.spawn(|| {
+                let mut local = Vec::new();
+                loop {
+                    let n = next.fetch_add(1, Ordering::Relaxed);
+                    if n >= requests {
+                        break;
+                    }
+                    let (kind, query) = mix.query(n);
+                    let start = Instant::now();
+                    let error = send(host, port, query).err();
+                    local.push(Sample {
+                        kind,
+                        latency: start.elapsed(),
+                        error,
+                    });
+                }
+                samples.lock().unwrap().extend(local);
+            });
+        }
+    });
+    samples.into_inner().unwrap()
+}
+
+/// Send one query, succeeding when the server redirects or renders a page
+fn send(host: &str, port: u16, query: &str) -> Result<(), String> {
+    let request = || -> std::io::Result<String> {
+        let mut stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        write!(
+            stream,
+            "GET /?cmd={} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            encode_url(query),
+            host
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    };
+    let response = request().map_err(|e| e.to_string())?;
+    match response.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') || status.starts_with('3') => Ok(()),
+        Some(status) => Err(format!("HTTP {}", status)),
+        None => Err("empty response".to_string()),
+    }
+}
+
+/// Latency and errors for one group of requests
+#[derive(Debug, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub requests: usize,
+    pub errors: usize,
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+impl LatencySummary {
+    fn of<'a>(samples: impl IntoIterator<Item = &'a Sample>) -> Self {
+        let mut errors = 0;
+        let mut latencies: Vec<u64> = samples
+            .into_iter()
+            .inspect(|sample| errors += usize::from(sample.error.is_some()))
+            .map(|sample| sample.latency.as_micros() as u64)
+            .collect();
+        latencies.sort_unstable();
+        let at = |pct| {
+            if latencies.is_empty() {
+                0
+            } else {
+                percentile(&latencies, pct)
+            }
+        };
+        LatencySummary {
+            requests: latencies.len(),
+            errors,
+            p50_us: at(50),
+            p90_us: at(90),
+            p99_us: at(99),
+            max_us: latencies.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// A benchmark's results: totals, a summary per kind, and the errors seen
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchReport {
+    pub target: String,
+    pub concurrency: usize,
+    pub elapsed: Duration,
+    pub total: LatencySummary,
+    pub by_kind: Vec<(QueryKind, LatencySummary)>,
+    /// Each distinct error with how often it happened, most frequent first
+    pub errors: Vec<(String, usize)>,
+}
+
+impl BenchReport {
+    pub fn new(target: String, concurrency: usize, elapsed: Duration, samples: &[Sample]) -> Self {
+        let mut kinds: Vec<QueryKind> = samples.iter().map(|sample| sample.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        let by_kind = kinds
+            .into_iter()
+            .map(|kind| {
+                let summary = LatencySummary::of(samples.iter().filter(|s| s.kind == kind));
+                (kind, summary)
+            })
+            .collect();
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for error in samples.iter().filter_map(|sample| sample.error.as_deref()) {
+            *counts.entry(error).or_default() += 1;
+        }
+        let mut errors: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(error, count)| (error.to_string(), count))
+            .collect();
+        errors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        BenchReport {
+            target,
+            concurrency,
+            elapsed,
+            total: LatencySummary::of(samples),
+            by_kind,
+            errors,
+        }
+    }
+}
+
+/// Microseconds as milliseconds, e.g. "1.25ms"
+fn format_ms(us: u64) -> String {
+    format!("{:.2}ms", us as f64 / 1000.0)
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.elapsed.as_secs_f64();
+        let rate = if secs > 0.0 {
+            self.total.requests as f64 / secs
+        } else {
+            0.0
+        };
+        writeln!(
+            f,
+            "Sent {} requests to {} ({} at a time) in {:.2}s: {:.1} requests/s",
+            self.total.requests, self.target, self.concurrency, secs, rate
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<8}  {:>8}  {:>14}  {:>9}  {:>9}  {:>9}  {:>9}",
+            "kind", "requests", "errors", "p50", "p90", "p99", "max"
+        )?;
+        let rows = self
+            .by_kind
+            .iter()
+            .map(|(kind, summary)| (kind.name(), summary))
+            .chain(std::iter::once(("all", &self.total)));
+        for (name, summary) in rows {
+            let error_rate = if summary.requests == 0 {
+                0.0
+            } else {
+                summary.errors as f64 * 100.0 / summary.requests as f64
+            };
+            writeln!(
+                f,
+                "{:<8}  {:>8}  {:>14}  {:>9}  {:>9}  {:>9}  {:>9}",
+                name,
+                summary.requests,
+                format!("{} ({:.1}%)", summary.errors, error_rate),
+                format_ms(summary.p50_us),
+                format_ms(summary.p90_us),
+                format_ms(summary.p99_us),
+                format_ms(summary.max_us)
+            )?;
+        }
+        if !self.errors.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Errors:")?;
+            for (error, count) in &self.errors {
+                writeln!(f, "  {:>6}  {}", count, error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(kind: QueryKind, ms: u64, error: Option<&str>) -> Sample {
+        Sample {
+            kind,
+            latency: Duration::from_millis(ms),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn test_query_mix_takes_turns() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mbinns".to_string());
+        let mix = QueryMix::new(
+            &config,
+            vec![
+                "gh facebook/react".to_string(),
+                "how to exit vim".to_string(),
+                "work".to_string(),
+                "ig reels".to_string(),
+                " ".to_string(),
+            ],
+        );
+        let sent: Vec<(QueryKind, &str)> = (0..6).map(|n| mix.query(n)).collect();
+        assert_eq!(
+            sent,
+            [
+                (QueryKind::Command, "gh facebook/react"),
+                (QueryKind::Alias, "work"),
+                (QueryKind::Fallback, "how to exit vim"),
+                (QueryKind::Command, "ig reels"),
+                (QueryKind::Alias, "work"),
+                (QueryKind::Fallback, "how to exit vim"),
+            ]
+        );
+        assert!(QueryMix::new(&config, Vec::new()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_default_mix_covers_every_kind() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mbinns".to_string());
+        let mix = QueryMix::from_config(&config);
+        let kinds: Vec<QueryKind> = mix.groups.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            [QueryKind::Command, QueryKind::Alias, QueryKind::Fallback]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut samples: Vec<Sample> = (1..=100)
+            .map(|ms| sample(QueryKind::Command, ms, None))
+            .collect();
+        samples.push(sample(QueryKind::Fallback, 5, Some("HTTP 500")));
+        samples.push(sample(QueryKind::Fallback, 7, Some("HTTP 500")));
+        samples.push(sample(QueryKind::Fallback, 9, Some("connection refused")));
+
+        let report = BenchReport::new(
+            "127.0.0.1:8000".to_string(),
+            4,
+            Duration::from_secs(2),
+            &samples,
+        );
+        let command = &report.by_kind[0].1;
+        assert_eq!(report.by_kind[0].0, QueryKind::Command);
+        assert_eq!(
+            (
+                command.p50_us,
+                command.p90_us,
+                command.p99_us,
+                command.max_us
+            ),
+            (50_000, 90_000, 99_000, 100_000)
+        );
+        assert_eq!(report.by_kind[1].1.errors, 3);
+        assert_eq!(report.total.requests, 103);
+        assert_eq!(
+            report.errors,
+            [
+                ("HTTP 500".to_string(), 2),
+                ("connection refused".to_string(), 1)
+            ]
+        );
+
+        let text = report.to_string();
+        assert!(text.starts_with(
+            "Sent 103 requests to 127.0.0.1:8000 (4 at a time) in 2.00s: 51.5 requests/s\n"
+        ));
+        assert!(
+            text.contains("\nfallback         3      3 (100.0%)"),
+            "{}",
+            text
+        );
+        assert!(text.contains("\nErrors:\n       2  HTTP 500\n"), "{}", text);
+    }
+}
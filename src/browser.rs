@@ -0,0 +1,303 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Opening resolved URLs from the CLI, in the browser `[browsers]` picks for
+//! the command, or `browser`, or the OS default
+//!
+//! A browser on its own is started the way the `open` crate starts apps on
+//! each platform. Starting a particular profile takes browser-specific flags,
+//! so profiles only work for the browsers in `KNOWN_BROWSERS`.
+
+use std::process::{Command, Stdio};
+
+use crate::BunnylolCommandRegistry;
+use crate::config::{BrowserChoice, BunnylolConfig};
+use crate::utils;
+
+/// How a browser is told which profile to use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProfileFlag {
+    /// `--profile-directory=<dir>`, for Chromium-based browsers
+    Directory,
+    /// `-P <name>`, for Firefox
+    Name,
+}
+
+/// A browser that can be started with a profile, with what it's called on
+/// each platform
+struct KnownBrowser {
+    /// Names it goes by in the config, lowercase
+    names: &'static [&'static str],
+    flag: ProfileFlag,
+    /// Executable on Linux and other Unix systems
+    #[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+    executable: &'static str,
+    /// Application name for `open -a` on macOS
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    app: &'static str,
+    /// Name `start` finds it by on Windows
+    #[cfg_attr(not(windows), allow(dead_code))]
+    windows: &'static str,
+}
+
+const KNOWN_BROWSERS: &[KnownBrowser] = &[
+    KnownBrowser {
+        names: &["chrome", "google-chrome", "google chrome"],
+        flag: ProfileFlag::Directory,
+        executable: "google-chrome",
+        app: "Google Chrome",
+        windows: "chrome",
+    },
+    KnownBrowser {
+        names: &["chromium", "chromium-browser"],
+        flag: ProfileFlag::Directory,
+        executable: "chromium",
+        app: "Chromium",
+        windows: "chromium",
+    },
+    KnownBrowser {
+        names: &["edge", "msedge", "microsoft-edge", "microsoft edge"],
+        flag: ProfileFlag::Directory,
+        executable: "microsoft-edge",
+        app: "Microsoft Edge",
+        windows: "msedge",
+    },
+    KnownBrowser {
+        names: &["brave", "brave-browser", "brave browser"],
+        flag: ProfileFlag::Directory,
+        executable: "brave-browser",
+        app: "Brave Browser",
+        windows: "brave",
+    },
+    KnownBrowser {
+        names: &["vivaldi"],
+        flag: ProfileFlag::Directory,
+        executable: "vivaldi",
+        app: "Vivaldi",
+        windows: "vivaldi",
+    },
+    KnownBrowser {
+        names: &["firefox"],
+        flag: ProfileFlag::Name,
+        executable: "firefox",
+        app: "Firefox",
+        windows: "firefox",
+    },
+];
+
+fn known_browser(name: &str) -> Option<&'static KnownBrowser> {
+    let name = name.trim().to_lowercase();
+    KNOWN_BROWSERS
+        .iter()
+        .find(|known| known.names.contains(&name.as_str()))
+}
+
+/// Why `choice` can't be opened, if it can't
+pub fn check_choice(choice: &BrowserChoice) -> Result<(), String> {
+    if choice.profile().is_some() && known_browser(choice.browser()).is_none() {
+        return Err(format!(
+            "profiles aren't supported for '{}'; use chrome, chromium, edge, brave, vivaldi, or firefox",
+            choice.browser()
+        ));
+    }
+    Ok(())
+}
+
+/// The browser for the URL `query` resolved to by `command`: the
+/// `[browsers]` entry for the alias it starts with or for any binding of
+/// `command`, else `browser`; `None` means the OS default
+///
+/// Chained queries, which have no single command, use `browser`.
+pub fn browser_for(
+    config: &BunnylolConfig,
+    query: &str,
+    command: Option<&str>,
+) -> Option<BrowserChoice> {
+    let default = || config.browser.clone().map(BrowserChoice::Browser);
+    let Some(command) = command else {
+        return default();
+    };
+    let bindings = BunnylolCommandRegistry::get_command_info(command)
+        .map(|info| info.bindings.as_slice())
+        .unwrap_or_default();
+    [utils::get_command_from_query_string(query), command]
+        .into_iter()
+        .chain(bindings.iter().map(String::as_str))
+        .find_map(|binding| config.browsers.get(binding))
+        .cloned()
+        .or_else(default)
+}
+
+/// Open `url` in `browser`, or the OS default browser
+pub fn open(url: &str, browser: Option<&BrowserChoice>) -> Result<(), String> {
+    match browser {
+        None => open::that(url).map_err(|e| format!("Failed to open browser: {}", e)),
+        Some(BrowserChoice::Browser(browser)) => open::with(url, browser)
+            .map_err(|e| format!("Failed to open browser '{}': {}", browser, e)),
+        Some(choice @ BrowserChoice::Profile { browser, profile }) => {
+            check_choice(choice)?;
+            let known = known_browser(browser).expect("checked above");
+            launch_command(known, profile, url)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(drop)
+                .map_err(|e| {
+                    format!(
+                        "Failed to open browser '{}' with profile '{}': {}",
+                        browser, profile, e
+                    )
+                })
+        }
+    }
+}
+
+/// The flags that start `known` with `profile`
+fn profile_args(known: &KnownBrowser, profile: &str) -> Vec<String> {
+    match known.flag {
+        ProfileFlag::Directory => vec![format!("--profile-directory={}", profile)],
+        ProfileFlag::Name => vec!["-P".to_string(), profile.to_string()],
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn launch_command(known: &KnownBrowser, profile: &str, url: &str) -> Command {
+    let mut command = Command::new(known.executable);
+    command.args(profile_args(known, profile)).arg(url);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn launch_command(known: &KnownBrowser, profile: &str, url: &str) -> Command {
+    // -n, since a running browser would otherwise ignore --args
+    let mut command = Command::new("open");
+    command
+        .args(["-n", "-a", known.app, "--args"])
+        .args(profile_args(known, profile))
+        .arg(url);
+    command
+}
+
+#[cfg(windows)]
+fn launch_command(known: &KnownBrowser, profile: &str, url: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    // `start` finds browsers through App Paths, like the Run dialog. Every
+    // argument is quoted so cmd doesn't treat a URL's `&` as a separator.
+    let quoted: Vec<String> = profile_args(known, profile)
+        .into_iter()
+        .chain([url.to_string()])
+        .map(|arg| format!("\"{}\"", arg))
+        .collect();
+    let mut command = Command::new("cmd");
+    command.raw_arg(format!(
+        "/C start \"\" {} {}",
+        known.windows,
+        quoted.join(" ")
+    ));
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "dev")]
+    fn config() -> BunnylolConfig {
+        let mut config = BunnylolConfig {
+            browser: Some("safari".to_string()),
+            ..Default::default()
+        };
+        config
+            .aliases
+            .insert("work".to_string(), "gh mbinns".to_string());
+        config.browsers.insert(
+            "github".to_string(),
+            BrowserChoice::Profile {
+                browser: "chrome".to_string(),
+                profile: "Profile 1".to_string(),
+            },
+        );
+        config.browsers.insert(
+            "work".to_string(),
+            BrowserChoice::Browser("firefox".to_string()),
+        );
+        config
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_browser_for() {
+        let config = config();
+        // Any binding of the command matches
+        assert_eq!(
+            browser_for(&config, "gh facebook/react", Some("gh"))
+                .unwrap()
+                .profile(),
+            Some("Profile 1")
+        );
+        // The alias typed wins over its command
+        assert_eq!(
+            browser_for(&config, "work", Some("gh")),
+            Some(BrowserChoice::Browser("firefox".to_string()))
+        );
+        assert_eq!(
+            browser_for(&config, "yt cats", Some("yt")),
+            Some(BrowserChoice::Browser("safari".to_string()))
+        );
+        assert_eq!(
+            browser_for(&config, "gh rust; work", None),
+            Some(BrowserChoice::Browser("safari".to_string()))
+        );
+        assert_eq!(
+            browser_for(&BunnylolConfig::default(), "gh", Some("gh")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_choice() {
+        let profile = |browser: &str| BrowserChoice::Profile {
+            browser: browser.to_string(),
+            profile: "Work".to_string(),
+        };
+        assert!(check_choice(&profile("Google Chrome")).is_ok());
+        assert!(check_choice(&profile("firefox")).is_ok());
+        assert!(check_choice(&BrowserChoice::Browser("lynx".to_string())).is_ok());
+        assert!(
+            check_choice(&profile("lynx"))
+                .unwrap_err()
+                .contains("profiles aren't supported for 'lynx'")
+        );
+    }
+
+    #[test]
+    fn test_profile_args() {
+        assert_eq!(
+            profile_args(known_browser("edge").unwrap(), "Profile 2"),
+            ["--profile-directory=Profile 2"]
+        );
+        assert_eq!(
+            profile_args(known_browser("Firefox").unwrap(), "work"),
+            ["-P", "work"]
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_launch_command() {
+        let command = launch_command(
+            known_browser("chrome").unwrap(),
+            "Default",
+            "https://github.com",
+        );
+        assert_eq!(command.get_program(), "google-chrome");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["--profile-directory=Default", "https://github.com"]);
+    }
+}
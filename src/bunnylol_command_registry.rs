@@ -1,43 +1,74 @@
-use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::borrow::Cow;
+use std::sync::{LazyLock, OnceLock};
 
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::config::get_global_config;
+use crate::commands::bunnylol_command::{BunnylolCommandInfo, CommandError, RegisteredCommand};
+use crate::config::{BunnylolConfig, get_global_config};
+use crate::plugins::{self, PluginCommand};
+use crate::utils::args::ArgKind;
+use crate::utils::fuzzy::{FuzzyIndex, FuzzyMatch};
+use crate::utils::url_encoding::{TEMPLATE_PLACEHOLDER, expand_template};
 
 // Type alias for command handler functions
-type CommandHandler = fn(&str) -> String;
+pub type CommandHandler = fn(&str) -> Result<Cow<'static, str>, CommandError>;
 
-// Global command lookup table, initialized once on first access
-static COMMAND_LOOKUP: OnceLock<HashMap<&'static str, CommandHandler>> = OnceLock::new();
-static BINDINGS_DATA: OnceLock<Vec<BunnylolCommandInfo>> = OnceLock::new();
+// Global command lookup table, generated at compile time by build.rs
+include!(concat!(env!("OUT_DIR"), "/command_lookup.rs"));
 
-/// Macro to register all commands in one place
-/// This prevents bugs where a command is defined but not registered
-macro_rules! register_commands {
-    ($($cmd:ty),+ $(,)?) => {
-        /// Initialize the command lookup HashMap
-        /// Maps all command aliases to their handler functions
-        fn initialize_command_lookup() -> HashMap<&'static str, CommandHandler> {
-            let mut map = HashMap::new();
-
-            $(
-                for alias in <$cmd>::BINDINGS {
-                    map.insert(*alias, <$cmd>::process_args as CommandHandler);
-                }
-            )+
+/// A command reached by a leading prefix instead of a binding, e.g. `$META`
+pub struct PrefixCommand {
+    pub prefix: &'static str,
+    pub description: &'static str,
+    /// Called with the whole query, prefix included
+    pub handler: CommandHandler,
+}
 
-            map
-        }
+/// Built-in prefixes, checked before the config's `[prefixes]`
+pub static PREFIX_COMMANDS: &[PrefixCommand] = &[
+    #[cfg(feature = "finance")]
+    PrefixCommand {
+        prefix: "$",
+        description: "stock ticker, e.g. $META",
+        handler: |args| {
+            crate::commands::StockCommand::process_ticker(
+                crate::utils::get_command_from_query_string(args),
+            )
+            .map(Cow::Owned)
+        },
+    },
+    PrefixCommand {
+        prefix: "@",
+        description: "people lookup, e.g. @jane, in the configured people_source",
+        handler: |args| crate::commands::people::process_mention(args).map(Cow::Owned),
+    },
+    PrefixCommand {
+        prefix: "!",
+        description: "bang, e.g. !gh rust, run here or forwarded to DuckDuckGo",
+        handler: BunnylolCommandRegistry::process_bang,
+    },
+];
 
-        /// Get all registered command bindings
-        fn get_all_commands_impl() -> Vec<BunnylolCommandInfo> {
-            vec![
-                $(
-                    <$cmd>::get_info(),
-                )+
-            ]
-        }
-    };
+/// Where the text after a configured prefix goes in its URL
+pub const PREFIX_PLACEHOLDER: &str = TEMPLATE_PLACEHOLDER;
+
+/// Every binding, plugins' and scripts' included, indexed for typo lookups
+static BINDING_INDEX: LazyLock<FuzzyIndex> = LazyLock::new(|| {
+    FuzzyIndex::new(
+        COMMAND_LOOKUP
+            .keys()
+            .copied()
+            .chain(BunnylolCommandRegistry::extension_bindings()),
+    )
+});
+
+static BINDINGS_DATA: OnceLock<Vec<BunnylolCommandInfo>> = OnceLock::new();
+
+/// Every command registered by `#[derive(BunnylolCommand)]` or `register_command!`,
+/// ordered by primary binding so listings don't depend on link order
+fn registered_commands() -> Vec<&'static RegisteredCommand> {
+    let mut commands: Vec<&'static RegisteredCommand> =
+        inventory::iter::<RegisteredCommand>.into_iter().collect();
+    commands.sort_by_key(|command| command.bindings.first().copied());
+    commands
 }
 
 /// Bunnylol Command Registry that manages all Bunnylol commands
@@ -47,98 +78,502 @@ macro_rules! register_commands {
 pub struct BunnylolCommandRegistry;
 
 impl BunnylolCommandRegistry {
-    // Register all commands here - ADD NEW COMMANDS TO THIS LIST
-    register_commands! {
-        crate::commands::BindingsCommand,
-        crate::commands::GitHubCommand,
-        crate::commands::GitlabCommand,
-        crate::commands::TwitterCommand,
-        crate::commands::RedditCommand,
-        crate::commands::GmailCommand,
-        crate::commands::REICommand,
-        crate::commands::InstagramCommand,
-        crate::commands::KagiCommand,
-        crate::commands::LinkedInCommand,
-        crate::commands::FacebookCommand,
-        crate::commands::ThreadsCommand,
-        crate::commands::WhatsAppCommand,
-        crate::commands::MetaCommand,
-        crate::commands::CargoCommand,
-        crate::commands::NpmCommand,
-        crate::commands::OnePasswordCommand,
-        crate::commands::ClaudeCommand,
-        crate::commands::ChatGPTCommand,
-        crate::commands::RustCommand,
-        crate::commands::HackCommand,
-        crate::commands::AmazonCommand,
-        crate::commands::YouTubeCommand,
-        crate::commands::WikipediaCommand,
-        crate::commands::DuckDuckGoCommand,
-        crate::commands::SchwabCommand,
-        crate::commands::SoundCloudCommand,
-        crate::commands::StockCommand,
-        crate::commands::GoogleDocsCommand,
-        crate::commands::GoogleMapsCommand,
-        crate::commands::GoogleSheetsCommand,
-        crate::commands::GoogleSlidesCommand,
-        crate::commands::GoogleChatCommand,
-        crate::commands::GoogleSearchCommand,
-        crate::commands::BrewCommand,
-        crate::commands::ChocoCommand,
-        crate::commands::DockerhubCommand,
-        crate::commands::GodocsCommand,
-        crate::commands::GopkgCommand,
-        crate::commands::MdnCommand,
-        crate::commands::NodeCommand,
-        crate::commands::NugetCommand,
-        crate::commands::OpenCommand,
-        crate::commands::PackagistCommand,
-        crate::commands::PypiCommand,
-        crate::commands::PythonCommand,
-        crate::commands::RubygemsCommand,
-        crate::commands::StackOverflowCommand,
-    }
-
-    /// Process commands that use special prefixes (like $ for stock tickers)
-    fn process_prefix_commands(command: &str) -> Option<String> {
-        use crate::commands::*;
+    /// Get all registered command bindings, followed by the plugins'
+    ///
+    /// WebAssembly plugins are listed as loaded when this is first called;
+    /// ones added later still run, but aren't listed until a restart.
+    fn get_all_commands_impl() -> Vec<BunnylolCommandInfo> {
+        #[allow(unused_mut)]
+        let mut commands: Vec<BunnylolCommandInfo> = registered_commands()
+            .into_iter()
+            .map(|command| (command.get_info)())
+            .chain(
+                plugins::installed()
+                    .iter()
+                    .map(|plugin| plugin.info().clone()),
+            )
+            .collect();
+        #[cfg(feature = "scripting")]
+        commands.extend(
+            crate::scripting::installed()
+                .iter()
+                .map(|script| script.info().clone()),
+        );
+        #[cfg(feature = "wasm-plugins")]
+        if let Some(plugins) = crate::wasm_plugins::watched() {
+            commands.extend(plugins.infos());
+        }
+        commands
+    }
 
-        if command.starts_with('$') {
-            // Don't process bare $ - let it fall through to default search
-            if command.len() <= 1 {
-                return None;
+    /// Process commands that use special prefixes, like `$` for stock
+    /// tickers or a prefix from the `[prefixes]` config section
+    fn process_prefix_commands(
+        command: &str,
+        full_args: &str,
+    ) -> Option<Result<Cow<'static, str>, CommandError>> {
+        if let Some(prefix) = Self::builtin_prefix(command) {
+            return Some((prefix.handler)(full_args));
+        }
+
+        Self::expand_configured_prefix(get_global_config()?, command).map(|url| Ok(url.into()))
+    }
+
+    /// The built-in prefix `command` starts with; a bare prefix doesn't
+    /// match, so it falls through to the default search
+    fn builtin_prefix(command: &str) -> Option<&'static PrefixCommand> {
+        PREFIX_COMMANDS
+            .iter()
+            .find(|p| command.len() > p.prefix.len() && command.starts_with(p.prefix))
+    }
+
+    /// Run a DuckDuckGo-style bang like `!gh rust` as the bunnylol command
+    /// `gh rust` when `gh` is a binding, and otherwise hand the whole bang to
+    /// DuckDuckGo, which knows thousands more
+    fn process_bang(full_args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let query = full_args.strip_prefix('!').unwrap_or(full_args);
+        let command = crate::utils::get_command_from_query_string(query);
+        if let Some(registered) = COMMAND_LOOKUP.get(command) {
+            return (registered.process_args)(query);
+        }
+        Self::process_plugin(command, query)
+            .unwrap_or_else(|| Ok(crate::commands::search_url("ddg", full_args).into()))
+    }
+
+    /// The URL for `command` from the longest matching `[prefixes]` entry
+    fn expand_configured_prefix(config: &BunnylolConfig, command: &str) -> Option<String> {
+        Self::configured_prefixes(config)
+            .filter(|(prefix, _)| command.len() > prefix.len() && command.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, template)| expand_template(template, &command[prefix.len()..]))
+    }
+
+    /// The `[numbers]` URL for a bare number after `command`, e.g. `gh 1234`
+    fn process_number(command: &str, full_args: &str) -> Option<String> {
+        let config = get_global_config()?;
+        if config.numbers.is_empty() {
+            return None;
+        }
+        Self::expand_number(config, command, full_args)
+    }
+
+    /// The URL from `config`'s `[numbers]` when the only argument is a number;
+    /// the template can be keyed by any of the command's bindings
+    fn expand_number(config: &BunnylolConfig, command: &str, full_args: &str) -> Option<String> {
+        let args = full_args.trim_start().strip_prefix(command)?;
+        let ArgKind::Number(number) = ArgKind::classify(args) else {
+            return None;
+        };
+        let template = config.numbers.get(command).or_else(|| {
+            Self::get_command_info(command)?
+                .bindings
+                .iter()
+                .find_map(|binding| config.numbers.get(binding))
+        })?;
+        Some(expand_template(template, &number.to_string()))
+    }
+
+    /// The `[keywords]` URL for `command`, if it's a configured keyword
+    fn process_keyword(command: &str, full_args: &str) -> Option<String> {
+        Self::expand_keyword(get_global_config()?, command, full_args)
+    }
+
+    /// A keyword's URL with the arguments after `command` filled in; a bare
+    /// keyword opens the site its template points into
+    fn expand_keyword(config: &BunnylolConfig, command: &str, full_args: &str) -> Option<String> {
+        let template = config.keywords.get(command)?;
+        let args = full_args
+            .trim_start()
+            .strip_prefix(command)
+            .unwrap_or_default()
+            .trim();
+        if !args.is_empty() || !template.contains(TEMPLATE_PLACEHOLDER) {
+            return Some(expand_template(template, args));
+        }
+        let url = expand_template(template, "");
+        Some(match url::Url::parse(&url) {
+            Ok(parsed) if parsed.origin().is_tuple() => {
+                format!("{}/", parsed.origin().ascii_serialization())
+            }
+            _ => url,
+        })
+    }
+
+    /// The config's `[keywords]` entries a query can reach; a keyword named
+    /// like a binding or starting with a prefix is never run
+    pub fn active_keywords(config: &BunnylolConfig) -> impl Iterator<Item = (&str, &str)> {
+        config
+            .keywords
+            .iter()
+            .filter(|(keyword, _)| {
+                !COMMAND_LOOKUP.contains_key(keyword.as_str())
+                    && Self::builtin_prefix(keyword).is_none()
+                    && Self::expand_configured_prefix(config, keyword).is_none()
+            })
+            .map(|(keyword, url)| (keyword.as_str(), url.as_str()))
+    }
+
+    /// The config's `[prefixes]` entries that pass `check_prefix`; the others
+    /// are reported by `prefix_errors` and otherwise ignored
+    pub fn configured_prefixes(config: &BunnylolConfig) -> impl Iterator<Item = (&str, &str)> {
+        config
+            .prefixes
+            .iter()
+            .filter(|(prefix, template)| Self::check_prefix(prefix, template).is_ok())
+            .map(|(prefix, template)| (prefix.as_str(), template.as_str()))
+    }
+
+    /// Check a configured prefix against the built-in prefixes and bindings
+    ///
+    /// A prefix can't overlap a built-in prefix, and no binding may start with
+    /// it, since the prefix would then swallow that command (`g` would
+    /// turn `gh` into a lookup of `h`).
+    pub fn check_prefix(prefix: &str, template: &str) -> Result<(), String> {
+        if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+            return Err(format!(
+                "prefix '{}' must be non-empty and contain no whitespace",
+                prefix
+            ));
+        }
+        if !template.contains(PREFIX_PLACEHOLDER) {
+            return Err(format!(
+                "prefix '{}' has no {} in its URL",
+                prefix, PREFIX_PLACEHOLDER
+            ));
+        }
+        if let Some(builtin) = PREFIX_COMMANDS
+            .iter()
+            .find(|p| p.prefix.starts_with(prefix) || prefix.starts_with(p.prefix))
+        {
+            return Err(format!(
+                "prefix '{}' overlaps the built-in '{}' prefix ({})",
+                prefix, builtin.prefix, builtin.description
+            ));
+        }
+        let mut shadowed: Vec<&str> = COMMAND_LOOKUP
+            .keys()
+            .copied()
+            .filter(|binding| binding.starts_with(prefix))
+            .collect();
+        if !shadowed.is_empty() {
+            shadowed.sort();
+            return Err(format!(
+                "prefix '{}' would shadow the {} binding(s)",
+                prefix,
+                shadowed.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Why each invalid `[prefixes]` entry in `config` is ignored, for
+    /// warning about them at startup
+    pub fn prefix_errors(config: &BunnylolConfig) -> Vec<String> {
+        let mut errors: Vec<String> = config
+            .prefixes
+            .iter()
+            .filter_map(|(prefix, template)| Self::check_prefix(prefix, template).err())
+            .collect();
+        errors.sort();
+        errors
+    }
+
+    /// Check the bindings of a plugin or scripted command against the
+    /// built-in bindings and prefixes and `taken`, the bindings of those
+    /// installed before it
+    pub fn check_plugin_bindings(
+        name: &str,
+        bindings: &[String],
+        taken: &[&str],
+    ) -> Result<(), String> {
+        for binding in bindings {
+            if binding.is_empty() || binding.contains(char::is_whitespace) {
+                return Err(format!(
+                    "command '{}' has binding '{}', which must be non-empty and contain no whitespace",
+                    name, binding
+                ));
+            }
+            if COMMAND_LOOKUP.contains_key(binding.as_str()) || taken.contains(&binding.as_str()) {
+                return Err(format!(
+                    "command '{}' binding '{}' is already taken",
+                    name, binding
+                ));
+            }
+            if let Some(prefix) = PREFIX_COMMANDS
+                .iter()
+                .find(|p| binding.starts_with(p.prefix))
+            {
+                return Err(format!(
+                    "command '{}' binding '{}' starts with the built-in '{}' prefix",
+                    name, binding, prefix.prefix
+                ));
             }
-            return Some(StockCommand::process_ticker(command));
         }
+        Ok(())
+    }
 
-        None
+    /// Install loaded plugins, skipping any whose bindings collide with
+    /// a command that's already registered, and return why each was skipped
+    ///
+    /// Call this once at startup, before any query is resolved.
+    pub fn install_plugins(loaded: Vec<PluginCommand>) -> Vec<String> {
+        let mut installed: Vec<PluginCommand> = Vec::new();
+        let mut errors = Vec::new();
+        for plugin in loaded {
+            let taken: Vec<&str> = installed
+                .iter()
+                .flat_map(|p| p.bindings().iter().map(String::as_str))
+                .collect();
+            match Self::check_plugin_bindings(&plugin.name, plugin.bindings(), &taken) {
+                Ok(()) => installed.push(plugin),
+                Err(e) => errors.push(e),
+            }
+        }
+        plugins::install(installed);
+        errors
+    }
+
+    /// Install compiled scripted commands, skipping any whose bindings
+    /// collide with a command or plugin that's already registered, and
+    /// return why each was skipped
+    ///
+    /// Call this once at startup, after `install_plugins`.
+    #[cfg(feature = "scripting")]
+    pub fn install_scripts(loaded: Vec<crate::scripting::ScriptCommand>) -> Vec<String> {
+        let mut installed: Vec<crate::scripting::ScriptCommand> = Vec::new();
+        let mut errors = Vec::new();
+        for script in loaded {
+            let taken: Vec<&str> = Self::extension_bindings()
+                .into_iter()
+                .chain(
+                    installed
+                        .iter()
+                        .flat_map(|s| s.bindings().iter().map(String::as_str)),
+                )
+                .collect();
+            match Self::check_plugin_bindings(&script.name, script.bindings(), &taken) {
+                Ok(()) => installed.push(script),
+                Err(e) => errors.push(e),
+            }
+        }
+        crate::scripting::install(installed);
+        errors
+    }
+
+    /// The bindings of every installed shared-library plugin and scripted
+    /// command; WebAssembly plugins come and go, so they aren't included
+    pub fn extension_bindings() -> Vec<&'static str> {
+        #[allow(unused_mut)]
+        let mut bindings: Vec<&'static str> = plugins::installed()
+            .iter()
+            .flat_map(|plugin| plugin.bindings().iter().map(String::as_str))
+            .collect();
+        #[cfg(feature = "scripting")]
+        bindings.extend(
+            crate::scripting::installed()
+                .iter()
+                .flat_map(|script| script.bindings().iter().map(String::as_str)),
+        );
+        bindings
     }
 
-    /// Process a command string and return the appropriate URL
-    pub fn process_command(command: &str, full_args: &str) -> String {
+    /// Process a command string and return the appropriate URL, or the
+    /// command's error when it rejects its arguments
+    ///
+    /// Fixed URLs (a bare `gh`) come back borrowed, so the common case doesn't
+    /// allocate.
+    pub fn process_command(
+        command: &str,
+        full_args: &str,
+    ) -> Result<Cow<'static, str>, CommandError> {
         // Check for prefix commands first (special case)
-        if let Some(url) = Self::process_prefix_commands(command) {
+        if let Some(url) = Self::process_prefix_commands(command, full_args) {
             return url;
         }
+        if let Some(url) = Self::process_number(command, full_args) {
+            return Ok(url.into());
+        }
 
-        // Initialize lookup table once, then use O(1) HashMap lookup
-        let lookup = COMMAND_LOOKUP.get_or_init(Self::initialize_command_lookup);
+        if let Some(registered) = COMMAND_LOOKUP.get(command) {
+            return (registered.process_args)(full_args);
+        }
+        if let Some(url) = Self::process_keyword(command, full_args) {
+            return Ok(url.into());
+        }
+        Self::process_plugin(command, full_args)
+            .unwrap_or_else(|| Ok(Self::default_search_url(full_args)))
+    }
 
-        match lookup.get(command) {
-            Some(handler) => handler(full_args),
-            None => {
-                let engine = get_global_config()
-                    .map(|cfg| cfg.default_search.as_str())
-                    .unwrap_or("google");
-                crate::commands::search_url(engine, full_args)
-            }
+    /// Like `process_command`, but awaits the command's async handler, so
+    /// commands that do network I/O can finish it
+    pub async fn process_command_async(
+        command: &str,
+        full_args: &str,
+    ) -> Result<Cow<'static, str>, CommandError> {
+        if let Some(url) = Self::process_prefix_commands(command, full_args) {
+            return url;
         }
+        if let Some(url) = Self::process_number(command, full_args) {
+            return Ok(url.into());
+        }
+
+        if let Some(registered) = COMMAND_LOOKUP.get(command) {
+            return (registered.process_args_async)(full_args).await;
+        }
+        if let Some(url) = Self::process_keyword(command, full_args) {
+            return Ok(url.into());
+        }
+        Self::process_plugin(command, full_args)
+            .unwrap_or_else(|| Ok(Self::default_search_url(full_args)))
+    }
+
+    /// Run the plugin or scripted command bound to `command`, if any
+    fn process_plugin(
+        command: &str,
+        full_args: &str,
+    ) -> Option<Result<Cow<'static, str>, CommandError>> {
+        if let Some(plugin) = plugins::find(command) {
+            return Some(plugin.resolve(full_args).map(Cow::Owned));
+        }
+        #[cfg(feature = "scripting")]
+        if let Some(script) = crate::scripting::find(command) {
+            return Some(script.resolve(full_args).map(Cow::Owned));
+        }
+        #[cfg(feature = "wasm-plugins")]
+        if let Some(plugins) = crate::wasm_plugins::watched() {
+            return plugins
+                .resolve(command, full_args)
+                .map(|url| url.map(Cow::Owned));
+        }
+        None
+    }
+
+    fn has_plugin(command: &str) -> bool {
+        #[cfg(feature = "scripting")]
+        if crate::scripting::find(command).is_some() {
+            return true;
+        }
+        #[cfg(feature = "wasm-plugins")]
+        if crate::wasm_plugins::watched().is_some_and(|plugins| plugins.has_binding(command)) {
+            return true;
+        }
+        plugins::find(command).is_some()
+    }
+
+    /// The URL for an unmatched query from the config's fallback chain,
+    /// ending at the default search engine
+    fn default_search_url(full_args: &str) -> Cow<'static, str> {
+        match get_global_config() {
+            Some(config) => crate::fallback::resolve(config, full_args).url.into(),
+            None => crate::commands::search_url("google", full_args).into(),
+        }
+    }
+
+    /// Process a query and return every URL it resolves to, in order
+    ///
+    /// Callers that can only follow one URL should use `process_command`; the
+    /// web server shows an interstitial when this returns more than one.
+    pub fn process_targets(command: &str, full_args: &str) -> Result<Vec<String>, CommandError> {
+        Ok(vec![
+            Self::process_command(command, full_args)?.into_owned(),
+        ])
+    }
+
+    /// Like `process_targets`, but awaits each command's async handler
+    pub async fn process_targets_async(
+        command: &str,
+        full_args: &str,
+    ) -> Result<Vec<String>, CommandError> {
+        Ok(vec![
+            Self::process_command_async(command, full_args)
+                .await?
+                .into_owned(),
+        ])
+    }
+
+    /// Check whether a command token is handled by a registered binding,
+    /// prefix, keyword, or plugin
+    pub fn has_command(command: &str) -> bool {
+        Self::builtin_prefix(command).is_some()
+            || get_global_config().is_some_and(|config| {
+                config.keywords.contains_key(command)
+                    || Self::expand_configured_prefix(config, command).is_some()
+            })
+            || COMMAND_LOOKUP.contains_key(command)
+            || Self::has_plugin(command)
+    }
+
+    /// Get the handler registered for a binding, without the prefix or
+    /// default-search fallbacks `process_command` applies
+    pub fn get_handler(binding: &str) -> Option<CommandHandler> {
+        COMMAND_LOOKUP
+            .get(binding)
+            .map(|registered| registered.process_args)
+    }
+
+    /// Suggest the binding (or user alias) the user most likely meant when
+    /// `command` doesn't match anything, e.g. `gihub` → `github`
+    pub fn suggest_command<'a>(
+        command: &str,
+        aliases: impl IntoIterator<Item = &'a str>,
+    ) -> Option<String> {
+        let aliases = FuzzyIndex::new(aliases);
+        Self::closest_command(command, &aliases).map(|found| found.candidate.to_string())
+    }
+
+    /// The binding or alias nearest to `command`, when `command` is neither
+    ///
+    /// Bindings are searched through an index built once, so this stays cheap
+    /// however many there are; `aliases` is the caller's index of alias names.
+    pub fn closest_command<'a>(command: &str, aliases: &'a FuzzyIndex) -> Option<FuzzyMatch<'a>> {
+        if Self::has_command(command) || aliases.contains(command) {
+            return None;
+        }
+        let matches: Vec<FuzzyMatch<'a>> = [
+            BINDING_INDEX.best_match(command),
+            aliases.best_match(command),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let best = matches
+            .iter()
+            .min_by_key(|found| (found.distance, found.candidate))?;
+        let tied = matches
+            .iter()
+            .any(|found| found.distance == best.distance && found.candidate != best.candidate);
+        Some(FuzzyMatch {
+            unambiguous: best.unambiguous && !tied,
+            ..best.clone()
+        })
+    }
+
+    /// `query` with its command corrected, when the nearest binding or alias
+    /// is unambiguous and at most `max_distance` edits away
+    ///
+    /// A `max_distance` of 0 never corrects, leaving near-misses to the
+    /// "did you mean" page.
+    pub fn autocorrect(query: &str, aliases: &FuzzyIndex, max_distance: usize) -> Option<String> {
+        if max_distance == 0 {
+            return None;
+        }
+        let command = crate::utils::get_command_from_query_string(query);
+        Self::closest_command(command, aliases)
+            .filter(|found| found.unambiguous && found.distance <= max_distance)
+            .map(|found| format!("{}{}", found.candidate, &query[command.len()..]))
     }
 
     /// Get all registered command bindings
     pub fn get_all_commands() -> &'static Vec<BunnylolCommandInfo> {
         BINDINGS_DATA.get_or_init(Self::get_all_commands_impl)
     }
+
+    /// Get the info for the command that owns the given binding
+    pub fn get_command_info(binding: &str) -> Option<&'static BunnylolCommandInfo> {
+        Self::get_all_commands()
+            .iter()
+            .find(|info| info.bindings.iter().any(|b| b == binding))
+    }
 }
 
 #[cfg(test)]
@@ -146,8 +581,9 @@ mod cache_tests {
     use super::*;
 
     #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
     fn test_command_lookup_contains_all_bindings() {
-        let lookup = COMMAND_LOOKUP.get_or_init(BunnylolCommandRegistry::initialize_command_lookup);
+        let lookup = &COMMAND_LOOKUP;
 
         // Verify key bindings are present (using actual command bindings)
         assert!(lookup.contains_key("gh"));
@@ -168,26 +604,35 @@ mod cache_tests {
     }
 
     #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
     fn test_command_lookup_correctness() {
         use crate::commands::*;
 
-        let lookup = COMMAND_LOOKUP.get_or_init(BunnylolCommandRegistry::initialize_command_lookup);
+        let lookup = &COMMAND_LOOKUP;
 
         // Test GitHub command handler
-        let gh_handler = lookup.get("gh").expect("GitHub command should exist");
+        let gh_handler = lookup
+            .get("gh")
+            .expect("GitHub command should exist")
+            .process_args;
         assert_eq!(gh_handler("gh"), GitHubCommand::process_args("gh"));
+        assert!(gh_handler("gh facebook/").is_err());
 
         // Test Instagram command handler
-        let ig_handler = lookup.get("ig").expect("Instagram command should exist");
-        assert_eq!(ig_handler("ig"), InstagramCommand::process_args("ig"));
+        let ig_handler = lookup
+            .get("ig")
+            .expect("Instagram command should exist")
+            .process_args;
+        assert_eq!(ig_handler("ig"), Ok(InstagramCommand::process_args("ig")));
     }
 
     #[test]
+    #[cfg(feature = "all-commands")]
     fn test_bindings_data_cache() {
         let commands = BunnylolCommandRegistry::get_all_commands();
 
         // Verify we have all expected commands
-        assert_eq!(commands.len(), 48, "Expected 48 commands");
+        assert_eq!(commands.len(), 65, "Expected 65 commands");
 
         // Verify cache returns same pointer (not regenerated)
         let commands2 = BunnylolCommandRegistry::get_all_commands();
@@ -197,6 +642,214 @@ mod cache_tests {
         );
     }
 
+    #[test]
+    fn test_command_lookup_matches_registered_commands() {
+        // build.rs reads bindings from source; it must agree with what registered
+        let mut registered: Vec<&str> = registered_commands()
+            .iter()
+            .flat_map(|command| command.bindings.iter().copied())
+            .collect();
+        registered.sort();
+        let mut generated: Vec<&str> = COMMAND_LOOKUP.keys().copied().collect();
+        generated.sort();
+        assert_eq!(generated, registered);
+
+        #[cfg(feature = "finance")]
+        {
+            let handler =
+                BunnylolCommandRegistry::get_handler("stock").expect("stock is registered");
+            assert_eq!(
+                handler("stock META"),
+                <crate::commands::StockCommand as crate::commands::bunnylol_command::BunnylolCommand>::process_args("stock META")
+            );
+        }
+        #[cfg(not(feature = "finance"))]
+        assert!(BunnylolCommandRegistry::get_handler("stock").is_none());
+    }
+
+    #[test]
+    #[cfg(all(feature = "finance", feature = "google"))]
+    fn test_registered_commands_are_ordered() {
+        let commands = registered_commands();
+        let primaries: Vec<&str> = commands.iter().map(|c| c.bindings[0]).collect();
+        let mut sorted = primaries.clone();
+        sorted.sort();
+        assert_eq!(primaries, sorted);
+
+        // Hand-written impls register through register_command!
+        assert!(primaries.contains(&"g"));
+        assert!(primaries.contains(&"stock"));
+    }
+
+    #[test]
+    #[cfg(feature = "social")]
+    fn test_get_command_info() {
+        let info = BunnylolCommandRegistry::get_command_info("instagram")
+            .expect("instagram should be registered");
+        assert!(info.bindings.contains(&"ig".to_string()));
+        assert!(BunnylolCommandRegistry::get_command_info("nope").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_process_targets_single_command() {
+        assert_eq!(
+            BunnylolCommandRegistry::process_targets("gh", "gh facebook/react"),
+            Ok(vec!["https://github.com/facebook/react".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_fixed_urls_are_borrowed() {
+        // Cow's == compares contents, so match on the variant
+        assert!(matches!(
+            BunnylolCommandRegistry::process_command("bindings", "bindings"),
+            Ok(Cow::Borrowed("/bindings"))
+        ));
+        assert!(matches!(
+            BunnylolCommandRegistry::process_command("bindings", "bindings gh"),
+            Ok(Cow::Owned(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "finance")]
+    async fn test_process_command_async() {
+        assert_eq!(
+            BunnylolCommandRegistry::process_command_async("gh", "gh facebook/react").await,
+            BunnylolCommandRegistry::process_command("gh", "gh facebook/react")
+        );
+        assert!(
+            BunnylolCommandRegistry::process_targets_async("stock", "stock apple inc")
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_has_command() {
+        assert!(BunnylolCommandRegistry::has_command("gh"));
+        assert!(BunnylolCommandRegistry::has_command("$META"));
+        assert!(!BunnylolCommandRegistry::has_command("gihub"));
+        assert!(!BunnylolCommandRegistry::has_command("$"));
+        assert!(BunnylolCommandRegistry::has_command("!gh"));
+        assert!(!BunnylolCommandRegistry::has_command("!"));
+        assert!(BunnylolCommandRegistry::has_command("@jane"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_bang_runs_own_binding() {
+        assert_eq!(
+            BunnylolCommandRegistry::process_command("!gh", "!gh facebook/react"),
+            BunnylolCommandRegistry::process_command("gh", "gh facebook/react")
+        );
+        // The ticker prefix still only looks at its own token
+        assert_eq!(
+            BunnylolCommandRegistry::process_command("$META", "$META"),
+            BunnylolCommandRegistry::process_command("$META", "$META extra")
+        );
+    }
+
+    #[test]
+    fn test_bang_forwards_unknown_to_duckduckgo() {
+        assert_eq!(
+            BunnylolCommandRegistry::process_command("!imdb", "!imdb dune").as_deref(),
+            Ok("https://duckduckgo.com/?q=!imdb%20dune")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "social")]
+    fn test_suggest_command() {
+        assert_eq!(
+            BunnylolCommandRegistry::suggest_command("yuotube", []),
+            Some("youtube".to_string())
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::suggest_command("wrok", ["work"]),
+            Some("work".to_string())
+        );
+        assert_eq!(BunnylolCommandRegistry::suggest_command("gh", []), None);
+        assert_eq!(
+            BunnylolCommandRegistry::suggest_command("weather", []),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_closest_command_completes_unique_prefix() {
+        let aliases = FuzzyIndex::default();
+        let found = BunnylolCommandRegistry::closest_command("stackov", &aliases)
+            .expect("stackoverflow is the only binding starting with stackov");
+        assert_eq!(found.candidate, "stackoverflow");
+        assert!(found.unambiguous);
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_autocorrect() {
+        let aliases = FuzzyIndex::new(["work"]);
+        assert_eq!(
+            BunnylolCommandRegistry::autocorrect("gihub facebook/react", &aliases, 1).as_deref(),
+            Some("github facebook/react")
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::autocorrect("wrok", &aliases, 1).as_deref(),
+            Some("work")
+        );
+        // Off, too far, or already a command
+        assert_eq!(
+            BunnylolCommandRegistry::autocorrect("gihub", &aliases, 0),
+            None
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::autocorrect("yuotbue", &aliases, 1),
+            None
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::autocorrect("gh rust", &aliases, 1),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_check_plugin_bindings() {
+        let bindings = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(
+            BunnylolCommandRegistry::check_plugin_bindings("ticket", &bindings(&["ticket"]), &[])
+                .is_ok()
+        );
+
+        let error = BunnylolCommandRegistry::check_plugin_bindings("mine", &bindings(&["gh"]), &[])
+            .unwrap_err();
+        assert!(error.contains("'gh' is already taken"), "{}", error);
+        assert!(
+            BunnylolCommandRegistry::check_plugin_bindings(
+                "ticket2",
+                &bindings(&["ticket"]),
+                &["ticket"]
+            )
+            .is_err()
+        );
+        assert!(
+            BunnylolCommandRegistry::check_plugin_bindings("cash", &bindings(&["$x"]), &[])
+                .is_err()
+        );
+        assert!(
+            BunnylolCommandRegistry::check_plugin_bindings("bad", &bindings(&["a b"]), &[])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_binding_index_covers_every_binding() {
+        assert_eq!(BINDING_INDEX.len(), COMMAND_LOOKUP.len());
+    }
+
     #[test]
     fn test_no_binding_collisions() {
         use std::collections::HashMap;
@@ -225,4 +878,230 @@ mod cache_tests {
             collisions.join("\n")
         );
     }
+
+    fn config_with_prefixes(prefixes: &[(&str, &str)]) -> BunnylolConfig {
+        BunnylolConfig {
+            prefixes: prefixes
+                .iter()
+                .map(|(prefix, template)| (prefix.to_string(), template.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_expand_number() {
+        let config = BunnylolConfig {
+            numbers: [(
+                "gh".to_string(),
+                "https://github.com/facebook/react/issues/{}".to_string(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let expand = |command, full_args| {
+            BunnylolCommandRegistry::expand_number(&config, command, full_args)
+        };
+        assert_eq!(
+            expand("gh", "gh 1234").as_deref(),
+            Some("https://github.com/facebook/react/issues/1234")
+        );
+        // Any binding of the command, and #1234, work too
+        assert_eq!(
+            expand("github", "github #42").as_deref(),
+            Some("https://github.com/facebook/react/issues/42")
+        );
+        assert_eq!(expand("gh", "gh"), None);
+        assert_eq!(expand("gh", "gh facebook/react"), None);
+        assert_eq!(expand("gh", "gh 12 34"), None);
+        assert_eq!(expand("yt", "yt 1234"), None);
+    }
+
+    #[test]
+    fn test_expand_keyword() {
+        let config = BunnylolConfig {
+            keywords: [
+                (
+                    "w".to_string(),
+                    "https://en.wikipedia.org/w/index.php?search={}".to_string(),
+                ),
+                ("hn".to_string(), "https://news.ycombinator.com".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let expand = |command, full_args| {
+            BunnylolCommandRegistry::expand_keyword(&config, command, full_args)
+        };
+        assert_eq!(
+            expand("w", "w rust (programming language)").as_deref(),
+            Some("https://en.wikipedia.org/w/index.php?search=rust%20(programming%20language)")
+        );
+        assert_eq!(
+            expand("w", "w").as_deref(),
+            Some("https://en.wikipedia.org/")
+        );
+        assert_eq!(
+            expand("hn", "hn rust").as_deref(),
+            Some("https://news.ycombinator.com")
+        );
+        assert_eq!(expand("gh", "gh rust"), None);
+    }
+
+    #[test]
+    fn test_builtin_prefixes_shadow_no_binding() {
+        for prefix in PREFIX_COMMANDS {
+            assert!(
+                !COMMAND_LOOKUP.keys().any(|b| b.starts_with(prefix.prefix)),
+                "a binding starts with the built-in '{}' prefix",
+                prefix.prefix
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_configured_prefix() {
+        let config = config_with_prefixes(&[
+            ("#", "https://jira.example.com/browse/{}"),
+            ("~", "http://{}.corp.example.com/"),
+            ("~~", "https://wiki.example.com/{}"),
+        ]);
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "#PROJ-123").as_deref(),
+            Some("https://jira.example.com/browse/PROJ-123")
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "~grafana").as_deref(),
+            Some("http://grafana.corp.example.com/")
+        );
+        // The longest matching prefix wins
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "~~oncall").as_deref(),
+            Some("https://wiki.example.com/oncall")
+        );
+        // A bare prefix or an unrelated token doesn't match
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "#"),
+            None
+        );
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "gh"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_check_prefix_collisions() {
+        let url = "https://example.com/{}";
+        assert!(BunnylolCommandRegistry::check_prefix("#", url).is_ok());
+        assert!(
+            BunnylolCommandRegistry::check_prefix("$", url)
+                .unwrap_err()
+                .contains("built-in '$'")
+        );
+        assert!(
+            BunnylolCommandRegistry::check_prefix("$$", url)
+                .unwrap_err()
+                .contains("built-in '$'")
+        );
+        let error = BunnylolCommandRegistry::check_prefix("g", url).unwrap_err();
+        assert!(error.contains("gh"), "{}", error);
+        assert!(BunnylolCommandRegistry::check_prefix("", url).is_err());
+        assert!(BunnylolCommandRegistry::check_prefix("#", "https://example.com/").is_err());
+    }
+
+    #[test]
+    fn test_invalid_prefixes_are_ignored() {
+        let config = config_with_prefixes(&[
+            ("#", "https://jira.example.com/browse/{}"),
+            ("k", "https://example.com/{}"),
+        ]);
+        assert_eq!(BunnylolCommandRegistry::prefix_errors(&config).len(), 1);
+        assert_eq!(
+            BunnylolCommandRegistry::expand_configured_prefix(&config, "kg"),
+            None
+        );
+        assert!(BunnylolCommandRegistry::expand_configured_prefix(&config, "#1").is_some());
+    }
+
+    /// Arguments that have broken URLs before: reserved and escape characters,
+    /// an existing escape, and non-ASCII text
+    const AWKWARD_ARGS: &[&str] = &[
+        "a+b",
+        "100%",
+        "%zz %41",
+        "Tom & Jerry",
+        "a=b?c#d",
+        "café ☕ 日本",
+        "\"quoted\" <tag>",
+    ];
+
+    /// Whether `url` parses and every `%` starts a valid escape
+    fn is_well_formed(url: &str) -> bool {
+        let parsed = if url.starts_with('/') {
+            url::Url::parse("http://relative.invalid").and_then(|origin| origin.join(url))
+        } else {
+            url::Url::parse(url)
+        };
+        let bytes = url.as_bytes();
+        parsed.is_ok()
+            && !url.contains(' ')
+            && url.match_indices('%').all(|(i, _)| {
+                bytes.len() > i + 2
+                    && bytes[i + 1].is_ascii_hexdigit()
+                    && bytes[i + 2].is_ascii_hexdigit()
+            })
+    }
+
+    #[test]
+    fn test_every_command_has_metadata() {
+        for info in BunnylolCommandRegistry::get_all_commands() {
+            assert!(!info.tags.is_empty(), "{} has no tags", info.bindings[0]);
+            assert_eq!(info.examples[0], info.example);
+            for example in &info.examples {
+                let command = example.split_whitespace().next().unwrap_or_default();
+                assert!(
+                    BunnylolCommandRegistry::has_command(command),
+                    "{} example {:?} doesn't start with a command",
+                    info.bindings[0],
+                    example
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_command_encodes_awkward_args() {
+        let mut malformed = Vec::new();
+        for info in BunnylolCommandRegistry::get_all_commands() {
+            for binding in &info.bindings {
+                for args in AWKWARD_ARGS {
+                    let query = format!("{} {}", binding, args);
+                    if let Ok(url) = BunnylolCommandRegistry::process_command(binding, &query)
+                        && !is_well_formed(&url)
+                    {
+                        malformed.push(format!("{:?} -> {:?}", query, url));
+                    }
+                }
+            }
+        }
+        assert!(malformed.is_empty(), "{:#?}", malformed);
+    }
+
+    #[test]
+    fn test_configured_templates_encode_awkward_args() {
+        for args in AWKWARD_ARGS {
+            let urls = [
+                expand_template("https://jira.example.com/browse/{}", args),
+                crate::commands::search_url("ddg", args),
+                crate::commands::people::people_url("github", args),
+                crate::commands::people::people_url("https://directory.example.com/?q={}", args),
+            ];
+            for url in urls {
+                assert!(is_well_formed(&url), "{:?} produced {:?}", args, url);
+            }
+        }
+    }
 }
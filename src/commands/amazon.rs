@@ -7,40 +7,65 @@
 /// - az cart -> https://www.amazon.com/gp/cart/view.html/
 /// - az pay/wallet -> https://www.amazon.com/cpe/yourpayments/wallet
 /// - az [search terms] -> https://www.amazon.com/s?k=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("az", "amzn", "azn", "amazon"),
+    description = "Navigate to Amazon or search for products",
+    example = "az headphones",
+    examples("az orders", "az"),
+    args = "[orders | cart | <search terms>]",
+    category = Shopping,
+    tags("shopping", "store", "products", "buy"),
+    usage("az" = "Open Amazon", "az <search terms>" = "Search products"),
+    subcommands
+)]
 pub struct AmazonCommand;
 
-impl BunnylolCommand for AmazonCommand {
-    const BINDINGS: &'static [&'static str] = &["az", "amzn", "azn", "amazon"];
+impl AmazonCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(
+            &["orders"],
+            "Open order history",
+            "https://www.amazon.com/gp/css/order-history?ref_=nav_orders_first",
+        ),
+        Subcommand::url(
+            &["cart"],
+            "Open the cart",
+            "https://www.amazon.com/gp/cart/view.html/",
+        ),
+        Subcommand::url(
+            &["account"],
+            "Open your account",
+            "https://www.amazon.com/gp/css/homepage.html?ref_=nav_youraccount_btn",
+        ),
+        Subcommand::url(
+            &["messages"],
+            "Open your messages",
+            "https://www.amazon.com/gp/message",
+        ),
+        Subcommand::url(
+            &["pay", "wallet"],
+            "Open your payment methods",
+            "https://www.amazon.com/cpe/yourpayments/wallet",
+        ),
+    ];
 
-    fn process_args(args: &str) -> String {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://amazon.com/".to_string()
+            "https://amazon.com/".into()
         } else {
-            match query {
-                "orders" => {
-                    "https://www.amazon.com/gp/css/order-history?ref_=nav_orders_first".to_string()
-                }
-                "account" => "https://www.amazon.com/gp/css/homepage.html?ref_=nav_youraccount_btn"
-                    .to_string(),
-                "messages" => "https://www.amazon.com/gp/message".to_string(),
-                "cart" => "https://www.amazon.com/gp/cart/view.html/".to_string(),
-                "pay" | "wallet" => "https://www.amazon.com/cpe/yourpayments/wallet".to_string(),
-                _ => build_search_url("https://www.amazon.com/s", "k", query),
-            }
+            build_search_url("https://www.amazon.com/s", "k", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Amazon or search for products",
-            "az headphones",
-        )
-    }
 }
 
 #[cfg(test)]
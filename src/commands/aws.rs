@@ -0,0 +1,248 @@
+/// AWS Console command handler
+/// Supports:
+/// - aws -> the `[aws] sso_start_url` portal, or the console home
+/// - aws [service] [region] -> the service's console, e.g. `aws ec2 us-west-2`
+/// - aws [service] [resource] [region] -> a resource's page, e.g. a Lambda
+///   function or an S3 bucket
+///
+/// Pages open in the `[aws] region` when the query doesn't name a region.
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::{AwsConfig, get_global_config};
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::configured_base_url;
+
+/// Services whose console path isn't the name people call them by, with
+/// those names
+const SERVICES: [(&str, &[&str]); 6] = [
+    ("cloudformation", &["cloudformation", "cfn"]),
+    ("cloudwatch", &["cloudwatch", "cw"]),
+    ("dynamodbv2", &["dynamodb", "ddb", "dynamo"]),
+    ("iam", &["iam"]),
+    ("systems-manager", &["ssm", "systems-manager"]),
+    ("vpcconsole", &["vpc"]),
+];
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("aws"),
+    description = "Open the AWS Console on a service, resource, or region",
+    example = "aws ec2 us-west-2",
+    examples("aws s3", "aws lambda my-function", "aws s3 my-bucket eu-west-1"),
+    args = "[<service> [<resource>] [<region>]]",
+    category = Dev,
+    tags("cloud", "console", "amazon", "infrastructure"),
+    config_keys("aws.region", "aws.sso_start_url"),
+    usage(
+        "aws" = "Open your SSO portal, or the console home",
+        "aws <service> [<region>]" = "Open a service, like s3, ec2, or lambda",
+        "aws <service> <resource> [<region>]" = "Open a Lambda function, S3 bucket, EC2 instance, DynamoDB table, or CloudFormation stack"
+    )
+)]
+pub struct AwsCommand;
+
+impl AwsCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let config = get_global_config()
+            .map(|config| config.aws.clone())
+            .unwrap_or_default();
+        Self::url(&config, Self::get_command_args(args)).map(Cow::Owned)
+    }
+
+    /// The console page for `query`
+    fn url(config: &AwsConfig, query: &str) -> Result<String, CommandError> {
+        let mut words: Vec<String> = Args::new(query).collect();
+        let named_region = words
+            .iter()
+            .rposition(|word| is_region(word))
+            .map(|at| words.remove(at).to_ascii_lowercase());
+        if let Some(region) = config.region.as_deref().filter(|region| !is_region(region)) {
+            return Err(CommandError::InvalidArguments(format!(
+                "[aws] region '{}' isn't a region code, like us-west-2",
+                region
+            )));
+        }
+        let region = named_region.clone().or_else(|| config.region.clone());
+        let console = match &region {
+            Some(region) => format!("https://{}.console.aws.amazon.com", region),
+            None => "https://console.aws.amazon.com".to_string(),
+        };
+        let in_region = |url: UrlBuilder| match &region {
+            Some(region) => url.query("region", region).build(),
+            None => url.build(),
+        };
+
+        let (service, resource) = match &words[..] {
+            [] => {
+                let portal = config
+                    .sso_start_url
+                    .as_deref()
+                    .and_then(configured_base_url);
+                return Ok(match portal {
+                    Some(portal) if named_region.is_none() => portal.to_string(),
+                    _ => in_region(UrlBuilder::new(&console).path("console/home")),
+                });
+            }
+            [service] => (service_path(service), None),
+            [service, resource] => (service_path(service), Some(resource.as_str())),
+            [_, _, extra, ..] => {
+                return Err(CommandError::InvalidArguments(format!(
+                    "aws takes a service, a resource, and a region; '{}' is one too many",
+                    extra
+                )));
+            }
+        };
+
+        let service_home = UrlBuilder::new(&console).segment(&service).segment("home");
+        let Some(resource) = resource else {
+            return Ok(in_region(service_home));
+        };
+        let route = match service.as_str() {
+            "s3" => {
+                return Ok(in_region(
+                    UrlBuilder::new(&console)
+                        .path("s3/buckets")
+                        .segment(resource),
+                ));
+            }
+            "lambda" => "/functions/",
+            "ec2" if resource.starts_with("i-") => "InstanceDetails:instanceId=",
+            "ec2" => "Instances:search=",
+            "dynamodbv2" => "table?name=",
+            "cloudformation" => "/stacks?filteringText=",
+            _ => {
+                return Err(CommandError::InvalidArguments(format!(
+                    "aws {} doesn't open resources, and '{}' isn't a region",
+                    service, resource
+                )));
+            }
+        };
+        Ok(in_region(service_home.fragment_with(route, resource)))
+    }
+}
+
+/// The console path for `service`, like `dynamodbv2` for `ddb`
+fn service_path(service: &str) -> String {
+    let service = service.to_ascii_lowercase();
+    SERVICES
+        .iter()
+        .find(|(_, names)| names.contains(&service.as_str()))
+        .map(|(path, _)| path.to_string())
+        .unwrap_or(service)
+}
+
+/// Whether `word` is a region code, like `us-west-2` or `us-gov-east-1`
+fn is_region(word: &str) -> bool {
+    let parts: Vec<&str> = word.split('-').collect();
+    let Some((number, names)) = parts.split_last() else {
+        return false;
+    };
+    (2..=3).contains(&names.len())
+        && names[0].len() == 2
+        && names
+            .iter()
+            .all(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()))
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(query: &str) -> Result<String, CommandError> {
+        AwsCommand::url(&AwsConfig::default(), query)
+    }
+
+    #[test]
+    fn test_aws_command_base() {
+        assert_eq!(
+            AwsCommand::process_args("aws").as_deref(),
+            Ok("https://console.aws.amazon.com/console/home")
+        );
+        let config = AwsConfig {
+            region: Some("us-east-2".to_string()),
+            sso_start_url: Some("https://myorg.awsapps.com/start/".to_string()),
+        };
+        assert_eq!(
+            AwsCommand::url(&config, "").as_deref(),
+            Ok("https://myorg.awsapps.com/start")
+        );
+        assert_eq!(
+            AwsCommand::url(&config, "eu-west-1").as_deref(),
+            Ok("https://eu-west-1.console.aws.amazon.com/console/home?region=eu-west-1")
+        );
+    }
+
+    #[test]
+    fn test_aws_command_services() {
+        assert_eq!(
+            url("s3").as_deref(),
+            Ok("https://console.aws.amazon.com/s3/home")
+        );
+        assert_eq!(
+            url("ec2 us-west-2").as_deref(),
+            Ok("https://us-west-2.console.aws.amazon.com/ec2/home?region=us-west-2")
+        );
+        assert_eq!(
+            url("DDB").as_deref(),
+            Ok("https://console.aws.amazon.com/dynamodbv2/home")
+        );
+        let config = AwsConfig {
+            region: Some("us-east-2".to_string()),
+            sso_start_url: None,
+        };
+        assert_eq!(
+            AwsCommand::url(&config, "lambda").as_deref(),
+            Ok("https://us-east-2.console.aws.amazon.com/lambda/home?region=us-east-2")
+        );
+    }
+
+    #[test]
+    fn test_aws_command_rejects_invalid_config_region() {
+        let config = AwsConfig {
+            region: Some("us west 2".to_string()),
+            sso_start_url: None,
+        };
+        assert!(matches!(
+            AwsCommand::url(&config, "lambda"),
+            Err(CommandError::InvalidArguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_aws_command_resources() {
+        assert_eq!(
+            url("lambda my-function").as_deref(),
+            Ok("https://console.aws.amazon.com/lambda/home#/functions/my-function")
+        );
+        assert_eq!(
+            url("s3 my-bucket eu-west-1").as_deref(),
+            Ok("https://eu-west-1.console.aws.amazon.com/s3/buckets/my-bucket?region=eu-west-1")
+        );
+        assert_eq!(
+            url("ec2 us-west-2 i-0abc123").as_deref(),
+            Ok(
+                "https://us-west-2.console.aws.amazon.com/ec2/home?region=us-west-2#InstanceDetails:instanceId=i-0abc123"
+            )
+        );
+        assert_eq!(
+            url("cfn prod-api").as_deref(),
+            Ok("https://console.aws.amazon.com/cloudformation/home#/stacks?filteringText=prod-api")
+        );
+        assert!(url("iam admins").is_err());
+        assert!(url("lambda a b").is_err());
+    }
+
+    #[test]
+    fn test_is_region() {
+        assert!(is_region("us-west-2"));
+        assert!(is_region("ap-southeast-1"));
+        assert!(is_region("us-gov-east-1"));
+        assert!(!is_region("my-function"));
+        assert!(!is_region("i-0abc123"));
+        assert!(!is_region("west-2"));
+    }
+}
@@ -6,25 +6,36 @@
 /// Examples:
 /// - bindings -> /bindings
 /// - list -> /bindings
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+/// - help gh -> /help/gh
+use std::borrow::Cow;
 
-pub struct BindingsCommand;
-
-impl BunnylolCommand for BindingsCommand {
-    const BINDINGS: &'static [&'static str] = &[
-        "bindings", "commands", "list", "bunny", "cmd", "cmds", "help",
-    ];
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::args::Args;
+use crate::utils::url_encoding::build_path_url;
 
-    fn process_args(_args: &str) -> String {
-        "/bindings".to_string()
-    }
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("bindings", "commands", "list", "bunny", "cmd", "cmds", "help"),
+    description = "View all Bunnylol command bindings in a web portal",
+    example = "bindings",
+    examples("help gh", "commands"),
+    args = "[<command>]",
+    category = Other,
+    tags("help", "commands", "cheatsheet", "docs"),
+    usage(
+        "bindings" = "List every command",
+        "help <command>" = "Show the help page for a command"
+    )
+)]
+pub struct BindingsCommand;
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "View all Bunnylol command bindings in a web portal",
-            "bindings",
-        )
+impl BindingsCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let query = Self::get_command_args(args);
+        match Args::new(query).next() {
+            Some(command) => build_path_url("/help", &command).into(),
+            None => "/bindings".into(),
+        }
     }
 }
 
@@ -61,4 +72,10 @@ mod tests {
     fn test_help_command() {
         assert_eq!(BindingsCommand::process_args("help"), "/bindings");
     }
+
+    #[test]
+    fn test_help_for_command() {
+        assert_eq!(BindingsCommand::process_args("help gh"), "/help/gh");
+        assert_eq!(BindingsCommand::process_args("help $"), "/help/$");
+    }
 }
@@ -2,30 +2,35 @@
 /// Supports:
 /// - brew/homebrew -> https://formulae.brew.sh
 /// - brew [search terms] -> https://formulae.brew.sh/?search=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct BrewCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for BrewCommand {
-    const BINDINGS: &'static [&'static str] = &["brew", "homebrew"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("brew", "homebrew"),
+    description = "Navigate to formulae.brew.sh or search for Homebrew packages",
+    example = "brew wget",
+    examples("brew"),
+    args = "[<package>]",
+    category = Packages,
+    tags("packages", "macos", "formulae", "install")
+)]
+pub struct BrewCommand;
 
-    fn process_args(args: &str) -> String {
+impl BrewCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://formulae.brew.sh".to_string()
+            "https://formulae.brew.sh".into()
         } else {
-            format!("https://formulae.brew.sh/?search={}", encode_url(query))
+            UrlBuilder::new("https://formulae.brew.sh/")
+                .query("search", query)
+                .build()
+                .into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to formulae.brew.sh or search for Homebrew packages",
-            "brew wget",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,14 @@
-use serde::Serialize;
+use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::{Deserialize, Serialize};
+
+/// `#[derive(BunnylolCommand)]`: generates `BINDINGS` and `get_info()` from a
+/// `#[bunnylol(...)]` attribute and registers the command; the command writes
+/// `process_args` as an inherent method
+pub use bunnylol_derive::BunnylolCommand;
 
 /// Information about a registered command binding
 #[derive(Clone, Serialize)]
@@ -6,6 +16,82 @@ pub struct BunnylolCommandInfo {
     pub bindings: Vec<String>,
     pub description: String,
     pub example: String,
+    /// Every example, `example` first
+    pub examples: Vec<String>,
+    /// Argument signature after the binding, e.g. "[<owner>/<repo> | <search terms>]";
+    /// empty for commands that ignore their arguments
+    pub args: String,
+    /// Extra words the command is found by when searching, e.g. "code" for `gh`
+    pub tags: Vec<String>,
+    /// Accepted argument forms, shown on the command's help page
+    pub usage: Vec<CommandUsage>,
+    /// The command's `SUBCOMMANDS`, as argument forms
+    pub subcommands: Vec<CommandUsage>,
+    /// Config keys (dotted paths, e.g. "stock_provider") that change this command's behavior
+    pub config_keys: Vec<String>,
+    /// Group the command is listed under on the bindings page
+    pub category: CommandCategory,
+}
+
+/// Category used to group commands on the bindings page
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandCategory {
+    Search,
+    Dev,
+    Docs,
+    Packages,
+    Ai,
+    Productivity,
+    Social,
+    Media,
+    Shopping,
+    Finance,
+    #[default]
+    Other,
+}
+
+impl CommandCategory {
+    /// All categories, in the order they're displayed
+    pub const ALL: &'static [CommandCategory] = &[
+        CommandCategory::Search,
+        CommandCategory::Dev,
+        CommandCategory::Docs,
+        CommandCategory::Packages,
+        CommandCategory::Ai,
+        CommandCategory::Productivity,
+        CommandCategory::Social,
+        CommandCategory::Media,
+        CommandCategory::Shopping,
+        CommandCategory::Finance,
+        CommandCategory::Other,
+    ];
+
+    /// Human-readable name shown as the section heading
+    pub fn label(self) -> &'static str {
+        match self {
+            CommandCategory::Search => "Search",
+            CommandCategory::Dev => "Dev",
+            CommandCategory::Docs => "Docs",
+            CommandCategory::Packages => "Packages",
+            CommandCategory::Ai => "AI",
+            CommandCategory::Productivity => "Productivity",
+            CommandCategory::Social => "Social",
+            CommandCategory::Media => "Media",
+            CommandCategory::Shopping => "Shopping",
+            CommandCategory::Finance => "Finance",
+            CommandCategory::Other => "Other",
+        }
+    }
+}
+
+/// One accepted argument form for a command, e.g. `gh @<user>`
+#[derive(Clone, Serialize)]
+pub struct CommandUsage {
+    pub syntax: String,
+    pub description: String,
 }
 
 impl BunnylolCommandInfo {
@@ -15,31 +101,247 @@ impl BunnylolCommandInfo {
             bindings: bindings.iter().map(|s| s.to_string()).collect(),
             description: description.to_string(),
             example: example.to_string(),
+            examples: vec![example.to_string()],
+            args: String::new(),
+            tags: Vec::new(),
+            usage: Vec::new(),
+            subcommands: Vec::new(),
+            config_keys: Vec::new(),
+            category: CommandCategory::default(),
+        }
+    }
+
+    /// Set the group this command is listed under
+    pub fn with_category(mut self, category: CommandCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Add examples after the main one
+    pub fn with_examples(mut self, examples: &[&str]) -> Self {
+        self.examples
+            .extend(examples.iter().map(|example| example.to_string()));
+        self
+    }
+
+    /// Set the argument signature shown after the binding
+    pub fn with_args(mut self, args: &str) -> Self {
+        self.args = args.to_string();
+        self
+    }
+
+    /// Set the extra words the command is found by
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    /// The primary binding followed by the argument signature, e.g. "gh [<owner>/<repo>]"
+    pub fn signature(&self) -> String {
+        let binding = self
+            .bindings
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default();
+        if self.args.is_empty() {
+            binding.to_string()
+        } else {
+            format!("{} {}", binding, self.args)
+        }
+    }
+
+    /// Document the accepted argument forms as (syntax, description) pairs
+    pub fn with_usage(mut self, usage: &[(&str, &str)]) -> Self {
+        self.usage = usage
+            .iter()
+            .map(|(syntax, description)| CommandUsage {
+                syntax: syntax.to_string(),
+                description: description.to_string(),
+            })
+            .collect();
+        self
+    }
+
+    /// Document a command's subcommands, as reached through `binding`
+    pub fn with_subcommands(mut self, binding: &str, subcommands: &[Subcommand]) -> Self {
+        self.subcommands = subcommands
+            .iter()
+            .map(|subcommand| {
+                let syntax = match subcommand.target {
+                    SubcommandTarget::Url(_) => format!("{} {}", binding, subcommand.names[0]),
+                    SubcommandTarget::Handler { args, .. } => {
+                        format!("{} {} {}", binding, subcommand.names[0], args)
+                    }
+                };
+                let description = match subcommand.names[1..].join(", ") {
+                    aliases if aliases.is_empty() => subcommand.description.to_string(),
+                    aliases => format!("{} (also: {})", subcommand.description, aliases),
+                };
+                CommandUsage {
+                    syntax,
+                    description,
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Document which config keys this command reads
+    pub fn with_config_keys(mut self, keys: &[&str]) -> Self {
+        self.config_keys = keys.iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+/// A word after a command's binding that picks one of its forms, e.g.
+/// `gh tokens`, declared in a table instead of an if-chain
+pub struct Subcommand {
+    /// The words that pick it; the first is the one shown on help pages
+    pub names: &'static [&'static str],
+    pub description: &'static str,
+    pub target: SubcommandTarget,
+}
+
+/// Where a subcommand goes
+pub enum SubcommandTarget {
+    /// A fixed page, used only when the subcommand is the whole argument, so
+    /// `az orders` opens your orders but `az orders tray` still searches
+    Url(&'static str),
+    /// A URL built from the arguments after the subcommand; `args` documents
+    /// them (e.g. "<search terms>")
+    Handler {
+        args: &'static str,
+        handler: fn(&str) -> Cow<'static, str>,
+    },
+}
+
+impl Subcommand {
+    /// A subcommand that opens a fixed page
+    pub const fn url(
+        names: &'static [&'static str],
+        description: &'static str,
+        url: &'static str,
+    ) -> Self {
+        Subcommand {
+            names,
+            description,
+            target: SubcommandTarget::Url(url),
         }
     }
+
+    /// A subcommand that builds its URL from the arguments after it
+    pub const fn handler(
+        names: &'static [&'static str],
+        args: &'static str,
+        description: &'static str,
+        handler: fn(&str) -> Cow<'static, str>,
+    ) -> Self {
+        Subcommand {
+            names,
+            description,
+            target: SubcommandTarget::Handler { args, handler },
+        }
+    }
+}
+
+/// Why a command couldn't turn its arguments into a URL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    /// The arguments don't fit any of the command's forms (e.g. `gh facebook/`)
+    InvalidArguments(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::InvalidArguments(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// What a command's inherent `process_args` may return: a URL, or a URL or an
+/// error. Lets `#[derive(BunnylolCommand)]` take either, so commands that
+/// can't fail keep returning a URL. A `Cow` URL lets fixed URLs (a bare `gh`)
+/// skip allocating.
+pub trait IntoCommandResult {
+    fn into_command_result(self) -> Result<Cow<'static, str>, CommandError>;
+}
+
+impl IntoCommandResult for String {
+    fn into_command_result(self) -> Result<Cow<'static, str>, CommandError> {
+        Ok(Cow::Owned(self))
+    }
+}
+
+impl IntoCommandResult for Cow<'static, str> {
+    fn into_command_result(self) -> Result<Cow<'static, str>, CommandError> {
+        Ok(self)
+    }
+}
+
+impl IntoCommandResult for Result<String, CommandError> {
+    fn into_command_result(self) -> Result<Cow<'static, str>, CommandError> {
+        self.map(Cow::Owned)
+    }
 }
 
+impl IntoCommandResult for Result<Cow<'static, str>, CommandError> {
+    fn into_command_result(self) -> Result<Cow<'static, str>, CommandError> {
+        self
+    }
+}
+
+/// The URL (or error) an async command handler resolves to
+pub type CommandFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Cow<'static, str>, CommandError>> + Send + 'a>>;
+
 /// Bunnylol Command trait that all URL builders must implement
 pub trait BunnylolCommand {
     /// All command strings that trigger this binding (e.g., ["gh", "github"])
     const BINDINGS: &'static [&'static str];
 
-    /// Process the command arguments and return the appropriate URL
-    fn process_args(args: &str) -> String;
+    /// Process the command arguments and return the appropriate URL, or why
+    /// the arguments can't be turned into one
+    fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError>;
+
+    /// Async version of `process_args`, for commands that need network I/O to
+    /// build their URL. The server and CLI await this; dry-run resolution
+    /// (`/api/resolve`) can't, and uses `process_args`. Defaults to
+    /// `process_args`.
+    fn process_args_async(args: &str) -> CommandFuture<'_> {
+        Box::pin(std::future::ready(Self::process_args(args)))
+    }
 
     /// Get the command portion from the full arguments string
     fn get_command_args(args: &str) -> &str {
-        // Check if args starts with any of the bindings
-        for binding in Self::BINDINGS {
-            if args.split_whitespace().next() == Some(*binding) {
-                if args.len() <= binding.len() {
-                    return "";
-                } else {
-                    return args[binding.len()..].trim_start();
-                }
-            }
+        // Only the first word can be a binding, so find it once
+        let args = args.trim_start();
+        let command = args.split_whitespace().next().unwrap_or_default();
+        if Self::BINDINGS.contains(&command) {
+            args[command.len()..].trim_start()
+        } else {
+            args
         }
-        args
+    }
+
+    /// The command's subcommands; with `#[bunnylol(subcommands)]`, the
+    /// derive takes them from an inherent `SUBCOMMANDS` const
+    const SUBCOMMANDS: &'static [Subcommand] = &[];
+
+    /// The URL of the subcommand the arguments start with, if any
+    fn process_subcommand(args: &str) -> Option<Cow<'static, str>> {
+        let query = Self::get_command_args(args);
+        let name = query.split_whitespace().next()?;
+        let rest = query[name.len()..].trim_start();
+        Self::SUBCOMMANDS
+            .iter()
+            .filter(|subcommand| subcommand.names.contains(&name))
+            .find_map(|subcommand| match subcommand.target {
+                SubcommandTarget::Url(url) => rest.is_empty().then_some(Cow::Borrowed(url)),
+                SubcommandTarget::Handler { handler, .. } => Some(handler(rest)),
+            })
     }
 
     /// Check if this binding matches the given command
@@ -51,6 +353,39 @@ pub trait BunnylolCommand {
     fn get_info() -> BunnylolCommandInfo;
 }
 
+/// A command as the registry stores it: the trait's items as plain function
+/// pointers, so every command fits in one collection
+pub struct RegisteredCommand {
+    pub bindings: &'static [&'static str],
+    pub process_args: fn(&str) -> Result<Cow<'static, str>, CommandError>,
+    pub process_args_async: for<'a> fn(&'a str) -> CommandFuture<'a>,
+    pub get_info: fn() -> BunnylolCommandInfo,
+}
+
+impl RegisteredCommand {
+    pub const fn of<C: BunnylolCommand>() -> Self {
+        RegisteredCommand {
+            bindings: C::BINDINGS,
+            process_args: C::process_args,
+            process_args_async: C::process_args_async,
+            get_info: C::get_info,
+        }
+    }
+}
+
+inventory::collect!(RegisteredCommand);
+
+/// Register a command that implements `BunnylolCommand` by hand;
+/// `#[derive(BunnylolCommand)]` registers the command itself
+#[macro_export]
+macro_rules! register_command {
+    ($cmd:ty) => {
+        $crate::inventory::submit! {
+            $crate::commands::bunnylol_command::RegisteredCommand::of::<$cmd>()
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,12 +396,12 @@ mod tests {
     impl BunnylolCommand for TestCommand {
         const BINDINGS: &'static [&'static str] = &["test", "t"];
 
-        fn process_args(args: &str) -> String {
+        fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
             let query = Self::get_command_args(args);
             if query.is_empty() {
-                "https://test.com".to_string()
+                Ok(Cow::Borrowed("https://test.com"))
             } else {
-                format!("https://test.com/search?q={}", query)
+                Ok(Cow::Owned(format!("https://test.com/search?q={}", query)))
             }
         }
 
@@ -79,6 +414,8 @@ mod tests {
     fn test_bunnylol_command_get_command_args() {
         assert_eq!(TestCommand::get_command_args("test"), "");
         assert_eq!(TestCommand::get_command_args("test hello"), "hello");
+        assert_eq!(TestCommand::get_command_args("  t  hello"), "hello");
+        assert_eq!(TestCommand::get_command_args("other hello"), "other hello");
         assert_eq!(
             TestCommand::get_command_args("test hello world"),
             "hello world"
@@ -92,17 +429,194 @@ mod tests {
         assert!(!TestCommand::matches_command("other"));
     }
 
+    #[test]
+    fn test_bunnylol_command_info_builders() {
+        let info = TestCommand::get_info()
+            .with_usage(&[("test <query>", "Search test.com")])
+            .with_config_keys(&["default_search"])
+            .with_category(CommandCategory::Search);
+        assert_eq!(info.category, CommandCategory::Search);
+        assert_eq!(info.usage.len(), 1);
+        assert_eq!(info.usage[0].syntax, "test <query>");
+        assert_eq!(info.config_keys, vec!["default_search".to_string()]);
+    }
+
+    /// Same command as `TestCommand`, with the boilerplate derived
+    #[derive(BunnylolCommand)]
+    #[bunnylol(
+        bindings("derived", "d"),
+        description = "Derived command",
+        example = "derived query",
+        category = Search,
+        usage("derived" = "Open test.com", "derived <query>" = "Search test.com"),
+        config_keys("default_search"),
+        unregistered
+    )]
+    struct DerivedCommand;
+
+    impl DerivedCommand {
+        fn process_args(args: &str) -> String {
+            format!("https://test.com/search?q={}", Self::get_command_args(args))
+        }
+    }
+
+    #[test]
+    fn test_derived_command() {
+        assert_eq!(DerivedCommand::BINDINGS, &["derived", "d"]);
+        assert_eq!(
+            <DerivedCommand as BunnylolCommand>::process_args("d hello").as_deref(),
+            Ok("https://test.com/search?q=hello")
+        );
+
+        let info = DerivedCommand::get_info();
+        assert_eq!(info.bindings, vec!["derived", "d"]);
+        assert_eq!(info.description, "Derived command");
+        assert_eq!(info.example, "derived query");
+        assert_eq!(info.category, CommandCategory::Search);
+        assert_eq!(info.usage.len(), 2);
+        assert_eq!(info.usage[1].syntax, "derived <query>");
+        assert_eq!(info.config_keys, vec!["default_search".to_string()]);
+    }
+
+    /// A derived command with a subcommand table
+    #[derive(BunnylolCommand)]
+    #[bunnylol(
+        bindings("sub"),
+        description = "Command with subcommands",
+        example = "sub new",
+        subcommands,
+        unregistered
+    )]
+    struct SubcommandCommand;
+
+    impl SubcommandCommand {
+        const SUBCOMMANDS: &[Subcommand] = &[
+            Subcommand::url(&["new", "n"], "Open new posts", "https://test.com/new"),
+            Subcommand::handler(&["search"], "<query>", "Search posts", |rest| {
+                format!("https://test.com/search?q={}", rest).into()
+            }),
+        ];
+
+        fn process_args(args: &str) -> Cow<'static, str> {
+            Self::process_subcommand(args).unwrap_or(Cow::Borrowed("https://test.com"))
+        }
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let process = <SubcommandCommand as BunnylolCommand>::process_args;
+        assert!(matches!(
+            process("sub n"),
+            Ok(Cow::Borrowed("https://test.com/new"))
+        ));
+        assert_eq!(
+            process("sub search rust").as_deref(),
+            Ok("https://test.com/search?q=rust")
+        );
+        // A fixed page only matches on its own
+        assert_eq!(process("sub new posts").as_deref(), Ok("https://test.com"));
+        assert_eq!(process("sub").as_deref(), Ok("https://test.com"));
+
+        let info = SubcommandCommand::get_info();
+        assert_eq!(info.subcommands.len(), 2);
+        assert_eq!(info.subcommands[0].syntax, "sub new");
+        assert_eq!(info.subcommands[0].description, "Open new posts (also: n)");
+        assert_eq!(info.subcommands[1].syntax, "sub search <query>");
+        assert!(TestCommand::get_info().subcommands.is_empty());
+    }
+
+    /// A derived command whose `process_args` can reject its arguments
+    #[derive(BunnylolCommand)]
+    #[bunnylol(
+        bindings("fallible"),
+        description = "Fallible command",
+        example = "fallible 42",
+        unregistered
+    )]
+    struct FallibleCommand;
+
+    impl FallibleCommand {
+        fn process_args(args: &str) -> Result<String, CommandError> {
+            let query = Self::get_command_args(args);
+            query
+                .parse::<u32>()
+                .map(|id| format!("https://test.com/{}", id))
+                .map_err(|_| CommandError::InvalidArguments(format!("'{}' isn't an id", query)))
+        }
+    }
+
+    #[test]
+    fn test_derived_fallible_command() {
+        assert_eq!(
+            <FallibleCommand as BunnylolCommand>::process_args("fallible 42").as_deref(),
+            Ok("https://test.com/42")
+        );
+        let error = <FallibleCommand as BunnylolCommand>::process_args("fallible x").unwrap_err();
+        assert_eq!(error.to_string(), "'x' isn't an id");
+    }
+
+    /// A derived command that does its real work asynchronously
+    #[derive(BunnylolCommand)]
+    #[bunnylol(
+        bindings("async"),
+        description = "Async command",
+        example = "async short",
+        async_handler,
+        unregistered
+    )]
+    struct AsyncCommand;
+
+    impl AsyncCommand {
+        fn process_args(args: &str) -> String {
+            format!("https://test.com/{}", Self::get_command_args(args))
+        }
+
+        async fn process_args_async(args: &str) -> Result<String, CommandError> {
+            tokio::task::yield_now().await;
+            Ok(format!(
+                "https://test.com/expanded/{}",
+                Self::get_command_args(args)
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_handler() {
+        assert_eq!(
+            <AsyncCommand as BunnylolCommand>::process_args_async("async short")
+                .await
+                .as_deref(),
+            Ok("https://test.com/expanded/short")
+        );
+        // Sync callers still get the plain handler
+        assert_eq!(
+            <AsyncCommand as BunnylolCommand>::process_args("async short").as_deref(),
+            Ok("https://test.com/short")
+        );
+        // Commands without one fall back to process_args
+        assert_eq!(
+            TestCommand::process_args_async("t hello").await.as_deref(),
+            Ok("https://test.com/search?q=hello")
+        );
+    }
+
     #[test]
     fn test_bunnylol_command_process_args() {
-        assert_eq!(TestCommand::process_args("test"), "https://test.com");
-        assert_eq!(TestCommand::process_args("t"), "https://test.com");
         assert_eq!(
-            TestCommand::process_args("test hello"),
-            "https://test.com/search?q=hello"
+            TestCommand::process_args("test").as_deref(),
+            Ok("https://test.com")
+        );
+        assert_eq!(
+            TestCommand::process_args("t").as_deref(),
+            Ok("https://test.com")
+        );
+        assert_eq!(
+            TestCommand::process_args("test hello").as_deref(),
+            Ok("https://test.com/search?q=hello")
         );
         assert_eq!(
-            TestCommand::process_args("t hello"),
-            "https://test.com/search?q=hello"
+            TestCommand::process_args("t hello").as_deref(),
+            Ok("https://test.com/search?q=hello")
         );
     }
 }
@@ -4,31 +4,50 @@
 /// - cargo [search terms] -> https://crates.io/search?q=[search terms]
 /// - cargo settings -> https://crates.io/settings/profile
 /// - cargo tokens/api -> https://crates.io/settings/tokens
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("cargo", "crates"),
+    description = "Navigate to crates.io or search for Rust crates",
+    example = "cargo serde",
+    examples("cargo tokens", "cargo"),
+    args = "[tokens | settings | <search terms>]",
+    category = Packages,
+    tags("rust", "packages", "crates.io", "library"),
+    usage("cargo" = "Open crates.io", "cargo <search terms>" = "Search crates"),
+    subcommands
+)]
 pub struct CargoCommand;
 
-impl BunnylolCommand for CargoCommand {
-    const BINDINGS: &'static [&'static str] = &["cargo", "crates"];
+impl CargoCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(
+            &["tokens", "api"],
+            "Open API token settings",
+            "https://crates.io/settings/tokens",
+        ),
+        Subcommand::url(
+            &["settings"],
+            "Open profile settings",
+            "https://crates.io/settings/profile",
+        ),
+    ];
 
-    fn process_args(args: &str) -> String {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
         let query = Self::get_command_args(args);
-        match query {
-            "" => "https://crates.io".to_string(),
-            "settings" => "https://crates.io/settings/profile".to_string(),
-            "tokens" | "api" => "https://crates.io/settings/tokens".to_string(),
-            _ => build_search_url("https://crates.io/search", "q", query),
+        if query.is_empty() {
+            "https://crates.io".into()
+        } else {
+            build_search_url("https://crates.io/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to crates.io or search for Rust crates",
-            "cargo serde",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,18 +1,22 @@
 /// ChatGPT command handler
 /// Supports: chatgpt -> redirects to chatgpt.com
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct ChatGPTCommand;
-
-impl BunnylolCommand for ChatGPTCommand {
-    const BINDINGS: &'static [&'static str] = &["chatgpt"];
+use crate::commands::bunnylol_command::BunnylolCommand;
 
-    fn process_args(_args: &str) -> String {
-        "https://chatgpt.com".to_string()
-    }
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("chatgpt"),
+    description = "Navigate to ChatGPT",
+    example = "chatgpt",
+    category = Ai,
+    tags("ai", "openai", "chat", "llm")
+)]
+pub struct ChatGPTCommand;
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to ChatGPT", "chatgpt")
+impl ChatGPTCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        "https://chatgpt.com".into()
     }
 }
 
@@ -2,30 +2,32 @@
 /// Supports:
 /// - choco/chocolatey -> https://community.chocolatey.org
 /// - choco [search terms] -> https://community.chocolatey.org/packages?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("choco", "chocolatey"),
+    description = "Navigate to community.chocolatey.org or search for Windows packages",
+    example = "choco git",
+    examples("choco"),
+    args = "[<package>]",
+    category = Packages,
+    tags("packages", "windows", "install")
+)]
 pub struct ChocoCommand;
 
-impl BunnylolCommand for ChocoCommand {
-    const BINDINGS: &'static [&'static str] = &["choco", "chocolatey"];
-
-    fn process_args(args: &str) -> String {
+impl ChocoCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://community.chocolatey.org".to_string()
+            "https://community.chocolatey.org".into()
         } else {
-            build_search_url("https://community.chocolatey.org/packages", "q", query)
+            build_search_url("https://community.chocolatey.org/packages", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to community.chocolatey.org or search for Windows packages",
-            "choco git",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,42 +1,68 @@
 /// Claude command handler
 /// Supports: claude -> redirects to claude.ai
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct ClaudeCommand;
-
-impl BunnylolCommand for ClaudeCommand {
-    const BINDINGS: &'static [&'static str] = &["claude"];
-
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
-        println!(
-            "ClaudeCommand::process_args - input: {:?}, query: {:?}",
-            args, query
-        );
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
 
-        let result = match query {
-            "platform" => "https://platform.claude.com".to_string(),
-            "api" | "keys" | "apikey" => "https://platform.claude.com/settings/keys".to_string(),
-            "billing" | "cost" => "https://claude.ai/settings/billing".to_string(),
-            "artifacts" => "https://claude.ai/artifacts".to_string(),
-            "artifacts my" => "https://claude.ai/artifacts/my".to_string(),
-            "chats" => "https://claude.ai/recents".to_string(),
-            "projects" => "https://claude.ai/projects".to_string(),
-            "usage" => "https://claude.ai/settings/usage".to_string(),
-            "upgrade" => "https://claude.ai/upgrade".to_string(),
-            _ => "https://claude.ai".to_string(),
-        };
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("claude"),
+    description = "Navigate to Claude AI (supports: billing, cost, artifacts, chats, projects)",
+    example = "claude projects",
+    examples("claude chats", "claude artifacts my"),
+    args = "[<subcommand>]",
+    category = Ai,
+    tags("ai", "anthropic", "chat", "llm"),
+    usage("claude" = "Open Claude"),
+    subcommands
+)]
+pub struct ClaudeCommand;
 
-        println!("   >> ClaudeCommand::process_args - returning: {}", result);
-        result
-    }
+impl ClaudeCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(&["projects"], "Open projects", "https://claude.ai/projects"),
+        Subcommand::url(&["chats"], "Open recent chats", "https://claude.ai/recents"),
+        Subcommand::handler(
+            &["artifacts"],
+            "[my]",
+            "Open artifacts, or your own",
+            |rest| {
+                if rest == "my" {
+                    "https://claude.ai/artifacts/my".into()
+                } else {
+                    "https://claude.ai/artifacts".into()
+                }
+            },
+        ),
+        Subcommand::url(
+            &["billing", "cost"],
+            "Open billing settings",
+            "https://claude.ai/settings/billing",
+        ),
+        Subcommand::url(
+            &["usage"],
+            "Open usage settings",
+            "https://claude.ai/settings/usage",
+        ),
+        Subcommand::url(
+            &["upgrade"],
+            "Upgrade your plan",
+            "https://claude.ai/upgrade",
+        ),
+        Subcommand::url(
+            &["api", "keys", "apikey"],
+            "Open API keys on the Claude platform",
+            "https://platform.claude.com/settings/keys",
+        ),
+        Subcommand::url(
+            &["platform"],
+            "Open the Claude platform",
+            "https://platform.claude.com",
+        ),
+    ];
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Claude AI (supports: billing, cost, artifacts, chats, projects)",
-            "claude projects",
-        )
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        Self::process_subcommand(args).unwrap_or(Cow::Borrowed("https://claude.ai"))
     }
 }
 
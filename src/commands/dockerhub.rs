@@ -2,30 +2,32 @@
 /// Supports:
 /// - dockerhub/docker -> https://hub.docker.com
 /// - dockerhub [search terms] -> https://hub.docker.com/search?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("dockerhub", "docker"),
+    description = "Navigate to Docker Hub or search for container images",
+    example = "docker nginx",
+    examples("docker"),
+    args = "[<image>]",
+    category = Packages,
+    tags("containers", "images", "packages", "registry")
+)]
 pub struct DockerhubCommand;
 
-impl BunnylolCommand for DockerhubCommand {
-    const BINDINGS: &'static [&'static str] = &["dockerhub", "docker"];
-
-    fn process_args(args: &str) -> String {
+impl DockerhubCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://hub.docker.com".to_string()
+            "https://hub.docker.com".into()
         } else {
-            build_search_url("https://hub.docker.com/search", "q", query)
+            build_search_url("https://hub.docker.com/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Docker Hub or search for container images",
-            "docker nginx",
-        )
-    }
 }
 
 #[cfg(test)]
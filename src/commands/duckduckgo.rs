@@ -1,21 +1,25 @@
 /// DuckDuckGo Search command handler
 /// Supports: ddg [search terms], duckduckgo [search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("ddg", "duckduckgo"),
+    description = "Search DuckDuckGo",
+    example = "ddg rust programming",
+    examples("ddg !w rust"),
+    args = "<search terms>",
+    category = Search,
+    tags("search", "web", "privacy")
+)]
 pub struct DuckDuckGoCommand;
 
-impl BunnylolCommand for DuckDuckGoCommand {
-    const BINDINGS: &'static [&'static str] = &["ddg", "duckduckgo"];
-
-    fn process_args(args: &str) -> String {
+impl DuckDuckGoCommand {
+    pub fn process_args(args: &str) -> String {
         let query = Self::get_command_args(args);
         build_search_url("https://duckduckgo.com/", "q", query)
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Search DuckDuckGo", "ddg rust programming")
-    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,21 @@
 /// Facebook command handler
 /// Supports: fb, fb [username/page], fb [search terms]
 /// Subcommands: mp/buy/sell -> Marketplace
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::{build_path_url, build_search_url};
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("fb"),
+    description = "Navigate to Facebook pages or search Facebook",
+    example = "fb Meta",
+    examples("fb mp", "fb meta open source"),
+    args = "[mp | <page> | <search terms>]",
+    category = Social,
+    tags("social", "marketplace", "pages")
+)]
 pub struct FacebookCommand;
 
 impl FacebookCommand {
@@ -18,26 +30,16 @@ impl FacebookCommand {
     }
 }
 
-impl BunnylolCommand for FacebookCommand {
-    const BINDINGS: &'static [&'static str] = &["fb"];
-
-    fn process_args(args: &str) -> String {
+impl FacebookCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         match query {
-            "" => "https://www.facebook.com".to_string(),
-            "mp" | "buy" | "sell" => Self::MARKETPLACE_URL.to_string(),
-            _ if !query.contains(' ') => Self::construct_profile_url(query),
-            _ => Self::construct_search_url(query),
+            "" => "https://www.facebook.com".into(),
+            "mp" | "buy" | "sell" => Self::MARKETPLACE_URL.into(),
+            _ if !query.contains(' ') => Self::construct_profile_url(query).into(),
+            _ => Self::construct_search_url(query).into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Facebook pages or search Facebook",
-            "fb Meta",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,132 @@
+/// Figma command handler
+/// Supports:
+/// - figma/fig -> your recent files
+/// - figma [file URL | file key] -> the design
+/// - figma [search terms] -> a search of your files, or the `[figma] team` team's
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("figma", "fig"),
+    description = "Open Figma files and designs, or search them",
+    example = "figma",
+    examples("fig onboarding flow", "fig https://www.figma.com/design/abc123/App"),
+    args = "[<file URL> | <file key> | <search terms>]",
+    category = Productivity,
+    tags("design", "prototypes", "ui", "work"),
+    config_keys("figma.team"),
+    usage(
+        "figma" = "Open your recent files",
+        "figma <file URL>" = "Open a Figma link",
+        "figma <file key>" = "Open a design by its key",
+        "figma <search terms>" = "Search your files, or your team's"
+    )
+)]
+pub struct FigmaCommand;
+
+impl FigmaCommand {
+    /// Length of the key in a file's URL, like `figma.com/design/<key>/...`
+    const FILE_KEY_LEN: usize = 22;
+
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let team = get_global_config().and_then(|config| config.figma.team.as_deref());
+        Self::url(team, Self::get_command_args(args)).into()
+    }
+
+    /// The page for `query`, searching `team` when set
+    fn url(team: Option<&str>, query: &str) -> String {
+        if query.is_empty() {
+            "https://www.figma.com/files/recent".to_string()
+        } else if let Some(url) = Self::figma_link(query) {
+            url
+        } else if query.len() == Self::FILE_KEY_LEN
+            && query.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            UrlBuilder::new("https://www.figma.com/design")
+                .segment(query)
+                .build()
+        } else {
+            let files = UrlBuilder::new("https://www.figma.com/files");
+            match team.map(str::trim).filter(|team| !team.is_empty()) {
+                Some(team) => files.path("team").segment(team).path("search"),
+                None => files.path("search"),
+            }
+            .query("q", query)
+            .build()
+        }
+    }
+
+    /// `query` as a link to a page on figma.com, with or without the scheme
+    fn figma_link(query: &str) -> Option<String> {
+        if query.contains(char::is_whitespace) {
+            return None;
+        }
+        let url = if query.contains("://") {
+            url::Url::parse(query)
+        } else {
+            url::Url::parse(&format!("https://{}", query))
+        }
+        .ok()?;
+        let host = url.host_str()?;
+        let is_figma = host == "figma.com" || host.ends_with(".figma.com");
+        (matches!(url.scheme(), "http" | "https") && is_figma).then(|| url.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_figma_command_base() {
+        assert_eq!(
+            FigmaCommand::process_args("figma"),
+            "https://www.figma.com/files/recent"
+        );
+        assert_eq!(
+            FigmaCommand::process_args("fig"),
+            "https://www.figma.com/files/recent"
+        );
+    }
+
+    #[test]
+    fn test_figma_command_links() {
+        assert_eq!(
+            FigmaCommand::process_args("fig https://www.figma.com/design/abc123/App?node-id=1-2"),
+            "https://www.figma.com/design/abc123/App?node-id=1-2"
+        );
+        assert_eq!(
+            FigmaCommand::process_args("fig figma.com/file/abc123/App"),
+            "https://figma.com/file/abc123/App"
+        );
+        // Other sites are searched, not opened
+        assert_eq!(
+            FigmaCommand::process_args("fig https://example.com/figma.com"),
+            "https://www.figma.com/files/search?q=https://example.com/figma.com"
+        );
+    }
+
+    #[test]
+    fn test_figma_command_file_key() {
+        assert_eq!(
+            FigmaCommand::process_args("fig aBcDeFgHiJkLmNoPqRsTuV"),
+            "https://www.figma.com/design/aBcDeFgHiJkLmNoPqRsTuV"
+        );
+    }
+
+    #[test]
+    fn test_figma_command_search() {
+        assert_eq!(
+            FigmaCommand::url(None, "onboarding flow"),
+            "https://www.figma.com/files/search?q=onboarding%20flow"
+        );
+        assert_eq!(
+            FigmaCommand::url(Some("1234567890"), "onboarding flow"),
+            "https://www.figma.com/files/team/1234567890/search?q=onboarding%20flow"
+        );
+    }
+}
@@ -1,48 +1,280 @@
 /// GitHub command handler
-/// Supports: gh, gh @[user], gh [user/repo], gh token[s], gh [search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::{build_path_url, build_search_url};
+/// Supports: gh/github, gh @[user], gh [user/repo], gh [user/repo] issues|pr [number],
+/// gh [user/repo/path], gh code [query], gh token[s], gh prs, gh issues, gh review,
+/// gh notifications, gh [search terms]
+use std::borrow::Cow;
 
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError, Subcommand};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::build_path_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gh", "github"),
+    description = "Navigate to GitHub profiles, repositories, or search GitHub",
+    example = "gh facebook/react",
+    examples(
+        "gh @octocat",
+        "gh rust-lang/rust pr 1234",
+        "gh facebook/react/blob/main/README.md",
+        "gh code tokio::select",
+        "gh pr",
+        "gh rust http client"
+    ),
+    args = "[@<user> | <owner>/<repo>[/<path>] [issues|pr [<number>]] | <subcommand> | <search terms>]",
+    category = Dev,
+    tags("git", "code", "repositories", "source"),
+    config_keys("github.org"),
+    usage(
+        "gh" = "Open GitHub",
+        "gh @<user>" = "Open a user or organization profile",
+        "gh <owner>/<repo>" = "Open a repository",
+        "gh <owner>/<repo> issues [<n>]" = "Open a repository's issues, or issue <n>; other words search them",
+        "gh <owner>/<repo> pr [<n>]" = "Open a repository's pull requests, or pull request <n>; other words search them",
+        "gh <owner>/<repo> <terms>" = "Search a repository's code",
+        "gh <owner>/<repo>/<path>" = "Open a file or directory, or a page like tree/main",
+        "gh code <search terms>" = "Search code across GitHub",
+        "gh <search terms>" = "Search GitHub repositories"
+    ),
+    subcommands
+)]
 pub struct GitHubCommand;
 
-impl BunnylolCommand for GitHubCommand {
-    const BINDINGS: &'static [&'static str] = &["gh"];
+impl GitHubCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(
+            &["token", "tokens"],
+            "Open personal access token settings",
+            "https://github.com/settings/personal-access-tokens",
+        ),
+        Subcommand::handler(
+            &["prs", "pr", "pulls"],
+            "[<search terms>]",
+            "Open your pull requests",
+            |rest| Self::MY_PULLS.url(Self::org().as_deref(), rest).into(),
+        ),
+        Subcommand::handler(
+            &["issues"],
+            "[<search terms>]",
+            "Open the issues assigned to you",
+            |rest| Self::MY_ISSUES.url(Self::org().as_deref(), rest).into(),
+        ),
+        Subcommand::handler(
+            &["review", "reviews"],
+            "[<search terms>]",
+            "Open the pull requests waiting for your review",
+            |rest| Self::MY_REVIEWS.url(Self::org().as_deref(), rest).into(),
+        ),
+        Subcommand::handler(
+            &["notifications", "notifs", "inbox"],
+            "[<search terms>]",
+            "Open your notifications",
+            |rest| {
+                Self::MY_NOTIFICATIONS
+                    .url(Self::org().as_deref(), rest)
+                    .into()
+            },
+        ),
+        Subcommand::handler(&["code"], "<search terms>", "Search code", |rest| {
+            Self::code_search_url(rest).into()
+        }),
+    ];
+
+    const MY_PULLS: Dashboard = Dashboard {
+        view: "https://github.com/pulls",
+        search: "https://github.com/pulls",
+        param: "q",
+        filter: "is:open is:pr author:@me",
+    };
+    const MY_ISSUES: Dashboard = Dashboard {
+        view: "https://github.com/issues/assigned",
+        search: "https://github.com/issues",
+        param: "q",
+        filter: "is:open is:issue assignee:@me",
+    };
+    const MY_REVIEWS: Dashboard = Dashboard {
+        view: "https://github.com/pulls/review-requested",
+        search: "https://github.com/pulls",
+        param: "q",
+        filter: "is:open is:pr review-requested:@me",
+    };
+    const MY_NOTIFICATIONS: Dashboard = Dashboard {
+        view: "https://github.com/notifications",
+        search: "https://github.com/notifications",
+        param: "query",
+        filter: "",
+    };
+
+    /// `[github] org`, which the personal dashboards are limited to
+    fn org() -> Option<String> {
+        get_global_config().and_then(|config| config.github.org.clone())
+    }
+
+    /// Repository pages that open as-is after `<owner>/<repo>/`; other paths
+    /// are files or directories on the default branch
+    const REPO_ROUTES: &[&str] = &[
+        "actions",
+        "blob",
+        "branches",
+        "commit",
+        "commits",
+        "compare",
+        "discussions",
+        "graphs",
+        "issues",
+        "labels",
+        "milestones",
+        "network",
+        "projects",
+        "pull",
+        "pulls",
+        "pulse",
+        "releases",
+        "security",
+        "settings",
+        "tags",
+        "tree",
+        "wiki",
+    ];
+
+    /// Repository search for `query`
+    fn search_url(query: &str) -> String {
+        UrlBuilder::new("https://github.com/search")
+            .query("q", query)
+            .query("type", "repositories")
+            .build()
+    }
+
+    /// Code search for `query`, or the empty code search page
+    fn code_search_url(query: &str) -> String {
+        let builder = UrlBuilder::new("https://github.com/search");
+        if query.is_empty() {
+            builder.query("type", "code").build()
+        } else {
+            builder.query("q", query).query("type", "code").build()
+        }
+    }
+
+    /// The page for `<owner>/<repo>[/<path>]` followed by `rest`: the
+    /// repository's issues or pull requests (one by number, or a search), or
+    /// a search of its code
+    fn repo_url(owner: &str, repo: &str, path: Option<&str>, rest: &str) -> String {
+        let repo_url = UrlBuilder::new("https://github.com")
+            .segment(owner)
+            .segment(repo);
+        let code_search = |scope: String| Self::code_search_url(&format!("{} {}", scope, rest));
+        match path {
+            Some(path) if rest.is_empty() => return Self::path_url(repo_url, path),
+            Some(path) => return code_search(format!("repo:{}/{} path:{}", owner, repo, path)),
+            None if rest.is_empty() => return repo_url.build(),
+            None => {}
+        }
+
+        let (word, words) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let words = words.trim();
+        let (list_page, item_page, filter) = match word {
+            "issues" | "issue" => ("issues", "issues", "is:issue is:open"),
+            "pr" | "prs" | "pull" | "pulls" => ("pulls", "pull", "is:pr is:open"),
+            _ => return code_search(format!("repo:{}/{}", owner, repo)),
+        };
+        if words.is_empty() {
+            repo_url.segment(list_page).build()
+        } else if let Ok(number) = words.trim_start_matches('#').parse::<u64>() {
+            repo_url
+                .segment(item_page)
+                .segment(&number.to_string())
+                .build()
+        } else {
+            repo_url
+                .segment(list_page)
+                .query("q", &format!("{} {}", filter, words))
+                .build()
+        }
+    }
+
+    /// A path inside a repository: GitHub pages as given, anything else a
+    /// file or directory on the default branch
+    fn path_url(repo_url: UrlBuilder, path: &str) -> String {
+        let first = path.split('/').next().unwrap_or_default();
+        if Self::REPO_ROUTES.contains(&first) {
+            repo_url.path(path).build()
+        } else {
+            repo_url.path("blob/HEAD").path(path).build()
+        }
+    }
 
-    fn process_args(args: &str) -> String {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return Ok(url);
+        }
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://github.com".to_string()
-        } else if query == "token" || query == "tokens" {
-            "https://github.com/settings/personal-access-tokens".to_string()
+            Ok("https://github.com".into())
         } else if let Some(username) = query.strip_prefix('@') {
             if username.is_empty() {
-                "https://github.com".to_string()
+                Ok("https://github.com".into())
             } else {
-                build_path_url("https://github.com", username)
+                Ok(build_path_url("https://github.com", username).into())
             }
-        } else if let Some((author, repo)) = query.split_once('/') {
-            if !author.is_empty() && !repo.is_empty() {
-                build_path_url("https://github.com", query)
+        } else if query.contains('/') {
+            // `<owner>/<repo>[/<path>]`, then what to open there
+            let (target, rest) = query
+                .split_once(char::is_whitespace)
+                .map_or((query, ""), |(target, rest)| (target, rest.trim()));
+            let mut parts = target.splitn(3, '/');
+            let owner = parts.next().unwrap_or_default();
+            let repo = parts.next().unwrap_or_default();
+            let path = parts
+                .next()
+                .map(|path| path.trim_end_matches('/'))
+                .filter(|path| !path.is_empty());
+            let valid = !owner.is_empty()
+                && !repo.is_empty()
+                && path.is_none_or(|path| !path.split('/').any(str::is_empty));
+            if valid {
+                Ok(Self::repo_url(owner, repo, path, rest).into())
+            } else if query.contains(char::is_whitespace) {
+                Ok(Self::search_url(query).into())
             } else {
-                format!(
-                    "{}&type=repositories",
-                    build_search_url("https://github.com/search", "q", query)
-                )
+                // A lone `facebook/` or `/react` is a repo path missing half
+                Err(CommandError::InvalidArguments(format!(
+                    "'{}' isn't a repository; expected <owner>/<repo>",
+                    query
+                )))
             }
         } else {
-            format!(
-                "{}&type=repositories",
-                build_search_url("https://github.com/search", "q", query)
-            )
+            Ok(Self::search_url(query).into())
         }
     }
+}
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to GitHub profiles, repositories, or search GitHub",
-            "gh facebook/react",
-        )
+/// One of the logged-in user's GitHub views, like their pull requests
+struct Dashboard {
+    /// The view itself, for when there's nothing to filter by
+    view: &'static str,
+    /// Where the view's filters are searched
+    search: &'static str,
+    /// The query parameter holding the filters
+    param: &'static str,
+    /// The filters that make the search match the view
+    filter: &'static str,
+}
+
+impl Dashboard {
+    /// The view limited to `org` when set, and searched for `words`
+    fn url(&self, org: Option<&str>, words: &str) -> String {
+        let org = org.map(|org| format!("org:{}", org));
+        if org.is_none() && words.is_empty() {
+            return self.view.to_string();
+        }
+        let terms: Vec<&str> = [self.filter, org.as_deref().unwrap_or_default(), words]
+            .into_iter()
+            .filter(|term| !term.is_empty())
+            .collect();
+        UrlBuilder::new(self.search)
+            .query(self.param, &terms.join(" "))
+            .build()
     }
 }
 
@@ -52,59 +284,213 @@ mod tests {
 
     #[test]
     fn test_github_command_base() {
-        assert_eq!(GitHubCommand::process_args("gh"), "https://github.com");
+        assert_eq!(
+            GitHubCommand::process_args("gh").as_deref(),
+            Ok("https://github.com")
+        );
+    }
+
+    #[test]
+    fn test_github_command_long_binding() {
+        assert_eq!(
+            GitHubCommand::process_args("github facebook/react").as_deref(),
+            Ok("https://github.com/facebook/react")
+        );
     }
 
     #[test]
     fn test_github_command_profile() {
         assert_eq!(
-            GitHubCommand::process_args("gh @facebook"),
-            "https://github.com/facebook"
+            GitHubCommand::process_args("gh @facebook").as_deref(),
+            Ok("https://github.com/facebook")
         );
     }
 
     #[test]
     fn test_github_command_empty_username() {
-        assert_eq!(GitHubCommand::process_args("gh @"), "https://github.com");
+        assert_eq!(
+            GitHubCommand::process_args("gh @").as_deref(),
+            Ok("https://github.com")
+        );
     }
 
     #[test]
     fn test_github_command_repo() {
         assert_eq!(
-            GitHubCommand::process_args("gh facebook/react"),
-            "https://github.com/facebook/react"
+            GitHubCommand::process_args("gh facebook/react").as_deref(),
+            Ok("https://github.com/facebook/react")
         );
     }
 
     #[test]
     fn test_github_command_search() {
         assert_eq!(
-            GitHubCommand::process_args("gh rust async"),
-            "https://github.com/search?q=rust%20async&type=repositories"
+            GitHubCommand::process_args("gh rust async").as_deref(),
+            Ok("https://github.com/search?q=rust%20async&type=repositories")
         );
     }
 
     #[test]
     fn test_github_command_search_single_word() {
         assert_eq!(
-            GitHubCommand::process_args("gh react"),
-            "https://github.com/search?q=react&type=repositories"
+            GitHubCommand::process_args("gh react").as_deref(),
+            Ok("https://github.com/search?q=react&type=repositories")
         );
     }
 
     #[test]
     fn test_github_command_token() {
         assert_eq!(
-            GitHubCommand::process_args("gh token"),
-            "https://github.com/settings/personal-access-tokens"
+            GitHubCommand::process_args("gh token").as_deref(),
+            Ok("https://github.com/settings/personal-access-tokens")
         );
     }
 
     #[test]
     fn test_github_command_tokens() {
         assert_eq!(
-            GitHubCommand::process_args("gh tokens"),
-            "https://github.com/settings/personal-access-tokens"
+            GitHubCommand::process_args("gh tokens").as_deref(),
+            Ok("https://github.com/settings/personal-access-tokens")
+        );
+    }
+
+    #[test]
+    fn test_github_command_dashboards() {
+        assert_eq!(
+            GitHubCommand::process_args("gh pr").as_deref(),
+            Ok("https://github.com/pulls")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh prs").as_deref(),
+            Ok("https://github.com/pulls")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh issues").as_deref(),
+            Ok("https://github.com/issues/assigned")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh review").as_deref(),
+            Ok("https://github.com/pulls/review-requested")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh notifications").as_deref(),
+            Ok("https://github.com/notifications")
+        );
+    }
+
+    #[test]
+    fn test_github_dashboard_org_and_search() {
+        assert_eq!(
+            GitHubCommand::MY_PULLS.url(Some("rust-lang"), ""),
+            "https://github.com/pulls?q=is:open%20is:pr%20author:@me%20org:rust-lang"
+        );
+        assert_eq!(
+            GitHubCommand::MY_ISSUES.url(Some("rust-lang"), "borrowck"),
+            "https://github.com/issues?q=is:open%20is:issue%20assignee:@me%20org:rust-lang%20borrowck"
+        );
+        assert_eq!(
+            GitHubCommand::MY_REVIEWS.url(None, "docs"),
+            "https://github.com/pulls?q=is:open%20is:pr%20review-requested:@me%20docs"
+        );
+        assert_eq!(
+            GitHubCommand::MY_NOTIFICATIONS.url(Some("rust-lang"), ""),
+            "https://github.com/notifications?query=org:rust-lang"
+        );
+    }
+
+    #[test]
+    fn test_github_command_incomplete_repo() {
+        assert!(GitHubCommand::process_args("gh facebook/").is_err());
+        assert!(GitHubCommand::process_args("gh /react").is_err());
+        assert!(GitHubCommand::process_args("gh facebook//react").is_err());
+        assert!(GitHubCommand::process_args("gh facebook/react//src").is_err());
+    }
+
+    #[test]
+    fn test_github_command_repo_issues() {
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust issues").as_deref(),
+            Ok("https://github.com/rust-lang/rust/issues")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust issue 42").as_deref(),
+            Ok("https://github.com/rust-lang/rust/issues/42")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust issues borrow checker").as_deref(),
+            Ok("https://github.com/rust-lang/rust/issues?q=is:issue%20is:open%20borrow%20checker")
+        );
+    }
+
+    #[test]
+    fn test_github_command_repo_pull_requests() {
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust pr 1234").as_deref(),
+            Ok("https://github.com/rust-lang/rust/pull/1234")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust pr #1234").as_deref(),
+            Ok("https://github.com/rust-lang/rust/pull/1234")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust prs").as_deref(),
+            Ok("https://github.com/rust-lang/rust/pulls")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust pr const generics").as_deref(),
+            Ok("https://github.com/rust-lang/rust/pulls?q=is:pr%20is:open%20const%20generics")
+        );
+    }
+
+    #[test]
+    fn test_github_command_code_search() {
+        assert_eq!(
+            GitHubCommand::process_args("gh code tokio::select").as_deref(),
+            Ok("https://github.com/search?q=tokio::select&type=code")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh code").as_deref(),
+            Ok("https://github.com/search?type=code")
+        );
+        // Other words after a repository search its code
+        assert_eq!(
+            GitHubCommand::process_args("gh facebook/react useState").as_deref(),
+            Ok("https://github.com/search?q=repo:facebook/react%20useState&type=code")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh facebook/react/packages useState").as_deref(),
+            Ok(
+                "https://github.com/search?q=repo:facebook/react%20path:packages%20useState&type=code"
+            )
+        );
+    }
+
+    #[test]
+    fn test_github_command_repo_path() {
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust/library/core/src/lib.rs").as_deref(),
+            Ok("https://github.com/rust-lang/rust/blob/HEAD/library/core/src/lib.rs")
+        );
+        // GitHub's own pages pass through
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust/tree/master/library").as_deref(),
+            Ok("https://github.com/rust-lang/rust/tree/master/library")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh rust-lang/rust/actions").as_deref(),
+            Ok("https://github.com/rust-lang/rust/actions")
+        );
+        assert_eq!(
+            GitHubCommand::process_args("gh facebook/react/").as_deref(),
+            Ok("https://github.com/facebook/react")
+        );
+    }
+
+    #[test]
+    fn test_github_command_search_with_slash() {
+        assert_eq!(
+            GitHubCommand::process_args("gh rust async/await").as_deref(),
+            Ok("https://github.com/search?q=rust%20async/await&type=repositories")
         );
     }
 }
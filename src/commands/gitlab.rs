@@ -1,46 +1,113 @@
 /// GitLab command handler
 /// Supports:
-/// - gitlab/gl -> https://gitlab.com
+/// - gitlab/gl -> https://gitlab.com, or the `[gitlab] base_url` instance
 /// - gitlab [user/project] -> https://gitlab.com/[user/project]
+/// - gitlab [user/project] mrs|issues [number] -> the project's merge requests or issues
 /// - gitlab [search terms] -> https://gitlab.com/search?search=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::configured_base_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gitlab", "gl"),
+    description = "Navigate to GitLab projects, their merge requests and issues, or search GitLab",
+    example = "gitlab gitlab-org/gitlab",
+    examples("gl @gitlab-org", "gl gitlab-org/gitlab mr 1234", "gl runner"),
+    args = "[<group>/<project> [mrs|issues [<number>]] | <search terms>]",
+    category = Dev,
+    tags("git", "code", "repositories", "source"),
+    config_keys("gitlab.base_url"),
+    usage(
+        "gl" = "Open GitLab",
+        "gl <group>/<project>" = "Open a project",
+        "gl <group>/<project> mrs [<n>]" = "Open a project's merge requests, or merge request <n>; other words search them",
+        "gl <group>/<project> issues [<n>]" = "Open a project's issues, or issue <n>; other words search them",
+        "gl <search terms>" = "Search GitLab"
+    )
+)]
 pub struct GitlabCommand;
 
-impl BunnylolCommand for GitlabCommand {
-    const BINDINGS: &'static [&'static str] = &["gitlab", "gl"];
+impl GitlabCommand {
+    const DEFAULT_BASE_URL: &str = "https://gitlab.com";
+
+    /// `[gitlab] base_url`, or gitlab.com when it's unset or not an http(s)
+    /// URL (`bunnylol doctor` reports that)
+    fn base_url() -> String {
+        get_global_config()
+            .and_then(|config| config.gitlab.base_url.as_deref())
+            .and_then(configured_base_url)
+            .unwrap_or(Self::DEFAULT_BASE_URL)
+            .to_string()
+    }
 
-    fn process_args(args: &str) -> String {
-        let query = Self::get_command_args(args);
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        Self::url(&Self::base_url(), Self::get_command_args(args)).into()
+    }
+
+    /// The page for `query` on the GitLab at `base`
+    fn url(base: &str, query: &str) -> String {
         if query.is_empty() {
-            "https://gitlab.com".to_string()
-        } else if query.contains('/') {
-            // Validate and encode project path (user/project format)
-            let parts: Vec<&str> = query.split('/').collect();
-            if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
-                // Valid user/project format - encode each part
-                format!(
-                    "https://gitlab.com/{}/{}",
-                    encode_url(parts[0]),
-                    encode_url(parts[1])
-                )
-            } else {
+            return base.to_string();
+        }
+        // `<group>/<project> mrs ...` or `... issues ...`
+        if let Some((target, rest)) = query.split_once(char::is_whitespace)
+            && let Some(project) = Self::project_url(base, target)
+            && let Some(url) = Self::project_page(project, rest.trim())
+        {
+            return url;
+        }
+        if query.contains('/') {
+            match Self::project_url(base, query) {
+                Some(project) => project.build(),
                 // Invalid path format (e.g., foo//bar or foo/bar/baz), fall back to search
-                format!("https://gitlab.com/search?search={}", encode_url(query))
+                None => Self::search_url(base, query),
             }
         } else {
             // Otherwise, treat it as a search query
-            format!("https://gitlab.com/search?search={}", encode_url(query))
+            Self::search_url(base, query)
         }
     }
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to GitLab projects or search GitLab",
-            "gitlab gitlab-org/gitlab",
-        )
+    /// The project at `<user>/<project>`, encoding each part, if `path` is one
+    fn project_url(base: &str, path: &str) -> Option<UrlBuilder> {
+        let parts: Vec<&str> = path.split('/').collect();
+        if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
+            Some(UrlBuilder::new(base).segment(parts[0]).segment(parts[1]))
+        } else {
+            None
+        }
+    }
+
+    /// A project's merge requests or issues for `rest`: the list, one by
+    /// number (`12`, `!12`, or `#12`), or a search; `None` if `rest` names
+    /// neither
+    fn project_page(project: UrlBuilder, rest: &str) -> Option<String> {
+        let (word, words) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let words = words.trim();
+        let page = match word {
+            "mr" | "mrs" | "merge_requests" => "merge_requests",
+            "issue" | "issues" => "issues",
+            _ => return None,
+        };
+        let list = project.path("-").segment(page);
+        Some(if words.is_empty() {
+            list.build()
+        } else if let Ok(number) = words.trim_start_matches(['!', '#']).parse::<u64>() {
+            list.segment(&number.to_string()).build()
+        } else {
+            list.query("search", words).build()
+        })
+    }
+
+    fn search_url(base: &str, query: &str) -> String {
+        UrlBuilder::new(base)
+            .segment("search")
+            .query("search", query)
+            .build()
     }
 }
 
@@ -115,4 +182,76 @@ mod tests {
             "https://gitlab.com/user/my%20project"
         );
     }
+
+    #[test]
+    fn test_gitlab_command_project_special_chars() {
+        assert_eq!(
+            GitlabCommand::process_args("gl user/proj#1?x"),
+            "https://gitlab.com/user/proj%231%3Fx"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl café/crème"),
+            "https://gitlab.com/caf%C3%A9/cr%C3%A8me"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_command_merge_requests() {
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab mrs"),
+            "https://gitlab.com/gitlab-org/gitlab/-/merge_requests"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab mr 1234"),
+            "https://gitlab.com/gitlab-org/gitlab/-/merge_requests/1234"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab mr !1234"),
+            "https://gitlab.com/gitlab-org/gitlab/-/merge_requests/1234"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab mrs runner cache"),
+            "https://gitlab.com/gitlab-org/gitlab/-/merge_requests?search=runner%20cache"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_command_issues() {
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab issues"),
+            "https://gitlab.com/gitlab-org/gitlab/-/issues"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab issue #42"),
+            "https://gitlab.com/gitlab-org/gitlab/-/issues/42"
+        );
+        assert_eq!(
+            GitlabCommand::process_args("gl gitlab-org/gitlab issues flaky"),
+            "https://gitlab.com/gitlab-org/gitlab/-/issues?search=flaky"
+        );
+        // Without a project, the words are a search
+        assert_eq!(
+            GitlabCommand::process_args("gl issues 42"),
+            "https://gitlab.com/search?search=issues%2042"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_self_hosted() {
+        let base = "https://gitlab.example.com";
+        assert_eq!(GitlabCommand::url(base, ""), base);
+        assert_eq!(
+            GitlabCommand::url(base, "infra/deploy mr 7"),
+            "https://gitlab.example.com/infra/deploy/-/merge_requests/7"
+        );
+        assert_eq!(
+            GitlabCommand::url(base, "terraform"),
+            "https://gitlab.example.com/search?search=terraform"
+        );
+        // An instance under a path keeps it
+        assert_eq!(
+            GitlabCommand::url("https://example.com/gitlab", "infra/deploy"),
+            "https://example.com/gitlab/infra/deploy"
+        );
+    }
 }
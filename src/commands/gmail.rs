@@ -1,18 +1,103 @@
 /// Gmail command handler
-/// Supports: mail (simple redirect to Gmail)
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+/// Supports:
+/// - gmail/mail -> https://mail.google.com, or the `[gmail]` or `[google]` account inbox
+/// - mail compose [to] [subject] -> a new message with those filled in
+/// - mail search [query] -> a search of your mail
+use std::borrow::Cow;
 
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::config::get_global_config;
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gmail", "mail"),
+    description = "Navigate to Gmail, write a message, or search your mail",
+    example = "mail",
+    examples("mail compose alice@example.com Lunch plans", "mail search from:alice"),
+    args = "[compose [<to>] [<subject>] | search <query>]",
+    category = Productivity,
+    tags("email", "inbox", "google"),
+    config_keys("gmail.account", "google.account"),
+    usage("mail" = "Open your inbox"),
+    subcommands
+)]
 pub struct GmailCommand;
 
-impl BunnylolCommand for GmailCommand {
-    const BINDINGS: &'static [&'static str] = &["gmail", "mail"];
+impl GmailCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::handler(
+            &["compose", "new", "write"],
+            "[<email> ...] [<subject>]",
+            "Write a message",
+            |rest| Self::compose_url(Self::account(), rest).into(),
+        ),
+        Subcommand::handler(&["search"], "<query>", "Search your mail", |rest| {
+            Self::search_url(Self::account(), rest).into()
+        }),
+    ];
+
+    /// `[gmail] account`, or else `[google] account`: the signed-in account
+    /// to use
+    fn account() -> Option<u32> {
+        get_global_config().and_then(|config| config.gmail.account.or(config.google.account))
+    }
+
+    /// The mailbox of signed-in account `account`, the first when unset
+    fn mailbox(account: Option<u32>) -> UrlBuilder {
+        UrlBuilder::new("https://mail.google.com/mail/u")
+            .segment(&account.unwrap_or_default().to_string())
+            .segment("")
+    }
+
+    /// A new message to the addresses `message` starts with, with the rest
+    /// of it as the subject
+    fn compose_url(account: Option<u32>, message: &str) -> String {
+        let mut args = Args::new(message);
+        let mut to = Vec::new();
+        while let Some(address) = args
+            .clone()
+            .next()
+            .filter(|word| word.contains('@') && !word.contains(char::is_whitespace))
+        {
+            args.next();
+            to.extend(
+                address
+                    .split(',')
+                    .filter(|address| !address.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let subject = args.rest_unquoted();
+
+        let mut url = Self::mailbox(account).query("view", "cm").query("fs", "1");
+        if !to.is_empty() {
+            url = url.query("to", &to.join(","));
+        }
+        if !subject.is_empty() {
+            url = url.query("su", &subject);
+        }
+        url.build()
+    }
 
-    fn process_args(_args: &str) -> String {
-        "https://mail.google.com".to_string()
+    fn search_url(account: Option<u32>, query: &str) -> String {
+        let mailbox = Self::mailbox(account);
+        if query.is_empty() {
+            mailbox.build()
+        } else {
+            mailbox.fragment(&format!("search/{}", query)).build()
+        }
     }
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Gmail", "mail")
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
+        match Self::account() {
+            Some(account) => Self::mailbox(Some(account)).build().into(),
+            None => "https://mail.google.com".into(),
+        }
     }
 }
 
@@ -36,4 +121,40 @@ mod tests {
             "https://mail.google.com"
         );
     }
+
+    #[test]
+    fn test_gmail_command_compose() {
+        assert_eq!(
+            GmailCommand::process_args("mail compose alice@example.com Lunch plans"),
+            "https://mail.google.com/mail/u/0/?view=cm&fs=1&to=alice@example.com&su=Lunch%20plans"
+        );
+        assert_eq!(
+            GmailCommand::process_args("gmail compose a@example.com,b@example.com c@example.com"),
+            "https://mail.google.com/mail/u/0/?view=cm&fs=1&to=a@example.com,b@example.com,c@example.com"
+        );
+        assert_eq!(
+            GmailCommand::process_args("mail compose a@x.com \"Lunch @ noon?\""),
+            "https://mail.google.com/mail/u/0/?view=cm&fs=1&to=a@x.com&su=Lunch%20@%20noon?"
+        );
+        assert_eq!(
+            GmailCommand::process_args("mail compose"),
+            "https://mail.google.com/mail/u/0/?view=cm&fs=1"
+        );
+        assert_eq!(
+            GmailCommand::compose_url(Some(2), "Q3 numbers & notes"),
+            "https://mail.google.com/mail/u/2/?view=cm&fs=1&su=Q3%20numbers%20%26%20notes"
+        );
+    }
+
+    #[test]
+    fn test_gmail_command_search() {
+        assert_eq!(
+            GmailCommand::process_args("mail search from:alice has:attachment"),
+            "https://mail.google.com/mail/u/0/#search/from:alice%20has:attachment"
+        );
+        assert_eq!(
+            GmailCommand::search_url(Some(1), ""),
+            "https://mail.google.com/mail/u/1/"
+        );
+    }
 }
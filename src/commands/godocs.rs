@@ -1,24 +1,24 @@
 /// Go documentation command handler
 /// Supports:
 /// - godocs -> https://go.dev/doc/
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct GodocsCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
 
-impl BunnylolCommand for GodocsCommand {
-    const BINDINGS: &'static [&'static str] = &["godocs"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("godocs"),
+    description = "Navigate to Go language documentation",
+    example = "godocs",
+    category = Docs,
+    tags("go", "golang", "documentation", "reference")
+)]
+pub struct GodocsCommand;
 
-    fn process_args(_args: &str) -> String {
+impl GodocsCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
         // Always redirect to Go documentation
-        "https://go.dev/doc/".to_string()
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Go language documentation",
-            "godocs",
-        )
+        "https://go.dev/doc/".into()
     }
 }
 
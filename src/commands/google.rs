@@ -1,6 +1,10 @@
 /// Google Search command handler (default fallback)
 /// Supports: g [search terms], or any unrecognized command
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{
+    BunnylolCommand, BunnylolCommandInfo, CommandCategory, CommandError,
+};
 use crate::utils::url_encoding::build_search_url;
 
 pub struct GoogleSearchCommand;
@@ -8,9 +12,9 @@ pub struct GoogleSearchCommand;
 impl BunnylolCommand for GoogleSearchCommand {
     const BINDINGS: &'static [&'static str] = &["g"];
 
-    fn process_args(args: &str) -> String {
+    fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
         let query = Self::get_command_args(args);
-        build_search_url("https://google.com/search", "q", query)
+        Ok(build_search_url("https://google.com/search", "q", query).into())
     }
 
     fn get_info() -> BunnylolCommandInfo {
@@ -19,9 +23,15 @@ impl BunnylolCommand for GoogleSearchCommand {
             "Search Google (default fallback for any unrecognized command)",
             "g rust programming",
         )
+        .with_args("<search terms>")
+        .with_category(CommandCategory::Search)
+        .with_tags(&["search", "web", "default"])
+        .with_config_keys(&["default_search"])
     }
 }
 
+crate::register_command!(GoogleSearchCommand);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -29,40 +39,40 @@ mod tests {
     #[test]
     fn test_google_search_command_simple() {
         assert_eq!(
-            GoogleSearchCommand::process_args("hello"),
-            "https://google.com/search?q=hello"
+            GoogleSearchCommand::process_args("hello").as_deref(),
+            Ok("https://google.com/search?q=hello")
         );
     }
 
     #[test]
     fn test_google_search_command_with_spaces() {
         assert_eq!(
-            GoogleSearchCommand::process_args("hello world"),
-            "https://google.com/search?q=hello%20world"
+            GoogleSearchCommand::process_args("hello world").as_deref(),
+            Ok("https://google.com/search?q=hello%20world")
         );
     }
 
     #[test]
     fn test_google_search_command_with_g_prefix() {
         assert_eq!(
-            GoogleSearchCommand::process_args("g hello world"),
-            "https://google.com/search?q=hello%20world"
+            GoogleSearchCommand::process_args("g hello world").as_deref(),
+            Ok("https://google.com/search?q=hello%20world")
         );
     }
 
     #[test]
     fn test_google_search_command_g_only() {
         assert_eq!(
-            GoogleSearchCommand::process_args("g"),
-            "https://google.com/search?q="
+            GoogleSearchCommand::process_args("g").as_deref(),
+            Ok("https://google.com/search?q=")
         );
     }
 
     #[test]
     fn test_google_search_command_with_ampersand() {
         assert_eq!(
-            GoogleSearchCommand::process_args("g Peak Sports & Spine Physical Therapy"),
-            "https://google.com/search?q=Peak%20Sports%20%26%20Spine%20Physical%20Therapy"
+            GoogleSearchCommand::process_args("g Peak Sports & Spine Physical Therapy").as_deref(),
+            Ok("https://google.com/search?q=Peak%20Sports%20%26%20Spine%20Physical%20Therapy")
         );
     }
 }
@@ -0,0 +1,343 @@
+/// Google Calendar command handler
+/// Supports:
+/// - cal/gcal -> https://calendar.google.com/calendar/u/0/r, or the
+///   `[google] account` calendar
+/// - cal [today | tomorrow | friday | 2025-03-01] -> that day
+/// - cal new [title] [at 3pm] [friday] -> a new event with those filled in
+/// - cal [search terms] -> a search of your events
+///
+/// Days are counted from today's date in the local time zone.
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::config::get_global_config;
+use crate::utils::args::Args;
+use crate::utils::date::{civil_from_days, days_from_civil, local_today};
+use crate::utils::url_builder::UrlBuilder;
+
+/// Names for each day of the week, starting with Monday
+const WEEKDAYS: [&[&str]; 7] = [
+    &["monday", "mon"],
+    &["tuesday", "tue", "tues"],
+    &["wednesday", "wed"],
+    &["thursday", "thu", "thurs"],
+    &["friday", "fri"],
+    &["saturday", "sat"],
+    &["sunday", "sun"],
+];
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("cal", "gcal"),
+    description = "Open Google Calendar on a day, create an event, or search events",
+    example = "cal tomorrow",
+    examples("cal 2025-03-01", "cal new Design review at 3pm friday", "cal offsite"),
+    args = "[<date> | new <event> | <search terms>]",
+    category = Productivity,
+    tags("calendar", "events", "google", "schedule"),
+    config_keys("google.account"),
+    usage(
+        "cal" = "Open your calendar",
+        "cal <date>" = "Open a day: today, tomorrow, yesterday, a weekday, or YYYY-MM-DD",
+        "cal <search terms>" = "Search your events"
+    ),
+    subcommands
+)]
+pub struct GoogleCalendarCommand;
+
+impl GoogleCalendarCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[Subcommand::handler(
+        &["new", "add"],
+        "[<title>] [at <time>] [<date>]",
+        "Create an event, e.g. new Design review at 3pm friday",
+        |rest| Self::new_event_url(Self::account(), local_today(), rest).into(),
+    )];
+
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
+        Self::url(Self::account(), local_today(), Self::get_command_args(args)).into()
+    }
+
+    /// `[google] account`, the signed-in account to use
+    fn account() -> Option<u32> {
+        get_global_config().and_then(|config| config.google.account)
+    }
+
+    /// The calendar of signed-in account `account`, the first when unset
+    fn calendar(account: Option<u32>) -> UrlBuilder {
+        UrlBuilder::new("https://calendar.google.com/calendar/u")
+            .segment(&account.unwrap_or_default().to_string())
+            .segment("r")
+    }
+
+    /// The calendar, the day `query` names, or a search for it
+    fn url(account: Option<u32>, today: i64, query: &str) -> String {
+        if query.is_empty() {
+            Self::calendar(account).build()
+        } else if let Some(day) = parse_date(today, query) {
+            let (year, month, day) = civil_from_days(day);
+            Self::calendar(account)
+                .path(&format!("day/{}/{}/{}", year, month, day))
+                .build()
+        } else {
+            Self::calendar(account)
+                .path("search")
+                .query("q", query)
+                .build()
+        }
+    }
+
+    /// The new event page for `<title> [at <time>] [<date>]`, the time and
+    /// date in either order at the end: an hour-long event at a time, an
+    /// all-day event on a date alone, or just the title; quote a title to
+    /// keep it whole
+    fn new_event_url(account: Option<u32>, today: i64, event: &str) -> String {
+        let mut words: Vec<String> = Args::new(event).collect();
+        let mut date = None;
+        let mut time = None;
+        loop {
+            match &words[..] {
+                [.., at, last] if time.is_none() && at.eq_ignore_ascii_case("at") => {
+                    let Some(parsed) = parse_time(last) else {
+                        break;
+                    };
+                    time = Some(parsed);
+                    words.truncate(words.len() - 2);
+                }
+                [.., last] if date.is_none() => {
+                    let Some(parsed) = parse_date(today, last) else {
+                        break;
+                    };
+                    date = Some(parsed);
+                    words.pop();
+                    if words.last().is_some_and(|on| on.eq_ignore_ascii_case("on")) {
+                        words.pop();
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let mut url = Self::calendar(account).path("eventedit");
+        let title = words.join(" ");
+        if !title.is_empty() {
+            url = url.query("text", &title);
+        }
+        let day = date.unwrap_or(today);
+        let dates = match time {
+            Some(minutes) => format!(
+                "{}/{}",
+                format_date_time(day, minutes),
+                format_date_time(day, minutes + 60)
+            ),
+            None if date.is_some() => format!("{}/{}", format_date(day), format_date(day + 1)),
+            None => return url.build(),
+        };
+        url.query("dates", &dates).build()
+    }
+}
+
+/// `text` as a day, in days since 1970-01-01: today, tomorrow, yesterday,
+/// the next such weekday (today included), or YYYY-MM-DD
+fn parse_date(today: i64, text: &str) -> Option<i64> {
+    let text = text.to_ascii_lowercase();
+    match text.as_str() {
+        "today" => return Some(today),
+        "tomorrow" | "tmrw" => return Some(today + 1),
+        "yesterday" => return Some(today - 1),
+        _ => {}
+    }
+    if let Some(weekday) = WEEKDAYS
+        .iter()
+        .position(|names| names.contains(&text.as_str()))
+    {
+        // 1970-01-01 was a Thursday, day 3 counting from Monday
+        let current = (today + 3).rem_euclid(7);
+        return Some(today + (weekday as i64 - current).rem_euclid(7));
+    }
+    let [year, month, day] = text.split('-').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let date = (
+        year.parse::<i64>().ok()?,
+        month.parse::<u32>().ok()?,
+        day.parse::<u32>().ok()?,
+    );
+    let days = days_from_civil(date.0, date.1, date.2);
+    // Dates like 2025-02-30 come back as a different day
+    (civil_from_days(days) == date).then_some(days)
+}
+
+/// `text` as minutes after midnight: 3pm, 3:30pm, 15:00, or noon
+fn parse_time(text: &str) -> Option<u32> {
+    let text = text.to_ascii_lowercase();
+    if text == "noon" {
+        return Some(12 * 60);
+    }
+    let (clock, meridiem) = match text.strip_suffix("am") {
+        Some(clock) => (clock, Some(0)),
+        None => match text.strip_suffix("pm") {
+            Some(clock) => (clock, Some(12)),
+            None => (text.as_str(), None),
+        },
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let (hour, minute) = (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?);
+    if minute >= 60 {
+        return None;
+    }
+    let hour = match meridiem {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        // A bare number is too likely part of the title, like "Q3 review 2"
+        None if clock.contains(':') && hour < 24 => hour,
+        _ => return None,
+    };
+    Some(hour * 60 + minute)
+}
+
+/// A day as Google Calendar's all-day `YYYYMMDD`
+fn format_date(day: i64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// `minutes` after the start of `day` as Google Calendar's local
+/// `YYYYMMDDTHHMMSS`; past midnight rolls over to the next day
+fn format_date_time(day: i64, minutes: u32) -> String {
+    let day = day + i64::from(minutes / (24 * 60));
+    let minutes = minutes % (24 * 60);
+    format!(
+        "{}T{:02}{:02}00",
+        format_date(day),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday, 2025-03-05
+    fn wednesday() -> i64 {
+        days_from_civil(2025, 3, 5)
+    }
+
+    #[test]
+    fn test_google_calendar_command_base() {
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal"),
+            "https://calendar.google.com/calendar/u/0/r"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::process_args("gcal"),
+            "https://calendar.google.com/calendar/u/0/r"
+        );
+    }
+
+    #[test]
+    fn test_google_calendar_command_day() {
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal 2025-03-01"),
+            "https://calendar.google.com/calendar/u/0/r/day/2025/3/1"
+        );
+        let (year, month, day) = civil_from_days(local_today() + 1);
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal tomorrow"),
+            format!(
+                "https://calendar.google.com/calendar/u/0/r/day/{}/{}/{}",
+                year, month, day
+            )
+        );
+    }
+
+    #[test]
+    fn test_google_calendar_command_search() {
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal team offsite"),
+            "https://calendar.google.com/calendar/u/0/r/search?q=team%20offsite"
+        );
+        // Not a real date
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal 2025-02-30"),
+            "https://calendar.google.com/calendar/u/0/r/search?q=2025-02-30"
+        );
+    }
+
+    #[test]
+    fn test_google_calendar_command_account() {
+        assert_eq!(
+            GoogleCalendarCommand::url(Some(1), wednesday(), "friday"),
+            "https://calendar.google.com/calendar/u/1/r/day/2025/3/7"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(Some(2), wednesday(), "Lunch at noon"),
+            "https://calendar.google.com/calendar/u/2/r/eventedit?text=Lunch&dates=20250305T120000/20250305T130000"
+        );
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let today = wednesday();
+        assert_eq!(parse_date(today, "today"), Some(today));
+        assert_eq!(parse_date(today, "Tomorrow"), Some(today + 1));
+        assert_eq!(parse_date(today, "yesterday"), Some(today - 1));
+        assert_eq!(parse_date(today, "wed"), Some(today));
+        assert_eq!(parse_date(today, "friday"), Some(today + 2));
+        assert_eq!(parse_date(today, "mon"), Some(today + 5));
+        assert_eq!(
+            parse_date(today, "2024-02-29"),
+            Some(days_from_civil(2024, 2, 29))
+        );
+        assert_eq!(parse_date(today, "2025-13-01"), None);
+        assert_eq!(parse_date(today, "review"), None);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("3pm"), Some(15 * 60));
+        assert_eq!(parse_time("3:30PM"), Some(15 * 60 + 30));
+        assert_eq!(parse_time("12am"), Some(0));
+        assert_eq!(parse_time("12pm"), Some(12 * 60));
+        assert_eq!(parse_time("noon"), Some(12 * 60));
+        assert_eq!(parse_time("09:15"), Some(9 * 60 + 15));
+        assert_eq!(parse_time("13pm"), None);
+        assert_eq!(parse_time("3"), None);
+        assert_eq!(parse_time("24:00"), None);
+    }
+
+    #[test]
+    fn test_new_event_url() {
+        let today = wednesday();
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "Design review at 3pm friday"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Design%20review&dates=20250307T150000/20250307T160000"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "Design review on friday at 11:30pm"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Design%20review&dates=20250307T233000/20250308T003000"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "Lunch at noon"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Lunch&dates=20250305T120000/20250305T130000"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "Offsite 2025-04-01"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Offsite&dates=20250401/20250402"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "Look at the roadmap"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Look%20at%20the%20roadmap"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::new_event_url(None, today, "\"Lunch at noon\" friday"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit?text=Lunch%20at%20noon&dates=20250307/20250308"
+        );
+        assert_eq!(
+            GoogleCalendarCommand::process_args("cal new"),
+            "https://calendar.google.com/calendar/u/0/r/eventedit"
+        );
+    }
+}
@@ -1,18 +1,22 @@
 /// Google Chat command handler
 /// Supports: gchat -> redirects to Google Chat
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct GoogleChatCommand;
-
-impl BunnylolCommand for GoogleChatCommand {
-    const BINDINGS: &'static [&'static str] = &["gchat"];
+use crate::commands::bunnylol_command::BunnylolCommand;
 
-    fn process_args(_args: &str) -> String {
-        "https://chat.google.com/".to_string()
-    }
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gchat"),
+    description = "Navigate to Google Chat",
+    example = "gchat",
+    category = Productivity,
+    tags("chat", "messages", "google", "workspace")
+)]
+pub struct GoogleChatCommand;
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Google Chat", "gchat")
+impl GoogleChatCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        "https://chat.google.com/".into()
     }
 }
 
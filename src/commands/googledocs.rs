@@ -1,18 +1,34 @@
 /// Google Docs command handler
 /// Supports: docs, gdoc -> redirects to Google Docs
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct GoogleDocsCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for GoogleDocsCommand {
-    const BINDINGS: &'static [&'static str] = &["docs", "gdoc"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("docs", "gdoc"),
+    description = "Navigate to Google Docs",
+    example = "docs",
+    category = Productivity,
+    tags("documents", "google", "workspace", "writing"),
+    config_keys("google.account")
+)]
+pub struct GoogleDocsCommand;
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/document/u/0/".to_string()
+impl GoogleDocsCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        let account = get_global_config().and_then(|config| config.google.account);
+        Self::url(account).into()
     }
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Google Docs", "docs")
+    /// Google Docs for signed-in account `account`, the first when unset
+    fn url(account: Option<u32>) -> String {
+        UrlBuilder::new("https://docs.google.com/document/u")
+            .segment(&account.unwrap_or_default().to_string())
+            .segment("")
+            .build()
     }
 }
 
@@ -39,4 +55,12 @@ mod tests {
             "https://docs.google.com/document/u/0/"
         );
     }
+
+    #[test]
+    fn test_google_docs_command_account() {
+        assert_eq!(
+            GoogleDocsCommand::url(Some(1)),
+            "https://docs.google.com/document/u/1/"
+        );
+    }
 }
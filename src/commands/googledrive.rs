@@ -0,0 +1,104 @@
+/// Google Drive command handler
+/// Supports:
+/// - drive/gd -> https://drive.google.com/drive/u/0/my-drive, or the
+///   `[google] account` drive
+/// - drive shared|recent -> files shared with you, or your recent files
+/// - drive [search terms] -> a search of your Drive
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("drive", "gd"),
+    description = "Navigate to Google Drive or search your files",
+    example = "drive budget 2025",
+    examples("drive shared", "gd recent"),
+    args = "[shared | recent | <search terms>]",
+    category = Productivity,
+    tags("files", "storage", "google", "workspace"),
+    config_keys("google.account"),
+    usage(
+        "drive" = "Open My Drive",
+        "drive shared" = "Open files shared with you",
+        "drive recent" = "Open your recent files",
+        "drive <search terms>" = "Search your files"
+    )
+)]
+pub struct GoogleDriveCommand;
+
+impl GoogleDriveCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let account = get_global_config().and_then(|config| config.google.account);
+        Self::url(account, Self::get_command_args(args)).into()
+    }
+
+    /// The page for `query` in signed-in account `account`'s Drive, the
+    /// first when unset; a shortcut followed by more words is a search
+    fn url(account: Option<u32>, query: &str) -> String {
+        let drive = UrlBuilder::new("https://drive.google.com/drive/u")
+            .segment(&account.unwrap_or_default().to_string());
+        match query.to_ascii_lowercase().as_str() {
+            "" => drive.segment("my-drive").build(),
+            "shared" => drive.segment("shared-with-me").build(),
+            "recent" => drive.segment("recent").build(),
+            _ => drive.segment("search").query("q", query).build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_google_drive_command_base() {
+        assert_eq!(
+            GoogleDriveCommand::process_args("drive"),
+            "https://drive.google.com/drive/u/0/my-drive"
+        );
+        assert_eq!(
+            GoogleDriveCommand::process_args("gd"),
+            "https://drive.google.com/drive/u/0/my-drive"
+        );
+    }
+
+    #[test]
+    fn test_google_drive_command_shortcuts() {
+        assert_eq!(
+            GoogleDriveCommand::process_args("drive shared"),
+            "https://drive.google.com/drive/u/0/shared-with-me"
+        );
+        assert_eq!(
+            GoogleDriveCommand::process_args("gd recent"),
+            "https://drive.google.com/drive/u/0/recent"
+        );
+    }
+
+    #[test]
+    fn test_google_drive_command_search() {
+        assert_eq!(
+            GoogleDriveCommand::process_args("drive budget 2025"),
+            "https://drive.google.com/drive/u/0/search?q=budget%202025"
+        );
+        // A shortcut followed by more words is a search
+        assert_eq!(
+            GoogleDriveCommand::process_args("drive recent invoices"),
+            "https://drive.google.com/drive/u/0/search?q=recent%20invoices"
+        );
+    }
+
+    #[test]
+    fn test_google_drive_command_account() {
+        assert_eq!(
+            GoogleDriveCommand::url(Some(1), ""),
+            "https://drive.google.com/drive/u/1/my-drive"
+        );
+        assert_eq!(
+            GoogleDriveCommand::url(Some(2), "budget"),
+            "https://drive.google.com/drive/u/2/search?q=budget"
+        );
+    }
+}
@@ -1,31 +1,177 @@
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+/// Google Maps command handler
+/// Supports:
+/// - gmaps/maps/gm -> https://www.google.com/maps
+/// - gm [from] [origin] to [destination] [by mode] -> directions, `home` and
+///   `work` standing for the `[maps]` addresses
+/// - gm [lat,long | plus code] -> a pin on that spot
+/// - gm [place] -> a search for the place
+use std::borrow::Cow;
 
-pub struct GoogleMapsCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::{MapsConfig, get_global_config};
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+
+/// Digits of an Open Location Code, in order
+const PLUS_CODE_DIGITS: &str = "23456789CFGHJMPQRVWX";
 
-impl BunnylolCommand for GoogleMapsCommand {
-    const BINDINGS: &'static [&'static str] = &["gmaps", "maps"];
+/// Words after `by` for each Maps `travelmode`
+const TRAVEL_MODES: [(&str, &[&str]); 4] = [
+    ("driving", &["car", "driving", "drive"]),
+    ("walking", &["foot", "walking", "walk"]),
+    ("bicycling", &["bike", "bicycle", "bicycling", "cycling"]),
+    ("transit", &["transit", "train", "bus", "subway"]),
+];
 
-    fn process_args(args: &str) -> String {
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gmaps", "maps", "gm"),
+    description = "Navigate to Google Maps, search for a location, or get directions",
+    example = "gmaps san francisco",
+    examples(
+        "maps",
+        "gm from home to airport",
+        "gm SFO to \"Union Square\" by transit",
+        "gm 37.7749,-122.4194",
+        "gm 849VCWC8+R9"
+    ),
+    args = "[[from] [<origin>] to <destination> [by <mode>] | <lat,long> | <plus code> | <place>]",
+    category = Other,
+    tags("maps", "directions", "places", "google"),
+    config_keys("maps.home", "maps.work"),
+    usage(
+        "gm <place>" = "Search for a place",
+        "gm [from] [<origin>] to <destination>" = "Get directions, from where you are if there's no origin; home and work stand for the [maps] addresses",
+        "gm ... by <mode>" = "Get directions by car, walk, bike, or transit",
+        "gm <lat,long>" = "Drop a pin at coordinates, or at a plus code like 849VCWC8+R9"
+    )
+)]
+pub struct GoogleMapsCommand;
+
+impl GoogleMapsCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
 
         if query.is_empty() {
-            return "https://www.google.com/maps".to_string();
+            return "https://www.google.com/maps".into();
+        }
+
+        let places = get_global_config()
+            .map(|config| config.maps.clone())
+            .unwrap_or_default();
+        if let Some(url) = Self::directions_url(&places, query) {
+            return url.into();
+        }
+        if is_coordinates(query) || is_plus_code(query) {
+            return UrlBuilder::new("https://www.google.com/maps/search/")
+                .query("api", "1")
+                .query(
+                    "query",
+                    &query.split_whitespace().collect::<Vec<_>>().join(" "),
+                )
+                .build()
+                .into();
+        }
+
+        // Maps doesn't treat quotes specially, so `gm "coffee near me"` drops them
+        UrlBuilder::new("https://www.google.com/maps/search")
+            .segment(&Args::new(query).rest_unquoted())
+            .segment("")
+            .build()
+            .into()
+    }
+
+    /// Directions for `[from] [<origin>] to <destination> [by <mode>]`, if
+    /// `query` reads that way; a quoted "to", as in `"coffee to go"`, doesn't
+    /// count
+    fn directions_url(places: &MapsConfig, query: &str) -> Option<String> {
+        let mut words: Vec<String> = Args::new(query).collect();
+        if words
+            .first()
+            .is_some_and(|from| from.eq_ignore_ascii_case("from"))
+        {
+            words.remove(0);
+        }
+        let to = words.iter().position(|to| to.eq_ignore_ascii_case("to"))?;
+        let mode = match &words[to + 1..] {
+            [.., by, mode] if by.eq_ignore_ascii_case("by") => travel_mode(mode),
+            _ => None,
+        };
+        if mode.is_some() {
+            words.truncate(words.len() - 2);
+        }
+        let (origin, destination) = (&words[..to], &words[to + 1..]);
+        if destination.is_empty() {
+            return None;
+        }
+
+        let mut url = UrlBuilder::new("https://www.google.com/maps/dir/").query("api", "1");
+        // No origin means from wherever you are
+        if !origin.is_empty() {
+            url = url.query("origin", &Self::place(places, origin));
         }
+        url = url.query("destination", &Self::place(places, destination));
+        Some(match mode {
+            Some(mode) => url.query("travelmode", mode).build(),
+            None => url.build(),
+        })
+    }
 
-        let encoded_query = encode_url(query);
-        format!("https://www.google.com/maps/search/{}/", encoded_query)
+    /// `words` as one place, `home` and `work` swapped for their addresses
+    fn place(places: &MapsConfig, words: &[String]) -> String {
+        let place = words.join(" ");
+        let saved = match place.to_ascii_lowercase().as_str() {
+            "home" => places.home.as_ref(),
+            "work" => places.work.as_ref(),
+            _ => None,
+        };
+        saved.cloned().unwrap_or(place)
     }
+}
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Google Maps or search for a location",
-            "gmaps san francisco",
-        )
+/// Maps' `travelmode` for the word after `by`, like `bike` for `bicycling`
+fn travel_mode(word: &str) -> Option<&'static str> {
+    let word = word.to_ascii_lowercase();
+    TRAVEL_MODES
+        .iter()
+        .find(|(_, words)| words.contains(&word.as_str()))
+        .map(|(mode, _)| *mode)
+}
+
+/// Whether `text` is a `latitude,longitude` pair, like `37.7749, -122.4194`
+fn is_coordinates(text: &str) -> bool {
+    let Some((lat, long)) = text.split_once(',') else {
+        return false;
+    };
+    match (lat.trim().parse::<f64>(), long.trim().parse::<f64>()) {
+        (Ok(lat), Ok(long)) => (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&long),
+        _ => false,
     }
 }
 
+/// Whether `text` starts with a plus code: a full one like `849VCWC8+R9`, or
+/// a short one like `CWC8+R9` followed by the town it's in
+fn is_plus_code(text: &str) -> bool {
+    let mut words = text.split_whitespace();
+    let Some((area, local)) = words.next().and_then(|code| code.split_once('+')) else {
+        return false;
+    };
+    let is_code = |part: &str| {
+        part.chars()
+            .all(|c| PLUS_CODE_DIGITS.contains(c.to_ascii_uppercase()))
+    };
+    let has_town = words.next().is_some();
+    let area_fits = match area.len() {
+        8 => true,
+        4 | 6 => has_town,
+        _ => false,
+    };
+    area_fits
+        && (local.is_empty() || (2..=7).contains(&local.len()))
+        && is_code(area)
+        && is_code(local)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +223,116 @@ mod tests {
             "https://www.google.com/maps/search/coffee%20shop/"
         );
     }
+
+    #[test]
+    fn test_google_maps_command_quoted_phrase() {
+        assert_eq!(
+            GoogleMapsCommand::process_args(r#""coffee near me""#),
+            "https://www.google.com/maps/search/coffee%20near%20me/"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_gm() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm"),
+            "https://www.google.com/maps"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm coffee"),
+            "https://www.google.com/maps/search/coffee/"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_directions() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm SFO to \"Union Square\""),
+            "https://www.google.com/maps/dir/?api=1&origin=SFO&destination=Union%20Square"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm from Oakland To San Jose"),
+            "https://www.google.com/maps/dir/?api=1&origin=Oakland&destination=San%20Jose"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm to airport"),
+            "https://www.google.com/maps/dir/?api=1&destination=airport"
+        );
+        // Not directions
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm \"coffee to go\""),
+            "https://www.google.com/maps/search/coffee%20to%20go/"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm drive to"),
+            "https://www.google.com/maps/search/drive%20to/"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_saved_places() {
+        let places = MapsConfig {
+            home: Some("1 Main St, Springfield".to_string()),
+            work: None,
+        };
+        assert_eq!(
+            GoogleMapsCommand::directions_url(&places, "from home to work").as_deref(),
+            Some(
+                "https://www.google.com/maps/dir/?api=1&origin=1%20Main%20St,%20Springfield&destination=work"
+            )
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_travel_mode() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm SFO to \"Union Square\" by transit"),
+            "https://www.google.com/maps/dir/?api=1&origin=SFO&destination=Union%20Square&travelmode=transit"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm to Golden Gate Park By Bike"),
+            "https://www.google.com/maps/dir/?api=1&destination=Golden%20Gate%20Park&travelmode=bicycling"
+        );
+        // Not a mode, so part of the destination
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm to Stand by Me"),
+            "https://www.google.com/maps/dir/?api=1&destination=Stand%20by%20Me"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm to by transit"),
+            "https://www.google.com/maps/search/to%20by%20transit/"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_coordinates() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm 37.7749,-122.4194"),
+            "https://www.google.com/maps/search/?api=1&query=37.7749,-122.4194"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm 37.7749, -122.4194"),
+            "https://www.google.com/maps/search/?api=1&query=37.7749,%20-122.4194"
+        );
+        // Out of range
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm 137.7749,-122.4194"),
+            "https://www.google.com/maps/search/137.7749,-122.4194/"
+        );
+    }
+
+    #[test]
+    fn test_google_maps_command_plus_codes() {
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm 849VCWC8+R9"),
+            "https://www.google.com/maps/search/?api=1&query=849VCWC8%2BR9"
+        );
+        assert_eq!(
+            GoogleMapsCommand::process_args("gm CWC8+R9 Mountain View"),
+            "https://www.google.com/maps/search/?api=1&query=CWC8%2BR9%20Mountain%20View"
+        );
+        assert!(!is_plus_code("CWC8+R9"));
+        assert!(!is_plus_code("c++ meetup"));
+        assert!(!is_plus_code("ABCDEFGH+12"));
+    }
 }
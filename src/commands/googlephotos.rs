@@ -0,0 +1,92 @@
+/// Google Photos command handler
+/// Supports:
+/// - photos -> https://photos.google.com/, or the `[google] account` library
+/// - photos albums -> your albums
+/// - photos [search terms] -> a search of your photos
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("photos"),
+    description = "Navigate to Google Photos or search your photos",
+    example = "photos beach 2022",
+    examples("photos", "photos albums"),
+    args = "[albums | <search terms>]",
+    category = Productivity,
+    tags("photos", "pictures", "albums", "google"),
+    config_keys("google.account"),
+    usage(
+        "photos" = "Open your library",
+        "photos albums" = "Open your albums",
+        "photos <search terms>" = "Search your photos, e.g. by place, person, or year"
+    )
+)]
+pub struct GooglePhotosCommand;
+
+impl GooglePhotosCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let account = get_global_config().and_then(|config| config.google.account);
+        Self::url(account, Self::get_command_args(args)).into()
+    }
+
+    /// The page for `query` in signed-in account `account`'s photos, the
+    /// one Google picks when unset
+    fn url(account: Option<u32>, query: &str) -> String {
+        let mut url = UrlBuilder::new("https://photos.google.com");
+        if let Some(account) = account {
+            url = url.segment("u").segment(&account.to_string());
+        }
+        if query.is_empty() {
+            url.segment("").build()
+        } else if query.eq_ignore_ascii_case("albums") {
+            url.segment("albums").build()
+        } else {
+            url.segment("search").segment(query).build()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_google_photos_command_base() {
+        assert_eq!(
+            GooglePhotosCommand::process_args("photos"),
+            "https://photos.google.com/"
+        );
+        assert_eq!(
+            GooglePhotosCommand::url(Some(1), ""),
+            "https://photos.google.com/u/1/"
+        );
+    }
+
+    #[test]
+    fn test_google_photos_command_albums() {
+        assert_eq!(
+            GooglePhotosCommand::process_args("photos albums"),
+            "https://photos.google.com/albums"
+        );
+        assert_eq!(
+            GooglePhotosCommand::url(Some(2), "albums"),
+            "https://photos.google.com/u/2/albums"
+        );
+    }
+
+    #[test]
+    fn test_google_photos_command_search() {
+        assert_eq!(
+            GooglePhotosCommand::process_args("photos beach 2022"),
+            "https://photos.google.com/search/beach%202022"
+        );
+        assert_eq!(
+            GooglePhotosCommand::url(Some(1), "albums from paris"),
+            "https://photos.google.com/u/1/search/albums%20from%20paris"
+        );
+    }
+}
@@ -1,18 +1,34 @@
 /// Google Sheets command handler
 /// Supports: gsheets -> redirects to Google Sheets
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct GoogleSheetsCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for GoogleSheetsCommand {
-    const BINDINGS: &'static [&'static str] = &["gsheets"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gsheets"),
+    description = "Navigate to Google Sheets",
+    example = "gsheets",
+    category = Productivity,
+    tags("spreadsheets", "google", "workspace"),
+    config_keys("google.account")
+)]
+pub struct GoogleSheetsCommand;
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/spreadsheets/u/0/".to_string()
+impl GoogleSheetsCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        let account = get_global_config().and_then(|config| config.google.account);
+        Self::url(account).into()
     }
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Google Sheets", "gsheets")
+    /// Google Sheets for signed-in account `account`, the first when unset
+    fn url(account: Option<u32>) -> String {
+        UrlBuilder::new("https://docs.google.com/spreadsheets/u")
+            .segment(&account.unwrap_or_default().to_string())
+            .segment("")
+            .build()
     }
 }
 
@@ -35,4 +51,12 @@ mod tests {
             "https://docs.google.com/spreadsheets/u/0/"
         );
     }
+
+    #[test]
+    fn test_google_sheets_command_account() {
+        assert_eq!(
+            GoogleSheetsCommand::url(Some(1)),
+            "https://docs.google.com/spreadsheets/u/1/"
+        );
+    }
 }
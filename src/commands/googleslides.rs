@@ -1,18 +1,34 @@
 /// Google Slides command handler
 /// Supports: gslides -> redirects to Google Slides
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct GoogleSlidesCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for GoogleSlidesCommand {
-    const BINDINGS: &'static [&'static str] = &["gslides"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("gslides"),
+    description = "Navigate to Google Slides",
+    example = "gslides",
+    category = Productivity,
+    tags("presentations", "google", "workspace"),
+    config_keys("google.account")
+)]
+pub struct GoogleSlidesCommand;
 
-    fn process_args(_args: &str) -> String {
-        "https://docs.google.com/presentation/u/0/".to_string()
+impl GoogleSlidesCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        let account = get_global_config().and_then(|config| config.google.account);
+        Self::url(account).into()
     }
 
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to Google Slides", "gslides")
+    /// Google Slides for signed-in account `account`, the first when unset
+    fn url(account: Option<u32>) -> String {
+        UrlBuilder::new("https://docs.google.com/presentation/u")
+            .segment(&account.unwrap_or_default().to_string())
+            .segment("")
+            .build()
     }
 }
 
@@ -35,4 +51,12 @@ mod tests {
             "https://docs.google.com/presentation/u/0/"
         );
     }
+
+    #[test]
+    fn test_google_slides_command_account() {
+        assert_eq!(
+            GoogleSlidesCommand::url(Some(1)),
+            "https://docs.google.com/presentation/u/1/"
+        );
+    }
 }
@@ -5,32 +5,34 @@
 /// - go playground -> https://go.dev/play/
 /// - go tour -> https://go.dev/tour/
 /// - go docs -> https://go.dev/doc/
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("go", "golang", "gopkg"),
+    description = "Navigate to pkg.go.dev or search for Go packages",
+    example = "go http",
+    examples("go play", "go tour"),
+    args = "[play | tour | docs | <search terms>]",
+    category = Packages,
+    tags("golang", "packages", "modules", "library")
+)]
 pub struct GopkgCommand;
 
-impl BunnylolCommand for GopkgCommand {
-    const BINDINGS: &'static [&'static str] = &["go", "golang", "gopkg"];
-
-    fn process_args(args: &str) -> String {
+impl GopkgCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         match query {
-            "" => "https://pkg.go.dev".to_string(),
-            "playground" | "play" => "https://go.dev/play/".to_string(),
-            "tour" => "https://go.dev/tour/".to_string(),
-            "docs" | "doc" => "https://go.dev/doc/".to_string(),
-            _ => build_search_url("https://pkg.go.dev/search", "q", query),
+            "" => "https://pkg.go.dev".into(),
+            "playground" | "play" => "https://go.dev/play/".into(),
+            "tour" => "https://go.dev/tour/".into(),
+            "docs" | "doc" => "https://go.dev/doc/".into(),
+            _ => build_search_url("https://pkg.go.dev/search", "q", query).into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to pkg.go.dev or search for Go packages",
-            "go http",
-        )
-    }
 }
 
 #[cfg(test)]
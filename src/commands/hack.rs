@@ -2,30 +2,32 @@
 /// Supports:
 /// - hack -> https://docs.hhvm.com/hack/
 /// - hack [search terms] -> https://docs.hhvm.com/search?term=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("hack"),
+    description = "Navigate to Hack documentation or search Hack docs",
+    example = "hack async",
+    examples("hack"),
+    args = "[<search terms>]",
+    category = Docs,
+    tags("hhvm", "php", "documentation", "reference")
+)]
 pub struct HackCommand;
 
-impl BunnylolCommand for HackCommand {
-    const BINDINGS: &'static [&'static str] = &["hack"];
-
-    fn process_args(args: &str) -> String {
+impl HackCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://docs.hhvm.com/hack/".to_string()
+            "https://docs.hhvm.com/hack/".into()
         } else {
-            build_search_url("https://docs.hhvm.com/search", "term", query)
+            build_search_url("https://docs.hhvm.com/search", "term", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Hack documentation or search Hack docs",
-            "hack async",
-        )
-    }
 }
 
 #[cfg(test)]
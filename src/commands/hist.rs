@@ -0,0 +1,55 @@
+/// History search command handler
+/// Supports:
+/// - hist -> /history (your recent commands)
+/// - hist [search terms] -> /history?q=[search terms]
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_encoding::build_search_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("hist"),
+    description = "Search your own command history and re-run past commands",
+    example = "hist react",
+    examples("hist"),
+    args = "[<text>]",
+    category = Productivity,
+    tags("history", "recent", "past", "search"),
+    usage(
+        "hist" = "List your recent commands",
+        "hist <text>" = "List your past commands containing <text>"
+    ),
+    config_keys("history.enabled")
+)]
+pub struct HistCommand;
+
+impl HistCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let query = Self::get_command_args(args);
+        if query.is_empty() {
+            "/history".into()
+        } else {
+            build_search_url("/history", "q", query).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hist_command_base() {
+        assert_eq!(HistCommand::process_args("hist"), "/history");
+    }
+
+    #[test]
+    fn test_hist_command_search() {
+        assert_eq!(HistCommand::process_args("hist react"), "/history?q=react");
+        assert_eq!(
+            HistCommand::process_args("hist gh facebook"),
+            "/history?q=gh%20facebook"
+        );
+    }
+}
@@ -2,9 +2,28 @@
 /// Supports: ig, instagram, ig @[username], ig [search terms]
 /// Supports: ig reels -> redirects to Instagram Reels
 /// Supports: ig messages/msg/chat -> redirects to Instagram Direct Inbox
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::{build_path_url, build_search_url};
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("ig", "instagram"),
+    description = "Navigate to Instagram profiles, search Instagram, or access Reels/Messages",
+    example = "ig @instagram",
+    examples("ig reels", "ig cute dogs"),
+    args = "[@<user> | reels | messages | <search terms>]",
+    category = Social,
+    tags("social", "photos", "reels", "dm"),
+    usage(
+        "ig" = "Open Instagram",
+        "ig @<user>" = "Open a profile",
+        "ig reels" = "Open Reels",
+        "ig messages" = "Open the Direct inbox (also: msg, chat)",
+        "ig <search terms>" = "Search Instagram"
+    )
+)]
 pub struct InstagramCommand;
 
 impl InstagramCommand {
@@ -21,44 +40,32 @@ impl InstagramCommand {
     }
 }
 
-impl BunnylolCommand for InstagramCommand {
-    const BINDINGS: &'static [&'static str] = &["ig", "instagram"];
-
-    fn process_args(args: &str) -> String {
+impl InstagramCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://www.instagram.com".to_string()
+            "https://www.instagram.com".into()
         } else {
             // Check for specific subcommands first
             match query {
-                "reels" => "https://www.instagram.com/reels/".to_string(),
-                "messages" | "msg" | "chat" => {
-                    "https://www.instagram.com/direct/inbox/".to_string()
-                }
+                "reels" => "https://www.instagram.com/reels/".into(),
+                "messages" | "msg" | "chat" => "https://www.instagram.com/direct/inbox/".into(),
                 _ => {
                     // Check if it looks like an Instagram profile
                     if let Some(username) = query.strip_prefix('@') {
                         if !username.is_empty() {
-                            Self::construct_profile_url(username)
+                            Self::construct_profile_url(username).into()
                         } else {
                             // Just '@' with no username - go to homepage
-                            "https://www.instagram.com".to_string()
+                            "https://www.instagram.com".into()
                         }
                     } else {
-                        Self::construct_search_url(query)
+                        Self::construct_search_url(query).into()
                     }
                 }
             }
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Instagram profiles, search Instagram, or access Reels/Messages",
-            "ig @instagram",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,142 @@
+/// Jira command handler
+/// Supports:
+/// - jira -> the `[jira] base_url` site
+/// - jira [PROJ-123] -> the issue
+/// - jira board -> the `[jira] board` board, or the list of boards
+/// - jira [search terms] -> a JQL text search
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::configured_base_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("jira"),
+    description = "Open Jira issues or your board, or search Jira",
+    example = "jira PROJ-123",
+    examples("jira board", "jira login page timeout"),
+    args = "[<issue key> | board | <search terms>]",
+    category = Productivity,
+    tags("issues", "tickets", "atlassian", "work"),
+    config_keys("jira.base_url", "jira.board"),
+    usage(
+        "jira" = "Open your Jira site",
+        "jira <PROJ-123>" = "Open an issue",
+        "jira board" = "Open your default board",
+        "jira <search terms>" = "Search issue text with JQL"
+    )
+)]
+pub struct JiraCommand;
+
+impl JiraCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let config = get_global_config();
+        let base = config
+            .and_then(|config| config.jira.base_url.as_deref())
+            .and_then(configured_base_url)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments(
+                    "jira needs your Jira site: set base_url under [jira] in the config, \
+                     e.g. https://example.atlassian.net"
+                        .to_string(),
+                )
+            })?;
+        let board = config.and_then(|config| config.jira.board);
+        Ok(Self::url(base, board, Self::get_command_args(args)).into())
+    }
+
+    /// The page for `query` on the Jira site at `base`
+    fn url(base: &str, board: Option<u64>, query: &str) -> String {
+        if query.is_empty() {
+            base.to_string()
+        } else if query == "board" {
+            match board {
+                Some(board) => UrlBuilder::new(base)
+                    .path("secure/RapidBoard.jspa")
+                    .query("rapidView", &board.to_string())
+                    .build(),
+                None => UrlBuilder::new(base)
+                    .path("secure/ManageRapidViews.jspa")
+                    .build(),
+            }
+        } else if let Some(key) = Self::issue_key(query) {
+            UrlBuilder::new(base).path("browse").segment(&key).build()
+        } else {
+            let text = query.replace('\\', "\\\\").replace('"', "\\\"");
+            UrlBuilder::new(base)
+                .path("issues/")
+                .query("jql", &format!("text ~ \"{}\" ORDER BY updated DESC", text))
+                .build()
+        }
+    }
+
+    /// `query` as an issue key like `PROJ-123`, uppercased, if it is one
+    fn issue_key(query: &str) -> Option<String> {
+        let (project, number) = query.rsplit_once('-')?;
+        let is_project = project.starts_with(|c: char| c.is_ascii_alphabetic())
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+        (is_project && is_number).then(|| query.to_ascii_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://example.atlassian.net";
+
+    #[test]
+    fn test_jira_command_base() {
+        assert_eq!(JiraCommand::url(BASE, None, ""), BASE);
+    }
+
+    #[test]
+    fn test_jira_command_issue() {
+        assert_eq!(
+            JiraCommand::url(BASE, None, "PROJ-123"),
+            "https://example.atlassian.net/browse/PROJ-123"
+        );
+        assert_eq!(
+            JiraCommand::url(BASE, None, "proj-7"),
+            "https://example.atlassian.net/browse/PROJ-7"
+        );
+    }
+
+    #[test]
+    fn test_jira_command_board() {
+        assert_eq!(
+            JiraCommand::url(BASE, Some(12), "board"),
+            "https://example.atlassian.net/secure/RapidBoard.jspa?rapidView=12"
+        );
+        assert_eq!(
+            JiraCommand::url(BASE, None, "board"),
+            "https://example.atlassian.net/secure/ManageRapidViews.jspa"
+        );
+    }
+
+    #[test]
+    fn test_jira_command_search() {
+        assert_eq!(
+            JiraCommand::url(BASE, None, "login page"),
+            "https://example.atlassian.net/issues/?jql=text%20~%20%22login%20page%22%20ORDER%20BY%20updated%20DESC"
+        );
+        // Not issue keys
+        assert!(JiraCommand::url(BASE, None, "x-ray").contains("jql="));
+        assert!(JiraCommand::url(BASE, None, "2024-10").contains("jql="));
+        // Quotes can't end the JQL string early
+        assert!(JiraCommand::url(BASE, None, "say \"hi\"").contains("%22say%20\\%22hi\\%22%22"),);
+    }
+
+    #[test]
+    fn test_jira_command_needs_base_url() {
+        assert!(matches!(
+            JiraCommand::process_args("jira PROJ-1"),
+            Err(CommandError::InvalidArguments(_))
+        ));
+    }
+}
@@ -1,25 +1,25 @@
 /// Kagi Search command handler
 /// Supports: kagi [search terms], kg [search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("kagi", "kg"),
+    description = "Search Kagi",
+    example = "kagi rust programming",
+    examples("kg"),
+    args = "<search terms>",
+    category = Search,
+    tags("search", "web")
+)]
 pub struct KagiCommand;
 
-impl BunnylolCommand for KagiCommand {
-    const BINDINGS: &'static [&'static str] = &["kagi", "kg"];
-
-    fn process_args(args: &str) -> String {
+impl KagiCommand {
+    pub fn process_args(args: &str) -> String {
         let query = Self::get_command_args(args);
         build_search_url("https://kagi.com/search", "q", query)
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo {
-            bindings: Self::BINDINGS.iter().map(|s| s.to_string()).collect(),
-            description: "Search Kagi".to_string(),
-            example: "kagi rust programming".to_string(),
-        }
-    }
 }
 
 #[cfg(test)]
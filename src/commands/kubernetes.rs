@@ -0,0 +1,244 @@
+/// Kubernetes command handler
+/// Supports:
+/// - k8s/kube -> https://kubernetes.io/docs/home/
+/// - k8s api [kind] -> the API reference for a resource kind, like `deploy`
+/// - k8s dash [namespace] -> the `[k8s] dashboard`, on a namespace's workloads
+/// - k8s [search terms] -> a search of the Kubernetes docs
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::configured_base_url;
+
+/// API reference pages under kubernetes.io/docs/reference/kubernetes-api/,
+/// with the kind's name, short names, and plural
+const KINDS: [(&str, &[&str]); 24] = [
+    ("workload-resources/pod-v1", &["pod", "po", "pods"]),
+    (
+        "workload-resources/deployment-v1",
+        &["deployment", "deploy", "deployments"],
+    ),
+    (
+        "workload-resources/replica-set-v1",
+        &["replicaset", "rs", "replicasets"],
+    ),
+    (
+        "workload-resources/stateful-set-v1",
+        &["statefulset", "sts", "statefulsets"],
+    ),
+    (
+        "workload-resources/daemon-set-v1",
+        &["daemonset", "ds", "daemonsets"],
+    ),
+    ("workload-resources/job-v1", &["job", "jobs"]),
+    (
+        "workload-resources/cron-job-v1",
+        &["cronjob", "cj", "cronjobs"],
+    ),
+    (
+        "workload-resources/horizontal-pod-autoscaler-v2",
+        &["horizontalpodautoscaler", "hpa", "horizontalpodautoscalers"],
+    ),
+    (
+        "service-resources/service-v1",
+        &["service", "svc", "services"],
+    ),
+    (
+        "service-resources/ingress-v1",
+        &["ingress", "ing", "ingresses"],
+    ),
+    ("service-resources/endpoints-v1", &["endpoints", "ep"]),
+    (
+        "config-and-storage-resources/config-map-v1",
+        &["configmap", "cm", "configmaps"],
+    ),
+    (
+        "config-and-storage-resources/secret-v1",
+        &["secret", "secrets"],
+    ),
+    (
+        "config-and-storage-resources/persistent-volume-claim-v1",
+        &["persistentvolumeclaim", "pvc", "persistentvolumeclaims"],
+    ),
+    (
+        "config-and-storage-resources/persistent-volume-v1",
+        &["persistentvolume", "pv", "persistentvolumes"],
+    ),
+    (
+        "config-and-storage-resources/storage-class-v1",
+        &["storageclass", "sc", "storageclasses"],
+    ),
+    (
+        "cluster-resources/namespace-v1",
+        &["namespace", "ns", "namespaces"],
+    ),
+    ("cluster-resources/node-v1", &["node", "no", "nodes"]),
+    (
+        "authentication-resources/service-account-v1",
+        &["serviceaccount", "sa", "serviceaccounts"],
+    ),
+    ("authorization-resources/role-v1", &["role", "roles"]),
+    (
+        "authorization-resources/role-binding-v1",
+        &["rolebinding", "rolebindings"],
+    ),
+    (
+        "authorization-resources/cluster-role-v1",
+        &["clusterrole", "clusterroles"],
+    ),
+    (
+        "policy-resources/network-policy-v1",
+        &["networkpolicy", "netpol", "networkpolicies"],
+    ),
+    (
+        "extend-resources/custom-resource-definition-v1",
+        &["customresourcedefinition", "crd", "crds"],
+    ),
+];
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("k8s", "kube", "kubernetes"),
+    description = "Search the Kubernetes docs, open a resource's API reference, or your cluster dashboard",
+    example = "k8s pod disruption budget",
+    examples("k8s api deploy", "k8s dash payments"),
+    args = "[api <kind> | dash [<namespace>] | <search terms>]",
+    category = Dev,
+    tags("kubernetes", "containers", "docs", "infrastructure"),
+    config_keys("k8s.dashboard"),
+    usage(
+        "k8s" = "Open the Kubernetes docs",
+        "k8s <search terms>" = "Search the docs",
+        "k8s api <kind>" = "Open the API reference for a kind, like deployment or svc",
+        "k8s dash [<namespace>]" = "Open the dashboard under [k8s], on a namespace's workloads"
+    )
+)]
+pub struct KubernetesCommand;
+
+impl KubernetesCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let dashboard = get_global_config().and_then(|config| config.k8s.dashboard.as_deref());
+        Self::url(dashboard, Self::get_command_args(args)).map(Cow::Owned)
+    }
+
+    /// The page for `query`, opening `dashboard` for `dash`
+    fn url(dashboard: Option<&str>, query: &str) -> Result<String, CommandError> {
+        let (first, rest) = query
+            .split_once(char::is_whitespace)
+            .map(|(first, rest)| (first, rest.trim()))
+            .unwrap_or((query, ""));
+        match first.to_ascii_lowercase().as_str() {
+            "" => Ok("https://kubernetes.io/docs/home/".to_string()),
+            "api" if !rest.is_empty() => Ok(Self::api_url(rest)),
+            "dash" | "dashboard" => Self::dashboard_url(dashboard, rest),
+            _ => Ok(Self::search_url(query)),
+        }
+    }
+
+    fn search_url(query: &str) -> String {
+        UrlBuilder::new("https://kubernetes.io/search/")
+            .query("q", query)
+            .build()
+    }
+
+    /// The API reference page for `kind`, or a search for it if it isn't a
+    /// built-in kind
+    fn api_url(kind: &str) -> String {
+        let name = kind.to_ascii_lowercase().replace(['-', '_'], "");
+        match KINDS
+            .iter()
+            .find(|(_, names)| names.contains(&name.as_str()))
+        {
+            Some((page, _)) => {
+                UrlBuilder::new("https://kubernetes.io/docs/reference/kubernetes-api")
+                    .path(page)
+                    .segment("")
+                    .build()
+            }
+            None => Self::search_url(&format!("{} API", kind)),
+        }
+    }
+
+    /// The Kubernetes Dashboard at `dashboard`, on `namespace`'s workloads
+    fn dashboard_url(dashboard: Option<&str>, namespace: &str) -> Result<String, CommandError> {
+        let Some(dashboard) = dashboard.and_then(configured_base_url) else {
+            return Err(CommandError::InvalidArguments(
+                "k8s dash needs your dashboard: set dashboard under [k8s] in the config, \
+                 e.g. dashboard = \"https://dashboard.example.com\""
+                    .to_string(),
+            ));
+        };
+        let home = UrlBuilder::new(dashboard).segment("");
+        Ok(if namespace.is_empty() {
+            home.build()
+        } else {
+            home.fragment_with("/workloads?namespace=", namespace)
+                .build()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kubernetes_command_base() {
+        assert_eq!(
+            KubernetesCommand::process_args("k8s").as_deref(),
+            Ok("https://kubernetes.io/docs/home/")
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_command_search() {
+        assert_eq!(
+            KubernetesCommand::process_args("k8s pod disruption budget").as_deref(),
+            Ok("https://kubernetes.io/search/?q=pod%20disruption%20budget")
+        );
+        // `api` alone is a search for it
+        assert_eq!(
+            KubernetesCommand::process_args("kube api").as_deref(),
+            Ok("https://kubernetes.io/search/?q=api")
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_command_api() {
+        assert_eq!(
+            KubernetesCommand::process_args("k8s api deploy").as_deref(),
+            Ok(
+                "https://kubernetes.io/docs/reference/kubernetes-api/workload-resources/deployment-v1/"
+            )
+        );
+        assert_eq!(
+            KubernetesCommand::process_args("k8s api ConfigMap").as_deref(),
+            Ok(
+                "https://kubernetes.io/docs/reference/kubernetes-api/config-and-storage-resources/config-map-v1/"
+            )
+        );
+        assert_eq!(
+            KubernetesCommand::process_args("k8s api Certificate").as_deref(),
+            Ok("https://kubernetes.io/search/?q=Certificate%20API")
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_command_dashboard() {
+        assert!(KubernetesCommand::process_args("k8s dash payments").is_err());
+        let dashboard = Some("https://dashboard.example.com/");
+        assert_eq!(
+            KubernetesCommand::url(dashboard, "dash").as_deref(),
+            Ok("https://dashboard.example.com/")
+        );
+        assert_eq!(
+            KubernetesCommand::url(dashboard, "dash payments").as_deref(),
+            Ok("https://dashboard.example.com/#/workloads?namespace=payments")
+        );
+        assert_eq!(
+            KubernetesCommand::url(Some("https://example.com/k8s"), "dash team a&b").as_deref(),
+            Ok("https://example.com/k8s/#/workloads?namespace=team%20a%26b")
+        );
+    }
+}
@@ -0,0 +1,60 @@
+/// Repeat command handler
+/// Supports:
+/// - last / repeat -> /history/last (re-run your most recent command)
+/// - last [n] -> /history/last?n=[n] (re-run your nth most recent command)
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("last", "repeat"),
+    description = "Re-run your most recent command (or the nth most recent)",
+    example = "last 3",
+    examples("last"),
+    args = "[<n>]",
+    category = Productivity,
+    tags("history", "repeat", "recent", "again"),
+    usage(
+        "last" = "Re-run your most recent command",
+        "last <n>" = "Re-run your nth most recent distinct command"
+    ),
+    config_keys("history.enabled")
+)]
+pub struct LastCommand;
+
+impl LastCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        match Self::get_command_args(args).parse::<usize>() {
+            Ok(n) if n > 1 => UrlBuilder::new("/history/last")
+                .query("n", &n.to_string())
+                .build()
+                .into(),
+            _ => "/history/last".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_command_base() {
+        assert_eq!(LastCommand::process_args("last"), "/history/last");
+        assert_eq!(LastCommand::process_args("repeat"), "/history/last");
+        assert_eq!(LastCommand::process_args("last 1"), "/history/last");
+    }
+
+    #[test]
+    fn test_last_command_nth() {
+        assert_eq!(LastCommand::process_args("last 3"), "/history/last?n=3");
+        assert_eq!(LastCommand::process_args("repeat 2"), "/history/last?n=2");
+    }
+
+    #[test]
+    fn test_last_command_ignores_non_numbers() {
+        assert_eq!(LastCommand::process_args("last gh"), "/history/last");
+    }
+}
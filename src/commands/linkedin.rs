@@ -1,31 +1,32 @@
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct LinkedInCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for LinkedInCommand {
-    const BINDINGS: &'static [&'static str] = &["li", "linkedin"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("li", "linkedin"),
+    description = "Navigate to LinkedIn or search",
+    example = "li software engineer",
+    examples("li"),
+    args = "[<search terms>]",
+    category = Social,
+    tags("social", "jobs", "professional", "people")
+)]
+pub struct LinkedInCommand;
 
-    fn process_args(args: &str) -> String {
+impl LinkedInCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
 
         if query.is_empty() {
-            return "https://www.linkedin.com/".to_string();
+            return "https://www.linkedin.com/".into();
         }
 
-        let encoded_query = encode_url(query);
-        format!(
-            "https://www.linkedin.com/search/results/all/?keywords={}",
-            encoded_query
-        )
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to LinkedIn or search",
-            "li software engineer",
-        )
+        UrlBuilder::new("https://www.linkedin.com/search/results/all/")
+            .query("keywords", query)
+            .build()
+            .into()
     }
 }
 
@@ -2,30 +2,32 @@
 /// Supports:
 /// - mdn -> https://developer.mozilla.org
 /// - mdn [search terms] -> https://developer.mozilla.org/en-US/search?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("mdn"),
+    description = "Navigate to MDN Web Docs or search for web development resources",
+    example = "mdn flexbox",
+    examples("mdn"),
+    args = "[<search terms>]",
+    category = Docs,
+    tags("web", "javascript", "css", "html", "documentation")
+)]
 pub struct MdnCommand;
 
-impl BunnylolCommand for MdnCommand {
-    const BINDINGS: &'static [&'static str] = &["mdn"];
-
-    fn process_args(args: &str) -> String {
+impl MdnCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://developer.mozilla.org".to_string()
+            "https://developer.mozilla.org".into()
         } else {
-            build_search_url("https://developer.mozilla.org/en-US/search", "q", query)
+            build_search_url("https://developer.mozilla.org/en-US/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to MDN Web Docs or search for web development resources",
-            "mdn flexbox",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -3,34 +3,42 @@
 /// Supports: meta accounts/account -> redirects to Meta Accounts Center
 /// Supports: metaai/meta ai -> redirects to Meta AI
 /// Supports: meta pay -> redirects to Meta Pay
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct MetaCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
 
-impl BunnylolCommand for MetaCommand {
-    const BINDINGS: &'static [&'static str] = &["meta", "metaai"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("meta", "metaai"),
+    description = "Navigate to Meta, Meta AI, Meta Accounts Center, or Meta Pay",
+    example = "meta accounts",
+    examples("meta ai", "meta pay"),
+    args = "[accounts | ai | pay]",
+    category = Social,
+    tags("social", "accounts", "ai", "payments"),
+    usage(
+        "meta" = "Open meta.com",
+        "meta accounts" = "Open Accounts Center",
+        "meta ai" = "Open Meta AI (also: metaai)",
+        "meta pay" = "Open Meta Pay"
+    )
+)]
+pub struct MetaCommand;
 
-    fn process_args(args: &str) -> String {
+impl MetaCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         match query {
-            "accounts" | "account" => "https://accountscenter.meta.com".to_string(),
-            "ai" => "https://www.meta.ai".to_string(),
+            "accounts" | "account" => "https://accountscenter.meta.com".into(),
+            "ai" => "https://www.meta.ai".into(),
             "pay" => {
                 "https://accountscenter.meta.com/meta_pay_wallet/?referrer=accounts_center_home"
-                    .to_string()
+                    .into()
             }
-            "" if args.starts_with("metaai") => "https://www.meta.ai".to_string(),
-            _ => "https://www.meta.com".to_string(),
+            "" if args.starts_with("metaai") => "https://www.meta.ai".into(),
+            _ => "https://www.meta.com".into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Meta, Meta AI, Meta Accounts Center, or Meta Pay",
-            "meta accounts",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,105 +1,246 @@
+// Commands in a feature group (social, dev, google, microsoft, finance,
+// shopping) are compiled only with that feature; build.rs reads these `cfg`s
+// to leave disabled commands out of the lookup table
+#[cfg(feature = "shopping")]
 pub mod amazon;
+#[cfg(feature = "dev")]
+pub mod aws;
 pub mod bindings;
+#[cfg(feature = "dev")]
 pub mod brew;
 /// Command module exports
 ///
 /// This module re-exports all the individual command implementations
 /// for easy importing in the registry.
 pub mod bunnylol_command;
+#[cfg(feature = "dev")]
 pub mod cargo;
 pub mod chatgpt;
+#[cfg(feature = "dev")]
 pub mod choco;
 pub mod claude;
+#[cfg(feature = "dev")]
 pub mod dockerhub;
 pub mod duckduckgo;
+#[cfg(feature = "social")]
 pub mod facebook;
+pub mod figma;
+#[cfg(feature = "dev")]
 pub mod github;
+#[cfg(feature = "dev")]
 pub mod gitlab;
+#[cfg(feature = "google")]
 pub mod gmail;
+#[cfg(feature = "dev")]
 pub mod godocs;
+#[cfg(feature = "google")]
 pub mod google;
+#[cfg(feature = "google")]
+pub mod googlecalendar;
+#[cfg(feature = "google")]
 pub mod googlechat;
+#[cfg(feature = "google")]
 pub mod googledocs;
+#[cfg(feature = "google")]
+pub mod googledrive;
+#[cfg(feature = "google")]
 pub mod googlemaps;
+#[cfg(feature = "google")]
+pub mod googlephotos;
+#[cfg(feature = "google")]
 pub mod googlesheets;
+#[cfg(feature = "google")]
 pub mod googleslides;
+#[cfg(feature = "dev")]
 pub mod gopkg;
+#[cfg(feature = "dev")]
 pub mod hack;
+pub mod hist;
+#[cfg(feature = "social")]
 pub mod instagram;
+pub mod jira;
 pub mod kagi;
+#[cfg(feature = "dev")]
+pub mod kubernetes;
+pub mod last;
+#[cfg(feature = "social")]
 pub mod linkedin;
+#[cfg(feature = "dev")]
 pub mod mdn;
+#[cfg(feature = "social")]
 pub mod meta;
+#[cfg(feature = "dev")]
 pub mod node;
+pub mod notion;
+#[cfg(feature = "dev")]
 pub mod npm;
+#[cfg(feature = "dev")]
 pub mod nuget;
+#[cfg(feature = "microsoft")]
+pub mod office365;
+#[cfg(feature = "microsoft")]
+pub mod onedrive;
 pub mod onepassword;
 pub mod open;
+#[cfg(feature = "microsoft")]
+pub mod outlook;
+#[cfg(feature = "dev")]
 pub mod packagist;
+pub mod people;
+#[cfg(feature = "dev")]
 pub mod pypi;
+#[cfg(feature = "dev")]
 pub mod python;
+#[cfg(feature = "social")]
 pub mod reddit;
+#[cfg(feature = "shopping")]
 pub mod rei;
+#[cfg(feature = "dev")]
 pub mod rubygems;
+#[cfg(feature = "dev")]
 pub mod rust;
+#[cfg(feature = "finance")]
 pub mod schwab;
 pub mod search;
+#[cfg(feature = "social")]
 pub mod soundcloud;
+#[cfg(feature = "dev")]
 pub mod stackoverflow;
+#[cfg(feature = "finance")]
 pub mod stock;
+#[cfg(feature = "microsoft")]
+pub mod teams;
+#[cfg(feature = "dev")]
+pub mod terraform;
+#[cfg(feature = "social")]
 pub mod threads;
+#[cfg(feature = "google")]
+pub mod translate;
+#[cfg(feature = "social")]
 pub mod twitter;
+#[cfg(feature = "social")]
 pub mod whatsapp;
 pub mod wikipedia;
+#[cfg(feature = "social")]
 pub mod youtube;
+pub mod zoom;
 
 // Re-export the command structs for convenience
+#[cfg(feature = "shopping")]
 pub use amazon::AmazonCommand;
+#[cfg(feature = "dev")]
+pub use aws::AwsCommand;
 pub use bindings::BindingsCommand;
+#[cfg(feature = "dev")]
 pub use brew::BrewCommand;
+#[cfg(feature = "dev")]
 pub use cargo::CargoCommand;
 pub use chatgpt::ChatGPTCommand;
+#[cfg(feature = "dev")]
 pub use choco::ChocoCommand;
 pub use claude::ClaudeCommand;
+#[cfg(feature = "dev")]
 pub use dockerhub::DockerhubCommand;
 pub use duckduckgo::DuckDuckGoCommand;
+#[cfg(feature = "social")]
 pub use facebook::FacebookCommand;
+pub use figma::FigmaCommand;
+#[cfg(feature = "dev")]
 pub use github::GitHubCommand;
+#[cfg(feature = "dev")]
 pub use gitlab::GitlabCommand;
+#[cfg(feature = "google")]
 pub use gmail::GmailCommand;
+#[cfg(feature = "dev")]
 pub use godocs::GodocsCommand;
+#[cfg(feature = "google")]
 pub use google::GoogleSearchCommand;
+#[cfg(feature = "google")]
+pub use googlecalendar::GoogleCalendarCommand;
+#[cfg(feature = "google")]
 pub use googlechat::GoogleChatCommand;
+#[cfg(feature = "google")]
 pub use googledocs::GoogleDocsCommand;
+#[cfg(feature = "google")]
+pub use googledrive::GoogleDriveCommand;
+#[cfg(feature = "google")]
 pub use googlemaps::GoogleMapsCommand;
+#[cfg(feature = "google")]
+pub use googlephotos::GooglePhotosCommand;
+#[cfg(feature = "google")]
 pub use googlesheets::GoogleSheetsCommand;
+#[cfg(feature = "google")]
 pub use googleslides::GoogleSlidesCommand;
+#[cfg(feature = "dev")]
 pub use gopkg::GopkgCommand;
+#[cfg(feature = "dev")]
 pub use hack::HackCommand;
+pub use hist::HistCommand;
+#[cfg(feature = "social")]
 pub use instagram::InstagramCommand;
+pub use jira::JiraCommand;
 pub use kagi::KagiCommand;
+#[cfg(feature = "dev")]
+pub use kubernetes::KubernetesCommand;
+pub use last::LastCommand;
+#[cfg(feature = "social")]
 pub use linkedin::LinkedInCommand;
+#[cfg(feature = "dev")]
 pub use mdn::MdnCommand;
+#[cfg(feature = "social")]
 pub use meta::MetaCommand;
+#[cfg(feature = "dev")]
 pub use node::NodeCommand;
+pub use notion::NotionCommand;
+#[cfg(feature = "dev")]
 pub use npm::NpmCommand;
+#[cfg(feature = "dev")]
 pub use nuget::NugetCommand;
+#[cfg(feature = "microsoft")]
+pub use office365::Office365Command;
+#[cfg(feature = "microsoft")]
+pub use onedrive::OneDriveCommand;
 pub use onepassword::OnePasswordCommand;
 pub use open::OpenCommand;
+#[cfg(feature = "microsoft")]
+pub use outlook::OutlookCommand;
+#[cfg(feature = "dev")]
 pub use packagist::PackagistCommand;
+pub use people::people_url;
+#[cfg(feature = "dev")]
 pub use pypi::PypiCommand;
+#[cfg(feature = "dev")]
 pub use python::PythonCommand;
+#[cfg(feature = "social")]
 pub use reddit::RedditCommand;
+#[cfg(feature = "shopping")]
 pub use rei::REICommand;
+#[cfg(feature = "dev")]
 pub use rubygems::RubygemsCommand;
+#[cfg(feature = "dev")]
 pub use rust::RustCommand;
+#[cfg(feature = "finance")]
 pub use schwab::SchwabCommand;
 pub use search::search_url;
+#[cfg(feature = "social")]
 pub use soundcloud::SoundCloudCommand;
+#[cfg(feature = "dev")]
 pub use stackoverflow::StackOverflowCommand;
+#[cfg(feature = "finance")]
 pub use stock::StockCommand;
+#[cfg(feature = "microsoft")]
+pub use teams::TeamsCommand;
+#[cfg(feature = "dev")]
+pub use terraform::TerraformCommand;
+#[cfg(feature = "social")]
 pub use threads::ThreadsCommand;
+#[cfg(feature = "google")]
+pub use translate::TranslateCommand;
+#[cfg(feature = "social")]
 pub use twitter::TwitterCommand;
+#[cfg(feature = "social")]
 pub use whatsapp::WhatsAppCommand;
 pub use wikipedia::WikipediaCommand;
+#[cfg(feature = "social")]
 pub use youtube::YouTubeCommand;
+pub use zoom::ZoomCommand;
@@ -2,8 +2,21 @@
 /// Supports:
 /// - node/nodejs -> https://nodejs.org/api/
 /// - node [module] -> https://nodejs.org/api/[module].html
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("node", "nodejs"),
+    description = "Navigate to Node.js API documentation or specific module docs",
+    example = "node fs",
+    examples("node"),
+    args = "[<module>]",
+    category = Docs,
+    tags("javascript", "nodejs", "documentation", "api")
+)]
 pub struct NodeCommand;
 
 impl NodeCommand {
@@ -19,29 +32,22 @@ impl NodeCommand {
     }
 }
 
-impl BunnylolCommand for NodeCommand {
-    const BINDINGS: &'static [&'static str] = &["node", "nodejs"];
-
-    fn process_args(args: &str) -> String {
+impl NodeCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://nodejs.org/api/".to_string()
+            "https://nodejs.org/api/".into()
         } else if !query.contains(' ') && Self::is_valid_module_name(query) {
             // Single word queries with valid module names are treated as module names
-            format!("https://nodejs.org/api/{}.html", query)
+            UrlBuilder::new("https://nodejs.org/api")
+                .segment(&format!("{}.html", query))
+                .build()
+                .into()
         } else {
             // Multi-word queries or invalid module names just go to base docs
-            "https://nodejs.org/api/".to_string()
+            "https://nodejs.org/api/".into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Node.js API documentation or specific module docs",
-            "node fs",
-        )
-    }
 }
 
 #[cfg(test)]
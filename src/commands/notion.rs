@@ -0,0 +1,84 @@
+/// Notion command handler
+/// Supports:
+/// - notion/no -> Notion, or the `[notion] domain` workspace
+/// - notion [search terms] -> a search of the workspace
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("notion", "no"),
+    description = "Open your Notion workspace or search it",
+    example = "notion",
+    examples("no roadmap"),
+    args = "[<search terms>]",
+    category = Productivity,
+    tags("notes", "docs", "wiki", "work"),
+    config_keys("notion.domain"),
+    usage(
+        "notion" = "Open your workspace",
+        "notion <search terms>" = "Search your workspace"
+    )
+)]
+pub struct NotionCommand;
+
+impl NotionCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let domain = get_global_config().and_then(|config| config.notion.domain.as_deref());
+        Self::url(domain, Self::get_command_args(args)).into()
+    }
+
+    /// The workspace at `domain`, or the last one used, searched for `query`
+    fn url(domain: Option<&str>, query: &str) -> String {
+        let mut workspace = UrlBuilder::new("https://www.notion.so");
+        if let Some(domain) = domain
+            .map(|domain| domain.trim().trim_matches('/'))
+            .filter(|domain| !domain.is_empty())
+        {
+            workspace = workspace.segment(domain);
+        }
+        if query.is_empty() {
+            workspace.build()
+        } else {
+            workspace.path("search").query("q", query).build()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notion_command_base() {
+        assert_eq!(
+            NotionCommand::process_args("notion"),
+            "https://www.notion.so/"
+        );
+        assert_eq!(NotionCommand::process_args("no"), "https://www.notion.so/");
+    }
+
+    #[test]
+    fn test_notion_command_search() {
+        assert_eq!(
+            NotionCommand::process_args("no q3 roadmap"),
+            "https://www.notion.so/search?q=q3%20roadmap"
+        );
+    }
+
+    #[test]
+    fn test_notion_command_workspace() {
+        assert_eq!(
+            NotionCommand::url(Some("acme"), ""),
+            "https://www.notion.so/acme"
+        );
+        assert_eq!(
+            NotionCommand::url(Some("/acme/"), "roadmap"),
+            "https://www.notion.so/acme/search?q=roadmap"
+        );
+        assert_eq!(NotionCommand::url(Some(""), ""), "https://www.notion.so/");
+    }
+}
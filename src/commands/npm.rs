@@ -2,30 +2,32 @@
 /// Supports:
 /// - npm/npmjs -> https://www.npmjs.com
 /// - npm [search terms] -> https://www.npmjs.com/search?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("npm", "npmjs"),
+    description = "Navigate to npmjs.com or search for npm packages",
+    example = "npm react",
+    examples("npm"),
+    args = "[<package>]",
+    category = Packages,
+    tags("javascript", "packages", "node", "library")
+)]
 pub struct NpmCommand;
 
-impl BunnylolCommand for NpmCommand {
-    const BINDINGS: &'static [&'static str] = &["npm", "npmjs"];
-
-    fn process_args(args: &str) -> String {
+impl NpmCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://www.npmjs.com".to_string()
+            "https://www.npmjs.com".into()
         } else {
-            build_search_url("https://www.npmjs.com/search", "q", query)
+            build_search_url("https://www.npmjs.com/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to npmjs.com or search for npm packages",
-            "npm react",
-        )
-    }
 }
 
 #[cfg(test)]
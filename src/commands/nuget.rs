@@ -2,30 +2,32 @@
 /// Supports:
 /// - nuget -> https://www.nuget.org
 /// - nuget [search terms] -> https://www.nuget.org/packages?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("nuget"),
+    description = "Navigate to nuget.org or search for .NET packages",
+    example = "nuget newtonsoft",
+    examples("nuget"),
+    args = "[<package>]",
+    category = Packages,
+    tags(".net", "csharp", "packages", "library")
+)]
 pub struct NugetCommand;
 
-impl BunnylolCommand for NugetCommand {
-    const BINDINGS: &'static [&'static str] = &["nuget"];
-
-    fn process_args(args: &str) -> String {
+impl NugetCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://www.nuget.org".to_string()
+            "https://www.nuget.org".into()
         } else {
-            build_search_url("https://www.nuget.org/packages", "q", query)
+            build_search_url("https://www.nuget.org/packages", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to nuget.org or search for .NET packages",
-            "nuget newtonsoft",
-        )
-    }
 }
 
 #[cfg(test)]
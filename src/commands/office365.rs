@@ -0,0 +1,101 @@
+/// Microsoft 365 command handler
+/// Supports:
+/// - o365/m365 -> the Microsoft 365 home page and its app launcher
+/// - o365 [app] -> an app, like word, excel, or powerpoint
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+/// Apps `o365 <app>` opens, with the names that pick them; the first is the
+/// app's path under `/launch`
+const APPS: [&[&str]; 6] = [
+    &["word", "doc", "docs"],
+    &["excel", "xls", "sheets"],
+    &["powerpoint", "ppt", "slides"],
+    &["onenote", "notes"],
+    &["forms"],
+    &["sway"],
+];
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("o365", "m365", "office"),
+    description = "Open Microsoft 365 or one of its apps",
+    example = "o365 word",
+    examples("o365", "m365 excel"),
+    args = "[word | excel | powerpoint | onenote | forms | sway]",
+    category = Productivity,
+    tags("office", "documents", "microsoft", "work"),
+    config_keys("microsoft.tenant"),
+    usage(
+        "o365" = "Open the Microsoft 365 home page",
+        "o365 <app>" = "Open an app: word, excel, powerpoint, onenote, forms, or sway"
+    )
+)]
+pub struct Office365Command;
+
+impl Office365Command {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let tenant = get_global_config().and_then(|config| config.microsoft.tenant.as_deref());
+        Self::url(tenant, Self::get_command_args(args)).map(Cow::Owned)
+    }
+
+    /// The page for `app`, signing in with a work account when there's a
+    /// tenant and a personal one otherwise
+    fn url(tenant: Option<&str>, app: &str) -> Result<String, CommandError> {
+        let mut url = UrlBuilder::new("https://www.microsoft365.com/");
+        if !app.is_empty() {
+            let app = app.to_ascii_lowercase();
+            let Some(names) = APPS.iter().find(|names| names.contains(&app.as_str())) else {
+                return Err(CommandError::InvalidArguments(format!(
+                    "'{}' isn't a Microsoft 365 app; try {}",
+                    app,
+                    APPS.map(|names| names[0]).join(", ")
+                )));
+            };
+            url = url.segment("launch").segment(names[0]);
+        }
+        Ok(match tenant {
+            Some(_) => url.query("auth", "2").build(),
+            None => url.query("auth", "1").build(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_office365_command_base() {
+        assert_eq!(
+            Office365Command::process_args("o365").as_deref(),
+            Ok("https://www.microsoft365.com/?auth=1")
+        );
+        assert_eq!(
+            Office365Command::url(Some("contoso.com"), "").as_deref(),
+            Ok("https://www.microsoft365.com/?auth=2")
+        );
+    }
+
+    #[test]
+    fn test_office365_command_apps() {
+        assert_eq!(
+            Office365Command::process_args("m365 Word").as_deref(),
+            Ok("https://www.microsoft365.com/launch/word?auth=1")
+        );
+        assert_eq!(
+            Office365Command::url(Some("contoso.com"), "ppt").as_deref(),
+            Ok("https://www.microsoft365.com/launch/powerpoint?auth=2")
+        );
+        assert_eq!(
+            Office365Command::process_args("o365 paint"),
+            Err(CommandError::InvalidArguments(
+                "'paint' isn't a Microsoft 365 app; try word, excel, powerpoint, onenote, forms, sway"
+                    .to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+/// OneDrive command handler
+/// Supports:
+/// - onedrive/od -> your files, at work with `[microsoft] sharepoint` or personal
+/// - onedrive [search terms] -> a search of your files
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::{MicrosoftConfig, get_global_config};
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::configured_base_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("onedrive", "od"),
+    description = "Navigate to OneDrive or search your files",
+    example = "onedrive budget 2025",
+    examples("od"),
+    args = "[<search terms>]",
+    category = Productivity,
+    tags("files", "storage", "microsoft", "work"),
+    config_keys("microsoft.tenant", "microsoft.sharepoint"),
+    usage(
+        "onedrive" = "Open your files",
+        "onedrive <search terms>" = "Search your files"
+    )
+)]
+pub struct OneDriveCommand;
+
+impl OneDriveCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let microsoft = get_global_config()
+            .map(|config| config.microsoft.clone())
+            .unwrap_or_default();
+        Self::url(&microsoft, Self::get_command_args(args)).map(Cow::Owned)
+    }
+
+    /// The page for `query` in your organization's OneDrive at
+    /// `[microsoft] sharepoint`, or your personal one without a tenant
+    fn url(microsoft: &MicrosoftConfig, query: &str) -> Result<String, CommandError> {
+        let files = match (&microsoft.sharepoint, &microsoft.tenant) {
+            (Some(sharepoint), _) => {
+                let Some(host) = configured_base_url(sharepoint) else {
+                    return Err(CommandError::InvalidArguments(format!(
+                        "[microsoft] sharepoint = \"{}\" isn't a URL like https://contoso-my.sharepoint.com",
+                        sharepoint
+                    )));
+                };
+                UrlBuilder::new(host).path("_layouts/15/onedrive.aspx")
+            }
+            (None, Some(_)) => {
+                return Err(CommandError::InvalidArguments(
+                    "onedrive needs your organization's OneDrive: set sharepoint under \
+                     [microsoft] in the config, e.g. sharepoint = \"https://contoso-my.sharepoint.com\""
+                        .to_string(),
+                ));
+            }
+            (None, None) => {
+                let files = UrlBuilder::new("https://onedrive.live.com/");
+                return Ok(if query.is_empty() {
+                    files.build()
+                } else {
+                    files.query("qt", "search").query("q", query).build()
+                });
+            }
+        };
+        Ok(if query.is_empty() {
+            files.build()
+        } else {
+            files.query("q", query).build()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_onedrive_command_personal() {
+        assert_eq!(
+            OneDriveCommand::process_args("onedrive").as_deref(),
+            Ok("https://onedrive.live.com/")
+        );
+        assert_eq!(
+            OneDriveCommand::process_args("od budget 2025").as_deref(),
+            Ok("https://onedrive.live.com/?qt=search&q=budget%202025")
+        );
+    }
+
+    #[test]
+    fn test_onedrive_command_work() {
+        let microsoft = MicrosoftConfig {
+            tenant: Some("contoso.onmicrosoft.com".to_string()),
+            sharepoint: Some("https://contoso-my.sharepoint.com/".to_string()),
+        };
+        assert_eq!(
+            OneDriveCommand::url(&microsoft, "").as_deref(),
+            Ok("https://contoso-my.sharepoint.com/_layouts/15/onedrive.aspx")
+        );
+        assert_eq!(
+            OneDriveCommand::url(&microsoft, "budget 2025").as_deref(),
+            Ok("https://contoso-my.sharepoint.com/_layouts/15/onedrive.aspx?q=budget%202025")
+        );
+    }
+
+    #[test]
+    fn test_onedrive_command_needs_sharepoint() {
+        let tenant_only = MicrosoftConfig {
+            tenant: Some("contoso.onmicrosoft.com".to_string()),
+            sharepoint: None,
+        };
+        assert!(OneDriveCommand::url(&tenant_only, "").is_err());
+        let not_a_url = MicrosoftConfig {
+            tenant: None,
+            sharepoint: Some("contoso".to_string()),
+        };
+        assert!(OneDriveCommand::url(&not_a_url, "").is_err());
+    }
+}
@@ -1,19 +1,23 @@
 /// 1Password command
 ///
 /// Shortcut to 1Password home page
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("1password", "1p", "onepassword"),
+    description = "1Password home page",
+    example = "1p",
+    category = Productivity,
+    tags("passwords", "vault", "security", "login")
+)]
 pub struct OnePasswordCommand;
 
-impl BunnylolCommand for OnePasswordCommand {
-    const BINDINGS: &'static [&'static str] = &["1password", "1p", "onepassword"];
-
-    fn process_args(_args: &str) -> String {
-        "https://my.1password.com/home".to_string()
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "1Password home page", "1p")
+impl OnePasswordCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        "https://my.1password.com/home".into()
     }
 }
 
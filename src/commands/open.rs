@@ -1,32 +1,46 @@
 /// Open command handler
 /// Supports: open (FQDN)
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
 
-pub struct OpenCommand;
+use url::Url;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
 
-impl BunnylolCommand for OpenCommand {
-    const BINDINGS: &'static [&'static str] = &["open"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("open"),
+    description = "Open an arbitrary website by FQDN",
+    example = "open example.com",
+    examples("open https://example.com/docs"),
+    args = "<domain or URL>",
+    category = Other,
+    tags("website", "url", "domain", "browse")
+)]
+pub struct OpenCommand;
 
-    fn process_args(args: &str) -> String {
+impl OpenCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
         let fqdn = Self::get_command_args(args).trim();
 
         if fqdn.is_empty() {
-            return "https://".to_string();
+            return Ok("https://".into());
         }
 
-        if fqdn.starts_with("http://") || fqdn.starts_with("https://") {
+        let address = if fqdn.starts_with("http://") || fqdn.starts_with("https://") {
             fqdn.to_string()
         } else {
             format!("https://{}", fqdn)
+        };
+        // Let the url crate encode the path and punycode the host, but keep a
+        // bare host as typed rather than adding a trailing slash
+        let url = Url::parse(&address).map_err(|e| {
+            CommandError::InvalidArguments(format!("'{}' isn't a web address: {}", fqdn, e))
+        })?;
+        let mut url = String::from(url);
+        if url.ends_with('/') && !address.ends_with('/') {
+            url.pop();
         }
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo {
-            bindings: Self::BINDINGS.iter().map(|s| s.to_string()).collect(),
-            description: "Open an arbitrary website by FQDN".to_string(),
-            example: "open example.com".to_string(),
-        }
+        Ok(url.into())
     }
 }
 
@@ -37,37 +51,55 @@ mod tests {
     #[test]
     fn test_open_command_fqdn() {
         assert_eq!(
-            OpenCommand::process_args("open example.com"),
-            "https://example.com"
+            OpenCommand::process_args("open example.com").as_deref(),
+            Ok("https://example.com")
         );
     }
 
     #[test]
     fn test_open_command_with_path() {
         assert_eq!(
-            OpenCommand::process_args("open example.com/docs"),
-            "https://example.com/docs"
+            OpenCommand::process_args("open example.com/docs").as_deref(),
+            Ok("https://example.com/docs")
         );
     }
 
     #[test]
     fn test_open_command_https_url() {
         assert_eq!(
-            OpenCommand::process_args("open https://example.com"),
-            "https://example.com"
+            OpenCommand::process_args("open https://example.com").as_deref(),
+            Ok("https://example.com")
         );
     }
 
     #[test]
     fn test_open_command_http_url() {
         assert_eq!(
-            OpenCommand::process_args("open http://example.com"),
-            "http://example.com"
+            OpenCommand::process_args("open http://example.com").as_deref(),
+            Ok("http://example.com")
+        );
+    }
+
+    #[test]
+    fn test_open_command_encodes_path() {
+        assert_eq!(
+            OpenCommand::process_args("open example.com/a b?q=café").as_deref(),
+            Ok("https://example.com/a%20b?q=caf%C3%A9")
+        );
+        assert_eq!(
+            OpenCommand::process_args("open bücher.de").as_deref(),
+            Ok("https://xn--bcher-kva.de")
         );
     }
 
+    #[test]
+    fn test_open_command_rejects_invalid_host() {
+        assert!(OpenCommand::process_args("open Tom & Jerry").is_err());
+        assert!(OpenCommand::process_args("open 100%").is_err());
+    }
+
     #[test]
     fn test_open_command_no_args() {
-        assert_eq!(OpenCommand::process_args("open"), "https://");
+        assert_eq!(OpenCommand::process_args("open").as_deref(), Ok("https://"));
     }
 }
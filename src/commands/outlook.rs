@@ -0,0 +1,150 @@
+/// Outlook command handler
+/// Supports:
+/// - outlook/ol -> your inbox, at work with `[microsoft] tenant` or personal
+/// - outlook compose [to] [subject] -> a new message with those filled in
+/// - outlook [search terms] -> a search of your mail
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::config::get_global_config;
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("outlook", "ol"),
+    description = "Navigate to Outlook, write a message, or search your mail",
+    example = "outlook",
+    examples("outlook compose alice@example.com Lunch plans", "ol quarterly report"),
+    args = "[compose [<to>] [<subject>] | <search terms>]",
+    category = Productivity,
+    tags("email", "inbox", "microsoft", "work"),
+    config_keys("microsoft.tenant"),
+    usage(
+        "outlook" = "Open your inbox",
+        "outlook <search terms>" = "Search your mail"
+    ),
+    subcommands
+)]
+pub struct OutlookCommand;
+
+impl OutlookCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[Subcommand::handler(
+        &["compose", "new", "write"],
+        "[<email> ...] [<subject>]",
+        "Write a message",
+        |rest| Self::compose_url(Self::tenant().as_deref(), rest).into(),
+    )];
+
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
+        Self::url(Self::tenant().as_deref(), Self::get_command_args(args)).into()
+    }
+
+    /// `[microsoft] tenant`, the organization to sign in to
+    fn tenant() -> Option<String> {
+        get_global_config().and_then(|config| config.microsoft.tenant.clone())
+    }
+
+    /// Outlook's mail app, for work with a tenant and personal without
+    fn mail(tenant: Option<&str>) -> UrlBuilder {
+        let host = match tenant {
+            Some(_) => "https://outlook.office.com/mail",
+            None => "https://outlook.live.com/mail",
+        };
+        UrlBuilder::new(host)
+    }
+
+    /// The inbox, with sign-in pointed at the tenant, or a search for `query`
+    fn url(tenant: Option<&str>, query: &str) -> String {
+        if !query.is_empty() {
+            return Self::mail(tenant).path("search").query("q", query).build();
+        }
+        let inbox = Self::mail(tenant).segment("");
+        match tenant {
+            Some(tenant) => inbox.query("realm", tenant).build(),
+            None => inbox.build(),
+        }
+    }
+
+    /// A new message to the addresses `message` starts with, with the rest
+    /// of it as the subject; quote a subject to keep it whole
+    fn compose_url(tenant: Option<&str>, message: &str) -> String {
+        let mut args = Args::new(message);
+        let mut to = Vec::new();
+        while let Some(address) = args
+            .clone()
+            .next()
+            .filter(|word| word.contains('@') && !word.contains(char::is_whitespace))
+        {
+            args.next();
+            to.extend(
+                address
+                    .split(',')
+                    .filter(|address| !address.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let subject = args.rest_unquoted();
+
+        let mut url = Self::mail(tenant).path("deeplink/compose");
+        if !to.is_empty() {
+            url = url.query("to", &to.join(","));
+        }
+        if !subject.is_empty() {
+            url = url.query("subject", &subject);
+        }
+        url.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outlook_command_base() {
+        assert_eq!(
+            OutlookCommand::process_args("outlook"),
+            "https://outlook.live.com/mail/"
+        );
+        assert_eq!(
+            OutlookCommand::url(Some("contoso.com"), ""),
+            "https://outlook.office.com/mail/?realm=contoso.com"
+        );
+    }
+
+    #[test]
+    fn test_outlook_command_compose() {
+        assert_eq!(
+            OutlookCommand::process_args("ol compose alice@example.com Lunch plans"),
+            "https://outlook.live.com/mail/deeplink/compose?to=alice@example.com&subject=Lunch%20plans"
+        );
+        assert_eq!(
+            OutlookCommand::compose_url(Some("contoso.com"), "a@example.com,b@example.com"),
+            "https://outlook.office.com/mail/deeplink/compose?to=a@example.com,b@example.com"
+        );
+        assert_eq!(
+            OutlookCommand::process_args("ol compose alice@example.com \"Lunch @ noon?\""),
+            "https://outlook.live.com/mail/deeplink/compose?to=alice@example.com&subject=Lunch%20@%20noon?"
+        );
+        assert_eq!(
+            OutlookCommand::process_args("outlook compose"),
+            "https://outlook.live.com/mail/deeplink/compose"
+        );
+    }
+
+    #[test]
+    fn test_outlook_command_search() {
+        assert_eq!(
+            OutlookCommand::process_args("outlook quarterly report"),
+            "https://outlook.live.com/mail/search?q=quarterly%20report"
+        );
+        assert_eq!(
+            OutlookCommand::url(Some("contoso.com"), "from:alice"),
+            "https://outlook.office.com/mail/search?q=from:alice"
+        );
+    }
+}
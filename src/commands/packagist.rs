@@ -2,30 +2,35 @@
 /// Supports:
 /// - packagist/composer -> https://packagist.org
 /// - packagist [search terms] -> https://packagist.org/search/?query=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct PackagistCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for PackagistCommand {
-    const BINDINGS: &'static [&'static str] = &["packagist", "composer"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("packagist", "composer"),
+    description = "Navigate to packagist.org or search for PHP packages",
+    example = "packagist symfony",
+    examples("composer"),
+    args = "[<package>]",
+    category = Packages,
+    tags("php", "packages", "composer", "library")
+)]
+pub struct PackagistCommand;
 
-    fn process_args(args: &str) -> String {
+impl PackagistCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://packagist.org".to_string()
+            "https://packagist.org".into()
         } else {
-            format!("https://packagist.org/search/?query={}", encode_url(query))
+            UrlBuilder::new("https://packagist.org/search/")
+                .query("query", query)
+                .build()
+                .into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to packagist.org or search for PHP packages",
-            "packagist symfony",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,73 @@
+/// People lookup for the `@name` prefix
+/// Searches the deployment's configured people source for a name
+use crate::commands::bunnylol_command::CommandError;
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::{TEMPLATE_PLACEHOLDER, expand_template};
+
+/// Build a people search URL for the given source and name.
+/// `source` is "linkedin", "github", or a URL with `{}` where the name goes
+/// (e.g. an internal directory); anything else falls back to LinkedIn.
+pub fn people_url(source: &str, name: &str) -> String {
+    match source {
+        "github" => UrlBuilder::new("https://github.com/search")
+            .query("q", name)
+            .query("type", "users")
+            .build(),
+        template if template.contains(TEMPLATE_PLACEHOLDER) => expand_template(template, name),
+        _ => UrlBuilder::new("https://www.linkedin.com/search/results/people/")
+            .query("keywords", name)
+            .build(),
+    }
+}
+
+/// Process an `@name` query (e.g. "@jane doe") against the configured source
+pub fn process_mention(args: &str) -> Result<String, CommandError> {
+    let source = get_global_config()
+        .map(|cfg| cfg.people_source.as_str())
+        .unwrap_or("linkedin");
+    let name = args.strip_prefix('@').unwrap_or(args).trim();
+    Ok(people_url(source, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_people_url_linkedin() {
+        assert_eq!(
+            people_url("linkedin", "jane doe"),
+            "https://www.linkedin.com/search/results/people/?keywords=jane%20doe"
+        );
+    }
+
+    #[test]
+    fn test_people_url_github() {
+        assert_eq!(
+            people_url("github", "octocat"),
+            "https://github.com/search?q=octocat&type=users"
+        );
+    }
+
+    #[test]
+    fn test_people_url_template() {
+        assert_eq!(
+            people_url("https://directory.example.com/search?q={}", "jane doe"),
+            "https://directory.example.com/search?q=jane%20doe"
+        );
+    }
+
+    #[test]
+    fn test_people_url_unknown_source() {
+        assert_eq!(people_url("myspace", "tom"), people_url("linkedin", "tom"));
+    }
+
+    #[test]
+    fn test_process_mention_strips_prefix() {
+        assert_eq!(
+            process_mention("@jane doe"),
+            Ok(people_url("linkedin", "jane doe"))
+        );
+    }
+}
@@ -2,30 +2,32 @@
 /// Supports:
 /// - pypi/pip -> https://pypi.org
 /// - pypi [search terms] -> https://pypi.org/search/?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("pypi", "pip"),
+    description = "Navigate to pypi.org or search for Python packages",
+    example = "pypi requests",
+    examples("pip"),
+    args = "[<package>]",
+    category = Packages,
+    tags("python", "packages", "pip", "library")
+)]
 pub struct PypiCommand;
 
-impl BunnylolCommand for PypiCommand {
-    const BINDINGS: &'static [&'static str] = &["pypi", "pip"];
-
-    fn process_args(args: &str) -> String {
+impl PypiCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://pypi.org".to_string()
+            "https://pypi.org".into()
         } else {
-            build_search_url("https://pypi.org/search/", "q", query)
+            build_search_url("https://pypi.org/search/", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to pypi.org or search for Python packages",
-            "pypi requests",
-        )
-    }
 }
 
 #[cfg(test)]
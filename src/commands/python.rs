@@ -5,32 +5,40 @@
 /// - python tutorial -> https://docs.python.org/3/tutorial/
 /// - python library -> https://docs.python.org/3/library/
 /// - python reference -> https://docs.python.org/3/reference/
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("python", "pydocs", "py"),
+    description = "Navigate to Python documentation or search for Python resources",
+    example = "python list",
+    examples("py tutorial", "py library"),
+    args = "[tutorial | library | <search terms>]",
+    category = Docs,
+    tags("documentation", "reference", "stdlib"),
+    usage(
+        "py" = "Open the Python docs",
+        "py tutorial" = "Open the tutorial",
+        "py library" = "Open the standard library reference",
+        "py <search terms>" = "Search the docs"
+    )
+)]
 pub struct PythonCommand;
 
-impl BunnylolCommand for PythonCommand {
-    const BINDINGS: &'static [&'static str] = &["python", "pydocs", "py"];
-
-    fn process_args(args: &str) -> String {
+impl PythonCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         match query {
-            "" => "https://docs.python.org/3/".to_string(),
-            "tutorial" => "https://docs.python.org/3/tutorial/".to_string(),
-            "library" | "lib" => "https://docs.python.org/3/library/".to_string(),
-            "reference" | "ref" => "https://docs.python.org/3/reference/".to_string(),
-            _ => build_search_url("https://docs.python.org/3/search.html", "q", query),
+            "" => "https://docs.python.org/3/".into(),
+            "tutorial" => "https://docs.python.org/3/tutorial/".into(),
+            "library" | "lib" => "https://docs.python.org/3/library/".into(),
+            "reference" | "ref" => "https://docs.python.org/3/reference/".into(),
+            _ => build_search_url("https://docs.python.org/3/search.html", "q", query).into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Python documentation or search for Python resources",
-            "python list",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -4,48 +4,82 @@
 /// - r [search terms] -> https://www.reddit.com/search/?q=[search terms]
 /// - r r/[subreddit] -> https://reddit.com/r/[subreddit]
 /// - r r/[subreddit] [search terms] -> https://reddit.com/r/[subreddit]/search/?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+/// - r new / r popular -> the newest or popular posts
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("r", "reddit"),
+    description = "Navigate to Reddit or search subreddits",
+    example = "r r/rust",
+    examples("r r/rust async", "r new"),
+    args = "[r/<subreddit> | <subcommand> | <search terms>]",
+    category = Social,
+    tags("social", "forum", "subreddit", "community"),
+    usage(
+        "r" = "Open Reddit",
+        "r r/<subreddit>" = "Open a subreddit",
+        "r r/<subreddit> <search terms>" = "Search within a subreddit",
+        "r <search terms>" = "Search all of Reddit"
+    ),
+    subcommands
+)]
 pub struct RedditCommand;
 
-impl BunnylolCommand for RedditCommand {
-    const BINDINGS: &'static [&'static str] = &["r", "reddit"];
+impl RedditCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(
+            &["new"],
+            "Open the newest posts",
+            "https://www.reddit.com/new/",
+        ),
+        Subcommand::url(
+            &["popular"],
+            "Open popular posts",
+            "https://www.reddit.com/r/popular/",
+        ),
+    ];
 
-    fn process_args(args: &str) -> String {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://reddit.com".to_string()
+            "https://reddit.com".into()
         } else {
+            let mut tokens = Args::new(query);
+            let first = tokens.next().unwrap_or_default();
             // Check if it starts with r/ (subreddit pattern)
-            if let Some(subreddit_part) = query.strip_prefix("r/") {
-                // Check if there are search terms after the subreddit
-                if let Some(space_idx) = subreddit_part.find(' ') {
-                    let subreddit = &subreddit_part[..space_idx];
-                    let search_terms = &subreddit_part[space_idx + 1..];
-                    build_search_url(
-                        &format!("https://reddit.com/r/{}/search/", subreddit),
-                        "q",
-                        search_terms,
-                    )
+            if let Some(subreddit) = first.strip_prefix("r/") {
+                // Search terms after the subreddit are kept verbatim, since
+                // Reddit search treats "quoted phrases" as exact matches
+                let search_terms = tokens.rest();
+                if !search_terms.is_empty() {
+                    UrlBuilder::new("https://reddit.com/r")
+                        .path(subreddit)
+                        .path("search/")
+                        .query("q", search_terms)
+                        .build()
+                        .into()
                 } else {
                     // Just a subreddit
-                    format!("https://reddit.com/r/{}", subreddit_part)
+                    UrlBuilder::new("https://reddit.com/r")
+                        .path(subreddit)
+                        .build()
+                        .into()
                 }
             } else {
                 // General reddit search
-                build_search_url("https://www.reddit.com/search/", "q", query)
+                build_search_url("https://www.reddit.com/search/", "q", query).into()
             }
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Reddit or search subreddits",
-            "r r/rust",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -57,6 +91,18 @@ mod tests {
         assert_eq!(RedditCommand::process_args("r"), "https://reddit.com");
     }
 
+    #[test]
+    fn test_reddit_command_subcommands() {
+        assert_eq!(
+            RedditCommand::process_args("r new"),
+            "https://www.reddit.com/new/"
+        );
+        assert_eq!(
+            RedditCommand::process_args("r new zealand"),
+            "https://www.reddit.com/search/?q=new%20zealand"
+        );
+    }
+
     #[test]
     fn test_reddit_command_general_search() {
         assert_eq!(
@@ -80,4 +126,20 @@ mod tests {
             "https://reddit.com/r/rust/search/?q=async%20await"
         );
     }
+
+    #[test]
+    fn test_reddit_command_subreddit_search_special_chars() {
+        assert_eq!(
+            RedditCommand::process_args("r r/csharp c# & f# 100%"),
+            "https://reddit.com/r/csharp/search/?q=c%23%20%26%20f%23%20100%25"
+        );
+    }
+
+    #[test]
+    fn test_reddit_command_subreddit_search_keeps_quotes() {
+        assert_eq!(
+            RedditCommand::process_args(r#"r r/rust "async await""#),
+            "https://reddit.com/r/rust/search/?q=%22async%20await%22"
+        );
+    }
 }
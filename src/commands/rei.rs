@@ -1,29 +1,31 @@
 /// REI command handler
 /// Supports: rei -> https://www.rei.com, rei [search terms] -> https://www.rei.com/search?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("rei"),
+    description = "Navigate to REI or search for outdoor gear",
+    example = "rei hiking boots",
+    examples("rei"),
+    args = "[<search terms>]",
+    category = Shopping,
+    tags("shopping", "outdoor", "camping", "gear")
+)]
 pub struct REICommand;
 
-impl BunnylolCommand for REICommand {
-    const BINDINGS: &'static [&'static str] = &["rei"];
-
-    fn process_args(args: &str) -> String {
+impl REICommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://www.rei.com".to_string()
+            "https://www.rei.com".into()
         } else {
-            build_search_url("https://www.rei.com/search", "q", query)
+            build_search_url("https://www.rei.com/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to REI or search for outdoor gear",
-            "rei hiking boots",
-        )
-    }
 }
 
 #[cfg(test)]
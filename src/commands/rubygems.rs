@@ -2,30 +2,35 @@
 /// Supports:
 /// - rubygems/gem/gems -> https://rubygems.org
 /// - rubygems [search terms] -> https://rubygems.org/search?query=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct RubygemsCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for RubygemsCommand {
-    const BINDINGS: &'static [&'static str] = &["rubygems", "gem", "gems"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("rubygems", "gem", "gems"),
+    description = "Navigate to rubygems.org or search for Ruby gems",
+    example = "gem rails",
+    examples("gem"),
+    args = "[<gem>]",
+    category = Packages,
+    tags("ruby", "packages", "gems", "library")
+)]
+pub struct RubygemsCommand;
 
-    fn process_args(args: &str) -> String {
+impl RubygemsCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://rubygems.org".to_string()
+            "https://rubygems.org".into()
         } else {
-            format!("https://rubygems.org/search?query={}", encode_url(query))
+            UrlBuilder::new("https://rubygems.org/search")
+                .query("query", query)
+                .build()
+                .into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to rubygems.org or search for Ruby gems",
-            "gem rails",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -2,34 +2,37 @@
 /// Supports:
 /// - rust -> https://doc.rust-lang.org/stable/std/index.html
 /// - rust [search terms] -> https://doc.rust-lang.org/stable/std/index.html?search=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("rust"),
+    description = "Navigate to Rust documentation or search Rust std docs",
+    example = "rust HashMap",
+    examples("rust"),
+    args = "[<search terms>]",
+    category = Docs,
+    tags("documentation", "std", "reference", "api")
+)]
 pub struct RustCommand;
 
-impl BunnylolCommand for RustCommand {
-    const BINDINGS: &'static [&'static str] = &["rust"];
-
-    fn process_args(args: &str) -> String {
+impl RustCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://doc.rust-lang.org/stable/std/index.html".to_string()
+            "https://doc.rust-lang.org/stable/std/index.html".into()
         } else {
             build_search_url(
                 "https://doc.rust-lang.org/stable/std/index.html",
                 "search",
                 query,
             )
+            .into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Rust documentation or search Rust std docs",
-            "rust HashMap",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -8,39 +8,49 @@
 /// - transfer/transfers/payments: Transfers and payments page
 /// - security: Security settings page
 /// - contact/contactus/call: Contact us page
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("schwab"),
+    description = "Charles Schwab shortcuts (billpay, orders, trade, transfer, security, contact)",
+    example = "schwab trade",
+    examples("schwab orders", "schwab"),
+    args = "[billpay | orders | trade | transfer | security | contact]",
+    category = Finance,
+    tags("finance", "brokerage", "banking", "investing"),
+    usage(
+        "schwab" = "Open the account summary",
+        "schwab billpay" = "Open bill pay",
+        "schwab orders" = "Open order status",
+        "schwab trade" = "Open the trade ticket",
+        "schwab transfer" = "Open transfers and payments",
+        "schwab security" = "Open security settings",
+        "schwab contact" = "Open contact options"
+    )
+)]
 pub struct SchwabCommand;
 
-impl BunnylolCommand for SchwabCommand {
-    const BINDINGS: &'static [&'static str] = &["schwab"];
-
-    fn process_args(args: &str) -> String {
+impl SchwabCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
 
         match query {
-            "billpay" => "https://client.schwab.com/app/accounts/billpay/#/billpay".to_string(),
-            "orders" => "https://client.schwab.com/app/trade/orderstatus/#/orderstatus".to_string(),
-            "trade" => "https://client.schwab.com/app/trade/tom/trade".to_string(),
+            "billpay" => "https://client.schwab.com/app/accounts/billpay/#/billpay".into(),
+            "orders" => "https://client.schwab.com/app/trade/orderstatus/#/orderstatus".into(),
+            "trade" => "https://client.schwab.com/app/trade/tom/trade".into(),
             "transfer" | "transfers" | "payments" => {
-                "https://client.schwab.com/app/accounts/transfers_and_payments_overview/#/"
-                    .to_string()
+                "https://client.schwab.com/app/accounts/transfers_and_payments_overview/#/".into()
             }
-            "security" => "https://client.schwab.com/app/access/securitysettings".to_string(),
+            "security" => "https://client.schwab.com/app/access/securitysettings".into(),
             "contact" | "contactus" | "call" => {
-                "https://client.schwab.com/app/service/contactus/contactus".to_string()
+                "https://client.schwab.com/app/service/contactus/contactus".into()
             }
-            _ => "https://client.schwab.com/app/accounts/summary/".to_string(),
+            _ => "https://client.schwab.com/app/accounts/summary/".into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Charles Schwab shortcuts (billpay, orders, trade, transfer, security, contact)",
-            "schwab trade",
-        )
-    }
 }
 
 #[cfg(test)]
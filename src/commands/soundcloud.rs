@@ -1,31 +1,38 @@
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct SoundCloudCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for SoundCloudCommand {
-    const BINDINGS: &'static [&'static str] = &["sc", "soundcloud"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("sc", "soundcloud"),
+    description = "Navigate to SoundCloud (supports: likes)",
+    example = "sc edm",
+    examples("sc likes"),
+    args = "[likes | <search terms>]",
+    category = Media,
+    tags("music", "audio", "songs", "streaming"),
+    usage(
+        "sc" = "Open Discover",
+        "sc likes" = "Open your likes",
+        "sc <search terms>" = "Search SoundCloud"
+    )
+)]
+pub struct SoundCloudCommand;
 
-    fn process_args(args: &str) -> String {
+impl SoundCloudCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
 
         match query {
-            "likes" => "https://soundcloud.com/you/likes".to_string(),
-            "" => "https://soundcloud.com/discover".to_string(),
-            _ => {
-                let encoded_query = encode_url(query);
-                format!("https://soundcloud.com/search?q={}", encoded_query)
-            }
+            "likes" => "https://soundcloud.com/you/likes".into(),
+            "" => "https://soundcloud.com/discover".into(),
+            _ => UrlBuilder::new("https://soundcloud.com/search")
+                .query("q", query)
+                .build()
+                .into(),
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to SoundCloud (supports: likes)",
-            "sc edm",
-        )
-    }
 }
 
 #[cfg(test)]
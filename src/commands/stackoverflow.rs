@@ -2,30 +2,32 @@
 /// Supports:
 /// - stackoverflow/so -> https://stackoverflow.com
 /// - stackoverflow [search terms] -> https://stackoverflow.com/search?q=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("stackoverflow", "so"),
+    description = "Navigate to Stack Overflow or search for programming questions",
+    example = "so rust ownership",
+    examples("so"),
+    args = "[<search terms>]",
+    category = Dev,
+    tags("questions", "answers", "programming", "help")
+)]
 pub struct StackOverflowCommand;
 
-impl BunnylolCommand for StackOverflowCommand {
-    const BINDINGS: &'static [&'static str] = &["stackoverflow", "so"];
-
-    fn process_args(args: &str) -> String {
+impl StackOverflowCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://stackoverflow.com".to_string()
+            "https://stackoverflow.com".into()
         } else {
-            build_search_url("https://stackoverflow.com/search", "q", query)
+            build_search_url("https://stackoverflow.com/search", "q", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Stack Overflow or search for programming questions",
-            "so rust ownership",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use crate::commands::bunnylol_command::{
+    BunnylolCommand, BunnylolCommandInfo, CommandCategory, CommandError,
+};
 use crate::config::get_global_config;
+use crate::utils::args::Args;
 use crate::utils::url_encoding::encode_url_special_char;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
@@ -82,24 +86,42 @@ impl StockCommand {
 
     /// Process a ticker with $ prefix (e.g., "$META")
     /// Uses config preference, defaults to yahoo if no config
-    pub fn process_ticker(ticker_with_dollar: &str) -> String {
+    pub fn process_ticker(ticker_with_dollar: &str) -> Result<String, CommandError> {
         Self::process_ticker_with_provider(ticker_with_dollar, Self::configured_provider())
     }
 
     /// testable version of process_ticker that takes an explicit provider name
-    fn process_ticker_with_provider(ticker_with_dollar: &str, provider_name: &str) -> String {
+    fn process_ticker_with_provider(
+        ticker_with_dollar: &str,
+        provider_name: &str,
+    ) -> Result<String, CommandError> {
         if ticker_with_dollar.len() <= 1 {
             // No ticker - return provider homepage
             let provider = Self::get_provider(provider_name);
-            return provider.homepage.to_string();
+            return Ok(provider.homepage.to_string());
         }
 
         let ticker = &ticker_with_dollar[1..];
         Self::build_url_for_provider(ticker, provider_name)
     }
 
+    /// Whether `ticker` looks like a symbol (e.g. META, BRK.B, RTY=F, ^GSPC,
+    /// META:NASDAQ) rather than free text
+    fn is_valid_ticker(ticker: &str) -> bool {
+        !ticker.is_empty()
+            && ticker
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || ".-=^:".contains(c))
+    }
+
     /// Build stock URL for a specific provider
-    fn build_url_for_provider(ticker: &str, provider_name: &str) -> String {
+    fn build_url_for_provider(ticker: &str, provider_name: &str) -> Result<String, CommandError> {
+        if !Self::is_valid_ticker(ticker) {
+            return Err(CommandError::InvalidArguments(format!(
+                "'{}' isn't a ticker symbol",
+                ticker
+            )));
+        }
         let provider = Self::get_provider(provider_name);
 
         let ticker_str = if provider.needs_encoding {
@@ -108,62 +130,67 @@ impl StockCommand {
             ticker.to_string()
         };
 
-        provider.ticker_url_template.replace("{}", &ticker_str)
+        Ok(provider.ticker_url_template.replace("{}", &ticker_str))
     }
 
     /// Testable version of process_args that takes an explicit provider name
-    fn process_args_with_provider(args: &str, provider_name: &str) -> String {
+    fn process_args_with_provider(args: &str, provider_name: &str) -> Result<String, CommandError> {
         let query = Self::get_command_args(args);
 
         if query.is_empty() {
             let provider = Self::get_provider(provider_name);
-            return provider.homepage.to_string();
+            return Ok(provider.homepage.to_string());
         }
 
         let (provider_override, ticker) = Self::parse_provider_and_ticker(query);
-        let final_provider = provider_override.unwrap_or(provider_name);
-        Self::build_url_for_provider(ticker, final_provider)
+        let final_provider = provider_override.as_deref().unwrap_or(provider_name);
+        Self::build_url_for_provider(&ticker, final_provider)
     }
 
     /// Parse provider from query (e.g., "finviz AAPL" or "AAPL")
     /// Returns (Option<provider>, ticker)
-    fn parse_provider_and_ticker(query: &str) -> (Option<&str>, &str) {
-        let parts: Vec<&str> = query.split_whitespace().collect();
-
-        if parts.len() >= 2 {
-            let potential_provider = parts[0].to_lowercase();
-
-            if PROVIDER_LOOKUP.contains_key(potential_provider.as_str()) {
-                // Return provider and rest of query, ticker starts after first whitespace + provider length
-                let ticker_start = query
-                    .find(char::is_whitespace)
-                    .map(|pos| query[pos..].trim_start())
-                    .unwrap_or("");
-                return (Some(parts[0]), ticker_start);
-            }
+    fn parse_provider_and_ticker(query: &str) -> (Option<String>, String) {
+        let mut args = Args::new(query);
+        if let Some(potential_provider) = args.next()
+            && !args.rest().is_empty()
+            && PROVIDER_LOOKUP.contains_key(potential_provider.to_lowercase().as_str())
+        {
+            return (Some(potential_provider), args.rest_unquoted());
         }
 
         // no provider is specified
-        (None, query)
+        (None, Args::new(query).rest_unquoted())
     }
 }
 
 impl BunnylolCommand for StockCommand {
     const BINDINGS: &'static [&'static str] = &["stock", "stocks", "finance"];
 
-    fn process_args(args: &str) -> String {
-        Self::process_args_with_provider(args, Self::configured_provider())
+    fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        Self::process_args_with_provider(args, Self::configured_provider()).map(Cow::Owned)
     }
 
     fn get_info() -> BunnylolCommandInfo {
         BunnylolCommandInfo::new(
             &["stock", "stocks", "finance", "$<ticker>"],
             "Look up stock prices on Yahoo Finance, Finviz, TradingView, Google Finance, or Investing.com",
-            "stock META  or  stock finviz META  or  $META",
+            "stock META",
         )
+        .with_examples(&["stock finviz META", "$META"])
+        .with_args("[<provider>] <ticker>")
+        .with_category(CommandCategory::Finance)
+        .with_tags(&["stocks", "ticker", "quote", "market", "investing"])
+        .with_usage(&[
+            ("stock <ticker>", "Look up a ticker with the configured provider"),
+            ("stock <provider> <ticker>", "Look up a ticker with a specific provider (yahoo, finviz, tv, gf, inv)"),
+            ("$<ticker>", "Shorthand for stock <ticker>"),
+        ])
+        .with_config_keys(&["stock_provider"])
     }
 }
 
+crate::register_command!(StockCommand);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,16 +199,16 @@ mod tests {
     #[test]
     fn test_stock_command_default_yahoo() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock META", "yahoo"),
-            "https://finance.yahoo.com/quote/META/"
+            StockCommand::process_args_with_provider("stock META", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/quote/META/")
         );
     }
 
     #[test]
     fn test_stock_command_no_ticker() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock", "yahoo"),
-            "https://finance.yahoo.com/"
+            StockCommand::process_args_with_provider("stock", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/")
         );
     }
 
@@ -189,24 +216,32 @@ mod tests {
     #[test]
     fn test_stock_command_finviz_override() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock finviz META", "yahoo"),
-            "https://finviz.com/quote.ashx?t=META"
+            StockCommand::process_args_with_provider("stock finviz META", "yahoo").as_deref(),
+            Ok("https://finviz.com/quote.ashx?t=META")
+        );
+    }
+
+    #[test]
+    fn test_stock_command_quoted_ticker() {
+        assert_eq!(
+            StockCommand::process_args_with_provider(r#"stock finviz "META""#, "yahoo").as_deref(),
+            Ok("https://finviz.com/quote.ashx?t=META")
         );
     }
 
     #[test]
     fn test_stock_command_tradingview_alias() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock tv AAPL", "yahoo"),
-            "https://www.tradingview.com/symbols/AAPL/"
+            StockCommand::process_args_with_provider("stock tv AAPL", "yahoo").as_deref(),
+            Ok("https://www.tradingview.com/symbols/AAPL/")
         );
     }
 
     #[test]
     fn test_stock_command_google_alias() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock gf META:NASDAQ", "yahoo"),
-            "https://www.google.com/finance/quote/META:NASDAQ"
+            StockCommand::process_args_with_provider("stock gf META:NASDAQ", "yahoo").as_deref(),
+            Ok("https://www.google.com/finance/quote/META:NASDAQ")
         );
     }
 
@@ -214,24 +249,24 @@ mod tests {
     #[test]
     fn test_stock_command_with_finviz_default() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock META", "finviz"),
-            "https://finviz.com/quote.ashx?t=META"
+            StockCommand::process_args_with_provider("stock META", "finviz").as_deref(),
+            Ok("https://finviz.com/quote.ashx?t=META")
         );
     }
 
     #[test]
     fn test_stock_command_no_ticker_with_finviz_default() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock", "finviz"),
-            "https://finviz.com/"
+            StockCommand::process_args_with_provider("stock", "finviz").as_deref(),
+            Ok("https://finviz.com/")
         );
     }
 
     #[test]
     fn test_stock_command_with_equals() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock RTY=F", "yahoo"),
-            "https://finance.yahoo.com/quote/RTY%3DF/"
+            StockCommand::process_args_with_provider("stock RTY=F", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/quote/RTY%3DF/")
         );
     }
 
@@ -239,8 +274,8 @@ mod tests {
     #[test]
     fn test_stock_command_override_beats_config() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock yahoo META", "finviz"),
-            "https://finance.yahoo.com/quote/META/"
+            StockCommand::process_args_with_provider("stock yahoo META", "finviz").as_deref(),
+            Ok("https://finance.yahoo.com/quote/META/")
         );
     }
 
@@ -248,16 +283,16 @@ mod tests {
     #[test]
     fn test_dollar_ticker_default() {
         assert_eq!(
-            StockCommand::process_ticker_with_provider("$META", "yahoo"),
-            "https://finance.yahoo.com/quote/META/"
+            StockCommand::process_ticker_with_provider("$META", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/quote/META/")
         );
     }
 
     #[test]
     fn test_dollar_ticker_with_finviz() {
         assert_eq!(
-            StockCommand::process_ticker_with_provider("$AAPL", "finviz"),
-            "https://finviz.com/quote.ashx?t=AAPL"
+            StockCommand::process_ticker_with_provider("$AAPL", "finviz").as_deref(),
+            Ok("https://finviz.com/quote.ashx?t=AAPL")
         );
     }
 
@@ -265,24 +300,32 @@ mod tests {
     #[test]
     fn test_stock_command_special_chars() {
         assert_eq!(
-            StockCommand::process_args_with_provider("stock BRK.B", "yahoo"),
-            "https://finance.yahoo.com/quote/BRK%2EB/"
+            StockCommand::process_args_with_provider("stock BRK.B", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/quote/BRK%2EB/")
         );
     }
 
     #[test]
     fn test_stock_ticker_prefix_edge_case_empty_ticker() {
         assert_eq!(
-            StockCommand::process_ticker_with_provider("$", "yahoo"),
-            "https://finance.yahoo.com/"
+            StockCommand::process_ticker_with_provider("$", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/")
         );
     }
 
     #[test]
     fn test_stock_ticker_prefix_edge_case_empty_string() {
         assert_eq!(
-            StockCommand::process_ticker_with_provider("", "yahoo"),
-            "https://finance.yahoo.com/"
+            StockCommand::process_ticker_with_provider("", "yahoo").as_deref(),
+            Ok("https://finance.yahoo.com/")
         );
     }
+
+    #[test]
+    fn test_stock_command_rejects_free_text() {
+        let error =
+            StockCommand::process_args_with_provider("stock apple inc", "yahoo").unwrap_err();
+        assert_eq!(error.to_string(), "'apple inc' isn't a ticker symbol");
+        assert!(StockCommand::process_ticker_with_provider("$META!", "yahoo").is_err());
+    }
 }
@@ -0,0 +1,101 @@
+/// Microsoft Teams command handler
+/// Supports:
+/// - teams -> https://teams.microsoft.com/
+/// - teams chat [people] -> a new chat with them
+/// - teams [search terms] -> a Teams search
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("teams"),
+    description = "Open Microsoft Teams, start a chat, or search Teams",
+    example = "teams",
+    examples("teams chat jane@example.com", "teams quarterly planning"),
+    args = "[chat <people> | <search terms>]",
+    category = Productivity,
+    tags("chat", "meetings", "microsoft", "work"),
+    usage(
+        "teams" = "Open Teams",
+        "teams <search terms>" = "Search messages, files, and people"
+    ),
+    subcommands
+)]
+pub struct TeamsCommand;
+
+impl TeamsCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[Subcommand::handler(
+        &["chat"],
+        "[<email> ...]",
+        "Start a chat with people, by email",
+        |rest| Self::chat_url(rest).into(),
+    )];
+
+    /// A new chat with `people`, a space- or comma-separated list of emails
+    fn chat_url(people: &str) -> String {
+        let users: Vec<&str> = people
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|user| !user.is_empty())
+            .collect();
+        let chat = UrlBuilder::new("https://teams.microsoft.com/l/chat/0/0");
+        if users.is_empty() {
+            chat.build()
+        } else {
+            chat.query("users", &users.join(",")).build()
+        }
+    }
+
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
+        let query = Self::get_command_args(args);
+        if query.is_empty() {
+            "https://teams.microsoft.com/".into()
+        } else {
+            UrlBuilder::new("https://teams.microsoft.com/v2/")
+                .query("q", query)
+                .build()
+                .into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_teams_command_base() {
+        assert_eq!(
+            TeamsCommand::process_args("teams"),
+            "https://teams.microsoft.com/"
+        );
+    }
+
+    #[test]
+    fn test_teams_command_chat() {
+        assert_eq!(
+            TeamsCommand::process_args("teams chat jane@example.com"),
+            "https://teams.microsoft.com/l/chat/0/0?users=jane@example.com"
+        );
+        assert_eq!(
+            TeamsCommand::process_args("teams chat jane@example.com, joe@example.com"),
+            "https://teams.microsoft.com/l/chat/0/0?users=jane@example.com,joe@example.com"
+        );
+        assert_eq!(
+            TeamsCommand::process_args("teams chat"),
+            "https://teams.microsoft.com/l/chat/0/0"
+        );
+    }
+
+    #[test]
+    fn test_teams_command_search() {
+        assert_eq!(
+            TeamsCommand::process_args("teams quarterly planning"),
+            "https://teams.microsoft.com/v2/?q=quarterly%20planning"
+        );
+    }
+}
@@ -0,0 +1,291 @@
+/// Terraform Registry command handler
+/// Supports:
+/// - tf/terraform -> https://registry.terraform.io/
+/// - tf [resource type] -> the resource's docs, e.g. `tf aws_s3_bucket`
+/// - tf data [data source] -> the data source's docs
+/// - tf data -> the `[terraform] provider` docs, or the registry
+/// - tf module [search terms] -> a search of registry modules
+/// - tf [search terms] -> a search of the registry
+///
+/// Types without a known provider prefix belong to the `[terraform] provider`.
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+/// Providers HashiCorp publishes, found without a namespace
+const HASHICORP_PROVIDERS: &[&str] = &[
+    "archive",
+    "aws",
+    "azuread",
+    "azurerm",
+    "cloudinit",
+    "consul",
+    "dns",
+    "external",
+    "google",
+    "helm",
+    "http",
+    "kubernetes",
+    "local",
+    "nomad",
+    "null",
+    "random",
+    "time",
+    "tls",
+    "vault",
+];
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("tf", "terraform"),
+    description = "Open Terraform provider docs or search the Terraform Registry",
+    example = "tf aws_s3_bucket",
+    examples("tf data aws_ami", "tf module vpc", "tf aws"),
+    args = "[<resource type> | data <data source> | module <search terms> | <search terms>]",
+    category = Dev,
+    tags("terraform", "infrastructure", "iac", "docs"),
+    config_keys("terraform.provider"),
+    usage(
+        "tf" = "Open the Terraform Registry",
+        "tf <provider>" = "Open a provider's docs, like aws",
+        "tf <resource type>" = "Open a resource's docs, like aws_s3_bucket",
+        "tf <search terms>" = "Search the registry"
+    ),
+    subcommands
+)]
+pub struct TerraformCommand;
+
+impl TerraformCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::handler(
+            &["data"],
+            "<data source>",
+            "Open a data source's docs, like aws_ami",
+            |rest| Self::data_url(Self::provider().as_deref(), rest).into(),
+        ),
+        Subcommand::handler(
+            &["module", "modules", "mod"],
+            "<search terms>",
+            "Search registry modules",
+            |rest| {
+                UrlBuilder::new("https://registry.terraform.io/search/modules")
+                    .query("q", rest)
+                    .build()
+                    .into()
+            },
+        ),
+    ];
+
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
+        let query = Self::get_command_args(args);
+        if query.is_empty() {
+            return "https://registry.terraform.io/".into();
+        }
+        let provider = Self::provider();
+        if let Some(url) = Self::provider_url(provider.as_deref(), query)
+            .or_else(|| Self::docs_url(provider.as_deref(), "resources", query))
+        {
+            return url.into();
+        }
+        Self::search_url(query).into()
+    }
+
+    /// `[terraform] provider`, for types without a known provider prefix
+    fn provider() -> Option<String> {
+        get_global_config().and_then(|config| config.terraform.provider.clone())
+    }
+
+    /// The docs for data source `data_source`, falling back to the default
+    /// provider's docs or the registry when none is given
+    fn data_url(default: Option<&str>, data_source: &str) -> String {
+        if data_source.trim().is_empty() {
+            return match default.map(Self::namespaced) {
+                Some((namespace, provider)) => Self::provider_docs(namespace, provider).build(),
+                None => "https://registry.terraform.io/".to_string(),
+            };
+        }
+        Self::docs_url(default, "data-sources", data_source)
+            .unwrap_or_else(|| Self::search_url(data_source))
+    }
+
+    fn search_url(query: &str) -> String {
+        UrlBuilder::new("https://registry.terraform.io/search")
+            .query("q", query)
+            .build()
+    }
+
+    /// `provider` as its registry namespace and name, HashiCorp's unless
+    /// given as `namespace/name`
+    fn namespaced(provider: &str) -> (&str, &str) {
+        provider.split_once('/').unwrap_or(("hashicorp", provider))
+    }
+
+    /// A provider's docs, for a query that's just a HashiCorp provider or
+    /// the default one, like `tf aws`
+    fn provider_url(default: Option<&str>, query: &str) -> Option<String> {
+        let name = query.to_ascii_lowercase();
+        let (namespace, name) = if HASHICORP_PROVIDERS.contains(&name.as_str()) {
+            ("hashicorp", name.as_str())
+        } else {
+            default
+                .map(Self::namespaced)
+                .filter(|(_, provider)| provider.eq_ignore_ascii_case(&name))?
+        };
+        Some(Self::provider_docs(namespace, name).build())
+    }
+
+    /// The docs index of provider `namespace/name`
+    fn provider_docs(namespace: &str, name: &str) -> UrlBuilder {
+        UrlBuilder::new("https://registry.terraform.io/providers")
+            .segment(namespace)
+            .segment(name)
+            .path("latest/docs")
+    }
+
+    /// The docs for resource or data source type `kind` (`resources` or
+    /// `data-sources`), if it looks like one and its provider is known
+    fn docs_url(default: Option<&str>, kind: &str, type_name: &str) -> Option<String> {
+        let type_name = type_name.trim().to_ascii_lowercase();
+        let is_type = !type_name.is_empty()
+            && type_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_type {
+            return None;
+        }
+
+        let prefix = type_name.split_once('_').map(|(prefix, _)| prefix);
+        let default = default.map(Self::namespaced);
+        let (namespace, provider, name) = match (prefix, default) {
+            (Some(prefix), _) if HASHICORP_PROVIDERS.contains(&prefix) => {
+                ("hashicorp", prefix, &type_name[prefix.len() + 1..])
+            }
+            (Some(prefix), Some((namespace, provider))) if prefix == provider => {
+                (namespace, provider, &type_name[prefix.len() + 1..])
+            }
+            // The default provider's type, without its prefix
+            (_, Some((namespace, provider))) => (namespace, provider, type_name.as_str()),
+            (_, None) => return None,
+        };
+        let docs = Self::provider_docs(namespace, provider);
+        // Just the prefix, like `aws_`
+        if name.is_empty() {
+            return Some(docs.build());
+        }
+        Some(docs.segment(kind).segment(name).build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terraform_command_base() {
+        assert_eq!(
+            TerraformCommand::process_args("tf"),
+            "https://registry.terraform.io/"
+        );
+    }
+
+    #[test]
+    fn test_terraform_command_resources() {
+        assert_eq!(
+            TerraformCommand::process_args("tf aws_s3_bucket"),
+            "https://registry.terraform.io/providers/hashicorp/aws/latest/docs/resources/s3_bucket"
+        );
+        assert_eq!(
+            TerraformCommand::process_args("terraform data google_compute_image"),
+            "https://registry.terraform.io/providers/hashicorp/google/latest/docs/data-sources/compute_image"
+        );
+        assert_eq!(
+            TerraformCommand::process_args("tf Kubernetes"),
+            "https://registry.terraform.io/providers/hashicorp/kubernetes/latest/docs"
+        );
+    }
+
+    #[test]
+    fn test_terraform_command_default_provider() {
+        let default = Some("cloudflare/cloudflare");
+        assert_eq!(
+            TerraformCommand::docs_url(default, "resources", "cloudflare_record").as_deref(),
+            Some(
+                "https://registry.terraform.io/providers/cloudflare/cloudflare/latest/docs/resources/record"
+            )
+        );
+        assert_eq!(
+            TerraformCommand::docs_url(default, "resources", "aws_instance").as_deref(),
+            Some(
+                "https://registry.terraform.io/providers/hashicorp/aws/latest/docs/resources/instance"
+            )
+        );
+        assert_eq!(
+            TerraformCommand::docs_url(Some("aws"), "resources", "s3_bucket").as_deref(),
+            Some(
+                "https://registry.terraform.io/providers/hashicorp/aws/latest/docs/resources/s3_bucket"
+            )
+        );
+        assert_eq!(
+            TerraformCommand::provider_url(default, "cloudflare").as_deref(),
+            Some("https://registry.terraform.io/providers/cloudflare/cloudflare/latest/docs")
+        );
+        assert_eq!(
+            TerraformCommand::docs_url(None, "resources", "cloudflare_record"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_terraform_command_bare_prefix_opens_provider_docs() {
+        assert_eq!(
+            TerraformCommand::process_args("tf aws_"),
+            "https://registry.terraform.io/providers/hashicorp/aws/latest/docs"
+        );
+        assert_eq!(
+            TerraformCommand::docs_url(
+                Some("cloudflare/cloudflare"),
+                "data-sources",
+                "cloudflare_"
+            )
+            .as_deref(),
+            Some("https://registry.terraform.io/providers/cloudflare/cloudflare/latest/docs")
+        );
+    }
+
+    #[test]
+    fn test_terraform_command_data_without_source() {
+        assert_eq!(
+            TerraformCommand::data_url(None, ""),
+            "https://registry.terraform.io/"
+        );
+        assert_eq!(
+            TerraformCommand::data_url(Some("cloudflare/cloudflare"), " "),
+            "https://registry.terraform.io/providers/cloudflare/cloudflare/latest/docs"
+        );
+        assert_eq!(
+            TerraformCommand::data_url(None, "aws_ami"),
+            "https://registry.terraform.io/providers/hashicorp/aws/latest/docs/data-sources/ami"
+        );
+    }
+
+    #[test]
+    fn test_terraform_command_search() {
+        assert_eq!(
+            TerraformCommand::process_args("tf module vpc"),
+            "https://registry.terraform.io/search/modules?q=vpc"
+        );
+        assert_eq!(
+            TerraformCommand::process_args("tf cloudflare_record"),
+            "https://registry.terraform.io/search?q=cloudflare_record"
+        );
+        assert_eq!(
+            TerraformCommand::process_args("tf remote state"),
+            "https://registry.terraform.io/search?q=remote%20state"
+        );
+    }
+}
@@ -1,13 +1,33 @@
 /// Threads command handler
 /// Supports: threads, threads @[username], threads [search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::{build_path_url, build_search_url};
+use std::borrow::Cow;
 
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::build_search_url;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("threads"),
+    description = "Navigate to Threads profiles or search Threads",
+    example = "threads @zuck",
+    examples("threads rust"),
+    args = "[@<user> | <search terms>]",
+    category = Social,
+    tags("social", "posts", "meta"),
+    usage(
+        "threads" = "Open Threads",
+        "threads @<user>" = "Open a profile",
+        "threads <search terms>" = "Search Threads"
+    )
+)]
 pub struct ThreadsCommand;
 
 impl ThreadsCommand {
     fn construct_profile_url(profile: &str) -> String {
-        build_path_url("https://www.threads.net", &format!("@{}", profile))
+        UrlBuilder::new("https://www.threads.net")
+            .segment(&format!("@{}", profile))
+            .build()
     }
 
     fn construct_search_url(query: &str) -> String {
@@ -15,35 +35,25 @@ impl ThreadsCommand {
     }
 }
 
-impl BunnylolCommand for ThreadsCommand {
-    const BINDINGS: &'static [&'static str] = &["threads"];
-
-    fn process_args(args: &str) -> String {
+impl ThreadsCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://www.threads.net".to_string()
+            "https://www.threads.net".into()
         } else {
             // Check if it looks like a Threads profile
             if let Some(username) = query.strip_prefix('@') {
                 if !username.is_empty() {
-                    Self::construct_profile_url(username)
+                    Self::construct_profile_url(username).into()
                 } else {
                     // Just '@' with no username - go to homepage
-                    "https://www.threads.net".to_string()
+                    "https://www.threads.net".into()
                 }
             } else {
-                Self::construct_search_url(query)
+                Self::construct_search_url(query).into()
             }
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Threads profiles or search Threads",
-            "threads @zuck",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,133 @@
+/// Google Translate command handler
+/// Supports:
+/// - tr/translate -> https://translate.google.com/
+/// - tr [text] -> the text translated between the `[translate]` languages
+/// - tr [from]>[to] [text] -> the text translated between those languages
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::config::{TranslateConfig, get_global_config};
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("tr", "translate"),
+    description = "Translate text with Google Translate",
+    example = "tr en>de good morning",
+    examples("tr \"bonjour\"", "tr >ja thank you"),
+    args = "[<from>>[<to>]] [<text>]",
+    category = Other,
+    tags("translation", "languages", "google"),
+    config_keys("translate.source", "translate.target"),
+    usage(
+        "tr" = "Open Google Translate",
+        "tr <text>" = "Translate between your default languages",
+        "tr <from>><to> <text>" = "Translate between two languages, e.g. en>de; leave either out for the default"
+    )
+)]
+pub struct TranslateCommand;
+
+impl TranslateCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        let defaults = get_global_config()
+            .map(|config| config.translate.clone())
+            .unwrap_or_default();
+        Self::url(&defaults, Self::get_command_args(args)).into()
+    }
+
+    /// The translation of `query`, with its languages or the defaults
+    fn url(defaults: &TranslateConfig, query: &str) -> String {
+        let mut args = Args::new(query);
+        let pair = Args::new(query)
+            .next()
+            .and_then(|first| Self::language_pair(&first));
+        if pair.is_some() {
+            args.next();
+        }
+        let (source, target) = pair.unwrap_or_default();
+        let text = args.rest_unquoted();
+
+        let mut url = UrlBuilder::new("https://translate.google.com/")
+            .query("sl", source.as_deref().unwrap_or(&defaults.source))
+            .query("tl", target.as_deref().unwrap_or(&defaults.target));
+        if !text.is_empty() {
+            url = url.query("text", &text);
+        }
+        url.query("op", "translate").build()
+    }
+
+    /// `word` as `<from>><to>`, either side possibly left out
+    fn language_pair(word: &str) -> Option<(Option<String>, Option<String>)> {
+        let (source, target) = word.split_once('>')?;
+        let language = |code: &str| -> Option<Option<String>> {
+            if code.is_empty() {
+                Some(None)
+            } else if code.len() <= 8 && code.chars().all(|c| c.is_ascii_alphabetic() || c == '-') {
+                Some(Some(code.to_string()))
+            } else {
+                None
+            }
+        };
+        let pair = (language(source)?, language(target)?);
+        (pair != (None, None)).then_some(pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_command_base() {
+        assert_eq!(
+            TranslateCommand::process_args("tr"),
+            "https://translate.google.com/?sl=auto&tl=en&op=translate"
+        );
+    }
+
+    #[test]
+    fn test_translate_command_text() {
+        assert_eq!(
+            TranslateCommand::process_args("tr \"bonjour\""),
+            "https://translate.google.com/?sl=auto&tl=en&text=bonjour&op=translate"
+        );
+        assert_eq!(
+            TranslateCommand::process_args("translate où & quand?"),
+            "https://translate.google.com/?sl=auto&tl=en&text=o%C3%B9%20%26%20quand?&op=translate"
+        );
+    }
+
+    #[test]
+    fn test_translate_command_language_pair() {
+        assert_eq!(
+            TranslateCommand::process_args("tr en>de good morning"),
+            "https://translate.google.com/?sl=en&tl=de&text=good%20morning&op=translate"
+        );
+        assert_eq!(
+            TranslateCommand::process_args("tr >zh-CN thank you"),
+            "https://translate.google.com/?sl=auto&tl=zh-CN&text=thank%20you&op=translate"
+        );
+        // Not a language pair
+        assert_eq!(
+            TranslateCommand::process_args("tr a>b>c"),
+            "https://translate.google.com/?sl=auto&tl=en&text=a%3Eb%3Ec&op=translate"
+        );
+    }
+
+    #[test]
+    fn test_translate_command_defaults() {
+        let defaults = TranslateConfig {
+            source: "fr".to_string(),
+            target: "es".to_string(),
+        };
+        assert_eq!(
+            TranslateCommand::url(&defaults, "bonjour"),
+            "https://translate.google.com/?sl=fr&tl=es&text=bonjour&op=translate"
+        );
+        assert_eq!(
+            TranslateCommand::url(&defaults, "de> guten Tag"),
+            "https://translate.google.com/?sl=de&tl=es&text=guten%20Tag&op=translate"
+        );
+    }
+}
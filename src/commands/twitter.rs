@@ -1,8 +1,25 @@
 /// Twitter command handler
 /// Supports: tw, tw @[username], tw [search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
 use crate::utils::url_encoding::{build_path_url, build_search_url};
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("tw"),
+    description = "Navigate to Twitter profiles or search Twitter",
+    example = "tw @MetaOpenSource",
+    examples("tw rust"),
+    args = "[@<user> | <search terms>]",
+    category = Social,
+    tags("social", "x", "tweets", "posts"),
+    usage(
+        "tw" = "Open Twitter",
+        "tw @<user>" = "Open a profile",
+        "tw <search terms>" = "Search Twitter"
+    )
+)]
 pub struct TwitterCommand;
 
 impl TwitterCommand {
@@ -15,35 +32,25 @@ impl TwitterCommand {
     }
 }
 
-impl BunnylolCommand for TwitterCommand {
-    const BINDINGS: &'static [&'static str] = &["tw"];
-
-    fn process_args(args: &str) -> String {
+impl TwitterCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://twitter.com".to_string()
+            "https://twitter.com".into()
         } else {
             // Check if it looks like a Twitter profile
             if let Some(username) = query.strip_prefix('@') {
                 if !username.is_empty() {
-                    Self::construct_profile_url(username)
+                    Self::construct_profile_url(username).into()
                 } else {
                     // Just '@' with no username - go to homepage
-                    "https://twitter.com".to_string()
+                    "https://twitter.com".into()
                 }
             } else {
-                Self::construct_search_url(query)
+                Self::construct_search_url(query).into()
             }
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to Twitter profiles or search Twitter",
-            "tw @MetaOpenSource",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -1,18 +1,22 @@
 /// WhatsApp command handler
 /// Supports: wa, whatsapp -> redirects to WhatsApp Web
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::BunnylolCommand;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("wa", "whatsapp"),
+    description = "Navigate to WhatsApp",
+    example = "wa",
+    category = Social,
+    tags("messages", "chat", "meta")
+)]
 pub struct WhatsAppCommand;
 
-impl BunnylolCommand for WhatsAppCommand {
-    const BINDINGS: &'static [&'static str] = &["wa", "whatsapp"];
-
-    fn process_args(_args: &str) -> String {
-        "https://www.whatsapp.com".to_string()
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(Self::BINDINGS, "Navigate to WhatsApp", "wa")
+impl WhatsAppCommand {
+    pub fn process_args(_args: &str) -> Cow<'static, str> {
+        "https://www.whatsapp.com".into()
     }
 }
 
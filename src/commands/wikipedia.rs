@@ -1,29 +1,32 @@
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
-use crate::utils::url_encoding::encode_url;
+use std::borrow::Cow;
 
-pub struct WikipediaCommand;
+use crate::commands::bunnylol_command::BunnylolCommand;
+use crate::utils::url_builder::UrlBuilder;
 
-impl BunnylolCommand for WikipediaCommand {
-    const BINDINGS: &'static [&'static str] = &["wiki", "wikipedia"];
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("wiki", "wikipedia"),
+    description = "Search on Wikipedia",
+    example = "wiki rust programming",
+    examples("wikipedia bunny"),
+    args = "<search terms>",
+    category = Search,
+    tags("encyclopedia", "reference", "articles", "search")
+)]
+pub struct WikipediaCommand;
 
-    fn process_args(args: &str) -> String {
+impl WikipediaCommand {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            return "https://en.wikipedia.org/".to_string();
+            return "https://en.wikipedia.org/".into();
         }
-        let encoded_query = encode_url(query);
-        format!(
-            "https://en.wikipedia.org/w/index.php?search={}&title=Special%3ASearch&ns0=1",
-            encoded_query
-        )
-    }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Search on Wikipedia",
-            "wiki rust programming",
-        )
+        UrlBuilder::new("https://en.wikipedia.org/w/index.php")
+            .query("search", query)
+            .query("title", "Special:Search")
+            .query("ns0", "1")
+            .build()
+            .into()
     }
 }
 
@@ -35,7 +38,7 @@ mod tests {
     fn test_wikipedia_command_simple() {
         assert_eq!(
             WikipediaCommand::process_args("hello"),
-            "https://en.wikipedia.org/w/index.php?search=hello&title=Special%3ASearch&ns0=1"
+            "https://en.wikipedia.org/w/index.php?search=hello&title=Special:Search&ns0=1"
         );
     }
 
@@ -43,7 +46,7 @@ mod tests {
     fn test_wikipedia_command_with_spaces() {
         assert_eq!(
             WikipediaCommand::process_args("hello world"),
-            "https://en.wikipedia.org/w/index.php?search=hello%20world&title=Special%3ASearch&ns0=1"
+            "https://en.wikipedia.org/w/index.php?search=hello%20world&title=Special:Search&ns0=1"
         );
     }
 
@@ -51,7 +54,7 @@ mod tests {
     fn test_wikipedia_command_with_wiki_prefix() {
         assert_eq!(
             WikipediaCommand::process_args("wiki hello world"),
-            "https://en.wikipedia.org/w/index.php?search=hello%20world&title=Special%3ASearch&ns0=1"
+            "https://en.wikipedia.org/w/index.php?search=hello%20world&title=Special:Search&ns0=1"
         );
     }
 
@@ -2,34 +2,50 @@
 /// Supports:
 /// - yt/youtube -> https://youtube.com/
 /// - yt [search terms] -> https://www.youtube.com/results?search_query=[search terms]
-use crate::commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+use std::borrow::Cow;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, Subcommand};
 use crate::utils::url_encoding::build_search_url;
 
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("yt", "youtube"),
+    description = "Navigate to YouTube or search for videos",
+    example = "yt rust programming",
+    examples("yt subs", "yt studio"),
+    args = "[studio | subs | <search terms>]",
+    category = Media,
+    tags("videos", "media", "streaming", "music"),
+    usage("yt" = "Open YouTube", "yt <search terms>" = "Search videos"),
+    subcommands
+)]
 pub struct YouTubeCommand;
 
-impl BunnylolCommand for YouTubeCommand {
-    const BINDINGS: &'static [&'static str] = &["yt", "youtube"];
+impl YouTubeCommand {
+    const SUBCOMMANDS: &[Subcommand] = &[
+        Subcommand::url(
+            &["studio"],
+            "Open YouTube Studio",
+            "https://studio.youtube.com/",
+        ),
+        Subcommand::url(
+            &["subs", "subscriptions"],
+            "Open subscriptions",
+            "https://www.youtube.com/feed/subscriptions",
+        ),
+    ];
 
-    fn process_args(args: &str) -> String {
+    pub fn process_args(args: &str) -> Cow<'static, str> {
+        if let Some(url) = Self::process_subcommand(args) {
+            return url;
+        }
         let query = Self::get_command_args(args);
         if query.is_empty() {
-            "https://youtube.com/".to_string()
-        } else if query == "studio" {
-            "https://studio.youtube.com/".to_string()
-        } else if query == "subscriptions" || query == "subs" {
-            "https://www.youtube.com/feed/subscriptions".to_string()
+            "https://youtube.com/".into()
         } else {
-            build_search_url("https://www.youtube.com/results", "search_query", query)
+            build_search_url("https://www.youtube.com/results", "search_query", query).into()
         }
     }
-
-    fn get_info() -> BunnylolCommandInfo {
-        BunnylolCommandInfo::new(
-            Self::BINDINGS,
-            "Navigate to YouTube or search for videos",
-            "yt rust programming",
-        )
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,143 @@
+/// Zoom command handler
+/// Supports:
+/// - zoom -> your meetings in the Zoom web portal
+/// - zoom [meeting ID] [passcode] -> the meeting's join link
+/// - zoom [name] -> a meeting from `[zoom.meetings]`
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::commands::bunnylol_command::{BunnylolCommand, CommandError};
+use crate::config::get_global_config;
+use crate::utils::url_builder::UrlBuilder;
+
+#[derive(BunnylolCommand)]
+#[bunnylol(
+    bindings("zoom"),
+    description = "Join a Zoom meeting by ID or by name, or open your meetings",
+    example = "zoom 123456789",
+    examples("zoom 123456789 passcode", "zoom standup"),
+    args = "[<meeting ID> [<passcode>] | <meeting name>]",
+    category = Productivity,
+    tags("meetings", "video", "calls", "work"),
+    config_keys("zoom.meetings"),
+    usage(
+        "zoom" = "Open your meetings",
+        "zoom <meeting ID> [<passcode>]" = "Join a meeting",
+        "zoom <name>" = "Join a meeting named under [zoom.meetings]"
+    )
+)]
+pub struct ZoomCommand;
+
+impl ZoomCommand {
+    pub fn process_args(args: &str) -> Result<Cow<'static, str>, CommandError> {
+        let meetings = get_global_config().map(|config| &config.zoom.meetings);
+        Self::url(meetings, Self::get_command_args(args)).map(Cow::Owned)
+    }
+
+    /// The page for `query`, looking names up in `meetings`
+    fn url(
+        meetings: Option<&HashMap<String, String>>,
+        query: &str,
+    ) -> Result<String, CommandError> {
+        if query.is_empty() {
+            return Ok("https://zoom.us/meeting".to_string());
+        }
+        if let Some(meeting) = meetings.and_then(|meetings| meetings.get(query)) {
+            return Self::link(meeting)
+                .or_else(|| Self::join_url(meeting))
+                .ok_or_else(|| {
+                    CommandError::InvalidArguments(format!(
+                        "[zoom.meetings] '{}' = \"{}\" isn't a meeting ID or a join link",
+                        query, meeting
+                    ))
+                });
+        }
+        Self::join_url(query).ok_or_else(|| {
+            CommandError::InvalidArguments(format!(
+                "'{}' isn't a meeting ID or a meeting under [zoom.meetings]",
+                query
+            ))
+        })
+    }
+
+    /// The join link for `<meeting ID> [<passcode>]`; dashes in the ID are
+    /// ignored, as in `123-456-789`
+    fn join_url(meeting: &str) -> Option<String> {
+        let mut words = meeting.split_whitespace();
+        let id = words.next()?.replace('-', "");
+        let passcode = words.next();
+        let is_id = (9..=11).contains(&id.len()) && id.chars().all(|c| c.is_ascii_digit());
+        if !is_id || words.next().is_some() {
+            return None;
+        }
+        let join = UrlBuilder::new("https://zoom.us/j").segment(&id);
+        Some(match passcode {
+            Some(passcode) => join.query("pwd", passcode).build(),
+            None => join.build(),
+        })
+    }
+
+    /// `meeting` as-is if it's a link, such as one copied from an invite
+    fn link(meeting: &str) -> Option<String> {
+        url::Url::parse(meeting.trim())
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .map(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_command_base() {
+        assert_eq!(
+            ZoomCommand::process_args("zoom").as_deref(),
+            Ok("https://zoom.us/meeting")
+        );
+    }
+
+    #[test]
+    fn test_zoom_command_join() {
+        assert_eq!(
+            ZoomCommand::process_args("zoom 123456789").as_deref(),
+            Ok("https://zoom.us/j/123456789")
+        );
+        assert_eq!(
+            ZoomCommand::process_args("zoom 123-4567-8901 s3cret").as_deref(),
+            Ok("https://zoom.us/j/12345678901?pwd=s3cret")
+        );
+        assert!(ZoomCommand::process_args("zoom 12345").is_err());
+        assert!(ZoomCommand::process_args("zoom 123456789 a b").is_err());
+    }
+
+    #[test]
+    fn test_zoom_command_named_meetings() {
+        let meetings: HashMap<String, String> = [
+            ("standup".to_string(), "987654321 daily".to_string()),
+            (
+                "all hands".to_string(),
+                "https://example.zoom.us/j/111222333?pwd=abc".to_string(),
+            ),
+            ("broken".to_string(), "tuesdays".to_string()),
+        ]
+        .into();
+        assert_eq!(
+            ZoomCommand::url(Some(&meetings), "standup").as_deref(),
+            Ok("https://zoom.us/j/987654321?pwd=daily")
+        );
+        assert_eq!(
+            ZoomCommand::url(Some(&meetings), "all hands").as_deref(),
+            Ok("https://example.zoom.us/j/111222333?pwd=abc")
+        );
+        assert_eq!(
+            ZoomCommand::url(Some(&meetings), "broken"),
+            Err(CommandError::InvalidArguments(
+                "[zoom.meetings] 'broken' = \"tuesdays\" isn't a meeting ID or a join link"
+                    .to_string()
+            ))
+        );
+        assert!(ZoomCommand::url(Some(&meetings), "retro").is_err());
+    }
+}
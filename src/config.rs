@@ -6,22 +6,41 @@
  */
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 
-/// Global singleton for BunnylolConfig, initialized once at startup!
-static GLOBAL_CONFIG: OnceLock<BunnylolConfig> = OnceLock::new();
+/// Global singleton for BunnylolConfig, initialized at startup and swapped
+/// when the config is reloaded
+///
+/// Configs it's swapped away from are leaked, since commands may still hold
+/// references to them; reloads are rare and a config is small.
+static GLOBAL_CONFIG: RwLock<Option<&'static BunnylolConfig>> = RwLock::new(None);
 
 /// Call once on startup
 pub fn init_global_config(config: BunnylolConfig) {
-    let _ = GLOBAL_CONFIG.set(config);
+    let mut global = GLOBAL_CONFIG
+        .write()
+        .expect("global config should not be poisoned");
+    if global.is_none() {
+        *global = Some(Box::leak(Box::new(config)));
+    }
+}
+
+/// Swap in a reloaded config, for the commands resolved from now on
+pub fn replace_global_config(config: BunnylolConfig) {
+    *GLOBAL_CONFIG
+        .write()
+        .expect("global config should not be poisoned") = Some(Box::leak(Box::new(config)));
 }
 
 /// Get a reference to the global config, after initialized.
 pub fn get_global_config() -> Option<&'static BunnylolConfig> {
-    GLOBAL_CONFIG.get()
+    *GLOBAL_CONFIG
+        .read()
+        .expect("global config should not be poisoned")
 }
 
 /// Configuration for bunnylol CLI
@@ -33,6 +52,12 @@ pub struct BunnylolConfig {
     #[serde(default)]
     pub browser: Option<String>,
 
+    /// Browsers for particular commands, mapping a binding or alias to a
+    /// browser or a browser profile (e.g. `yt = "firefox"`); the rest use
+    /// `browser`
+    #[serde(default)]
+    pub browsers: HashMap<String, BrowserChoice>,
+
     /// Default search engine when command not recognized (optional)
     /// Options: "google" (default), "ddg", "bing", "kagi"
     #[serde(default = "default_search_engine")]
@@ -43,10 +68,115 @@ pub struct BunnylolConfig {
     #[serde(default = "default_stock_provider")]
     pub stock_provider: String,
 
+    /// Where `@name` looks people up
+    /// Options: "linkedin" (default), "github", or a URL with `{}` for the name
+    #[serde(default = "default_people_source")]
+    pub people_source: String,
+
+    /// Match commands and aliases regardless of case (`GH rust` runs `gh`),
+    /// leaving the arguments as typed
+    #[serde(default = "default_case_insensitive")]
+    pub case_insensitive: bool,
+
     /// Custom command aliases
     #[serde(default)]
     pub aliases: HashMap<String, String>,
 
+    /// Custom prefix commands, mapping a prefix to a URL with `{}` where the
+    /// text after the prefix goes (e.g. `"#" = "https://jira.example.com/browse/{}"`)
+    #[serde(default)]
+    pub prefixes: HashMap<String, String>,
+
+    /// Where a command sends a bare number, mapping a binding to a URL with
+    /// `{}` for the number (e.g. `gh = "https://github.com/facebook/react/issues/{}"`)
+    #[serde(default)]
+    pub numbers: HashMap<String, String>,
+
+    /// Keyword commands, mapping a binding to a URL with `{}` where its
+    /// arguments go (e.g. `w = "https://en.wikipedia.org/w/index.php?search={}"`),
+    /// as written by `bunnylol import`
+    #[serde(default)]
+    pub keywords: HashMap<String, String>,
+
+    /// Typo handling for unrecognized commands
+    #[serde(default)]
+    pub fuzzy: FuzzyConfig,
+
+    /// Settings for the `gh` command
+    #[serde(default)]
+    pub github: GitHubConfig,
+
+    /// `gl` settings
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+
+    /// `jira` settings
+    #[serde(default)]
+    pub jira: JiraConfig,
+
+    /// `notion` settings
+    #[serde(default)]
+    pub notion: NotionConfig,
+
+    /// `figma` settings
+    #[serde(default)]
+    pub figma: FigmaConfig,
+
+    /// `zoom` settings
+    #[serde(default)]
+    pub zoom: ZoomConfig,
+
+    /// Settings shared by the Google commands
+    #[serde(default)]
+    pub google: GoogleConfig,
+
+    /// `gmail` settings
+    #[serde(default)]
+    pub gmail: GmailConfig,
+
+    /// `tr` settings
+    #[serde(default)]
+    pub translate: TranslateConfig,
+
+    /// `gmaps` settings
+    #[serde(default)]
+    pub maps: MapsConfig,
+
+    /// `outlook`, `onedrive`, and `o365` settings
+    #[serde(default)]
+    pub microsoft: MicrosoftConfig,
+
+    /// `aws` settings
+    #[serde(default)]
+    pub aws: AwsConfig,
+
+    /// `k8s` settings
+    #[serde(default)]
+    pub k8s: K8sConfig,
+
+    /// `tf` settings
+    #[serde(default)]
+    pub terraform: TerraformConfig,
+
+    /// Steps tried in order for queries that match no command, before the
+    /// default search engine
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<FallbackStep>,
+
+    /// Hooks run in order on every query before it's resolved and on every
+    /// URL it resolves to, see `crate::middleware`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub middleware: Vec<MiddlewareStep>,
+
+    /// Commands loaded from shared libraries, see `crate::plugins`
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+
+    /// Commands written as Rhai scripts, keyed by binding, see
+    /// `crate::scripting`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commands: HashMap<String, ScriptCommandConfig>,
+
     /// Command history settings
     #[serde(default)]
     pub history: HistoryConfig,
@@ -60,15 +190,320 @@ impl Default for BunnylolConfig {
     fn default() -> Self {
         Self {
             browser: None,
+            browsers: HashMap::new(),
             default_search: default_search_engine(),
             stock_provider: default_stock_provider(),
+            people_source: default_people_source(),
+            case_insensitive: default_case_insensitive(),
             aliases: HashMap::new(),
+            prefixes: HashMap::new(),
+            numbers: HashMap::new(),
+            keywords: HashMap::new(),
+            fuzzy: FuzzyConfig::default(),
+            github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            jira: JiraConfig::default(),
+            notion: NotionConfig::default(),
+            figma: FigmaConfig::default(),
+            zoom: ZoomConfig::default(),
+            google: GoogleConfig::default(),
+            gmail: GmailConfig::default(),
+            translate: TranslateConfig::default(),
+            maps: MapsConfig::default(),
+            microsoft: MicrosoftConfig::default(),
+            aws: AwsConfig::default(),
+            k8s: K8sConfig::default(),
+            terraform: TerraformConfig::default(),
+            fallback: Vec::new(),
+            middleware: Vec::new(),
+            plugins: PluginsConfig::default(),
+            commands: HashMap::new(),
             history: HistoryConfig::default(),
             server: ServerConfig::default(),
         }
     }
 }
 
+/// Configuration for typo correction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FuzzyConfig {
+    /// An unrecognized command at most this many edits from exactly one
+    /// binding or alias runs as that command instead of showing "did you
+    /// mean"; 0 (default) never corrects automatically
+    #[serde(default)]
+    pub autocorrect_distance: usize,
+}
+
+/// Configuration for the `gh` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    /// Organization `gh prs`, `gh issues`, `gh review`, and `gh notifications`
+    /// are limited to; they cover everything when unset
+    #[serde(default)]
+    pub org: Option<String>,
+}
+
+/// Configuration for the `gl` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitLabConfig {
+    /// A self-hosted GitLab, e.g. `https://gitlab.example.com`; gitlab.com
+    /// when unset
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// Configuration for the `jira` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JiraConfig {
+    /// The Jira site, e.g. `https://example.atlassian.net`; `jira` can't open
+    /// anything until it's set
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Id of the board `jira board` opens, the number after `boards/` in its
+    /// URL; the list of boards when unset
+    #[serde(default)]
+    pub board: Option<u64>,
+}
+
+/// Configuration for the `notion` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotionConfig {
+    /// The workspace's domain, the name after notion.so/ in its URL (e.g.
+    /// `acme` for notion.so/acme); the last workspace used when unset
+    #[serde(default)]
+    pub domain: Option<String>,
+}
+
+/// Configuration for the `figma` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FigmaConfig {
+    /// Id of the team `figma <query>` searches, the number after `team/` in
+    /// its URL; every file you can see when unset
+    #[serde(default)]
+    pub team: Option<String>,
+}
+
+/// Configuration for the `zoom` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZoomConfig {
+    /// Meetings joined by name, like `zoom standup`: a meeting ID with an
+    /// optional passcode after it, or a join link
+    #[serde(default)]
+    pub meetings: HashMap<String, String>,
+}
+
+/// Configuration shared by the Google commands (`gmail`, `cal`, `drive`,
+/// `docs`, `gsheets`, `gslides`, `photos`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoogleConfig {
+    /// Which signed-in Google account to use, the N in `/u/N` URLs; the first
+    /// when unset
+    #[serde(default)]
+    pub account: Option<u32>,
+}
+
+/// Configuration for the `gmail` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GmailConfig {
+    /// Which signed-in Google account to use, the N in `mail.google.com/mail/u/N`;
+    /// `[google] account` when unset
+    #[serde(default)]
+    pub account: Option<u32>,
+}
+
+/// Configuration for the `tr` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslateConfig {
+    /// Language translated from when a query doesn't name one, as a code
+    /// like "fr"; "auto" (default) detects it
+    #[serde(default = "default_translate_source")]
+    pub source: String,
+
+    /// Language translated into when a query doesn't name one (default "en")
+    #[serde(default = "default_translate_target")]
+    pub target: String,
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        Self {
+            source: default_translate_source(),
+            target: default_translate_target(),
+        }
+    }
+}
+
+/// Configuration for the `gmaps` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MapsConfig {
+    /// Address `home` stands for in directions, like `gm home to airport`
+    #[serde(default)]
+    pub home: Option<String>,
+
+    /// Address `work` stands for in directions
+    #[serde(default)]
+    pub work: Option<String>,
+}
+
+/// Configuration for the Microsoft 365 commands (`outlook`, `onedrive`, `o365`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MicrosoftConfig {
+    /// Your organization's Microsoft 365 domain, like `contoso.onmicrosoft.com`
+    /// or `contoso.com`; personal Microsoft account pages are used when unset
+    #[serde(default)]
+    pub tenant: Option<String>,
+
+    /// Your organization's OneDrive host, like `https://contoso-my.sharepoint.com`,
+    /// where `onedrive` opens work files
+    #[serde(default)]
+    pub sharepoint: Option<String>,
+}
+
+/// Configuration for the `aws` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AwsConfig {
+    /// Region console pages open in when a query doesn't name one, like
+    /// `us-west-2`; the console's last-used region when unset
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// Your IAM Identity Center (SSO) access portal, like
+    /// `https://myorg.awsapps.com/start`, opened by a bare `aws`
+    #[serde(default)]
+    pub sso_start_url: Option<String>,
+}
+
+/// Configuration for the `k8s` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct K8sConfig {
+    /// Your cluster's Kubernetes Dashboard, like `https://dashboard.example.com`,
+    /// opened by `k8s dash`
+    #[serde(default)]
+    pub dashboard: Option<String>,
+}
+
+/// Configuration for the `tf` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerraformConfig {
+    /// Provider that resource types without a known provider prefix belong
+    /// to: a HashiCorp one by name (`aws`) or any other as `namespace/name`
+    #[serde(default)]
+    pub provider: Option<String>,
+}
+
+/// Configuration for command plugins
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// Directory scanned for plugin libraries (`.so`, `.dylib`, or `.dll`)
+    /// at startup and for `.wasm` plugins while running; no plugins are
+    /// loaded when unset
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+/// A `[browsers]` entry: a browser, or one of its profiles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BrowserChoice {
+    /// A browser, as for `browser`, e.g. `"firefox"`
+    Browser(String),
+    /// A browser profile, e.g. `{ browser = "chrome", profile = "Work" }`:
+    /// the profile's directory name for Chrome and its relatives, or its
+    /// name for Firefox
+    Profile { browser: String, profile: String },
+}
+
+impl BrowserChoice {
+    pub fn browser(&self) -> &str {
+        match self {
+            BrowserChoice::Browser(browser) | BrowserChoice::Profile { browser, .. } => browser,
+        }
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            BrowserChoice::Browser(_) => None,
+            BrowserChoice::Profile { profile, .. } => Some(profile),
+        }
+    }
+}
+
+/// A `[commands.<binding>]` entry: a command run by a Rhai script
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptCommandConfig {
+    /// Path to the script, relative to the config file's directory
+    pub script: String,
+    /// More bindings for the command, besides the entry's name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Shown in the bindings list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+}
+
+/// One step of the fallback chain for unmatched queries, see `crate::fallback`
+///
+/// Each step only handles queries whose command is in `commands`, or every
+/// query when `commands` is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum FallbackStep {
+    /// Another URL with `{}` for the query, e.g. a company bunnylol
+    Url {
+        url: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        commands: Vec<String>,
+    },
+    /// Whatever another bunnylol's commands resolve the query to, e.g. the
+    /// company-wide instance; skipped when it would only search
+    Upstream {
+        /// The upstream's base URL, e.g. `https://bunny.corp.example.com`
+        url: String,
+        /// How long to wait for an answer
+        #[serde(default = "default_upstream_timeout_ms")]
+        timeout_ms: u64,
+        /// How long to remember an answer
+        #[serde(default = "default_upstream_cache_seconds")]
+        cache_seconds: u64,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        commands: Vec<String>,
+    },
+    /// The query as a DuckDuckGo bang, e.g. `imdb dune` → `!imdb dune`
+    Bang {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        commands: Vec<String>,
+    },
+    /// A search engine, `default_search` unless `engine` is set
+    Search {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        engine: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        commands: Vec<String>,
+    },
+}
+
+/// A `[[middleware]]` hook: query hooks rewrite the query before it's
+/// resolved, URL hooks each URL it resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MiddlewareStep {
+    /// Trim the query and collapse runs of whitespace
+    Normalize,
+    /// Replace text in the query, e.g. a `$me` macro
+    Replace { find: String, replace: String },
+    /// Drop query parameters from URLs; a trailing `*` matches any name
+    /// starting with the rest, e.g. `utm_*`
+    StripParams {
+        #[serde(default = "default_tracking_params")]
+        params: Vec<String>,
+    },
+    /// Upgrade `http://` URLs to HTTPS, except on localhost
+    ForceHttps,
+}
+
 /// Configuration for command history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryConfig {
@@ -79,6 +514,26 @@ pub struct HistoryConfig {
     /// Maximum number of history entries to keep
     #[serde(default = "default_max_entries")]
     pub max_entries: usize,
+
+    /// Drop entries older than this many days (unset keeps them until
+    /// `max_entries` pushes them out)
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+
+    /// Where history is stored
+    /// Options: "file" (default), "sqlite" (requires the `sqlite` feature),
+    /// "redis" (requires the `redis` feature and `redis_url`)
+    #[serde(default = "default_history_backend")]
+    pub backend: String,
+
+    /// Redis server for the "redis" backend, e.g. "redis://127.0.0.1:6379/"
+    #[serde(default)]
+    pub redis_url: Option<String>,
+
+    /// How client IPs are stored by the server
+    /// Options: "full" (default), "truncated", "hashed", "none"
+    #[serde(default = "default_ip_mode")]
+    pub ip_mode: String,
 }
 
 impl Default for HistoryConfig {
@@ -86,6 +541,10 @@ impl Default for HistoryConfig {
         Self {
             enabled: default_history_enabled(),
             max_entries: default_max_entries(),
+            retention_days: None,
+            backend: default_history_backend(),
+            redis_url: None,
+            ip_mode: default_ip_mode(),
         }
     }
 }
@@ -118,6 +577,22 @@ pub struct ServerConfig {
     /// If not set, defaults to http://localhost:{port}
     #[serde(default)]
     pub server_display_url: Option<String>,
+
+    /// Request header carrying the logged-in user, set by an authenticating
+    /// reverse proxy (e.g. "X-Forwarded-User" from oauth2-proxy)
+    ///
+    /// When set and present on a request, history is keyed by that user
+    /// instead of the client IP. Only enable this behind a proxy that
+    /// overwrites the header, since clients could otherwise send any name.
+    #[serde(default)]
+    pub identity_header: Option<String>,
+
+    /// Where the Windows service and `serve --daemon` write the server's
+    /// output; defaults to bunnylol.log beside the system config for the
+    /// service, and in the data directory for `--daemon`. The Linux service
+    /// logs to the journal instead.
+    #[serde(default)]
+    pub log_file: Option<String>,
 }
 
 impl Default for ServerConfig {
@@ -127,6 +602,8 @@ impl Default for ServerConfig {
             address: default_address(),
             log_level: default_log_level(),
             server_display_url: None,
+            identity_header: None,
+            log_file: None,
         }
     }
 }
@@ -176,6 +653,38 @@ fn default_stock_provider() -> String {
     "yahoo".to_string()
 }
 
+fn default_upstream_timeout_ms() -> u64 {
+    500
+}
+
+fn default_upstream_cache_seconds() -> u64 {
+    300
+}
+
+fn default_tracking_params() -> Vec<String> {
+    [
+        "utm_*", "fbclid", "gclid", "msclkid", "mc_cid", "mc_eid", "igshid",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_people_source() -> String {
+    "linkedin".to_string()
+}
+
+fn default_translate_source() -> String {
+    "auto".to_string()
+}
+
+fn default_translate_target() -> String {
+    "en".to_string()
+}
+
+fn default_case_insensitive() -> bool {
+    true
+}
+
 fn default_history_enabled() -> bool {
     true
 }
@@ -184,6 +693,14 @@ fn default_max_entries() -> usize {
     1000
 }
 
+fn default_history_backend() -> String {
+    "file".to_string()
+}
+
+fn default_ip_mode() -> String {
+    "full".to_string()
+}
+
 fn default_port() -> u16 {
     8000
 }
@@ -197,8 +714,21 @@ fn default_log_level() -> String {
 }
 
 impl BunnylolConfig {
-    fn get_existing_config_path() -> Option<PathBuf> {
-        let system_config = PathBuf::from("/etc/bunnylol/config.toml");
+    /// Where the system-wide config lives
+    /// Returns: /etc/bunnylol, or %ProgramData%\bunnylol on Windows
+    pub fn get_system_config_dir() -> PathBuf {
+        if cfg!(windows) {
+            let program_data =
+                std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into());
+            PathBuf::from(program_data).join("bunnylol")
+        } else {
+            PathBuf::from("/etc/bunnylol")
+        }
+    }
+
+    /// The config file `load` reads, if one exists
+    pub fn get_existing_config_path() -> Option<PathBuf> {
+        let system_config = Self::get_system_config_dir().join("config.toml");
         if system_config.exists() {
             return Some(system_config);
         }
@@ -236,7 +766,7 @@ impl BunnylolConfig {
     ///       or $XDG_CONFIG_HOME/bunnylol/config.toml (user-specific fallback)
     pub fn get_config_path() -> Option<PathBuf> {
         let user_config = Self::get_config_dir().map(|dir| dir.join("config.toml"));
-        let system_config = PathBuf::from("/etc/bunnylol/config.toml");
+        let system_config = Self::get_system_config_dir().join("config.toml");
 
         if system_config.exists() {
             // Warn if both configs exist
@@ -262,8 +792,13 @@ impl BunnylolConfig {
             return Some(existing_path);
         }
 
-        let system_config_dir = PathBuf::from("/etc/bunnylol");
-        if !system_config_dir.exists() && std::fs::create_dir_all(&system_config_dir).is_ok() {
+        // Anyone can create directories under %ProgramData%, so there only the
+        // service installer starts a system config
+        let system_config_dir = Self::get_system_config_dir();
+        if !cfg!(windows)
+            && !system_config_dir.exists()
+            && std::fs::create_dir_all(&system_config_dir).is_ok()
+        {
             return Some(system_config_dir.join("config.toml"));
         }
 
@@ -276,6 +811,36 @@ impl BunnylolConfig {
         Self::get_data_dir().map(|dir| dir.join("history"))
     }
 
+    /// Get the full path to the salt used for `ip_mode = "hashed"`
+    /// Returns: $XDG_DATA_HOME/bunnylol/history.salt
+    pub fn get_history_salt_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("history.salt"))
+    }
+
+    /// Get the full path to the log of history purges
+    /// Returns: $XDG_DATA_HOME/bunnylol/history-audit.log
+    pub fn get_history_audit_log_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("history-audit.log"))
+    }
+
+    /// Get the full path to the SQLite history database
+    /// Returns: $XDG_DATA_HOME/bunnylol/history.sqlite3
+    pub fn get_history_db_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("history.sqlite3"))
+    }
+
+    /// Get the default pid file for `serve --daemon`
+    /// Returns: $XDG_DATA_HOME/bunnylol/bunnylol.pid
+    pub fn get_pid_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("bunnylol.pid"))
+    }
+
+    /// Get the default log file for `serve --daemon`
+    /// Returns: $XDG_DATA_HOME/bunnylol/bunnylol.log
+    pub fn get_daemon_log_path() -> Option<PathBuf> {
+        Self::get_data_dir().map(|dir| dir.join("bunnylol.log"))
+    }
+
     /// Load configuration from the config file
     /// If the file doesn't exist, creates it with default configuration
     /// If the file exists but is invalid, returns an error
@@ -330,11 +895,28 @@ impl BunnylolConfig {
     }
 
     /// Convert config to TOML string with helpful comments
-    fn to_toml_with_comments(&self) -> String {
+    pub fn to_toml_with_comments(&self) -> String {
         let browser_line = match &self.browser {
             Some(b) => format!("browser = \"{}\"", b),
             None => "# browser = \"firefox\"".to_string(),
         };
+        let browsers_content = if self.browsers.is_empty() {
+            "# gh = { browser = \"chrome\", profile = \"Profile 1\" }\n# yt = \"firefox\""
+                .to_string()
+        } else {
+            let mut browsers: Vec<_> = self.browsers.iter().collect();
+            browsers.sort_by_key(|(binding, _)| *binding);
+            browsers
+                .into_iter()
+                .map(|(binding, choice)| {
+                    let choice = toml::Value::try_from(choice)
+                        .map(|choice| choice.to_string())
+                        .unwrap_or_default();
+                    format!("{} = {}", toml::Value::String(binding.clone()), choice)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
         let aliases_content = if self.aliases.is_empty() {
             "# my-alias = \"gh username/repo\"".to_string()
         } else {
@@ -344,10 +926,211 @@ impl BunnylolConfig {
                 .collect::<Vec<_>>()
                 .join("\n")
         };
+        let prefixes_content = if self.prefixes.is_empty() {
+            "# \"#\" = \"https://jira.example.com/browse/{}\"".to_string()
+        } else {
+            let mut prefixes: Vec<_> = self.prefixes.iter().collect();
+            prefixes.sort();
+            prefixes
+                .into_iter()
+                .map(|(k, v)| {
+                    // Prefixes like "#" need quoting as TOML keys
+                    let quote = |s: &String| toml::Value::String(s.clone()).to_string();
+                    format!("{} = {}", quote(k), quote(v))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let numbers_content = if self.numbers.is_empty() {
+            "# gh = \"https://github.com/owner/repo/issues/{}\"".to_string()
+        } else {
+            let mut numbers: Vec<_> = self.numbers.iter().collect();
+            numbers.sort();
+            numbers
+                .into_iter()
+                .map(|(k, v)| {
+                    let quote = |s: &String| toml::Value::String(s.clone()).to_string();
+                    format!("{} = {}", quote(k), quote(v))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let keywords_content = if self.keywords.is_empty() {
+            "# w = \"https://en.wikipedia.org/w/index.php?search={}\"".to_string()
+        } else {
+            let mut keywords: Vec<_> = self.keywords.iter().collect();
+            keywords.sort();
+            keywords
+                .into_iter()
+                .map(|(k, v)| {
+                    let quote = |s: &String| toml::Value::String(s.clone()).to_string();
+                    format!("{} = {}", quote(k), quote(v))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let zoom_meetings_content = if self.zoom.meetings.is_empty() {
+            "# standup = \"1234567890 passcode\"".to_string()
+        } else {
+            let mut meetings: Vec<_> = self.zoom.meetings.iter().collect();
+            meetings.sort();
+            meetings
+                .into_iter()
+                .map(|(k, v)| {
+                    let quote = |s: &String| toml::Value::String(s.clone()).to_string();
+                    format!("{} = {}", quote(k), quote(v))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let fallback_content = if self.fallback.is_empty() {
+            [
+                "# [[fallback]]",
+                "# type = \"url\"",
+                "# url = \"https://bunny.example.com/?cmd={}\"",
+                "# commands = [\"jira\", \"wiki\"]",
+                "#",
+                "# [[fallback]]",
+                "# type = \"bang\"",
+            ]
+            .join("\n")
+        } else {
+            #[derive(Serialize)]
+            struct Steps<'a> {
+                fallback: &'a [FallbackStep],
+            }
+            toml::to_string(&Steps {
+                fallback: &self.fallback,
+            })
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+        };
+        let middleware_content = if self.middleware.is_empty() {
+            [
+                "# [[middleware]]",
+                "# type = \"replace\"",
+                "# find = \"$me\"",
+                "# replace = \"octocat\"",
+                "#",
+                "# [[middleware]]",
+                "# type = \"strip_params\"",
+            ]
+            .join("\n")
+        } else {
+            #[derive(Serialize)]
+            struct Steps<'a> {
+                middleware: &'a [MiddlewareStep],
+            }
+            toml::to_string(&Steps {
+                middleware: &self.middleware,
+            })
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+        };
+        let commands_content = if self.commands.is_empty() {
+            "# [commands.jira]\n# script = \"scripts/jira.rhai\"".to_string()
+        } else {
+            #[derive(Serialize)]
+            struct Commands<'a> {
+                commands: std::collections::BTreeMap<&'a String, &'a ScriptCommandConfig>,
+            }
+            toml::to_string(&Commands {
+                commands: self.commands.iter().collect(),
+            })
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+        };
+        let retention_days_line = match self.history.retention_days {
+            Some(days) => format!("retention_days = {}", days),
+            None => "# retention_days = 90".to_string(),
+        };
+        let redis_url_line = match &self.history.redis_url {
+            Some(url) => format!("redis_url = {}", toml::Value::String(url.clone())),
+            None => "# redis_url = \"redis://127.0.0.1:6379/\"".to_string(),
+        };
+        let github_org_line = match &self.github.org {
+            Some(org) => format!("org = {}", toml::Value::String(org.clone())),
+            None => "# org = \"mycompany\"".to_string(),
+        };
+        let gitlab_base_url_line = match &self.gitlab.base_url {
+            Some(url) => format!("base_url = {}", toml::Value::String(url.clone())),
+            None => "# base_url = \"https://gitlab.example.com\"".to_string(),
+        };
+        let jira_base_url_line = match &self.jira.base_url {
+            Some(url) => format!("base_url = {}", toml::Value::String(url.clone())),
+            None => "# base_url = \"https://example.atlassian.net\"".to_string(),
+        };
+        let jira_board_line = match self.jira.board {
+            Some(board) => format!("board = {}", board),
+            None => "# board = 12".to_string(),
+        };
+        let notion_domain_line = match &self.notion.domain {
+            Some(domain) => format!("domain = {}", toml::Value::String(domain.clone())),
+            None => "# domain = \"mycompany\"".to_string(),
+        };
+        let figma_team_line = match &self.figma.team {
+            Some(team) => format!("team = {}", toml::Value::String(team.clone())),
+            None => "# team = \"1234567890\"".to_string(),
+        };
+        let google_account_line = match self.google.account {
+            Some(account) => format!("account = {}", account),
+            None => "# account = 1".to_string(),
+        };
+        let gmail_account_line = match self.gmail.account {
+            Some(account) => format!("account = {}", account),
+            None => "# account = 1".to_string(),
+        };
+        let maps_home_line = match &self.maps.home {
+            Some(home) => format!("home = {}", toml::Value::String(home.clone())),
+            None => "# home = \"1 Main St, Springfield\"".to_string(),
+        };
+        let maps_work_line = match &self.maps.work {
+            Some(work) => format!("work = {}", toml::Value::String(work.clone())),
+            None => "# work = \"100 Market St, Springfield\"".to_string(),
+        };
+        let microsoft_tenant_line = match &self.microsoft.tenant {
+            Some(tenant) => format!("tenant = {}", toml::Value::String(tenant.clone())),
+            None => "# tenant = \"contoso.onmicrosoft.com\"".to_string(),
+        };
+        let microsoft_sharepoint_line = match &self.microsoft.sharepoint {
+            Some(sharepoint) => format!("sharepoint = {}", toml::Value::String(sharepoint.clone())),
+            None => "# sharepoint = \"https://contoso-my.sharepoint.com\"".to_string(),
+        };
+        let aws_region_line = match &self.aws.region {
+            Some(region) => format!("region = {}", toml::Value::String(region.clone())),
+            None => "# region = \"us-west-2\"".to_string(),
+        };
+        let aws_sso_start_url_line = match &self.aws.sso_start_url {
+            Some(url) => format!("sso_start_url = {}", toml::Value::String(url.clone())),
+            None => "# sso_start_url = \"https://myorg.awsapps.com/start\"".to_string(),
+        };
+        let k8s_dashboard_line = match &self.k8s.dashboard {
+            Some(url) => format!("dashboard = {}", toml::Value::String(url.clone())),
+            None => "# dashboard = \"https://dashboard.example.com\"".to_string(),
+        };
+        let terraform_provider_line = match &self.terraform.provider {
+            Some(provider) => format!("provider = {}", toml::Value::String(provider.clone())),
+            None => "# provider = \"cloudflare/cloudflare\"".to_string(),
+        };
+        let plugins_dir_line = match &self.plugins.dir {
+            Some(dir) => format!("dir = {}", toml::Value::String(dir.clone())),
+            None => "# dir = \"/etc/bunnylol/plugins\"".to_string(),
+        };
         let server_display_url_line = match &self.server.server_display_url {
             Some(url) => format!("server_display_url = \"{}\"", url),
             None => "# server_display_url = \"bunny.example.com\"".to_string(),
         };
+        let identity_header_line = match &self.server.identity_header {
+            Some(header) => format!("identity_header = {}", toml::Value::String(header.clone())),
+            None => "# identity_header = \"X-Forwarded-User\"".to_string(),
+        };
+        let log_file_line = match &self.server.log_file {
+            Some(path) => format!("log_file = {}", toml::Value::String(path.clone())),
+            None => "# log_file = 'C:\\ProgramData\\bunnylol\\bunnylol.log'".to_string(),
+        };
 
         format!(
             r#"# Bunnylol Configuration File
@@ -369,15 +1152,169 @@ default_search = "{}"
 # Options: "yahoo" (default), "finviz", "tradingview", "google", "investing"
 stock_provider = "{}"
 
+# Where @name looks people up
+# Options: "linkedin" (default), "github", or an internal directory URL with {{}}
+#   for the name, e.g. "https://directory.example.com/search?q={{}}"
+people_source = "{}"
+
+# Match commands and aliases regardless of case ("GH rust" runs gh); the
+# arguments are left as typed
+case_insensitive = {}
+
+# Browsers for particular commands, by binding or alias; the rest use browser
+# above. A profile is the profile's directory name for Chrome, Chromium, Edge,
+# Brave, and Vivaldi (see chrome://version), or its name for Firefox
+[browsers]
+{}
+
 # Custom command aliases
 # Example: work = "gh mycompany/repo"
 [aliases]
 {}
 
+# Custom prefix commands: a token starting with the prefix opens the URL,
+# with {{}} replaced by the rest of the token (e.g. #PROJ-123, ~wiki)
+# A prefix can't overlap "$" (stock tickers) or the start of any binding
+[prefixes]
+{}
+
+# Where a command sends a bare number (or #number), with {{}} replaced by it:
+# with gh below, "gh 1234" opens issue 1234; other arguments work as usual
+[numbers]
+{}
+
+# Keyword commands, e.g. from `bunnylol import` of DuckDuckGo bangs, browser
+# bookmarks, or a keyword,url CSV: "w rust" opens the URL with {{}} replaced by
+# the arguments, and a bare "w" opens the site
+[keywords]
+{}
+
+# Typo correction for unrecognized commands
+# autocorrect_distance: run a command at most this many typos from exactly one
+#   binding or alias (e.g. 1 turns "gihub" into "github"); 0 shows "did you mean"
+[fuzzy]
+autocorrect_distance = {}
+
+# The gh command
+# org: limit gh prs, gh issues, gh review, and gh notifications to one
+#   organization
+[github]
+{}
+
+# The gl command
+# base_url: a self-hosted GitLab to use instead of gitlab.com
+[gitlab]
+{}
+
+# The jira command
+# base_url: your Jira site
+# board: id of the board "jira board" opens (the number after boards/ in its URL)
+[jira]
+{}
+{}
+
+# The notion command
+# domain: your workspace's name after notion.so/ in its URL
+[notion]
+{}
+
+# The figma command
+# team: id of the team to search (the number after team/ in its URL)
+[figma]
+{}
+
+# Meetings for the zoom command to join by name, e.g. "zoom standup": a meeting
+# ID with an optional passcode after it, or a join link
+[zoom.meetings]
+{}
+
+# The Google commands (gmail, cal, drive, docs, gsheets, gslides, photos)
+# account: which signed-in Google account to use, the N in /u/N URLs
+[google]
+{}
+
+# The gmail command
+# account: which signed-in Google account to use, the N in mail.google.com/mail/u/N;
+# overrides [google] account
+[gmail]
+{}
+
+# The tr command's languages when a query doesn't name them, as codes like "de"
+# source: "auto" (default) detects the language
+[translate]
+source = {}
+target = {}
+
+# Addresses "home" and "work" stand for in gmaps directions, e.g. "gm home to airport"
+[maps]
+{}
+{}
+
+# The outlook, onedrive, and o365 commands
+# tenant: your organization's Microsoft 365 domain; personal account pages when unset
+# sharepoint: your organization's OneDrive host, for onedrive's work files
+[microsoft]
+{}
+{}
+
+# The aws command
+# region: where console pages open when a query doesn't name a region
+# sso_start_url: your SSO access portal, opened by a bare "aws"
+[aws]
+{}
+{}
+
+# The k8s command
+# dashboard: your cluster's Kubernetes Dashboard, opened by "k8s dash [namespace]"
+[k8s]
+{}
+
+# The tf command
+# provider: where resource types without a known provider prefix come from,
+# a HashiCorp provider by name ("aws") or any other as "namespace/name"
+[terraform]
+{}
+
+# Fallback chain for queries that match no command, tried in order before
+# default_search. Each step only handles the listed commands (all if omitted):
+#   type = "url": a URL with {{}} for the query, e.g. a company bunnylol
+#   type = "upstream": ask another bunnylol's resolve API (url = its base URL),
+#     used when one of its commands matches (needs the "federation" feature);
+#     optional timeout_ms (default 500) and cache_seconds (default 300)
+#   type = "bang": the query as a DuckDuckGo bang (imdb dune → !imdb dune)
+#   type = "search": a search engine, default_search unless engine is set
+{}
+
+# Hooks run in order on every query before it's resolved, and on every URL it
+# resolves to:
+#   type = "normalize": trim the query and collapse repeated spaces
+#   type = "replace": replace find with replace in the query, e.g. a $me macro
+#   type = "strip_params": drop tracking parameters from URLs; params defaults
+#     to utm_*, fbclid, gclid, and friends (a trailing * matches a prefix)
+#   type = "force_https": open http:// URLs over HTTPS, except on localhost
+{}
+
+# Command plugins: shared libraries exporting bunnylol_plugin_v1, loaded at
+# startup (needs the "plugins" feature), and sandboxed .wasm modules, reloaded
+# when they change (needs the "wasm-plugins" feature)
+[plugins]
+{}
+
+# Commands written as Rhai scripts (needs the "scripting" feature). The script
+# gets `command`, `args`, and `query`, and evaluates to the URL; the path is
+# relative to this file. Optional: aliases, description, example
+{}
+
 # Command history settings
+# backend: "file" (default), "sqlite", or "redis" (shared across replicas)
+# ip_mode: how client IPs are stored: "full" (default), "truncated", "hashed", "none"
 [history]
 enabled = {}
 max_entries = {}
+{}
+backend = "{}"
+{}
+ip_mode = "{}"
 
 # Server configuration (for bunnylol serve)
 # server_display_url: Public-facing URL shown in the bindings page
@@ -388,32 +1325,112 @@ max_entries = {}
 #     - "https://bunny.example.com" → used as-is
 #     - "http://bunny.local" → used as-is
 #   If not set, defaults to http://localhost:{{port}}
+# identity_header: Header with the logged-in user from an auth proxy; history
+#   is then kept per user instead of per IP (only set behind such a proxy)
+# log_file: Where the Windows service and `bunnylol serve --daemon` write the
+#   server's output
 [server]
 port = {}
 address = "{}"
 log_level = "{}"
 {}
+{}
+{}
 "#,
             browser_line,
             self.default_search,
             self.stock_provider,
+            self.people_source,
+            self.case_insensitive,
+            browsers_content,
             aliases_content,
+            prefixes_content,
+            numbers_content,
+            keywords_content,
+            self.fuzzy.autocorrect_distance,
+            github_org_line,
+            gitlab_base_url_line,
+            jira_base_url_line,
+            jira_board_line,
+            notion_domain_line,
+            figma_team_line,
+            zoom_meetings_content,
+            google_account_line,
+            gmail_account_line,
+            toml::Value::String(self.translate.source.clone()),
+            toml::Value::String(self.translate.target.clone()),
+            maps_home_line,
+            maps_work_line,
+            microsoft_tenant_line,
+            microsoft_sharepoint_line,
+            aws_region_line,
+            aws_sso_start_url_line,
+            k8s_dashboard_line,
+            terraform_provider_line,
+            fallback_content,
+            middleware_content,
+            plugins_dir_line,
+            commands_content,
             self.history.enabled,
             self.history.max_entries,
+            retention_days_line,
+            self.history.backend,
+            redis_url_line,
+            self.history.ip_mode,
             self.server.port,
             self.server.address,
             self.server.log_level,
             server_display_url_line,
+            identity_header_line,
+            log_file_line,
         )
     }
 
     /// Resolve a command, checking aliases first
-    /// Returns the resolved command (either from alias or original)
-    pub fn resolve_command(&self, command: &str) -> String {
-        self.aliases
-            .get(command)
-            .cloned()
-            .unwrap_or_else(|| command.to_string())
+    /// Returns the resolved command (either from alias or original), with its
+    /// command lowercased when only that spelling matches and
+    /// `case_insensitive` is on
+    pub fn resolve_command<'a>(&'a self, command: &'a str) -> Cow<'a, str> {
+        if let Some(target) = self.aliases.get(command) {
+            return Cow::Borrowed(target);
+        }
+        if let Some(lowered) = self.lowercase_command(command) {
+            return match self.aliases.get(&lowered) {
+                Some(target) => Cow::Borrowed(target),
+                None => Cow::Owned(lowered),
+            };
+        }
+        Cow::Borrowed(command)
+    }
+
+    /// `query` with its command lowercased (`GH rust` → `gh rust`), when
+    /// `case_insensitive` is on and it matches a command or alias only that way
+    pub fn lowercase_command(&self, query: &str) -> Option<String> {
+        let command = crate::utils::get_command_from_query_string(query);
+        if !self.case_insensitive
+            || !command.chars().any(char::is_uppercase)
+            || self.aliases.contains_key(query)
+            || crate::BunnylolCommandRegistry::has_command(command)
+        {
+            return None;
+        }
+        let lowered = command.to_lowercase();
+        let query = format!("{}{}", lowered, &query[command.len()..]);
+        (crate::BunnylolCommandRegistry::has_command(&lowered) || self.aliases.contains_key(&query))
+            .then_some(query)
+    }
+
+    /// Look up the current value of a config key by dotted path (e.g. "server.port")
+    /// Returns None if the key is unset or doesn't exist
+    pub fn setting_value(&self, key: &str) -> Option<String> {
+        let mut value = toml::Value::try_from(self).ok()?;
+        for part in key.split('.') {
+            value = value.get(part)?.clone();
+        }
+        match value {
+            toml::Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        }
     }
 
     /// Get the search engine URL for a query
@@ -423,6 +1440,7 @@ log_level = "{}"
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
 
@@ -432,13 +1450,43 @@ mod tests {
         assert_eq!(config.browser, None);
         assert_eq!(config.default_search, "google");
         assert_eq!(config.stock_provider, "yahoo");
+        assert_eq!(config.people_source, "linkedin");
+        assert!(config.case_insensitive);
         assert!(config.aliases.is_empty());
+        assert!(config.prefixes.is_empty());
+        assert_eq!(config.fuzzy.autocorrect_distance, 0);
+        assert_eq!(config.github.org, None);
+        assert_eq!(config.gitlab.base_url, None);
+        assert_eq!(config.jira.base_url, None);
+        assert_eq!(config.jira.board, None);
+        assert_eq!(config.notion.domain, None);
+        assert_eq!(config.figma.team, None);
+        assert!(config.zoom.meetings.is_empty());
+        assert_eq!(config.google.account, None);
+        assert_eq!(config.gmail.account, None);
+        assert_eq!(config.translate.source, "auto");
+        assert_eq!(config.translate.target, "en");
+        assert_eq!(config.maps.home, None);
+        assert_eq!(config.maps.work, None);
+        assert_eq!(config.microsoft.tenant, None);
+        assert_eq!(config.microsoft.sharepoint, None);
+        assert_eq!(config.aws.region, None);
+        assert_eq!(config.aws.sso_start_url, None);
+        assert_eq!(config.k8s.dashboard, None);
+        assert_eq!(config.terraform.provider, None);
+        assert!(config.fallback.is_empty());
+        assert_eq!(config.plugins.dir, None);
+        assert!(config.commands.is_empty());
         assert!(config.history.enabled);
         assert_eq!(config.history.max_entries, 1000);
+        assert_eq!(config.history.backend, "file");
+        assert_eq!(config.history.retention_days, None);
+        assert_eq!(config.history.ip_mode, "full");
         assert_eq!(config.server.port, 8000);
         assert_eq!(config.server.address, "127.0.0.1");
         assert_eq!(config.server.log_level, "normal");
         assert_eq!(config.server.server_display_url, None);
+        assert_eq!(config.server.identity_header, None);
     }
 
     #[test]
@@ -453,6 +1501,27 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_command_ignores_case() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mycompany".to_string());
+        assert!(config.case_insensitive);
+
+        // Only the command is lowercased
+        assert_eq!(config.resolve_command("GH Rust-Lang"), "gh Rust-Lang");
+        assert_eq!(config.resolve_command("WORK"), "gh mycompany");
+        // Prefixes and unknown words are left alone
+        assert_eq!(config.resolve_command("$META"), "$META");
+        assert_eq!(config.resolve_command("Weather today"), "Weather today");
+
+        config.case_insensitive = false;
+        assert_eq!(config.resolve_command("GH Rust-Lang"), "GH Rust-Lang");
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
     fn test_resolved_alias_produces_correct_redirect() {
         let mut config = BunnylolConfig::default();
         config
@@ -463,8 +1532,8 @@ mod tests {
         let command = crate::utils::get_command_from_query_string(&resolved);
         let url = crate::BunnylolCommandRegistry::process_command(command, &resolved);
         assert_eq!(
-            url,
-            "https://github.com/search?q=mycompany&type=repositories"
+            url.as_deref(),
+            Ok("https://github.com/search?q=mycompany&type=repositories")
         );
     }
 
@@ -476,6 +1545,21 @@ mod tests {
         assert!(url.starts_with("https://kagi.com/search?q="));
     }
 
+    #[test]
+    fn test_setting_value() {
+        let config = BunnylolConfig::default();
+        assert_eq!(
+            config.setting_value("stock_provider"),
+            Some("yahoo".to_string())
+        );
+        assert_eq!(
+            config.setting_value("server.port"),
+            Some("8000".to_string())
+        );
+        assert_eq!(config.setting_value("browser"), None);
+        assert_eq!(config.setting_value("does.not.exist"), None);
+    }
+
     #[test]
     fn test_server_config_defaults() {
         let config = ServerConfig::default();
@@ -498,6 +1582,9 @@ mod tests {
             [history]
             enabled = false
             max_entries = 500
+            retention_days = 30
+            backend = "sqlite"
+            ip_mode = "hashed"
 
             [server]
             port = 9000
@@ -518,11 +1605,407 @@ mod tests {
         );
         assert!(!config.history.enabled);
         assert_eq!(config.history.max_entries, 500);
+        assert_eq!(config.history.backend, "sqlite");
+        assert_eq!(config.history.retention_days, Some(30));
+        assert_eq!(config.history.ip_mode, "hashed");
         assert_eq!(config.server.port, 9000);
         assert_eq!(config.server.address, "0.0.0.0");
         assert_eq!(config.server.log_level, "debug");
     }
 
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_prefixes_round_trip() {
+        let toml_str = r##"
+            [prefixes]
+            "#" = "https://jira.example.com/browse/{}"
+            "~" = "http://{}.corp.example.com/"
+        "##;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.prefixes.get("#"),
+            Some(&"https://jira.example.com/browse/{}".to_string())
+        );
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.prefixes, config.prefixes);
+        assert!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .prefixes
+                .is_empty()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_numbers_round_trip() {
+        let toml_str = r#"
+            [numbers]
+            gh = "https://github.com/facebook/react/issues/{}"
+            jira = "https://jira.example.com/browse/PROJ-{}"
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.numbers.len(), 2);
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.numbers, config.numbers);
+        assert!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .numbers
+                .is_empty()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_keywords_round_trip() {
+        let toml_str = r#"
+            [keywords]
+            w = "https://en.wikipedia.org/w/index.php?search={}"
+            "r/" = "https://reddit.com/r/{}"
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.keywords.len(), 2);
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.keywords, config.keywords);
+        assert!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .keywords
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_github_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[github]\norg = \"rust-lang\"\n").unwrap();
+        assert_eq!(config.github.org.as_deref(), Some("rust-lang"));
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.github.org.as_deref(), Some("rust-lang"));
+        assert_eq!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .github
+                .org,
+            None
+        );
+    }
+
+    #[test]
+    fn test_gitlab_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[gitlab]\nbase_url = \"https://gitlab.example.com\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.gitlab.base_url.as_deref(),
+            Some("https://gitlab.example.com")
+        );
+    }
+
+    #[test]
+    fn test_jira_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[jira]\nbase_url = \"https://example.atlassian.net\"\nboard = 12\n")
+                .unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.jira.base_url.as_deref(),
+            Some("https://example.atlassian.net")
+        );
+        assert_eq!(written.jira.board, Some(12));
+    }
+
+    #[test]
+    fn test_notion_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[notion]\ndomain = \"acme\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.notion.domain.as_deref(), Some("acme"));
+    }
+
+    #[test]
+    fn test_figma_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[figma]\nteam = \"1234567890\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.figma.team.as_deref(), Some("1234567890"));
+    }
+
+    #[test]
+    fn test_zoom_round_trip() {
+        let config: BunnylolConfig = toml::from_str(
+            "[zoom.meetings]\nstandup = \"1234567890 secret\"\n\"all hands\" = \"https://zoom.us/j/987654321\"\n",
+        )
+        .unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.zoom.meetings, config.zoom.meetings);
+        assert_eq!(written.zoom.meetings.len(), 2);
+    }
+
+    #[test]
+    fn test_google_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[google]\naccount = 2\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.google.account, Some(2));
+    }
+
+    #[test]
+    fn test_gmail_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[gmail]\naccount = 1\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.gmail.account, Some(1));
+    }
+
+    #[test]
+    fn test_translate_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[translate]\ntarget = \"de\"\n").unwrap();
+        assert_eq!(config.translate.source, "auto");
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.translate.source, "auto");
+        assert_eq!(written.translate.target, "de");
+    }
+
+    #[test]
+    fn test_maps_round_trip() {
+        let config: BunnylolConfig = toml::from_str("[maps]\nhome = \"1 Main St\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.maps.home.as_deref(), Some("1 Main St"));
+        assert_eq!(written.maps.work, None);
+    }
+
+    #[test]
+    fn test_microsoft_round_trip() {
+        let config: BunnylolConfig = toml::from_str(
+            "[microsoft]\ntenant = \"contoso.onmicrosoft.com\"\nsharepoint = \"https://contoso-my.sharepoint.com\"\n",
+        )
+        .unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.microsoft.tenant.as_deref(),
+            Some("contoso.onmicrosoft.com")
+        );
+        assert_eq!(
+            written.microsoft.sharepoint.as_deref(),
+            Some("https://contoso-my.sharepoint.com")
+        );
+    }
+
+    #[test]
+    fn test_aws_round_trip() {
+        let config: BunnylolConfig = toml::from_str(
+            "[aws]\nregion = \"eu-west-1\"\nsso_start_url = \"https://myorg.awsapps.com/start\"\n",
+        )
+        .unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.aws.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(
+            written.aws.sso_start_url.as_deref(),
+            Some("https://myorg.awsapps.com/start")
+        );
+    }
+
+    #[test]
+    fn test_k8s_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[k8s]\ndashboard = \"https://dashboard.example.com\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.k8s.dashboard.as_deref(),
+            Some("https://dashboard.example.com")
+        );
+    }
+
+    #[test]
+    fn test_terraform_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[terraform]\nprovider = \"cloudflare/cloudflare\"\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.terraform.provider.as_deref(),
+            Some("cloudflare/cloudflare")
+        );
+    }
+
+    #[test]
+    fn test_redis_url_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[history]\nredis_url = 'redis://:p\"a\\ss@127.0.0.1:6379/'\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.history.redis_url.as_deref(),
+            Some("redis://:p\"a\\ss@127.0.0.1:6379/")
+        );
+    }
+
+    #[test]
+    fn test_identity_header_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[server]\nidentity_header = 'X-\"User\"\\'\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.server.identity_header.as_deref(),
+            Some("X-\"User\"\\")
+        );
+    }
+
+    #[test]
+    fn test_plugins_round_trip() {
+        let config: BunnylolConfig =
+            toml::from_str("[plugins]\ndir = 'C:\\Users\\me\\plugins'\n").unwrap();
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(
+            written.plugins.dir.as_deref(),
+            Some("C:\\Users\\me\\plugins")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_browsers_round_trip() {
+        let toml_str = r#"
+            browser = "safari"
+
+            [browsers]
+            yt = "firefox"
+            gh = { browser = "chrome", profile = "Profile 1" }
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.browsers["yt"],
+            BrowserChoice::Browser("firefox".to_string())
+        );
+        assert_eq!(config.browsers["gh"].browser(), "chrome");
+        assert_eq!(config.browsers["gh"].profile(), Some("Profile 1"));
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.browsers, config.browsers);
+        assert!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .browsers
+                .is_empty()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_fallback_round_trip() {
+        let toml_str = r#"
+            [[fallback]]
+            type = "url"
+            url = "https://bunny.corp.example.com/?cmd={}"
+            commands = ["jira"]
+
+            [[fallback]]
+            type = "upstream"
+            url = "https://bunny.corp.example.com"
+
+            [[fallback]]
+            type = "search"
+            engine = "ddg"
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.fallback,
+            vec![
+                FallbackStep::Url {
+                    url: "https://bunny.corp.example.com/?cmd={}".to_string(),
+                    commands: vec!["jira".to_string()],
+                },
+                FallbackStep::Upstream {
+                    url: "https://bunny.corp.example.com".to_string(),
+                    timeout_ms: 500,
+                    cache_seconds: 300,
+                    commands: Vec::new(),
+                },
+                FallbackStep::Search {
+                    engine: Some("ddg".to_string()),
+                    commands: Vec::new(),
+                },
+            ]
+        );
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.fallback, config.fallback);
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_middleware_round_trip() {
+        let toml_str = r#"
+            [[middleware]]
+            type = "normalize"
+
+            [[middleware]]
+            type = "replace"
+            find = "$me"
+            replace = "octocat"
+
+            [[middleware]]
+            type = "strip_params"
+
+            [[middleware]]
+            type = "force_https"
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.middleware,
+            vec![
+                MiddlewareStep::Normalize,
+                MiddlewareStep::Replace {
+                    find: "$me".to_string(),
+                    replace: "octocat".to_string(),
+                },
+                MiddlewareStep::StripParams {
+                    params: default_tracking_params(),
+                },
+                MiddlewareStep::ForceHttps,
+            ]
+        );
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.middleware, config.middleware);
+        assert!(
+            toml::from_str::<BunnylolConfig>(&BunnylolConfig::default().to_toml_with_comments())
+                .unwrap()
+                .middleware
+                .is_empty()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "cli")]
+    fn test_script_commands_round_trip() {
+        let toml_str = r#"
+            [commands.jira]
+            script = "scripts/jira.rhai"
+            aliases = ["j"]
+
+            [commands.wiki]
+            script = "/opt/bunnylol/wiki.rhai"
+            description = "Search the wiki"
+        "#;
+
+        let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.commands["jira"].script, "scripts/jira.rhai");
+        assert_eq!(config.commands["jira"].aliases, vec!["j".to_string()]);
+        assert_eq!(
+            config.commands["wiki"].description.as_deref(),
+            Some("Search the wiki")
+        );
+
+        let written: BunnylolConfig = toml::from_str(&config.to_toml_with_comments()).unwrap();
+        assert_eq!(written.commands, config.commands);
+    }
+
     #[test]
     fn test_server_display_url_defaults() {
         let config = ServerConfig::default();
@@ -611,6 +2094,7 @@ mod tests {
             address = "0.0.0.0"
             log_level = "normal"
             server_display_url = "bunny.example.com"
+            identity_header = "X-Forwarded-User"
         "#;
 
         let config: BunnylolConfig = toml::from_str(toml_str).unwrap();
@@ -618,6 +2102,10 @@ mod tests {
             config.server.server_display_url,
             Some("bunny.example.com".to_string())
         );
+        assert_eq!(
+            config.server.identity_header,
+            Some("X-Forwarded-User".to_string())
+        );
         assert_eq!(config.server.get_display_url(), "https://bunny.example.com");
     }
 }
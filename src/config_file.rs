@@ -0,0 +1,312 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Editing the config file in place for CLI commands like `bunnylol alias add`,
+//! `bunnylol import`, and `bunnylol config edit`
+//!
+//! Unlike [`BunnylolConfig::save`], which writes the file from scratch, edits
+//! here keep the user's comments, ordering, and formatting, and are only
+//! written once the result still loads as a config.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{DocumentMut, Item, Table, value};
+
+use crate::BunnylolCommandRegistry;
+use crate::config::BunnylolConfig;
+
+/// A config file's contents, edited as a document
+pub struct ConfigFile {
+    path: PathBuf,
+    document: DocumentMut,
+}
+
+impl ConfigFile {
+    /// Open the config file `BunnylolConfig::save` would write
+    pub fn open_for_writing() -> Result<Self, String> {
+        let path = BunnylolConfig::get_config_path_for_writing()
+            .ok_or_else(|| "Could not determine a writable config path".to_string())?;
+        Self::open(path)
+    }
+
+    /// Open the config file at `path`, or start an empty one if it doesn't exist
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read config file {:?}: {}", path, e)),
+        };
+        let document = contents
+            .parse()
+            .map_err(|e| format!("Failed to parse config file {:?}: {}", path, e))?;
+        Ok(ConfigFile { path, document })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `[<section>]` table, created if the file has none
+    fn table_mut(&mut self, section: &str) -> Result<&mut Table, String> {
+        self.document
+            .entry(section)
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| format!("[{}] in the config file is not a table", section))
+    }
+
+    /// Point alias `name` at `target`, returning what it pointed at before
+    pub fn set_alias(&mut self, name: &str, target: &str) -> Result<Option<String>, String> {
+        self.set_entry("aliases", name, target)
+    }
+
+    /// Point keyword `name` at `url`, returning what it pointed at before
+    pub fn set_keyword(&mut self, name: &str, url: &str) -> Result<Option<String>, String> {
+        self.set_entry("keywords", name, url)
+    }
+
+    /// Set `name` in `[<section>]`, returning its previous value
+    fn set_entry(
+        &mut self,
+        section: &str,
+        name: &str,
+        target: &str,
+    ) -> Result<Option<String>, String> {
+        let table = self.table_mut(section)?;
+        match table.get_mut(name).and_then(Item::as_value_mut) {
+            Some(existing) => {
+                // Keep the comment after the old value
+                let previous = existing.as_str().map(str::to_string);
+                let decor = existing.decor().clone();
+                *existing = target.into();
+                *existing.decor_mut() = decor;
+                Ok(previous)
+            }
+            None => {
+                table.insert(name, value(target));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove alias `name`, returning what it pointed at
+    pub fn remove_alias(&mut self, name: &str) -> Option<String> {
+        let removed = self
+            .document
+            .get_mut("aliases")?
+            .as_table_mut()?
+            .remove(name)?;
+        removed.as_str().map(str::to_string)
+    }
+
+    /// Write the file, unless the edits would leave a config that doesn't load
+    pub fn save(&self) -> Result<(), String> {
+        let contents = self.document.to_string();
+        validate(&contents)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        fs::write(&self.path, contents).map_err(|e| format!("Failed to write config file: {}", e))
+    }
+}
+
+/// Check that `contents` loads as a config
+pub fn validate(contents: &str) -> Result<BunnylolConfig, String> {
+    toml::from_str(contents).map_err(|e| format!("Refusing to write an invalid config: {}", e))
+}
+
+/// Where `replace` keeps the file it replaced
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Replace the config file at `path` with `contents` if they load, keeping the
+/// previous file at `backup_path(path)`; returns the backup's path when there
+/// was a file to back up
+///
+/// The new file is written beside the old one and renamed over it, so a
+/// server reading the config never sees half of it.
+pub fn replace(path: &Path, contents: &str) -> Result<Option<PathBuf>, String> {
+    validate(contents)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let backup = if path.exists() {
+        let backup = backup_path(path);
+        fs::copy(path, &backup)
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        Some(backup)
+    } else {
+        None
+    };
+    let mut staged = path.as_os_str().to_os_string();
+    staged.push(".tmp");
+    let staged = PathBuf::from(staged);
+    fs::write(&staged, contents).map_err(|e| format!("Failed to write config file: {}", e))?;
+    fs::rename(&staged, path).map_err(|e| {
+        let _ = fs::remove_file(&staged);
+        format!("Failed to replace {}: {}", path.display(), e)
+    })?;
+    Ok(backup)
+}
+
+/// Why `name` can't be an alias for `target`, if it can't
+///
+/// An alias named after a built-in binding would never be reached, since
+/// built-in commands are matched first.
+pub fn check_alias(name: &str, target: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Alias name is required".to_string());
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err("Alias names cannot contain spaces".to_string());
+    }
+    if target.trim().is_empty() {
+        return Err("Alias target is required".to_string());
+    }
+    if BunnylolCommandRegistry::has_command(name)
+        || BunnylolCommandRegistry::has_command(&name.to_lowercase())
+    {
+        return Err(format!(
+            "'{}' is already a built-in command; pick another name",
+            name
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"# My bunnylol config
+default_search = "ddg"
+
+[aliases]
+# Work repos
+work = "gh mbinns"  # the main one
+"#;
+
+    fn config_file(contents: &str) -> ConfigFile {
+        ConfigFile {
+            path: PathBuf::from("config.toml"),
+            document: contents.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_set_alias_keeps_comments() {
+        let mut file = config_file(CONFIG);
+        assert_eq!(file.set_alias("docs", "gh mbinns/docs"), Ok(None));
+        assert_eq!(
+            file.set_alias("work", "gh octocat"),
+            Ok(Some("gh mbinns".to_string()))
+        );
+        let contents = file.document.to_string();
+        assert!(
+            contents.starts_with("# My bunnylol config\n"),
+            "{}",
+            contents
+        );
+        assert!(contents.contains("# Work repos\nwork = \"gh octocat\"  # the main one\n"));
+        assert!(contents.contains("docs = \"gh mbinns/docs\""));
+
+        let config: BunnylolConfig = toml::from_str(&contents).unwrap();
+        assert_eq!(config.aliases["docs"], "gh mbinns/docs");
+        assert_eq!(config.default_search, "ddg");
+    }
+
+    #[test]
+    fn test_set_alias_adds_aliases_table() {
+        let mut file = config_file("browser = \"firefox\"\n");
+        file.set_alias("work", "gh mbinns").unwrap();
+        assert_eq!(
+            file.document.to_string(),
+            "browser = \"firefox\"\n\n[aliases]\nwork = \"gh mbinns\"\n"
+        );
+    }
+
+    #[test]
+    fn test_set_keyword_quotes_keys() {
+        let mut file = config_file(CONFIG);
+        file.set_keyword("r/", "https://reddit.com/r/{}").unwrap();
+        let config: BunnylolConfig = toml::from_str(&file.document.to_string()).unwrap();
+        assert_eq!(config.keywords["r/"], "https://reddit.com/r/{}");
+        assert_eq!(config.aliases["work"], "gh mbinns");
+    }
+
+    #[test]
+    fn test_remove_alias() {
+        let mut file = config_file(CONFIG);
+        assert_eq!(file.remove_alias("work").as_deref(), Some("gh mbinns"));
+        assert_eq!(file.remove_alias("work"), None);
+        assert!(!file.document.to_string().contains("work ="));
+        assert_eq!(config_file("").remove_alias("work"), None);
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let dir = std::env::temp_dir().join(format!("bunnylol-config-file-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let mut file = ConfigFile::open(path.clone()).unwrap();
+        file.set_alias("work", "gh mbinns").unwrap();
+        file.save().unwrap();
+
+        let mut file = ConfigFile::open(path.clone()).unwrap();
+        assert_eq!(file.remove_alias("work").as_deref(), Some("gh mbinns"));
+
+        // A config that wouldn't load is never written
+        fs::write(&path, "aliases = \"nope\"\n").unwrap();
+        assert!(ConfigFile::open(path.clone()).unwrap().save().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_replace_keeps_backup() {
+        let dir =
+            std::env::temp_dir().join(format!("bunnylol-config-replace-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        assert_eq!(replace(&path, "browser = \"firefox\"\n"), Ok(None));
+
+        let backup = replace(&path, "browser = \"chrome\"\n").unwrap();
+        assert_eq!(
+            backup.as_deref(),
+            Some(dir.join("config.toml.bak").as_path())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "browser = \"chrome\"\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("config.toml.bak")).unwrap(),
+            "browser = \"firefox\"\n"
+        );
+
+        // A broken config leaves both files alone
+        assert!(replace(&path, "aliases = \"nope\"\n").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "browser = \"chrome\"\n");
+        assert!(!dir.join("config.toml.tmp").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "google")]
+    fn test_check_alias() {
+        assert!(check_alias("work", "gh mbinns").is_ok());
+        assert!(check_alias("", "gh mbinns").is_err());
+        assert!(check_alias("my work", "gh mbinns").is_err());
+        assert!(check_alias("work", " ").is_err());
+        assert!(
+            check_alias("g", "gh mbinns")
+                .unwrap_err()
+                .contains("built-in")
+        );
+        assert!(check_alias("G", "gh mbinns").is_err());
+    }
+}
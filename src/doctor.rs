@@ -0,0 +1,710 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Checks behind `bunnylol doctor`, each reporting findings with a fix for
+//! anything that's wrong
+//!
+//! A finding is an error when something is broken (a config that doesn't
+//! parse, a template that can't produce a URL), and a warning when it works
+//! but likely isn't what was meant (an alias hiding a built-in command).
+
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::BunnylolCommandRegistry;
+use crate::config::{BunnylolConfig, FallbackStep};
+use crate::history::{self, SCHEMA_VERSION};
+use crate::utils::url_encoding::{TEMPLATE_PLACEHOLDER, expand_template};
+
+/// Search engines `default_search` knows; anything else searches Google
+const SEARCH_ENGINES: &[&str] = &["google", "ddg", "duckduckgo", "bing", "kagi"];
+
+/// Options for `[history] ip_mode`
+const IP_MODES: &[&str] = &["full", "truncated", "hashed", "none"];
+
+/// How long to wait for whatever holds the server's port to answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// The result of one check
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// The area checked, e.g. "config" or "history"
+    pub check: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// What to do about it, for warnings and errors
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Finding {
+            check,
+            severity: Severity::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Finding {
+            check,
+            severity: Severity::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(check: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Finding {
+            check,
+            severity: Severity::Error,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.severity {
+            Severity::Ok => "✓",
+            Severity::Warning => "⚠",
+            Severity::Error => "✗",
+        };
+        write!(f, "{} {:<10} {}", mark, self.check, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n  {:<10} → {}", "", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// The exit status for CI: 0 when every check passed, 1 with any error, and
+/// 2 with only warnings
+pub fn exit_code(findings: &[Finding]) -> i32 {
+    match findings.iter().map(|finding| finding.severity).max() {
+        Some(Severity::Error) => 1,
+        Some(Severity::Warning) => 2,
+        _ => 0,
+    }
+}
+
+/// Whether the config file at `path` parses, and whether it sets anything
+/// bunnylol ignores
+pub fn check_config_file(path: Option<&Path>) -> Vec<Finding> {
+    let Some(path) = path else {
+        return vec![Finding::ok("config", "no config file; using the defaults")];
+    };
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return vec![Finding::error(
+                "config",
+                format!("can't read {}: {}", path.display(), e),
+                "check the file's permissions",
+            )];
+        }
+    };
+    let config: BunnylolConfig = match toml::from_str(&contents) {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![Finding::error(
+                "config",
+                format!(
+                    "{} doesn't parse, so the defaults are used: {}",
+                    path.display(),
+                    e.message()
+                ),
+                "fix the file, or move it aside to start from a fresh default config",
+            )];
+        }
+    };
+
+    let mut findings = Vec::new();
+    let known = toml::Table::try_from(BunnylolConfig::default()).unwrap_or_default();
+    let mut unknown: Vec<String> = contents
+        .parse::<toml::Table>()
+        .map(|table| {
+            table
+                .keys()
+                .filter(|key| !is_known_key(&known, key))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    unknown.sort();
+    for key in unknown {
+        findings.push(Finding::warning(
+            "config",
+            format!("unknown key '{}' is ignored", key),
+            "check its spelling against the README's configuration example",
+        ));
+    }
+    findings.extend(check_values(&config));
+    if findings.is_empty() {
+        findings.push(Finding::ok("config", format!("{} parses", path.display())));
+    }
+    findings
+}
+
+/// Whether `key` is a top-level config key; ones left out of the serialized
+/// defaults when empty count too
+fn is_known_key(known: &toml::Table, key: &str) -> bool {
+    known.contains_key(key) || ["fallback", "middleware", "commands"].contains(&key)
+}
+
+/// Settings that parse but name something bunnylol doesn't know
+fn check_values(config: &BunnylolConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if !SEARCH_ENGINES.contains(&config.default_search.as_str()) {
+        findings.push(Finding::warning(
+            "config",
+            format!(
+                "default_search '{}' isn't a known engine, so Google is used",
+                config.default_search
+            ),
+            format!("use one of {}", SEARCH_ENGINES.join(", ")),
+        ));
+    }
+    if !crate::history_migrate::BACKENDS.contains(&config.history.backend.as_str()) {
+        findings.push(Finding::warning(
+            "config",
+            format!(
+                "[history] backend '{}' isn't a known backend, so the history file is used",
+                config.history.backend
+            ),
+            format!("use one of {}", crate::history_migrate::BACKENDS.join(", ")),
+        ));
+    }
+    let mut browsers: Vec<_> = config.browsers.iter().collect();
+    browsers.sort_by_key(|(binding, _)| *binding);
+    for (binding, choice) in browsers {
+        if let Err(e) = crate::browser::check_choice(choice) {
+            findings.push(Finding::warning(
+                "config",
+                format!("[browsers] {}: {}", binding, e),
+                "drop the profile, or pick one of those browsers",
+            ));
+        }
+    }
+    if !IP_MODES.contains(&config.history.ip_mode.as_str()) {
+        findings.push(Finding::warning(
+            "config",
+            format!(
+                "[history] ip_mode '{}' isn't a known mode",
+                config.history.ip_mode
+            ),
+            format!("use one of {}", IP_MODES.join(", ")),
+        ));
+    }
+    findings
+}
+
+/// Whether `bunnylol serve` could listen on `address`:`port`, or bunnylol
+/// already does
+pub fn check_port(address: &str, port: u16) -> Finding {
+    let error = match TcpListener::bind((address, port)) {
+        Ok(_) => {
+            return Finding::ok("port", format!("{}:{} is free", address, port));
+        }
+        Err(e) => e,
+    };
+    match error.kind() {
+        ErrorKind::AddrInUse if is_bunnylol(address, port) => Finding::ok(
+            "port",
+            format!("bunnylol is already serving on {}:{}", address, port),
+        ),
+        ErrorKind::AddrInUse => Finding::error(
+            "port",
+            format!("{}:{} is in use by another program", address, port),
+            "stop that program, or change [server] port (or pass --port to bunnylol serve)",
+        ),
+        ErrorKind::PermissionDenied => Finding::error(
+            "port",
+            format!("not allowed to listen on {}:{}", address, port),
+            "ports below 1024 need root; use a higher [server] port",
+        ),
+        _ => Finding::error(
+            "port",
+            format!("can't listen on {}:{}: {}", address, port, error),
+            "set [server] address to 127.0.0.1, 0.0.0.0, or an address of this machine",
+        ),
+    }
+}
+
+/// Whether a bunnylol server answers its health check at `address`:`port`
+pub fn is_bunnylol(address: &str, port: u16) -> bool {
+    // A server listening on every interface is reachable over loopback too
+    let host = match address {
+        "0.0.0.0" => "127.0.0.1",
+        "::" => "::1",
+        address => address,
+    };
+    let probe = || -> std::io::Result<String> {
+        let mut stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(PROBE_TIMEOUT))?;
+        write!(
+            stream,
+            "GET /health HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            host
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    };
+    probe().is_ok_and(|response| {
+        response.starts_with("HTTP/1.1 200") && response.trim_end().ends_with("\r\n\r\nok")
+    })
+}
+
+/// Whether the configured history backend opens and can be read
+pub fn check_history(config: &BunnylolConfig) -> Finding {
+    if !config.history.enabled {
+        return Finding::ok("history", "disabled");
+    }
+    let store = match history::open_store(config, &config.history.backend) {
+        Ok(store) => store,
+        Err(e) => {
+            return Finding::error(
+                "history",
+                e,
+                "fix the [history] settings, or set backend = \"file\"",
+            );
+        }
+    };
+    match store.schema_version() {
+        Ok(version) if version > SCHEMA_VERSION => {
+            return Finding::error(
+                "history",
+                history::newer_schema_error(version),
+                "upgrade bunnylol",
+            );
+        }
+        Err(e) => {
+            return Finding::error("history", e, "run bunnylol migrate to upgrade the history");
+        }
+        Ok(_) => {}
+    }
+    match store.read_all() {
+        Ok(entries) => Finding::ok(
+            "history",
+            format!(
+                "{} entries in the {} backend",
+                entries.len(),
+                config.history.backend
+            ),
+        ),
+        Err(e) => Finding::error(
+            "history",
+            e,
+            "check the history store is reachable and readable",
+        ),
+    }
+}
+
+/// Whether the system service is installed and running
+pub fn check_service() -> Finding {
+    use crate::server::service::{self, ServiceState};
+
+    match service::service_state() {
+        Ok(ServiceState::Running) => Finding::ok("service", "installed and running"),
+        Ok(ServiceState::Stopped) => Finding::warning(
+            "service",
+            "installed but not running",
+            "run bunnylol service start, or bunnylol service logs to see why it stopped",
+        ),
+        Ok(ServiceState::NotInstalled) => Finding::ok(
+            "service",
+            "not installed (bunnylol service install runs the server at boot)",
+        ),
+        Err(service::ServiceError::UnsupportedPlatform) => Finding::ok(
+            "service",
+            "not supported on this platform; run bunnylol serve or use Docker",
+        ),
+        Err(e) => Finding::warning(
+            "service",
+            format!("can't tell whether it's installed: {}", e),
+            if cfg!(windows) {
+                "check the Service Control Manager is reachable"
+            } else {
+                "check systemctl is available"
+            },
+        ),
+    }
+}
+
+/// Why plugins and scripted commands were skipped when they were loaded
+pub fn check_extensions(warnings: &[String]) -> Vec<Finding> {
+    if warnings.is_empty() {
+        return vec![Finding::ok("plugins", "every plugin and script loaded")];
+    }
+    warnings
+        .iter()
+        .map(|warning| {
+            Finding::error(
+                "plugins",
+                warning.clone(),
+                "fix or remove it; a binding another command has must be renamed",
+            )
+        })
+        .collect()
+}
+
+/// Bindings that collide: configured prefixes that are ignored, and aliases
+/// or keywords hiding a command
+pub fn check_bindings(config: &BunnylolConfig) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = BunnylolCommandRegistry::prefix_errors(config)
+        .into_iter()
+        .map(|error| {
+            Finding::error(
+                "bindings",
+                format!("ignoring [prefixes] entry: {}", error),
+                "pick a prefix no binding starts with",
+            )
+        })
+        .collect();
+
+    let mut aliases: Vec<&String> = config
+        .aliases
+        .keys()
+        .filter(|alias| BunnylolCommandRegistry::get_handler(alias).is_some())
+        .collect();
+    aliases.sort();
+    findings.extend(aliases.into_iter().map(|alias| {
+        Finding::warning(
+            "bindings",
+            format!("alias '{}' hides the built-in {} command", alias, alias),
+            "rename the alias, unless replacing the command is intended",
+        )
+    }));
+
+    let active: Vec<&str> = BunnylolCommandRegistry::active_keywords(config)
+        .map(|(keyword, _)| keyword)
+        .collect();
+    let mut keywords: Vec<&String> = config
+        .keywords
+        .keys()
+        .filter(|keyword| {
+            !active.contains(&keyword.as_str()) || config.aliases.contains_key(*keyword)
+        })
+        .collect();
+    keywords.sort();
+    findings.extend(keywords.into_iter().map(|keyword| {
+        Finding::warning(
+            "bindings",
+            format!(
+                "keyword '{}' is never reached: a command, alias, or prefix matches it first",
+                keyword
+            ),
+            "rename or remove the keyword",
+        )
+    }));
+
+    if findings.is_empty() {
+        findings.push(Finding::ok("bindings", "no collisions"));
+    }
+    findings
+}
+
+/// Configured URL templates that can't produce a working URL
+pub fn check_templates(config: &BunnylolConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut broken = |section: &str, name: &str, template: &str, needs_placeholder: bool| {
+        if let Some(problem) = template_problem(template, needs_placeholder) {
+            findings.push(Finding::error(
+                "templates",
+                format!("{} '{}' = \"{}\" {}", section, name, template, problem),
+                format!(
+                    "use an http(s) URL{}",
+                    if needs_placeholder {
+                        format!(" with {} where the text goes", TEMPLATE_PLACEHOLDER)
+                    } else {
+                        String::new()
+                    }
+                ),
+            ));
+        }
+    };
+
+    for (keyword, template) in sorted(&config.keywords) {
+        broken("[keywords]", keyword, template, false);
+    }
+    for (prefix, template) in sorted(&config.prefixes) {
+        broken("[prefixes]", prefix, template, true);
+    }
+    for (binding, template) in sorted(&config.numbers) {
+        broken("[numbers]", binding, template, true);
+    }
+    for step in &config.fallback {
+        if let FallbackStep::Url { url, .. } = step {
+            broken("[[fallback]]", "url", url, true);
+        }
+    }
+    if config.people_source.contains("://") {
+        broken("people_source", "URL", &config.people_source, true);
+    }
+    if let Some(base_url) = &config.gitlab.base_url {
+        broken("[gitlab]", "base_url", base_url, false);
+    }
+    if let Some(base_url) = &config.jira.base_url {
+        broken("[jira]", "base_url", base_url, false);
+    }
+    if let Some(sharepoint) = &config.microsoft.sharepoint {
+        broken("[microsoft]", "sharepoint", sharepoint, false);
+    }
+    if let Some(start_url) = &config.aws.sso_start_url {
+        broken("[aws]", "sso_start_url", start_url, false);
+    }
+    if let Some(dashboard) = &config.k8s.dashboard {
+        broken("[k8s]", "dashboard", dashboard, false);
+    }
+
+    let mut unused: Vec<&String> = config
+        .numbers
+        .keys()
+        .filter(|binding| !BunnylolCommandRegistry::has_command(binding))
+        .collect();
+    unused.sort();
+    findings.extend(unused.into_iter().map(|binding| {
+        Finding::warning(
+            "templates",
+            format!(
+                "[numbers] '{}' isn't a command binding, so it's never used",
+                binding
+            ),
+            "key it by one of the command's bindings, e.g. gh",
+        )
+    }));
+
+    if findings.is_empty() {
+        findings.push(Finding::ok("templates", "every URL template is valid"));
+    }
+    findings
+}
+
+/// A map's entries ordered by key, so findings come out the same every run
+fn sorted(map: &std::collections::HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut entries: Vec<(&String, &String)> = map.iter().collect();
+    entries.sort();
+    entries
+}
+
+/// What's wrong with a URL template, if anything
+fn template_problem(template: &str, needs_placeholder: bool) -> Option<&'static str> {
+    if needs_placeholder && !template.contains(TEMPLATE_PLACEHOLDER) {
+        return Some("has no {} for the text");
+    }
+    match url::Url::parse(&expand_template(template, "test")) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => None,
+        _ => Some("isn't an http(s) URL"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "dev")]
+    use crate::config::GitLabConfig;
+
+    fn config_file(contents: &str) -> Vec<Finding> {
+        let dir = std::env::temp_dir().join(format!(
+            "bunnylol-doctor-{}-{}",
+            std::process::id(),
+            contents.len()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, contents).unwrap();
+        let findings = check_config_file(Some(&path));
+        std::fs::remove_dir_all(&dir).unwrap();
+        findings
+    }
+
+    fn severities(findings: &[Finding]) -> Vec<Severity> {
+        findings.iter().map(|finding| finding.severity).collect()
+    }
+
+    #[test]
+    fn test_check_config_file() {
+        assert_eq!(severities(&check_config_file(None)), [Severity::Ok]);
+        assert_eq!(
+            severities(&config_file(
+                "default_search = \"ddg\"\n[aliases]\nwork = \"gh a\"\n"
+            )),
+            [Severity::Ok]
+        );
+
+        let findings = config_file("aliases = \"nope\"\n");
+        assert_eq!(severities(&findings), [Severity::Error]);
+        assert!(findings[0].message.contains("doesn't parse"));
+
+        let findings = config_file("default_search = \"yahoo\"\naliasses = {}\n");
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "unknown key 'aliasses' is ignored",
+                "default_search 'yahoo' isn't a known engine, so Google is used",
+            ]
+        );
+
+        let findings = config_file(
+            "[browsers]\nyt = \"firefox\"\ngh = { browser = \"lynx\", profile = \"work\" }\n",
+        );
+        assert_eq!(severities(&findings), [Severity::Warning]);
+        assert!(
+            findings[0]
+                .message
+                .starts_with("[browsers] gh: profiles aren't supported for 'lynx'")
+        );
+    }
+
+    #[test]
+    fn test_check_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let finding = check_port("127.0.0.1", port);
+        assert_eq!(finding.severity, Severity::Error);
+        assert!(finding.message.contains("in use"));
+
+        drop(listener);
+        assert_eq!(check_port("127.0.0.1", port).severity, Severity::Ok);
+    }
+
+    #[test]
+    fn test_check_port_finds_running_bunnylol() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\nok"
+                );
+            }
+        });
+        let finding = check_port("127.0.0.1", port);
+        assert_eq!(finding.severity, Severity::Ok);
+        assert!(finding.message.contains("already serving"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn test_check_bindings() {
+        let config = BunnylolConfig {
+            aliases: [("gh".to_string(), "ddg".to_string())].into(),
+            keywords: [
+                ("yt".to_string(), "https://example.com/{}".to_string()),
+                ("kb".to_string(), "https://kb.example.com/{}".to_string()),
+            ]
+            .into(),
+            prefixes: [("g".to_string(), "https://g.example.com/{}".to_string())].into(),
+            ..Default::default()
+        };
+        let findings = check_bindings(&config);
+        assert_eq!(
+            severities(&findings),
+            [Severity::Error, Severity::Warning, Severity::Warning]
+        );
+        assert!(findings[0].message.contains("prefix 'g'"));
+        assert!(findings[1].message.contains("alias 'gh'"));
+        assert!(findings[2].message.contains("keyword 'yt'"));
+
+        assert_eq!(
+            severities(&check_bindings(&BunnylolConfig::default())),
+            [Severity::Ok]
+        );
+        assert_eq!(
+            severities(&check_extensions(&["skipping plugin: x".to_string()])),
+            [Severity::Error]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_check_templates() {
+        let config = BunnylolConfig {
+            keywords: [
+                ("kb".to_string(), "https://kb.example.com/{}".to_string()),
+                ("bad".to_string(), "kb.example.com/{}".to_string()),
+            ]
+            .into(),
+            numbers: [
+                (
+                    "gh".to_string(),
+                    "https://github.com/a/b/issues".to_string(),
+                ),
+                ("nope".to_string(), "https://example.com/{}".to_string()),
+            ]
+            .into(),
+            gitlab: GitLabConfig {
+                base_url: Some("gitlab.example.com".to_string()),
+            },
+            ..Default::default()
+        };
+        let messages: Vec<String> = check_templates(&config)
+            .into_iter()
+            .map(|finding| finding.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "[keywords] 'bad' = \"kb.example.com/{}\" isn't an http(s) URL",
+                "[numbers] 'gh' = \"https://github.com/a/b/issues\" has no {} for the text",
+                "[gitlab] 'base_url' = \"gitlab.example.com\" isn't an http(s) URL",
+                "[numbers] 'nope' isn't a command binding, so it's never used",
+            ]
+        );
+        assert_eq!(
+            severities(&check_templates(&BunnylolConfig::default())),
+            [Severity::Ok]
+        );
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let ok = Finding::ok("config", "fine");
+        let warning = Finding::warning("config", "hmm", "fix");
+        let error = Finding::error("config", "bad", "fix");
+        assert_eq!(exit_code(std::slice::from_ref(&ok)), 0);
+        assert_eq!(exit_code(&[ok.clone(), warning.clone()]), 2);
+        assert_eq!(exit_code(&[warning, error, ok]), 1);
+    }
+
+    #[test]
+    fn test_finding_json() {
+        let json = serde_json::to_value(Finding::warning("history", "hmm", "fix it")).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "check": "history",
+                "severity": "warning",
+                "message": "hmm",
+                "fix": "fix it",
+            })
+        );
+        let json = serde_json::to_value(Finding::ok("config", "fine")).unwrap();
+        assert_eq!(json["severity"], "ok");
+        assert_eq!(json["fix"], serde_json::Value::Null);
+    }
+}
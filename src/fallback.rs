@@ -0,0 +1,199 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The fallback chain for queries that match no command: each `[[fallback]]`
+//! step in the config is tried in order, and the default search engine
+//! answers when none of them matches. An `upstream` step also passes when the
+//! other bunnylol has no command for the query.
+
+use crate::config::{BunnylolConfig, FallbackStep};
+use crate::utils;
+use crate::utils::url_encoding::expand_template;
+
+/// The URL an unmatched query goes to, and the step that produced it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FallbackMatch {
+    pub url: String,
+    /// Describes the matching step, for logs, e.g. `bang` or `search (ddg)`
+    pub step: String,
+}
+
+impl FallbackStep {
+    /// Whether this step handles queries starting with `command`
+    fn applies_to(&self, command: &str) -> bool {
+        let commands = match self {
+            FallbackStep::Url { commands, .. }
+            | FallbackStep::Upstream { commands, .. }
+            | FallbackStep::Bang { commands }
+            | FallbackStep::Search { commands, .. } => commands,
+        };
+        commands.is_empty() || commands.iter().any(|c| c == command)
+    }
+
+    /// The search engine a `search` step uses
+    fn engine<'a>(engine: &'a Option<String>, config: &'a BunnylolConfig) -> &'a str {
+        engine.as_deref().unwrap_or(&config.default_search)
+    }
+
+    /// This step's URL for `query`, or `None` to pass it to the next step
+    fn url(&self, query: &str, config: &BunnylolConfig) -> Option<String> {
+        match self {
+            FallbackStep::Url { url, .. } => Some(expand_template(url, query)),
+            FallbackStep::Upstream {
+                url,
+                timeout_ms,
+                cache_seconds,
+                ..
+            } => Self::upstream_url(url, query, *timeout_ms, *cache_seconds),
+            FallbackStep::Bang { .. } => {
+                Some(crate::commands::search_url("ddg", &format!("!{}", query)))
+            }
+            FallbackStep::Search { engine, .. } => Some(crate::commands::search_url(
+                Self::engine(engine, config),
+                query,
+            )),
+        }
+    }
+
+    #[cfg(feature = "federation")]
+    fn upstream_url(url: &str, query: &str, timeout_ms: u64, cache_seconds: u64) -> Option<String> {
+        crate::federation::resolve(
+            url,
+            query,
+            std::time::Duration::from_millis(timeout_ms),
+            std::time::Duration::from_secs(cache_seconds),
+        )
+    }
+
+    /// Without the `federation` feature an upstream step never answers
+    #[cfg(not(feature = "federation"))]
+    fn upstream_url(
+        _url: &str,
+        _query: &str,
+        _timeout_ms: u64,
+        _cache_seconds: u64,
+    ) -> Option<String> {
+        None
+    }
+
+    fn describe(&self, config: &BunnylolConfig) -> String {
+        match self {
+            FallbackStep::Url { url, .. } => format!("url ({})", url),
+            FallbackStep::Upstream { url, .. } => format!("upstream ({})", url),
+            FallbackStep::Bang { .. } => "bang".to_string(),
+            FallbackStep::Search { engine, .. } => {
+                format!("search ({})", Self::engine(engine, config))
+            }
+        }
+    }
+}
+
+/// Resolve a query that matched no command through the config's fallback
+/// chain
+pub fn resolve(config: &BunnylolConfig, query: &str) -> FallbackMatch {
+    let command = utils::get_command_from_query_string(query);
+    let default_search = FallbackStep::Search {
+        engine: None,
+        commands: Vec::new(),
+    };
+    config
+        .fallback
+        .iter()
+        .filter(|step| step.applies_to(command))
+        .chain(std::iter::once(&default_search))
+        .find_map(|step| {
+            step.url(query, config).map(|url| FallbackMatch {
+                url,
+                step: step.describe(config),
+            })
+        })
+        .expect("the default search always has a URL")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_steps(fallback: Vec<FallbackStep>) -> BunnylolConfig {
+        BunnylolConfig {
+            fallback,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_chain_uses_default_search() {
+        let config = BunnylolConfig {
+            default_search: "kagi".to_string(),
+            ..Default::default()
+        };
+        let found = resolve(&config, "weather tomorrow");
+        assert_eq!(found.url, config.get_search_url("weather tomorrow"));
+        assert_eq!(found.step, "search (kagi)");
+    }
+
+    #[test]
+    fn test_steps_are_tried_in_order() {
+        let config = config_with_steps(vec![
+            FallbackStep::Url {
+                url: "https://bunny.corp.example.com/?cmd={}".to_string(),
+                commands: vec!["jira".to_string(), "wiki".to_string()],
+            },
+            FallbackStep::Bang {
+                commands: vec!["imdb".to_string()],
+            },
+            FallbackStep::Search {
+                engine: Some("ddg".to_string()),
+                commands: Vec::new(),
+            },
+        ]);
+
+        let found = resolve(&config, "jira PROJ-1");
+        assert_eq!(
+            found.url,
+            "https://bunny.corp.example.com/?cmd=jira%20PROJ-1"
+        );
+        assert_eq!(found.step, "url (https://bunny.corp.example.com/?cmd={})");
+
+        let found = resolve(&config, "imdb dune");
+        assert_eq!(found.url, "https://duckduckgo.com/?q=!imdb%20dune");
+        assert_eq!(found.step, "bang");
+
+        let found = resolve(&config, "weather");
+        assert_eq!(found.url, "https://duckduckgo.com/?q=weather");
+        assert_eq!(found.step, "search (ddg)");
+    }
+
+    #[test]
+    fn test_upstream_without_answer_passes_to_next_step() {
+        // Nothing listens on a port that was just freed
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let config = config_with_steps(vec![
+            FallbackStep::Upstream {
+                url: upstream,
+                timeout_ms: 500,
+                cache_seconds: 0,
+                commands: Vec::new(),
+            },
+            FallbackStep::Bang {
+                commands: Vec::new(),
+            },
+        ]);
+        assert_eq!(resolve(&config, "imdb dune").step, "bang");
+    }
+
+    #[test]
+    fn test_unmatched_chain_falls_back_to_default_search() {
+        let config = config_with_steps(vec![FallbackStep::Bang {
+            commands: vec!["imdb".to_string()],
+        }]);
+        assert_eq!(resolve(&config, "weather").step, "search (google)");
+    }
+}
@@ -0,0 +1,253 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Asking another bunnylol where a query goes, for the `upstream` fallback
+//! step, so a personal instance can layer on top of a shared one
+//!
+//! The upstream's `/api/resolve` answer is used only when one of its own
+//! commands or aliases handled the query; when it would just search, the
+//! local fallback chain carries on. Answers are cached, and an upstream that
+//! can't be reached is skipped for a while instead of costing every query
+//! its timeout.
+//!
+//! Requests block, so async callers run `resolve` on a blocking thread.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::utils::url_builder::UrlBuilder;
+
+/// How long an upstream that couldn't be reached is skipped, at most
+const ERROR_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Cached answers kept at most; expired ones are dropped first when full
+const MAX_CACHE_ENTRIES: usize = 1000;
+
+/// The parts of the upstream's `resolver::Resolution` that matter here
+#[derive(Deserialize)]
+struct UpstreamResolution {
+    layer: String,
+    #[serde(default)]
+    urls: Vec<String>,
+}
+
+struct CacheEntry {
+    url: Option<String>,
+    expires: Instant,
+}
+
+/// Answers by (upstream, query)
+static CACHE: LazyLock<Mutex<HashMap<(String, String), CacheEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Upstreams that couldn't be reached, and until when they're skipped
+static DOWN_UNTIL: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The URL `upstream` resolves `query` to, if one of its commands handles it
+pub fn resolve(
+    upstream: &str,
+    query: &str,
+    timeout: Duration,
+    cache_ttl: Duration,
+) -> Option<String> {
+    let key = (upstream.to_string(), query.to_string());
+    if let Some(entry) = CACHE.lock().unwrap().get(&key)
+        && entry.expires > Instant::now()
+    {
+        return entry.url.clone();
+    }
+    if DOWN_UNTIL
+        .lock()
+        .unwrap()
+        .get(upstream)
+        .is_some_and(|until| *until > Instant::now())
+    {
+        return None;
+    }
+
+    let url = match fetch(upstream, query, timeout) {
+        Ok(body) => {
+            DOWN_UNTIL.lock().unwrap().remove(upstream);
+            parse_resolution(&body)
+        }
+        Err(e) => {
+            eprintln!("Warning: upstream {} failed: {}", upstream, e);
+            DOWN_UNTIL.lock().unwrap().insert(
+                upstream.to_string(),
+                Instant::now() + cache_ttl.min(ERROR_CACHE_TTL),
+            );
+            return None;
+        }
+    };
+
+    let mut cache = CACHE.lock().unwrap();
+    if cache.len() >= MAX_CACHE_ENTRIES {
+        let now = Instant::now();
+        cache.retain(|_, entry| entry.expires > now);
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            cache.clear();
+        }
+    }
+    cache.insert(
+        key,
+        CacheEntry {
+            url: url.clone(),
+            expires: Instant::now() + cache_ttl,
+        },
+    );
+    url
+}
+
+/// The upstream's resolve API URL for `query`
+fn resolve_api_url(upstream: &str, query: &str) -> String {
+    UrlBuilder::new(&format!("{}/api/resolve", upstream.trim_end_matches('/')))
+        .query("cmd", query)
+        .build()
+}
+
+fn fetch(upstream: &str, query: &str, timeout: Duration) -> Result<String, ureq::Error> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .build()
+        .into();
+    agent
+        .get(&resolve_api_url(upstream, query))
+        .call()?
+        .body_mut()
+        .read_to_string()
+}
+
+/// The URL in a resolve API answer, unless the upstream fell back to a
+/// search or rejected the query
+fn parse_resolution(body: &str) -> Option<String> {
+    let resolution: UpstreamResolution = serde_json::from_str(body).ok()?;
+    if resolution.layer == "fallback" {
+        return None;
+    }
+    resolution.urls.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_resolve_api_url() {
+        assert_eq!(
+            resolve_api_url("https://bunny.corp.example.com/", "jira PROJ-1"),
+            "https://bunny.corp.example.com/api/resolve?cmd=jira%20PROJ-1"
+        );
+    }
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(
+            parse_resolution(
+                r#"{"layer":"builtin","urls":["https://jira.example.com/browse/PROJ-1"]}"#
+            )
+            .as_deref(),
+            Some("https://jira.example.com/browse/PROJ-1")
+        );
+        assert_eq!(
+            parse_resolution(
+                r#"{"layer":"fallback","urls":["https://www.google.com/search?q=x"]}"#
+            ),
+            None
+        );
+        // The upstream's command rejected the arguments
+        assert_eq!(
+            parse_resolution(r#"{"layer":"builtin","urls":[],"error":"bad"}"#),
+            None
+        );
+        assert_eq!(parse_resolution("<html>"), None);
+    }
+
+    /// An upstream answering every request with `body`, counting requests
+    fn serve(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        serve_status("200 OK", body)
+    }
+
+    /// An upstream answering every request with `status` and `body`,
+    /// counting requests
+    fn serve_status(status: &'static str, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (upstream, requests)
+    }
+
+    #[test]
+    fn test_resolve_caches_answers() {
+        let (upstream, requests) =
+            serve(r#"{"layer":"alias","urls":["https://wiki.corp.example.com/"]}"#);
+        let timeout = Duration::from_secs(5);
+        let ttl = Duration::from_secs(60);
+        for _ in 0..2 {
+            assert_eq!(
+                resolve(&upstream, "wiki", timeout, ttl).as_deref(),
+                Some("https://wiki.corp.example.com/")
+            );
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A zero TTL asks again
+        resolve(&upstream, "wiki2", timeout, Duration::ZERO);
+        resolve(&upstream, "wiki2", timeout, Duration::ZERO);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_resolve_unreachable_upstream() {
+        // Nothing listens on a port that was just freed
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        assert_eq!(
+            resolve(
+                &upstream,
+                "wiki",
+                Duration::from_secs(1),
+                Duration::from_secs(60)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_skips_failing_upstream() {
+        let (upstream, requests) = serve_status("500 Internal Server Error", "{}");
+        let timeout = Duration::from_secs(5);
+        let ttl = Duration::from_secs(60);
+        assert_eq!(resolve(&upstream, "wiki", timeout, ttl), None);
+        // Other queries don't wait on it again
+        assert_eq!(resolve(&upstream, "jira PROJ-1", timeout, ttl), None);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
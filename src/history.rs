@@ -5,11 +5,36 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 
+use serde::Serialize;
+
 use crate::config::BunnylolConfig;
+use crate::history_stats::{self, CommandScore, FrecencyScore};
+
+/// Version of the history storage layout this build reads and writes.
+/// Stores written before the layout was versioned report 0.
+///
+/// - 0: `timestamp|user|command` entries
+/// - 1: entries may carry a resolution latency; unknown-command counts and
+///   frecency scores are stored alongside them
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Start of a history line that carries a resolution latency. Unmarked lines
+/// are always `timestamp|user|command`, so a command starting with digits and
+/// a `|` is never mistaken for a latency.
+const TIMED_LINE_PREFIX: &str = "v1|";
+
+/// Error for a store written by a newer bunnylol, which this build must not touch
+pub fn newer_schema_error(version: u32) -> String {
+    format!(
+        "History was written with schema v{}, but this bunnylol only understands up to v{}; upgrade bunnylol to use it",
+        version, SCHEMA_VERSION
+    )
+}
 
 /// Command history entry
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +42,8 @@ pub struct HistoryEntry {
     pub command: String,
     pub timestamp: String,
     pub user: String,
+    /// Time taken to resolve the command, in microseconds, when measured
+    pub latency_us: Option<u64>,
 }
 
 impl HistoryEntry {
@@ -33,45 +60,302 @@ impl HistoryEntry {
             command,
             timestamp,
             user,
+            latency_us: None,
         }
     }
 
+    /// Record how long the command took to resolve
+    pub fn with_latency(mut self, latency: std::time::Duration) -> Self {
+        self.latency_us = Some(u64::try_from(latency.as_micros()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// Whether `user` can be stored in a history line: it must not contain
+    /// the `|` separator or control characters such as newlines
+    pub fn is_storable_user(user: &str) -> bool {
+        !user.contains(|c: char| c == '|' || c.is_control())
+    }
+
     /// Parse a history entry from a line in the history file
-    /// Format: timestamp|user|command
+    /// Format: timestamp|user|command, or v1|timestamp|user|latency_us|command
+    /// when the resolution time was measured
     pub fn from_line(line: &str) -> Option<Self> {
-        let parts: Vec<&str> = line.splitn(3, '|').collect();
-        if parts.len() == 3 {
-            Some(Self {
+        if let Some(timed) = line.strip_prefix(TIMED_LINE_PREFIX) {
+            let parts: Vec<&str> = timed.splitn(4, '|').collect();
+            if parts.len() != 4 {
+                return None;
+            }
+            return Some(Self {
                 timestamp: parts[0].to_string(),
                 user: parts[1].to_string(),
-                command: parts[2].to_string(),
-            })
-        } else {
-            None
+                command: parts[3].to_string(),
+                latency_us: Some(parts[2].parse().ok()?),
+            });
+        }
+        let parts: Vec<&str> = line.splitn(3, '|').collect();
+        if parts.len() != 3 {
+            return None;
         }
+        Some(Self {
+            timestamp: parts[0].to_string(),
+            user: parts[1].to_string(),
+            command: parts[2].to_string(),
+            latency_us: None,
+        })
     }
 
     /// Convert entry to a line for the history file
-    /// Format: timestamp|user|command
+    /// Format: timestamp|user|command, or v1|timestamp|user|latency_us|command
     pub fn to_line(&self) -> String {
-        format!("{}|{}|{}", self.timestamp, self.user, self.command)
+        match self.latency_us {
+            Some(latency_us) => format!(
+                "{}{}|{}|{}|{}",
+                TIMED_LINE_PREFIX, self.timestamp, self.user, latency_us, self.command
+            ),
+            None => format!("{}|{}|{}", self.timestamp, self.user, self.command),
+        }
     }
 }
 
-/// Command history manager
-pub struct History {
-    path: PathBuf,
+/// How often a command token that matched no binding or alias was run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownCommand {
+    pub command: String,
+    pub count: u64,
+}
+
+/// Add the `added` counts to `counts`, keeping the `max_entries` most
+/// frequent, most frequent first (ties alphabetical)
+pub fn count_unknown(
+    counts: Vec<UnknownCommand>,
+    added: &[UnknownCommand],
     max_entries: usize,
+) -> Vec<UnknownCommand> {
+    let mut by_command: HashMap<String, u64> = counts
+        .into_iter()
+        .map(|unknown| (unknown.command, unknown.count))
+        .collect();
+    for unknown in added {
+        let count = by_command.entry(unknown.command.clone()).or_insert(0);
+        *count = count.saturating_add(unknown.count);
+    }
+    let mut counts: Vec<UnknownCommand> = by_command
+        .into_iter()
+        .map(|(command, count)| UnknownCommand { command, count })
+        .collect();
+    counts.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    counts.truncate(max_entries);
+    counts
 }
 
-impl History {
-    /// Create a new history manager
-    pub fn new(config: &BunnylolConfig) -> Option<Self> {
-        let path = BunnylolConfig::get_history_path()?;
-        Some(Self {
-            path,
-            max_entries: config.history.max_entries,
+/// Fold `entries` into per-(user, command) frecency scores, keeping the
+/// `max_entries` most recently used pairs
+pub fn bump_frecency(
+    scores: Vec<(String, FrecencyScore)>,
+    entries: &[HistoryEntry],
+    max_entries: usize,
+) -> Vec<(String, FrecencyScore)> {
+    let mut by_key: HashMap<(String, String), FrecencyScore> = scores
+        .into_iter()
+        .map(|(user, score)| ((user, score.command.clone()), score))
+        .collect();
+    for entry in entries {
+        let (Some(command), Ok(timestamp)) = (
+            history_stats::frecency_key(entry),
+            entry.timestamp.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        by_key
+            .entry((entry.user.clone(), command.to_string()))
+            .or_insert_with(|| FrecencyScore::new(command))
+            .bump(timestamp);
+    }
+    let mut scores: Vec<(String, FrecencyScore)> = by_key
+        .into_iter()
+        .map(|((user, _), score)| (user, score))
+        .collect();
+    scores.sort_by(|(a_user, a), (b_user, b)| {
+        b.last_used
+            .cmp(&a.last_used)
+            .then_with(|| a_user.cmp(b_user))
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    scores.truncate(max_entries);
+    scores
+}
+
+/// Storage backend for command history
+///
+/// Implementations must keep entries in insertion order; `History` handles
+/// everything on top of that (filtering, aggregation).
+pub trait HistoryStore: Send + Sync {
+    /// Append an entry, dropping the oldest entries beyond `max_entries`
+    fn append(&self, entry: &HistoryEntry, max_entries: usize) -> Result<(), String>;
+
+    /// Append several entries at once; stores should override this when they
+    /// can write a batch more cheaply than one entry at a time
+    fn append_batch(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
+        entries
+            .iter()
+            .try_for_each(|entry| self.append(entry, max_entries))
+    }
+
+    /// Read all entries, oldest first
+    fn read_all(&self) -> Result<Vec<HistoryEntry>, String>;
+
+    /// Delete all entries, including unknown-command counts and frecency
+    /// scores; returns how many entries were removed
+    fn clear(&self) -> Result<usize, String>;
+
+    /// Remove entries older than `cutoff` (seconds since the epoch, when given)
+    /// and all but the newest `max_entries`; returns how many were removed
+    fn prune(&self, cutoff: Option<u64>, max_entries: usize) -> Result<usize, String>;
+
+    /// Remove every entry and frecency score recorded for `user`; returns how
+    /// many entries were removed
+    fn remove_user(&self, user: &str) -> Result<usize, String>;
+
+    /// Remove the entries `filter` matches, leaving frecency scores and
+    /// unknown-command counts alone; returns how many were removed
+    fn remove_matching(&self, filter: &HistoryFilter) -> Result<usize, String>;
+
+    /// Update the per-(user, command) frecency scores with newly recorded
+    /// entries, keeping at most `max_entries` pairs
+    fn record_frecency(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String>;
+
+    /// Every stored frecency score for `user`, undecayed
+    fn frecency_for_user(&self, user: &str) -> Result<Vec<FrecencyScore>, String>;
+
+    /// The layout version the stored data was written with (see `SCHEMA_VERSION`)
+    fn schema_version(&self) -> Result<u32, String>;
+
+    /// Upgrade the stored data to `SCHEMA_VERSION` in place; callers must
+    /// check the store isn't newer first
+    fn migrate_schema(&self) -> Result<(), String>;
+
+    /// Count command tokens that matched no binding or alias, kept apart from
+    /// the entries and capped at the `max_entries` most frequent
+    fn record_unknown(&self, tokens: &[String], max_entries: usize) -> Result<(), String> {
+        let counts: Vec<UnknownCommand> = tokens
+            .iter()
+            .map(|token| UnknownCommand {
+                command: token.clone(),
+                count: 1,
+            })
+            .collect();
+        self.add_unknown_counts(&counts, max_entries)
+    }
+
+    /// Add each command's count to its unknown-command count, keeping the
+    /// `max_entries` most frequent
+    fn add_unknown_counts(
+        &self,
+        counts: &[UnknownCommand],
+        max_entries: usize,
+    ) -> Result<(), String>;
+
+    /// The `limit` most frequent unknown command tokens, most frequent first
+    fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String>;
+
+    /// The `limit` highest-frecency commands run at or after `since`; stores
+    /// that can aggregate without loading every entry should override this
+    fn top_commands(
+        &self,
+        since: Option<u64>,
+        now: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandScore>, String> {
+        let entries = self.read_all()?;
+        Ok(top_of(entries.iter(), since, now, limit))
+    }
+}
+
+/// Rank entries at or after `since` by frecency and keep the first `limit`
+pub fn top_of<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    since: Option<u64>,
+    now: u64,
+    limit: usize,
+) -> Vec<CommandScore> {
+    let recent = entries.into_iter().filter(|entry| {
+        since.is_none_or(|since| entry.timestamp.parse::<u64>().is_ok_and(|t| t >= since))
+    });
+    let mut ranked = history_stats::rank_commands(recent, now);
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Which entries `bunnylol history list`, `top`, and `clear` act on; the
+/// default matches every entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    /// Only entries at or after this time, in seconds since the epoch
+    pub since: Option<u64>,
+    /// Only entries run with this binding (the first word, e.g. "gh")
+    pub command: Option<String>,
+    /// Only entries recorded for this user, as stored (an IP after
+    /// `ip_mode`, or a logged-in user name)
+    pub user: Option<String>,
+}
+
+impl HistoryFilter {
+    /// Whether this filter matches every entry
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.since.is_none_or(|since| {
+            entry
+                .timestamp
+                .parse::<u64>()
+                .is_ok_and(|timestamp| timestamp >= since)
+        }) && self.command.as_deref().is_none_or(|command| {
+            entry
+                .command
+                .split_whitespace()
+                .next()
+                .is_some_and(|binding| binding.eq_ignore_ascii_case(command))
+        }) && self.user.as_ref().is_none_or(|user| *user == entry.user)
+    }
+}
+
+/// Apply a retention policy to entries held in memory, oldest first
+pub fn retain_entries(
+    entries: Vec<HistoryEntry>,
+    cutoff: Option<u64>,
+    max_entries: usize,
+) -> Vec<HistoryEntry> {
+    let mut kept: Vec<HistoryEntry> = entries
+        .into_iter()
+        .filter(|entry| match (cutoff, entry.timestamp.parse::<u64>()) {
+            (Some(cutoff), Ok(timestamp)) => timestamp >= cutoff,
+            // Keep entries we can't date rather than silently losing them
+            _ => true,
         })
+        .collect();
+    if kept.len() > max_entries {
+        kept.drain(..kept.len() - max_entries);
+    }
+    kept
+}
+
+/// History stored as `timestamp|user|command` lines in a plain text file, with
+/// unknown commands counted as `command|count` lines in `<path>-unknown`,
+/// frecency scores as `last_used|count|score|user|command` lines in
+/// `<path>-frecency`, and the schema version in `<path>-version`
+pub struct FileHistoryStore {
+    path: PathBuf,
+}
+
+impl FileHistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
     }
 
     /// Ensure the parent directory exists
@@ -83,34 +367,129 @@ impl History {
         Ok(())
     }
 
-    /// Add a command to history
-    pub fn add(&self, command: &str, user: &str) -> Result<(), String> {
-        if command.trim().is_empty() {
-            return Ok(());
+    fn unknown_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push("-unknown");
+        path.into()
+    }
+
+    fn version_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push("-version");
+        path.into()
+    }
+
+    fn write_version(&self) -> Result<(), String> {
+        fs::write(self.version_path(), format!("{}\n", SCHEMA_VERSION))
+            .map_err(|e| format!("Failed to write history schema version: {}", e))
+    }
+
+    fn frecency_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push("-frecency");
+        path.into()
+    }
+
+    fn read_frecency(&self) -> Result<Vec<(String, FrecencyScore)>, String> {
+        let path = self.frecency_path();
+        if !path.exists() {
+            return Ok(Vec::new());
         }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read frecency scores: {}", e))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.splitn(5, '|').collect();
+                let [last_used, count, score, user, command] = parts[..] else {
+                    return None;
+                };
+                Some((
+                    user.to_string(),
+                    FrecencyScore {
+                        command: command.to_string(),
+                        count: count.parse().ok()?,
+                        last_used: last_used.parse().ok()?,
+                        score: score.parse().ok()?,
+                    },
+                ))
+            })
+            .collect())
+    }
 
+    fn write_frecency(&self, scores: &[(String, FrecencyScore)]) -> Result<(), String> {
+        let content: String = scores
+            .iter()
+            .map(|(user, score)| {
+                format!(
+                    "{}|{}|{}|{}|{}\n",
+                    score.last_used, score.count, score.score, user, score.command
+                )
+            })
+            .collect();
+        fs::write(self.frecency_path(), content)
+            .map_err(|e| format!("Failed to write frecency scores: {}", e))
+    }
+
+    fn read_unknown(&self) -> Result<Vec<UnknownCommand>, String> {
+        let path = self.unknown_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read unknown commands: {}", e))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| {
+                let (command, count) = line.rsplit_once('|')?;
+                Some(UnknownCommand {
+                    command: command.to_string(),
+                    count: count.parse().ok()?,
+                })
+            })
+            .collect())
+    }
+
+    /// Write all history entries to file
+    fn write_all(&self, entries: &[HistoryEntry]) -> Result<(), String> {
+        self.write_version()?;
+        let mut file = fs::File::create(&self.path)
+            .map_err(|e| format!("Failed to create history file: {}", e))?;
+
+        for entry in entries {
+            writeln!(file, "{}", entry.to_line())
+                .map_err(|e| format!("Failed to write to history file: {}", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl HistoryStore for FileHistoryStore {
+    fn append(&self, entry: &HistoryEntry, max_entries: usize) -> Result<(), String> {
+        self.append_batch(std::slice::from_ref(entry), max_entries)
+    }
+
+    fn append_batch(&self, new_entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
         self.ensure_parent_dir()?;
 
         // Read existing history
         let mut entries = self.read_all()?;
 
-        // Add new entry
-        entries.push(HistoryEntry::new(command.to_string(), user.to_string()));
+        // Add new entries
+        entries.extend_from_slice(new_entries);
 
         // Trim to max_entries
-        if entries.len() > self.max_entries {
-            let skip_count = entries.len() - self.max_entries;
+        if entries.len() > max_entries {
+            let skip_count = entries.len() - max_entries;
             entries = entries.into_iter().skip(skip_count).collect();
         }
 
         // Write back to file
-        self.write_all(&entries)?;
-
-        Ok(())
+        self.write_all(&entries)
     }
 
-    /// Read all history entries
-    pub fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
+    fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
         if !self.path.exists() {
             return Ok(Vec::new());
         }
@@ -128,17 +507,267 @@ impl History {
         Ok(entries)
     }
 
-    /// Write all history entries to file
-    fn write_all(&self, entries: &[HistoryEntry]) -> Result<(), String> {
-        let mut file = fs::File::create(&self.path)
-            .map_err(|e| format!("Failed to create history file: {}", e))?;
+    fn clear(&self) -> Result<usize, String> {
+        let removed = self.read_all()?.len();
+        for path in [self.path.clone(), self.unknown_path(), self.frecency_path()] {
+            if path.exists() {
+                fs::remove_file(&path).map_err(|e| format!("Failed to clear history: {}", e))?;
+            }
+        }
+        Ok(removed)
+    }
 
-        for entry in entries {
-            writeln!(file, "{}", entry.to_line())
-                .map_err(|e| format!("Failed to write to history file: {}", e))?;
+    fn add_unknown_counts(
+        &self,
+        added: &[UnknownCommand],
+        max_entries: usize,
+    ) -> Result<(), String> {
+        self.ensure_parent_dir()?;
+        let counts = count_unknown(self.read_unknown()?, added, max_entries);
+        let content: String = counts
+            .iter()
+            .map(|unknown| format!("{}|{}\n", unknown.command, unknown.count))
+            .collect();
+        fs::write(self.unknown_path(), content)
+            .map_err(|e| format!("Failed to write unknown commands: {}", e))
+    }
+
+    fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String> {
+        let mut counts = count_unknown(self.read_unknown()?, &[], usize::MAX);
+        counts.truncate(limit);
+        Ok(counts)
+    }
+
+    fn prune(&self, cutoff: Option<u64>, max_entries: usize) -> Result<usize, String> {
+        let entries = self.read_all()?;
+        let before = entries.len();
+        let kept = retain_entries(entries, cutoff, max_entries);
+        let removed = before - kept.len();
+        if removed > 0 {
+            self.write_all(&kept)?;
         }
+        Ok(removed)
+    }
 
-        Ok(())
+    fn remove_user(&self, user: &str) -> Result<usize, String> {
+        let scores = self.read_frecency()?;
+        if scores.iter().any(|(owner, _)| owner == user) {
+            let kept: Vec<(String, FrecencyScore)> = scores
+                .into_iter()
+                .filter(|(owner, _)| owner != user)
+                .collect();
+            self.write_frecency(&kept)?;
+        }
+
+        let entries = self.read_all()?;
+        let before = entries.len();
+        let kept: Vec<HistoryEntry> = entries.into_iter().filter(|e| e.user != user).collect();
+        let removed = before - kept.len();
+        if removed > 0 {
+            self.write_all(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    fn remove_matching(&self, filter: &HistoryFilter) -> Result<usize, String> {
+        let entries = self.read_all()?;
+        let before = entries.len();
+        let kept: Vec<HistoryEntry> = entries.into_iter().filter(|e| !filter.matches(e)).collect();
+        let removed = before - kept.len();
+        if removed > 0 {
+            self.write_all(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    fn record_frecency(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
+        self.ensure_parent_dir()?;
+        let scores = bump_frecency(self.read_frecency()?, entries, max_entries);
+        self.write_frecency(&scores)
+    }
+
+    fn frecency_for_user(&self, user: &str) -> Result<Vec<FrecencyScore>, String> {
+        Ok(self
+            .read_frecency()?
+            .into_iter()
+            .filter(|(owner, _)| owner == user)
+            .map(|(_, score)| score)
+            .collect())
+    }
+
+    fn schema_version(&self) -> Result<u32, String> {
+        let path = self.version_path();
+        if !path.exists() {
+            // A history file without a version predates versioning
+            return Ok(if self.path.exists() {
+                0
+            } else {
+                SCHEMA_VERSION
+            });
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read history schema version: {}", e))?;
+        content
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid history schema version in {}", path.display()))
+    }
+
+    fn migrate_schema(&self) -> Result<(), String> {
+        // v0 entries are valid v1 entries; only the version needs recording
+        self.ensure_parent_dir()?;
+        self.write_version()
+    }
+}
+
+/// Open the store for `backend` ("file", "sqlite", or "redis"); backends not
+/// built into this binary fall back to the history file with a warning
+#[cfg_attr(not(feature = "redis"), allow(unused_variables))]
+pub fn open_store(config: &BunnylolConfig, backend: &str) -> Result<Box<dyn HistoryStore>, String> {
+    match backend {
+        #[cfg(feature = "sqlite")]
+        "sqlite" => {
+            let path = BunnylolConfig::get_history_db_path()
+                .ok_or("Could not determine the history location")?;
+            crate::history_sqlite::SqliteHistoryStore::open(&path)
+                .map(|store| Box::new(store) as Box<dyn HistoryStore>)
+                .map_err(|e| format!("Failed to open history database: {}", e))
+        }
+        #[cfg(feature = "redis")]
+        "redis" => {
+            let url = config
+                .history
+                .redis_url
+                .as_deref()
+                .ok_or("history backend 'redis' needs [history] redis_url")?;
+            crate::history_redis::RedisHistoryStore::open(url)
+                .map(|store| Box::new(store) as Box<dyn HistoryStore>)
+                .map_err(|e| format!("Failed to connect to Redis for history: {}", e))
+        }
+        backend => {
+            if backend != "file" {
+                eprintln!(
+                    "Warning: history backend '{}' is not available in this build, using the history file",
+                    backend
+                );
+            }
+            let path = BunnylolConfig::get_history_path()
+                .ok_or("Could not determine the history location")?;
+            Ok(Box::new(FileHistoryStore::new(path)))
+        }
+    }
+}
+
+/// Refuse to run against history written by a newer bunnylol; a store that
+/// can't be opened at all is left for `History::new` to warn about
+pub fn check_schema(config: &BunnylolConfig) -> Result<(), String> {
+    if !config.history.enabled {
+        return Ok(());
+    }
+    let Ok(store) = open_store(config, &config.history.backend) else {
+        return Ok(());
+    };
+    match store.schema_version() {
+        Ok(version) if version > SCHEMA_VERSION => Err(newer_schema_error(version)),
+        _ => Ok(()),
+    }
+}
+
+/// Command history manager
+pub struct History {
+    store: Box<dyn HistoryStore>,
+    max_entries: usize,
+    retention_days: Option<u64>,
+}
+
+impl History {
+    /// Create a new history manager using the backend selected in the config
+    ///
+    /// Returns `None` (with a warning) when the store can't be opened or was
+    /// written by a newer bunnylol, so it's never modified by this one.
+    pub fn new(config: &BunnylolConfig) -> Option<Self> {
+        let store = match open_store(config, &config.history.backend) {
+            Ok(store) => store,
+            Err(e) => {
+                eprintln!("Warning: {}", e);
+                return None;
+            }
+        };
+        match store.schema_version() {
+            Ok(version) if version > SCHEMA_VERSION => {
+                eprintln!("Warning: {}", newer_schema_error(version));
+                return None;
+            }
+            Err(e) => {
+                eprintln!("Warning: {}", e);
+                return None;
+            }
+            Ok(_) => {}
+        }
+        Some(
+            Self::with_store(store, config.history.max_entries)
+                .with_retention_days(config.history.retention_days),
+        )
+    }
+
+    /// Create a history manager backed by a specific file
+    pub fn with_path(path: PathBuf, max_entries: usize) -> Self {
+        Self::with_store(Box::new(FileHistoryStore::new(path)), max_entries)
+    }
+
+    /// Create a history manager backed by any store
+    pub fn with_store(store: Box<dyn HistoryStore>, max_entries: usize) -> Self {
+        Self {
+            store,
+            max_entries,
+            retention_days: None,
+        }
+    }
+
+    /// Drop entries older than this many days when pruning
+    pub fn with_retention_days(mut self, retention_days: Option<u64>) -> Self {
+        self.retention_days = retention_days;
+        self
+    }
+
+    /// Apply the retention policy (`retention_days` and `max_entries`),
+    /// returning how many entries were removed
+    pub fn prune(&self) -> Result<usize, String> {
+        let cutoff = self.retention_days.map(|days| {
+            history_stats::now_secs().saturating_sub(days.saturating_mul(history_stats::DAY_SECS))
+        });
+        self.store.prune(cutoff, self.max_entries)
+    }
+
+    /// Add a command to history
+    pub fn add(&self, command: &str, user: &str) -> Result<(), String> {
+        if command.trim().is_empty() {
+            return Ok(());
+        }
+
+        let entry = HistoryEntry::new(command.to_string(), user.to_string());
+        self.store.append(&entry, self.max_entries)?;
+        self.store
+            .record_frecency(std::slice::from_ref(&entry), self.max_entries)
+    }
+
+    /// Add several already-timestamped entries in one write, skipping blank commands
+    pub fn add_entries(&self, entries: &[HistoryEntry]) -> Result<(), String> {
+        let entries: Vec<HistoryEntry> = entries
+            .iter()
+            .filter(|entry| !entry.command.trim().is_empty())
+            .cloned()
+            .collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.store.append_batch(&entries, self.max_entries)?;
+        self.store.record_frecency(&entries, self.max_entries)
+    }
+
+    /// Read all history entries
+    pub fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
+        self.store.read_all()
     }
 
     /// Get the last N commands from history
@@ -147,12 +776,75 @@ impl History {
         Ok(entries.into_iter().rev().take(n).collect())
     }
 
-    /// Clear all history
-    pub fn clear(&self) -> Result<(), String> {
-        if self.path.exists() {
-            fs::remove_file(&self.path).map_err(|e| format!("Failed to clear history: {}", e))?;
+    /// Get the last N distinct commands run by one user, most recent first
+    pub fn get_recent_for_user(&self, user: &str, n: usize) -> Result<Vec<String>, String> {
+        let entries = self.read_all()?;
+        let own: Vec<&HistoryEntry> = entries.iter().filter(|e| e.user == user).collect();
+        Ok(history_stats::recent_commands(own, n))
+    }
+
+    /// Clear all history, returning how many entries were removed
+    pub fn clear(&self) -> Result<usize, String> {
+        self.store.clear()
+    }
+
+    /// The `limit` most used commands (by frecency) since `since`, across all users
+    pub fn top_commands(
+        &self,
+        since: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<CommandScore>, String> {
+        self.store
+            .top_commands(since, history_stats::now_secs(), limit)
+    }
+
+    /// One user's `limit` highest-frecency commands, from the incrementally
+    /// maintained scores rather than a scan of the history
+    pub fn top_frecency(&self, user: &str, limit: usize) -> Result<Vec<CommandScore>, String> {
+        let scores = self.store.frecency_for_user(user)?;
+        Ok(history_stats::rank_frecency(
+            scores,
+            history_stats::now_secs(),
+            limit,
+        ))
+    }
+
+    /// Count command tokens that fell through to the default search
+    pub fn record_unknown(&self, tokens: &[String]) -> Result<(), String> {
+        let tokens: Vec<String> = tokens
+            .iter()
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return Ok(());
         }
-        Ok(())
+        self.store.record_unknown(&tokens, self.max_entries)
+    }
+
+    /// The `limit` most frequent command tokens that matched nothing
+    pub fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String> {
+        self.store.top_unknown(limit)
+    }
+
+    /// Entries `filter` matches, oldest first
+    pub fn read_matching(&self, filter: &HistoryFilter) -> Result<Vec<HistoryEntry>, String> {
+        let mut entries = self.read_all()?;
+        entries.retain(|entry| filter.matches(entry));
+        Ok(entries)
+    }
+
+    /// Delete the entries `filter` matches, returning how many were removed;
+    /// frecency scores and unknown-command counts are kept
+    pub fn remove_matching(&self, filter: &HistoryFilter) -> Result<usize, String> {
+        self.store.remove_matching(filter)
+    }
+
+    /// Delete every entry and frecency score recorded for one user (an
+    /// identifier as stored, e.g. an IP after `ip_mode`), returning how many
+    /// entries were removed
+    pub fn remove_user(&self, user: &str) -> Result<usize, String> {
+        self.store.remove_user(user)
     }
 }
 
@@ -183,25 +875,294 @@ mod tests {
             timestamp: "1234567890".to_string(),
             user: "testuser".to_string(),
             command: "ig reels".to_string(),
+            latency_us: None,
         };
         assert_eq!(entry.to_line(), "1234567890|testuser|ig reels");
     }
 
+    #[test]
+    fn test_is_storable_user() {
+        assert!(HistoryEntry::is_storable_user("alice@example.com"));
+        assert!(!HistoryEntry::is_storable_user("alice|1|gh"));
+        assert!(!HistoryEntry::is_storable_user("alice\nbob"));
+    }
+
     #[test]
     fn test_history_entry_from_line_invalid() {
         let line = "invalid";
         assert!(HistoryEntry::from_line(line).is_none());
     }
 
+    #[test]
+    fn test_get_recent_for_user() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-recent-{}", std::process::id()));
+        let history = History::with_path(path.clone(), 100);
+        history.clear().unwrap();
+        for (command, user) in [
+            ("gh", "10.0.0.1"),
+            ("yt cats", "10.0.0.2"),
+            ("ig reels", "10.0.0.1"),
+            ("gh", "10.0.0.1"),
+            ("tw @rust", "10.0.0.1"),
+        ] {
+            history.add(command, user).unwrap();
+        }
+
+        assert_eq!(
+            history.get_recent_for_user("10.0.0.1", 10).unwrap(),
+            vec!["tw @rust", "gh", "ig reels"]
+        );
+        assert_eq!(
+            history.get_recent_for_user("10.0.0.1", 2).unwrap(),
+            vec!["tw @rust", "gh"]
+        );
+        assert!(history.get_recent_for_user("nobody", 5).unwrap().is_empty());
+
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_file_store_trims_to_max_entries() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-trim-{}", std::process::id()));
+        let history = History::with_path(path, 2);
+        history.clear().unwrap();
+        for command in ["gh", "yt", "ig"] {
+            history.add(command, "testuser").unwrap();
+        }
+
+        let commands: Vec<String> = history
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.command)
+            .collect();
+        assert_eq!(commands, vec!["yt", "ig"]);
+
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_retain_entries_applies_cutoff_and_cap() {
+        let entry = |timestamp: &str, command: &str| HistoryEntry {
+            timestamp: timestamp.to_string(),
+            user: "testuser".to_string(),
+            command: command.to_string(),
+            latency_us: None,
+        };
+        let entries = vec![
+            entry("100", "old"),
+            entry("bad", "undated"),
+            entry("200", "gh"),
+            entry("300", "yt"),
+        ];
+
+        let kept = retain_entries(entries.clone(), Some(150), 10);
+        let commands: Vec<&str> = kept.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, vec!["undated", "gh", "yt"]);
+
+        let kept = retain_entries(entries, None, 2);
+        let commands: Vec<&str> = kept.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, vec!["gh", "yt"]);
+    }
+
+    #[test]
+    fn test_history_filter_matches() {
+        let entry = |timestamp: &str, user: &str, command: &str| HistoryEntry {
+            timestamp: timestamp.to_string(),
+            user: user.to_string(),
+            command: command.to_string(),
+            latency_us: None,
+        };
+        let filter = HistoryFilter {
+            since: Some(150),
+            command: Some("gh".to_string()),
+            user: Some("alice".to_string()),
+        };
+        assert!(filter.matches(&entry("200", "alice", "GH facebook/react")));
+        assert!(!filter.matches(&entry("100", "alice", "gh")));
+        assert!(!filter.matches(&entry("bad", "alice", "gh")));
+        assert!(!filter.matches(&entry("200", "bob", "gh")));
+        assert!(!filter.matches(&entry("200", "alice", "ghost")));
+        assert!(HistoryFilter::default().is_empty());
+        assert!(HistoryFilter::default().matches(&entry("bad", "bob", "")));
+    }
+
+    #[test]
+    fn test_prune_removes_expired_entries_from_file() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-prune-{}", std::process::id()));
+        let store = FileHistoryStore::new(path.clone());
+        store.clear().unwrap();
+        let now = history_stats::now_secs();
+        let old = HistoryEntry {
+            timestamp: (now - 40 * history_stats::DAY_SECS).to_string(),
+            user: "testuser".to_string(),
+            command: "old".to_string(),
+            latency_us: None,
+        };
+        store.append(&old, 100).unwrap();
+
+        let history = History::with_path(path, 100).with_retention_days(Some(30));
+        history.add("gh", "testuser").unwrap();
+        assert_eq!(history.prune().unwrap(), 1);
+        assert_eq!(history.read_all().unwrap()[0].command, "gh");
+        assert_eq!(history.prune().unwrap(), 0);
+
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_remove_user_keeps_other_users() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-purge-{}", std::process::id()));
+        let history = History::with_path(path, 100);
+        history.clear().unwrap();
+        history.add("gh", "10.0.0.1").unwrap();
+        history.add("yt", "10.0.0.2").unwrap();
+        history.add("ig", "10.0.0.1").unwrap();
+
+        assert_eq!(history.remove_user("10.0.0.1").unwrap(), 2);
+        let entries = history.read_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].command, "yt");
+        assert_eq!(history.remove_user("10.0.0.1").unwrap(), 0);
+
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_unknown_commands_are_counted_and_capped() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-unknown-{}", std::process::id()));
+        let history = History::with_path(path, 2);
+        history.clear().unwrap();
+        let tokens = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        history
+            .record_unknown(&tokens(&["jira", "wiki|x", " "]))
+            .unwrap();
+        history.record_unknown(&tokens(&["jira"])).unwrap();
+        history.record_unknown(&tokens(&["zzz"])).unwrap();
+
+        let top = history.top_unknown(10).unwrap();
+        assert_eq!(
+            top,
+            vec![
+                UnknownCommand {
+                    command: "jira".to_string(),
+                    count: 2
+                },
+                UnknownCommand {
+                    command: "wiki|x".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(history.top_unknown(1).unwrap().len(), 1);
+
+        history.clear().unwrap();
+        assert!(history.top_unknown(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_frecency_is_kept_per_user_and_purged_with_them() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-frecency-{}", std::process::id()));
+        let history = History::with_path(path, 100);
+        history.clear().unwrap();
+        for (command, user) in [
+            ("gh", "10.0.0.1"),
+            ("yt cats", "10.0.0.1"),
+            ("gh", "10.0.0.1"),
+            ("hist", "10.0.0.1"),
+            ("ig", "10.0.0.2"),
+        ] {
+            history.add(command, user).unwrap();
+        }
+
+        let top = history.top_frecency("10.0.0.1", 10).unwrap();
+        let commands: Vec<&str> = top.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(commands, vec!["gh", "yt cats"]);
+        assert_eq!((top[0].count, top[0].score), (2, 200));
+
+        history.remove_user("10.0.0.1").unwrap();
+        assert!(history.top_frecency("10.0.0.1", 10).unwrap().is_empty());
+        assert_eq!(history.top_frecency("10.0.0.2", 10).unwrap().len(), 1);
+
+        history.clear().unwrap();
+        assert!(history.top_frecency("10.0.0.2", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bump_frecency_keeps_most_recently_used_pairs() {
+        let entry = |timestamp: &str, user: &str, command: &str| HistoryEntry {
+            timestamp: timestamp.to_string(),
+            user: user.to_string(),
+            command: command.to_string(),
+            latency_us: None,
+        };
+        let scores = bump_frecency(
+            Vec::new(),
+            &[
+                entry("100", "a", "gh"),
+                entry("bad", "a", "undated"),
+                entry("200", "b", "gh"),
+                entry("300", "a", "yt"),
+            ],
+            2,
+        );
+        let kept: Vec<(&str, &str)> = scores
+            .iter()
+            .map(|(user, score)| (user.as_str(), score.command.as_str()))
+            .collect();
+        assert_eq!(kept, vec![("a", "yt"), ("b", "gh")]);
+    }
+
     #[test]
     fn test_history_entry_roundtrip() {
         let original = HistoryEntry {
             timestamp: "1234567890".to_string(),
             user: "testuser".to_string(),
             command: "test command".to_string(),
+            latency_us: None,
         };
         let line = original.to_line();
         let parsed = HistoryEntry::from_line(&line).unwrap();
         assert_eq!(original, parsed);
+
+        let timed = original.with_latency(std::time::Duration::from_micros(250));
+        let line = timed.to_line();
+        assert_eq!(line, "v1|1234567890|testuser|250|test command");
+        assert_eq!(HistoryEntry::from_line(&line).unwrap(), timed);
+
+        // Older lines whose command contains a '|' still parse as before
+        let parsed = HistoryEntry::from_line("1234567890|testuser|gh a|b").unwrap();
+        assert_eq!(parsed.command, "gh a|b");
+        assert_eq!(parsed.latency_us, None);
+    }
+
+    #[test]
+    fn test_history_entry_command_that_looks_like_a_latency() {
+        // Digits and a '|' at the start of a command aren't a latency
+        let parsed = HistoryEntry::from_line("1234567890|testuser|42|foo").unwrap();
+        assert_eq!(parsed.command, "42|foo");
+        assert_eq!(parsed.latency_us, None);
+
+        let untimed = HistoryEntry {
+            timestamp: "1234567890".to_string(),
+            user: "testuser".to_string(),
+            command: "42|foo".to_string(),
+            latency_us: None,
+        };
+        assert_eq!(
+            HistoryEntry::from_line(&untimed.to_line()).unwrap(),
+            untimed
+        );
+        let timed = untimed.with_latency(std::time::Duration::from_micros(7));
+        assert_eq!(HistoryEntry::from_line(&timed.to_line()).unwrap(), timed);
+
+        assert!(HistoryEntry::from_line("v1|1234567890|testuser|fast|gh").is_none());
     }
 }
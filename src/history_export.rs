@@ -0,0 +1,186 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Export command history as CSV or JSON, shared by `bunnylol history export`
+//! and the `/api/v1/history/export` endpoint.
+
+use serde::Serialize;
+
+use crate::history::HistoryEntry;
+use crate::history_stats::DAY_SECS;
+use crate::utils::csv::csv_row;
+use crate::utils::date::days_from_civil;
+
+/// File formats history can be exported as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryExportFormat {
+    Csv,
+    Json,
+}
+
+impl HistoryExportFormat {
+    /// Parse "csv" or "json" (case-insensitive)
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Some(HistoryExportFormat::Csv),
+            "json" => Some(HistoryExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Suggested file name for the download
+    pub fn file_name(self) -> &'static str {
+        match self {
+            HistoryExportFormat::Csv => "bunnylol-history.csv",
+            HistoryExportFormat::Json => "bunnylol-history.json",
+        }
+    }
+}
+
+/// One exported history row; timestamps are numeric so spreadsheets can sort them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedEntry {
+    pub timestamp: u64,
+    pub user: String,
+    pub command: String,
+}
+
+/// Parse a `--since` value into seconds since the epoch
+///
+/// Accepts a relative age (`30m`, `12h`, `7d`, `2w`), a date (`2024-01-31`,
+/// midnight UTC), or a raw Unix timestamp.
+pub fn parse_since(since: &str, now: u64) -> Result<u64, String> {
+    let since = since.trim();
+    let invalid = || {
+        format!(
+            "Invalid --since value '{}': use an age like 7d, a date like 2024-01-31, or a Unix timestamp",
+            since
+        )
+    };
+
+    if let Some(unit) = since.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        let amount: u64 = since[..since.len() - 1].parse().map_err(|_| invalid())?;
+        let unit_secs = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => DAY_SECS,
+            'w' => 7 * DAY_SECS,
+            _ => return Err(invalid()),
+        };
+        return Ok(now.saturating_sub(amount.saturating_mul(unit_secs)));
+    }
+
+    if let [year, month, day] = since.split('-').collect::<Vec<_>>()[..] {
+        let (year, month, day) = (
+            year.parse::<i64>().map_err(|_| invalid())?,
+            month.parse::<u32>().map_err(|_| invalid())?,
+            day.parse::<u32>().map_err(|_| invalid())?,
+        );
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(invalid());
+        }
+        let days = days_from_civil(year, month, day);
+        return u64::try_from(days * DAY_SECS as i64).map_err(|_| invalid());
+    }
+
+    since.parse().map_err(|_| invalid())
+}
+
+/// Entries at or after `since` (all entries when `None`), oldest first
+pub fn select_entries<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    since: Option<u64>,
+) -> Vec<ExportedEntry> {
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let timestamp = entry.timestamp.parse::<u64>().ok()?;
+            (timestamp >= since.unwrap_or(0)).then(|| ExportedEntry {
+                timestamp,
+                user: entry.user.clone(),
+                command: entry.command.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Render exported entries in the requested format
+pub fn render_history_export(entries: &[ExportedEntry], format: HistoryExportFormat) -> String {
+    match format {
+        HistoryExportFormat::Json => {
+            serde_json::to_string_pretty(entries).expect("history entries should serialize")
+        }
+        HistoryExportFormat::Csv => {
+            let mut out = String::from("timestamp,user,command\n");
+            for entry in entries {
+                let timestamp = entry.timestamp.to_string();
+                out.push_str(&csv_row([
+                    timestamp.as_str(),
+                    entry.user.as_str(),
+                    entry.command.as_str(),
+                ]));
+                out.push('\n');
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn entry(timestamp: &str, command: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: timestamp.to_string(),
+            user: "10.0.0.1".to_string(),
+            command: command.to_string(),
+            latency_us: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("7d", NOW), Ok(NOW - 7 * DAY_SECS));
+        assert_eq!(parse_since("12h", NOW), Ok(NOW - 12 * 60 * 60));
+        assert_eq!(parse_since("2024-01-31", NOW), Ok(1_706_659_200));
+        assert_eq!(parse_since("1970-01-01", NOW), Ok(0));
+        assert_eq!(parse_since("1690000000", NOW), Ok(1_690_000_000));
+        assert!(parse_since("yesterday", NOW).is_err());
+        assert!(parse_since("7y", NOW).is_err());
+        assert!(parse_since("2024-13-01", NOW).is_err());
+    }
+
+    #[test]
+    fn test_select_entries_filters_by_since() {
+        let entries = vec![
+            entry("100", "old"),
+            entry("bad", "undated"),
+            entry("300", "gh"),
+        ];
+
+        let selected = select_entries(&entries, Some(200));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].command, "gh");
+        assert_eq!(select_entries(&entries, None).len(), 2);
+    }
+
+    #[test]
+    fn test_render_history_export() {
+        let entries = select_entries(&[entry("300", "gh a, b")], None);
+
+        let csv = render_history_export(&entries, HistoryExportFormat::Csv);
+        assert_eq!(csv, "timestamp,user,command\n300,10.0.0.1,\"gh a, b\"\n");
+
+        let json = render_history_export(&entries, HistoryExportFormat::Json);
+        assert!(json.contains(r#""timestamp": 300"#));
+        assert!(json.contains(r#""command": "gh a, b""#));
+    }
+}
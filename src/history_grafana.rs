@@ -0,0 +1,418 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! History aggregates in the shape Grafana's simple JSON datasource (and the
+//! Infinity datasource pointed at the same URLs) expects, served under
+//! `/api/v1/grafana` so usage can be charted without running Prometheus.
+//!
+//! Metrics:
+//! - `requests`: commands run per interval, as a time series
+//! - `requests:<binding>`: the same, for one binding
+//! - `commands`: a table of bindings with their count and last use in the range
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::HistoryEntry;
+use crate::history_stats::DAY_SECS;
+use crate::utils::date::days_from_civil;
+
+/// Time series of every command run
+pub const REQUESTS_METRIC: &str = "requests";
+
+/// Prefix of the per-binding time series (`requests:gh`)
+pub const BINDING_METRIC_PREFIX: &str = "requests:";
+
+/// Table of per-binding counts
+pub const COMMANDS_METRIC: &str = "commands";
+
+/// Most buckets one series returns, however small the interval
+const MAX_BUCKETS: u64 = 10_000;
+
+/// Body of `POST /search`: the text typed into the metric picker
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchRequest {
+    #[serde(default)]
+    pub target: String,
+}
+
+/// Body of `POST /query` (only the fields bunnylol uses)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub range: QueryRange,
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[serde(default)]
+    pub max_data_points: Option<u64>,
+    #[serde(default)]
+    pub targets: Vec<QueryTarget>,
+}
+
+/// Dashboard time range as RFC 3339 UTC timestamps
+#[derive(Debug, Deserialize)]
+pub struct QueryRange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryTarget {
+    #[serde(default)]
+    pub target: String,
+}
+
+/// One entry of the `POST /query` response
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum QueryResult {
+    /// `[value, unix_ms]` pairs, oldest first
+    TimeSeries {
+        target: String,
+        datapoints: Vec<[u64; 2]>,
+    },
+    Table {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        columns: Vec<TableColumn>,
+        rows: Vec<(String, u64, u64)>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TableColumn {
+    pub text: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+}
+
+/// Parse a Grafana timestamp (`2024-01-31T06:33:44.866Z`) into milliseconds
+/// since the epoch; a bare number is taken as milliseconds already
+pub fn parse_timestamp_ms(timestamp: &str) -> Option<u64> {
+    let timestamp = timestamp.trim();
+    if let Ok(ms) = timestamp.parse::<u64>() {
+        return Some(ms);
+    }
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let [year, month, day] = date.split('-').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let (year, month, day) = (
+        year.parse::<i64>().ok()?,
+        month.parse::<u32>().ok()?,
+        day.parse::<u32>().ok()?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let (time, millis) = match time.split_once('.') {
+        Some((time, fraction)) => {
+            let digits: String = fraction.chars().chain("000".chars()).take(3).collect();
+            (time, digits.parse::<u64>().ok()?)
+        }
+        None => (time, 0),
+    };
+    let [hours, minutes, seconds] = time.split(':').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let secs_of_day = hours.parse::<u64>().ok()? * 3600
+        + minutes.parse::<u64>().ok()? * 60
+        + seconds.parse::<u64>().ok()?;
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some((days * DAY_SECS + secs_of_day) * 1000 + millis)
+}
+
+/// Metric names matching what was typed into the picker: the fixed metrics
+/// plus `requests:<binding>` for every binding in history
+pub fn search_metrics(entries: &[HistoryEntry], query: &str) -> Vec<String> {
+    let bindings: BTreeSet<&str> = entries
+        .iter()
+        .filter_map(|entry| entry.command.split_whitespace().next())
+        .collect();
+    let query = query.to_lowercase();
+    [REQUESTS_METRIC.to_string(), COMMANDS_METRIC.to_string()]
+        .into_iter()
+        .chain(
+            bindings
+                .into_iter()
+                .map(|binding| format!("{}{}", BINDING_METRIC_PREFIX, binding)),
+        )
+        .filter(|metric| metric.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Answer a `POST /query`, skipping targets that aren't known metrics
+pub fn run_query(
+    entries: &[HistoryEntry],
+    query: &QueryRequest,
+) -> Result<Vec<QueryResult>, String> {
+    let (Some(from), Some(to)) = (
+        parse_timestamp_ms(&query.range.from),
+        parse_timestamp_ms(&query.range.to),
+    ) else {
+        return Err("range.from and range.to must be RFC 3339 UTC timestamps".to_string());
+    };
+    if to < from {
+        return Err("range.to is before range.from".to_string());
+    }
+    let interval_ms = bucket_interval_ms(from, to, query.interval_ms, query.max_data_points);
+
+    // Timestamps in history are whole seconds
+    let in_range: Vec<(u64, &HistoryEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let ms = entry.timestamp.parse::<u64>().ok()?.checked_mul(1000)?;
+            (from..=to).contains(&ms).then_some((ms, entry))
+        })
+        .collect();
+
+    Ok(query
+        .targets
+        .iter()
+        .filter_map(|target| {
+            let target = target.target.as_str();
+            if target == COMMANDS_METRIC {
+                return Some(command_table(&in_range));
+            }
+            let binding = match target.strip_prefix(BINDING_METRIC_PREFIX) {
+                Some(binding) => Some(binding),
+                None if target == REQUESTS_METRIC => None,
+                None => return None,
+            };
+            let times = in_range
+                .iter()
+                .filter(|(_, entry)| {
+                    binding.is_none_or(|binding| {
+                        entry.command.split_whitespace().next() == Some(binding)
+                    })
+                })
+                .map(|(ms, _)| *ms);
+            Some(QueryResult::TimeSeries {
+                target: target.to_string(),
+                datapoints: bucket(times, from, to, interval_ms),
+            })
+        })
+        .collect())
+}
+
+/// The interval Grafana asked for, widened so the series fits in
+/// `max_data_points` (and `MAX_BUCKETS`), and never under a second
+fn bucket_interval_ms(
+    from: u64,
+    to: u64,
+    interval_ms: Option<u64>,
+    max_data_points: Option<u64>,
+) -> u64 {
+    let span = to - from + 1;
+    let points = max_data_points.unwrap_or(MAX_BUCKETS).clamp(1, MAX_BUCKETS);
+    interval_ms
+        .unwrap_or(0)
+        .max(span.div_ceil(points))
+        .max(1000)
+}
+
+/// Count `times` into `interval_ms` buckets starting at `from`; empty buckets
+/// are included so Grafana draws zeros rather than gaps
+fn bucket(times: impl Iterator<Item = u64>, from: u64, to: u64, interval_ms: u64) -> Vec<[u64; 2]> {
+    let buckets = (to - from) / interval_ms + 1;
+    let mut counts = vec![0u64; buckets as usize];
+    for ms in times {
+        counts[((ms - from) / interval_ms) as usize] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| [count, from + i as u64 * interval_ms])
+        .collect()
+}
+
+/// Per-binding counts in the range, most used first
+fn command_table(in_range: &[(u64, &HistoryEntry)]) -> QueryResult {
+    let mut by_binding: HashMap<&str, (u64, u64)> = HashMap::new();
+    for (ms, entry) in in_range {
+        let Some(binding) = entry.command.split_whitespace().next() else {
+            continue;
+        };
+        let (count, last_used) = by_binding.entry(binding).or_default();
+        *count += 1;
+        *last_used = (*last_used).max(*ms);
+    }
+    let mut rows: Vec<(String, u64, u64)> = by_binding
+        .into_iter()
+        .map(|(binding, (count, last_used))| (binding.to_string(), count, last_used))
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    QueryResult::Table {
+        kind: "table",
+        columns: vec![
+            TableColumn {
+                text: "Command",
+                kind: "string",
+            },
+            TableColumn {
+                text: "Count",
+                kind: "number",
+            },
+            TableColumn {
+                text: "Last used",
+                kind: "time",
+            },
+        ],
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, command: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: timestamp.to_string(),
+            user: "10.0.0.1".to_string(),
+            command: command.to_string(),
+            latency_us: None,
+        }
+    }
+
+    fn query(from: &str, to: &str, interval_ms: u64, targets: &[&str]) -> QueryRequest {
+        QueryRequest {
+            range: QueryRange {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            interval_ms: Some(interval_ms),
+            max_data_points: None,
+            targets: targets
+                .iter()
+                .map(|target| QueryTarget {
+                    target: target.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(
+            parse_timestamp_ms("2024-01-31T00:00:00Z"),
+            Some(1_706_659_200_000)
+        );
+        assert_eq!(
+            parse_timestamp_ms("2024-01-31T06:33:44.866Z"),
+            Some(1_706_682_824_866)
+        );
+        assert_eq!(
+            parse_timestamp_ms("2024-01-31T06:33:44.8Z"),
+            Some(1_706_682_824_800)
+        );
+        assert_eq!(parse_timestamp_ms("1706659200000"), Some(1_706_659_200_000));
+        assert_eq!(parse_timestamp_ms("2024-01-31T06:33:44+01:00"), None);
+        assert_eq!(parse_timestamp_ms("now-6h"), None);
+    }
+
+    #[test]
+    fn test_search_metrics_lists_bindings() {
+        let entries = vec![
+            entry(100, "gh facebook/react"),
+            entry(200, "yt cats"),
+            entry(300, "gh"),
+        ];
+        assert_eq!(
+            search_metrics(&entries, ""),
+            vec!["requests", "commands", "requests:gh", "requests:yt"]
+        );
+        assert_eq!(search_metrics(&entries, "GH"), vec!["requests:gh"]);
+    }
+
+    #[test]
+    fn test_run_query_buckets_requests() {
+        // 2024-01-31T00:00:00Z
+        let start = 1_706_659_200;
+        let entries = vec![
+            entry(start - 1, "gh too early"),
+            entry(start, "gh facebook/react"),
+            entry(start + 30, "yt cats"),
+            entry(start + 90, "gh"),
+            entry(start + 200, "gh too late"),
+        ];
+        let results = run_query(
+            &entries,
+            &query(
+                "2024-01-31T00:00:00Z",
+                "2024-01-31T00:02:00Z",
+                60_000,
+                &["requests", "requests:gh", "bogus"],
+            ),
+        )
+        .unwrap();
+
+        let ms = start * 1000;
+        assert_eq!(
+            results,
+            vec![
+                QueryResult::TimeSeries {
+                    target: "requests".to_string(),
+                    datapoints: vec![[2, ms], [1, ms + 60_000], [0, ms + 120_000]],
+                },
+                QueryResult::TimeSeries {
+                    target: "requests:gh".to_string(),
+                    datapoints: vec![[1, ms], [1, ms + 60_000], [0, ms + 120_000]],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_query_commands_table_and_errors() {
+        let start = 1_706_659_200;
+        let entries = vec![
+            entry(start, "gh a"),
+            entry(start + 5, "yt"),
+            entry(start + 10, "gh b"),
+        ];
+        let results = run_query(
+            &entries,
+            &query(
+                "2024-01-31T00:00:00Z",
+                "2024-01-31T01:00:00Z",
+                1000,
+                &["commands"],
+            ),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&results).unwrap();
+        assert!(json.contains(r#""type":"table""#));
+        assert!(json.contains(r#""rows":[["gh",2,1706659210000],["yt",1,1706659205000]]"#));
+
+        assert!(run_query(&entries, &query("yesterday", "now", 1000, &["requests"])).is_err());
+        assert!(
+            run_query(
+                &entries,
+                &query(
+                    "2024-01-31T01:00:00Z",
+                    "2024-01-31T00:00:00Z",
+                    1000,
+                    &["requests"]
+                )
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bucket_interval_respects_max_data_points() {
+        let day_ms = DAY_SECS * 1000;
+        assert_eq!(
+            bucket_interval_ms(0, day_ms, Some(1), Some(24)),
+            3_600_000 + 1
+        );
+        assert_eq!(bucket_interval_ms(0, 10_000, Some(10), None), 1000);
+        assert_eq!(bucket_interval_ms(0, 10_000, Some(5000), Some(100)), 5000);
+    }
+}
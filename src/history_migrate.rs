@@ -0,0 +1,157 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Moving history between schema versions and backends, for `bunnylol migrate`.
+
+use crate::history::{HistoryStore, SCHEMA_VERSION, newer_schema_error};
+
+/// History backends `bunnylol migrate --to` accepts
+pub const BACKENDS: &[&str] = &["file", "sqlite", "redis"];
+
+/// Whether `backend` was built into this binary
+pub fn backend_available(backend: &str) -> bool {
+    backend == "file"
+        || (backend == "sqlite" && cfg!(feature = "sqlite"))
+        || (backend == "redis" && cfg!(feature = "redis"))
+}
+
+/// Upgrade `store` in place, returning the version it was at, or `None` when
+/// it was already current
+pub fn upgrade(store: &dyn HistoryStore) -> Result<Option<u32>, String> {
+    let version = store.schema_version()?;
+    if version > SCHEMA_VERSION {
+        return Err(newer_schema_error(version));
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(None);
+    }
+    store.migrate_schema()?;
+    Ok(Some(version))
+}
+
+/// Copy every entry and unknown-command count from `from` into the empty
+/// store `to`, returning how many entries were copied
+///
+/// Frecency scores are rebuilt from the copied entries. `from` is left as it was.
+pub fn copy_history(
+    from: &dyn HistoryStore,
+    to: &dyn HistoryStore,
+    max_entries: usize,
+) -> Result<usize, String> {
+    for store in [from, to] {
+        let version = store.schema_version()?;
+        if version > SCHEMA_VERSION {
+            return Err(newer_schema_error(version));
+        }
+    }
+    let existing = to.read_all()?.len();
+    if existing > 0 {
+        return Err(format!(
+            "The target history already has {} entries; clear it first so nothing is duplicated",
+            existing
+        ));
+    }
+
+    let entries = from.read_all()?;
+    to.migrate_schema()?;
+    to.append_batch(&entries, max_entries)?;
+    to.record_frecency(&entries, max_entries)?;
+    to.add_unknown_counts(&from.top_unknown(usize::MAX)?, max_entries)?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{FileHistoryStore, HistoryEntry, UnknownCommand};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("bunnylol-migrate-{}-{}", name, std::process::id()))
+    }
+
+    fn remove(path: &std::path::Path) {
+        for suffix in ["", "-unknown", "-frecency", "-version"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_upgrade_versions_an_old_history_file() {
+        let path = temp_path("upgrade");
+        remove(&path);
+        std::fs::write(&path, "1700000000|testuser|gh\n").unwrap();
+        let store = FileHistoryStore::new(path.clone());
+
+        assert_eq!(store.schema_version().unwrap(), 0);
+        assert_eq!(upgrade(&store).unwrap(), Some(0));
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        assert_eq!(upgrade(&store).unwrap(), None);
+        assert_eq!(store.read_all().unwrap()[0].command, "gh");
+
+        std::fs::write(format!("{}-version", path.display()), "99\n").unwrap();
+        assert!(upgrade(&store).unwrap_err().contains("schema v99"));
+        remove(&path);
+    }
+
+    #[test]
+    fn test_copy_history_between_stores() {
+        let (from_path, to_path) = (temp_path("from"), temp_path("to"));
+        remove(&from_path);
+        remove(&to_path);
+        let from = FileHistoryStore::new(from_path.clone());
+        let to = FileHistoryStore::new(to_path.clone());
+        let entries = vec![
+            HistoryEntry::new("gh".to_string(), "10.0.0.1".to_string()),
+            HistoryEntry::new("yt cats".to_string(), "10.0.0.2".to_string()),
+        ];
+        from.append_batch(&entries, 100).unwrap();
+        from.record_unknown(&["jira".to_string(), "jira".to_string()], 100)
+            .unwrap();
+        // Copied as a count, not replayed one hit at a time
+        let wiki = UnknownCommand {
+            command: "wiki".to_string(),
+            count: 50_000_000,
+        };
+        from.add_unknown_counts(&[wiki], 100).unwrap();
+
+        assert_eq!(copy_history(&from, &to, 100).unwrap(), 2);
+        assert_eq!(to.read_all().unwrap(), entries);
+        assert_eq!(to.top_unknown(10).unwrap(), from.top_unknown(10).unwrap());
+        assert_eq!(to.frecency_for_user("10.0.0.1").unwrap()[0].command, "gh");
+
+        // Copying again would duplicate every entry
+        assert!(copy_history(&from, &to, 100).is_err());
+        remove(&from_path);
+        remove(&to_path);
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn test_copy_history_file_to_sqlite() {
+        let path = temp_path("sqlite");
+        remove(&path);
+        std::fs::write(
+            &path,
+            "1700000000|testuser|gh\nv1|1700000001|testuser|250|yt\n",
+        )
+        .unwrap();
+        let from = FileHistoryStore::new(path.clone());
+        let to = crate::history_sqlite::SqliteHistoryStore::open_in_memory().unwrap();
+
+        assert_eq!(copy_history(&from, &to, 100).unwrap(), 2);
+        assert_eq!(to.read_all().unwrap(), from.read_all().unwrap());
+        assert_eq!(to.schema_version().unwrap(), SCHEMA_VERSION);
+        remove(&path);
+    }
+
+    #[test]
+    fn test_backend_available() {
+        assert!(backend_available("file"));
+        assert_eq!(backend_available("sqlite"), cfg!(feature = "sqlite"));
+        assert!(!backend_available("postgres"));
+    }
+}
@@ -0,0 +1,252 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! How much of a client's IP address ends up in history, set with
+//! `[history] ip_mode`:
+//!
+//! - `full`: the address as seen by the server (default)
+//! - `truncated`: the network only (/24 for IPv4, /48 for IPv6)
+//! - `hashed`: a salted hash, so per-client features still work but the
+//!   address can't be read back; the salt is stored next to the history data
+//! - `none`: no client identifier at all
+//!
+//! Users logged in through an auth proxy (`[server] identity_header`) are
+//! stored by their user name instead, whatever the mode: they have already
+//! identified themselves, and their history should follow them across devices.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::BunnylolConfig;
+
+/// Stored in place of a user when `ip_mode = "none"`
+pub const ANONYMOUS_USER: &str = "-";
+
+const SALT_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IpMode {
+    Full,
+    Truncated,
+    Hashed([u8; SALT_LEN]),
+    Omitted,
+}
+
+/// Turns a client IP into the identifier stored in history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAnonymizer {
+    mode: IpMode,
+}
+
+impl Default for UserAnonymizer {
+    fn default() -> Self {
+        Self { mode: IpMode::Full }
+    }
+}
+
+impl UserAnonymizer {
+    /// Build from `[history] ip_mode`, loading (or creating) the salt for
+    /// "hashed". Falls back to storing nothing if the mode is unknown or the
+    /// salt can't be read, rather than storing raw addresses.
+    pub fn from_config(config: &BunnylolConfig) -> Self {
+        let mode = match config.history.ip_mode.as_str() {
+            "full" => IpMode::Full,
+            "truncated" => IpMode::Truncated,
+            "hashed" => match BunnylolConfig::get_history_salt_path()
+                .ok_or_else(|| "could not determine the data directory".to_string())
+                .and_then(|path| load_or_create_salt(&path))
+            {
+                Ok(salt) => IpMode::Hashed(salt),
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Failed to load history salt ({}), not storing client IPs",
+                        e
+                    );
+                    IpMode::Omitted
+                }
+            },
+            "none" => IpMode::Omitted,
+            other => {
+                eprintln!(
+                    "Warning: unknown history ip_mode '{}', not storing client IPs",
+                    other
+                );
+                IpMode::Omitted
+            }
+        };
+        Self { mode }
+    }
+
+    /// Hash client identifiers with the given salt
+    pub fn hashed(salt: [u8; SALT_LEN]) -> Self {
+        Self {
+            mode: IpMode::Hashed(salt),
+        }
+    }
+
+    /// Keep only the network part of client IPs
+    pub fn truncated() -> Self {
+        Self {
+            mode: IpMode::Truncated,
+        }
+    }
+
+    /// Don't store any client identifier
+    pub fn omitted() -> Self {
+        Self {
+            mode: IpMode::Omitted,
+        }
+    }
+
+    /// The identifier to store for this client, or `None` when clients
+    /// aren't identified at all (so per-client views should be skipped)
+    pub fn anonymize(&self, user: &str) -> Option<String> {
+        match &self.mode {
+            IpMode::Full => Some(user.to_string()),
+            IpMode::Truncated => Some(truncate_ip(user)),
+            IpMode::Hashed(salt) => Some(hash_user(salt, user)),
+            IpMode::Omitted => None,
+        }
+    }
+
+    /// The identifier to store for a request: the logged-in user when there
+    /// is one, otherwise the anonymized client IP
+    pub fn identify(&self, logged_in_user: Option<&str>, ip: &str) -> Option<String> {
+        match logged_in_user
+            .map(str::trim)
+            .filter(|user| !user.is_empty())
+        {
+            Some(user) => Some(user.to_string()),
+            None => self.anonymize(ip),
+        }
+    }
+}
+
+/// Zero the host part of an address; anything that isn't an IP is kept as-is
+fn truncate_ip(user: &str) -> String {
+    match user.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            let [a, b, c, _] = ip.octets();
+            Ipv4Addr::new(a, b, c, 0).to_string()
+        }
+        Ok(IpAddr::V6(ip)) => {
+            let [a, b, c, ..] = ip.segments();
+            Ipv6Addr::new(a, b, c, 0, 0, 0, 0, 0).to_string()
+        }
+        Err(_) => user.to_string(),
+    }
+}
+
+/// First 8 bytes of SHA-256(salt || user), hex encoded
+fn hash_user(salt: &[u8], user: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(user.as_bytes())
+        .finalize();
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Read the hex-encoded salt at `path`, generating one on first use
+fn load_or_create_salt(path: &Path) -> Result<[u8; SALT_LEN], String> {
+    if path.exists() {
+        let hex = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        return parse_salt(hex.trim()).ok_or_else(|| format!("{} is corrupt", path.display()));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::fill(&mut salt).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let hex: String = salt.iter().map(|b| format!("{:02x}", b)).collect();
+    std::fs::write(path, hex).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(salt)
+}
+
+fn parse_salt(hex: &str) -> Option<[u8; SALT_LEN]> {
+    if hex.len() != SALT_LEN * 2 {
+        return None;
+    }
+    let mut salt = [0u8; SALT_LEN];
+    for (i, byte) in salt.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(salt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_ip() {
+        assert_eq!(truncate_ip("203.0.113.42"), "203.0.113.0");
+        assert_eq!(truncate_ip("2001:db8:abcd:12::1"), "2001:db8:abcd::");
+        assert_eq!(truncate_ip("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_hashed_is_stable_per_salt() {
+        let anonymizer = UserAnonymizer::hashed([7; SALT_LEN]);
+        let hashed = anonymizer.anonymize("203.0.113.42").unwrap();
+        assert_eq!(hashed.len(), 16);
+        assert!(!hashed.contains("203"));
+        assert_eq!(anonymizer.anonymize("203.0.113.42").unwrap(), hashed);
+        assert_ne!(anonymizer.anonymize("203.0.113.43").unwrap(), hashed);
+        assert_ne!(
+            UserAnonymizer::hashed([8; SALT_LEN])
+                .anonymize("203.0.113.42")
+                .unwrap(),
+            hashed
+        );
+    }
+
+    #[test]
+    fn test_full_and_omitted() {
+        assert_eq!(
+            UserAnonymizer::default().anonymize("10.0.0.1"),
+            Some("10.0.0.1".to_string())
+        );
+        assert_eq!(UserAnonymizer::omitted().anonymize("10.0.0.1"), None);
+    }
+
+    #[test]
+    fn test_identify_prefers_logged_in_user() {
+        let anonymizer = UserAnonymizer::omitted();
+        assert_eq!(
+            anonymizer.identify(Some("alice"), "10.0.0.1"),
+            Some("alice".to_string())
+        );
+        assert_eq!(anonymizer.identify(Some("  "), "10.0.0.1"), None);
+        assert_eq!(
+            UserAnonymizer::truncated().identify(None, "10.0.0.1"),
+            Some("10.0.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_salt_is_created_once_and_reused() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-salt-{}/history.salt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let salt = load_or_create_salt(&path).unwrap();
+        assert_eq!(load_or_create_salt(&path).unwrap(), salt);
+
+        std::fs::write(&path, "not hex").unwrap();
+        assert!(load_or_create_salt(&path).is_err());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Deleting history on request, for `bunnylol history purge` and
+//! `DELETE /api/v1/history`. Every purge is appended to an audit log next to
+//! the history data, so there is a record of what was deleted and who asked.
+
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+
+use crate::config::BunnylolConfig;
+use crate::history::{History, HistoryFilter};
+use crate::history_stats::now_secs;
+
+/// Whose history a purge deletes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PurgeTarget {
+    /// Entries recorded for one user, as stored (an IP after `ip_mode`, or a
+    /// logged-in user name)
+    User(String),
+    /// Entries a filter matches, leaving frecency scores alone
+    Matching(HistoryFilter),
+    /// Every entry
+    All,
+}
+
+impl fmt::Display for PurgeTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PurgeTarget::User(user) => write!(f, "user:{}", user),
+            PurgeTarget::Matching(filter) => {
+                let mut conditions = Vec::new();
+                if let Some(since) = filter.since {
+                    conditions.push(format!("since={}", since));
+                }
+                if let Some(command) = &filter.command {
+                    conditions.push(format!("command={}", command));
+                }
+                if let Some(user) = &filter.user {
+                    conditions.push(format!("user={}", user));
+                }
+                write!(f, "matching:{}", conditions.join(","))
+            }
+            PurgeTarget::All => write!(f, "all"),
+        }
+    }
+}
+
+/// Delete the target's entries, returning how many were removed
+pub fn purge(history: &History, target: &PurgeTarget) -> Result<usize, String> {
+    match target {
+        PurgeTarget::User(user) => history.remove_user(user),
+        PurgeTarget::Matching(filter) => history.remove_matching(filter),
+        PurgeTarget::All => history.clear(),
+    }
+}
+
+/// Log a completed purge and append it to the audit log. Failing to write
+/// the audit log only warns: the entries are already gone.
+pub fn record_purge(target: &PurgeTarget, requested_by: &str, removed: usize) {
+    println!(
+        "History purge of {} requested by {}: removed {} entries",
+        target, requested_by, removed
+    );
+    let Some(path) = BunnylolConfig::get_history_audit_log_path() else {
+        eprintln!("Warning: could not determine the data directory, purge not audited");
+        return;
+    };
+    if let Err(e) = append_audit_record(&path, now_secs(), target, requested_by, removed) {
+        eprintln!("Warning: Failed to write history audit log: {}", e);
+    }
+}
+
+/// Append `timestamp|requested_by|target|removed` to the audit log at `path`,
+/// with the text fields escaped so each purge stays on one line
+fn append_audit_record(
+    path: &Path,
+    timestamp: u64,
+    target: &PurgeTarget,
+    requested_by: &str,
+    removed: usize,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    writeln!(
+        file,
+        "{}|{}|{}|{}",
+        timestamp,
+        escape_field(requested_by),
+        escape_field(&target.to_string()),
+        removed
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// `field` with backslashes, `|`, and control characters backslash-escaped,
+/// since user names come from request headers
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '|' => escaped.push_str("\\|"),
+            c if c.is_control() => escaped.extend(c.escape_default()),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_user_and_all() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-purge-history-{}", std::process::id()));
+        let history = History::with_path(path, 100);
+        history.clear().unwrap();
+        for (command, user) in [("gh", "10.0.0.1"), ("yt", "alice"), ("ig", "10.0.0.1")] {
+            history.add(command, user).unwrap();
+        }
+
+        let target = PurgeTarget::User("10.0.0.1".to_string());
+        assert_eq!(purge(&history, &target).unwrap(), 2);
+        assert_eq!(purge(&history, &PurgeTarget::All).unwrap(), 1);
+        assert!(history.read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_purge_matching() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-purge-matching-{}", std::process::id()));
+        let history = History::with_path(path, 100);
+        history.clear().unwrap();
+        for (command, user) in [("gh rust", "10.0.0.1"), ("yt", "alice"), ("GH", "alice")] {
+            history.add(command, user).unwrap();
+        }
+
+        let target = PurgeTarget::Matching(HistoryFilter {
+            command: Some("gh".to_string()),
+            user: Some("alice".to_string()),
+            ..HistoryFilter::default()
+        });
+        assert_eq!(target.to_string(), "matching:command=gh,user=alice");
+        assert_eq!(purge(&history, &target).unwrap(), 1);
+        let left: Vec<String> = history
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.command)
+            .collect();
+        assert_eq!(left, ["gh rust", "yt"]);
+        history.clear().unwrap();
+    }
+
+    #[test]
+    fn test_audit_records_are_appended() {
+        let path = std::env::temp_dir().join(format!(
+            "bunnylol-purge-audit-{}/audit.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let target = PurgeTarget::User("10.0.0.1".to_string());
+        append_audit_record(&path, 100, &target, "cli", 2).unwrap();
+        append_audit_record(&path, 200, &PurgeTarget::All, "127.0.0.1", 5).unwrap();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log, "100|cli|user:10.0.0.1|2\n200|127.0.0.1|all|5\n");
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_audit_records_escape_user_names() {
+        let path = std::env::temp_dir().join(format!(
+            "bunnylol-purge-audit-escape-{}/audit.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let target = PurgeTarget::User("eve|1\n2\nforged|0".to_string());
+        append_audit_record(&path, 100, &target, "a\\b|c", 0).unwrap();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(log, "100|a\\\\b\\|c|user:eve\\|1\\n2\\nforged\\|0|0\n");
+        assert_eq!(log.lines().count(), 1);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,397 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Redis-backed command history, selected with `[history] backend = "redis"`.
+//!
+//! Entries live in a capped stream, so several bunnylol replicas behind a load
+//! balancer can share one history. Stream IDs keep entries in insertion order.
+
+use std::sync::Mutex;
+
+use redis::streams::{StreamId, StreamMaxlen, StreamRangeReply};
+use redis::{Commands, Connection};
+
+use crate::history::{
+    HistoryEntry, HistoryFilter, HistoryStore, SCHEMA_VERSION, UnknownCommand, top_of,
+};
+use crate::history_stats::{CommandScore, FRECENCY_HALF_LIFE, FrecencyScore};
+
+/// Stream key shared by every replica pointed at the same Redis
+pub const HISTORY_STREAM_KEY: &str = "bunnylol:history";
+
+/// Sorted set of unknown command tokens, scored by how often they were run
+pub const UNKNOWN_COMMANDS_KEY: &str = "bunnylol:unknown";
+
+/// Schema version of the data under the `bunnylol:` keys
+pub const SCHEMA_VERSION_KEY: &str = "bunnylol:schema-version";
+
+/// Prefix of the per-user frecency hashes (`bunnylol:frecency:<user>`), each
+/// mapping a command to `last_used|count|score`
+pub const FRECENCY_KEY_PREFIX: &str = "bunnylol:frecency:";
+
+/// Set of users with a frecency hash, so `clear` can find them all
+pub const FRECENCY_USERS_KEY: &str = "bunnylol:frecency-users";
+
+/// Frecency hashes of clients that stop using bunnylol expire once their
+/// scores have decayed to nothing
+const FRECENCY_TTL_SECS: i64 = 10 * FRECENCY_HALF_LIFE as i64;
+
+/// History stored in a Redis stream capped at `max_entries`
+pub struct RedisHistoryStore {
+    client: redis::Client,
+    /// Reused between calls; dropped after an error so the next call reconnects
+    conn: Mutex<Option<Connection>>,
+}
+
+impl RedisHistoryStore {
+    /// Connect to the Redis server at `url` (e.g. "redis://127.0.0.1:6379/")
+    pub fn open(url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(url).map_err(|e| format!("Invalid Redis URL: {}", e))?;
+        let conn = client
+            .get_connection()
+            .map_err(|e| format!("Failed to connect to Redis: {}", e))?;
+        Ok(Self {
+            client,
+            conn: Mutex::new(Some(conn)),
+        })
+    }
+
+    fn with_conn<T>(
+        &self,
+        action: &str,
+        f: impl FnOnce(&mut Connection) -> redis::RedisResult<T>,
+    ) -> Result<T, String> {
+        let mut guard = self
+            .conn
+            .lock()
+            .expect("redis connection lock should not be poisoned");
+        let conn = match guard.as_mut() {
+            Some(conn) => conn,
+            None => guard.insert(
+                self.client
+                    .get_connection()
+                    .map_err(|e| format!("Failed to connect to Redis: {}", e))?,
+            ),
+        };
+        f(conn).map_err(|e| {
+            *guard = None;
+            format!("Failed to {}: {}", action, e)
+        })
+    }
+}
+
+/// Stream fields for an entry; `latency_us` only when it was measured
+fn entry_fields(entry: &HistoryEntry) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("timestamp", entry.timestamp.clone()),
+        ("user", entry.user.clone()),
+        ("command", entry.command.clone()),
+    ];
+    if let Some(latency_us) = entry.latency_us {
+        fields.push(("latency_us", latency_us.to_string()));
+    }
+    fields
+}
+
+fn frecency_key(user: &str) -> String {
+    format!("{}{}", FRECENCY_KEY_PREFIX, user)
+}
+
+fn frecency_value(score: &FrecencyScore) -> String {
+    format!("{}|{}|{}", score.last_used, score.count, score.score)
+}
+
+fn frecency_from_value(command: String, value: &str) -> Option<FrecencyScore> {
+    let mut parts = value.splitn(3, '|');
+    Some(FrecencyScore {
+        command,
+        last_used: parts.next()?.parse().ok()?,
+        count: parts.next()?.parse().ok()?,
+        score: parts.next()?.parse().ok()?,
+    })
+}
+
+/// Rebuild an entry from its stream fields, skipping malformed messages
+fn entry_from_stream(id: &StreamId) -> Option<HistoryEntry> {
+    Some(HistoryEntry {
+        timestamp: id.get("timestamp")?,
+        user: id.get("user")?,
+        command: id.get("command")?,
+        latency_us: id.get("latency_us"),
+    })
+}
+
+impl HistoryStore for RedisHistoryStore {
+    fn append(&self, entry: &HistoryEntry, max_entries: usize) -> Result<(), String> {
+        self.with_conn("write history", |conn| {
+            redis::pipe()
+                .xadd_maxlen(
+                    HISTORY_STREAM_KEY,
+                    StreamMaxlen::Equals(max_entries),
+                    "*",
+                    &entry_fields(entry),
+                )
+                .ignore()
+                // A new stream is written with the current schema
+                .set_nx(SCHEMA_VERSION_KEY, SCHEMA_VERSION)
+                .ignore()
+                .query::<()>(conn)
+        })
+    }
+
+    fn schema_version(&self) -> Result<u32, String> {
+        self.with_conn("read history schema", |conn| {
+            let version: Option<u32> = conn.get(SCHEMA_VERSION_KEY)?;
+            match version {
+                Some(version) => Ok(version),
+                // A stream without a version predates versioning
+                None if conn.exists(HISTORY_STREAM_KEY)? => Ok(0),
+                None => Ok(SCHEMA_VERSION),
+            }
+        })
+    }
+
+    fn migrate_schema(&self) -> Result<(), String> {
+        // v0 streams are valid v1 streams; only the version needs recording
+        self.with_conn("update history schema", |conn| {
+            conn.set::<_, _, ()>(SCHEMA_VERSION_KEY, SCHEMA_VERSION)
+        })
+    }
+
+    fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
+        let reply: StreamRangeReply =
+            self.with_conn("read history", |conn| conn.xrange_all(HISTORY_STREAM_KEY))?;
+        Ok(reply.ids.iter().filter_map(entry_from_stream).collect())
+    }
+
+    fn clear(&self) -> Result<usize, String> {
+        self.with_conn("clear history", |conn| {
+            let removed: usize = conn.xlen(HISTORY_STREAM_KEY)?;
+            let users: Vec<String> = conn.smembers(FRECENCY_USERS_KEY)?;
+            let mut keys: Vec<String> = users.iter().map(|user| frecency_key(user)).collect();
+            keys.extend(
+                [HISTORY_STREAM_KEY, UNKNOWN_COMMANDS_KEY, FRECENCY_USERS_KEY].map(str::to_string),
+            );
+            conn.del::<_, ()>(keys)?;
+            Ok(removed)
+        })
+    }
+
+    fn record_frecency(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
+        let mut by_user: std::collections::BTreeMap<&str, Vec<HistoryEntry>> = Default::default();
+        for entry in entries {
+            by_user.entry(&entry.user).or_default().push(entry.clone());
+        }
+        self.with_conn("update frecency", |conn| {
+            for (user, entries) in by_user {
+                let key = frecency_key(user);
+                let stored: Vec<(String, String)> = conn.hgetall(&key)?;
+                let scores = stored
+                    .into_iter()
+                    .filter_map(|(command, value)| frecency_from_value(command, &value))
+                    .map(|score| (user.to_string(), score))
+                    .collect();
+                let scores = crate::history::bump_frecency(scores, &entries, max_entries);
+
+                let mut pipe = redis::pipe();
+                pipe.atomic().del(&key).ignore();
+                let fields: Vec<(&str, String)> = scores
+                    .iter()
+                    .map(|(_, score)| (score.command.as_str(), frecency_value(score)))
+                    .collect();
+                if !fields.is_empty() {
+                    pipe.hset_multiple(&key, &fields)
+                        .ignore()
+                        .expire(&key, FRECENCY_TTL_SECS)
+                        .ignore();
+                }
+                pipe.sadd(FRECENCY_USERS_KEY, user).ignore();
+                pipe.query::<()>(conn)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn frecency_for_user(&self, user: &str) -> Result<Vec<FrecencyScore>, String> {
+        let stored: Vec<(String, String)> =
+            self.with_conn("read frecency", |conn| conn.hgetall(frecency_key(user)))?;
+        Ok(stored
+            .into_iter()
+            .filter_map(|(command, value)| frecency_from_value(command, &value))
+            .collect())
+    }
+
+    fn add_unknown_counts(
+        &self,
+        counts: &[UnknownCommand],
+        max_entries: usize,
+    ) -> Result<(), String> {
+        self.with_conn("record unknown commands", |conn| {
+            let mut pipe = redis::pipe();
+            for unknown in counts {
+                pipe.zincr(UNKNOWN_COMMANDS_KEY, &unknown.command, unknown.count)
+                    .ignore();
+            }
+            // Keep only the `max_entries` highest-scored tokens
+            let keep = isize::try_from(max_entries).unwrap_or(isize::MAX);
+            pipe.zremrangebyrank(UNKNOWN_COMMANDS_KEY, 0, -keep.saturating_add(1))
+                .ignore();
+            pipe.query::<()>(conn)
+        })
+    }
+
+    fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let stop = isize::try_from(limit - 1).unwrap_or(isize::MAX);
+        let top: Vec<(String, f64)> = self.with_conn("read unknown commands", |conn| {
+            conn.zrevrange_withscores(UNKNOWN_COMMANDS_KEY, 0, stop)
+        })?;
+        Ok(top
+            .into_iter()
+            .map(|(command, count)| UnknownCommand {
+                command,
+                count: count as u64,
+            })
+            .collect())
+    }
+
+    fn prune(&self, cutoff: Option<u64>, max_entries: usize) -> Result<usize, String> {
+        self.with_conn("prune history", |conn| {
+            // Stream IDs start with the insertion time in milliseconds
+            let expired: usize = match cutoff {
+                Some(cutoff) => redis::cmd("XTRIM")
+                    .arg(HISTORY_STREAM_KEY)
+                    .arg("MINID")
+                    .arg(cutoff.saturating_mul(1000))
+                    .query(conn)?,
+                None => 0,
+            };
+            let over_cap: usize =
+                conn.xtrim(HISTORY_STREAM_KEY, StreamMaxlen::Equals(max_entries))?;
+            Ok(expired + over_cap)
+        })
+    }
+
+    fn top_commands(
+        &self,
+        since: Option<u64>,
+        now: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandScore>, String> {
+        // Stream IDs start with the insertion time, so only read the window
+        let start = since.map_or("-".to_string(), |since| {
+            since.saturating_mul(1000).to_string()
+        });
+        let reply: StreamRangeReply = self.with_conn("read history", |conn| {
+            conn.xrange(HISTORY_STREAM_KEY, start, "+")
+        })?;
+        let entries: Vec<HistoryEntry> = reply.ids.iter().filter_map(entry_from_stream).collect();
+        Ok(top_of(&entries, since, now, limit))
+    }
+
+    fn remove_user(&self, user: &str) -> Result<usize, String> {
+        self.with_conn("purge history", |conn| {
+            redis::pipe()
+                .del(frecency_key(user))
+                .ignore()
+                .srem(FRECENCY_USERS_KEY, user)
+                .ignore()
+                .query::<()>(conn)?;
+            let reply: StreamRangeReply = conn.xrange_all(HISTORY_STREAM_KEY)?;
+            let ids: Vec<&str> = reply
+                .ids
+                .iter()
+                .filter(|id| id.get::<String>("user").as_deref() == Some(user))
+                .map(|id| id.id.as_str())
+                .collect();
+            if ids.is_empty() {
+                return Ok(0);
+            }
+            conn.xdel(HISTORY_STREAM_KEY, &ids)
+        })
+    }
+
+    fn remove_matching(&self, filter: &HistoryFilter) -> Result<usize, String> {
+        self.with_conn("clear history", |conn| {
+            let reply: StreamRangeReply = conn.xrange_all(HISTORY_STREAM_KEY)?;
+            let ids: Vec<&str> = reply
+                .ids
+                .iter()
+                .filter(|id| entry_from_stream(id).is_some_and(|entry| filter.matches(&entry)))
+                .map(|id| id.id.as_str())
+                .collect();
+            if ids.is_empty() {
+                return Ok(0);
+            }
+            conn.xdel(HISTORY_STREAM_KEY, &ids)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn stream_id(fields: &[(&str, &str)]) -> StreamId {
+        StreamId {
+            id: "1700000000000-0".to_string(),
+            map: fields
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.to_string(),
+                        redis::Value::BulkString(v.as_bytes().to_vec()),
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_stream_fields_roundtrip() {
+        let entry = HistoryEntry {
+            timestamp: "1700000000".to_string(),
+            user: "10.0.0.1".to_string(),
+            command: "gh facebook/react".to_string(),
+            latency_us: Some(250),
+        };
+
+        let fields = entry_fields(&entry);
+        let fields: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let parsed = entry_from_stream(&stream_id(&fields));
+        assert_eq!(parsed, Some(entry));
+    }
+
+    #[test]
+    fn test_frecency_value_roundtrip() {
+        let mut score = FrecencyScore::new("gh");
+        score.bump(1_700_000_000);
+        let value = frecency_value(&score);
+        assert_eq!(
+            frecency_from_value("gh".to_string(), &value),
+            Some(score.clone())
+        );
+        assert_eq!(frecency_from_value("gh".to_string(), "1|x|1"), None);
+        assert_eq!(
+            crate::history_stats::rank_frecency([score], 1_700_000_000, 1)[0].score,
+            100
+        );
+    }
+
+    #[test]
+    fn test_malformed_stream_message_is_skipped() {
+        assert_eq!(entry_from_stream(&stream_id(&[("command", "gh")])), None);
+    }
+
+    #[test]
+    fn test_open_rejects_invalid_url() {
+        assert!(RedisHistoryStore::open("not a url").is_err());
+    }
+}
@@ -0,0 +1,357 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The usage report printed by `bunnylol stats`: how much bunnylol was used
+//! over the last few days compared with the days before, which commands,
+//! which queries matched nothing, and at what time of day.
+//!
+//! Hours are UTC, like history timestamps.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::BunnylolCommandRegistry;
+use crate::config::BunnylolConfig;
+use crate::history::HistoryEntry;
+use crate::history_stats::DAY_SECS;
+use crate::utils::get_command_from_query_string;
+
+/// Hours listed under "Busiest hours"
+const BUSIEST_HOURS: usize = 5;
+
+/// Uses of one binding in the report's period and the one before
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandTrend {
+    pub command: String,
+    pub count: usize,
+    pub previous_count: usize,
+}
+
+/// Queries in one hour of the day (UTC)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HourCount {
+    pub hour: u8,
+    pub count: usize,
+}
+
+/// A command that matched nothing and fell through to the default search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissCount {
+    pub command: String,
+    pub count: usize,
+}
+
+/// Usage over the last `days` days, compared with the `days` before
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UsageReport {
+    pub days: u64,
+    /// Start of the period, in seconds since the epoch
+    pub since: u64,
+    pub total: usize,
+    pub previous_total: usize,
+    /// Clients (users or anonymized IPs) that ran a query in the period
+    pub users: usize,
+    /// Most used bindings, most used first
+    pub top_commands: Vec<CommandTrend>,
+    /// Most frequent misses, most frequent first
+    pub unknown_commands: Vec<MissCount>,
+    /// Hours of the day with the most queries, busiest first
+    pub busiest_hours: Vec<HourCount>,
+}
+
+impl UsageReport {
+    /// Summarize `entries` for the `days` days up to `now`, keeping `limit`
+    /// commands and misses
+    ///
+    /// A query is a miss when its command, after alias expansion, isn't a
+    /// binding, prefix, keyword, or plugin: the same test the server uses to
+    /// record unknown commands.
+    pub fn new(
+        entries: &[HistoryEntry],
+        config: &BunnylolConfig,
+        now: u64,
+        days: u64,
+        limit: usize,
+    ) -> Self {
+        let since = now.saturating_sub(days * DAY_SECS);
+        let previous_since = since.saturating_sub(days * DAY_SECS);
+
+        let mut total = 0;
+        let mut previous_total = 0;
+        let mut users: Vec<&str> = Vec::new();
+        let mut commands: HashMap<&str, CommandTrend> = HashMap::new();
+        let mut misses: HashMap<String, usize> = HashMap::new();
+        let mut hours = [0usize; 24];
+
+        for entry in entries {
+            let Ok(timestamp) = entry.timestamp.parse::<u64>() else {
+                continue;
+            };
+            let Some(binding) = entry.command.split_whitespace().next() else {
+                continue;
+            };
+            if timestamp < previous_since || timestamp > now {
+                continue;
+            }
+            let trend = commands.entry(binding).or_insert_with(|| CommandTrend {
+                command: binding.to_string(),
+                count: 0,
+                previous_count: 0,
+            });
+            if timestamp < since {
+                previous_total += 1;
+                trend.previous_count += 1;
+                continue;
+            }
+
+            total += 1;
+            trend.count += 1;
+            users.push(&entry.user);
+            hours[((timestamp % DAY_SECS) / 3600) as usize] += 1;
+            let resolved = config.resolve_command(entry.command.trim());
+            let command = get_command_from_query_string(&resolved);
+            if !BunnylolCommandRegistry::has_command(command) {
+                *misses.entry(command.to_string()).or_default() += 1;
+            }
+        }
+        users.sort_unstable();
+        users.dedup();
+
+        let mut top_commands: Vec<CommandTrend> = commands
+            .into_values()
+            .filter(|trend| trend.count > 0)
+            .collect();
+        top_commands.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        top_commands.truncate(limit);
+
+        let mut unknown_commands: Vec<MissCount> = misses
+            .into_iter()
+            .map(|(command, count)| MissCount { command, count })
+            .collect();
+        unknown_commands.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.command.cmp(&b.command))
+        });
+        unknown_commands.truncate(limit);
+
+        let mut busiest_hours: Vec<HourCount> = hours
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(hour, count)| HourCount {
+                hour: hour as u8,
+                count: *count,
+            })
+            .collect();
+        busiest_hours.sort_by(|a, b| b.count.cmp(&a.count).then(a.hour.cmp(&b.hour)));
+        busiest_hours.truncate(BUSIEST_HOURS);
+
+        UsageReport {
+            days,
+            since,
+            total,
+            previous_total,
+            users: users.len(),
+            top_commands,
+            unknown_commands,
+            busiest_hours,
+        }
+    }
+}
+
+/// How `count` compares with `previous`, e.g. "+25%", or "new" when there
+/// was nothing before
+fn change(count: usize, previous: usize) -> String {
+    if previous == 0 {
+        return if count == 0 { "—" } else { "new" }.to_string();
+    }
+    let pct = (count as f64 - previous as f64) * 100.0 / previous as f64;
+    format!("{:+.0}%", pct)
+}
+
+impl fmt::Display for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let period = if self.days == 1 {
+            "day".to_string()
+        } else {
+            format!("{} days", self.days)
+        };
+        writeln!(
+            f,
+            "Last {}: {} queries from {} {} ({} vs. {} the {} before)",
+            period,
+            self.total,
+            self.users,
+            if self.users == 1 { "client" } else { "clients" },
+            change(self.total, self.previous_total),
+            self.previous_total,
+            period
+        )?;
+        if self.total == 0 {
+            return Ok(());
+        }
+
+        let width = self
+            .top_commands
+            .iter()
+            .map(|trend| trend.command.len())
+            .chain(["Top commands".len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<width$}  {:>6}  {:>8}  {:>6}",
+            "Top commands",
+            "uses",
+            "previous",
+            "change",
+            width = width
+        )?;
+        for trend in &self.top_commands {
+            writeln!(
+                f,
+                "{:<width$}  {:>6}  {:>8}  {:>6}",
+                trend.command,
+                trend.count,
+                trend.previous_count,
+                change(trend.count, trend.previous_count),
+                width = width
+            )?;
+        }
+
+        if !self.unknown_commands.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Unknown commands (sent to the default search)")?;
+            for miss in &self.unknown_commands {
+                writeln!(f, "{:>6}  {}", miss.count, miss.command)?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Busiest hours (UTC)")?;
+        for hour in &self.busiest_hours {
+            writeln!(f, "{:>6}  {:02}:00", hour.count, hour.hour)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "dev"))]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 100 * DAY_SECS;
+
+    fn entry(command: &str, timestamp: u64, user: &str) -> HistoryEntry {
+        HistoryEntry {
+            command: command.to_string(),
+            timestamp: timestamp.to_string(),
+            user: user.to_string(),
+            latency_us: None,
+        }
+    }
+
+    fn report() -> UsageReport {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mbinns".to_string());
+        // `hour` o'clock on the day that ended `days_ago` days before NOW
+        let day = |days_ago: u64, hour: u64| NOW - (days_ago + 1) * DAY_SECS + hour * 3600;
+        let entries = vec![
+            // Before the previous period
+            entry("gh old", day(20, 0), "a"),
+            // The previous period
+            entry("gh rust", day(10, 9), "a"),
+            entry("gh tokio", day(9, 9), "a"),
+            entry("yt cats", day(8, 9), "a"),
+            // This period
+            entry("gh react", day(3, 9), "a"),
+            entry("gh serde", day(2, 14), "b"),
+            entry("gh tokio", day(1, 14), "b"),
+            entry("work", day(1, 14), "a"),
+            entry("wat is this", day(1, 9), "a"),
+            entry("wat else", day(0, 10), "c"),
+            HistoryEntry {
+                timestamp: "yesterday".to_string(),
+                ..entry("gh", 0, "a")
+            },
+        ];
+        UsageReport::new(&entries, &config, NOW, 7, 2)
+    }
+
+    #[test]
+    fn test_usage_report() {
+        let report = report();
+        assert_eq!(report.since, NOW - 7 * DAY_SECS);
+        assert_eq!(
+            (report.total, report.previous_total, report.users),
+            (6, 3, 3)
+        );
+        assert_eq!(
+            report.top_commands,
+            [
+                CommandTrend {
+                    command: "gh".to_string(),
+                    count: 3,
+                    previous_count: 2
+                },
+                CommandTrend {
+                    command: "wat".to_string(),
+                    count: 2,
+                    previous_count: 0
+                },
+            ]
+        );
+        assert_eq!(
+            report.unknown_commands,
+            [MissCount {
+                command: "wat".to_string(),
+                count: 2
+            }]
+        );
+        assert_eq!(report.busiest_hours[0], HourCount { hour: 14, count: 3 });
+    }
+
+    #[test]
+    fn test_usage_report_display() {
+        let text = report().to_string();
+        assert!(
+            text.starts_with(
+                "Last 7 days: 6 queries from 3 clients (+100% vs. 3 the 7 days before)\n"
+            ),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("\ngh                 3         2    +50%\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("\nwat                2         0     new\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("\nUnknown commands (sent to the default search)\n     2  wat\n"));
+        assert!(text.contains("\nBusiest hours (UTC)\n     3  14:00\n"));
+
+        let empty = UsageReport::new(&[], &BunnylolConfig::default(), NOW, 1, 10);
+        assert_eq!(
+            empty.to_string(),
+            "Last day: 0 queries from 0 clients (— vs. 0 the day before)\n"
+        );
+    }
+}
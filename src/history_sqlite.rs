@@ -0,0 +1,666 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! SQLite-backed command history, selected with `[history] backend = "sqlite"`.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::history::{HistoryEntry, HistoryFilter, HistoryStore, SCHEMA_VERSION, UnknownCommand};
+use crate::history_stats::{self, CommandScore, DAY_SECS, FrecencyScore};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        user TEXT NOT NULL,
+        command TEXT NOT NULL,
+        latency_us INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history (timestamp);
+    CREATE INDEX IF NOT EXISTS idx_history_command ON history (command);
+    CREATE TABLE IF NOT EXISTS unknown_commands (
+        command TEXT PRIMARY KEY,
+        count INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS frecency (
+        user TEXT NOT NULL,
+        command TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        last_used INTEGER NOT NULL,
+        score REAL NOT NULL,
+        PRIMARY KEY (user, command)
+    );
+";
+
+/// History stored in a SQLite database in WAL mode
+pub struct SqliteHistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteHistoryStore {
+    /// Open (or create) the database at `path` and apply the schema
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create history directory: {}", e))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open history database: {}", e))?;
+        // WAL lets readers (the landing page, /stats) run alongside writes
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| format!("Failed to enable WAL mode: {}", e))?;
+        Self::with_connection(conn)
+    }
+
+    /// Use an in-memory database, mainly for tests
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("Failed to open history database: {}", e))?;
+        Self::with_connection(conn)
+    }
+
+    /// Apply the schema, upgrading databases written by older versions; a
+    /// database from a newer bunnylol is left untouched for `History::new`
+    /// to refuse
+    fn with_connection(conn: Connection) -> Result<Self, String> {
+        let store = Self {
+            conn: Mutex::new(conn),
+        };
+        if store.schema_version()? <= SCHEMA_VERSION {
+            store.migrate_schema()?;
+        }
+        Ok(store)
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .expect("history database lock should not be poisoned")
+    }
+}
+
+impl HistoryStore for SqliteHistoryStore {
+    fn append(&self, entry: &HistoryEntry, max_entries: usize) -> Result<(), String> {
+        self.append_batch(std::slice::from_ref(entry), max_entries)
+    }
+
+    fn append_batch(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to write history: {}", e))?;
+        {
+            let mut insert = tx
+                .prepare_cached(
+                    "INSERT INTO history (timestamp, user, command, latency_us)
+                    VALUES (?1, ?2, ?3, ?4)",
+                )
+                .map_err(|e| format!("Failed to write history: {}", e))?;
+            for entry in entries {
+                insert
+                    .execute(params![
+                        entry.timestamp.parse::<i64>().unwrap_or(0),
+                        entry.user,
+                        entry.command,
+                        entry.latency_us.map(|us| us.min(i64::MAX as u64) as i64)
+                    ])
+                    .map_err(|e| format!("Failed to write history: {}", e))?;
+            }
+        }
+        tx.execute(
+            "DELETE FROM history WHERE id NOT IN
+                (SELECT id FROM history ORDER BY id DESC LIMIT ?1)",
+            params![max_entries as i64],
+        )
+        .map_err(|e| format!("Failed to trim history: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to write history: {}", e))
+    }
+
+    fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT timestamp, user, command, latency_us FROM history ORDER BY id")
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(HistoryEntry {
+                    timestamp: row.get::<_, i64>(0)?.to_string(),
+                    user: row.get(1)?,
+                    command: row.get(2)?,
+                    latency_us: row.get::<_, Option<i64>>(3)?.map(|us| us as u64),
+                })
+            })
+            .map_err(|e| format!("Failed to read history: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read history: {}", e))?;
+        Ok(entries)
+    }
+
+    fn clear(&self) -> Result<usize, String> {
+        let err = |e: rusqlite::Error| format!("Failed to clear history: {}", e);
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(err)?;
+        let removed = tx.execute("DELETE FROM history", []).map_err(err)?;
+        tx.execute_batch("DELETE FROM unknown_commands; DELETE FROM frecency;")
+            .map_err(err)?;
+        tx.commit().map_err(err)?;
+        Ok(removed)
+    }
+
+    fn add_unknown_counts(
+        &self,
+        counts: &[UnknownCommand],
+        max_entries: usize,
+    ) -> Result<(), String> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to record unknown commands: {}", e))?;
+        {
+            let mut upsert = tx
+                .prepare_cached(
+                    "INSERT INTO unknown_commands (command, count) VALUES (?1, ?2)
+                    ON CONFLICT (command) DO UPDATE SET count = count + ?2",
+                )
+                .map_err(|e| format!("Failed to record unknown commands: {}", e))?;
+            for unknown in counts {
+                let count = i64::try_from(unknown.count).unwrap_or(i64::MAX);
+                upsert
+                    .execute(params![unknown.command, count])
+                    .map_err(|e| format!("Failed to record unknown commands: {}", e))?;
+            }
+        }
+        tx.execute(
+            "DELETE FROM unknown_commands WHERE command NOT IN
+                (SELECT command FROM unknown_commands ORDER BY count DESC, command LIMIT ?1)",
+            params![max_entries.min(i64::MAX as usize) as i64],
+        )
+        .map_err(|e| format!("Failed to record unknown commands: {}", e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to record unknown commands: {}", e))
+    }
+
+    fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT command, count FROM unknown_commands
+                ORDER BY count DESC, command LIMIT ?1",
+            )
+            .map_err(|e| format!("Failed to read unknown commands: {}", e))?;
+        let rows = stmt
+            .query_map(params![limit.min(i64::MAX as usize) as i64], |row| {
+                Ok(UnknownCommand {
+                    command: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as u64,
+                })
+            })
+            .map_err(|e| format!("Failed to read unknown commands: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read unknown commands: {}", e))
+    }
+
+    fn prune(&self, cutoff: Option<u64>, max_entries: usize) -> Result<usize, String> {
+        let conn = self.conn();
+        let expired = match cutoff {
+            Some(cutoff) => conn
+                .execute(
+                    "DELETE FROM history WHERE timestamp < ?1",
+                    params![cutoff as i64],
+                )
+                .map_err(|e| format!("Failed to prune history: {}", e))?,
+            None => 0,
+        };
+        let over_cap = conn
+            .execute(
+                "DELETE FROM history WHERE id NOT IN
+                    (SELECT id FROM history ORDER BY id DESC LIMIT ?1)",
+                params![max_entries as i64],
+            )
+            .map_err(|e| format!("Failed to prune history: {}", e))?;
+        Ok(expired + over_cap)
+    }
+
+    fn remove_user(&self, user: &str) -> Result<usize, String> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM frecency WHERE user = ?1", params![user])
+            .map_err(|e| format!("Failed to purge history: {}", e))?;
+        conn.execute("DELETE FROM history WHERE user = ?1", params![user])
+            .map_err(|e| format!("Failed to purge history: {}", e))
+    }
+
+    fn remove_matching(&self, filter: &HistoryFilter) -> Result<usize, String> {
+        let err = |e: rusqlite::Error| format!("Failed to clear history: {}", e);
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(err)?;
+        let ids: Vec<i64> = {
+            let mut stmt = tx
+                .prepare("SELECT id, timestamp, user, command FROM history")
+                .map_err(err)?;
+            stmt.query_map([], |row| {
+                let entry = HistoryEntry {
+                    timestamp: row.get::<_, i64>(1)?.to_string(),
+                    user: row.get(2)?,
+                    command: row.get(3)?,
+                    latency_us: None,
+                };
+                Ok((row.get::<_, i64>(0)?, entry))
+            })
+            .map_err(err)?
+            .filter_map(|row| match row {
+                Ok((id, entry)) => filter.matches(&entry).then_some(Ok(id)),
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(err)?
+        };
+        {
+            let mut delete = tx
+                .prepare_cached("DELETE FROM history WHERE id = ?1")
+                .map_err(err)?;
+            for id in &ids {
+                delete.execute(params![id]).map_err(err)?;
+            }
+        }
+        tx.commit().map_err(err)?;
+        Ok(ids.len())
+    }
+
+    fn record_frecency(&self, entries: &[HistoryEntry], max_entries: usize) -> Result<(), String> {
+        let err = |e: rusqlite::Error| format!("Failed to update frecency: {}", e);
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(err)?;
+        {
+            let mut select = tx
+                .prepare_cached(
+                    "SELECT count, last_used, score FROM frecency
+                    WHERE user = ?1 AND command = ?2",
+                )
+                .map_err(err)?;
+            let mut upsert = tx
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO frecency (user, command, count, last_used, score)
+                    VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(err)?;
+            for entry in entries {
+                let (Some(command), Ok(timestamp)) = (
+                    history_stats::frecency_key(entry),
+                    entry.timestamp.parse::<u64>(),
+                ) else {
+                    continue;
+                };
+                let mut score = select
+                    .query_row(params![entry.user, command], |row| {
+                        Ok(FrecencyScore {
+                            command: command.to_string(),
+                            count: row.get::<_, i64>(0)? as usize,
+                            last_used: row.get::<_, i64>(1)? as u64,
+                            score: row.get(2)?,
+                        })
+                    })
+                    .optional()
+                    .map_err(err)?
+                    .unwrap_or_else(|| FrecencyScore::new(command));
+                score.bump(timestamp);
+                upsert
+                    .execute(params![
+                        entry.user,
+                        command,
+                        score.count as i64,
+                        score.last_used as i64,
+                        score.score
+                    ])
+                    .map_err(err)?;
+            }
+        }
+        tx.execute(
+            "DELETE FROM frecency WHERE rowid NOT IN
+                (SELECT rowid FROM frecency ORDER BY last_used DESC, user, command LIMIT ?1)",
+            params![max_entries.min(i64::MAX as usize) as i64],
+        )
+        .map_err(err)?;
+        tx.commit().map_err(err)
+    }
+
+    fn schema_version(&self) -> Result<u32, String> {
+        let conn = self.conn();
+        let version: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(|e| format!("Failed to read history schema: {}", e))?;
+        if version > 0 {
+            return Ok(version);
+        }
+        // Unversioned: either brand new, or created before versioning
+        let has_history = conn
+            .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'history'")
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(|e| format!("Failed to read history schema: {}", e))?;
+        Ok(if has_history { 0 } else { SCHEMA_VERSION })
+    }
+
+    fn migrate_schema(&self) -> Result<(), String> {
+        let conn = self.conn();
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create history schema: {}", e))?;
+        // Databases created before latency was recorded lack the column
+        let has_latency = conn
+            .prepare("SELECT 1 FROM pragma_table_info('history') WHERE name = 'latency_us'")
+            .and_then(|mut stmt| stmt.exists([]))
+            .map_err(|e| format!("Failed to read history schema: {}", e))?;
+        if !has_latency {
+            conn.execute("ALTER TABLE history ADD COLUMN latency_us INTEGER", [])
+                .map_err(|e| format!("Failed to update history schema: {}", e))?;
+        }
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(|e| format!("Failed to update history schema: {}", e))
+    }
+
+    fn frecency_for_user(&self, user: &str) -> Result<Vec<FrecencyScore>, String> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare_cached("SELECT command, count, last_used, score FROM frecency WHERE user = ?1")
+            .map_err(|e| format!("Failed to read frecency: {}", e))?;
+        let rows = stmt
+            .query_map(params![user], |row| {
+                Ok(FrecencyScore {
+                    command: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as usize,
+                    last_used: row.get::<_, i64>(2)? as u64,
+                    score: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to read frecency: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read frecency: {}", e))
+    }
+
+    fn top_commands(
+        &self,
+        since: Option<u64>,
+        now: u64,
+        limit: usize,
+    ) -> Result<Vec<CommandScore>, String> {
+        // The CASE mirrors history_stats::frecency_weight
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT TRIM(command) AS cmd, COUNT(*), MAX(timestamp),
+                    SUM(CASE
+                        WHEN (?1 - timestamp) / ?2 <= 3 THEN 100
+                        WHEN (?1 - timestamp) / ?2 <= 13 THEN 70
+                        WHEN (?1 - timestamp) / ?2 <= 30 THEN 50
+                        WHEN (?1 - timestamp) / ?2 <= 89 THEN 30
+                        ELSE 10
+                    END) AS score
+                FROM history
+                WHERE timestamp >= ?3 AND cmd != ''
+                GROUP BY cmd
+                ORDER BY score DESC, MAX(timestamp) DESC, cmd ASC
+                LIMIT ?4",
+            )
+            .map_err(|e| format!("Failed to rank history: {}", e))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    now as i64,
+                    DAY_SECS as i64,
+                    since.unwrap_or(0) as i64,
+                    limit.min(i64::MAX as usize) as i64
+                ],
+                |row| {
+                    Ok(CommandScore {
+                        command: row.get(0)?,
+                        count: row.get::<_, i64>(1)? as usize,
+                        last_used: row.get::<_, i64>(2)? as u64,
+                        score: row.get::<_, i64>(3)? as u64,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to rank history: {}", e))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to rank history: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::History;
+
+    #[test]
+    fn test_sqlite_store_appends_in_order_and_trims() {
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        let history = History::with_store(Box::new(store), 2);
+        for command in ["gh", "yt cats", "ig reels"] {
+            history.add(command, "10.0.0.1").unwrap();
+        }
+
+        let entries = history.read_all().unwrap();
+        let commands: Vec<&str> = entries.iter().map(|e| e.command.as_str()).collect();
+        assert_eq!(commands, vec!["yt cats", "ig reels"]);
+        assert_eq!(entries[0].user, "10.0.0.1");
+        assert!(entries[0].timestamp.parse::<u64>().is_ok());
+
+        history.clear().unwrap();
+        assert!(history.read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_store_prunes_by_age_and_count() {
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        for (timestamp, command) in [("100", "old"), ("200", "gh"), ("300", "yt"), ("400", "ig")] {
+            let entry = HistoryEntry {
+                timestamp: timestamp.to_string(),
+                user: "10.0.0.1".to_string(),
+                command: command.to_string(),
+                latency_us: None,
+            };
+            store.append(&entry, 100).unwrap();
+        }
+
+        assert_eq!(store.prune(Some(150), 2).unwrap(), 2);
+        let commands: Vec<String> = store
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.command)
+            .collect();
+        assert_eq!(commands, vec!["yt", "ig"]);
+    }
+
+    #[test]
+    fn test_sqlite_store_removes_one_user() {
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        for user in ["10.0.0.1", "10.0.0.2", "10.0.0.1"] {
+            store
+                .append(&HistoryEntry::new("gh".to_string(), user.to_string()), 100)
+                .unwrap();
+        }
+
+        assert_eq!(store.remove_user("10.0.0.1").unwrap(), 2);
+        let entries = store.read_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].user, "10.0.0.2");
+
+        assert_eq!(store.clear().unwrap(), 1);
+        assert!(store.read_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_top_commands_matches_in_memory_ranking() {
+        let now = 1_700_000_000;
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        let mut entries = Vec::new();
+        for (days_ago, command) in [
+            (200, "gh facebook/react"),
+            (200, "gh facebook/react"),
+            (40, "yt cats"),
+            (20, "gh facebook/react"),
+            (5, "ig reels"),
+            (0, "yt cats"),
+            (0, " "),
+        ] {
+            entries.push(HistoryEntry {
+                timestamp: (now - days_ago * DAY_SECS).to_string(),
+                user: "10.0.0.1".to_string(),
+                command: command.to_string(),
+                latency_us: None,
+            });
+        }
+        store.append_batch(&entries, 100).unwrap();
+
+        let expected = crate::history::top_of(&entries, None, now, 10);
+        assert_eq!(store.top_commands(None, now, 10).unwrap(), expected);
+
+        let since = Some(now - 30 * DAY_SECS);
+        let top = store.top_commands(since, now, 1).unwrap();
+        assert_eq!(top, crate::history::top_of(&entries, since, now, 1));
+        assert_eq!(top[0].command, "yt cats");
+    }
+
+    #[test]
+    fn test_sqlite_store_counts_unknown_commands() {
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        let tokens = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        store.record_unknown(&tokens(&["jira", "wiki"]), 2).unwrap();
+        store.record_unknown(&tokens(&["jira", "zzz"]), 2).unwrap();
+
+        let top = store.top_unknown(10).unwrap();
+        let commands: Vec<(&str, u64)> =
+            top.iter().map(|u| (u.command.as_str(), u.count)).collect();
+        assert_eq!(commands, vec![("jira", 2), ("wiki", 1)]);
+
+        let wiki = UnknownCommand {
+            command: "wiki".to_string(),
+            count: 5,
+        };
+        store.add_unknown_counts(&[wiki], 2).unwrap();
+        let top = store.top_unknown(10).unwrap();
+        assert_eq!((top[0].command.as_str(), top[0].count), ("wiki", 6));
+
+        store.clear().unwrap();
+        assert!(store.top_unknown(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_frecency_matches_file_store() {
+        let now = 1_700_000_000;
+        let store = SqliteHistoryStore::open_in_memory().unwrap();
+        let mut entries = Vec::new();
+        for (days_ago, user, command) in [
+            (30, "10.0.0.1", "gh facebook/react"),
+            (2, "10.0.0.1", "yt cats"),
+            (0, "10.0.0.1", "gh facebook/react"),
+            (0, "10.0.0.1", "last"),
+            (0, "10.0.0.2", "ig reels"),
+        ] {
+            entries.push(HistoryEntry {
+                timestamp: (now - days_ago * DAY_SECS).to_string(),
+                user: user.to_string(),
+                command: command.to_string(),
+                latency_us: None,
+            });
+        }
+        store.record_frecency(&entries[..2], 100).unwrap();
+        store.record_frecency(&entries[2..], 100).unwrap();
+
+        let expected: Vec<FrecencyScore> = crate::history::bump_frecency(Vec::new(), &entries, 100)
+            .into_iter()
+            .filter(|(user, _)| user == "10.0.0.1")
+            .map(|(_, score)| score)
+            .collect();
+        assert_eq!(
+            history_stats::rank_frecency(store.frecency_for_user("10.0.0.1").unwrap(), now, 10),
+            history_stats::rank_frecency(expected, now, 10)
+        );
+
+        // Capping keeps the most recently used pairs
+        let newer = HistoryEntry {
+            timestamp: (now + 1).to_string(),
+            ..entries[4].clone()
+        };
+        store.record_frecency(&[newer], 1).unwrap();
+        assert!(store.frecency_for_user("10.0.0.1").unwrap().is_empty());
+        assert_eq!(store.frecency_for_user("10.0.0.2").unwrap()[0].count, 2);
+        store.remove_user("10.0.0.2").unwrap();
+        assert!(store.frecency_for_user("10.0.0.2").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_store_keeps_latency_and_upgrades_old_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                user TEXT NOT NULL,
+                command TEXT NOT NULL
+            );
+            INSERT INTO history (timestamp, user, command) VALUES (100, 'testuser', 'old');",
+        )
+        .unwrap();
+        let store = SqliteHistoryStore::with_connection(conn).unwrap();
+
+        let timed = HistoryEntry::new("gh".to_string(), "testuser".to_string())
+            .with_latency(std::time::Duration::from_micros(250));
+        store.append(&timed, 100).unwrap();
+
+        let entries = store.read_all().unwrap();
+        assert_eq!(entries[0].latency_us, None);
+        assert_eq!(entries[1].latency_us, Some(250));
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_sqlite_store_leaves_newer_schema_untouched() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE history (id INTEGER PRIMARY KEY); PRAGMA user_version = 99;",
+        )
+        .unwrap();
+        let store = SqliteHistoryStore::with_connection(conn).unwrap();
+
+        assert_eq!(store.schema_version().unwrap(), 99);
+        let has_frecency: bool = store
+            .conn()
+            .prepare("SELECT 1 FROM sqlite_master WHERE name = 'frecency'")
+            .and_then(|mut stmt| stmt.exists([]))
+            .unwrap();
+        assert!(!has_frecency);
+    }
+
+    #[test]
+    fn test_sqlite_store_uses_wal_and_indexes() {
+        let path =
+            std::env::temp_dir().join(format!("bunnylol-history-{}.sqlite3", std::process::id()));
+        let store = SqliteHistoryStore::open(&path).unwrap();
+        {
+            let conn = store.conn();
+            let mode: String = conn
+                .pragma_query_value(None, "journal_mode", |row| row.get(0))
+                .unwrap();
+            assert_eq!(mode, "wal");
+            let indexes: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND tbl_name = 'history' AND name LIKE 'idx_%'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(indexes, 2);
+        }
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
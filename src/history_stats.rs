@@ -0,0 +1,602 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Aggregations over command history.
+//!
+//! Frecency combines how often a command is used with how recently: every use
+//! contributes a weight that decays with its age, so a command run a handful of
+//! times today outranks one run many times last quarter.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::history::HistoryEntry;
+
+/// Seconds in a day
+pub const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Number of days covered by the per-binding usage sparklines
+pub const SPARKLINE_DAYS: usize = 30;
+
+/// Half-life of the stored frecency scores: a use counts half as much after this long
+pub const FRECENCY_HALF_LIFE: u64 = 14 * DAY_SECS;
+
+/// Aggregated usage of a single command (or binding)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandScore {
+    pub command: String,
+    pub count: usize,
+    pub last_used: u64,
+    pub score: u64,
+}
+
+/// Incrementally maintained frecency of one command for one client
+///
+/// Unlike `rank_commands`, which rescans history, this is updated as each use
+/// is recorded: every use adds 1 and the total halves every `FRECENCY_HALF_LIFE`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrecencyScore {
+    pub command: String,
+    pub count: usize,
+    pub last_used: u64,
+    /// Score as of `last_used`; decayed to the present when read
+    pub score: f64,
+}
+
+impl FrecencyScore {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            count: 0,
+            last_used: 0,
+            score: 0.0,
+        }
+    }
+
+    /// Count one use at `timestamp`
+    pub fn bump(&mut self, timestamp: u64) {
+        if timestamp >= self.last_used {
+            self.score = self.score * decay(timestamp - self.last_used) + 1.0;
+            self.last_used = timestamp;
+        } else {
+            // An older use arriving late counts for what it would be worth by now
+            self.score += decay(self.last_used - timestamp);
+        }
+        self.count += 1;
+    }
+
+    /// The score decayed to `now`
+    pub fn score_at(&self, now: u64) -> f64 {
+        self.score * decay(now.saturating_sub(self.last_used))
+    }
+
+    /// As a `CommandScore`, scaled so a single use right now is worth 100
+    /// (the same scale as `frecency_weight`)
+    pub fn to_command_score(&self, now: u64) -> CommandScore {
+        CommandScore {
+            command: self.command.clone(),
+            count: self.count,
+            last_used: self.last_used,
+            score: (self.score_at(now) * 100.0).round() as u64,
+        }
+    }
+}
+
+/// Fraction of a score left after `age_secs`
+fn decay(age_secs: u64) -> f64 {
+    0.5f64.powf(age_secs as f64 / FRECENCY_HALF_LIFE as f64)
+}
+
+/// The command a history entry contributes to the frecency store, if any;
+/// history commands (`hist`, `last`) aren't worth suggesting
+pub fn frecency_key(entry: &HistoryEntry) -> Option<&str> {
+    let command = entry.command.trim();
+    (!command.is_empty() && !is_history_command(command)).then_some(command)
+}
+
+/// Rank stored frecency scores as of `now`, highest first, keeping `limit`
+pub fn rank_frecency(
+    scores: impl IntoIterator<Item = FrecencyScore>,
+    now: u64,
+    limit: usize,
+) -> Vec<CommandScore> {
+    let mut ranked: Vec<CommandScore> = scores
+        .into_iter()
+        .map(|score| score.to_command_score(now))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.last_used.cmp(&a.last_used))
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Sum command scores per binding (first word), highest first
+pub fn group_by_binding(commands: &[CommandScore]) -> Vec<CommandScore> {
+    let mut bindings: HashMap<&str, CommandScore> = HashMap::new();
+    for command in commands {
+        let Some(binding) = command.command.split_whitespace().next() else {
+            continue;
+        };
+        let total = bindings.entry(binding).or_insert_with(|| CommandScore {
+            command: binding.to_string(),
+            count: 0,
+            last_used: 0,
+            score: 0,
+        });
+        total.count += command.count;
+        total.last_used = total.last_used.max(command.last_used);
+        total.score += command.score;
+    }
+    let mut ranked: Vec<CommandScore> = bindings.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.last_used.cmp(&a.last_used))
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    ranked
+}
+
+/// Resolution latency percentiles for one binding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyStats {
+    pub command: String,
+    pub count: usize,
+    pub p50_us: u64,
+    pub p95_us: u64,
+}
+
+/// Current time as seconds since the Unix epoch, matching history timestamps
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Short relative age such as "5m ago" or "3d ago"
+pub fn format_age(age_secs: u64) -> String {
+    match age_secs {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", age_secs / 60),
+        3_600..86_400 => format!("{}h ago", age_secs / 3_600),
+        _ => format!("{}d ago", age_secs / 86_400),
+    }
+}
+
+/// Weight of a single use that happened `age_secs` ago
+pub fn frecency_weight(age_secs: u64) -> u64 {
+    match age_secs / DAY_SECS {
+        0..=3 => 100,
+        4..=13 => 70,
+        14..=30 => 50,
+        31..=89 => 30,
+        _ => 10,
+    }
+}
+
+/// The last `n` distinct commands, most recent first
+pub fn recent_commands<'a, I>(entries: I, n: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a HistoryEntry>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    let mut commands: Vec<String> = Vec::new();
+    for entry in entries.into_iter().rev() {
+        if commands.len() >= n {
+            break;
+        }
+        if !commands.contains(&entry.command) {
+            commands.push(entry.command.clone());
+        }
+    }
+    commands
+}
+
+/// Rank full command strings (e.g. "gh facebook/react") by frecency, highest first
+pub fn rank_commands<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    now: u64,
+) -> Vec<CommandScore> {
+    rank_by(entries, now, |command| command.trim())
+}
+
+/// Rank command bindings (the first word, e.g. "gh") by frecency, highest first
+pub fn rank_bindings<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    now: u64,
+) -> Vec<CommandScore> {
+    rank_by(entries, now, |command| {
+        command.split_whitespace().next().unwrap_or("")
+    })
+}
+
+/// Bindings of commands that act on history itself (`hist`, `last`/`repeat`);
+/// history lookups skip them so they never return themselves
+pub const HISTORY_COMMANDS: &[&str] = &["hist", "last", "repeat"];
+
+/// Whether `command` is one of the `HISTORY_COMMANDS`
+pub fn is_history_command(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .next()
+        .is_some_and(|binding| HISTORY_COMMANDS.contains(&binding))
+}
+
+/// The `n`th most recent distinct command (1-based), skipping history commands
+pub fn nth_recent_command<'a, I>(entries: I, n: usize) -> Option<String>
+where
+    I: IntoIterator<Item = &'a HistoryEntry>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    let n = n.max(1);
+    let entries = entries
+        .into_iter()
+        .filter(|entry| !is_history_command(&entry.command) && !entry.command.trim().is_empty());
+    recent_commands(entries, n).into_iter().nth(n - 1)
+}
+
+/// Distinct past commands containing `query` (case-insensitive; an empty
+/// query matches everything), most recently used first. History commands
+/// are left out so the results only list things worth re-running.
+pub fn search_commands<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    query: &str,
+    now: u64,
+) -> Vec<CommandScore> {
+    let query = query.trim().to_lowercase();
+    let matching = entries.into_iter().filter(|entry| {
+        !is_history_command(&entry.command) && entry.command.to_lowercase().contains(&query)
+    });
+
+    let mut results = rank_commands(matching, now);
+    results.sort_by(|a, b| {
+        b.last_used
+            .cmp(&a.last_used)
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    results
+}
+
+/// Uses per binding for each of the last `days` days, oldest day first and
+/// today last; entries older than the window are ignored
+pub fn daily_usage<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    now: u64,
+    days: usize,
+) -> HashMap<String, Vec<u32>> {
+    let mut usage: HashMap<String, Vec<u32>> = HashMap::new();
+
+    for entry in entries {
+        let Ok(timestamp) = entry.timestamp.parse::<u64>() else {
+            continue;
+        };
+        let Some(binding) = entry.command.split_whitespace().next() else {
+            continue;
+        };
+        let age_days = (now.saturating_sub(timestamp) / DAY_SECS) as usize;
+        if age_days >= days {
+            continue;
+        }
+
+        let buckets = usage
+            .entry(binding.to_string())
+            .or_insert_with(|| vec![0; days]);
+        buckets[days - 1 - age_days] += 1;
+    }
+
+    usage
+}
+
+/// p50/p95 resolution latency per binding for entries at or after `since`,
+/// slowest p95 first; entries without a measured latency are skipped
+pub fn latency_by_binding<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    since: Option<u64>,
+) -> Vec<LatencyStats> {
+    let mut samples: HashMap<String, Vec<u64>> = HashMap::new();
+
+    for entry in entries {
+        let Some(latency_us) = entry.latency_us else {
+            continue;
+        };
+        if let Some(since) = since
+            && entry.timestamp.parse::<u64>().is_ok_and(|t| t < since)
+        {
+            continue;
+        }
+        let Some(binding) = entry.command.split_whitespace().next() else {
+            continue;
+        };
+        samples
+            .entry(binding.to_string())
+            .or_default()
+            .push(latency_us);
+    }
+
+    let mut stats: Vec<LatencyStats> = samples
+        .into_iter()
+        .map(|(command, mut samples)| {
+            samples.sort_unstable();
+            LatencyStats {
+                command,
+                count: samples.len(),
+                p50_us: percentile(&samples, 50),
+                p95_us: percentile(&samples, 95),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.p95_us
+            .cmp(&a.p95_us)
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    stats
+}
+
+/// Nearest-rank percentile of non-empty, sorted samples
+pub(crate) fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn rank_by<'a>(
+    entries: impl IntoIterator<Item = &'a HistoryEntry>,
+    now: u64,
+    key: impl Fn(&str) -> &str,
+) -> Vec<CommandScore> {
+    let mut scores: HashMap<String, CommandScore> = HashMap::new();
+
+    for entry in entries {
+        let Ok(timestamp) = entry.timestamp.parse::<u64>() else {
+            continue;
+        };
+        let key = key(&entry.command);
+        if key.is_empty() {
+            continue;
+        }
+
+        let score = scores
+            .entry(key.to_string())
+            .or_insert_with(|| CommandScore {
+                command: key.to_string(),
+                count: 0,
+                last_used: 0,
+                score: 0,
+            });
+        score.count += 1;
+        score.last_used = score.last_used.max(timestamp);
+        score.score += frecency_weight(now.saturating_sub(timestamp));
+    }
+
+    let mut ranked: Vec<CommandScore> = scores.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.last_used.cmp(&a.last_used))
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn entry(command: &str, days_ago: u64) -> HistoryEntry {
+        HistoryEntry {
+            command: command.to_string(),
+            timestamp: (NOW - days_ago * DAY_SECS).to_string(),
+            user: "10.0.0.1".to_string(),
+            latency_us: None,
+        }
+    }
+
+    #[test]
+    fn test_frecency_weight_decays() {
+        assert_eq!(frecency_weight(0), 100);
+        assert_eq!(frecency_weight(5 * DAY_SECS), 70);
+        assert_eq!(frecency_weight(20 * DAY_SECS), 50);
+        assert_eq!(frecency_weight(60 * DAY_SECS), 30);
+        assert_eq!(frecency_weight(365 * DAY_SECS), 10);
+    }
+
+    #[test]
+    fn test_rank_commands_prefers_recent_over_old_frequent() {
+        let mut entries = vec![entry("yt cats", 0), entry("yt cats", 1)];
+        entries.extend((0..5).map(|_| entry("gh facebook/react", 200)));
+
+        let ranked = rank_commands(&entries, NOW);
+        assert_eq!(ranked[0].command, "yt cats");
+        assert_eq!(ranked[0].count, 2);
+        assert_eq!(ranked[0].score, 200);
+        assert_eq!(ranked[1].command, "gh facebook/react");
+        assert_eq!(ranked[1].score, 50);
+    }
+
+    #[test]
+    fn test_rank_bindings_groups_by_first_word() {
+        let entries = vec![
+            entry("gh facebook/react", 0),
+            entry("gh rust-lang/rust", 0),
+            entry("yt cats", 0),
+            entry("", 0),
+        ];
+
+        let ranked = rank_bindings(&entries, NOW);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].command, "gh");
+        assert_eq!(ranked[0].count, 2);
+    }
+
+    #[test]
+    fn test_recent_commands_are_distinct() {
+        let entries = vec![
+            entry("gh", 3),
+            entry("yt", 2),
+            entry("gh", 1),
+            entry("ig", 0),
+        ];
+
+        assert_eq!(recent_commands(&entries, 5), vec!["ig", "gh", "yt"]);
+        assert_eq!(recent_commands(&entries, 1), vec!["ig"]);
+    }
+
+    #[test]
+    fn test_daily_usage_buckets_by_day() {
+        let entries = vec![
+            entry("gh facebook/react", 0),
+            entry("gh rust-lang/rust", 0),
+            entry("work", 2),
+            entry("gh", 29),
+            entry("gh", 30),
+        ];
+
+        let usage = daily_usage(&entries, NOW, SPARKLINE_DAYS);
+        let gh = &usage["gh"];
+        assert_eq!(gh.len(), SPARKLINE_DAYS);
+        assert_eq!(gh[SPARKLINE_DAYS - 1], 2);
+        assert_eq!(gh[0], 1);
+        assert_eq!(gh.iter().sum::<u32>(), 3);
+        assert_eq!(usage["work"][SPARKLINE_DAYS - 3], 1);
+    }
+
+    #[test]
+    fn test_search_commands_matches_and_orders_by_last_use() {
+        let entries = vec![
+            entry("gh facebook/React", 5),
+            entry("yt cats", 3),
+            entry("npm react", 1),
+            entry("gh facebook/React", 0),
+            entry("hist react", 0),
+        ];
+
+        let results = search_commands(&entries, "REACT", NOW);
+        let commands: Vec<&str> = results.iter().map(|r| r.command.as_str()).collect();
+        assert_eq!(commands, vec!["gh facebook/React", "npm react"]);
+        assert_eq!(results[0].count, 2);
+
+        assert_eq!(search_commands(&entries, "", NOW).len(), 3);
+        assert!(search_commands(&entries, "nothing", NOW).is_empty());
+    }
+
+    #[test]
+    fn test_nth_recent_command_skips_history_commands() {
+        let entries = vec![
+            entry("yt cats", 4),
+            entry("gh facebook/react", 3),
+            entry("ig reels", 2),
+            entry("gh facebook/react", 1),
+            entry("hist react", 1),
+            entry("last 2", 0),
+        ];
+
+        assert_eq!(
+            nth_recent_command(&entries, 1).as_deref(),
+            Some("gh facebook/react")
+        );
+        assert_eq!(
+            nth_recent_command(&entries, 0),
+            nth_recent_command(&entries, 1)
+        );
+        assert_eq!(nth_recent_command(&entries, 3).as_deref(), Some("yt cats"));
+        assert_eq!(nth_recent_command(&entries, 4), None);
+    }
+
+    #[test]
+    fn test_latency_by_binding_percentiles() {
+        let mut entries: Vec<HistoryEntry> = (1..=20)
+            .map(|us| {
+                entry("gh facebook/react", 0).with_latency(std::time::Duration::from_micros(us))
+            })
+            .collect();
+        entries.push(entry("yt cats", 0).with_latency(std::time::Duration::from_micros(500)));
+        entries.push(entry("yt old", 10).with_latency(std::time::Duration::from_micros(900)));
+        entries.push(entry("ig", 0));
+
+        let stats = latency_by_binding(&entries, Some(NOW - DAY_SECS));
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0],
+            LatencyStats {
+                command: "yt".to_string(),
+                count: 1,
+                p50_us: 500,
+                p95_us: 500,
+            }
+        );
+        assert_eq!(
+            (stats[1].count, stats[1].p50_us, stats[1].p95_us),
+            (20, 10, 19)
+        );
+    }
+
+    #[test]
+    fn test_frecency_score_decays_and_bumps() {
+        let mut score = FrecencyScore::new("gh");
+        score.bump(NOW);
+        score.bump(NOW);
+        assert_eq!(score.to_command_score(NOW).score, 200);
+        assert_eq!(score.to_command_score(NOW + FRECENCY_HALF_LIFE).score, 100);
+
+        // A use one half-life later adds 1 to the decayed score
+        score.bump(NOW + FRECENCY_HALF_LIFE);
+        assert_eq!(score.to_command_score(NOW + FRECENCY_HALF_LIFE).score, 200);
+        // A late-arriving older use counts at its decayed value
+        score.bump(NOW);
+        assert_eq!(score.to_command_score(NOW + FRECENCY_HALF_LIFE).score, 250);
+        assert_eq!(score.count, 4);
+        assert_eq!(score.last_used, NOW + FRECENCY_HALF_LIFE);
+    }
+
+    #[test]
+    fn test_rank_frecency_and_group_by_binding() {
+        let score = |command: &str, uses: &[u64]| {
+            let mut score = FrecencyScore::new(command);
+            uses.iter().for_each(|&t| score.bump(t));
+            score
+        };
+        let ranked = rank_frecency(
+            [
+                score(
+                    "gh facebook/react",
+                    &[NOW - 60 * DAY_SECS, NOW - 60 * DAY_SECS],
+                ),
+                score("yt cats", &[NOW]),
+                score("gh rust-lang/rust", &[NOW]),
+            ],
+            NOW,
+            10,
+        );
+        let commands: Vec<&str> = ranked.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec!["gh rust-lang/rust", "yt cats", "gh facebook/react"]
+        );
+
+        let bindings = group_by_binding(&ranked);
+        assert_eq!(bindings[0].command, "gh");
+        assert_eq!(bindings[0].count, 3);
+        assert_eq!(bindings[0].score, 100 + ranked[2].score);
+    }
+
+    #[test]
+    fn test_rank_skips_invalid_timestamps() {
+        let mut bad = entry("gh", 0);
+        bad.timestamp = "not-a-number".to_string();
+
+        assert!(rank_commands(&[bad], NOW).is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Reading keyword commands from other tools for `bunnylol import`, into the
+//! config's `[keywords]` section
+//!
+//! Three formats are understood, and told apart by their contents:
+//!
+//! - DuckDuckGo's bang list, a JSON array of `{"t": "w", "u": "https://...{{{s}}}"}`
+//! - Netscape bookmark HTML, as exported by Firefox and Chrome; only
+//!   bookmarks with a keyword (`SHORTCUTURL`) are commands
+//! - CSV rows of `keyword,url`, with an optional header row
+//!
+//! Their search placeholders (`{{{s}}}`, `%s`) become bunnylol's `{}`.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::BunnylolCommandRegistry;
+use crate::config::BunnylolConfig;
+use crate::utils::csv::csv_fields;
+use crate::utils::url_encoding::TEMPLATE_PLACEHOLDER;
+
+/// File formats keywords can be imported from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    Bangs,
+    Bookmarks,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Names accepted by `parse`, for `--format`
+    pub const NAMES: &'static [&'static str] = &["bangs", "bookmarks", "csv"];
+
+    /// Parse "bangs", "bookmarks", or "csv" (case-insensitive)
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "bangs" => Some(ImportFormat::Bangs),
+            "bookmarks" => Some(ImportFormat::Bookmarks),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Guess the format of a file from its contents
+    pub fn detect(contents: &str) -> Self {
+        let start = contents.trim_start();
+        if start.starts_with('[') {
+            return ImportFormat::Bangs;
+        }
+        let head = start
+            .chars()
+            .take(1024)
+            .collect::<String>()
+            .to_ascii_uppercase();
+        if head.starts_with('<') || head.contains("NETSCAPE-BOOKMARK-FILE") {
+            ImportFormat::Bookmarks
+        } else {
+            ImportFormat::Csv
+        }
+    }
+}
+
+/// A keyword command read from an import file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportedKeyword {
+    pub keyword: String,
+    /// The URL, with `{}` where the arguments go
+    pub url: String,
+}
+
+impl ImportedKeyword {
+    fn new(keyword: &str, url: &str) -> Self {
+        ImportedKeyword {
+            keyword: keyword.trim().to_string(),
+            url: url
+                .trim()
+                .replace("{{{s}}}", TEMPLATE_PLACEHOLDER)
+                .replace("%s", TEMPLATE_PLACEHOLDER),
+        }
+    }
+}
+
+/// One entry of DuckDuckGo's bang list; the other fields are descriptive
+#[derive(Deserialize)]
+struct Bang {
+    t: String,
+    u: String,
+}
+
+/// The keyword commands in an import file, in file order
+pub fn parse(contents: &str, format: ImportFormat) -> Result<Vec<ImportedKeyword>, String> {
+    match format {
+        ImportFormat::Bangs => {
+            let bangs: Vec<Bang> = serde_json::from_str(contents)
+                .map_err(|e| format!("Failed to parse bang JSON: {}", e))?;
+            Ok(bangs
+                .iter()
+                .map(|bang| ImportedKeyword::new(&bang.t, &bang.u))
+                .collect())
+        }
+        ImportFormat::Bookmarks => Ok(parse_bookmarks(contents)),
+        ImportFormat::Csv => Ok(contents
+            .lines()
+            .map(csv_fields)
+            .filter(|fields| fields.len() >= 2)
+            .filter(|fields| !fields[0].trim().eq_ignore_ascii_case("keyword"))
+            .map(|fields| ImportedKeyword::new(&fields[0], &fields[1]))
+            .collect()),
+    }
+}
+
+/// The bookmarks with a keyword in Netscape bookmark HTML
+fn parse_bookmarks(html: &str) -> Vec<ImportedKeyword> {
+    let upper = html.to_ascii_uppercase();
+    let mut keywords = Vec::new();
+    let mut rest = 0;
+    while let Some(start) = upper[rest..].find("<A ").map(|i| rest + i) {
+        let Some(end) = upper[start..].find('>').map(|i| start + i) else {
+            break;
+        };
+        let attributes = tag_attributes(&html[start + 3..end]);
+        if let (Some(url), Some(keyword)) = (attributes.get("HREF"), attributes.get("SHORTCUTURL"))
+        {
+            keywords.push(ImportedKeyword::new(keyword, url));
+        }
+        rest = end;
+    }
+    keywords
+}
+
+/// A tag's `NAME="value"` attributes, keyed by uppercased name
+fn tag_attributes(tag: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = tag;
+    while let Some((name, after)) = rest.split_once('=') {
+        let name = name.split_whitespace().last().unwrap_or_default();
+        let after = after.trim_start();
+        let (value, remaining) = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => after[1..].split_once(quote).unwrap_or((&after[1..], "")),
+            _ => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        attributes.insert(name.to_ascii_uppercase(), decode_entities(value));
+        rest = remaining;
+    }
+    attributes
+}
+
+/// Undo the HTML escaping bookmark exporters apply to attribute values
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// What importing a file would change
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportPlan {
+    /// Keywords to add (or point somewhere new)
+    pub added: Vec<ImportedKeyword>,
+    /// Keywords already configured with the same URL
+    pub unchanged: usize,
+    /// Keywords left out, with why
+    pub skipped: Vec<(ImportedKeyword, String)>,
+}
+
+/// Sort imported keywords into those to add and those that would collide with
+/// a binding, alias, or existing keyword, or aren't usable at all
+pub fn plan(keywords: Vec<ImportedKeyword>, config: &BunnylolConfig) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for imported in keywords {
+        let reason = if imported.keyword.is_empty()
+            || imported.keyword.contains(char::is_whitespace)
+        {
+            Some("keywords must be non-empty and contain no whitespace".to_string())
+        } else if !(imported.url.starts_with("https://") || imported.url.starts_with("http://")) {
+            Some("not an http(s) URL".to_string())
+        } else if let Some(first) = seen.get(&imported.keyword) {
+            Some(format!("listed earlier for {}", first))
+        } else if let Some(existing) = config.keywords.get(&imported.keyword) {
+            if *existing == imported.url {
+                plan.unchanged += 1;
+                seen.insert(imported.keyword.clone(), imported.url.clone());
+                continue;
+            }
+            Some(format!("already a keyword for {}", existing))
+        } else if config.aliases.contains_key(&imported.keyword) {
+            Some("already an alias".to_string())
+        } else if BunnylolCommandRegistry::has_command(&imported.keyword) {
+            Some("already a built-in command".to_string())
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => plan.skipped.push((imported, reason)),
+            None => {
+                seen.insert(imported.keyword.clone(), imported.url.clone());
+                plan.added.push(imported);
+            }
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANGS: &str = r#"[
+        {"c":"Research","d":"en.wikipedia.org","r":0,"s":"Wikipedia","sc":"Reference","t":"w","u":"https://en.wikipedia.org/wiki/Special:Search?search={{{s}}}"},
+        {"s":"GitHub","t":"gh","u":"https://github.com/search?q={{{s}}}"}
+    ]"#;
+
+    const BOOKMARKS: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<DL><p>
+    <DT><A HREF="https://mdn.example.com/search?q=%s&amp;lang=en" ADD_DATE="1700000000" SHORTCUTURL="mdn">MDN</A>
+    <DT><A HREF="https://example.com/no-keyword">No keyword</A>
+    <DT><a href='https://wiki.example.com' shortcuturl='wk'>Wiki</a>
+</DL><p>
+"#;
+
+    fn keyword(keyword: &str, url: &str) -> ImportedKeyword {
+        ImportedKeyword {
+            keyword: keyword.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(ImportFormat::detect(BANGS), ImportFormat::Bangs);
+        assert_eq!(ImportFormat::detect(BOOKMARKS), ImportFormat::Bookmarks);
+        assert_eq!(
+            ImportFormat::detect("keyword,url\nw,https://w.example.com/{}"),
+            ImportFormat::Csv
+        );
+        assert_eq!(ImportFormat::parse("CSV"), Some(ImportFormat::Csv));
+        assert_eq!(ImportFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn test_parse_bangs() {
+        assert_eq!(
+            parse(BANGS, ImportFormat::Bangs).unwrap(),
+            [
+                keyword(
+                    "w",
+                    "https://en.wikipedia.org/wiki/Special:Search?search={}"
+                ),
+                keyword("gh", "https://github.com/search?q={}"),
+            ]
+        );
+        assert!(parse("[{\"t\": 1}]", ImportFormat::Bangs).is_err());
+    }
+
+    #[test]
+    fn test_parse_bookmarks() {
+        assert_eq!(
+            parse(BOOKMARKS, ImportFormat::Bookmarks).unwrap(),
+            [
+                keyword("mdn", "https://mdn.example.com/search?q={}&lang=en"),
+                keyword("wk", "https://wiki.example.com"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "keyword,url\nw,https://w.example.com/?q=%s\n\n\"t, q\",https://t.example.com/{}\nbad\n";
+        assert_eq!(
+            parse(csv, ImportFormat::Csv).unwrap(),
+            [
+                keyword("w", "https://w.example.com/?q={}"),
+                keyword("t, q", "https://t.example.com/{}"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_plan_reports_collisions() {
+        let config = BunnylolConfig {
+            aliases: [("work".to_string(), "gh mbinns".to_string())].into(),
+            keywords: [
+                ("w".to_string(), "https://w.example.com/{}".to_string()),
+                ("kb".to_string(), "https://old.example.com/{}".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let plan = plan(
+            vec![
+                keyword("w", "https://w.example.com/{}"),
+                keyword("kb", "https://kb.example.com/{}"),
+                keyword("work", "https://work.example.com"),
+                keyword("gh", "https://github.com/search?q={}"),
+                keyword("js", "javascript:alert(1)"),
+                keyword("new", "https://new.example.com/{}"),
+                keyword("new", "https://other.example.com/{}"),
+            ],
+            &config,
+        );
+        assert_eq!(plan.added, [keyword("new", "https://new.example.com/{}")]);
+        assert_eq!(plan.unchanged, 1);
+        let skipped: Vec<(&str, &str)> = plan
+            .skipped
+            .iter()
+            .map(|(imported, reason)| (imported.keyword.as_str(), reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("kb", "already a keyword for https://old.example.com/{}"),
+                ("work", "already an alias"),
+                ("gh", "already a built-in command"),
+                ("js", "not an http(s) URL"),
+                ("new", "listed earlier for https://new.example.com/{}"),
+            ]
+        );
+    }
+}
@@ -5,11 +5,67 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+//! Bunnylol turns short queries like `gh facebook/react` into URLs.
+//!
+//! Other programs (launchers, TUIs, browser-extension native hosts) can embed
+//! resolution with [`resolve`], which needs neither the `server` nor the `cli`
+//! feature:
+//!
+//! ```
+//! use bunnylol::{BunnylolConfig, ResolutionLayer};
+//!
+//! let config = BunnylolConfig::default();
+//! let resolution = bunnylol::resolve("ddg rust traits", &config);
+//! assert_eq!(resolution.layer, ResolutionLayer::Builtin);
+//! assert_eq!(
+//!     resolution.url(),
+//!     Some("https://duckduckgo.com/?q=rust%20traits")
+//! );
+//! ```
+//!
+//! [`BunnylolConfig::load`] reads the user's config file, so aliases and the
+//! default search engine match their browser and terminal.
+
+// Lets #[derive(BunnylolCommand)] refer to `::bunnylol` from inside this crate too
+extern crate self as bunnylol;
+
+#[cfg(feature = "cli")]
+pub mod bench;
+#[cfg(feature = "cli")]
+pub mod browser;
 pub mod bunnylol_command_registry;
 pub mod commands;
 pub mod config;
+#[cfg(feature = "cli")]
+pub mod config_file;
+#[cfg(feature = "cli")]
+pub mod doctor;
+pub mod fallback;
+#[cfg(feature = "federation")]
+pub mod federation;
 pub mod history;
+pub mod history_export;
+pub mod history_grafana;
+pub mod history_migrate;
+pub mod history_privacy;
+pub mod history_purge;
+#[cfg(feature = "redis")]
+pub mod history_redis;
+pub mod history_report;
+#[cfg(feature = "sqlite")]
+pub mod history_sqlite;
+pub mod history_stats;
+pub mod import;
+pub mod middleware;
+pub mod plugins;
+#[cfg(feature = "cli")]
+pub mod repl;
+pub mod resolver;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 pub mod utils;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm_plugins;
 
 // Server module is needed for both server runtime and CLI service management
 #[cfg(any(feature = "server", feature = "cli"))]
@@ -19,7 +75,20 @@ pub mod server;
 #[cfg(feature = "cli")]
 pub use server::service;
 
+// Used by #[derive(BunnylolCommand)] and register_command! to register commands
+#[doc(hidden)]
+pub use inventory;
+
 pub use bunnylol_command_registry::BunnylolCommandRegistry;
-pub use commands::bunnylol_command::{BunnylolCommand, BunnylolCommandInfo};
+pub use commands::bunnylol_command::{
+    BunnylolCommand, BunnylolCommandInfo, CommandCategory, CommandError,
+};
 pub use config::BunnylolConfig;
-pub use history::{History, HistoryEntry};
+pub use history::{FileHistoryStore, History, HistoryEntry, HistoryStore};
+pub use resolver::{Resolution, ResolutionLayer};
+
+/// Resolve `query` to the URL(s) it goes to, the same way the server would,
+/// without redirecting or recording history
+pub fn resolve(query: &str, config: &BunnylolConfig) -> Resolution {
+    resolver::resolve(config, query)
+}
@@ -5,14 +5,16 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{Parser, Subcommand};
 
 // BunnylolConfig is needed by both server and CLI
 use bunnylol::BunnylolConfig;
 
 // CLI-only imports
 #[cfg(feature = "cli")]
-use bunnylol::{BunnylolCommandRegistry, History, utils};
+use bunnylol::{BunnylolCommandRegistry, History, HistoryEntry, utils};
+#[cfg(feature = "cli")]
+use clap::CommandFactory;
 #[cfg(feature = "cli")]
 use clap_complete::generate;
 #[cfg(feature = "cli")]
@@ -21,6 +23,10 @@ use tabled::{
     settings::{Color, Modify, Style, Width, object::Columns},
 };
 
+/// Values of `--format` on commands that print either text or JSON
+#[cfg(feature = "cli")]
+const FORMATS: [&str; 2] = ["text", "json"];
+
 #[derive(Parser)]
 #[command(name = "bunnylol")]
 #[command(
@@ -39,6 +45,17 @@ struct Cli {
     /// List all available commands
     #[arg(short, long, global = true)]
     list: bool,
+
+    /// With --list, print one `binding<TAB>description` line per command,
+    /// alias, and keyword, for dmenu, rofi, or fzf
+    #[cfg(feature = "cli")]
+    #[arg(long)]
+    picker: bool,
+
+    /// Open the line picked from `--list --picker`, given as arguments or on stdin
+    #[cfg(feature = "cli")]
+    #[arg(long)]
+    from_picker: bool,
 }
 
 #[derive(Subcommand)]
@@ -53,15 +70,40 @@ enum Commands {
         /// Address to bind to (overrides config file)
         #[arg(short, long)]
         address: Option<String>,
+
+        /// Reload the config when it changes and print how each command resolved
+        #[arg(long)]
+        watch: bool,
+
+        /// Everything --watch does, plus debug logging of each request's route,
+        /// and opening the landing page in your browser once the server is up
+        #[arg(long, conflicts_with = "daemon")]
+        dev: bool,
+
+        /// Run in the background, writing output to --log-file (Linux and macOS)
+        #[arg(long)]
+        daemon: bool,
+
+        /// Record the server's pid here while it runs. Default with --daemon:
+        /// bunnylol.pid in the data directory
+        #[arg(long, value_name = "PATH")]
+        pid_file: Option<std::path::PathBuf>,
+
+        /// Where --daemon writes the server's output. Default: [server]
+        /// log_file, or bunnylol.log in the data directory
+        #[arg(long, value_name = "PATH", requires = "daemon")]
+        log_file: Option<std::path::PathBuf>,
     },
 
     /// List all available command bindings
     #[cfg(feature = "cli")]
     Bindings,
 
-    /// Generate shell completion scripts
+    /// Generate shell completion scripts, completing the current bindings and
+    /// aliases for `resolve` and `open` (regenerate after changing them)
     #[cfg(feature = "cli")]
-    Completion {
+    #[command(alias = "completion")]
+    Completions {
         /// Shell to generate completions for
         #[arg(value_enum)]
         shell: clap_complete::Shell,
@@ -74,6 +116,150 @@ enum Commands {
         action: ServiceAction,
     },
 
+    /// Add, remove, or list the aliases in the config file
+    #[cfg(feature = "cli")]
+    Alias {
+        #[command(subcommand)]
+        action: AliasAction,
+    },
+
+    /// Edit the config file
+    #[cfg(feature = "cli")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Print the effective command set (built-ins, aliases, keywords, prefixes,
+    /// and numbers, after overrides) for diffing configs or a team cheatsheet
+    #[cfg(feature = "cli")]
+    Export {
+        /// Output format
+        #[arg(long, default_value = "json", value_parser = bunnylol::server::export::ExportFormat::NAMES.to_vec())]
+        format: String,
+    },
+
+    /// Find commands by binding, description, tag, or example, e.g. `search package`
+    #[cfg(feature = "cli")]
+    Search {
+        /// Words to look for; a command has to match all of them
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Output format; json prints the same entries as `export`
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+
+    /// Check the config, server port, history backend, service, bindings, and
+    /// URL templates; exits 1 on errors and 2 on warnings only, for CI
+    #[cfg(feature = "cli")]
+    Doctor {
+        /// Output format; json prints the findings and the exit code
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+
+    /// Add keyword commands from DuckDuckGo bangs, browser bookmarks, or a
+    /// keyword,url CSV to the config (with --dry-run, only report what would change)
+    #[cfg(feature = "cli")]
+    Import {
+        /// The file to import, or - for stdin
+        file: String,
+        /// The file's format; detected from its contents when omitted
+        #[arg(long, value_parser = bunnylol::import::ImportFormat::NAMES.to_vec())]
+        format: Option<String>,
+    },
+
+    /// Manage command history
+    #[cfg(feature = "cli")]
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+
+    /// Report usage from the history: top commands, unknown commands, busiest
+    /// hours (UTC), and the change from the period before
+    #[cfg(feature = "cli")]
+    Stats {
+        /// How many days to report on, compared with the same number before
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+        /// How many commands and unknown commands to list
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+
+    /// Upgrade history to the current schema, or copy it to another backend
+    #[cfg(feature = "cli")]
+    Migrate {
+        /// Copy history from the configured backend into this one (e.g. sqlite)
+        #[arg(long, value_parser = bunnylol::history_migrate::BACKENDS.to_vec())]
+        to: Option<String>,
+    },
+
+    /// Print the URLs a query resolves to, the same way the server would, without opening them
+    #[cfg(feature = "cli")]
+    Resolve {
+        /// The query, e.g. `gh facebook/react`; one query per line is read from stdin when omitted
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+
+        /// Output format; json prints each query's full resolution (layer,
+        /// alias chain, command, URLs) as a JSON line
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+
+        /// Also put the resolved URLs on the clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+
+    /// Resolve queries line by line, showing the URL, the layer that matched,
+    /// and the alias expansion; `:reload` re-reads the config
+    #[cfg(feature = "cli")]
+    Repl,
+
+    /// Send a running server many queries at once (built-in commands, aliases,
+    /// and fallback searches) and report latency percentiles and errors; each
+    /// request is recorded in the server's history like any other
+    #[cfg(feature = "cli")]
+    Bench {
+        /// Queries to send instead of the default mix, e.g. "gh facebook/react"
+        queries: Vec<String>,
+
+        /// How many requests to send
+        #[arg(short = 'r', long, default_value_t = 1000)]
+        requests: usize,
+
+        /// How many requests to keep in flight at once
+        #[arg(short, long, default_value_t = 10)]
+        concurrency: usize,
+
+        /// Port of the server (overrides config file)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Address of the server (overrides config file)
+        #[arg(short, long)]
+        address: Option<String>,
+    },
+
+    /// Resolve a query and open it in the browser; a bare address like
+    /// `example.com` opens that site
+    #[cfg(feature = "cli")]
+    Open {
+        /// The query, e.g. `gh facebook/react`
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+
+        /// Put the URL on the clipboard instead of opening it
+        #[arg(long)]
+        copy: bool,
+    },
+
     /// Execute a bunnylol command
     #[cfg(feature = "cli")]
     #[command(external_subcommand)]
@@ -86,8 +272,15 @@ enum ServiceAction {
     /// Install bunnylol server as a service (uses config file for port/address)
     Install {
         /// Allow network access (bind to 0.0.0.0). Default: localhost only (127.0.0.1)
-        #[arg(short, long)]
+        #[arg(long)]
         network: bool,
+        /// Where the Windows service writes its output (saved to the config
+        /// file). Default: bunnylol.log beside the config. Linux logs to the journal
+        #[arg(long, value_name = "PATH")]
+        log_file: Option<String>,
+        /// Install a systemd user unit that runs as you, without root (Linux only)
+        #[arg(long)]
+        user: bool,
     },
     /// Uninstall bunnylol service
     Uninstall,
@@ -103,9 +296,174 @@ enum ServiceAction {
     Logs {
         #[arg(short, long)]
         follow: bool,
-        #[arg(short = 'n', long, default_value = "20")]
+        #[arg(long, default_value = "20")]
         lines: u32,
     },
+    /// Serve as the installed Windows service; started by Windows, not by hand
+    #[command(hide = true)]
+    Run,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum AliasAction {
+    /// Point an alias at a query, e.g. `alias add work gh mbinns`
+    Add {
+        /// The alias, typed in place of the query
+        name: String,
+        /// The query it expands to
+        #[arg(required = true)]
+        target: Vec<String>,
+        /// Ask the server running on this machine to pick up the change
+        #[arg(long)]
+        reload: bool,
+    },
+    /// Remove an alias
+    #[command(alias = "remove")]
+    Rm {
+        name: String,
+        /// Ask the server running on this machine to pick up the change
+        #[arg(long)]
+        reload: bool,
+    },
+    /// List the configured aliases
+    #[command(alias = "ls")]
+    List {
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Open the config in $VISUAL or $EDITOR, and replace it only if the edited
+    /// version loads (the previous one is kept as config.toml.bak)
+    Edit {
+        /// Ask the server running on this machine to pick up the change
+        #[arg(long)]
+        reload: bool,
+    },
+}
+
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum HistoryAction {
+    /// Remove entries older than the retention period and beyond max_entries
+    Prune {
+        /// Remove entries older than this many days (overrides [history] retention_days)
+        #[arg(short, long)]
+        days: Option<u64>,
+    },
+    /// Print history as CSV or JSON, for spreadsheets or analytics tools
+    Export {
+        /// Output format
+        #[arg(short, long, default_value = "csv", value_parser = ["csv", "json"])]
+        format: String,
+        /// Only include entries since an age (7d, 12h), a date (2024-01-31), or a Unix timestamp
+        #[arg(short, long)]
+        since: Option<String>,
+    },
+    /// List the most frequent commands that matched nothing and fell through to the default search
+    Unknown {
+        /// How many to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+    /// Delete history for one client, or all of it (recorded in history-audit.log)
+    #[command(group(clap::ArgGroup::new("target").required(true)))]
+    Purge {
+        /// Delete entries recorded for this client IP (as stored under [history] ip_mode)
+        #[arg(long, group = "target")]
+        ip: Option<String>,
+        /// Delete entries recorded for this user (a logged-in user name, or a stored identifier)
+        #[arg(long, group = "target")]
+        user: Option<String>,
+        /// Delete all history
+        #[arg(long, group = "target")]
+        all: bool,
+    },
+    /// List entries, newest first
+    List {
+        /// How many to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+    /// Rank the most used commands by frecency
+    Top {
+        /// How many to list
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+        /// Output format
+        #[arg(long, default_value = "text", value_parser = FORMATS.to_vec())]
+        format: String,
+    },
+    /// Delete the entries matching the filters, or all history with --all
+    /// (recorded in history-audit.log)
+    Clear {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+        /// Delete every entry, along with unknown-command counts and frecency scores
+        #[arg(long, conflicts_with_all = ["since", "command", "client"])]
+        all: bool,
+    },
+}
+
+/// Which entries `history list`, `top`, and `clear` act on
+#[cfg(feature = "cli")]
+#[derive(clap::Args)]
+struct HistoryFilterArgs {
+    /// Only entries since an age (7d, 12h), a date (2024-01-31), or a Unix timestamp
+    #[arg(long)]
+    since: Option<String>,
+    /// Only entries run with this binding, e.g. gh
+    #[arg(long)]
+    command: Option<String>,
+    /// Only entries from this client: an IP (matched as stored under [history] ip_mode) or a logged-in user
+    #[arg(long)]
+    client: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl HistoryFilterArgs {
+    fn to_filter(
+        &self,
+        config: &BunnylolConfig,
+    ) -> Result<bunnylol::history::HistoryFilter, String> {
+        use bunnylol::history_privacy::UserAnonymizer;
+
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| {
+                bunnylol::history_export::parse_since(since, bunnylol::history_stats::now_secs())
+            })
+            .transpose()?;
+        let user = match self.client.as_deref().map(str::trim) {
+            Some(ip) if ip.parse::<std::net::IpAddr>().is_ok() => Some(
+                UserAnonymizer::from_config(config)
+                    .anonymize(ip)
+                    .ok_or("History is not kept per client IP (ip_mode = \"none\")")?,
+            ),
+            client => client.map(str::to_string),
+        };
+        Ok(bunnylol::history::HistoryFilter {
+            since,
+            command: self.command.clone(),
+            user,
+        })
+    }
 }
 
 #[tokio::main]
@@ -122,19 +480,89 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // `doctor` reports these warnings itself
+    #[cfg(feature = "cli")]
+    let quiet = matches!(cli.command, Some(Commands::Doctor { .. }));
+    #[cfg(not(feature = "cli"))]
+    let quiet = false;
+
     // Initialize the global config singleton for commands that need it
     bunnylol::config::init_global_config(config.clone());
+    if !quiet {
+        for error in bunnylol::BunnylolCommandRegistry::prefix_errors(&config) {
+            eprintln!("Warning: ignoring [prefixes] entry: {}", error);
+        }
+    }
+    #[cfg(not(feature = "federation"))]
+    if config
+        .fallback
+        .iter()
+        .any(|step| matches!(step, bunnylol::config::FallbackStep::Upstream { .. }))
+    {
+        eprintln!(
+            "Warning: ignoring upstream [[fallback]] steps: bunnylol was built without the `federation` feature"
+        );
+    }
+    let extension_warnings = load_extensions(&config);
+    if !quiet {
+        for warning in &extension_warnings {
+            eprintln!("Warning: {}", warning);
+        }
+    }
 
     // Handle global --list flag
     #[cfg(feature = "cli")]
     if cli.list {
-        print_commands();
+        if cli.picker {
+            print_picker_lines(&config);
+        } else {
+            print_commands();
+        }
+        return Ok(());
+    }
+
+    // Handle --from-picker: open what a launcher returned
+    #[cfg(feature = "cli")]
+    if cli.from_picker {
+        let selection = match cli.command {
+            Some(Commands::Command(args)) => args.join(" "),
+            _ => {
+                let mut selection = String::new();
+                std::io::stdin().read_line(&mut selection)?;
+                selection
+            }
+        };
+        // Closing the launcher without picking anything isn't an error
+        let Some(query) = bunnylol::server::export::picker_selection(&selection) else {
+            return Ok(());
+        };
+        let query = open_query(&config, query.to_string());
+        execute_command(vec![query], &config, cli.dry_run).await?;
         return Ok(());
     }
 
     match cli.command {
         #[cfg(feature = "server")]
-        Some(Commands::Serve { port, address }) => {
+        Some(Commands::Serve {
+            port,
+            address,
+            watch,
+            dev,
+            daemon,
+            pid_file,
+            log_file,
+        }) => {
+            use bunnylol::server::daemon::PidFile;
+
+            let pid_file = pid_file.or_else(|| daemon.then(BunnylolConfig::get_pid_path).flatten());
+            if daemon {
+                if let Err(e) = start_daemon(&config, port, address, watch, pid_file, log_file) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
             // Override config with command-line arguments if provided
             let mut server_config = config.clone();
             if let Some(p) = port {
@@ -144,8 +572,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 server_config.server.address = a;
             }
 
+            // Removed again when the server shuts down
+            let _pid_file = match pid_file.as_deref().map(PidFile::create).transpose() {
+                Ok(pid_file) => pid_file,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
             // Launch the server
-            bunnylol::server::launch(server_config).await?;
+            bunnylol::server::launch(server_config, bunnylol::server::ServeOptions { watch, dev })
+                .await?;
             Ok(())
         }
 
@@ -156,9 +593,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         #[cfg(feature = "cli")]
-        Some(Commands::Completion { shell }) => {
-            let mut cmd = Cli::command();
+        Some(Commands::Completions { shell }) => {
+            let words = completion_words(&config);
+            let mut cmd = completion_command(&words);
             generate(shell, &mut cmd, "bunnylol", &mut std::io::stdout());
+            // Fish's generator leaves positional values out, so add them here
+            if shell == clap_complete::Shell::Fish {
+                for subcommand in ["resolve", "open"] {
+                    println!(
+                        "complete -c bunnylol -n \"__fish_bunnylol_using_subcommand {}\" -f -a \"{}\"",
+                        subcommand,
+                        words.join(" ")
+                    );
+                }
+            }
             Ok(())
         }
 
@@ -167,7 +615,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             use bunnylol::service::*;
 
             let result = match action {
-                ServiceAction::Install { network } => {
+                ServiceAction::Install {
+                    network,
+                    log_file,
+                    user,
+                } => {
                     // Use ServiceConfig with appropriate address based on --network flag
                     let service_config = ServiceConfig {
                         address: if network {
@@ -175,10 +627,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         } else {
                             "127.0.0.1".to_string() // Localhost only (secure default)
                         },
+                        log_file,
+                        user,
                         ..Default::default()
                     };
 
-                    install_systemd_service(service_config)
+                    install_service(service_config)
                 }
                 ServiceAction::Uninstall => uninstall_service(),
                 ServiceAction::Start => start_service(),
@@ -186,6 +640,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ServiceAction::Restart => restart_service(),
                 ServiceAction::Status => service_status(),
                 ServiceAction::Logs { follow, lines } => service_logs(follow, lines),
+                ServiceAction::Run => run_service(config),
+            };
+
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Stats {
+            days,
+            limit,
+            format,
+        }) => {
+            if let Err(e) = print_stats(&config, days, limit, &format) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::History { action }) => {
+            let result = match action {
+                HistoryAction::Prune { days } => prune_history(&config, days),
+                HistoryAction::Export { format, since } => {
+                    export_history(&config, &format, since.as_deref())
+                }
+                HistoryAction::Unknown { limit, format } => {
+                    list_unknown_commands(&config, limit, &format)
+                }
+                HistoryAction::Purge { ip, user, all } => {
+                    purge_history(&config, ip.as_deref(), user, all)
+                }
+                HistoryAction::List {
+                    limit,
+                    filter,
+                    format,
+                } => list_history(&config, &filter, limit, &format),
+                HistoryAction::Top {
+                    limit,
+                    filter,
+                    format,
+                } => top_history(&config, &filter, limit, &format),
+                HistoryAction::Clear { filter, all } => clear_history(&config, &filter, all),
             };
 
             if let Err(e) = result {
@@ -196,9 +698,167 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(())
         }
 
+        #[cfg(feature = "cli")]
+        Some(Commands::Migrate { to }) => {
+            if let Err(e) = migrate_history(&config, to.as_deref()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Alias { action }) => {
+            let (result, reload) = match action {
+                AliasAction::Add {
+                    name,
+                    target,
+                    reload,
+                } => (add_alias(&name, &target.join(" ")), reload),
+                AliasAction::Rm { name, reload } => (remove_alias(&name), reload),
+                AliasAction::List { format } => (list_aliases(&config, &format), false),
+            };
+
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if reload && let Err(e) = reload_server(&config) {
+                eprintln!(
+                    "Warning: could not reload the server ({}); restart it to pick up the change",
+                    e
+                );
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Config { action }) => {
+            let ConfigAction::Edit { reload } = action;
+            match edit_config() {
+                Ok(true) if reload => {
+                    if let Err(e) = reload_server(&config) {
+                        eprintln!(
+                            "Warning: could not reload the server ({}); restart it to pick up the change",
+                            e
+                        );
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Export { format }) => {
+            use bunnylol::server::export::{self, ExportFormat};
+
+            let format =
+                ExportFormat::from_extension(&format).expect("clap only accepts known formats");
+            print!("{}", export::render_bindings_export(&config, format));
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Search { query, format }) => {
+            if let Err(e) = search_commands(&config, &query.join(" "), &format) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Doctor { format }) => {
+            std::process::exit(run_doctor(&config, &extension_warnings, &format));
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Import { file, format }) => {
+            if let Err(e) = import_keywords(&config, &file, format.as_deref(), cli.dry_run) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Resolve {
+            query,
+            format,
+            copy,
+        }) => match resolve_queries(&config, query, format == "json", copy) {
+            Ok(true) => Ok(()),
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Bench {
+            queries,
+            requests,
+            concurrency,
+            port,
+            address,
+        }) => {
+            let address = address.unwrap_or_else(|| config.server.address.clone());
+            let port = port.unwrap_or(config.server.port);
+            if let Err(e) = run_bench(&config, &address, port, queries, requests, concurrency) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Repl) => {
+            use std::io::IsTerminal;
+
+            let interactive = std::io::stdin().is_terminal();
+            if interactive {
+                println!("bunnylol repl: type a query, or :help");
+            }
+            bunnylol::repl::run(
+                config,
+                std::io::stdin().lock(),
+                std::io::stdout(),
+                interactive.then_some("bunnylol> "),
+                || {
+                    let reloaded = BunnylolConfig::load()?;
+                    for error in BunnylolCommandRegistry::prefix_errors(&reloaded) {
+                        eprintln!("Warning: ignoring [prefixes] entry: {}", error);
+                    }
+                    bunnylol::config::replace_global_config(reloaded.clone());
+                    Ok(reloaded)
+                },
+            )?;
+            Ok(())
+        }
+
+        #[cfg(feature = "cli")]
+        Some(Commands::Open { query, copy }) => {
+            let query = open_query(&config, query.join(" "));
+            let urls = execute_command(vec![query], &config, cli.dry_run || copy).await?;
+            if copy
+                && !cli.dry_run
+                && let Err(e) = copy_to_clipboard(&urls.join("\n"))
+            {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
         #[cfg(feature = "cli")]
         Some(Commands::Command(args)) => {
-            execute_command(args, &config, cli.dry_run)?;
+            execute_command(args, &config, cli.dry_run).await?;
             Ok(())
         }
 
@@ -218,7 +878,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 std::process::exit(0);
             }
 
-            execute_command(args, &config, cli.dry_run)?;
+            execute_command(args, &config, cli.dry_run).await?;
             Ok(())
         }
 
@@ -232,61 +892,912 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 #[cfg(feature = "cli")]
-fn execute_command(
+fn add_alias(name: &str, target: &str) -> Result<(), String> {
+    use bunnylol::config_file::{ConfigFile, check_alias};
+
+    let target = target.trim();
+    check_alias(name, target)?;
+    let mut file = ConfigFile::open_for_writing()?;
+    let previous = file.set_alias(name, target)?;
+    file.save()?;
+    let action = if previous.is_some() {
+        "updated"
+    } else {
+        "added"
+    };
+    println!(
+        "Alias '{}' {} in {}: {}",
+        name,
+        action,
+        file.path().display(),
+        target
+    );
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn remove_alias(name: &str) -> Result<(), String> {
+    use bunnylol::config_file::ConfigFile;
+
+    let mut file = ConfigFile::open_for_writing()?;
+    if file.remove_alias(name).is_none() {
+        return Err(format!("Alias '{}' was not found", name));
+    }
+    file.save()?;
+    println!("Alias '{}' removed from {}", name, file.path().display());
+    Ok(())
+}
+
+/// Edit a copy of the config and install it once it loads, asking to edit
+/// again when it doesn't; returns whether the config changed
+#[cfg(feature = "cli")]
+fn edit_config() -> Result<bool, String> {
+    let path = BunnylolConfig::get_config_path_for_writing()
+        .ok_or("Could not determine a writable config path")?;
+    let original = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            BunnylolConfig::default().to_toml_with_comments()
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let draft = std::env::temp_dir().join(format!("bunnylol-config-{}.toml", std::process::id()));
+    std::fs::write(&draft, &original)
+        .map_err(|e| format!("Failed to write {}: {}", draft.display(), e))?;
+    let result = edit_until_valid(&path, &draft, &original);
+    let _ = std::fs::remove_file(&draft);
+    result
+}
+
+#[cfg(feature = "cli")]
+fn edit_until_valid(
+    path: &std::path::Path,
+    draft: &std::path::Path,
+    original: &str,
+) -> Result<bool, String> {
+    use bunnylol::config_file;
+
+    loop {
+        run_editor(draft)?;
+        let edited = std::fs::read_to_string(draft)
+            .map_err(|e| format!("Failed to read {}: {}", draft.display(), e))?;
+        if edited == original {
+            println!("No changes to {}", path.display());
+            return Ok(false);
+        }
+        if let Err(e) = config_file::validate(&edited) {
+            eprintln!("{}", e);
+            eprint!("What now? (e)dit again, (q)uit without saving: ");
+            let mut answer = String::new();
+            std::io::stdin()
+                .read_line(&mut answer)
+                .map_err(|e| e.to_string())?;
+            if answer.trim().eq_ignore_ascii_case("e") {
+                continue;
+            }
+            return Err(format!("{} left unchanged", path.display()));
+        }
+        let backup = config_file::replace(path, &edited)?;
+        println!("Saved {}", path.display());
+        if let Some(backup) = backup {
+            println!("Previous version kept at {}", backup.display());
+        }
+        return Ok(true);
+    }
+}
+
+/// Open `file` in $VISUAL or $EDITOR (which may carry arguments, e.g.
+/// `code --wait`), falling back to vi, and wait for it to exit
+#[cfg(feature = "cli")]
+fn run_editor(file: &std::path::Path) -> Result<(), String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(file)
+        .status()
+        .map_err(|e| format!("Failed to run editor '{}': {}", editor, e))?;
+    if !status.success() {
+        return Err(format!(
+            "Editor '{}' exited with {}; the config was left unchanged",
+            editor, status
+        ));
+    }
+    Ok(())
+}
+
+/// Start `serve` again in the background with the same options, and say
+/// where to find it
+#[cfg(feature = "server")]
+fn start_daemon(
+    config: &BunnylolConfig,
+    port: Option<u16>,
+    address: Option<String>,
+    watch: bool,
+    pid_file: Option<std::path::PathBuf>,
+    log_file: Option<std::path::PathBuf>,
+) -> Result<(), String> {
+    use bunnylol::server::daemon;
+
+    let pid_file = pid_file.ok_or("Could not determine the pid file location; pass --pid-file")?;
+    if let Some(pid) = daemon::running_pid(&pid_file) {
+        return Err(format!(
+            "bunnylol is already running (pid {}, from {})",
+            pid,
+            pid_file.display()
+        ));
+    }
+    let log_file = log_file
+        .or_else(|| config.server.log_file.as_ref().map(Into::into))
+        .or_else(BunnylolConfig::get_daemon_log_path)
+        .ok_or("Could not determine the log file location; pass --log-file")?;
+
+    let mut args = vec![
+        "serve".to_string(),
+        "--pid-file".to_string(),
+        pid_file.display().to_string(),
+    ];
+    if let Some(port) = port {
+        args.extend(["--port".to_string(), port.to_string()]);
+    }
+    if let Some(address) = address {
+        args.extend(["--address".to_string(), address]);
+    }
+    if watch {
+        args.push("--watch".to_string());
+    }
+    let pid = daemon::spawn(&args, &log_file)?;
+
+    println!("bunnylol is running in the background (pid {})", pid);
+    println!("  logs: {}", log_file.display());
+    println!("  stop: kill $(cat {})", pid_file.display());
+    Ok(())
+}
+
+/// Print every check's findings, returning the exit code
+#[cfg(feature = "cli")]
+fn run_doctor(config: &BunnylolConfig, extension_warnings: &[String], format: &str) -> i32 {
+    use bunnylol::doctor;
+
+    let config_path = BunnylolConfig::get_existing_config_path();
+    let mut findings = doctor::check_config_file(config_path.as_deref());
+    findings.push(doctor::check_port(
+        &config.server.address,
+        config.server.port,
+    ));
+    findings.push(doctor::check_history(config));
+    findings.push(doctor::check_service());
+    findings.extend(doctor::check_extensions(extension_warnings));
+    findings.extend(doctor::check_bindings(config));
+    findings.extend(doctor::check_templates(config));
+
+    let code = doctor::exit_code(&findings);
+    if format == "json" {
+        let report = serde_json::json!({ "findings": findings, "exit_code": code });
+        println!("{:#}", report);
+        return code;
+    }
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    println!();
+    println!(
+        "{}",
+        match code {
+            0 => "No problems found",
+            1 => "Found problems that need fixing",
+            _ => "Found warnings",
+        }
+    );
+    code
+}
+
+#[cfg(feature = "cli")]
+fn import_keywords(
+    config: &BunnylolConfig,
+    file: &str,
+    format: Option<&str>,
+    dry_run: bool,
+) -> Result<(), String> {
+    use bunnylol::config_file::ConfigFile;
+    use bunnylol::import::{self, ImportFormat};
+
+    let contents = if file == "-" {
+        std::io::read_to_string(std::io::stdin()).map_err(|e| e.to_string())?
+    } else {
+        std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file, e))?
+    };
+    let format = match format {
+        Some(format) => ImportFormat::parse(format)
+            .ok_or_else(|| format!("Unsupported import format '{}'", format))?,
+        None => ImportFormat::detect(&contents),
+    };
+    let plan = import::plan(import::parse(&contents, format)?, config);
+
+    let width = plan
+        .added
+        .iter()
+        .map(|keyword| &keyword.keyword)
+        .chain(plan.skipped.iter().map(|(keyword, _)| &keyword.keyword))
+        .map(String::len)
+        .max()
+        .unwrap_or(1);
+    if !plan.skipped.is_empty() {
+        println!("Skipping {}:", plan.skipped.len());
+        for (keyword, reason) in &plan.skipped {
+            println!("  {:<width$}  {}", keyword.keyword, reason, width = width);
+        }
+    }
+    if plan.unchanged > 0 {
+        println!("{} already configured", plan.unchanged);
+    }
+    if plan.added.is_empty() {
+        println!("No new keywords to import");
+        return Ok(());
+    }
+
+    let mut config_file = ConfigFile::open_for_writing()?;
+    println!(
+        "{} {} keyword{} to {}:",
+        if dry_run { "Would add" } else { "Adding" },
+        plan.added.len(),
+        if plan.added.len() == 1 { "" } else { "s" },
+        config_file.path().display()
+    );
+    for keyword in &plan.added {
+        println!(
+            "  {:<width$}  {}",
+            keyword.keyword,
+            keyword.url,
+            width = width
+        );
+    }
+    if dry_run {
+        return Ok(());
+    }
+    for keyword in &plan.added {
+        config_file.set_keyword(&keyword.keyword, &keyword.url)?;
+    }
+    config_file.save()
+}
+
+#[cfg(feature = "cli")]
+fn list_aliases(config: &BunnylolConfig, format: &str) -> Result<(), String> {
+    let mut aliases: Vec<_> = config.aliases.iter().collect();
+    aliases.sort();
+    if format == "json" {
+        let aliases: Vec<_> = aliases
+            .into_iter()
+            .map(|(name, target)| serde_json::json!({ "name": name, "target": target }))
+            .collect();
+        return print_json(&aliases);
+    }
+    if aliases.is_empty() {
+        println!("No aliases configured");
+        return Ok(());
+    }
+    let width = aliases
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(1);
+    for (name, target) in aliases {
+        println!("{:<width$}  {}", name, target, width = width);
+    }
+    Ok(())
+}
+
+/// Print `value` as pretty JSON, for `--format json`
+#[cfg(feature = "cli")]
+fn print_json(value: &impl serde::Serialize) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+/// Print the commands matching `query`, best match first
+#[cfg(feature = "cli")]
+fn search_commands(config: &BunnylolConfig, query: &str, format: &str) -> Result<(), String> {
+    let entries = bunnylol::server::export::search_entries(config, query);
+    if format == "json" {
+        return print_json(&entries);
+    }
+    if entries.is_empty() {
+        println!("No commands match '{}'", query);
+        return Ok(());
+    }
+    let bindings: Vec<String> = entries
+        .iter()
+        .map(|entry| entry.bindings.join(", "))
+        .collect();
+    let width = bindings.iter().map(String::len).max().unwrap_or(1);
+    for (bindings, entry) in bindings.iter().zip(&entries) {
+        let description: Vec<&str> = entry.description.split_whitespace().collect();
+        println!(
+            "{:<width$}  {}",
+            bindings,
+            description.join(" "),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+/// Ask the server running on this machine to re-read the config file
+#[cfg(feature = "cli")]
+fn reload_server(config: &BunnylolConfig) -> Result<(), String> {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    // A server listening on every interface is reachable over loopback too
+    let host = match config.server.address.as_str() {
+        "0.0.0.0" => "127.0.0.1",
+        "::" => "::1",
+        address => address,
+    };
+    let mut stream = TcpStream::connect((host, config.server.port))
+        .map_err(|e| format!("{}:{}: {}", host, config.server.port, e))?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .map_err(|e| e.to_string())?;
+    write!(
+        stream,
+        "POST /api/v1/reload HTTP/1.1\r\nHost: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        host
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .map_err(|e| e.to_string())?;
+    let status = response.split_whitespace().nth(1).unwrap_or_default();
+    if status == "200" {
+        println!("Server reloaded");
+        return Ok(());
+    }
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.trim())
+        .filter(|body| !body.is_empty());
+    Err(body.unwrap_or(status).to_string())
+}
+
+#[cfg(feature = "cli")]
+fn run_bench(
+    config: &BunnylolConfig,
+    address: &str,
+    port: u16,
+    queries: Vec<String>,
+    requests: usize,
+    concurrency: usize,
+) -> Result<(), String> {
+    use bunnylol::bench::{self, BenchReport, QueryMix};
+
+    // A server listening on every interface is reachable over loopback too
+    let host = match address {
+        "0.0.0.0" => "127.0.0.1",
+        "::" => "::1",
+        address => address,
+    };
+    if !bunnylol::doctor::is_bunnylol(host, port) {
+        return Err(format!(
+            "No bunnylol server is answering at {}:{}; start one with: bunnylol serve",
+            host, port
+        ));
+    }
+    let mix = if queries.is_empty() {
+        QueryMix::from_config(config)
+    } else {
+        QueryMix::new(config, queries)
+    };
+    if mix.is_empty() || requests == 0 {
+        return Err("Nothing to send".to_string());
+    }
+
+    let concurrency = concurrency.max(1);
+    let start = std::time::Instant::now();
+    let samples = bench::run(host, port, &mix, requests, concurrency);
+    let report = BenchReport::new(
+        format!("{}:{}", host, port),
+        concurrency,
+        start.elapsed(),
+        &samples,
+    );
+    print!("{}", report);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn prune_history(config: &BunnylolConfig, days: Option<u64>) -> Result<(), String> {
+    let history = History::new(config)
+        .ok_or("Could not determine the history location")?
+        .with_retention_days(days.or(config.history.retention_days));
+    let removed = history.prune()?;
+    println!("Pruned {} history entries", removed);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn migrate_history(config: &BunnylolConfig, to: Option<&str>) -> Result<(), String> {
+    use bunnylol::history::{SCHEMA_VERSION, open_store};
+    use bunnylol::history_migrate;
+
+    let backend = config.history.backend.as_str();
+    let source = open_store(config, backend)?;
+    let Some(to) = to else {
+        match history_migrate::upgrade(source.as_ref())? {
+            Some(from) => println!(
+                "Upgraded history ({}) from schema v{} to v{}",
+                backend, from, SCHEMA_VERSION
+            ),
+            None => println!(
+                "History ({}) is already at schema v{}",
+                backend, SCHEMA_VERSION
+            ),
+        }
+        return Ok(());
+    };
+
+    if to == backend {
+        return Err(format!("History already uses the '{}' backend", to));
+    }
+    if !history_migrate::backend_available(to) {
+        return Err(format!(
+            "This build doesn't include the '{}' history backend",
+            to
+        ));
+    }
+    let target = open_store(config, to)?;
+    let copied = history_migrate::copy_history(
+        source.as_ref(),
+        target.as_ref(),
+        config.history.max_entries,
+    )?;
+    println!(
+        "Copied {} history entries from {} to {}",
+        copied, backend, to
+    );
+    println!(
+        "Set [history] backend = \"{}\" in the config to use it; the {} history was left in place",
+        to, backend
+    );
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn print_stats(
+    config: &BunnylolConfig,
+    days: u64,
+    limit: usize,
+    format: &str,
+) -> Result<(), String> {
+    use bunnylol::history_report::UsageReport;
+    use bunnylol::history_stats::now_secs;
+
+    if days == 0 {
+        return Err("--days must be at least 1".to_string());
+    }
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let entries = history.read_all()?;
+    let report = UsageReport::new(&entries, config, now_secs(), days, limit);
+    if format == "json" {
+        return print_json(&report);
+    }
+    print!("{}", report);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn list_unknown_commands(
+    config: &BunnylolConfig,
+    limit: usize,
+    format: &str,
+) -> Result<(), String> {
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let unknown = history.top_unknown(limit)?;
+    if format == "json" {
+        return print_json(&unknown);
+    }
+    if unknown.is_empty() {
+        println!("No unknown commands recorded");
+        return Ok(());
+    }
+    let width = unknown
+        .iter()
+        .map(|u| u.count.to_string().len())
+        .max()
+        .unwrap_or(1);
+    for u in unknown {
+        println!("{:>width$}  {}", u.count, u.command, width = width);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn purge_history(
+    config: &BunnylolConfig,
+    ip: Option<&str>,
+    user: Option<String>,
+    all: bool,
+) -> Result<(), String> {
+    use bunnylol::history_privacy::UserAnonymizer;
+    use bunnylol::history_purge::{self, PurgeTarget};
+
+    let target = match (ip, user) {
+        _ if all => PurgeTarget::All,
+        (Some(ip), _) => PurgeTarget::User(
+            UserAnonymizer::from_config(config)
+                .anonymize(ip.trim())
+                .ok_or("History is not kept per client IP (ip_mode = \"none\"); use --all")?,
+        ),
+        (None, Some(user)) => PurgeTarget::User(user),
+        (None, None) => return Err("Specify --ip, --user, or --all".to_string()),
+    };
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let removed = history_purge::purge(&history, &target)?;
+    history_purge::record_purge(&target, "cli", removed);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn list_history(
+    config: &BunnylolConfig,
+    filter: &HistoryFilterArgs,
+    limit: usize,
+    format: &str,
+) -> Result<(), String> {
+    use bunnylol::history_stats::{format_age, now_secs};
+
+    let filter = filter.to_filter(config)?;
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let entries = history.read_matching(&filter)?;
+    if format == "json" {
+        // The same objects `history export --format json` prints
+        let mut recent = bunnylol::history_export::select_entries(entries.iter().rev(), None);
+        recent.truncate(limit);
+        return print_json(&recent);
+    }
+    if entries.is_empty() {
+        println!("No matching history entries");
+        return Ok(());
+    }
+    let now = now_secs();
+    let recent: Vec<(String, &HistoryEntry)> = entries
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|entry| {
+            let age = entry
+                .timestamp
+                .parse::<u64>()
+                .map(|timestamp| format_age(now.saturating_sub(timestamp)))
+                .unwrap_or_else(|_| "?".to_string());
+            (age, entry)
+        })
+        .collect();
+    let age_width = recent.iter().map(|(age, _)| age.len()).max().unwrap_or(1);
+    let user_width = recent
+        .iter()
+        .map(|(_, entry)| entry.user.len())
+        .max()
+        .unwrap_or(1);
+    for (age, entry) in recent {
+        println!(
+            "{:>age_width$}  {:<user_width$}  {}",
+            age,
+            entry.user,
+            entry.command,
+            age_width = age_width,
+            user_width = user_width
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn top_history(
+    config: &BunnylolConfig,
+    filter: &HistoryFilterArgs,
+    limit: usize,
+    format: &str,
+) -> Result<(), String> {
+    use bunnylol::history_stats::{format_age, now_secs, rank_commands};
+
+    let filter = filter.to_filter(config)?;
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let entries = history.read_matching(&filter)?;
+    let now = now_secs();
+    let mut top = rank_commands(&entries, now);
+    top.truncate(limit);
+    if format == "json" {
+        return print_json(&top);
+    }
+    if top.is_empty() {
+        println!("No matching history entries");
+        return Ok(());
+    }
+    let count_width = top
+        .iter()
+        .map(|command| command.count.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let command_width = top
+        .iter()
+        .map(|command| command.command.len())
+        .max()
+        .unwrap_or(1);
+    for command in top {
+        println!(
+            "{:>count_width$}  {:<command_width$}  {}",
+            command.count,
+            command.command,
+            format_age(now.saturating_sub(command.last_used)),
+            count_width = count_width,
+            command_width = command_width
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn clear_history(
+    config: &BunnylolConfig,
+    filter: &HistoryFilterArgs,
+    all: bool,
+) -> Result<(), String> {
+    use bunnylol::history_purge::{self, PurgeTarget};
+
+    let filter = filter.to_filter(config)?;
+    let target = if all {
+        PurgeTarget::All
+    } else if filter.is_empty() {
+        return Err(
+            "Specify --since, --command, or --client, or --all to delete everything".to_string(),
+        );
+    } else {
+        PurgeTarget::Matching(filter)
+    };
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let removed = history_purge::purge(&history, &target)?;
+    history_purge::record_purge(&target, "cli", removed);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn export_history(
+    config: &BunnylolConfig,
+    format: &str,
+    since: Option<&str>,
+) -> Result<(), String> {
+    use bunnylol::history_export::{self, HistoryExportFormat};
+
+    let format = HistoryExportFormat::parse(format)
+        .ok_or_else(|| format!("Unsupported export format '{}'", format))?;
+    let since = since
+        .map(|since| history_export::parse_since(since, bunnylol::history_stats::now_secs()))
+        .transpose()?;
+    let history = History::new(config).ok_or("Could not determine the history location")?;
+    let entries = history.read_all()?;
+    print!(
+        "{}",
+        history_export::render_history_export(
+            &history_export::select_entries(&entries, since),
+            format
+        )
+    );
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+async fn execute_command(
     args: Vec<String>,
     config: &BunnylolConfig,
     dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     // Special case: "list" should print commands table, not execute as a command
     if args.first().map(|s| s.as_str()) == Some("list") {
-        print_commands();
-        return Ok(());
+        if args[1..].iter().any(|arg| arg == "--picker") {
+            print_picker_lines(config);
+        } else {
+            print_commands();
+        }
+        return Ok(Vec::new());
     }
 
     // Join command parts (e.g., ["ig", "reels"] -> "ig reels")
     let full_args = args.join(" ");
 
-    // Resolve command aliases
-    let resolved_args = config.resolve_command(&full_args);
+    let started = std::time::Instant::now();
+    let query = bunnylol::middleware::rewrite_query(config, &full_args);
+    let commands = bunnylol::resolver::split_commands(config, &query);
+    let (urls, command) = if commands.len() > 1 {
+        // Chained commands (`gh rust; cal tomorrow`) open one tab each
+        let resolution = bunnylol::resolver::resolve(config, &full_args);
+        if let Some(error) = resolution.error {
+            return Err(error.into());
+        }
+        (resolution.urls, None)
+    } else {
+        // Resolve command aliases
+        let query = commands.first().map_or(&*query, String::as_str);
+        let mut resolved_args = config.resolve_command(query);
+        if let Some(corrected) = bunnylol::resolver::autocorrect(config, &resolved_args) {
+            eprintln!("Autocorrected to: {}", corrected);
+            resolved_args = config.resolve_command(&corrected).into_owned().into();
+        }
 
-    // Extract command and process with config for custom search engine
-    let command = utils::get_command_from_query_string(&resolved_args);
-    let url = BunnylolCommandRegistry::process_command(command, &resolved_args);
+        // Extract command and process with config for custom search engine
+        let command = utils::get_command_from_query_string(&resolved_args);
+        let url = BunnylolCommandRegistry::process_command_async(command, &resolved_args)
+            .await
+            .map_err(|e| command_error_message(command, &e))?;
+        (
+            vec![bunnylol::middleware::rewrite_url(config, url.into_owned())],
+            Some(command.to_string()),
+        )
+    };
+    let latency = started.elapsed();
+    let browser = bunnylol::browser::browser_for(config, &full_args, command.as_deref());
 
-    // Print URL
-    println!("{}", url);
+    // Print URLs
+    for url in &urls {
+        println!("{}", url);
+    }
 
     // Track command in history if enabled
     if config.history.enabled
         && let Some(history) = History::new(config)
     {
-        let username = whoami::username();
-        if let Err(e) = history.add(&full_args, &username) {
+        let entry = HistoryEntry::new(full_args, whoami::username()).with_latency(latency);
+        if let Err(e) = history.add_entries(&[entry]) {
             eprintln!("Warning: Failed to save command to history: {}", e);
         }
+        if let Some(command) = command
+            && !BunnylolCommandRegistry::has_command(&command)
+            && let Err(e) = history.record_unknown(&[command])
+        {
+            eprintln!("Warning: Failed to record unknown command: {}", e);
+        }
     }
 
     // Open in browser unless --dry-run
     if !dry_run {
-        open_url(&url, config)?;
+        for url in &urls {
+            bunnylol::browser::open(url, browser.as_ref())
+                .map_err(|e| format!("{}. URL printed above.", e))?;
+        }
     }
 
+    Ok(urls)
+}
+
+/// Put `text` on the system clipboard
+#[cfg(feature = "cli")]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Could not open the clipboard: {}", e))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| format!("Could not copy to the clipboard: {}", e))?;
+    eprintln!("Copied to clipboard");
     Ok(())
 }
 
+/// Print where each query goes, one URL per line, returning whether every
+/// query resolved; reads queries from stdin when `query` is empty
 #[cfg(feature = "cli")]
-fn open_url(url: &str, config: &BunnylolConfig) -> Result<(), Box<dyn std::error::Error>> {
-    match &config.browser {
-        Some(browser) => open::with(url, browser).map_err(|e| {
-            format!(
-                "Failed to open browser '{}': {}. URL printed above.",
-                browser, e
-            )
-            .into()
-        }),
-        None => open::that(url)
-            .map_err(|e| format!("Failed to open browser: {}. URL printed above.", e).into()),
+fn resolve_queries(
+    config: &BunnylolConfig,
+    query: Vec<String>,
+    json: bool,
+    copy: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let queries = if query.is_empty() {
+        std::io::stdin()
+            .lines()
+            .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![query.join(" ")]
+    };
+
+    let mut all_resolved = true;
+    let mut urls = Vec::new();
+    for query in &queries {
+        let resolution = bunnylol::resolve(query, config);
+        if json {
+            println!("{}", serde_json::to_string(&resolution)?);
+        } else {
+            for url in &resolution.urls {
+                println!("{}", url);
+            }
+        }
+        if let Some(error) = &resolution.error {
+            all_resolved = false;
+            if !json {
+                let command = resolution.command.as_deref().unwrap_or_default();
+                eprintln!("Error: {}", command_error_message(command, error));
+            }
+        }
+        urls.extend(resolution.urls);
     }
+    if copy && !urls.is_empty() {
+        copy_to_clipboard(&urls.join("\n"))?;
+    }
+    Ok(all_resolved)
+}
+
+/// Every binding and configured alias, offered when completing a `resolve`
+/// or `open` query
+#[cfg(feature = "cli")]
+fn completion_words(config: &BunnylolConfig) -> Vec<String> {
+    let mut words: Vec<String> = BunnylolCommandRegistry::get_all_commands()
+        .iter()
+        .flat_map(|info| info.bindings.iter().cloned())
+        .chain(
+            BunnylolCommandRegistry::extension_bindings()
+                .into_iter()
+                .map(String::from),
+        )
+        // Placeholders like "(default)" and "$<ticker>" aren't typed as-is
+        .filter(|binding| !binding.starts_with('(') && !binding.contains('<'))
+        .chain(config.aliases.keys().cloned())
+        .chain(config.keywords.keys().cloned())
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The CLI with `words` as the values `resolve` and `open` complete
+#[cfg(feature = "cli")]
+fn completion_command(words: &[String]) -> clap::Command {
+    let with_words =
+        |arg: clap::Arg| arg.value_parser(clap::builder::PossibleValuesParser::new(words.to_vec()));
+    Cli::command()
+        .mut_subcommand("resolve", |cmd| cmd.mut_arg("query", with_words))
+        .mut_subcommand("open", |cmd| cmd.mut_arg("query", with_words))
+}
+
+/// The query `bunnylol open` runs: as typed, unless it's a bare address like
+/// `example.com` that no command or alias claims, which the `open` command takes
+#[cfg(feature = "cli")]
+fn open_query(config: &BunnylolConfig, query: String) -> String {
+    let command = utils::get_command_from_query_string(&query);
+    let claimed = BunnylolCommandRegistry::has_command(command)
+        || config.aliases.contains_key(command)
+        || config.lowercase_command(command).is_some();
+    let address =
+        !query.contains(char::is_whitespace) && (query.contains("://") || query.contains('.'));
+    if !claimed && address {
+        format!("open {}", query)
+    } else {
+        query
+    }
+}
+
+/// A command's error followed by the forms it accepts
+#[cfg(feature = "cli")]
+fn command_error_message(command: &str, error: &dyn std::fmt::Display) -> String {
+    let mut message = error.to_string();
+    if let Some(info) = BunnylolCommandRegistry::get_command_info(command)
+        && !info.usage.is_empty()
+    {
+        message.push_str("\n\nUsage:");
+        for usage in &info.usage {
+            message.push_str(&format!("\n  {:<28} {}", usage.syntax, usage.description));
+        }
+    }
+    message
 }
 
 #[cfg(feature = "cli")]
@@ -302,6 +1813,68 @@ struct CommandRow {
     example: String,
 }
 
+/// Load and install the plugins in `[plugins] dir` and the scripted commands
+/// in `[commands]`, returning why any of them can't be used
+fn load_extensions(config: &BunnylolConfig) -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut warnings = Vec::new();
+    let plugin_dir = config.plugins.dir.as_deref().map(std::path::Path::new);
+
+    // Shared-library plugins claim their bindings first, then scripts, then
+    // WebAssembly plugins, which are reloaded as they change
+    #[cfg(feature = "plugins")]
+    if let Some(dir) = plugin_dir {
+        let (plugins, mut errors) = bunnylol::plugins::load_dir(dir);
+        errors.extend(BunnylolCommandRegistry::install_plugins(plugins));
+        warnings.extend(
+            errors
+                .into_iter()
+                .map(|e| format!("skipping plugin: {}", e)),
+        );
+    }
+    #[cfg(feature = "scripting")]
+    if !config.commands.is_empty() {
+        let base = BunnylolConfig::get_existing_config_path()
+            .and_then(|path| path.parent().map(std::path::Path::to_path_buf))
+            .unwrap_or_default();
+        let (scripts, mut errors) = bunnylol::scripting::load(&config.commands, &base);
+        errors.extend(BunnylolCommandRegistry::install_scripts(scripts));
+        warnings.extend(
+            errors
+                .into_iter()
+                .map(|e| format!("skipping script: {}", e)),
+        );
+    }
+    #[cfg(feature = "wasm-plugins")]
+    if let Some(dir) = plugin_dir {
+        warnings.extend(
+            bunnylol::wasm_plugins::watch(dir)
+                .into_iter()
+                .map(|e| format!("skipping plugin: {}", e)),
+        );
+    }
+
+    #[cfg(not(any(feature = "plugins", feature = "wasm-plugins")))]
+    if let Some(dir) = plugin_dir {
+        warnings.push(format!(
+            "ignoring [plugins] dir = {:?}: bunnylol was built without the `plugins` or `wasm-plugins` feature",
+            dir
+        ));
+    }
+    #[cfg(not(feature = "scripting"))]
+    if !config.commands.is_empty() {
+        warnings.push(
+            "ignoring [commands]: bunnylol was built without the `scripting` feature".to_string(),
+        );
+    }
+    warnings
+}
+
+#[cfg(feature = "cli")]
+fn print_picker_lines(config: &BunnylolConfig) {
+    print!("{}", bunnylol::server::export::render_picker_lines(config));
+}
+
 #[cfg(feature = "cli")]
 fn print_commands() {
     let mut commands = BunnylolCommandRegistry::get_all_commands().clone();
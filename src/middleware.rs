@@ -0,0 +1,193 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Hooks that rewrite every query before it's resolved and every URL it
+//! resolves to, configured as `[[middleware]]` steps and run in order:
+//!
+//! ```toml
+//! [[middleware]]
+//! type = "normalize"
+//!
+//! [[middleware]]
+//! type = "replace"
+//! find = "$me"
+//! replace = "octocat"
+//!
+//! [[middleware]]
+//! type = "strip_params"
+//!
+//! [[middleware]]
+//! type = "force_https"
+//! ```
+//!
+//! Query hooks run before alias expansion, so a macro can expand to an alias;
+//! URL hooks run on commands', aliases', and the fallback chain's URLs alike.
+
+use std::borrow::Cow;
+
+use url::Url;
+
+use crate::config::{BunnylolConfig, MiddlewareStep};
+
+/// Hosts `force_https` leaves alone, since local servers rarely have a certificate
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+impl MiddlewareStep {
+    /// This step's rewrite of a query, or `None` to leave it as it is
+    fn rewrite_query(&self, query: &str) -> Option<String> {
+        match self {
+            MiddlewareStep::Normalize => {
+                let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ");
+                (normalized != query).then_some(normalized)
+            }
+            MiddlewareStep::Replace { find, replace } => (!find.is_empty()
+                && query.contains(find.as_str()))
+            .then(|| query.replace(find.as_str(), replace)),
+            MiddlewareStep::StripParams { .. } | MiddlewareStep::ForceHttps => None,
+        }
+    }
+
+    /// This step's rewrite of a URL, or `None` to leave it as it is
+    fn rewrite_url(&self, url: &str) -> Option<String> {
+        match self {
+            MiddlewareStep::StripParams { params } => strip_params(url, params),
+            MiddlewareStep::ForceHttps => force_https(url),
+            MiddlewareStep::Normalize | MiddlewareStep::Replace { .. } => None,
+        }
+    }
+}
+
+/// `query` after the config's query hooks
+pub fn rewrite_query<'a>(config: &BunnylolConfig, query: &'a str) -> Cow<'a, str> {
+    config
+        .middleware
+        .iter()
+        .fold(Cow::Borrowed(query), |query, step| {
+            match step.rewrite_query(&query) {
+                Some(rewritten) => Cow::Owned(rewritten),
+                None => query,
+            }
+        })
+}
+
+/// `url` after the config's URL hooks
+pub fn rewrite_url(config: &BunnylolConfig, url: String) -> String {
+    config
+        .middleware
+        .iter()
+        .fold(url, |url, step| step.rewrite_url(&url).unwrap_or(url))
+}
+
+/// Whether a query parameter named `key` matches one of `params`, where a
+/// trailing `*` matches any name starting with the rest
+fn param_matches(key: &str, params: &[String]) -> bool {
+    params.iter().any(|param| match param.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == param,
+    })
+}
+
+/// `url` without the query parameters in `params`; the others keep their
+/// order and encoding
+fn strip_params(url: &str, params: &[String]) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    let query = parsed.query()?;
+    let pairs: Vec<&str> = query.split('&').collect();
+    let kept: Vec<&str> = pairs
+        .iter()
+        .copied()
+        .filter(|pair| !param_matches(pair.split('=').next().unwrap_or_default(), params))
+        .collect();
+    if kept.len() == pairs.len() {
+        return None;
+    }
+    let kept = kept.join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    Some(parsed.into())
+}
+
+/// `url` over HTTPS, when it's a plain `http://` URL to anything but this machine
+fn force_https(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    if parsed.scheme() != "http" || LOOPBACK_HOSTS.contains(&parsed.host_str()?) {
+        return None;
+    }
+    parsed.set_scheme("https").ok()?;
+    Some(parsed.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(middleware: Vec<MiddlewareStep>) -> BunnylolConfig {
+        BunnylolConfig {
+            middleware,
+            ..BunnylolConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_rewrite_query_runs_steps_in_order() {
+        let config = config(vec![
+            MiddlewareStep::Normalize,
+            MiddlewareStep::Replace {
+                find: "$me".to_string(),
+                replace: "octocat".to_string(),
+            },
+        ]);
+        assert_eq!(rewrite_query(&config, "  gh   @$me "), "gh @octocat");
+        assert!(matches!(
+            rewrite_query(&config, "gh rust"),
+            Cow::Borrowed("gh rust")
+        ));
+        assert_eq!(
+            rewrite_query(&BunnylolConfig::default(), "  gh  rust"),
+            "  gh  rust"
+        );
+    }
+
+    #[test]
+    fn test_strip_params() {
+        let config = config(vec![MiddlewareStep::StripParams {
+            params: vec!["utm_*".to_string(), "fbclid".to_string()],
+        }]);
+        assert_eq!(
+            rewrite_url(
+                &config,
+                "https://example.com/a?q=rust%20book&utm_source=x&fbclid=1#top".to_string()
+            ),
+            "https://example.com/a?q=rust%20book#top"
+        );
+        assert_eq!(
+            rewrite_url(&config, "https://example.com/?utm_medium=email".to_string()),
+            "https://example.com/"
+        );
+        assert_eq!(
+            rewrite_url(&config, "https://example.com/?q=a+b".to_string()),
+            "https://example.com/?q=a+b"
+        );
+        assert_eq!(rewrite_url(&config, "/bindings".to_string()), "/bindings");
+    }
+
+    #[test]
+    fn test_force_https() {
+        let config = config(vec![MiddlewareStep::ForceHttps]);
+        assert_eq!(
+            rewrite_url(&config, "http://example.com/docs?q=1".to_string()),
+            "https://example.com/docs?q=1"
+        );
+        assert_eq!(
+            rewrite_url(&config, "http://localhost:8000/".to_string()),
+            "http://localhost:8000/"
+        );
+        assert_eq!(
+            rewrite_url(&config, "https://example.com".to_string()),
+            "https://example.com"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Commands loaded at startup from shared libraries in `[plugins] dir`, so
+//! commands can ship separately from this crate.
+//!
+//! A plugin is a `cdylib` exporting one C function, `bunnylol_plugin_v1`,
+//! that returns a pointer to a static `BunnylolPluginV1`:
+//!
+//! ```no_run
+//! use std::ffi::{CStr, CString, c_char};
+//! use bunnylol::plugins::{BunnylolPluginV1, PLUGIN_ABI_VERSION};
+//!
+//! unsafe extern "C" fn resolve(args: *const c_char) -> *mut c_char {
+//!     let args = unsafe { CStr::from_ptr(args) }.to_string_lossy();
+//!     let ticket = args.split_whitespace().nth(1).unwrap_or_default();
+//!     CString::new(format!("https://jira.example.com/browse/{}", ticket))
+//!         .map_or(std::ptr::null_mut(), CString::into_raw)
+//! }
+//!
+//! unsafe extern "C" fn free_string(url: *mut c_char) {
+//!     drop(unsafe { CString::from_raw(url) });
+//! }
+//!
+//! // Raw pointers aren't `Sync`, so a static array of them needs a wrapper
+//! #[repr(transparent)]
+//! struct Bindings([*const c_char; 2]);
+//! unsafe impl Sync for Bindings {}
+//! static BINDINGS: Bindings = Bindings([c"jira".as_ptr(), std::ptr::null()]);
+//!
+//! static PLUGIN: BunnylolPluginV1 = BunnylolPluginV1 {
+//!     abi_version: PLUGIN_ABI_VERSION,
+//!     name: c"jira".as_ptr(),
+//!     description: c"Open a Jira ticket".as_ptr(),
+//!     example: c"jira PROJ-123".as_ptr(),
+//!     bindings: BINDINGS.0.as_ptr(),
+//!     resolve,
+//!     free_string,
+//! };
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn bunnylol_plugin_v1() -> *const BunnylolPluginV1 {
+//!     &PLUGIN
+//! }
+//! ```
+//!
+//! Loading needs the `plugins` feature; without it, the rest of this module
+//! still builds so plugin authors can use the ABI types.
+
+use std::ffi::{CStr, CString, c_char};
+use std::sync::OnceLock;
+
+use crate::commands::bunnylol_command::{BunnylolCommandInfo, CommandCategory, CommandError};
+
+/// The `abi_version` a plugin must report
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The function every plugin exports, returning its `BunnylolPluginV1`
+pub const PLUGIN_ENTRY_SYMBOL: &str = "bunnylol_plugin_v1";
+
+/// What a plugin's `bunnylol_plugin_v1` returns
+///
+/// Strings are NUL-terminated UTF-8, and everything here must stay valid for
+/// as long as the library is loaded.
+#[repr(C)]
+pub struct BunnylolPluginV1 {
+    /// Always `PLUGIN_ABI_VERSION`
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub description: *const c_char,
+    pub example: *const c_char,
+    /// The command's bindings, ending with a null pointer
+    pub bindings: *const *const c_char,
+    /// Turn the full query (e.g. "jira PROJ-123") into a URL allocated by the
+    /// plugin, or return null to reject the arguments
+    pub resolve: unsafe extern "C" fn(args: *const c_char) -> *mut c_char,
+    /// Free a URL returned by `resolve`
+    pub free_string: unsafe extern "C" fn(url: *mut c_char),
+}
+
+// The struct is immutable once returned, so sharing it between threads is fine
+unsafe impl Sync for BunnylolPluginV1 {}
+
+/// A command provided by a loaded plugin
+pub struct PluginCommand {
+    pub name: String,
+    info: BunnylolCommandInfo,
+    resolve: unsafe extern "C" fn(*const c_char) -> *mut c_char,
+    free_string: unsafe extern "C" fn(*mut c_char),
+    /// Keeps the functions above loaded
+    #[cfg(feature = "plugins")]
+    _library: Option<libloading::Library>,
+}
+
+impl PluginCommand {
+    /// Read a plugin's description of itself
+    ///
+    /// # Safety
+    /// `raw` must follow the `BunnylolPluginV1` contract: every pointer is
+    /// valid and NUL-terminated, and stays so while the plugin is loaded.
+    pub unsafe fn from_raw(raw: &BunnylolPluginV1) -> Result<Self, String> {
+        if raw.abi_version != PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "plugin ABI version {} isn't supported (expected {})",
+                raw.abi_version, PLUGIN_ABI_VERSION
+            ));
+        }
+        let string = |ptr: *const c_char, what: &str| -> Result<String, String> {
+            if ptr.is_null() {
+                return Err(format!("plugin has no {}", what));
+            }
+            unsafe { CStr::from_ptr(ptr) }
+                .to_str()
+                .map(str::to_string)
+                .map_err(|_| format!("plugin {} isn't UTF-8", what))
+        };
+
+        let name = string(raw.name, "name")?;
+        let mut bindings = Vec::new();
+        if !raw.bindings.is_null() {
+            for i in 0.. {
+                let binding = unsafe { *raw.bindings.add(i) };
+                if binding.is_null() {
+                    break;
+                }
+                bindings.push(string(binding, "binding")?);
+            }
+        }
+        if bindings.is_empty() {
+            return Err(format!("plugin '{}' has no bindings", name));
+        }
+        let bindings: Vec<&str> = bindings.iter().map(String::as_str).collect();
+        let info = BunnylolCommandInfo::new(
+            &bindings,
+            &string(raw.description, "description")?,
+            &string(raw.example, "example")?,
+        )
+        .with_category(CommandCategory::Other);
+
+        Ok(PluginCommand {
+            name,
+            info,
+            resolve: raw.resolve,
+            free_string: raw.free_string,
+            #[cfg(feature = "plugins")]
+            _library: None,
+        })
+    }
+
+    pub fn bindings(&self) -> &[String] {
+        &self.info.bindings
+    }
+
+    pub fn info(&self) -> &BunnylolCommandInfo {
+        &self.info
+    }
+
+    /// Ask the plugin for the URL of a full query, e.g. "jira PROJ-123"
+    pub fn resolve(&self, args: &str) -> Result<String, CommandError> {
+        let rejected = || {
+            CommandError::InvalidArguments(format!(
+                "the {} plugin couldn't handle '{}'",
+                self.name, args
+            ))
+        };
+        let args = CString::new(args).map_err(|_| rejected())?;
+        let url = unsafe { (self.resolve)(args.as_ptr()) };
+        if url.is_null() {
+            return Err(rejected());
+        }
+        let result = unsafe { CStr::from_ptr(url) }
+            .to_str()
+            .map(str::to_string)
+            .map_err(|_| rejected());
+        unsafe { (self.free_string)(url) };
+        result
+    }
+}
+
+static PLUGINS: OnceLock<Vec<PluginCommand>> = OnceLock::new();
+
+/// Make `plugins` available to the registry; only the first call has any effect
+pub fn install(plugins: Vec<PluginCommand>) {
+    let _ = PLUGINS.set(plugins);
+}
+
+/// Every installed plugin command
+pub fn installed() -> &'static [PluginCommand] {
+    PLUGINS.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// The installed plugin command with this binding
+pub fn find(binding: &str) -> Option<&'static PluginCommand> {
+    installed()
+        .iter()
+        .find(|plugin| plugin.bindings().iter().any(|b| b == binding))
+}
+
+/// Load every plugin in `dir`, returning the plugins and why any others
+/// couldn't be loaded
+#[cfg(feature = "plugins")]
+pub fn load_dir(dir: &std::path::Path) -> (Vec<PluginCommand>, Vec<String>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return (
+                Vec::new(),
+                vec![format!("Failed to read plugin directory {:?}: {}", dir, e)],
+            );
+        }
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut plugins = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match load(&path) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => errors.push(format!("Failed to load plugin {:?}: {}", path, e)),
+        }
+    }
+    (plugins, errors)
+}
+
+/// Load one plugin library
+#[cfg(feature = "plugins")]
+fn load(path: &std::path::Path) -> Result<PluginCommand, String> {
+    // Loading runs the library's initializers; the plugin directory is trusted
+    let library = unsafe { libloading::Library::new(path) }.map_err(|e| e.to_string())?;
+    let mut plugin = unsafe {
+        let entry: libloading::Symbol<unsafe extern "C" fn() -> *const BunnylolPluginV1> = library
+            .get(PLUGIN_ENTRY_SYMBOL.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let raw = entry();
+        if raw.is_null() {
+            return Err(format!("{} returned null", PLUGIN_ENTRY_SYMBOL));
+        }
+        PluginCommand::from_raw(&*raw)?
+    };
+    plugin._library = Some(library);
+    Ok(plugin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn resolve(args: *const c_char) -> *mut c_char {
+        let args = unsafe { CStr::from_ptr(args) }.to_string_lossy();
+        match args.split_whitespace().nth(1) {
+            Some(ticket) => CString::new(format!("https://jira.example.com/browse/{}", ticket))
+                .map_or(std::ptr::null_mut(), CString::into_raw),
+            None => std::ptr::null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn free_string(url: *mut c_char) {
+        drop(unsafe { CString::from_raw(url) });
+    }
+
+    #[repr(transparent)]
+    struct Bindings([*const c_char; 3]);
+    unsafe impl Sync for Bindings {}
+    static BINDINGS: Bindings = Bindings([c"jira".as_ptr(), c"ticket".as_ptr(), std::ptr::null()]);
+
+    fn plugin(abi_version: u32) -> BunnylolPluginV1 {
+        BunnylolPluginV1 {
+            abi_version,
+            name: c"jira".as_ptr(),
+            description: c"Open a Jira ticket".as_ptr(),
+            example: c"jira PROJ-123".as_ptr(),
+            bindings: BINDINGS.0.as_ptr(),
+            resolve,
+            free_string,
+        }
+    }
+
+    #[test]
+    fn test_from_raw_reads_metadata() {
+        let plugin = unsafe { PluginCommand::from_raw(&plugin(PLUGIN_ABI_VERSION)) }.unwrap();
+        assert_eq!(plugin.name, "jira");
+        assert_eq!(plugin.bindings(), ["jira", "ticket"]);
+        assert_eq!(plugin.info().description, "Open a Jira ticket");
+    }
+
+    #[test]
+    fn test_from_raw_rejects_other_abi_versions() {
+        let error = unsafe { PluginCommand::from_raw(&plugin(2)) }
+            .err()
+            .unwrap();
+        assert!(error.contains("ABI version 2"));
+    }
+
+    #[test]
+    fn test_resolve_calls_into_plugin() {
+        let plugin = unsafe { PluginCommand::from_raw(&plugin(PLUGIN_ABI_VERSION)) }.unwrap();
+        assert_eq!(
+            plugin.resolve("jira PROJ-123").as_deref(),
+            Ok("https://jira.example.com/browse/PROJ-123")
+        );
+        assert!(plugin.resolve("jira").is_err());
+        assert!(plugin.resolve("jira a\0b").is_err());
+    }
+}
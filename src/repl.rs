@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `bunnylol repl`: resolve queries line by line, showing where each goes and
+//! how it got there, for trying out new aliases and templates
+//!
+//! Lines starting with `:` are REPL commands; `:reload` re-reads the config,
+//! so an edit shows up in the next query without restarting.
+
+use std::io::{BufRead, Write};
+
+use crate::BunnylolConfig;
+use crate::resolver;
+
+const HELP: &str = "\
+Type a query to see where it goes, or:
+  :reload   re-read the config file
+  :help     show this help
+  :quit     leave (or press Ctrl-D)";
+
+/// What a line typed into the REPL asks for
+#[derive(Debug, PartialEq, Eq)]
+enum Input<'a> {
+    Query(&'a str),
+    Reload,
+    Help,
+    Quit,
+    Unknown(&'a str),
+    Empty,
+}
+
+impl<'a> Input<'a> {
+    fn parse(line: &'a str) -> Self {
+        let line = line.trim();
+        match line.strip_prefix(':') {
+            _ if line.is_empty() => Input::Empty,
+            None => Input::Query(line),
+            Some("reload" | "r") => Input::Reload,
+            Some("help" | "h" | "?") => Input::Help,
+            Some("quit" | "q" | "exit") => Input::Quit,
+            Some(command) => Input::Unknown(command),
+        }
+    }
+}
+
+/// Read queries from `input` until it ends or `:quit`, writing each one's
+/// resolution to `output`
+///
+/// `prompt` is written before each line when set. `reload` loads the config
+/// again for `:reload`; the old one stays in use when it fails.
+pub fn run(
+    mut config: BunnylolConfig,
+    input: impl BufRead,
+    mut output: impl Write,
+    prompt: Option<&str>,
+    mut reload: impl FnMut() -> Result<BunnylolConfig, String>,
+) -> std::io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if let Some(prompt) = prompt {
+            write!(output, "{}", prompt)?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else {
+            break;
+        };
+        match Input::parse(&line) {
+            Input::Query(query) => {
+                let resolution = resolver::resolve(&config, query);
+                writeln!(output, "{}", resolution.describe())?;
+            }
+            Input::Reload => match reload() {
+                Ok(reloaded) => {
+                    config = reloaded;
+                    writeln!(output, "Config reloaded")?;
+                }
+                Err(e) => writeln!(output, "Reload failed, keeping the previous config: {}", e)?,
+            },
+            Input::Help => writeln!(output, "{}", HELP)?,
+            Input::Quit => break,
+            Input::Unknown(command) => {
+                writeln!(output, "Unknown command ':{}'; :help lists them", command)?
+            }
+            Input::Empty => {}
+        }
+    }
+    // End the prompt line when input ends with Ctrl-D
+    if prompt.is_some() {
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "dev")]
+    fn config_with_alias(alias: &str, target: &str) -> BunnylolConfig {
+        let mut config = BunnylolConfig::default();
+        config.aliases.insert(alias.to_string(), target.to_string());
+        config
+    }
+
+    #[cfg(feature = "dev")]
+    fn session(input: &str, reloaded: BunnylolConfig) -> String {
+        let mut output = Vec::new();
+        let mut reloaded = Some(reloaded);
+        run(
+            config_with_alias("work", "gh mbinns/blog"),
+            input.as_bytes(),
+            &mut output,
+            None,
+            || reloaded.take().ok_or_else(|| "bad config".to_string()),
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(Input::parse("  gh rust "), Input::Query("gh rust"));
+        assert_eq!(Input::parse(":reload"), Input::Reload);
+        assert_eq!(Input::parse(":q"), Input::Quit);
+        assert_eq!(Input::parse(":nope"), Input::Unknown("nope"));
+        assert_eq!(Input::parse("   "), Input::Empty);
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_run_reloads_config() {
+        let output = session(
+            "work\n:reload\nwork\n:reload\nwork\n:quit\nwork\n",
+            config_with_alias("work", "gh octocat/hello"),
+        );
+        let lines: Vec<&str> = output
+            .lines()
+            .filter(|line| !line.starts_with("  "))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "https://github.com/mbinns/blog",
+                "Config reloaded",
+                "https://github.com/octocat/hello",
+                "Reload failed, keeping the previous config: bad config",
+                "https://github.com/octocat/hello",
+            ]
+        );
+    }
+}
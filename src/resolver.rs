@@ -0,0 +1,442 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Dry-run resolution of a query: which URL(s) it produces and how it got
+//! there, without redirecting or recording history.
+//!
+//! This is the API for embedding bunnylol; [`crate::resolve`] is the usual
+//! entry point. New fields and layers may be added, so `Resolution` and
+//! `ResolutionLayer` are `#[non_exhaustive]`.
+
+use serde::Serialize;
+
+use crate::utils::fuzzy::FuzzyIndex;
+use crate::{BunnylolCommandRegistry, BunnylolConfig, fallback, middleware, utils};
+
+/// Which layer produced the final URL
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ResolutionLayer {
+    /// A built-in command binding (or prefix like `$AAPL`)
+    Builtin,
+    /// A user alias expanded to a built-in command
+    Alias,
+    /// Nothing matched, so the default search engine was used
+    Fallback,
+    /// Several chained commands, each resolved in `steps`
+    Chain,
+}
+
+/// The outcome of resolving a query
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Resolution {
+    /// The query as entered
+    pub query: String,
+    /// The query after alias expansion
+    pub resolved_query: String,
+    /// Which layer produced the URL(s)
+    pub layer: ResolutionLayer,
+    /// Each step of alias expansion, starting with the original query
+    pub alias_chain: Vec<String>,
+    /// The query after typo correction, when `[fuzzy] autocorrect_distance`
+    /// corrected its command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autocorrected: Option<String>,
+    /// The binding that handled the query, if any
+    pub command: Option<String>,
+    /// Where the query goes; usually one URL, several for chains and
+    /// multi-target commands
+    pub urls: Vec<String>,
+    /// The fallback step that produced the URL, for the fallback layer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_step: Option<String>,
+    /// Why the command rejected its arguments; `urls` is empty when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// For a chained query, each command's resolution in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<Resolution>,
+}
+
+impl Resolution {
+    /// The first URL, which is where a redirect would go
+    pub fn url(&self) -> Option<&str> {
+        self.urls.first().map(String::as_str)
+    }
+
+    /// The URLs (or the command's error), then the layer that matched and the
+    /// alias expansion, one per line; as `bunnylol repl` and `serve --watch`
+    /// print it
+    pub fn describe(&self) -> String {
+        let mut lines = Vec::new();
+        self.describe_into("", &mut lines);
+        lines.join("\n")
+    }
+
+    fn describe_into(&self, indent: &str, lines: &mut Vec<String>) {
+        if self.layer == ResolutionLayer::Chain {
+            lines.push(format!("{}chain of {} commands", indent, self.steps.len()));
+            for (i, step) in self.steps.iter().enumerate() {
+                lines.push(format!("{}  {}. {}", indent, i + 1, step.query));
+                step.describe_into(&format!("{}     ", indent), lines);
+            }
+            return;
+        }
+
+        match &self.error {
+            Some(error) => lines.push(format!("{}error: {}", indent, error)),
+            None => lines.extend(self.urls.iter().map(|url| format!("{}{}", indent, url))),
+        }
+        let layer = match self.layer {
+            ResolutionLayer::Builtin => "builtin",
+            ResolutionLayer::Alias => "alias",
+            ResolutionLayer::Fallback => "fallback",
+            ResolutionLayer::Chain => "chain",
+        };
+        let via = self
+            .command
+            .as_deref()
+            .or(self.fallback_step.as_deref())
+            .map(|via| format!(" via {}", via))
+            .unwrap_or_default();
+        lines.push(format!("{}  layer: {}{}", indent, layer, via));
+        if let Some(corrected) = &self.autocorrected {
+            lines.push(format!("{}  autocorrected: {}", indent, corrected));
+        }
+        if !self.alias_chain.is_empty() {
+            lines.push(format!(
+                "{}  alias: {}",
+                indent,
+                self.alias_chain.join(" → ")
+            ));
+        }
+    }
+}
+
+/// Resolve a query the same way the server would, without side effects
+///
+/// A chained query (`gh rust; cal tomorrow`) resolves each command in turn;
+/// `urls` then holds every command's URLs, or none if any command failed.
+pub fn resolve(config: &BunnylolConfig, query: &str) -> Resolution {
+    let query = query.trim();
+    let rewritten = middleware::rewrite_query(config, query);
+    let commands = split_commands(config, &rewritten);
+    if commands.len() <= 1 {
+        let command = commands.first().map_or(&*rewritten, String::as_str);
+        return Resolution {
+            query: query.to_string(),
+            ..resolve_command(config, command)
+        };
+    }
+
+    let steps: Vec<Resolution> = commands
+        .iter()
+        .map(|command| resolve_command(config, command))
+        .collect();
+    let error = steps.iter().find_map(|step| step.error.clone());
+    let urls = match error {
+        Some(_) => Vec::new(),
+        None => steps.iter().flat_map(|step| step.urls.clone()).collect(),
+    };
+    Resolution {
+        query: query.to_string(),
+        resolved_query: commands.join("; "),
+        layer: ResolutionLayer::Chain,
+        alias_chain: Vec::new(),
+        autocorrected: None,
+        command: None,
+        fallback_step: None,
+        urls,
+        error,
+        steps,
+    }
+}
+
+/// Split `query` into the commands it chains, treating any binding, prefix,
+/// or alias after a `;` or `&&` as the start of a new command
+pub fn split_commands(config: &BunnylolConfig, query: &str) -> Vec<String> {
+    utils::chain::split_chain(query, |token| {
+        BunnylolCommandRegistry::has_command(token)
+            || config.aliases.contains_key(token)
+            || config.lowercase_command(token).is_some()
+    })
+}
+
+/// Resolve a single, unchained command
+fn resolve_command(config: &BunnylolConfig, query: &str) -> Resolution {
+    // `GH rust` is still a built-in, not an alias expansion
+    let query_lowered = config.lowercase_command(query);
+    let command_query = query_lowered.as_deref().unwrap_or(query);
+    let mut resolved = config.resolve_command(command_query).into_owned();
+    let mut alias_chain = if resolved != command_query {
+        vec![query.to_string(), resolved.clone()]
+    } else {
+        Vec::new()
+    };
+    let autocorrected = autocorrect(config, &resolved);
+    if let Some(corrected) = &autocorrected {
+        // The correction may itself be an alias
+        let expanded = config.resolve_command(corrected).into_owned();
+        if &expanded != corrected {
+            alias_chain = vec![corrected.clone(), expanded.clone()];
+        }
+        resolved = expanded;
+    }
+    let command = utils::get_command_from_query_string(&resolved);
+
+    let mut fallback_step = None;
+    let (layer, command, targets) = if BunnylolCommandRegistry::has_command(command) {
+        let layer = if alias_chain.is_empty() {
+            ResolutionLayer::Builtin
+        } else {
+            ResolutionLayer::Alias
+        };
+        (
+            layer,
+            Some(command.to_string()),
+            BunnylolCommandRegistry::process_targets(command, &resolved),
+        )
+    } else {
+        let fallback = fallback::resolve(config, &resolved);
+        fallback_step = Some(fallback.step);
+        (ResolutionLayer::Fallback, None, Ok(vec![fallback.url]))
+    };
+    let (urls, error) = match targets {
+        Ok(urls) => (
+            urls.into_iter()
+                .map(|url| middleware::rewrite_url(config, url))
+                .collect(),
+            None,
+        ),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+
+    Resolution {
+        query: query.to_string(),
+        resolved_query: resolved,
+        layer,
+        alias_chain,
+        autocorrected,
+        command,
+        fallback_step,
+        urls,
+        error,
+        steps: Vec::new(),
+    }
+}
+
+/// `query` with a typo in its command corrected, as `[fuzzy]
+/// autocorrect_distance` allows
+pub fn autocorrect(config: &BunnylolConfig, query: &str) -> Option<String> {
+    let max_distance = config.fuzzy.autocorrect_distance;
+    if max_distance == 0 {
+        return None;
+    }
+    let aliases = FuzzyIndex::new(config.aliases.keys().map(String::as_str));
+    BunnylolCommandRegistry::autocorrect(query, &aliases, max_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_builtin() {
+        let resolution = resolve(&BunnylolConfig::default(), "gh facebook/react");
+        assert_eq!(resolution.layer, ResolutionLayer::Builtin);
+        assert_eq!(resolution.command.as_deref(), Some("gh"));
+        assert_eq!(resolution.urls, vec!["https://github.com/facebook/react"]);
+        assert_eq!(resolution.url(), Some("https://github.com/facebook/react"));
+        assert!(resolution.alias_chain.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_alias() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mycompany/repo".to_string());
+
+        let resolution = resolve(&config, "work");
+        assert_eq!(resolution.layer, ResolutionLayer::Alias);
+        assert_eq!(resolution.alias_chain, vec!["work", "gh mycompany/repo"]);
+        assert_eq!(resolution.urls, vec!["https://github.com/mycompany/repo"]);
+    }
+
+    #[test]
+    fn test_resolve_fallback_uses_configured_engine() {
+        let config = BunnylolConfig {
+            default_search: "ddg".to_string(),
+            ..Default::default()
+        };
+
+        let resolution = resolve(&config, "weather tomorrow");
+        assert_eq!(resolution.layer, ResolutionLayer::Fallback);
+        assert_eq!(resolution.command, None);
+        assert_eq!(resolution.fallback_step.as_deref(), Some("search (ddg)"));
+        assert_eq!(
+            resolution.urls,
+            vec!["https://duckduckgo.com/?q=weather%20tomorrow"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "finance")]
+    fn test_resolve_invalid_arguments() {
+        let resolution = resolve(&BunnylolConfig::default(), "stock apple inc");
+        assert_eq!(resolution.command.as_deref(), Some("stock"));
+        assert!(resolution.urls.is_empty());
+        assert_eq!(resolution.url(), None);
+        assert_eq!(
+            resolution.error.as_deref(),
+            Some("'apple inc' isn't a ticker symbol")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_autocorrects_typos() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mycompany/repo".to_string());
+
+        // Off by default
+        assert_eq!(resolve(&config, "gihub rust").autocorrected, None);
+
+        config.fuzzy.autocorrect_distance = 1;
+        let resolution = resolve(&config, "gihub facebook/react");
+        assert_eq!(
+            resolution.autocorrected.as_deref(),
+            Some("github facebook/react")
+        );
+        assert_eq!(resolution.urls, vec!["https://github.com/facebook/react"]);
+
+        let resolution = resolve(&config, "wrok");
+        assert_eq!(resolution.layer, ResolutionLayer::Alias);
+        assert_eq!(resolution.alias_chain, vec!["work", "gh mycompany/repo"]);
+
+        // "bork" is as close to work as to fork, so it's left alone
+        config
+            .aliases
+            .insert("fork".to_string(), "gh mycompany/fork".to_string());
+        assert_eq!(resolve(&config, "bork").autocorrected, None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_resolve_chain() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mycompany/repo".to_string());
+
+        let resolution = resolve(&config, "gh rust-lang/rust; work && $META");
+        assert_eq!(resolution.layer, ResolutionLayer::Chain);
+        assert_eq!(
+            resolution.urls,
+            vec![
+                "https://github.com/rust-lang/rust",
+                "https://github.com/mycompany/repo",
+                "https://finance.yahoo.com/quote/META/",
+            ]
+        );
+        assert_eq!(resolution.steps.len(), 3);
+        assert_eq!(resolution.steps[1].layer, ResolutionLayer::Alias);
+
+        // One failing command fails the chain
+        let resolution = resolve(&config, "gh rust; stock apple inc");
+        assert!(resolution.urls.is_empty());
+        assert!(resolution.error.is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_runs_middleware() {
+        use crate::config::MiddlewareStep;
+
+        let mut config = BunnylolConfig {
+            middleware: vec![
+                MiddlewareStep::Replace {
+                    find: "$me".to_string(),
+                    replace: "octocat".to_string(),
+                },
+                MiddlewareStep::ForceHttps,
+            ],
+            ..BunnylolConfig::default()
+        };
+        config.aliases.insert(
+            "wiki".to_string(),
+            "open http://wiki.example.com".to_string(),
+        );
+
+        let resolution = resolve(&config, "gh @$me");
+        assert_eq!(resolution.query, "gh @$me");
+        assert_eq!(resolution.resolved_query, "gh @octocat");
+        assert_eq!(resolution.urls, vec!["https://github.com/octocat"]);
+
+        let resolution = resolve(&config, "wiki; gh @$me");
+        assert_eq!(
+            resolution.urls,
+            vec!["https://wiki.example.com/", "https://github.com/octocat"]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn test_resolve_ignores_command_case() {
+        let resolution = resolve(&BunnylolConfig::default(), "GH rust-lang/rust; YT Rust");
+        assert_eq!(
+            resolution.urls,
+            vec![
+                "https://github.com/rust-lang/rust",
+                "https://www.youtube.com/results?search_query=Rust",
+            ]
+        );
+        assert_eq!(resolution.steps[0].layer, ResolutionLayer::Builtin);
+    }
+
+    #[test]
+    #[cfg(feature = "google")]
+    fn test_resolve_keeps_semicolons_in_arguments() {
+        let resolution = resolve(&BunnylolConfig::default(), "g c++; java");
+        assert_eq!(resolution.layer, ResolutionLayer::Builtin);
+        assert!(resolution.steps.is_empty());
+        assert_eq!(resolution.resolved_query, "g c++; java");
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn test_describe() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mbinns/blog".to_string());
+        assert_eq!(
+            resolve(&config, "work").describe(),
+            "https://github.com/mbinns/blog\n  layer: alias via gh\n  alias: work → gh mbinns/blog"
+        );
+        assert_eq!(
+            resolve(&config, "stock apple inc").describe(),
+            "error: 'apple inc' isn't a ticker symbol\n  layer: builtin via stock"
+        );
+        assert_eq!(
+            resolve(&config, "weather tomorrow").describe(),
+            "https://www.google.com/search?q=weather%20tomorrow\n  layer: fallback via search (google)"
+        );
+        let chain = resolve(&config, "work; gh rust-lang").describe();
+        assert!(
+            chain.starts_with(
+                "chain of 2 commands\n  1. work\n     https://github.com/mbinns/blog\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,298 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Commands written as Rhai scripts, declared in the config's `[commands]`
+//! section:
+//!
+//! ```toml
+//! [commands.ticket]
+//! script = "scripts/ticket.rhai"
+//! ```
+//!
+//! A script sees the query through these variables:
+//!
+//! - `command`: the binding used, e.g. "ticket"
+//! - `args`: the arguments as an array, double-quoted phrases kept whole
+//! - `query`: the arguments as typed
+//!
+//! and can call `encode(text)`, `url(base, #{ key: value })` to add query
+//! parameters, `search(engine, text)`, and `config(key)` for a config value
+//! like "default_search" (empty when unset). It evaluates to the URL, or
+//! `throw`s a message to reject the arguments:
+//!
+//! ```text
+//! if args.is_empty() {
+//!     "https://tickets.example.com"
+//! } else if args[0].contains("-") {
+//!     `https://tickets.example.com/browse/${args[0].to_upper()}`
+//! } else {
+//!     url("https://tickets.example.com/search", #{ q: query })
+//! }
+//! ```
+//!
+//! Scripts are compiled once at startup, and each run is capped in
+//! operations so a stray loop can't hang a request.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
+
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use url::Url;
+
+use crate::commands::bunnylol_command::{BunnylolCommandInfo, CommandCategory, CommandError};
+use crate::config::{ScriptCommandConfig, get_global_config};
+use crate::utils::args::Args;
+use crate::utils::url_builder::UrlBuilder;
+use crate::utils::url_encoding::encode_url;
+
+/// Most operations one run of a script may take
+pub const MAX_OPERATIONS: u64 = 100_000;
+
+static ENGINE: LazyLock<Engine> = LazyLock::new(|| {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine
+        .register_fn("encode", |text: &str| encode_url(text))
+        .register_fn(
+            "url",
+            |base: &str, params: Map| -> Result<String, Box<EvalAltResult>> {
+                // UrlBuilder panics on an invalid base, and this one is the script's
+                Url::parse(base).map_err(|e| format!("invalid base URL '{}': {}", base, e))?;
+                Ok(params
+                    .iter()
+                    .fold(UrlBuilder::new(base), |url, (key, value)| {
+                        url.query(key, &value.to_string())
+                    })
+                    .build())
+            },
+        )
+        .register_fn("search", |engine: &str, text: &str| {
+            crate::commands::search_url(engine, text)
+        })
+        .register_fn("config", |key: &str| {
+            get_global_config()
+                .and_then(|config| config.setting_value(key))
+                .unwrap_or_default()
+        });
+    engine
+});
+
+/// A command whose URL comes from a Rhai script
+pub struct ScriptCommand {
+    pub name: String,
+    info: BunnylolCommandInfo,
+    ast: AST,
+}
+
+impl ScriptCommand {
+    /// Compile a `[commands.<name>]` entry's script
+    pub fn new(name: &str, config: &ScriptCommandConfig, source: &str) -> Result<Self, String> {
+        let ast = ENGINE.compile(source).map_err(|e| e.to_string())?;
+        let bindings: Vec<&str> = std::iter::once(name)
+            .chain(config.aliases.iter().map(String::as_str))
+            .collect();
+        let description = config
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Run {}", config.script));
+        let example = config.example.clone().unwrap_or_else(|| name.to_string());
+        Ok(ScriptCommand {
+            name: name.to_string(),
+            info: BunnylolCommandInfo::new(&bindings, &description, &example)
+                .with_category(CommandCategory::Other),
+            ast,
+        })
+    }
+
+    pub fn bindings(&self) -> &[String] {
+        &self.info.bindings
+    }
+
+    pub fn info(&self) -> &BunnylolCommandInfo {
+        &self.info
+    }
+
+    /// Run the script for a full query, e.g. "jira PROJ-123"
+    pub fn resolve(&self, full_args: &str) -> Result<String, CommandError> {
+        let mut args = Args::new(full_args);
+        let command = args.next().unwrap_or_default();
+        let query = args.rest().to_string();
+        let mut scope = Scope::new();
+        scope.push("command", command);
+        scope.push("args", args.map(Dynamic::from).collect::<Array>());
+        scope.push("query", query);
+
+        let result = ENGINE
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+            .map_err(|e| match *e {
+                EvalAltResult::ErrorRuntime(message, _) => {
+                    CommandError::InvalidArguments(message.to_string())
+                }
+                e => CommandError::InvalidArguments(format!(
+                    "the {} script failed: {}",
+                    self.name, e
+                )),
+            })?;
+        match result.into_string() {
+            Ok(url) if !url.is_empty() => Ok(url),
+            _ => Err(CommandError::InvalidArguments(format!(
+                "the {} script returned no URL",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// Compile the scripts in a config's `[commands]` section, returning the
+/// commands and why any others couldn't be loaded
+///
+/// Relative script paths are resolved against `base`, the config file's
+/// directory.
+pub fn load(
+    commands: &HashMap<String, ScriptCommandConfig>,
+    base: &Path,
+) -> (Vec<ScriptCommand>, Vec<String>) {
+    let mut names: Vec<&String> = commands.keys().collect();
+    names.sort();
+
+    let mut scripts = Vec::new();
+    let mut errors = Vec::new();
+    for name in names {
+        let config = &commands[name];
+        let path = base.join(&config.script);
+        let script = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| ScriptCommand::new(name, config, &source));
+        match script {
+            Ok(script) => scripts.push(script),
+            Err(e) => errors.push(format!("[commands.{}] {:?}: {}", name, path, e)),
+        }
+    }
+    (scripts, errors)
+}
+
+static SCRIPTS: OnceLock<Vec<ScriptCommand>> = OnceLock::new();
+
+/// Make `scripts` available to the registry; only the first call has any effect
+pub fn install(scripts: Vec<ScriptCommand>) {
+    let _ = SCRIPTS.set(scripts);
+}
+
+/// Every installed scripted command
+pub fn installed() -> &'static [ScriptCommand] {
+    SCRIPTS.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// The installed scripted command with this binding
+pub fn find(binding: &str) -> Option<&'static ScriptCommand> {
+    installed()
+        .iter()
+        .find(|script| script.bindings().iter().any(|b| b == binding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JIRA: &str = r#"
+        if args.is_empty() {
+            "https://jira.example.com"
+        } else if args[0].contains("-") {
+            `https://jira.example.com/browse/${args[0].to_upper()}`
+        } else if args[0] == "search" {
+            search("ddg", query)
+        } else {
+            url("https://jira.example.com/issues/", #{ jql: `text ~ "${query}"` })
+        }
+    "#;
+
+    fn script(source: &str) -> ScriptCommand {
+        let config = ScriptCommandConfig {
+            script: "jira.rhai".to_string(),
+            aliases: vec!["j".to_string()],
+            description: None,
+            example: None,
+        };
+        ScriptCommand::new("jira", &config, source).unwrap()
+    }
+
+    #[test]
+    fn test_script_routes_on_args() {
+        let jira = script(JIRA);
+        assert_eq!(jira.bindings(), ["jira", "j"]);
+        assert_eq!(jira.info().description, "Run jira.rhai");
+        assert_eq!(
+            jira.resolve("jira").as_deref(),
+            Ok("https://jira.example.com")
+        );
+        assert_eq!(
+            jira.resolve("j proj-12").as_deref(),
+            Ok("https://jira.example.com/browse/PROJ-12")
+        );
+        assert_eq!(
+            jira.resolve("jira login page").as_deref(),
+            Ok("https://jira.example.com/issues/?jql=text%20~%20%22login%20page%22")
+        );
+        assert_eq!(
+            jira.resolve("jira search rust").as_deref(),
+            Ok("https://duckduckgo.com/?q=search%20rust")
+        );
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let throws = script(r#"if args.is_empty() { throw "usage: jira <ticket>" } "x""#);
+        assert_eq!(
+            throws.resolve("jira"),
+            Err(CommandError::InvalidArguments(
+                "usage: jira <ticket>".to_string()
+            ))
+        );
+        assert!(script("()").resolve("jira x").is_err());
+        assert!(script("loop {}").resolve("jira").is_err());
+        assert_eq!(
+            script(r#"url("tickets.example.com/search", #{})"#).resolve("jira"),
+            Err(CommandError::InvalidArguments(
+                "invalid base URL 'tickets.example.com/search': relative URL without a base"
+                    .to_string()
+            ))
+        );
+
+        let config = ScriptCommandConfig {
+            script: "broken.rhai".to_string(),
+            aliases: Vec::new(),
+            description: None,
+            example: None,
+        };
+        assert!(ScriptCommand::new("broken", &config, "if {").is_err());
+    }
+
+    #[test]
+    fn test_load_resolves_relative_paths() {
+        let dir = std::env::temp_dir().join(format!("bunnylol-scripts-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(dir.join("scripts/jira.rhai"), JIRA).unwrap();
+
+        let entry = |script: &str| ScriptCommandConfig {
+            script: script.to_string(),
+            aliases: Vec::new(),
+            description: Some("Jira".to_string()),
+            example: None,
+        };
+        let commands = HashMap::from([
+            ("jira".to_string(), entry("scripts/jira.rhai")),
+            ("wiki".to_string(), entry("scripts/missing.rhai")),
+        ]);
+        let (scripts, errors) = load(&commands, &dir);
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].info().description, "Jira");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("[commands.wiki]"), "{}", errors[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! `bunnylol serve --daemon`: running the server in the background without
+//! installing a service
+//!
+//! Forking once the async runtime has started threads isn't safe, so the
+//! daemon is a fresh `bunnylol serve` started in its own process group, with
+//! its output appended to a log file. The server writes its pid file itself
+//! and removes it when it shuts down, so the file only names a running server.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// How long the parent watches a new daemon for an immediate failure, such as
+/// a port in use
+const STARTUP_GRACE: Duration = Duration::from_millis(750);
+
+/// A pid file naming this process, removed when dropped
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Write this process's id to `path`, unless it names another server
+    /// that's still running
+    pub fn create(path: &Path) -> Result<Self, String> {
+        if let Some(pid) = running_pid(path)
+            && pid != std::process::id()
+        {
+            return Err(format!(
+                "bunnylol is already running (pid {}, from {})",
+                pid,
+                path.display()
+            ));
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(PidFile {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Leave it alone if another server has taken it over
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// The pid recorded in `path`, if it holds one
+pub fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// The pid recorded in `path`, if that process is still running
+pub fn running_pid(path: &Path) -> Option<u32> {
+    read_pid(path).filter(|pid| is_running(*pid))
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    // Signal 0 checks that the process exists without touching it
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn is_running(#[allow(unused_variables)] pid: u32) -> bool {
+    false
+}
+
+/// Start `bunnylol <args>` in the background with its output appended to
+/// `log_path`, returning its pid once it has survived startup
+///
+/// `args` should pass `--pid-file` so the server records itself.
+#[cfg(unix)]
+pub fn spawn(args: &[String], log_path: &Path) -> Result<u32, String> {
+    use std::os::unix::process::CommandExt;
+
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let stderr = log
+        .try_clone()
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Could not find the bunnylol binary: {}", e))?;
+
+    // A process group of its own, so Ctrl-C in this terminal doesn't reach it
+    let mut child = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(stderr)
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("Failed to start the server: {}", e))?;
+
+    std::thread::sleep(STARTUP_GRACE);
+    match child.try_wait() {
+        Ok(None) => Ok(child.id()),
+        Ok(Some(status)) => Err(format!(
+            "The server exited right away ({}); see {}",
+            status,
+            log_path.display()
+        )),
+        Err(e) => Err(format!("Failed to check on the server: {}", e)),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn spawn(
+    #[allow(unused_variables)] args: &[String],
+    #[allow(unused_variables)] log_path: &Path,
+) -> Result<u32, String> {
+    Err("--daemon is only supported on Linux and macOS; \
+         use `bunnylol service install` to run the server in the background"
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("bunnylol-daemon-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_pid_file() {
+        let path = temp_path("pid");
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_pid_file_refuses_running_server() {
+        let path = temp_path("running");
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        std::fs::write(&path, format!("{}\n", child.id())).unwrap();
+        let error = PidFile::create(&path).unwrap_err();
+        assert!(error.contains("already running"), "{}", error);
+
+        // Once the server is gone, its stale pid file is replaced
+        child.kill().unwrap();
+        child.wait().unwrap();
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(running_pid(&path), Some(std::process::id()));
+        drop(pid_file);
+    }
+
+    #[test]
+    fn test_read_pid_ignores_garbage() {
+        let path = temp_path("garbage");
+        std::fs::write(&path, "not a pid\n").unwrap();
+        assert_eq!(read_pid(&path), None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read_pid(&path), None);
+    }
+}
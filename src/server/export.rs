@@ -0,0 +1,565 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Export the effective command list as JSON, CSV, or Markdown, for pasting a
+//! cheatsheet into a wiki or diffing configs between machines.
+//!
+//! The list is what queries actually reach: built-in bindings an alias
+//! overrides are left off their command, and keywords a binding or prefix
+//! shadows are left out.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::utils::csv::csv_row;
+use crate::utils::url_encoding::TEMPLATE_PLACEHOLDER;
+use crate::{BunnylolCommandRegistry, BunnylolConfig};
+
+/// File formats the bindings list can be exported as
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Names accepted by `from_extension`, for `--format`
+    pub const NAMES: &'static [&'static str] = &["json", "csv", "md", "markdown"];
+
+    /// Parse the format from a file extension ("json", "csv", "md")
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Suggested file name for the download
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "bunnylol-bindings.json",
+            ExportFormat::Csv => "bunnylol-bindings.csv",
+            ExportFormat::Markdown => "bunnylol-bindings.md",
+        }
+    }
+}
+
+/// One row of the exported command list
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExportEntry {
+    /// "command" for built-ins, plugins, and scripts, or "alias", "keyword",
+    /// "prefix", or "number" for the config's entries
+    pub kind: &'static str,
+    pub bindings: Vec<String>,
+    pub category: String,
+    pub description: String,
+    pub example: String,
+    /// Every example, `example` first
+    pub examples: Vec<String>,
+    /// Argument signature after the binding; empty for aliases and commands without arguments
+    pub args: String,
+    pub tags: Vec<String>,
+}
+
+/// An entry for one of the config's `[aliases]`, `[keywords]`, `[prefixes]`,
+/// or `[numbers]`
+fn config_entry(
+    kind: &'static str,
+    binding: &str,
+    category: &str,
+    description: String,
+    example: String,
+    args: &str,
+) -> ExportEntry {
+    ExportEntry {
+        kind,
+        bindings: vec![binding.to_string()],
+        category: category.to_string(),
+        description,
+        examples: vec![example.clone()],
+        example,
+        args: args.to_string(),
+        tags: Vec::new(),
+    }
+}
+
+/// Collect the commands queries reach: built-ins, then the configured
+/// aliases, keywords, numbers, and prefixes, each sorted
+pub fn export_entries(config: &BunnylolConfig) -> Vec<ExportEntry> {
+    let keywords: HashMap<&str, &str> = BunnylolCommandRegistry::active_keywords(config).collect();
+    let mut commands: Vec<ExportEntry> = BunnylolCommandRegistry::get_all_commands()
+        .iter()
+        .filter_map(|info| {
+            // Aliases are matched before bindings, and keywords before plugins
+            let bindings: Vec<String> = info
+                .bindings
+                .iter()
+                .filter(|binding| {
+                    !config.aliases.contains_key(*binding)
+                        && (BunnylolCommandRegistry::get_handler(binding).is_some()
+                            || !keywords.contains_key(binding.as_str()))
+                })
+                .cloned()
+                .collect();
+            (!bindings.is_empty()).then(|| ExportEntry {
+                kind: "command",
+                bindings,
+                category: info.category.label().to_string(),
+                description: info.description.clone(),
+                example: info.example.clone(),
+                examples: info.examples.clone(),
+                args: info.args.clone(),
+                tags: info.tags.clone(),
+            })
+        })
+        .collect();
+    commands.sort_by_key(|entry| entry.bindings.first().map(|b| b.to_lowercase()));
+
+    let mut configured: Vec<ExportEntry> = config
+        .aliases
+        .iter()
+        .map(|(alias, target)| {
+            config_entry(
+                "alias",
+                alias,
+                "Aliases",
+                format!("Alias for {}", target),
+                alias.clone(),
+                "",
+            )
+        })
+        .collect();
+    configured.extend(keywords.iter().map(|(keyword, url)| {
+        config_entry(
+            "keyword",
+            keyword,
+            "Keywords",
+            format!("Opens {}", url),
+            keyword.to_string(),
+            if url.contains(TEMPLATE_PLACEHOLDER) {
+                "[<args>]"
+            } else {
+                ""
+            },
+        )
+    }));
+    configured.extend(
+        BunnylolCommandRegistry::configured_prefixes(config).map(|(prefix, url)| {
+            config_entry(
+                "prefix",
+                prefix,
+                "Prefixes",
+                format!("Opens {}", url),
+                format!("{}<text>", prefix),
+                "",
+            )
+        }),
+    );
+    configured.extend(config.numbers.iter().map(|(binding, url)| {
+        config_entry(
+            "number",
+            binding,
+            "Numbers",
+            format!("Sends a bare number to {}", url),
+            format!("{} 1234", binding),
+            "<number>",
+        )
+    }));
+    configured.sort_by_key(|entry| (entry.kind, entry.bindings[0].to_lowercase()));
+
+    commands.extend(configured);
+    commands
+}
+
+/// Default and maximum page sizes for the commands API
+pub const COMMANDS_PAGE_DEFAULT: usize = 100;
+pub const COMMANDS_PAGE_MAX: usize = 500;
+
+/// One page of the command list, as served by `/api/commands`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CommandPage {
+    /// Number of entries matching the filter, across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub entries: Vec<ExportEntry>,
+}
+
+/// Page through the command list, keeping entries with a binding that starts
+/// with `prefix` (case-insensitive) when one is given
+///
+/// Entries whose bindings have a frecency score in `binding_scores` come
+/// first, highest score first; the rest keep their usual order.
+pub fn page_entries(
+    config: &BunnylolConfig,
+    prefix: Option<&str>,
+    binding_scores: &HashMap<String, u64>,
+    offset: usize,
+    limit: usize,
+) -> CommandPage {
+    let prefix = prefix.map(str::to_lowercase).filter(|p| !p.is_empty());
+    let limit = limit.clamp(1, COMMANDS_PAGE_MAX);
+    let mut matching: Vec<ExportEntry> = export_entries(config)
+        .into_iter()
+        .filter(|entry| match &prefix {
+            Some(prefix) => entry
+                .bindings
+                .iter()
+                .any(|b| b.to_lowercase().starts_with(prefix.as_str())),
+            None => true,
+        })
+        .collect();
+    if !binding_scores.is_empty() {
+        // Stable, so unscored entries keep their order
+        matching.sort_by_cached_key(|entry| {
+            std::cmp::Reverse(
+                entry
+                    .bindings
+                    .iter()
+                    .filter_map(|binding| binding_scores.get(binding))
+                    .max()
+                    .copied()
+                    .unwrap_or(0),
+            )
+        });
+    }
+
+    CommandPage {
+        total: matching.len(),
+        offset,
+        limit,
+        entries: matching.into_iter().skip(offset).take(limit).collect(),
+    }
+}
+
+/// Entries matching every word of `query` (case-insensitive) in a binding,
+/// tag, category, description, or example, best matches first
+///
+/// A word counts most as a whole binding, then as the start of one, then in
+/// a tag or the category, then anywhere in the text. Ties keep the command
+/// list's order.
+pub fn search_entries(config: &BunnylolConfig, query: &str) -> Vec<ExportEntry> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let score = |entry: &ExportEntry, term: &str| {
+        let bindings = entry.bindings.iter().map(|binding| binding.to_lowercase());
+        let text = || {
+            std::iter::once(&entry.description)
+                .chain(&entry.examples)
+                .any(|text| text.to_lowercase().contains(term))
+        };
+        if bindings.clone().any(|binding| binding == term) {
+            4
+        } else if bindings.clone().any(|binding| binding.starts_with(term)) {
+            3
+        } else if std::iter::once(&entry.category)
+            .chain(&entry.tags)
+            .any(|tag| tag.to_lowercase().contains(term))
+        {
+            2
+        } else if text() {
+            1
+        } else {
+            0
+        }
+    };
+
+    let mut matching: Vec<(u32, ExportEntry)> = export_entries(config)
+        .into_iter()
+        .filter_map(|entry| {
+            let mut total = 0;
+            for term in &terms {
+                match score(&entry, term) {
+                    0 => return None,
+                    points => total += points,
+                }
+            }
+            Some((total, entry))
+        })
+        .collect();
+    // Stable, so equally good matches keep their order
+    matching.sort_by_key(|(total, _)| std::cmp::Reverse(*total));
+    matching.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Render the command list in the requested format
+pub fn render_bindings_export(config: &BunnylolConfig, format: ExportFormat) -> String {
+    let entries = export_entries(config);
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(&entries).expect("entries should serialize")
+        }
+        ExportFormat::Csv => {
+            let mut out = String::from("kind,bindings,category,description,example\n");
+            for entry in &entries {
+                let row = [
+                    entry.kind.to_string(),
+                    entry.bindings.join(" "),
+                    entry.category.clone(),
+                    entry.description.clone(),
+                    entry.example.clone(),
+                ];
+                out.push_str(&csv_row(row.iter().map(String::as_str)));
+                out.push('\n');
+            }
+            out
+        }
+        ExportFormat::Markdown => {
+            let mut out = String::from(
+                "| Command | Category | Description | Example |\n|---------|----------|-------------|---------|\n",
+            );
+            for entry in &entries {
+                let bindings: Vec<String> = entry
+                    .bindings
+                    .iter()
+                    .map(|b| format!("`{}`", markdown_cell(b)))
+                    .collect();
+                out.push_str(&format!(
+                    "| {} | {} | {} | `{}` |\n",
+                    bindings.join(", "),
+                    markdown_cell(&entry.category),
+                    markdown_cell(&entry.description),
+                    markdown_cell(&entry.example),
+                ));
+            }
+            out
+        }
+    }
+}
+
+/// The command list as `binding<TAB>description` lines for a launcher like
+/// dmenu, rofi, or fzf: built-in commands, aliases, and keywords, which open
+/// without arguments (prefixes and numbers need some)
+pub fn render_picker_lines(config: &BunnylolConfig) -> String {
+    let mut out = String::new();
+    for entry in export_entries(config) {
+        if !matches!(entry.kind, "command" | "alias" | "keyword") {
+            continue;
+        }
+        let description: Vec<&str> = entry.description.split_whitespace().collect();
+        out.push_str(&format!(
+            "{}\t{}\n",
+            entry.bindings[0],
+            description.join(" ")
+        ));
+    }
+    out
+}
+
+/// The query in a line a launcher returned from `render_picker_lines`: the
+/// binding before the tab, or the whole line when the user typed their own
+/// (like `gh facebook/react`); `None` when nothing was picked
+pub fn picker_selection(line: &str) -> Option<&str> {
+    let query = line.split('\t').next().unwrap_or_default().trim();
+    (!query.is_empty()).then_some(query)
+}
+
+/// Escape pipes so a value can't break out of its Markdown table cell
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_alias() -> BunnylolConfig {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh a, b".to_string());
+        config
+    }
+
+    #[test]
+    fn test_export_format_from_extension() {
+        assert_eq!(
+            ExportFormat::from_extension("json"),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(ExportFormat::from_extension("CSV"), Some(ExportFormat::Csv));
+        assert_eq!(
+            ExportFormat::from_extension("md"),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(ExportFormat::from_extension("xml"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_export_entries_include_commands_then_aliases() {
+        let entries = export_entries(&config_with_alias());
+        let last = entries.last().expect("entries");
+        assert_eq!(last.kind, "alias");
+        assert_eq!(last.bindings, vec!["work".to_string()]);
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.kind == "command" && e.bindings.contains(&"gh".to_string()))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn test_export_entries_reflect_overrides() {
+        let mut config = config_with_alias();
+        config.aliases.insert("gh".to_string(), "ddg".to_string());
+        config.keywords = [
+            ("ig".to_string(), "https://example.com/{}".to_string()),
+            ("kb".to_string(), "https://kb.example.com/?q={}".to_string()),
+        ]
+        .into();
+        config.prefixes = [("#".to_string(), "https://jira.example.com/{}".to_string())].into();
+        config.numbers = [(
+            "gh".to_string(),
+            "https://github.com/a/b/issues/{}".to_string(),
+        )]
+        .into();
+
+        let entries = export_entries(&config);
+        let github = entries
+            .iter()
+            .find(|e| e.kind == "command" && e.bindings.contains(&"github".to_string()))
+            .expect("github command");
+        assert!(!github.bindings.contains(&"gh".to_string()));
+
+        let configured: Vec<(&str, &str)> = entries
+            .iter()
+            .filter(|e| e.kind != "command")
+            .map(|e| (e.kind, e.bindings[0].as_str()))
+            .collect();
+        assert_eq!(
+            configured,
+            [
+                ("alias", "gh"),
+                ("alias", "work"),
+                ("keyword", "kb"),
+                ("number", "gh"),
+                ("prefix", "#"),
+            ]
+        );
+        let kb = entries.iter().find(|e| e.kind == "keyword").unwrap();
+        assert_eq!(kb.description, "Opens https://kb.example.com/?q={}");
+        assert_eq!(kb.args, "[<args>]");
+    }
+
+    #[test]
+    fn test_render_csv_quotes_fields() {
+        let csv = render_bindings_export(&config_with_alias(), ExportFormat::Csv);
+        assert!(csv.starts_with("kind,bindings,category,description,example\n"));
+        assert!(csv.contains("alias,work,Aliases,\"Alias for gh a, b\",work\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "finance")]
+    fn test_render_markdown_and_json() {
+        let config = config_with_alias();
+        let markdown = render_bindings_export(&config, ExportFormat::Markdown);
+        assert!(markdown.starts_with("| Command | Category |"));
+        assert!(markdown.contains("| `work` | Aliases |"));
+
+        let json = render_bindings_export(&config, ExportFormat::Json);
+        assert!(json.contains(r#""kind": "alias""#));
+        assert!(json.contains(r#""args": "[<provider>] <ticker>""#));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_render_picker_lines() {
+        let mut config = config_with_alias();
+        config.keywords.insert(
+            "kb".to_string(),
+            "https://kb.example.com/search?q={}".to_string(),
+        );
+        config.prefixes.insert(
+            "#".to_string(),
+            "https://tickets.example.com/{}".to_string(),
+        );
+        let lines = render_picker_lines(&config);
+        assert!(lines.lines().all(|line| line.matches('\t').count() == 1));
+        assert!(lines.lines().any(|line| line.starts_with("gh\t")));
+        assert!(lines.contains("\nwork\tAlias for gh a, b\n"));
+        assert!(lines.contains("\nkb\tOpens https://kb.example.com/search?q={}\n"));
+        assert!(!lines.contains("tickets.example.com"));
+    }
+
+    #[test]
+    fn test_picker_selection() {
+        assert_eq!(picker_selection("gh\tGitHub\n"), Some("gh"));
+        assert_eq!(
+            picker_selection(" gh facebook/react \n"),
+            Some("gh facebook/react")
+        );
+        assert_eq!(picker_selection("\n"), None);
+        assert_eq!(picker_selection(""), None);
+    }
+
+    #[test]
+    fn test_page_entries_filters_and_slices() {
+        let config = config_with_alias();
+        let all = page_entries(&config, None, &HashMap::new(), 0, COMMANDS_PAGE_DEFAULT);
+        assert_eq!(all.total, export_entries(&config).len());
+
+        let page = page_entries(&config, None, &HashMap::new(), 2, 3);
+        assert_eq!(page.entries.len(), 3);
+        assert_eq!(page.entries[0], all.entries[2]);
+
+        let filtered = page_entries(&config, Some("WO"), &HashMap::new(), 0, 10);
+        assert_eq!(filtered.total, 1);
+        assert_eq!(filtered.entries[0].bindings, vec!["work".to_string()]);
+
+        assert_eq!(
+            page_entries(&config, None, &HashMap::new(), 0, 10_000).limit,
+            COMMANDS_PAGE_MAX
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_page_entries_puts_frecent_bindings_first() {
+        let config = config_with_alias();
+        let scores = HashMap::from([("work".to_string(), 300), ("gh".to_string(), 100)]);
+        let page = page_entries(&config, None, &scores, 0, 3);
+        assert_eq!(page.entries[0].bindings, vec!["work".to_string()]);
+        assert!(page.entries[1].bindings.contains(&"gh".to_string()));
+        assert_eq!(page.total, export_entries(&config).len());
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_entries() {
+        let config = config_with_alias();
+        let first_bindings = |query: &str| -> Vec<String> {
+            search_entries(&config, query)
+                .into_iter()
+                .map(|entry| entry.bindings[0].clone())
+                .collect()
+        };
+
+        let packages = first_bindings("Package");
+        for binding in ["npm", "cargo", "pypi", "nuget", "brew"] {
+            assert!(packages.contains(&binding.to_string()), "{:?}", packages);
+        }
+        // Starting a binding beats a tag
+        assert_eq!(first_bindings("dock")[0], "dockerhub");
+        // An exact binding beats everything else
+        assert_eq!(first_bindings("npm")[0], "npm");
+        // Every word has to match
+        assert_eq!(first_bindings("rust package")[0], "cargo");
+        assert!(!first_bindings("rust package").contains(&"npm".to_string()));
+        // Aliases are found by what they point at
+        assert_eq!(first_bindings("alias gh"), ["work"]);
+        assert!(first_bindings("zzzz").is_empty());
+        assert!(first_bindings("  ").is_empty());
+    }
+}
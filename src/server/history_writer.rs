@@ -0,0 +1,471 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Background history writes, so a slow disk (or database) never delays a
+//! redirect.
+//!
+//! The `search` handler hands entries to a bounded queue and returns
+//! immediately. A worker thread drains the queue in batches and writes each
+//! batch with a single store call. When the queue is full, new entries are
+//! dropped (and counted) rather than blocking the request. Dropping the writer,
+//! or calling `shutdown`, flushes everything still queued.
+//!
+//! The same thread applies the retention policy at startup and then every
+//! `PRUNE_INTERVAL`, and runs purges, so deletes never race a write to the
+//! same store.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::history_purge::{self, PurgeTarget};
+use crate::{History, HistoryEntry};
+
+/// How many entries can wait for the worker before new ones are dropped
+pub const QUEUE_CAPACITY: usize = 1024;
+
+/// Most entries written in one store call
+pub const BATCH_SIZE: usize = 64;
+
+/// How often the worker applies the retention policy
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Work for the worker thread, handled in the order it was queued
+enum Job {
+    Write(HistoryEntry),
+    /// A command token that matched no binding or alias
+    Unknown(String),
+    Purge(PurgeTarget, mpsc::Sender<Result<usize, String>>),
+}
+
+/// Queues history entries for a background worker thread
+pub struct HistoryWriter {
+    sender: Mutex<Option<SyncSender<Job>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+    dropped: AtomicU64,
+}
+
+impl HistoryWriter {
+    /// Start the worker thread writing to `history`
+    pub fn spawn(history: History) -> Self {
+        Self::with_capacity(history, QUEUE_CAPACITY)
+    }
+
+    fn with_capacity(history: History, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let worker = std::thread::Builder::new()
+            .name("bunnylol-history".to_string())
+            .spawn(move || write_batches(&history, &receiver))
+            .expect("failed to spawn history writer thread");
+
+        Self {
+            sender: Mutex::new(Some(sender)),
+            worker: Mutex::new(Some(worker)),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue a command, with how long it took to resolve when measured,
+    /// without waiting for it to be written
+    pub fn record(&self, command: &str, user: &str, latency: Option<Duration>) {
+        if command.trim().is_empty() {
+            return;
+        }
+        let entry = HistoryEntry::new(command.to_string(), user.to_string());
+        self.enqueue(Job::Write(match latency {
+            Some(latency) => entry.with_latency(latency),
+            None => entry,
+        }));
+    }
+
+    /// Queue a command token that fell through to the default search
+    pub fn record_unknown(&self, command: &str) {
+        if command.trim().is_empty() {
+            return;
+        }
+        self.enqueue(Job::Unknown(command.to_string()));
+    }
+
+    fn enqueue(&self, job: Job) {
+        let sender = self
+            .sender
+            .lock()
+            .expect("history writer lock should not be poisoned");
+        let Some(sender) = sender.as_ref() else {
+            return;
+        };
+        match sender.try_send(job) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                // Log the first drop and then every hundredth, not every one
+                if dropped == 1 || dropped.is_multiple_of(100) {
+                    eprintln!(
+                        "Warning: history write queue is full, dropped {} entries so far",
+                        dropped
+                    );
+                }
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                eprintln!("Warning: history writer has stopped, command not saved");
+            }
+        }
+    }
+
+    /// Delete history on the worker thread, after everything already queued
+    /// has been written, and wait for the number of entries removed
+    pub fn purge(&self, target: PurgeTarget) -> Result<usize, String> {
+        let sender = self
+            .sender
+            .lock()
+            .expect("history writer lock should not be poisoned")
+            .clone()
+            .ok_or_else(|| "history writer has stopped".to_string())?;
+        let (reply, result) = mpsc::channel();
+        // Unlike writes, a purge waits for room in the queue rather than being dropped
+        sender
+            .send(Job::Purge(target, reply))
+            .map_err(|_| "history writer has stopped".to_string())?;
+        result
+            .recv()
+            .map_err(|_| "history writer has stopped".to_string())?
+    }
+
+    /// Number of entries dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop accepting entries and wait for everything queued to be written
+    pub fn shutdown(&self) {
+        // Closing the channel lets the worker exit once the queue is drained
+        self.sender
+            .lock()
+            .expect("history writer lock should not be poisoned")
+            .take();
+        let worker = self
+            .worker
+            .lock()
+            .expect("history writer lock should not be poisoned")
+            .take();
+        if let Some(worker) = worker
+            && worker.join().is_err()
+        {
+            eprintln!("Warning: history writer thread panicked");
+        }
+    }
+}
+
+impl Drop for HistoryWriter {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Worker loop: block for one job, then take whatever else is already queued;
+/// prune whenever the interval has passed
+fn write_batches(history: &History, receiver: &Receiver<Job>) {
+    prune(history);
+    let mut next_prune = Instant::now() + PRUNE_INTERVAL;
+    loop {
+        let first =
+            match receiver.recv_timeout(next_prune.saturating_duration_since(Instant::now())) {
+                Ok(entry) => entry,
+                Err(RecvTimeoutError::Timeout) => {
+                    prune(history);
+                    next_prune = Instant::now() + PRUNE_INTERVAL;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+        let mut batch = Batch::default();
+        let mut next = Some(first);
+        while let Some(job) = next {
+            match job {
+                Job::Write(entry) => batch.entries.push(entry),
+                Job::Unknown(command) => batch.unknown.push(command),
+                Job::Purge(target, reply) => {
+                    // Entries queued before the purge are deleted by it too
+                    batch.flush(history);
+                    let _ = reply.send(history_purge::purge(history, &target));
+                }
+            }
+            next = if batch.len() < BATCH_SIZE {
+                receiver.try_recv().ok()
+            } else {
+                None
+            };
+        }
+        batch.flush(history);
+    }
+}
+
+/// Writes collected by the worker, saved with one store call each
+#[derive(Default)]
+struct Batch {
+    entries: Vec<HistoryEntry>,
+    unknown: Vec<String>,
+}
+
+impl Batch {
+    fn len(&self) -> usize {
+        self.entries.len() + self.unknown.len()
+    }
+
+    fn flush(&mut self, history: &History) {
+        if !self.entries.is_empty()
+            && let Err(e) = history.add_entries(&self.entries)
+        {
+            eprintln!("Warning: Failed to save commands to history: {}", e);
+        }
+        if !self.unknown.is_empty()
+            && let Err(e) = history.record_unknown(&self.unknown)
+        {
+            eprintln!("Warning: Failed to record unknown commands: {}", e);
+        }
+        self.entries.clear();
+        self.unknown.clear();
+    }
+}
+
+fn prune(history: &History) {
+    match history.prune() {
+        Ok(0) => {}
+        Ok(removed) => println!("Pruned {} old history entries", removed),
+        Err(e) => eprintln!("Warning: Failed to prune history: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HistoryStore;
+    use crate::history::UnknownCommand;
+    use crate::history_stats::FrecencyScore;
+    use std::sync::{Arc, Condvar};
+
+    /// In-memory store that records the size of every batch it receives and
+    /// can be made to block, to simulate a slow disk
+    #[derive(Clone, Default)]
+    struct TestStore {
+        entries: Arc<Mutex<Vec<HistoryEntry>>>,
+        batches: Arc<Mutex<Vec<usize>>>,
+        unknown: Arc<Mutex<Vec<UnknownCommand>>>,
+        frecency: Arc<Mutex<Vec<(String, FrecencyScore)>>>,
+        gate: Arc<(Mutex<bool>, Condvar)>,
+    }
+
+    impl TestStore {
+        fn blocked() -> Self {
+            let store = Self::default();
+            *store.gate.0.lock().unwrap() = true;
+            store
+        }
+
+        fn unblock(&self) {
+            *self.gate.0.lock().unwrap() = false;
+            self.gate.1.notify_all();
+        }
+    }
+
+    impl HistoryStore for TestStore {
+        fn append(&self, entry: &HistoryEntry, max_entries: usize) -> Result<(), String> {
+            self.append_batch(std::slice::from_ref(entry), max_entries)
+        }
+
+        fn append_batch(
+            &self,
+            entries: &[HistoryEntry],
+            _max_entries: usize,
+        ) -> Result<(), String> {
+            let (blocked, condvar) = &*self.gate;
+            let _unblocked = condvar
+                .wait_while(blocked.lock().unwrap(), |blocked| *blocked)
+                .unwrap();
+            self.batches.lock().unwrap().push(entries.len());
+            self.entries.lock().unwrap().extend_from_slice(entries);
+            Ok(())
+        }
+
+        fn read_all(&self) -> Result<Vec<HistoryEntry>, String> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+
+        fn clear(&self) -> Result<usize, String> {
+            let mut entries = self.entries.lock().unwrap();
+            let removed = entries.len();
+            entries.clear();
+            Ok(removed)
+        }
+
+        fn prune(&self, cutoff: Option<u64>, max_entries: usize) -> Result<usize, String> {
+            let mut entries = self.entries.lock().unwrap();
+            let before = entries.len();
+            *entries = crate::history::retain_entries(entries.clone(), cutoff, max_entries);
+            Ok(before - entries.len())
+        }
+
+        fn remove_matching(&self, filter: &crate::history::HistoryFilter) -> Result<usize, String> {
+            let mut entries = self.entries.lock().unwrap();
+            let before = entries.len();
+            entries.retain(|entry| !filter.matches(entry));
+            Ok(before - entries.len())
+        }
+
+        fn add_unknown_counts(
+            &self,
+            counts: &[UnknownCommand],
+            max_entries: usize,
+        ) -> Result<(), String> {
+            let mut unknown = self.unknown.lock().unwrap();
+            *unknown = crate::history::count_unknown(unknown.clone(), counts, max_entries);
+            Ok(())
+        }
+
+        fn top_unknown(&self, limit: usize) -> Result<Vec<UnknownCommand>, String> {
+            Ok(self
+                .unknown
+                .lock()
+                .unwrap()
+                .iter()
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+
+        fn record_frecency(
+            &self,
+            entries: &[HistoryEntry],
+            max_entries: usize,
+        ) -> Result<(), String> {
+            let mut frecency = self.frecency.lock().unwrap();
+            *frecency = crate::history::bump_frecency(frecency.clone(), entries, max_entries);
+            Ok(())
+        }
+
+        fn frecency_for_user(&self, user: &str) -> Result<Vec<FrecencyScore>, String> {
+            Ok(self
+                .frecency
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(owner, _)| owner == user)
+                .map(|(_, score)| score.clone())
+                .collect())
+        }
+
+        fn schema_version(&self) -> Result<u32, String> {
+            Ok(crate::history::SCHEMA_VERSION)
+        }
+
+        fn migrate_schema(&self) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn remove_user(&self, user: &str) -> Result<usize, String> {
+            self.frecency
+                .lock()
+                .unwrap()
+                .retain(|(owner, _)| owner != user);
+            let mut entries = self.entries.lock().unwrap();
+            let before = entries.len();
+            entries.retain(|entry| entry.user != user);
+            Ok(before - entries.len())
+        }
+    }
+
+    #[test]
+    fn test_shutdown_flushes_queued_entries_in_order() {
+        let store = TestStore::default();
+        let writer = HistoryWriter::spawn(History::with_store(Box::new(store.clone()), 100));
+        for command in ["gh", "yt cats", "", "ig reels"] {
+            writer.record(command, "10.0.0.1", None);
+        }
+        writer.record("wiki", "10.0.0.1", Some(Duration::from_micros(120)));
+        writer.shutdown();
+        assert_eq!(store.read_all().unwrap()[3].latency_us, Some(120));
+
+        let commands: Vec<String> = store
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.command)
+            .collect();
+        assert_eq!(commands, vec!["gh", "yt cats", "ig reels", "wiki"]);
+
+        // Recording after shutdown is a no-op rather than a panic
+        writer.record("late", "10.0.0.1", None);
+        assert_eq!(store.read_all().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_purge_runs_after_queued_writes() {
+        let store = TestStore::default();
+        let writer = HistoryWriter::spawn(History::with_store(Box::new(store.clone()), 100));
+        writer.record("gh", "10.0.0.1", None);
+        writer.record("yt", "10.0.0.2", None);
+
+        let removed = writer
+            .purge(PurgeTarget::User("10.0.0.1".to_string()))
+            .unwrap();
+        assert_eq!(removed, 1);
+        writer.record("ig", "10.0.0.1", None);
+        writer.shutdown();
+
+        let commands: Vec<String> = store
+            .read_all()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.command)
+            .collect();
+        assert_eq!(commands, vec!["yt", "ig"]);
+        assert!(
+            writer
+                .purge(PurgeTarget::All)
+                .is_err_and(|e| e.contains("stopped"))
+        );
+    }
+
+    #[test]
+    fn test_unknown_commands_are_written_by_the_worker() {
+        let store = TestStore::default();
+        let writer = HistoryWriter::spawn(History::with_store(Box::new(store.clone()), 100));
+        for command in ["jira", "", "jira", "wiki"] {
+            writer.record_unknown(command);
+        }
+        writer.shutdown();
+
+        let top = store.top_unknown(10).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].command.as_str(), top[0].count), ("jira", 2));
+    }
+
+    #[test]
+    fn test_full_queue_drops_instead_of_blocking() {
+        let store = TestStore::blocked();
+        let writer =
+            HistoryWriter::with_capacity(History::with_store(Box::new(store.clone()), 100), 2);
+
+        // The worker takes the first entry and blocks in the store; the next
+        // two fill the queue, and the rest are dropped
+        writer.record("cmd0", "10.0.0.1", None);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        for i in 1..10 {
+            writer.record(&format!("cmd{i}"), "10.0.0.1", None);
+        }
+        assert!(writer.dropped() > 0);
+
+        store.unblock();
+        writer.shutdown();
+        let written = store.read_all().unwrap().len() as u64;
+        assert_eq!(written + writer.dropped(), 10);
+        // Entries that queued up behind the slow write went out together
+        assert!(store.batches.lock().unwrap().iter().any(|&size| size > 1));
+    }
+}
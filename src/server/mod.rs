@@ -7,6 +7,12 @@
 
 // Server runtime (routes, web UI) - only needed for server feature
 #[cfg(feature = "server")]
+pub mod daemon;
+#[cfg(any(feature = "server", feature = "cli"))]
+pub mod export;
+#[cfg(feature = "server")]
+pub mod history_writer;
+#[cfg(feature = "server")]
 pub mod web;
 
 // Service management - only needed for CLI feature
@@ -19,23 +25,110 @@ use rocket::State;
 #[cfg(feature = "server")]
 use rocket::form::{Form, FromForm};
 #[cfg(feature = "server")]
+use rocket::http::{ContentType, Status};
+#[cfg(feature = "server")]
 use rocket::request::FlashMessage;
 #[cfg(feature = "server")]
 use rocket::request::{self, FromRequest, Request};
 #[cfg(feature = "server")]
+use rocket::response::stream::{Event, EventStream};
+#[cfg(feature = "server")]
 use rocket::response::{Flash, Redirect};
 #[cfg(feature = "server")]
-use std::sync::RwLock;
+use rocket::tokio::sync::broadcast;
+#[cfg(feature = "server")]
+use std::borrow::Cow;
+#[cfg(feature = "server")]
+use std::sync::{Arc, RwLock};
 
 #[cfg(feature = "server")]
-use crate::{BunnylolCommandRegistry, BunnylolConfig, History, utils};
+use crate::history_privacy::{self, UserAnonymizer};
+#[cfg(feature = "server")]
+use crate::utils::fuzzy::FuzzyIndex;
+#[cfg(feature = "server")]
+use crate::{
+    BunnylolCommandRegistry, BunnylolConfig, History, HistoryEntry, fallback, history_export,
+    history_grafana, history_purge, history_stats, middleware, resolver, utils,
+};
 
 #[cfg(feature = "server")]
 mod server_impl {
     use super::*;
 
+    /// How many of a client's recent commands the landing page shows
+    const RECENT_COMMANDS_LIMIT: usize = 8;
+
+    /// Most commands `/api/v1/frecency` returns, whatever `limit` asks for
+    const FRECENCY_MAX: usize = 500;
+
+    /// How many command events can queue up for a slow /stats subscriber
+    const EVENT_CHANNEL_CAPACITY: usize = 256;
+
     pub(super) struct AppState {
-        pub config: RwLock<BunnylolConfig>,
+        /// Shared with the `serve --watch` config watcher
+        pub config: Arc<RwLock<BunnylolConfig>>,
+        /// The config's alias names, indexed for typo lookups; rebuilt when
+        /// an alias is added or deleted
+        pub alias_index: Arc<RwLock<FuzzyIndex>>,
+        /// Live feed of command invocations, consumed by /stats/events
+        pub events: broadcast::Sender<CommandEvent>,
+        /// Writes history off the request path; `None` when history is disabled
+        pub history: Option<history_writer::HistoryWriter>,
+        /// Applies `[history] ip_mode` to client IPs before they're stored or shown
+        pub anonymizer: UserAnonymizer,
+        /// Print how each command resolved (`serve --watch`)
+        pub trace: bool,
+    }
+
+    impl AppState {
+        pub fn new(config: BunnylolConfig) -> Self {
+            let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+            let history = config
+                .history
+                .enabled
+                .then(|| History::new(&config))
+                .flatten()
+                .map(history_writer::HistoryWriter::spawn);
+            let anonymizer = UserAnonymizer::from_config(&config);
+            Self {
+                alias_index: Arc::new(RwLock::new(alias_index(&config))),
+                config: Arc::new(RwLock::new(config)),
+                events,
+                history,
+                anonymizer,
+                trace: false,
+            }
+        }
+    }
+
+    /// Swap in a config re-read from disk
+    pub(super) fn apply_reload(
+        config: &RwLock<BunnylolConfig>,
+        aliases: &RwLock<FuzzyIndex>,
+        mut reloaded: BunnylolConfig,
+    ) {
+        let mut config = config.write().expect("config state should not be poisoned");
+        // The address and port it's listening on only change on a restart
+        reloaded.server = config.server.clone();
+        *aliases.write().expect("alias index should not be poisoned") = alias_index(&reloaded);
+        // Commands read prefixes, keywords, and numbers from the global config
+        crate::config::replace_global_config(reloaded.clone());
+        *config = reloaded;
+    }
+
+    /// Index the config's alias names for `BunnylolCommandRegistry::closest_command`
+    fn alias_index(config: &BunnylolConfig) -> FuzzyIndex {
+        FuzzyIndex::new(config.aliases.keys().map(String::as_str))
+    }
+
+    /// A single command invocation, as streamed to the stats page
+    #[derive(Clone, Debug, rocket::serde::Serialize)]
+    #[serde(crate = "rocket::serde")]
+    pub(super) struct CommandEvent {
+        pub command: String,
+        pub url: String,
+        pub user: String,
+        pub timestamp: u64,
     }
 
     #[derive(FromForm)]
@@ -49,6 +142,31 @@ mod server_impl {
         pub alias: String,
     }
 
+    // File download with a Content-Disposition header so browsers save it
+    #[derive(rocket::Responder)]
+    pub(super) struct Download {
+        pub body: String,
+        pub content_type: ContentType,
+        pub disposition: rocket::http::Header<'static>,
+    }
+
+    /// `resolver::resolve` on a blocking thread, since an `upstream` fallback
+    /// step waits on another server
+    async fn resolve_blocking(config: &BunnylolConfig, query: &str) -> resolver::Resolution {
+        let (config, query) = (config.clone(), query.to_string());
+        rocket::tokio::task::spawn_blocking(move || resolver::resolve(&config, &query))
+            .await
+            .expect("resolving a query should not panic")
+    }
+
+    /// `fallback::resolve` on a blocking thread, as for `resolve_blocking`
+    async fn fallback_blocking(config: &BunnylolConfig, query: &str) -> fallback::FallbackMatch {
+        let (config, query) = (config.clone(), query.to_string());
+        rocket::tokio::task::spawn_blocking(move || fallback::resolve(&config, &query))
+            .await
+            .expect("resolving a fallback should not panic")
+    }
+
     fn alias_redirect(status: &str, message: &str) -> Flash<Redirect> {
         Flash::new(Redirect::to("/"), status, message)
     }
@@ -69,41 +187,257 @@ mod server_impl {
         }
     }
 
+    // Request guard: whether the connection comes from the server's own
+    // machine. Unlike `ClientIP`, it looks only at the socket peer, never at
+    // headers like `X-Real-IP` that a client can set to anything.
+    pub(super) struct LocalPeer(pub bool);
+
+    #[rocket::async_trait]
+    impl<'r> FromRequest<'r> for LocalPeer {
+        type Error = ();
+
+        async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+            let local = req.remote().is_some_and(|peer| peer.ip().is_loopback());
+            request::Outcome::Success(LocalPeer(local))
+        }
+    }
+
+    // Request guard identifying the client in history: the logged-in user from
+    // `server.identity_header` when present, otherwise the client IP after
+    // `history.ip_mode`. `None` when nothing identifies the client. A user name
+    // that can't be stored in a history line is rejected with 400.
+    pub(super) struct ClientUser(pub Option<String>);
+
+    #[rocket::async_trait]
+    impl<'r> FromRequest<'r> for ClientUser {
+        type Error = ();
+
+        async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+            let ClientIP(ip) = req
+                .guard::<ClientIP>()
+                .await
+                .succeeded()
+                .unwrap_or(ClientIP("unknown".to_string()));
+            let Some(state) = req.rocket().state::<AppState>() else {
+                return request::Outcome::Success(ClientUser(Some(ip)));
+            };
+            let identity_header = state
+                .config
+                .read()
+                .expect("config state should not be poisoned")
+                .server
+                .identity_header
+                .clone();
+            let logged_in_user = identity_header
+                .as_deref()
+                .and_then(|header| req.headers().get_one(header));
+            if logged_in_user.is_some_and(|user| !HistoryEntry::is_storable_user(user)) {
+                return request::Outcome::Error((Status::BadRequest, ()));
+            }
+            request::Outcome::Success(ClientUser(state.anonymizer.identify(logged_in_user, &ip)))
+        }
+    }
+
     // http://localhost:8000/?cmd=gh
-    #[rocket::get("/?<cmd>&<tab>")]
-    pub(super) fn search(
+    // http://localhost:8000/?cmd=gh&qr=1 renders the resolved URL as a QR code
+    // http://localhost:8000/?tab=commands&page=2 pages through the bindings list
+    #[rocket::get("/?<cmd>&<tab>&<qr>&<page>")]
+    pub(super) async fn search(
         cmd: Option<&str>,
         tab: Option<&str>,
+        qr: Option<&str>,
+        page: Option<usize>,
         flash: Option<FlashMessage<'_>>,
         state: &State<AppState>,
-        client_ip: ClientIP,
+        client_user: ClientUser,
     ) -> Result<Redirect, rocket::response::content::RawHtml<String>> {
         let config = state
             .config
             .read()
             .expect("config state should not be poisoned")
             .clone();
+        // `None` when ip_mode = "none" and nobody is logged in
+        let user = client_user.0;
 
         match cmd {
             Some(cmd_str) => {
                 println!("bunnylol command: {}", cmd_str);
-                let resolved = config.resolve_command(cmd_str);
-                let command = utils::get_command_from_query_string(&resolved);
-                let redirect_url = BunnylolCommandRegistry::process_command(command, &resolved);
-                println!("redirecting to: {}", redirect_url);
+                if state.trace {
+                    println!("{}", resolve_blocking(&config, cmd_str).await.describe());
+                }
+                let started = std::time::Instant::now();
+                let query = middleware::rewrite_query(&config, cmd_str);
+                let commands = resolver::split_commands(&config, &query);
+                let mut resolved = Cow::Borrowed(&*query);
+                let (command, mut redirect_urls) = if commands.len() > 1 {
+                    // Chained commands (`gh rust; cal tomorrow`) each resolve on
+                    // their own, and all their URLs open together
+                    let resolution = resolve_blocking(&config, cmd_str).await;
+                    if let Some(error) = resolution.error {
+                        println!("invalid command: {}", error);
+                        return Err(rocket::response::content::RawHtml(
+                            web::render_command_error_html(
+                                cmd_str,
+                                &error,
+                                None,
+                                &config.get_search_url(cmd_str),
+                            ),
+                        ));
+                    }
+                    (None, resolution.urls)
+                } else {
+                    resolved =
+                        config.resolve_command(commands.first().map_or(&*query, String::as_str));
 
-                // Track command in history if enabled
-                if config.history.enabled
-                    && let Some(history) = History::new(&config)
-                    && let Err(e) = history.add(cmd_str, &client_ip.0)
-                {
-                    eprintln!("Warning: Failed to save command to history: {}", e);
+                    // Typos close to exactly one command run as that command when
+                    // autocorrect allows; other near-misses get a correction offered
+                    let suggestion = {
+                        let aliases = state
+                            .alias_index
+                            .read()
+                            .expect("alias index should not be poisoned");
+                        if let Some(corrected) = BunnylolCommandRegistry::autocorrect(
+                            &resolved,
+                            &aliases,
+                            config.fuzzy.autocorrect_distance,
+                        ) {
+                            println!("autocorrected to: {}", corrected);
+                            resolved = Cow::Owned(config.resolve_command(&corrected).into_owned());
+                        }
+                        BunnylolCommandRegistry::closest_command(
+                            utils::get_command_from_query_string(&resolved),
+                            &aliases,
+                        )
+                        .map(|found| found.candidate.to_string())
+                    };
+                    let command = utils::get_command_from_query_string(&resolved);
+
+                    // `gh --help` shows the help page for a command
+                    if resolved.split_whitespace().nth(1) == Some("--help") {
+                        return Ok(Redirect::to(utils::url_encoding::build_path_url(
+                            "/help", command,
+                        )));
+                    }
+
+                    // Offer a correction instead of silently searching for a typo
+                    if let Some(suggestion) = suggestion {
+                        let suggested_query =
+                            format!("{}{}", suggestion, &resolved[command.len()..]);
+                        println!("did you mean: {}", suggested_query);
+                        return Err(rocket::response::content::RawHtml(
+                            web::render_did_you_mean_html(
+                                &resolved,
+                                &suggested_query,
+                                &config.get_search_url(&resolved),
+                            ),
+                        ));
+                    }
+
+                    if !BunnylolCommandRegistry::has_command(command) {
+                        let fallback = fallback_blocking(&config, &resolved).await;
+                        println!("fallback matched: {}", fallback.step);
+                        (
+                            Some(command.to_string()),
+                            vec![middleware::rewrite_url(&config, fallback.url)],
+                        )
+                    } else {
+                        match BunnylolCommandRegistry::process_targets_async(command, &resolved)
+                            .await
+                        {
+                            Ok(urls) => (
+                                Some(command.to_string()),
+                                urls.into_iter()
+                                    .map(|url| middleware::rewrite_url(&config, url))
+                                    .collect(),
+                            ),
+                            Err(e) => {
+                                println!("invalid command: {}", e);
+                                return Err(rocket::response::content::RawHtml(
+                                    web::render_command_error_html(
+                                        &resolved,
+                                        &e.to_string(),
+                                        BunnylolCommandRegistry::get_command_info(command),
+                                        &config.get_search_url(&resolved),
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                };
+                let latency = started.elapsed();
+                println!("redirecting to: {}", redirect_urls.join(", "));
+
+                // Track command in history if enabled; the write happens in the background
+                if let Some(history) = &state.history {
+                    history.record(
+                        cmd_str,
+                        user.as_deref().unwrap_or(history_privacy::ANONYMOUS_USER),
+                        Some(latency),
+                    );
+                    // Misses that fell through to the default search hint at missing commands
+                    if let Some(command) = &command
+                        && !BunnylolCommandRegistry::has_command(command)
+                    {
+                        history.record_unknown(command);
+                    }
+                }
+
+                // Nobody may be watching /stats; a send error just means no subscribers
+                let _ = state.events.send(CommandEvent {
+                    command: cmd_str.to_string(),
+                    url: redirect_urls.join(" "),
+                    user: user
+                        .clone()
+                        .unwrap_or_else(|| history_privacy::ANONYMOUS_USER.to_string()),
+                    timestamp: history_stats::now_secs(),
+                });
+
+                // A redirect can only target one URL, so let the user open the rest
+                if redirect_urls.len() > 1 {
+                    return Err(rocket::response::content::RawHtml(
+                        web::render_multi_target_html(&resolved, &redirect_urls),
+                    ));
+                }
+                let redirect_url = redirect_urls.remove(0);
+
+                if qr.is_some_and(|value| value != "0") {
+                    return Err(rocket::response::content::RawHtml(
+                        web::render_qr_page_html(&resolved, &redirect_url),
+                    ));
                 }
 
                 Ok(Redirect::to(redirect_url))
             }
             None => {
-                let page_state = web::LandingPageState::new(tab, flash);
+                // Pages are 1-based in the URL
+                let mut page_state = web::LandingPageState::new(tab, flash)
+                    .with_page(page.unwrap_or(1).saturating_sub(1));
+                if config.history.enabled
+                    && let Some(user) = &user
+                    && let Some(history) = History::new(&config)
+                {
+                    match history.read_all() {
+                        Ok(entries) => {
+                            let own: Vec<&HistoryEntry> =
+                                entries.iter().filter(|e| &e.user == user).collect();
+                            let now = history_stats::now_secs();
+                            page_state = page_state
+                                .with_recent_commands(history_stats::recent_commands(
+                                    own.iter().copied(),
+                                    RECENT_COMMANDS_LIMIT,
+                                ))
+                                .with_daily_usage(history_stats::daily_usage(
+                                    own.iter().copied(),
+                                    now,
+                                    history_stats::SPARKLINE_DAYS,
+                                ));
+                        }
+                        Err(e) => eprintln!("Warning: Failed to read history: {}", e),
+                    }
+                    let commands = client_frecency(&history, Some(user));
+                    page_state = page_state
+                        .with_frecency(&commands, &history_stats::group_by_binding(&commands));
+                }
                 Err(rocket::response::content::RawHtml(
                     web::render_landing_page_html(&config, &page_state),
                 ))
@@ -111,6 +445,53 @@ mod server_impl {
         }
     }
 
+    // http://localhost:8000/help/gh
+    #[rocket::get("/help/<command>")]
+    pub(super) fn help(
+        command: &str,
+        state: &State<AppState>,
+    ) -> Option<rocket::response::content::RawHtml<String>> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+
+        // Aliases show help for the command they expand to
+        let resolved = config.resolve_command(command);
+        let binding = utils::get_command_from_query_string(&resolved);
+        let info = BunnylolCommandRegistry::get_command_info(binding)?;
+
+        Some(rocket::response::content::RawHtml(
+            web::render_help_page_html(info, &config),
+        ))
+    }
+
+    // http://localhost:8000/bindings/export/md
+    #[rocket::get("/bindings/export/<format>")]
+    pub(super) fn export_bindings(format: &str, state: &State<AppState>) -> Option<Download> {
+        let format = export::ExportFormat::from_extension(format)?;
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+
+        let content_type = match format {
+            export::ExportFormat::Json => ContentType::JSON,
+            export::ExportFormat::Csv => ContentType::CSV,
+            export::ExportFormat::Markdown => ContentType::new("text", "markdown"),
+        };
+        Some(Download {
+            body: export::render_bindings_export(&config, format),
+            content_type,
+            disposition: rocket::http::Header::new(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        })
+    }
+
     #[rocket::post("/aliases", data = "<form>")]
     pub(super) fn add_alias(form: Form<AliasForm>, state: &State<AppState>) -> Flash<Redirect> {
         let alias = form.alias.trim();
@@ -149,6 +530,10 @@ mod server_impl {
             return alias_redirect("error", &format!("Could not save alias: {error}"));
         }
 
+        *state
+            .alias_index
+            .write()
+            .expect("alias index should not be poisoned") = alias_index(&config);
         alias_redirect(status, &format!("Alias '{alias}' saved."))
     }
 
@@ -177,103 +562,1587 @@ mod server_impl {
             return alias_redirect("error", &format!("Could not delete alias: {error}"));
         }
 
+        *state
+            .alias_index
+            .write()
+            .expect("alias index should not be poisoned") = alias_index(&config);
         alias_redirect("deleted", &format!("Alias '{alias}' deleted."))
     }
 
-    // Health check endpoint for Docker healthcheck (no verbose logging)
-    #[rocket::get("/health")]
-    pub(super) fn health() -> &'static str {
-        "ok"
+    // POST http://localhost:8000/api/v1/reload re-reads the config file, e.g.
+    // after `bunnylol alias add --reload`; only from the server itself
+    #[rocket::post("/api/v1/reload")]
+    pub(super) fn reload_config(
+        state: &State<AppState>,
+        local_peer: LocalPeer,
+    ) -> Result<&'static str, rocket::response::status::Custom<String>> {
+        if !local_peer.0 {
+            return Err(rocket::response::status::Custom(
+                Status::Forbidden,
+                "Reloading the config is only allowed from the server itself".to_string(),
+            ));
+        }
+        let reloaded = BunnylolConfig::load()
+            .map_err(|e| rocket::response::status::Custom(Status::InternalServerError, e))?;
+        apply_reload(&state.config, &state.alias_index, reloaded);
+        Ok("reloaded")
     }
 
-    // Catch 404 errors and show landing page
-    #[rocket::catch(404)]
-    pub(super) fn not_found(req: &rocket::Request) -> rocket::response::content::RawHtml<String> {
-        // Get config from request state
-        if let Some(state) = req.rocket().state::<AppState>() {
-            let config = state
-                .config
-                .read()
-                .expect("config state should not be poisoned")
-                .clone();
-            rocket::response::content::RawHtml(web::render_landing_page_html(
-                &config,
-                &web::LandingPageState::default(),
-            ))
-        } else {
-            // Fallback if config is not available (shouldn't happen)
-            rocket::response::content::RawHtml(
-                "<html><body><h1>404 Not Found</h1></body></html>".to_string(),
-            )
+    // Dry run: where a query would go, without redirecting or writing history
+    // http://localhost:8000/api/resolve?cmd=gh
+    #[rocket::get("/api/resolve?<cmd>")]
+    pub(super) async fn resolve_api(
+        cmd: &str,
+        state: &State<AppState>,
+    ) -> rocket::serde::json::Json<resolver::Resolution> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        rocket::serde::json::Json(resolve_blocking(&config, cmd).await)
+    }
+
+    /// Every command the client has run, by stored frecency; empty (with a
+    /// warning) when the store can't be read
+    fn client_frecency(history: &History, user: Option<&str>) -> Vec<history_stats::CommandScore> {
+        let user = user.unwrap_or(history_privacy::ANONYMOUS_USER);
+        history.top_frecency(user, usize::MAX).unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to read frecency: {}", e);
+            Vec::new()
+        })
+    }
+
+    // One page of the command list, for clients that can't load it all at once;
+    // the commands the client uses most come first
+    // http://localhost:8000/api/commands?q=gh&offset=0&limit=20
+    #[rocket::get("/api/commands?<q>&<offset>&<limit>")]
+    pub(super) fn commands_api(
+        q: Option<&str>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+        state: &State<AppState>,
+        client_user: ClientUser,
+    ) -> rocket::serde::json::Json<export::CommandPage> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let binding_scores = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+            .map(|history| {
+                history_stats::group_by_binding(&client_frecency(
+                    &history,
+                    client_user.0.as_deref(),
+                ))
+                .into_iter()
+                .map(|score| (score.command, score.score))
+                .collect()
+            })
+            .unwrap_or_default();
+        rocket::serde::json::Json(export::page_entries(
+            &config,
+            q,
+            &binding_scores,
+            offset.unwrap_or(0),
+            limit.unwrap_or(export::COMMANDS_PAGE_DEFAULT),
+        ))
+    }
+
+    // The requesting client's commands by frecency, for launchers to suggest
+    // from; `q` keeps commands starting with it (case-insensitive)
+    // http://localhost:8000/api/v1/frecency?q=gh&limit=10
+    #[rocket::get("/api/v1/frecency?<q>&<limit>")]
+    pub(super) fn frecency_api(
+        q: Option<&str>,
+        limit: Option<usize>,
+        state: &State<AppState>,
+        client_user: ClientUser,
+    ) -> Result<
+        rocket::serde::json::Json<Vec<history_stats::CommandScore>>,
+        rocket::response::status::Custom<String>,
+    > {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return Err(rocket::response::status::Custom(
+                Status::NotFound,
+                "History is disabled".to_string(),
+            ));
+        };
+        let user = client_user
+            .0
+            .unwrap_or_else(|| history_privacy::ANONYMOUS_USER.to_string());
+        let commands = history
+            .top_frecency(&user, usize::MAX)
+            .map_err(|e| rocket::response::status::Custom(Status::InternalServerError, e))?;
+        let q = q.map(str::to_lowercase).unwrap_or_default();
+        Ok(rocket::serde::json::Json(
+            commands
+                .into_iter()
+                .filter(|score| score.command.to_lowercase().starts_with(&q))
+                .take(limit.unwrap_or(web::STATS_TOP_LIMIT).min(FRECENCY_MAX))
+                .collect(),
+        ))
+    }
+
+    // The requesting client's history as a file; all history when ip_mode = "none"
+    // and there's no identity header, since then there is only one user
+    // http://localhost:8000/api/v1/history/export?format=csv&since=7d
+    #[rocket::get("/api/v1/history/export?<format>&<since>")]
+    pub(super) fn history_export_api(
+        format: Option<&str>,
+        since: Option<&str>,
+        state: &State<AppState>,
+        client_user: ClientUser,
+    ) -> Result<Download, rocket::response::status::Custom<String>> {
+        let error =
+            |status, message: String| Err(rocket::response::status::Custom(status, message));
+        let Some(format) = history_export::HistoryExportFormat::parse(format.unwrap_or("json"))
+        else {
+            return error(Status::BadRequest, "format must be csv or json".to_string());
+        };
+        let since = match since
+            .map(|since| history_export::parse_since(since, history_stats::now_secs()))
+            .transpose()
+        {
+            Ok(since) => since,
+            Err(e) => return error(Status::BadRequest, e),
+        };
+
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let user = client_user.0;
+        // Behind an auth proxy, a request without the header is nobody's
+        if user.is_none() && config.server.identity_header.is_some() {
+            return error(
+                Status::Forbidden,
+                "History export needs a logged-in user".to_string(),
+            );
         }
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return error(Status::NotFound, "History is disabled".to_string());
+        };
+        let entries = match history.read_all() {
+            Ok(entries) => entries,
+            Err(e) => return error(Status::InternalServerError, e),
+        };
+
+        let own = entries
+            .iter()
+            .filter(|entry| user.as_ref().is_none_or(|user| &entry.user == user));
+        let body = history_export::render_history_export(
+            &history_export::select_entries(own, since),
+            format,
+        );
+        let content_type = match format {
+            history_export::HistoryExportFormat::Csv => ContentType::CSV,
+            history_export::HistoryExportFormat::Json => ContentType::JSON,
+        };
+        Ok(Download {
+            body,
+            content_type,
+            disposition: rocket::http::Header::new(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", format.file_name()),
+            ),
+        })
     }
-}
 
-#[cfg(feature = "server")]
-use server_impl::*;
+    /// Most top commands `/api/v1/history/top` returns, whatever `limit` asks for
+    const TOP_COMMANDS_MAX: usize = 500;
 
-/// Launch the Bunnylol web server with the given configuration
-#[cfg(feature = "server")]
-pub async fn launch(config: BunnylolConfig) -> Result<(), Box<rocket::Error>> {
-    println!(
-        "Bunnylol server starting with default search: {}",
-        config.default_search
-    );
-    println!(
-        "Server listening on {}:{}",
-        config.server.address, config.server.port
-    );
+    /// Ranked command usage over a window, for dashboards
+    #[derive(Debug, rocket::serde::Serialize)]
+    #[serde(crate = "rocket::serde")]
+    pub(super) struct TopCommands {
+        /// Start of the window in seconds since the epoch; `None` for all history
+        pub since: Option<u64>,
+        pub commands: Vec<history_stats::CommandScore>,
+    }
 
-    let figment = rocket::Config::figment()
-        .merge(("address", config.server.address.clone()))
-        .merge(("port", config.server.port))
-        .merge(("log_level", config.server.log_level.clone()))
-        .merge(("ident", format!("Bunnylol/{}", env!("CARGO_PKG_VERSION"))));
-    let state = AppState {
-        config: RwLock::new(config),
-    };
+    /// Start of a `window=` query parameter (7d, a date, or a timestamp)
+    fn parse_window(
+        window: Option<&str>,
+    ) -> Result<Option<u64>, rocket::response::status::Custom<String>> {
+        window
+            .map(|window| history_export::parse_since(window, history_stats::now_secs()))
+            .transpose()
+            .map_err(|_| {
+                rocket::response::status::Custom(
+                    Status::BadRequest,
+                    "window must be an age like 7d, a date like 2024-01-31, or a Unix timestamp"
+                        .to_string(),
+                )
+            })
+    }
 
-    let _rocket = rocket::custom(figment)
-        .manage(state)
-        .mount(
-            "/",
-            rocket::routes![search, add_alias, delete_alias, health],
-        )
-        .register("/", rocket::catchers![not_found])
-        .launch()
-        .await?;
-    Ok(())
-}
+    // http://localhost:8000/api/v1/history/top?window=7d&limit=20
+    #[rocket::get("/api/v1/history/top?<window>&<limit>")]
+    pub(super) fn history_top_api(
+        window: Option<&str>,
+        limit: Option<usize>,
+        state: &State<AppState>,
+    ) -> Result<rocket::serde::json::Json<TopCommands>, rocket::response::status::Custom<String>>
+    {
+        let error =
+            |status, message: String| Err(rocket::response::status::Custom(status, message));
+        let since = parse_window(window)?;
 
-#[cfg(all(test, feature = "server"))]
-mod tests {
-    use std::collections::HashMap;
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return error(Status::NotFound, "History is disabled".to_string());
+        };
+        let limit = limit.unwrap_or(web::STATS_TOP_LIMIT).min(TOP_COMMANDS_MAX);
+        match history.top_commands(since, limit) {
+            Ok(commands) => Ok(rocket::serde::json::Json(TopCommands { since, commands })),
+            Err(e) => error(Status::InternalServerError, e),
+        }
+    }
 
-    use rocket::http::Status;
-    use rocket::local::blocking::Client;
+    /// Resolution latency per binding over a window
+    #[derive(Debug, rocket::serde::Serialize)]
+    #[serde(crate = "rocket::serde")]
+    pub(super) struct CommandLatencies {
+        /// Start of the window in seconds since the epoch; `None` for all history
+        pub since: Option<u64>,
+        pub commands: Vec<history_stats::LatencyStats>,
+    }
 
-    use super::*;
+    // http://localhost:8000/api/v1/history/latency?window=1d&limit=20: p50/p95
+    // time to resolve each binding, slowest first
+    #[rocket::get("/api/v1/history/latency?<window>&<limit>")]
+    pub(super) fn history_latency_api(
+        window: Option<&str>,
+        limit: Option<usize>,
+        state: &State<AppState>,
+    ) -> Result<rocket::serde::json::Json<CommandLatencies>, rocket::response::status::Custom<String>>
+    {
+        let error =
+            |status, message: String| Err(rocket::response::status::Custom(status, message));
+        let since = parse_window(window)?;
 
-    #[test]
-    fn test_search_resolves_aliases() {
-        let mut config = BunnylolConfig::default();
-        config.history.enabled = false;
-        config.aliases = HashMap::from([("work".to_string(), "gh @octocat".to_string())]);
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return error(Status::NotFound, "History is disabled".to_string());
+        };
+        let entries = match history.read_all() {
+            Ok(entries) => entries,
+            Err(e) => return error(Status::InternalServerError, e),
+        };
+        let mut commands = history_stats::latency_by_binding(&entries, since);
+        commands.truncate(limit.unwrap_or(web::STATS_TOP_LIMIT).min(TOP_COMMANDS_MAX));
+        Ok(rocket::serde::json::Json(CommandLatencies {
+            since,
+            commands,
+        }))
+    }
 
-        let state = AppState {
-            config: RwLock::new(config),
+    // http://localhost:8000/api/v1/history/unknown?limit=20: the most frequent
+    // commands that matched nothing and fell through to the default search
+    #[rocket::get("/api/v1/history/unknown?<limit>")]
+    pub(super) fn history_unknown_api(
+        limit: Option<usize>,
+        state: &State<AppState>,
+    ) -> Result<
+        rocket::serde::json::Json<Vec<crate::history::UnknownCommand>>,
+        rocket::response::status::Custom<String>,
+    > {
+        let error =
+            |status, message: String| Err(rocket::response::status::Custom(status, message));
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return error(Status::NotFound, "History is disabled".to_string());
         };
-        let rocket = rocket::build()
-            .manage(state)
-            .mount("/", rocket::routes![search]);
-        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let limit = limit.unwrap_or(web::STATS_TOP_LIMIT).min(TOP_COMMANDS_MAX);
+        match history.top_unknown(limit) {
+            Ok(unknown) => Ok(rocket::serde::json::Json(unknown)),
+            Err(e) => error(Status::InternalServerError, e),
+        }
+    }
 
-        let response = client.get("/?cmd=work").dispatch();
+    /// History for the Grafana endpoints, or 404 when it's disabled
+    fn grafana_history(
+        state: &State<AppState>,
+    ) -> Result<Vec<HistoryEntry>, rocket::response::status::Custom<String>> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let Some(history) = config
+            .history
+            .enabled
+            .then(|| History::new(&config))
+            .flatten()
+        else {
+            return Err(rocket::response::status::Custom(
+                Status::NotFound,
+                "History is disabled".to_string(),
+            ));
+        };
+        history
+            .read_all()
+            .map_err(|e| rocket::response::status::Custom(Status::InternalServerError, e))
+    }
 
-        assert_eq!(response.status(), Status::SeeOther);
-        assert_eq!(
-            response.headers().get_one("Location"),
-            Some("https://github.com/octocat")
-        );
+    // Grafana simple JSON datasource, URL http://localhost:8000/api/v1/grafana;
+    // "Save & test" only checks this answers
+    #[rocket::get("/api/v1/grafana")]
+    pub(super) fn grafana_health(
+        state: &State<AppState>,
+    ) -> Result<&'static str, rocket::response::status::Custom<String>> {
+        grafana_history(state).map(|_| "OK")
+    }
+
+    // Metric names for the query editor's picker
+    #[rocket::post("/api/v1/grafana/search", data = "<search>")]
+    pub(super) fn grafana_search(
+        search: Option<rocket::serde::json::Json<history_grafana::SearchRequest>>,
+        state: &State<AppState>,
+    ) -> Result<rocket::serde::json::Json<Vec<String>>, rocket::response::status::Custom<String>>
+    {
+        let entries = grafana_history(state)?;
+        let query = search
+            .map(|search| search.into_inner().target)
+            .unwrap_or_default();
+        Ok(rocket::serde::json::Json(history_grafana::search_metrics(
+            &entries, &query,
+        )))
+    }
+
+    #[rocket::post("/api/v1/grafana/query", data = "<query>")]
+    pub(super) fn grafana_query(
+        query: rocket::serde::json::Json<history_grafana::QueryRequest>,
+        state: &State<AppState>,
+    ) -> Result<
+        rocket::serde::json::Json<Vec<history_grafana::QueryResult>>,
+        rocket::response::status::Custom<String>,
+    > {
+        let entries = grafana_history(state)?;
+        history_grafana::run_query(&entries, &query)
+            .map(rocket::serde::json::Json)
+            .map_err(|e| rocket::response::status::Custom(Status::BadRequest, e))
+    }
+
+    /// Response to a history purge
+    #[derive(Debug, rocket::serde::Serialize)]
+    #[serde(crate = "rocket::serde")]
+    pub(super) struct PurgeResponse {
+        pub removed: usize,
+    }
+
+    // DELETE http://localhost:8000/api/v1/history deletes the caller's own
+    // history; `?ip=...` deletes another client's, only from the server itself
+    #[rocket::delete("/api/v1/history?<ip>")]
+    pub(super) fn purge_history_api(
+        ip: Option<&str>,
+        state: &State<AppState>,
+        local_peer: LocalPeer,
+        client_user: ClientUser,
+    ) -> Result<rocket::serde::json::Json<PurgeResponse>, rocket::response::status::Custom<String>>
+    {
+        let error =
+            |status, message: String| Err(rocket::response::status::Custom(status, message));
+        let user = match ip {
+            Some(ip) => {
+                if !local_peer.0 {
+                    return error(
+                        Status::Forbidden,
+                        "Purging another client's history is only allowed from the server itself"
+                            .to_string(),
+                    );
+                }
+                state.anonymizer.anonymize(ip.trim())
+            }
+            None => client_user.0.clone(),
+        };
+        let Some(history) = &state.history else {
+            return error(Status::NotFound, "History is disabled".to_string());
+        };
+        let Some(user) = user else {
+            return error(
+                Status::BadRequest,
+                "History is not kept per client (ip_mode = \"none\")".to_string(),
+            );
+        };
+
+        let target = history_purge::PurgeTarget::User(user);
+        let removed = match history.purge(target.clone()) {
+            Ok(removed) => removed,
+            Err(e) => return error(Status::InternalServerError, e),
+        };
+        let requested_by = client_user
+            .0
+            .unwrap_or_else(|| history_privacy::ANONYMOUS_USER.to_string());
+        history_purge::record_purge(&target, &format!("api:{}", requested_by), removed);
+        Ok(rocket::serde::json::Json(PurgeResponse { removed }))
+    }
+
+    // http://localhost:8000/history?q=react (what `hist react` redirects to)
+    #[rocket::get("/history?<q>")]
+    pub(super) fn history_search(
+        q: Option<&str>,
+        state: &State<AppState>,
+        client_user: ClientUser,
+    ) -> rocket::response::content::RawHtml<String> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+        let query = q.unwrap_or("");
+        let now = history_stats::now_secs();
+
+        // Only the client's own commands, so nothing to show without a client id
+        let user = client_user.0;
+        let available = config.history.enabled && user.is_some();
+        let results = if available && let Some(history) = History::new(&config) {
+            match history.read_all() {
+                Ok(entries) => history_stats::search_commands(
+                    entries
+                        .iter()
+                        .filter(|entry| Some(&entry.user) == user.as_ref()),
+                    query,
+                    now,
+                ),
+                Err(e) => {
+                    eprintln!("Warning: Failed to read history: {}", e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        rocket::response::content::RawHtml(web::render_history_search_html(
+            query, &results, now, available,
+        ))
+    }
+
+    // http://localhost:8000/history/last?n=2 (what `last 2` redirects to):
+    // re-runs the client's nth most recent command, or shows /history when
+    // there is nothing to repeat
+    #[rocket::get("/history/last?<n>")]
+    pub(super) fn history_last(
+        n: Option<usize>,
+        state: &State<AppState>,
+        client_user: ClientUser,
+    ) -> Redirect {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+
+        let command = if config.history.enabled
+            && let Some(user) = client_user.0
+            && let Some(history) = History::new(&config)
+        {
+            match history.read_all() {
+                Ok(entries) => history_stats::nth_recent_command(
+                    entries.iter().filter(|entry| entry.user == user),
+                    n.unwrap_or(1),
+                ),
+                Err(e) => {
+                    eprintln!("Warning: Failed to read history: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        match command {
+            Some(command) => {
+                Redirect::to(utils::url_encoding::build_search_url("/", "cmd", &command))
+            }
+            None => Redirect::to("/history"),
+        }
+    }
+
+    // http://localhost:8000/stats
+    #[rocket::get("/stats")]
+    pub(super) fn stats(state: &State<AppState>) -> rocket::response::content::RawHtml<String> {
+        let config = state
+            .config
+            .read()
+            .expect("config state should not be poisoned")
+            .clone();
+
+        let top_commands = if config.history.enabled
+            && let Some(history) = History::new(&config)
+        {
+            match history.top_commands(None, web::STATS_TOP_LIMIT) {
+                Ok(top_commands) => top_commands,
+                Err(e) => {
+                    eprintln!("Warning: Failed to read history: {}", e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        rocket::response::content::RawHtml(web::render_stats_html(
+            &top_commands,
+            config.history.enabled,
+        ))
+    }
+
+    // Server-sent events stream of commands as they're run
+    #[rocket::get("/stats/events")]
+    pub(super) fn stats_events(
+        state: &State<AppState>,
+        mut shutdown: rocket::Shutdown,
+    ) -> EventStream![] {
+        let mut events = state.events.subscribe();
+        EventStream! {
+            loop {
+                let event = rocket::tokio::select! {
+                    received = events.recv() => match received {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    },
+                    _ = &mut shutdown => break,
+                };
+                yield Event::json(&event).event("command");
+            }
+        }
+    }
+
+    // Web app manifest so the landing page can be installed as "Bunnylol"
+    #[rocket::get("/manifest.webmanifest")]
+    pub(super) fn web_app_manifest() -> (ContentType, String) {
+        let manifest = rocket::serde::json::to_string(&web::WebAppManifest::default())
+            .expect("manifest should serialize");
+        (ContentType::new("application", "manifest+json"), manifest)
+    }
+
+    #[rocket::get("/sw.js")]
+    pub(super) fn service_worker() -> (ContentType, &'static str) {
+        (ContentType::JavaScript, web::SERVICE_WORKER_JS)
+    }
+
+    #[rocket::get("/icon.svg")]
+    pub(super) fn app_icon() -> (ContentType, &'static str) {
+        (ContentType::SVG, web::APP_ICON_SVG)
+    }
+
+    // Health check endpoint for Docker healthcheck (no verbose logging)
+    #[rocket::get("/health")]
+    pub(super) fn health() -> &'static str {
+        "ok"
+    }
+
+    // Catch 404 errors and show landing page
+    #[rocket::catch(404)]
+    pub(super) fn not_found(req: &rocket::Request) -> rocket::response::content::RawHtml<String> {
+        // Get config from request state
+        if let Some(state) = req.rocket().state::<AppState>() {
+            let config = state
+                .config
+                .read()
+                .expect("config state should not be poisoned")
+                .clone();
+            rocket::response::content::RawHtml(web::render_landing_page_html(
+                &config,
+                &web::LandingPageState::default(),
+            ))
+        } else {
+            // Fallback if config is not available (shouldn't happen)
+            rocket::response::content::RawHtml(
+                "<!DOCTYPE html><html lang=\"en\"><head><title>404 Not Found</title></head><body><main><h1>404 Not Found</h1></main></body></html>".to_string(),
+            )
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+use server_impl::*;
+
+/// How often `serve --watch` checks the config file for changes
+#[cfg(feature = "server")]
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Notices when a file's modification time changes
+#[cfg(feature = "server")]
+struct FileWatcher {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+}
+
+#[cfg(feature = "server")]
+impl FileWatcher {
+    fn new(path: std::path::PathBuf) -> Self {
+        let modified = Self::modified(&path);
+        FileWatcher { path, modified }
+    }
+
+    fn modified(path: &std::path::Path) -> Option<std::time::SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Whether the file changed since the last check
+    ///
+    /// A file that's briefly missing, as when an editor saves by renaming, is
+    /// not a change; it's picked up once it's back.
+    fn changed(&mut self) -> bool {
+        match Self::modified(&self.path) {
+            Some(modified) if Some(modified) != self.modified => {
+                self.modified = Some(modified);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Reload the config whenever its file changes, for `serve --watch`
+#[cfg(feature = "server")]
+fn watch_config(config: Arc<RwLock<BunnylolConfig>>, aliases: Arc<RwLock<FuzzyIndex>>) {
+    let Some(path) = BunnylolConfig::get_existing_config_path() else {
+        eprintln!("Warning: no config file to watch");
+        return;
+    };
+    println!("Watching {} for changes", path.display());
+    let mut watcher = FileWatcher::new(path);
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            if !watcher.changed() {
+                continue;
+            }
+            match BunnylolConfig::load() {
+                Ok(reloaded) => {
+                    for error in BunnylolCommandRegistry::prefix_errors(&reloaded) {
+                        eprintln!("Warning: ignoring [prefixes] entry: {}", error);
+                    }
+                    apply_reload(&config, &aliases, reloaded);
+                    println!("Config changed, reloaded {}", watcher.path.display());
+                }
+                Err(e) => eprintln!(
+                    "Config changed but didn't load, keeping the previous one: {}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+/// Extras for working on the server or a config, off by default
+#[cfg(feature = "server")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ServeOptions {
+    /// Reload the config whenever its file changes and print how each
+    /// command resolved (`serve --watch`)
+    pub watch: bool,
+    /// Everything `watch` does, plus Rocket's debug logging of each request's
+    /// route matching, and opening the landing page once the server is
+    /// listening (`serve --dev`)
+    pub dev: bool,
+}
+
+/// Launch the Bunnylol web server with the given configuration
+#[cfg(feature = "server")]
+pub async fn launch(
+    config: BunnylolConfig,
+    options: ServeOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    build(config, options)?.launch().await?;
+    Ok(())
+}
+
+/// Launch the server like [`launch`], shutting it down gracefully (flushing
+/// history) once `stop` completes, for service managers that stop it without
+/// a signal
+#[cfg(feature = "server")]
+pub async fn launch_until(
+    config: BunnylolConfig,
+    stop: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rocket = build(config, ServeOptions::default())?.ignite().await?;
+    let shutdown = rocket.shutdown();
+    rocket::tokio::spawn(async move {
+        stop.await;
+        shutdown.notify();
+    });
+    rocket.launch().await?;
+    Ok(())
+}
+
+/// The server `launch` runs, with its routes and state
+#[cfg(feature = "server")]
+fn build(
+    config: BunnylolConfig,
+    options: ServeOptions,
+) -> Result<rocket::Rocket<rocket::Build>, Box<dyn std::error::Error>> {
+    // Never write to history laid out by a newer bunnylol
+    crate::history::check_schema(&config)?;
+
+    println!(
+        "Bunnylol server starting with default search: {}",
+        config.default_search
+    );
+    println!(
+        "Server listening on {}:{}",
+        config.server.address, config.server.port
+    );
+
+    let figment = rocket::Config::figment()
+        .merge(("address", config.server.address.clone()))
+        .merge(("port", config.server.port))
+        .merge((
+            "log_level",
+            if options.dev {
+                "debug".to_string()
+            } else {
+                config.server.log_level.clone()
+            },
+        ))
+        .merge(("ident", format!("Bunnylol/{}", env!("CARGO_PKG_VERSION"))));
+    let mut state = AppState::new(config);
+    if options.watch || options.dev {
+        state.trace = true;
+        watch_config(state.config.clone(), state.alias_index.clone());
+    }
+
+    let rocket = rocket::custom(figment)
+        .manage(state)
+        .mount(
+            "/",
+            rocket::routes![
+                search,
+                help,
+                export_bindings,
+                resolve_api,
+                commands_api,
+                frecency_api,
+                history_export_api,
+                purge_history_api,
+                history_top_api,
+                history_unknown_api,
+                grafana_health,
+                grafana_search,
+                grafana_query,
+                history_latency_api,
+                history_search,
+                history_last,
+                stats,
+                stats_events,
+                add_alias,
+                delete_alias,
+                reload_config,
+                web_app_manifest,
+                service_worker,
+                app_icon,
+                health
+            ],
+        )
+        .register("/", rocket::catchers![not_found])
+        .attach(rocket::fairing::AdHoc::on_liftoff(
+            "Open landing page",
+            move |rocket| {
+                Box::pin(async move {
+                    if options.dev {
+                        open_landing_page(rocket);
+                    }
+                })
+            },
+        ))
+        .attach(rocket::fairing::AdHoc::on_shutdown(
+            "Flush history",
+            |rocket| {
+                Box::pin(async move {
+                    if let Some(history) = rocket
+                        .state::<AppState>()
+                        .and_then(|state| state.history.as_ref())
+                    {
+                        history.shutdown();
+                    }
+                })
+            },
+        ));
+    Ok(rocket)
+}
+
+/// The landing page of a server bound to `address`, reachable from this
+/// machine even when it listens on every interface
+#[cfg(feature = "server")]
+fn landing_url(address: std::net::IpAddr, port: u16) -> String {
+    match address {
+        address if address.is_unspecified() => format!("http://localhost:{}/", port),
+        std::net::IpAddr::V6(address) => format!("http://[{}]:{}/", address, port),
+        address => format!("http://{}:{}/", address, port),
+    }
+}
+
+/// Open the landing page of the server that just started, in the configured
+/// browser
+#[cfg(feature = "server")]
+fn open_landing_page(rocket: &rocket::Rocket<rocket::Orbit>) {
+    let url = landing_url(rocket.config().address, rocket.config().port);
+    #[cfg(feature = "cli")]
+    {
+        let browser = rocket.state::<AppState>().and_then(|state| {
+            let config = state
+                .config
+                .read()
+                .expect("config state should not be poisoned");
+            crate::browser::browser_for(&config, "", None)
+        });
+        match crate::browser::open(&url, browser.as_ref()) {
+            Ok(()) => println!("Opened {} in your browser", url),
+            Err(e) => eprintln!("Warning: {}; visit {} instead", e, url),
+        }
+    }
+    #[cfg(not(feature = "cli"))]
+    println!("Visit {} to get started", url);
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+
+    use super::*;
+
+    #[test]
+    fn test_file_watcher_notices_changes() {
+        let dir = std::env::temp_dir().join(format!("bunnylol-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "browser = \"firefox\"\n").unwrap();
+        let mut watcher = FileWatcher::new(path.clone());
+        assert!(!watcher.changed());
+
+        let touch = |secs| {
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+        touch(1_700_000_000);
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        // Missing while an editor swaps it in, then back
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed());
+        std::fs::write(&path, "browser = \"chrome\"\n").unwrap();
+        touch(1_700_000_100);
+        assert!(watcher.changed());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_resolves_aliases() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.aliases = HashMap::from([("work".to_string(), "gh @octocat".to_string())]);
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=work").dispatch();
+
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://github.com/octocat")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_runs_middleware() {
+        use crate::config::MiddlewareStep;
+
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.middleware = vec![
+            MiddlewareStep::Normalize,
+            MiddlewareStep::StripParams {
+                params: vec!["utm_*".to_string()],
+            },
+        ];
+        config.fallback = vec![crate::config::FallbackStep::Url {
+            url: "https://intranet.example.com/?q={}&utm_source=bunnylol".to_string(),
+            commands: Vec::new(),
+        }];
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=%20gh%20%20@jane").dispatch();
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://github.com/jane")
+        );
+        let response = client.get("/?cmd=zzqx%20rust").dispatch();
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://intranet.example.com/?q=zzqx%20rust")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_renders_did_you_mean_for_typos() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=gihub%20foo").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("interstitial body");
+        assert!(body.contains("Did you mean"));
+        assert!(body.contains("/?cmd=github%20foo"));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_autocorrects_when_configured() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.fuzzy.autocorrect_distance = 1;
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=gihub%20facebook/react").dispatch();
+
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://github.com/facebook/react")
+        );
+    }
+
+    #[test]
+    fn test_search_uses_fallback_chain() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.fallback = vec![crate::config::FallbackStep::Bang {
+            commands: vec!["imdb".to_string()],
+        }];
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=imdb%20dune").dispatch();
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://duckduckgo.com/?q=!imdb%20dune")
+        );
+        let response = client.get("/?cmd=weather").dispatch();
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://www.google.com/search?q=weather")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_renders_qr_code() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=gh%20facebook/react&qr=1").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("qr page body");
+        assert!(body.contains("<svg"));
+        assert!(body.contains("https://github.com/facebook/react"));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_help_page() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.aliases = HashMap::from([("work".to_string(), "gh @octocat".to_string())]);
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search, help]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/help/gh").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("help page body");
+        assert!(body.contains("gh &#x2F; github"));
+        assert!(body.contains("work"));
+
+        let response = client.get("/help/work").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/help/definitely-not-a-command").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.get("/?cmd=gh%20--help").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(response.headers().get_one("Location"), Some("/help/gh"));
+
+        let response = client.get("/?cmd=help%20gh").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(response.headers().get_one("Location"), Some("/help/gh"));
+    }
+
+    #[test]
+    fn test_export_bindings_route() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.aliases = HashMap::from([("work".to_string(), "gh @octocat".to_string())]);
+
+        let state = AppState::new(config);
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![export_bindings]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/bindings/export/csv").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::CSV));
+        assert_eq!(
+            response.headers().get_one("Content-Disposition"),
+            Some(r#"attachment; filename="bunnylol-bindings.csv""#)
+        );
+        let body = response.into_string().expect("csv body");
+        assert!(body.contains("alias,work,Aliases,Alias for gh @octocat,work"));
+
+        let response = client.get("/bindings/export/md").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/bindings/export/xml").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_search_applies_ip_mode_to_events() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        let mut state = AppState::new(config);
+        state.anonymizer = UserAnonymizer::omitted();
+
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let mut events = client
+            .rocket()
+            .state::<AppState>()
+            .expect("app state")
+            .events
+            .subscribe();
+
+        client
+            .get("/?cmd=gh")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .dispatch();
+
+        let event = events.try_recv().expect("command event");
+        assert_eq!(event.user, history_privacy::ANONYMOUS_USER);
+    }
+
+    #[test]
+    fn test_search_keys_events_by_logged_in_user() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.server.identity_header = Some("X-Forwarded-User".to_string());
+        let mut state = AppState::new(config);
+        state.anonymizer = UserAnonymizer::omitted();
+
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let mut events = client
+            .rocket()
+            .state::<AppState>()
+            .expect("app state")
+            .events
+            .subscribe();
+
+        client
+            .get("/?cmd=gh")
+            .header(rocket::http::Header::new("X-Forwarded-User", "alice"))
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .dispatch();
+        client
+            .get("/?cmd=gh")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .dispatch();
+
+        assert_eq!(events.try_recv().expect("command event").user, "alice");
+        assert_eq!(
+            events.try_recv().expect("command event").user,
+            history_privacy::ANONYMOUS_USER
+        );
+
+        // A name that would split or break a history line
+        for user in ["alice|1|gh", "alice\nbob"] {
+            let response = client
+                .get("/?cmd=gh")
+                .header(rocket::http::Header::new("X-Forwarded-User", user))
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+        }
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_history_export_api_rejects_bad_requests() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![history_export_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/v1/history/export?format=xml").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let response = client
+            .get("/api/v1/history/export?since=someday")
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let response = client.get("/api/v1/history/export?format=csv").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_history_export_api_needs_logged_in_user() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.server.identity_header = Some("X-Forwarded-User".to_string());
+        let mut state = AppState::new(config);
+        state.anonymizer = UserAnonymizer::omitted();
+
+        let rocket = rocket::build()
+            .manage(state)
+            .mount("/", rocket::routes![history_export_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/v1/history/export").dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        let response = client
+            .get("/api/v1/history/export")
+            .header(rocket::http::Header::new("X-Forwarded-User", "alice"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_hist_redirects_to_history_search() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search, history_search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=hist%20react").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("/history?q=react")
+        );
+
+        let response = client.get("/history?q=react").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("history body");
+        assert!(body.contains("History is disabled"));
+        assert!(body.contains(r#"value="react""#));
+    }
+
+    #[test]
+    fn test_last_without_history_falls_back_to_history_page() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search, history_last]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/?cmd=last%203").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("/history/last?n=3")
+        );
+
+        let response = client.get("/history/last?n=3").dispatch();
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(response.headers().get_one("Location"), Some("/history"));
+    }
+
+    #[test]
+    fn test_history_stats_apis_reject_bad_requests() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build().manage(AppState::new(config)).mount(
+            "/",
+            rocket::routes![
+                history_top_api,
+                history_unknown_api,
+                history_latency_api,
+                frecency_api
+            ],
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .get("/api/v1/history/top?window=fortnight")
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body = response.into_string().expect("error body");
+        assert!(body.contains("window must be"));
+
+        let response = client
+            .get("/api/v1/history/top?window=7d&limit=5")
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client.get("/api/v1/history/unknown").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client.get("/api/v1/history/latency?window=soon").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let response = client.get("/api/v1/history/latency?window=1d").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client.get("/api/v1/frecency?q=gh").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_grafana_endpoints_need_history() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build().manage(AppState::new(config)).mount(
+            "/",
+            rocket::routes![grafana_health, grafana_search, grafana_query],
+        );
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/v1/grafana").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .post("/api/v1/grafana/search")
+            .header(ContentType::JSON)
+            .body(r#"{"target":""}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client
+            .post("/api/v1/grafana/query")
+            .header(ContentType::JSON)
+            .body(r#"{"range":{"from":"2024-01-31T00:00:00Z","to":"2024-01-31T01:00:00Z"},"intervalMs":60000,"targets":[{"target":"requests","refId":"A"}]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_purge_history_api_guards_other_clients() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![purge_history_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .delete("/api/v1/history?ip=10.0.0.1")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        // A client can't pass as local by claiming a loopback address
+        let response = client
+            .delete("/api/v1/history?ip=10.0.0.1")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .header(rocket::http::Header::new("X-Real-IP", "127.0.0.1"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .delete("/api/v1/history?ip=10.0.0.1")
+            .remote("127.0.0.1:5000".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.delete("/api/v1/history").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_reload_config_only_from_server() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![reload_config]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .post("/api/v1/reload")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .post("/api/v1/reload")
+            .remote("203.0.113.42:5000".parse().unwrap())
+            .header(rocket::http::Header::new("X-Real-IP", "127.0.0.1"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_publishes_command_events() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search, stats]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let mut events = client
+            .rocket()
+            .state::<AppState>()
+            .expect("app state")
+            .events
+            .subscribe();
+
+        client.get("/?cmd=gh%20facebook/react").dispatch();
+
+        let event = events.try_recv().expect("command event");
+        assert_eq!(event.command, "gh facebook/react");
+        assert_eq!(event.url, "https://github.com/facebook/react");
+
+        let response = client.get("/stats").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("stats body");
+        assert!(body.contains("/stats/events"));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_api() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+        config.aliases = HashMap::from([("work".to_string(), "gh @octocat".to_string())]);
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![resolve_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/resolve?cmd=work").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let body = response.into_string().expect("resolution body");
+        assert!(body.contains(r#""layer":"alias""#));
+        assert!(body.contains(r#""alias_chain":["work","gh @octocat"]"#));
+        assert!(body.contains(r#""urls":["https://github.com/octocat"]"#));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_resolve_api_chain() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![resolve_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .get("/api/resolve?cmd=gh%20facebook/react%3B%20npm%20react")
+            .dispatch();
+        let body = response.into_string().expect("resolution body");
+        assert!(body.contains(r#""layer":"chain""#));
+        assert!(body.contains(
+            r#""urls":["https://github.com/facebook/react","https://www.npmjs.com/search?q=react"]"#
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "google")]
+    fn test_resolve_api_plus_is_space() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![resolve_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        // Browsers send a space as `+` and a literal plus as `%2B`
+        let body = client
+            .get("/api/resolve?cmd=g+c%2B%2B+100%25")
+            .dispatch()
+            .into_string()
+            .expect("resolution body");
+        assert!(
+            body.contains(r#""urls":["https://google.com/search?q=c%2B%2B%20100%25"]"#),
+            "{}",
+            body
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_search_chain_opens_every_target() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![search]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client
+            .get("/?cmd=gh%20facebook/react%20%26%26%20npm%20react")
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().expect("interstitial body");
+        assert!(body.contains("Open 2 tabs"));
+        assert!(body.contains(r#"href="https://www.npmjs.com/search?q=react""#));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn test_commands_api() {
+        let mut config = BunnylolConfig::default();
+        config.history.enabled = false;
+
+        let rocket = rocket::build()
+            .manage(AppState::new(config))
+            .mount("/", rocket::routes![commands_api]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/api/commands?q=gh&limit=5").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let body = response.into_string().expect("commands body");
+        assert!(body.contains(r#""total":1"#));
+        assert!(body.contains(r#""bindings":["gh","github"]"#));
+
+        let body = client
+            .get("/api/commands?offset=2&limit=3")
+            .dispatch()
+            .into_string()
+            .expect("commands body");
+        assert!(body.contains(r#""offset":2,"limit":3"#));
+    }
+
+    #[test]
+    fn test_web_app_manifest_route() {
+        let rocket = rocket::build().mount("/", rocket::routes![web_app_manifest, service_worker]);
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let response = client.get("/manifest.webmanifest").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.content_type(),
+            Some(ContentType::new("application", "manifest+json"))
+        );
+        let body = response.into_string().expect("manifest body");
+        assert!(body.contains(r#""name":"Bunnylol""#));
+
+        let response = client.get("/sw.js").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::JavaScript));
+    }
+
+    #[test]
+    fn test_landing_url() {
+        let url = |address: &str, port| landing_url(address.parse().unwrap(), port);
+        assert_eq!(url("127.0.0.1", 8000), "http://127.0.0.1:8000/");
+        assert_eq!(url("0.0.0.0", 8000), "http://localhost:8000/");
+        assert_eq!(url("::", 80), "http://localhost:80/");
+        assert_eq!(url("::1", 8000), "http://[::1]:8000/");
     }
 }
@@ -9,17 +9,25 @@ use std::fmt;
 
 #[cfg(target_os = "linux")]
 use service_manager::*;
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", windows))]
 use std::ffi::OsString;
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", windows))]
 use std::path::PathBuf;
 #[cfg(target_os = "linux")]
 use std::process::Command;
+#[cfg(windows)]
+use std::time::Duration;
+#[cfg(windows)]
+use windows_service::service::{Service, ServiceAccess};
+
+#[cfg(any(target_os = "linux", windows))]
+use crate::config::BunnylolConfig;
 
 /// Service label used for systemd
 pub const SERVICE_LABEL: &str = "bunnylol";
 
-/// Service name used in systemctl/journalctl commands
+/// Service name used in systemctl/journalctl commands, and registered with
+/// the Windows Service Control Manager
 pub const SERVICE_NAME: &str = "bunnylol";
 
 // ============================================================================
@@ -64,8 +72,8 @@ impl fmt::Display for ServiceError {
             ServiceError::UnsupportedPlatform => {
                 write!(
                     f,
-                    "Native service installation is only supported on Linux (systemd).\n\n\
-                    For macOS and Windows, please use Docker instead:\n  \
+                    "Native service installation is only supported on Linux (systemd) and Windows.\n\n\
+                    For macOS, please use Docker instead:\n  \
                     docker compose up -d\n\n\
                     Or run the server directly:\n  \
                     bunnylol serve"
@@ -92,6 +100,12 @@ pub struct ServiceConfig {
     pub port: u16,
     pub address: String,
     pub log_level: String,
+    /// Where the Windows service writes its output, saved to the config as
+    /// `[server] log_file`; `None` keeps the configured (or default) file
+    pub log_file: Option<String>,
+    /// Install a systemd user unit running as the current user instead of a
+    /// system-wide service (Linux only)
+    pub user: bool,
 }
 
 impl Default for ServiceConfig {
@@ -100,6 +114,8 @@ impl Default for ServiceConfig {
             port: 8000,
             address: "127.0.0.1".to_string(), // Localhost only by default (secure)
             log_level: "normal".to_string(),
+            log_file: None,
+            user: false,
         }
     }
 }
@@ -108,70 +124,92 @@ impl Default for ServiceConfig {
 // Helper Functions
 // ============================================================================
 
-/// Common helper to set up service manager with label (Linux systemd only)
+/// Where the systemd unit file is installed: /etc/systemd/system for the
+/// system service, $XDG_CONFIG_HOME/systemd/user for a user unit
 #[cfg(target_os = "linux")]
-fn setup_manager() -> Result<(Box<dyn ServiceManager>, ServiceLabel), ServiceError> {
-    let mut manager = <dyn ServiceManager>::native()
-        .map_err(|e| ServiceError::ServiceManagerError(e.to_string()))?;
-
-    manager.set_level(ServiceLevel::System).map_err(|e| {
-        ServiceError::ServiceManagerError(format!("Failed to set service level: {}", e))
-    })?;
-
-    let label: ServiceLabel = SERVICE_LABEL
-        .parse()
-        .map_err(|e| ServiceError::ServiceManagerError(format!("Invalid label: {}", e)))?;
-
-    Ok((manager, label))
+fn unit_path(level: ServiceLevel) -> Result<PathBuf, ServiceError> {
+    let dir = match level {
+        ServiceLevel::System => PathBuf::from("/etc/systemd/system"),
+        ServiceLevel::User => xdg::BaseDirectories::new()
+            .get_config_home()
+            .ok_or_else(|| {
+                ServiceError::ConfigError(
+                    "Could not determine the systemd user unit directory".to_string(),
+                )
+            })?
+            .join("systemd/user"),
+    };
+    Ok(dir.join(format!("{}.service", SERVICE_NAME)))
 }
 
-// ============================================================================
-// Service Lifecycle Functions
-// ============================================================================
-
-/// Install bunnylol service using systemd (Linux only)
+/// Which unit the other service commands manage: the system service when it's
+/// installed, otherwise the user unit if there is one
 #[cfg(target_os = "linux")]
-pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError> {
-    println!("Installing bunnylol system service...");
-    println!("Platform: Linux (systemd)");
-    println!();
-
-    let binary_path = which::which("bunnylol").map_err(|_| ServiceError::BinaryNotFound)?;
-    println!("✓ Found bunnylol binary: {}", binary_path.display());
-    println!(
-        "✓ Service file will be created at: /etc/systemd/system/{}.service",
-        SERVICE_NAME
-    );
+fn installed_level() -> ServiceLevel {
+    let installed = |level| unit_path(level).is_ok_and(|path| path.exists());
+    if !installed(ServiceLevel::System) && installed(ServiceLevel::User) {
+        ServiceLevel::User
+    } else {
+        ServiceLevel::System
+    }
+}
 
-    // Create or update config file at /etc/bunnylol/config.toml
-    let system_config_path = PathBuf::from("/etc/bunnylol/config.toml");
+/// `program` (systemctl or journalctl), pointed at the user's units for `level`
+#[cfg(target_os = "linux")]
+fn systemd_command(program: &str, level: ServiceLevel) -> Command {
+    let mut command = Command::new(program);
+    if level == ServiceLevel::User {
+        command.arg("--user");
+    }
+    command
+}
 
+/// Create the config file at `path` with `config`'s settings, or update an
+/// existing one's address (and log file, when given)
+#[cfg(any(target_os = "linux", windows))]
+fn write_service_config(
+    config: &ServiceConfig,
+    path: &std::path::Path,
+) -> Result<(), ServiceError> {
     // Create directory if needed
-    std::fs::create_dir_all("/etc/bunnylol")
-        .map_err(|e| ServiceError::ConfigError(format!("Failed to create /etc/bunnylol: {}", e)))?;
-
-    use crate::config::BunnylolConfig;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            ServiceError::ConfigError(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+    }
 
-    if system_config_path.exists() {
-        println!("✓ Found existing config file: /etc/bunnylol/config.toml");
+    if path.exists() {
+        println!("✓ Found existing config file: {}", path.display());
 
         // Load existing config
-        let mut existing_config = BunnylolConfig::load().map_err(|e| {
-            ServiceError::ConfigError(format!("Failed to load existing config: {}", e))
-        })?;
+        let mut existing_config = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                toml::from_str::<BunnylolConfig>(&contents).map_err(|e| e.to_string())
+            })
+            .map_err(|e| {
+                ServiceError::ConfigError(format!("Failed to load existing config: {}", e))
+            })?;
 
         let current_address = existing_config.server.address.clone();
         println!("  Current address: {}", current_address);
         println!("  New address:     {}", config.address);
 
-        if current_address == config.address {
+        let log_file_changed = config
+            .log_file
+            .as_ref()
+            .is_some_and(|log_file| existing_config.server.log_file.as_ref() != Some(log_file));
+        if current_address == config.address && !log_file_changed {
             println!("✓ Config already has correct address, no changes needed");
         } else {
-            // Update only the address field, preserve everything else
+            // Update only the address and log file, preserve everything else
             existing_config.server.address = config.address.clone();
+            if log_file_changed {
+                existing_config.server.log_file = config.log_file.clone();
+            }
 
             println!("✓ Updating address in config file (preserving other settings)...");
-            if let Err(e) = existing_config.write_to_file(&system_config_path) {
+            if let Err(e) = existing_config.write_to_file(&path.to_path_buf()) {
                 return Err(ServiceError::ConfigError(format!(
                     "Failed to write config: {}",
                     e
@@ -179,16 +217,17 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
             }
         }
     } else {
-        println!("✓ Creating system config file: /etc/bunnylol/config.toml");
+        println!("✓ Creating config file: {}", path.display());
 
         // Create new config with provided ServiceConfig settings
         let mut default_config = BunnylolConfig::default();
         default_config.server.port = config.port;
         default_config.server.address = config.address.clone();
         default_config.server.log_level = config.log_level.clone();
+        default_config.server.log_file = config.log_file.clone();
 
         // Write config file
-        if let Err(e) = default_config.write_to_file(&system_config_path) {
+        if let Err(e) = default_config.write_to_file(&path.to_path_buf()) {
             return Err(ServiceError::ConfigError(format!(
                 "Failed to write config: {}",
                 e
@@ -196,13 +235,83 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         }
     }
 
+    Ok(())
+}
+
+/// Common helper to set up service manager with label (Linux systemd only)
+#[cfg(target_os = "linux")]
+fn setup_manager(
+    level: ServiceLevel,
+) -> Result<(Box<dyn ServiceManager>, ServiceLabel), ServiceError> {
+    let mut manager = <dyn ServiceManager>::native()
+        .map_err(|e| ServiceError::ServiceManagerError(e.to_string()))?;
+
+    manager.set_level(level).map_err(|e| {
+        ServiceError::ServiceManagerError(format!("Failed to set service level: {}", e))
+    })?;
+
+    let label: ServiceLabel = SERVICE_LABEL
+        .parse()
+        .map_err(|e| ServiceError::ServiceManagerError(format!("Invalid label: {}", e)))?;
+
+    Ok((manager, label))
+}
+
+// ============================================================================
+// Service Lifecycle Functions
+// ============================================================================
+
+/// Install bunnylol service using systemd, system-wide or as a user unit
+/// (Linux only)
+#[cfg(target_os = "linux")]
+pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError> {
+    let level = if config.user {
+        ServiceLevel::User
+    } else {
+        ServiceLevel::System
+    };
+    if config.user {
+        println!("Installing bunnylol user service...");
+        println!("Platform: Linux (systemd, user unit)");
+    } else {
+        println!("Installing bunnylol system service...");
+        println!("Platform: Linux (systemd)");
+    }
+    println!();
+
+    let binary_path = which::which("bunnylol").map_err(|_| ServiceError::BinaryNotFound)?;
+    println!("✓ Found bunnylol binary: {}", binary_path.display());
+    println!(
+        "✓ Service file will be created at: {}",
+        unit_path(level)?.display()
+    );
+
+    // A user unit reads the user's config, unless a system config exists,
+    // which bunnylol always prefers
+    let system_config_path = BunnylolConfig::get_system_config_dir().join("config.toml");
+    let config_path = if config.user && !system_config_path.exists() {
+        BunnylolConfig::get_config_dir()
+            .ok_or_else(|| {
+                ServiceError::ConfigError("Could not determine the config directory".to_string())
+            })?
+            .join("config.toml")
+    } else {
+        if config.user {
+            println!(
+                "⚠ {} exists and takes precedence over your own config",
+                system_config_path.display()
+            );
+        }
+        system_config_path
+    };
+    write_service_config(&config, &config_path)?;
     println!();
 
     println!("Service configuration:");
     println!("  Label:       {}", SERVICE_LABEL);
     println!("  Binary:      {}", binary_path.display());
     println!("  Command:     bunnylol serve");
-    println!("  Config:      /etc/bunnylol/config.toml");
+    println!("  Config:      {}", config_path.display());
     println!(
         "    Port:      {} (can be changed in config file)",
         config.port
@@ -215,15 +324,33 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         "    Log level: {} (can be changed in config file)",
         config.log_level
     );
-    println!("  Run as:      root");
-    println!("  Autostart:   enabled");
+    if config.user {
+        println!("  Run as:      {}", whoami::username());
+        println!("  Autostart:   enabled (when you log in)");
+    } else {
+        println!("  Run as:      root");
+        println!("  Autostart:   enabled");
+    }
     println!();
 
-    let (manager, label) = setup_manager()?;
+    let (manager, label) = setup_manager(level)?;
 
     let args = vec![OsString::from("serve")];
 
-    let environment = vec![];
+    // The user manager doesn't see the login shell's environment, so pin the
+    // directories the config and history were found in
+    let environment = if config.user {
+        let xdg = xdg::BaseDirectories::new();
+        [
+            ("XDG_CONFIG_HOME", xdg.get_config_home()),
+            ("XDG_DATA_HOME", xdg.get_data_home()),
+        ]
+        .into_iter()
+        .filter_map(|(var, dir)| Some((var.to_string(), dir?.to_string_lossy().into_owned())))
+        .collect()
+    } else {
+        vec![]
+    };
 
     println!("Creating service file...");
     let install_ctx = ServiceInstallCtx {
@@ -267,8 +394,15 @@ pub fn install_systemd_service(config: ServiceConfig) -> Result<(), ServiceError
         config.address, config.port
     );
     println!();
-    println!("To change port/address, edit: /etc/bunnylol/config.toml");
-    println!("Then restart the service: sudo bunnylol service restart");
+    println!("To change port/address, edit: {}", config_path.display());
+    if config.user {
+        println!("Then restart the service: bunnylol service restart");
+        println!();
+        println!("User services stop when you log out; to keep it running:");
+        println!("  loginctl enable-linger {}", whoami::username());
+    } else {
+        println!("Then restart the service: sudo bunnylol service restart");
+    }
 
     println!();
     println!("Manage service:");
@@ -285,14 +419,24 @@ pub fn install_systemd_service(_config: ServiceConfig) -> Result<(), ServiceErro
     Err(ServiceError::UnsupportedPlatform)
 }
 
+/// Install bunnylol as this platform's system service: a systemd unit on
+/// Linux, a Windows service on Windows
+pub fn install_service(config: ServiceConfig) -> Result<(), ServiceError> {
+    #[cfg(windows)]
+    return install_windows_service(config);
+    #[cfg(not(windows))]
+    install_systemd_service(config)
+}
+
 /// Uninstall bunnylol service (Linux only)
 #[cfg(target_os = "linux")]
 pub fn uninstall_service() -> Result<(), ServiceError> {
     println!("Uninstalling bunnylol system service...");
-    println!("Service file: /etc/systemd/system/{}.service", SERVICE_NAME);
+    let level = installed_level();
+    println!("Service file: {}", unit_path(level)?.display());
     println!();
 
-    let (manager, label) = setup_manager()?;
+    let (manager, label) = setup_manager(level)?;
 
     println!("Stopping service...");
     let stop_ctx = ServiceStopCtx {
@@ -327,7 +471,7 @@ pub fn uninstall_service() -> Result<(), ServiceError> {
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn uninstall_service() -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
@@ -335,7 +479,7 @@ pub fn uninstall_service() -> Result<(), ServiceError> {
 /// Start the bunnylol service (Linux only)
 #[cfg(target_os = "linux")]
 pub fn start_service() -> Result<(), ServiceError> {
-    let (manager, label) = setup_manager()?;
+    let (manager, label) = setup_manager(installed_level())?;
 
     manager
         .start(ServiceStartCtx { label })
@@ -345,7 +489,7 @@ pub fn start_service() -> Result<(), ServiceError> {
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn start_service() -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
@@ -353,7 +497,7 @@ pub fn start_service() -> Result<(), ServiceError> {
 /// Stop the bunnylol service (Linux only)
 #[cfg(target_os = "linux")]
 pub fn stop_service() -> Result<(), ServiceError> {
-    let (manager, label) = setup_manager()?;
+    let (manager, label) = setup_manager(installed_level())?;
 
     manager
         .stop(ServiceStopCtx { label })
@@ -363,7 +507,7 @@ pub fn stop_service() -> Result<(), ServiceError> {
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn stop_service() -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
@@ -373,7 +517,7 @@ pub fn stop_service() -> Result<(), ServiceError> {
 pub fn restart_service() -> Result<(), ServiceError> {
     println!("Restarting bunnylol service...");
 
-    let (manager, label) = setup_manager()?;
+    let (manager, label) = setup_manager(installed_level())?;
 
     manager
         .stop(ServiceStopCtx {
@@ -390,38 +534,159 @@ pub fn restart_service() -> Result<(), ServiceError> {
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn restart_service() -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
 
-/// Get the status of the bunnylol service (Linux systemd only)
-#[cfg(target_os = "linux")]
-pub fn service_status() -> Result<(), ServiceError> {
-    let cmd = Command::new("systemctl")
-        .args(["status", SERVICE_NAME])
-        .status()
-        .map_err(|e| ServiceError::ServiceManagerError(e.to_string()))?;
+/// Whether the service is installed, and if so whether it's running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceState {
+    NotInstalled,
+    Stopped,
+    Running,
+}
 
-    if !cmd.success() {
-        eprintln!(
-            "\nNote: Service may not be running (exit code: {})",
-            cmd.code().unwrap_or(-1)
-        );
+/// What `bunnylol service status` shows, laid out the same on every platform
+#[derive(Debug)]
+pub struct StatusReport {
+    pub state: ServiceState,
+    /// The unit file or service it's installed as, and for whom
+    pub installed_as: Option<String>,
+    /// The config file the service reads
+    pub config: Option<std::path::PathBuf>,
+    /// The configured URL, and whether a bunnylol server answers there
+    pub serving: Option<(String, bool)>,
+    /// Where `bunnylol service logs` reads from
+    pub logs: String,
+}
+
+impl fmt::Display for StatusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            ServiceState::NotInstalled => "not installed",
+            ServiceState::Stopped => "stopped",
+            ServiceState::Running => "running",
+        };
+        writeln!(f, "bunnylol service: {}", state)?;
+        if let Some(installed_as) = &self.installed_as {
+            writeln!(f, "  Installed: {}", installed_as)?;
+        }
+        if let Some(config) = &self.config {
+            writeln!(f, "  Config:    {}", config.display())?;
+        }
+        if let Some((url, answering)) = &self.serving {
+            let health = if *answering {
+                "answering health checks"
+            } else {
+                "not answering"
+            };
+            writeln!(f, "  Serving:   {} ({})", url, health)?;
+        }
+        write!(f, "  Logs:      {}", self.logs)
+    }
+}
+
+/// The configured URL of the server the service runs, and whether it's up
+#[cfg(any(target_os = "linux", windows))]
+fn serving(config: &BunnylolConfig) -> (String, bool) {
+    let server = &config.server;
+    (
+        format!("http://{}:{}", server.address, server.port),
+        crate::doctor::is_bunnylol(&server.address, server.port),
+    )
+}
+
+/// Show whether the service is installed and running, where it's serving,
+/// and its most recent log lines
+#[cfg(any(target_os = "linux", windows))]
+pub fn service_status() -> Result<(), ServiceError> {
+    let report = status_report()?;
+    println!("{}", report);
+    if report.state == ServiceState::NotInstalled {
+        println!();
+        println!("Install it with: bunnylol service install");
+        return Ok(());
     }
 
+    println!();
+    println!("Recent logs:");
+    if let Err(e) = service_logs(false, 10) {
+        println!("  (can't read them: {})", e);
+    }
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn service_status() -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
 
+/// The systemd unit's state, file, config, and journal (Linux only)
+#[cfg(target_os = "linux")]
+fn status_report() -> Result<StatusReport, ServiceError> {
+    let level = installed_level();
+    let state = service_state()?;
+    let user = if level == ServiceLevel::User {
+        " --user"
+    } else {
+        ""
+    };
+    let scope = if level == ServiceLevel::User {
+        "user unit"
+    } else {
+        "system unit"
+    };
+    Ok(StatusReport {
+        state,
+        installed_as: (state != ServiceState::NotInstalled)
+            .then(|| unit_path(level))
+            .transpose()?
+            .map(|unit| format!("{} ({})", unit.display(), scope)),
+        config: BunnylolConfig::get_existing_config_path(),
+        serving: BunnylolConfig::load().ok().map(|config| serving(&config)),
+        logs: format!("journalctl{} -u {}", user, SERVICE_NAME),
+    })
+}
+
+/// Check the bunnylol service's state without printing anything (Linux systemd only)
+#[cfg(target_os = "linux")]
+pub fn service_state() -> Result<ServiceState, ServiceError> {
+    let level = installed_level();
+    if !unit_path(level)?.exists() {
+        return Ok(ServiceState::NotInstalled);
+    }
+    let active = systemd_command("systemctl", level)
+        .args(["is-active", "--quiet", SERVICE_NAME])
+        .status()
+        .map_err(|e| ServiceError::ServiceManagerError(e.to_string()))?;
+    Ok(if active.success() {
+        ServiceState::Running
+    } else {
+        ServiceState::Stopped
+    })
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn service_state() -> Result<ServiceState, ServiceError> {
+    Err(ServiceError::UnsupportedPlatform)
+}
+
+/// The error for managing a service that isn't there
+#[cfg(any(target_os = "linux", windows))]
+fn not_installed() -> ServiceError {
+    ServiceError::ServiceManagerError(
+        "bunnylol service is not installed (bunnylol service install sets it up)".to_string(),
+    )
+}
+
 /// View logs for the bunnylol service (Linux systemd only)
 #[cfg(target_os = "linux")]
 pub fn service_logs(follow: bool, lines: u32) -> Result<(), ServiceError> {
-    let mut cmd = Command::new("journalctl");
+    if service_state()? == ServiceState::NotInstalled {
+        return Err(not_installed());
+    }
+    let mut cmd = systemd_command("journalctl", installed_level());
     cmd.args(["-u", SERVICE_NAME, "-n", &lines.to_string()]);
     if follow {
         cmd.arg("-f");
@@ -441,10 +706,577 @@ pub fn service_logs(follow: bool, lines: u32) -> Result<(), ServiceError> {
     Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", windows)))]
 pub fn service_logs(
     #[allow(unused_variables)] follow: bool,
     #[allow(unused_variables)] lines: u32,
 ) -> Result<(), ServiceError> {
     Err(ServiceError::UnsupportedPlatform)
 }
+
+// ============================================================================
+// Windows Service
+// ============================================================================
+
+/// Where the Windows service writes its output: `[server] log_file`, or
+/// bunnylol.log beside the system config
+#[cfg(windows)]
+fn service_log_path(config: &BunnylolConfig) -> PathBuf {
+    config
+        .server
+        .log_file
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| BunnylolConfig::get_system_config_dir().join("bunnylol.log"))
+}
+
+#[cfg(windows)]
+fn windows_error(e: windows_service::Error) -> ServiceError {
+    match e {
+        // The OS error says what went wrong; the wrapper only says it failed
+        windows_service::Error::Winapi(e) => ServiceError::ServiceManagerError(e.to_string()),
+        e => ServiceError::ServiceManagerError(e.to_string()),
+    }
+}
+
+/// Whether the Service Control Manager failed because bunnylol isn't installed
+#[cfg(windows)]
+fn is_not_installed(e: &windows_service::Error) -> bool {
+    use windows_sys::Win32::Foundation::ERROR_SERVICE_DOES_NOT_EXIST;
+
+    matches!(e, windows_service::Error::Winapi(e)
+        if e.raw_os_error() == Some(ERROR_SERVICE_DOES_NOT_EXIST as i32))
+}
+
+/// Open the installed bunnylol service with `access` (Windows only)
+#[cfg(windows)]
+fn open_windows_service(access: ServiceAccess) -> Result<Service, windows_service::Error> {
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+
+    ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?
+        .open_service(SERVICE_NAME, access)
+}
+
+/// Wait for a stop to finish, since the Service Control Manager only waits
+/// for the stop to be requested
+#[cfg(windows)]
+fn wait_until_stopped(service: &Service) -> Result<(), ServiceError> {
+    use windows_service::service::ServiceState as WindowsState;
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(30);
+    while service.query_status().map_err(windows_error)?.current_state != WindowsState::Stopped {
+        if std::time::Instant::now() >= deadline {
+            return Err(ServiceError::ServiceManagerError(
+                "timed out waiting for the service to stop".to_string(),
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    Ok(())
+}
+
+/// Install bunnylol as a Windows service (Windows only)
+#[cfg(windows)]
+pub fn install_windows_service(config: ServiceConfig) -> Result<(), ServiceError> {
+    use windows_service::service::{
+        ServiceAction, ServiceActionType, ServiceErrorControl, ServiceFailureActions,
+        ServiceFailureResetPeriod, ServiceInfo, ServiceStartType, ServiceType,
+    };
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+
+    println!("Installing bunnylol system service...");
+    println!("Platform: Windows (Service Control Manager)");
+    println!();
+
+    let binary_path = which::which("bunnylol").map_err(|_| ServiceError::BinaryNotFound)?;
+    println!("✓ Found bunnylol binary: {}", binary_path.display());
+
+    if config.user {
+        return Err(ServiceError::ConfigError(
+            "--user installs a systemd user unit; Windows services are always system-wide"
+                .to_string(),
+        ));
+    }
+    let system_config_path = BunnylolConfig::get_system_config_dir().join("config.toml");
+    write_service_config(&config, &system_config_path)?;
+    let log_path = BunnylolConfig::load()
+        .map(|config| service_log_path(&config))
+        .map_err(ServiceError::ConfigError)?;
+
+    println!();
+
+    println!("Service configuration:");
+    println!("  Name:        {}", SERVICE_NAME);
+    println!("  Binary:      {}", binary_path.display());
+    println!("  Command:     bunnylol service run");
+    println!("  Config:      {}", system_config_path.display());
+    println!(
+        "    Port:      {} (can be changed in config file)",
+        config.port
+    );
+    println!(
+        "    Address:   {} (can be changed in config file)",
+        config.address
+    );
+    println!(
+        "    Log level: {} (can be changed in config file)",
+        config.log_level
+    );
+    println!(
+        "    Log file:  {} (can be changed in config file)",
+        log_path.display()
+    );
+    println!("  Run as:      LocalSystem");
+    println!("  Autostart:   enabled");
+    println!();
+
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .map_err(windows_error)?;
+
+    println!("Registering service...");
+    let service_info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from("Bunnylol"),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: binary_path,
+        launch_arguments: vec![OsString::from("service"), OsString::from("run")],
+        dependencies: vec![],
+        account_name: None, // LocalSystem
+        account_password: None,
+    };
+    let service = manager
+        .create_service(
+            &service_info,
+            ServiceAccess::CHANGE_CONFIG | ServiceAccess::START,
+        )
+        .map_err(windows_error)?;
+    service
+        .set_description("Smart bookmark server: URL shortcuts for your browser's search bar")
+        .map_err(windows_error)?;
+
+    // Restart after 5 seconds when it fails, like the systemd unit
+    let restart = ServiceAction {
+        action_type: ServiceActionType::Restart,
+        delay: Duration::from_secs(5),
+    };
+    service
+        .update_failure_actions(ServiceFailureActions {
+            reset_period: ServiceFailureResetPeriod::After(Duration::from_secs(24 * 60 * 60)),
+            reboot_msg: None,
+            command: None,
+            actions: Some(vec![restart; 3]),
+        })
+        .map_err(windows_error)?;
+    // Exiting with an error counts as a failure, not just crashing
+    service
+        .set_failure_actions_on_non_crash_failures(true)
+        .map_err(windows_error)?;
+
+    println!("✓ Service registered");
+
+    println!();
+    println!("Starting service...");
+
+    service
+        .start(&[] as &[&str])
+        .map_err(|e| ServiceError::ServiceStartFailed(windows_error(e).to_string()))?;
+
+    println!("✓ Service started");
+
+    println!();
+    println!("🎉 Bunnylol server installed successfully!");
+    println!();
+    println!(
+        "Server URL (from config): http://{}:{}",
+        config.address, config.port
+    );
+    println!(
+        "Add to browser search: http://{}:{}/?cmd=%s",
+        config.address, config.port
+    );
+    println!();
+    println!(
+        "To change port/address, edit: {}",
+        system_config_path.display()
+    );
+    println!("Then restart the service: bunnylol service restart (as Administrator)");
+
+    println!();
+    println!("Manage service:");
+    println!("  bunnylol service status");
+    println!("  bunnylol service logs");
+    println!("  bunnylol service restart");
+    println!("  bunnylol service uninstall");
+
+    Ok(())
+}
+
+/// Uninstall the bunnylol Windows service (Windows only)
+#[cfg(windows)]
+pub fn uninstall_service() -> Result<(), ServiceError> {
+    use windows_service::service::ServiceState as WindowsState;
+
+    println!("Uninstalling bunnylol system service...");
+    println!();
+
+    let service = open_windows_service(
+        ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+    )
+    .map_err(windows_error)?;
+
+    println!("Stopping service...");
+    if service.query_status().map_err(windows_error)?.current_state == WindowsState::Stopped {
+        println!("ℹ Service was not running");
+    } else {
+        match service.stop() {
+            Ok(_) => {
+                wait_until_stopped(&service)?;
+                println!("✓ Service stopped");
+            }
+            Err(e) => println!("⚠ Warning: Could not stop service: {}", windows_error(e)),
+        }
+    }
+
+    println!("Removing service...");
+
+    service.delete().map_err(windows_error)?;
+
+    println!("✓ Service removed");
+    println!();
+    println!("✓ Bunnylol service uninstalled successfully");
+
+    Ok(())
+}
+
+/// Start the bunnylol Windows service (Windows only)
+#[cfg(windows)]
+pub fn start_service() -> Result<(), ServiceError> {
+    open_windows_service(ServiceAccess::START)
+        .and_then(|service| service.start(&[] as &[&str]))
+        .map_err(|e| ServiceError::ServiceStartFailed(windows_error(e).to_string()))?;
+
+    println!("✓ Service started");
+    Ok(())
+}
+
+/// Stop the bunnylol Windows service (Windows only)
+#[cfg(windows)]
+pub fn stop_service() -> Result<(), ServiceError> {
+    let service = open_windows_service(ServiceAccess::QUERY_STATUS | ServiceAccess::STOP)
+        .map_err(windows_error)?;
+    service.stop().map_err(windows_error)?;
+    wait_until_stopped(&service)?;
+
+    println!("✓ Service stopped");
+    Ok(())
+}
+
+/// Restart the bunnylol Windows service (Windows only)
+#[cfg(windows)]
+pub fn restart_service() -> Result<(), ServiceError> {
+    println!("Restarting bunnylol service...");
+
+    let service = open_windows_service(
+        ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::START,
+    )
+    .map_err(windows_error)?;
+
+    service.stop().map_err(windows_error)?;
+    wait_until_stopped(&service)?;
+    println!("✓ Service stopped");
+
+    service
+        .start(&[] as &[&str])
+        .map_err(|e| ServiceError::ServiceStartFailed(windows_error(e).to_string()))?;
+    println!("✓ Service started");
+
+    Ok(())
+}
+
+/// The Windows service's state, config, and log file (Windows only)
+#[cfg(windows)]
+fn status_report() -> Result<StatusReport, ServiceError> {
+    let state = service_state()?;
+    let config = BunnylolConfig::load().ok();
+    Ok(StatusReport {
+        state,
+        installed_as: (state != ServiceState::NotInstalled)
+            .then(|| format!("Windows service '{}' (LocalSystem)", SERVICE_NAME)),
+        config: Some(BunnylolConfig::get_system_config_dir().join("config.toml")),
+        serving: config.as_ref().map(serving),
+        logs: config
+            .as_ref()
+            .map(|config| service_log_path(config).display().to_string())
+            .unwrap_or_else(|| "the config can't be read to find the log file".to_string()),
+    })
+}
+
+/// Check the bunnylol Windows service's state without printing anything (Windows only)
+#[cfg(windows)]
+pub fn service_state() -> Result<ServiceState, ServiceError> {
+    use windows_service::service::ServiceState as WindowsState;
+
+    let service = match open_windows_service(ServiceAccess::QUERY_STATUS) {
+        Ok(service) => service,
+        Err(e) if is_not_installed(&e) => return Ok(ServiceState::NotInstalled),
+        Err(e) => return Err(windows_error(e)),
+    };
+    Ok(
+        match service.query_status().map_err(windows_error)?.current_state {
+            WindowsState::Running | WindowsState::StartPending | WindowsState::ContinuePending => {
+                ServiceState::Running
+            }
+            _ => ServiceState::Stopped,
+        },
+    )
+}
+
+/// Show the Windows service's log file, optionally following it as it grows
+/// (Windows only)
+#[cfg(windows)]
+pub fn service_logs(follow: bool, lines: u32) -> Result<(), ServiceError> {
+    use std::io::{Read, Write};
+
+    if service_state()? == ServiceState::NotInstalled {
+        return Err(not_installed());
+    }
+    let config = BunnylolConfig::load().map_err(ServiceError::ConfigError)?;
+    let path = service_log_path(&config);
+    let mut file = std::fs::File::open(&path).map_err(|e| {
+        ServiceError::ServiceManagerError(format!("Failed to open {}: {}", path.display(), e))
+    })?;
+
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    print!(
+        "{}",
+        last_lines(&String::from_utf8_lossy(&contents), lines as usize)
+    );
+
+    while follow {
+        std::thread::sleep(Duration::from_millis(500));
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        let mut stdout = std::io::stdout();
+        stdout.write_all(&appended)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+/// The last `count` lines of `text`
+#[cfg(any(windows, test))]
+fn last_lines(text: &str, count: usize) -> &str {
+    if count == 0 {
+        return "";
+    }
+    let start = text
+        .trim_end_matches('\n')
+        .rmatch_indices('\n')
+        .nth(count - 1)
+        .map_or(0, |(i, _)| i + 1);
+    &text[start..]
+}
+
+/// The config `service run` serves, handed from `run_service` to the
+/// service's main function
+#[cfg(all(windows, feature = "server"))]
+static SERVICE_CONFIG: std::sync::OnceLock<BunnylolConfig> = std::sync::OnceLock::new();
+
+/// Run the server under the Service Control Manager; what the installed
+/// service launches, as `bunnylol service run` (Windows only)
+#[cfg(all(windows, feature = "server"))]
+pub fn run_service(config: BunnylolConfig) -> Result<(), ServiceError> {
+    let _ = SERVICE_CONFIG.set(config);
+    // Blocks until the service stops
+    windows_service::service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        .map_err(windows_error)
+}
+
+#[cfg(not(all(windows, feature = "server")))]
+pub fn run_service(_config: crate::config::BunnylolConfig) -> Result<(), ServiceError> {
+    Err(ServiceError::UnsupportedPlatform)
+}
+
+#[cfg(all(windows, feature = "server"))]
+windows_service::define_windows_service!(ffi_service_main, service_main);
+
+#[cfg(all(windows, feature = "server"))]
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(e) = serve_as_service() {
+        eprintln!("Error: {}", e);
+    }
+}
+
+/// Serve until the Service Control Manager asks the service to stop
+#[cfg(all(windows, feature = "server"))]
+fn serve_as_service() -> Result<(), ServiceError> {
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState as WindowsState,
+        ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+
+    let config = SERVICE_CONFIG
+        .get()
+        .cloned()
+        .ok_or_else(|| ServiceError::ConfigError("no config to serve".to_string()))?;
+    redirect_output(&service_log_path(&config))?;
+
+    let (stop_tx, stop_rx) = rocket::tokio::sync::oneshot::channel();
+    let mut stop_tx = Some(stop_tx);
+    let status_handle =
+        service_control_handler::register(SERVICE_NAME, move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                if let Some(stop) = stop_tx.take() {
+                    let _ = stop.send(());
+                }
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })
+        .map_err(windows_error)?;
+    let set_state = |current_state, controls_accepted, exit_code| {
+        status_handle
+            .set_service_status(ServiceStatus {
+                service_type: ServiceType::OWN_PROCESS,
+                current_state,
+                controls_accepted,
+                exit_code: ServiceExitCode::Win32(exit_code),
+                checkpoint: 0,
+                wait_hint: Duration::default(),
+                process_id: None,
+            })
+            .map_err(windows_error)
+    };
+
+    set_state(
+        WindowsState::Running,
+        ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+        0,
+    )?;
+    let runtime = rocket::tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    let result = runtime.block_on(crate::server::launch_until(config, async {
+        let _ = stop_rx.await;
+    }));
+    // A non-zero exit code has the failure actions restart the service
+    set_state(
+        WindowsState::Stopped,
+        ServiceControlAccept::empty(),
+        if result.is_ok() { 0 } else { 1 },
+    )?;
+    result.map_err(|e| ServiceError::ServiceStartFailed(e.to_string()))
+}
+
+/// Send stdout and stderr to `path`, since a service has no console
+#[cfg(all(windows, feature = "server"))]
+fn redirect_output(path: &std::path::Path) -> Result<(), ServiceError> {
+    use std::os::windows::io::IntoRawHandle;
+    use windows_sys::Win32::System::Console::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE, SetStdHandle};
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    // The handle is never closed, so it stays valid for every later write
+    let handle = file.into_raw_handle();
+    let redirected = unsafe {
+        SetStdHandle(STD_OUTPUT_HANDLE, handle) != 0 && SetStdHandle(STD_ERROR_HANDLE, handle) != 0
+    };
+    if !redirected {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_write_service_config_keeps_other_settings() {
+        let dir = std::env::temp_dir().join(format!("bunnylol-service-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        let config = ServiceConfig {
+            port: 8123,
+            ..Default::default()
+        };
+        write_service_config(&config, &path).unwrap();
+        let written: BunnylolConfig =
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written.server.port, 8123);
+        assert_eq!(written.server.address, "127.0.0.1");
+
+        // Reinstalling with --network only changes the address
+        let mut edited = written;
+        edited.default_search = "ddg".to_string();
+        edited.write_to_file(&path).unwrap();
+        let network = ServiceConfig {
+            address: "0.0.0.0".to_string(),
+            ..Default::default()
+        };
+        write_service_config(&network, &path).unwrap();
+        let written: BunnylolConfig =
+            toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written.server.address, "0.0.0.0");
+        assert_eq!(written.server.port, 8123);
+        assert_eq!(written.default_search, "ddg");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_status_report_display() {
+        let report = StatusReport {
+            state: ServiceState::Running,
+            installed_as: Some("/etc/systemd/system/bunnylol.service (system unit)".to_string()),
+            config: Some(std::path::PathBuf::from("/etc/bunnylol/config.toml")),
+            serving: Some(("http://127.0.0.1:8000".to_string(), true)),
+            logs: "journalctl -u bunnylol".to_string(),
+        };
+        assert_eq!(
+            report.to_string(),
+            "bunnylol service: running\n  \
+             Installed: /etc/systemd/system/bunnylol.service (system unit)\n  \
+             Config:    /etc/bunnylol/config.toml\n  \
+             Serving:   http://127.0.0.1:8000 (answering health checks)\n  \
+             Logs:      journalctl -u bunnylol"
+        );
+
+        let report = StatusReport {
+            state: ServiceState::NotInstalled,
+            installed_as: None,
+            config: None,
+            serving: Some(("http://127.0.0.1:8000".to_string(), false)),
+            logs: "journalctl -u bunnylol".to_string(),
+        };
+        assert_eq!(
+            report.to_string(),
+            "bunnylol service: not installed\n  \
+             Serving:   http://127.0.0.1:8000 (not answering)\n  \
+             Logs:      journalctl -u bunnylol"
+        );
+    }
+
+    #[test]
+    fn test_last_lines() {
+        let log = "one\ntwo\nthree\n";
+        assert_eq!(last_lines(log, 2), "two\nthree\n");
+        assert_eq!(last_lines(log, 3), log);
+        assert_eq!(last_lines(log, 10), log);
+        assert_eq!(last_lines(log, 0), "");
+        assert_eq!(last_lines("one\ntwo", 1), "two");
+    }
+}
@@ -8,13 +8,35 @@
 use leptos::*;
 use rocket::request::FlashMessage;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::{BunnylolCommandInfo, BunnylolCommandRegistry, BunnylolConfig};
+use crate::history_stats::{CommandScore, format_age};
+use crate::{BunnylolCommandInfo, BunnylolCommandRegistry, BunnylolConfig, CommandCategory};
+
+/// How many "most used" commands the landing page shows
+const MOST_USED_LIMIT: usize = 6;
+
+/// How many cards each page of the commands and aliases lists renders
+pub const BINDINGS_PAGE_SIZE: usize = 120;
+
+/// Above this many entries, autocomplete queries /api/commands instead of
+/// embedding every binding in the page
+const SUGGESTIONS_EMBED_LIMIT: usize = 500;
 
 #[derive(Clone, Default, PartialEq, Eq)]
 pub struct LandingPageState {
     pub active_tab: String,
     pub alias_notice: Option<AliasNotice>,
+    /// The requesting client's most recent commands, newest first
+    pub recent_commands: Vec<String>,
+    /// The requesting client's highest-frecency commands, best first
+    pub most_used: Vec<String>,
+    /// Frecency score per binding, used to rank autocomplete suggestions
+    pub binding_scores: HashMap<String, u64>,
+    /// Zero-based page of the commands and aliases lists
+    pub page: usize,
+    /// Uses per binding over the last 30 days, or `None` without history
+    pub daily_usage: Option<HashMap<String, Vec<u32>>>,
 }
 
 impl LandingPageState {
@@ -29,6 +51,8 @@ impl LandingPageState {
         });
         let active_tab = match tab {
             Some("aliases") => "aliases".to_string(),
+            Some("playground") => "playground".to_string(),
+            Some("commands") => "commands".to_string(),
             _ if alias_notice.is_some() => "aliases".to_string(),
             _ => "commands".to_string(),
         };
@@ -36,8 +60,45 @@ impl LandingPageState {
         Self {
             active_tab,
             alias_notice,
+            recent_commands: Vec::new(),
+            most_used: Vec::new(),
+            binding_scores: HashMap::new(),
+            page: 0,
+            daily_usage: None,
         }
     }
+
+    /// Show the given zero-based page of the commands and aliases lists
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// Show these commands as one-click chips above the command list
+    pub fn with_recent_commands(mut self, recent_commands: Vec<String>) -> Self {
+        self.recent_commands = recent_commands;
+        self
+    }
+
+    /// Show a usage sparkline on each binding and alias card
+    pub fn with_daily_usage(mut self, daily_usage: HashMap<String, Vec<u32>>) -> Self {
+        self.daily_usage = Some(daily_usage);
+        self
+    }
+
+    /// Rank suggestions and fill the "most used" section from frecency scores
+    pub fn with_frecency(mut self, commands: &[CommandScore], bindings: &[CommandScore]) -> Self {
+        self.most_used = commands
+            .iter()
+            .take(MOST_USED_LIMIT)
+            .map(|score| score.command.clone())
+            .collect();
+        self.binding_scores = bindings
+            .iter()
+            .map(|score| (score.command.clone(), score.score))
+            .collect();
+        self
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -59,6 +120,13 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
     let aliases = config.aliases.clone();
     let initial_tab = page_state.active_tab.clone();
     let page_state = page_state.clone();
+    let mut suggestions = suggestion_entries(config, &page_state.binding_scores);
+    let suggestions_remote = suggestions.len() > SUGGESTIONS_EMBED_LIMIT;
+    suggestions.truncate(SUGGESTIONS_EMBED_LIMIT);
+    // Escape "</" so the JSON can't terminate the surrounding <script> tag
+    let suggestions_json = rocket::serde::json::to_string(&suggestions)
+        .unwrap_or_else(|_| "[]".to_string())
+        .replace("</", "<\\/");
     let body_content = leptos::ssr::render_to_string(move || {
         view! {
             <LandingPage
@@ -79,23 +147,96 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                         <meta name="viewport" content="width=device-width, initial-scale=1.0">
                         <title>bunnylol</title>
                         <link rel="icon" href="data:image/svg+xml,<svg xmlns=%22http://www.w3.org/2000/svg%22 viewBox=%220 0 100 100%22><text y=%22.9em%22 font-size=%2290%22>🐰</text></svg>">
+                        <link rel="manifest" href="/manifest.webmanifest">
+                        <link rel="apple-touch-icon" href="/icon.svg">
+                        <meta name="theme-color" content="{}">
+                        <meta name="apple-mobile-web-app-capable" content="yes">
+                        <meta name="apple-mobile-web-app-title" content="Bunnylol">
                         <link rel="preconnect" href="https://fonts.googleapis.com">
                         <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
                         <link href="https://fonts.googleapis.com/css2?family=JetBrains+Mono:wght@400;500;700&display=swap" rel="stylesheet">
                         <style>
                             :root {{
+                                color-scheme: light dark;
                                 --gradient-start: #87CEEB;
                                 --gradient-end: #6D28D9;
-                                --accent-blue: #008ECD;
+                                --accent-blue: #00729F;
                                 --accent-purple: #532ED1;
-                                --text-gray: #9CA3AF;
                                 --text-dark: #333;
-                                --text-medium: #666;
-                                --text-light: #888;
+                                --text-medium: #595959;
+                                --text-light: #6b6b6b;
                                 --bg-white: white;
                                 --bg-light-gray: #f5f7fa;
                                 --bg-gradient-gray: #c3cfe2;
                                 --border-light: #e0e0e0;
+                                --focus-ring: #532ED1;
+                                --alias-bg-start: #fff9e8;
+                                --alias-bg-end: #ffe8cc;
+                                --alias-border: #ffd8a8;
+                                --alias-input-border: #c9a65a;
+                                --danger: #a23f2d;
+                                --danger-border: #f1c3bb;
+                                --notice-success-bg: #eefbf3;
+                                --notice-success-border: #8bd8a8;
+                                --notice-error-bg: #fff3f1;
+                                --notice-error-border: #f2b3a8;
+                            }}
+                            @media (prefers-color-scheme: dark) {{
+                                :root {{
+                                    --gradient-start: #1b3a4b;
+                                    --gradient-end: #2e1065;
+                                    --accent-blue: #6cc6f5;
+                                    --accent-purple: #b9a8ff;
+                                    --text-dark: #ececf1;
+                                    --text-medium: #c5c5d2;
+                                    --text-light: #a9a9b8;
+                                    --bg-white: #1d1d24;
+                                    --bg-light-gray: #272732;
+                                    --bg-gradient-gray: #30303d;
+                                    --border-light: #44444f;
+                                    --focus-ring: #b9a8ff;
+                                    --alias-bg-start: #2f2a1d;
+                                    --alias-bg-end: #3a2d1c;
+                                    --alias-border: #6b5630;
+                                    --alias-input-border: #8a7140;
+                                    --danger: #ff9b87;
+                                    --danger-border: #7a3a2e;
+                                    --notice-success-bg: #17301f;
+                                    --notice-success-border: #2f7a4a;
+                                    --notice-error-bg: #3a1f1b;
+                                    --notice-error-border: #8a3f33;
+                                }}
+                            }}
+                            .visually-hidden {{
+                                position: absolute;
+                                width: 1px;
+                                height: 1px;
+                                margin: -1px;
+                                overflow: hidden;
+                                clip: rect(0 0 0 0);
+                                white-space: nowrap;
+                                border: 0;
+                            }}
+                            .skip-link {{
+                                position: absolute;
+                                left: 16px;
+                                top: -60px;
+                                background: var(--bg-white);
+                                color: var(--accent-purple);
+                                padding: 10px 16px;
+                                border-radius: 8px;
+                                z-index: 20;
+                            }}
+                            .skip-link:focus {{
+                                top: 16px;
+                            }}
+                            :focus-visible {{
+                                outline: 3px solid var(--focus-ring);
+                                outline-offset: 2px;
+                            }}
+                            @media (prefers-reduced-motion: reduce) {{
+                                * {{ transition: none !important; }}
+                                .binding-card:hover {{ transform: none; }}
                             }}
                             * {{ margin: 0; padding: 0; box-sizing: border-box; }}
                             body {{
@@ -107,7 +248,6 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                             }}
                             .binding-card {{
                                 box-shadow: 0 2px 4px rgba(0, 0, 0, 0.1);
-                                cursor: pointer;
                             }}
                             .binding-card:hover {{
                                 transform: translateY(-5px);
@@ -130,6 +270,34 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                                 border-color: transparent;
                                 box-shadow: 0 10px 20px rgba(83, 46, 209, 0.18);
                             }}
+                            .binding-grid {{
+                                display: grid;
+                                grid-template-columns: repeat(auto-fill, minmax(350px, 1fr));
+                                gap: 20px;
+                                margin: 16px 0 10px 0;
+                            }}
+                            .category-group {{
+                                margin-bottom: 18px;
+                            }}
+                            .category-group > summary {{
+                                cursor: pointer;
+                                font-weight: 700;
+                                color: var(--text-dark);
+                                padding: 8px 0;
+                                border-bottom: 1px solid var(--border-light);
+                            }}
+                            .recent-chip {{
+                                border: 1px solid var(--border-light);
+                                background: var(--bg-light-gray);
+                                color: var(--accent-purple);
+                                border-radius: 999px;
+                                padding: 4px 12px;
+                                font-size: 0.85em;
+                                text-decoration: none;
+                            }}
+                            .recent-chip:hover {{
+                                border-color: var(--accent-purple);
+                            }}
                             .tab-panel[hidden] {{
                                 display: none !important;
                             }}
@@ -156,10 +324,91 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                             .help-panel[hidden] {{
                                 display: none !important;
                             }}
+                            .suggestions {{
+                                position: absolute;
+                                top: calc(100% + 6px);
+                                left: 0;
+                                right: 0;
+                                list-style: none;
+                                background: var(--bg-white);
+                                border: 1px solid var(--border-light);
+                                border-radius: 12px;
+                                box-shadow: 0 10px 25px rgba(0, 0, 0, 0.15);
+                                max-height: 320px;
+                                overflow-y: auto;
+                                z-index: 5;
+                            }}
+                            .suggestions[hidden], .cheatsheet-backdrop[hidden] {{
+                                display: none !important;
+                            }}
+                            .suggestions li {{
+                                display: flex;
+                                gap: 12px;
+                                padding: 10px 16px;
+                                cursor: pointer;
+                                color: var(--text-medium);
+                            }}
+                            .suggestions li strong {{
+                                color: var(--accent-blue);
+                                min-width: 110px;
+                            }}
+                            .suggestions li[aria-selected="true"] {{
+                                background: var(--bg-light-gray);
+                            }}
+                            .cheatsheet-backdrop {{
+                                position: fixed;
+                                inset: 0;
+                                background: rgba(0, 0, 0, 0.45);
+                                display: flex;
+                                align-items: flex-start;
+                                justify-content: center;
+                                padding: 40px 20px;
+                                z-index: 10;
+                            }}
+                            .cheatsheet {{
+                                background: var(--bg-white);
+                                border-radius: 12px;
+                                padding: 24px;
+                                max-width: 900px;
+                                width: 100%;
+                                max-height: calc(100vh - 80px);
+                                overflow-y: auto;
+                                color: var(--text-dark);
+                            }}
+                            .cheatsheet-keys {{
+                                display: flex;
+                                flex-wrap: wrap;
+                                gap: 16px;
+                                margin-bottom: 20px;
+                                color: var(--text-medium);
+                            }}
+                            .cheatsheet kbd {{
+                                border: 1px solid var(--border-light);
+                                border-bottom-width: 2px;
+                                border-radius: 4px;
+                                padding: 1px 6px;
+                                margin-right: 4px;
+                                font-family: 'JetBrains Mono', monospace;
+                            }}
+                            .cheatsheet-table {{
+                                width: 100%;
+                                border-collapse: collapse;
+                                font-size: 0.9em;
+                            }}
+                            .cheatsheet-table td {{
+                                padding: 6px 8px;
+                                border-top: 1px solid var(--border-light);
+                                vertical-align: top;
+                            }}
+                            .cheatsheet-table code {{
+                                color: var(--accent-blue);
+                                font-weight: 700;
+                            }}
                         </style>
                     </head>
                     <body data-initial-tab="{}">
                         {}
+                        <script type="application/json" id="bunnylol-suggestions" data-remote="{}">{}</script>
                         <script>
                             (() => {{
                                 const initialTab = document.body.dataset.initialTab || 'commands';
@@ -170,13 +419,23 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                                         const isActive = button.dataset.tabButton === tabName;
                                         button.classList.toggle('active', isActive);
                                         button.setAttribute('aria-selected', isActive ? 'true' : 'false');
+                                        button.tabIndex = isActive ? 0 : -1;
                                     }});
                                     panels.forEach((panel) => {{
                                         panel.hidden = panel.dataset.tabPanel !== tabName;
                                     }});
                                 }};
-                                buttons.forEach((button) => {{
+                                buttons.forEach((button, index) => {{
                                     button.addEventListener('click', () => showTab(button.dataset.tabButton));
+                                    // Arrow keys move between tabs, as in the WAI-ARIA tabs pattern
+                                    button.addEventListener('keydown', (event) => {{
+                                        const moves = {{ ArrowRight: index + 1, ArrowLeft: index - 1, Home: 0, End: buttons.length - 1 }};
+                                        if (!(event.key in moves)) return;
+                                        event.preventDefault();
+                                        const next = buttons[(moves[event.key] + buttons.length) % buttons.length];
+                                        showTab(next.dataset.tabButton);
+                                        next.focus();
+                                    }});
                                 }});
                                 showTab(initialTab);
 
@@ -198,45 +457,1163 @@ pub fn render_landing_page_html(config: &BunnylolConfig, page_state: &LandingPag
                                         aliasNotice.remove();
                                     }}, 5000);
                                 }}
+
+                                const commandInput = document.querySelector('[data-command-input]');
+                                const suggestionList = document.querySelector('[data-suggestions]');
+                                const cheatsheet = document.querySelector('[data-cheatsheet]');
+                                const suggestionStatus = document.querySelector('[data-suggestion-status]');
+                                const suggestionScript = document.getElementById('bunnylol-suggestions');
+                                const suggestionData = JSON.parse(suggestionScript.textContent || '[]');
+                                // Large command lists aren't embedded in full; ask the server instead
+                                const suggestionsRemote = suggestionScript.dataset.remote === 'true';
+                                let activeSuggestion = -1;
+                                let suggestionRequest = 0;
+
+                                const findMatches = async (token) => {{
+                                    if (!suggestionsRemote) {{
+                                        return suggestionData.filter((s) => s.binding.toLowerCase().startsWith(token)).slice(0, 8);
+                                    }}
+                                    const response = await fetch(`/api/commands?q=${{encodeURIComponent(token)}}&limit=8`);
+                                    if (!response.ok) return [];
+                                    const page = await response.json();
+                                    return page.entries.map((entry) => ({{
+                                        binding: entry.bindings.find((b) => b.toLowerCase().startsWith(token)) || entry.bindings[0],
+                                        description: entry.description,
+                                    }}));
+                                }};
+                                const renderSuggestions = async () => {{
+                                    const value = commandInput.value.trimStart();
+                                    const token = value.split(/\s+/)[0].toLowerCase();
+                                    const request = ++suggestionRequest;
+                                    const matches = token && !value.includes(' ') ? await findMatches(token) : [];
+                                    if (request !== suggestionRequest) return;
+                                    suggestionList.innerHTML = '';
+                                    activeSuggestion = -1;
+                                    matches.forEach((match, index) => {{
+                                        const item = document.createElement('li');
+                                        item.id = `suggestion-${{index}}`;
+                                        item.setAttribute('role', 'option');
+                                        item.setAttribute('aria-selected', 'false');
+                                        item.dataset.binding = match.binding;
+                                        const name = document.createElement('strong');
+                                        name.textContent = match.binding;
+                                        const description = document.createElement('span');
+                                        description.textContent = match.description;
+                                        item.append(name, description);
+                                        item.addEventListener('mousedown', (event) => {{
+                                            event.preventDefault();
+                                            acceptSuggestion(item);
+                                        }});
+                                        suggestionList.appendChild(item);
+                                    }});
+                                    suggestionList.hidden = matches.length === 0;
+                                    commandInput.setAttribute('aria-expanded', matches.length > 0 ? 'true' : 'false');
+                                    if (suggestionStatus) {{
+                                        suggestionStatus.textContent = matches.length > 0
+                                            ? `${{matches.length}} suggestions, use up and down arrows to choose`
+                                            : '';
+                                    }}
+                                    commandInput.removeAttribute('aria-activedescendant');
+                                }};
+                                const highlightSuggestion = (index) => {{
+                                    const items = Array.from(suggestionList.children);
+                                    if (items.length === 0) return;
+                                    activeSuggestion = (index + items.length) % items.length;
+                                    items.forEach((item, i) => item.setAttribute('aria-selected', i === activeSuggestion ? 'true' : 'false'));
+                                    commandInput.setAttribute('aria-activedescendant', items[activeSuggestion].id);
+                                    items[activeSuggestion].scrollIntoView({{ block: 'nearest' }});
+                                }};
+                                const acceptSuggestion = (item) => {{
+                                    commandInput.value = `${{item.dataset.binding}} `;
+                                    renderSuggestions();
+                                    commandInput.focus();
+                                }};
+                                // Focus moves into the dialog when it opens and back to where it came from when it closes
+                                let cheatsheetReturnFocus = null;
+                                const toggleCheatsheet = (open) => {{
+                                    cheatsheet.hidden = !open;
+                                    if (open) {{
+                                        cheatsheetReturnFocus = document.activeElement;
+                                        cheatsheet.querySelector('[data-cheatsheet-close]').focus();
+                                    }} else {{
+                                        (cheatsheetReturnFocus || commandInput).focus();
+                                        cheatsheetReturnFocus = null;
+                                    }}
+                                }};
+
+                                if (commandInput && suggestionList) {{
+                                    commandInput.addEventListener('input', renderSuggestions);
+                                    commandInput.addEventListener('blur', () => {{ suggestionList.hidden = true; }});
+                                    commandInput.addEventListener('keydown', (event) => {{
+                                        if (event.key === 'ArrowDown') {{
+                                            event.preventDefault();
+                                            highlightSuggestion(activeSuggestion + 1);
+                                        }} else if (event.key === 'ArrowUp') {{
+                                            event.preventDefault();
+                                            highlightSuggestion(activeSuggestion - 1);
+                                        }} else if ((event.key === 'Enter' || event.key === 'Tab') && activeSuggestion >= 0) {{
+                                            event.preventDefault();
+                                            acceptSuggestion(suggestionList.children[activeSuggestion]);
+                                        }}
+                                    }});
+                                }}
+
+                                document.addEventListener('keydown', (event) => {{
+                                    const target = event.target;
+                                    const typing = target instanceof HTMLInputElement || target instanceof HTMLTextAreaElement;
+                                    if (event.key === 'Escape') {{
+                                        if (cheatsheet && !cheatsheet.hidden) {{
+                                            toggleCheatsheet(false);
+                                        }} else if (commandInput) {{
+                                            commandInput.value = '';
+                                            renderSuggestions();
+                                            commandInput.focus();
+                                        }}
+                                        return;
+                                    }}
+                                    if (typing || event.ctrlKey || event.metaKey || event.altKey) return;
+                                    if (event.key === '/') {{
+                                        event.preventDefault();
+                                        commandInput.focus();
+                                        commandInput.select();
+                                    }} else if (event.key === '?' && cheatsheet) {{
+                                        event.preventDefault();
+                                        toggleCheatsheet(cheatsheet.hidden);
+                                    }}
+                                }});
+                                if (cheatsheet) {{
+                                    cheatsheet.addEventListener('click', (event) => {{
+                                        if (event.target === cheatsheet) toggleCheatsheet(false);
+                                    }});
+                                    cheatsheet.querySelector('[data-cheatsheet-close]').addEventListener('click', () => toggleCheatsheet(false));
+                                    // Keep Tab inside the open dialog
+                                    cheatsheet.addEventListener('keydown', (event) => {{
+                                        if (event.key !== 'Tab') return;
+                                        const focusable = Array.from(cheatsheet.querySelectorAll('button, a[href], [tabindex="0"]'));
+                                        const first = focusable[0];
+                                        const last = focusable[focusable.length - 1];
+                                        if (event.shiftKey && document.activeElement === first) {{
+                                            event.preventDefault();
+                                            last.focus();
+                                        }} else if (!event.shiftKey && document.activeElement === last) {{
+                                            event.preventDefault();
+                                            first.focus();
+                                        }}
+                                    }});
+                                }}
+
+                                if ('serviceWorker' in navigator) {{
+                                    navigator.serviceWorker.register('/sw.js').catch(() => {{}});
+                                }}
                             }})();
                         </script>
                     </body>
                 </html>"#,
-        initial_tab, body_content
+        THEME_COLOR, initial_tab, body_content, suggestions_remote, suggestions_json
     )
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
-pub struct BindingData {
-    pub command: String,
-    pub description: String,
-    pub example: String,
+/// Wrap server-rendered body content in a minimal standalone HTML document
+///
+/// Used for the smaller pages (interstitials, help pages) that don't need the
+/// landing page's scripts.
+pub fn render_simple_page(title: &str, body_content: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+                    <html lang="en">
+                    <head>
+                        <meta charset="UTF-8">
+                        <meta name="viewport" content="width=device-width, initial-scale=1.0">
+                        <title>{} · bunnylol</title>
+                        <link rel="icon" href="/icon.svg">
+                        <meta name="theme-color" content="{}">
+                        <style>
+                            :root {{
+                                color-scheme: light dark;
+                                --page-bg: white;
+                                --page-text: #333;
+                                --page-muted: #595959;
+                                --page-subtle-bg: #f5f7fa;
+                                --page-border: #e0e0e0;
+                                --page-link: #00729F;
+                                --page-focus: #532ED1;
+                            }}
+                            @media (prefers-color-scheme: dark) {{
+                                :root {{
+                                    --page-bg: #1d1d24;
+                                    --page-text: #ececf1;
+                                    --page-muted: #c5c5d2;
+                                    --page-subtle-bg: #272732;
+                                    --page-border: #44444f;
+                                    --page-link: #6cc6f5;
+                                    --page-focus: #b9a8ff;
+                                }}
+                            }}
+                            * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+                            body {{
+                                font-family: 'JetBrains Mono', monospace;
+                                background: linear-gradient(135deg, #87CEEB 0%, #6D28D9 100%);
+                                background-attachment: fixed;
+                                min-height: 100vh;
+                                padding: 20px;
+                                color: var(--page-text);
+                            }}
+                            :focus-visible {{
+                                outline: 3px solid var(--page-focus);
+                                outline-offset: 2px;
+                            }}
+                            .page {{
+                                max-width: 760px;
+                                margin: 40px auto;
+                                background: var(--page-bg);
+                                border-radius: 12px;
+                                padding: 30px;
+                                box-shadow: 0 20px 60px rgba(0, 0, 0, 0.3);
+                                line-height: 1.6;
+                            }}
+                            .page a {{ color: var(--page-link); }}
+                            .page h1 {{ font-size: 1.6em; margin-bottom: 12px; }}
+                            .page p {{ margin-bottom: 14px; color: var(--page-muted); }}
+                            .page code {{
+                                background: var(--page-subtle-bg);
+                                border: 1px solid var(--page-border);
+                                border-radius: 4px;
+                                padding: 2px 6px;
+                            }}
+                            .choice {{
+                                display: block;
+                                padding: 14px 18px;
+                                margin-bottom: 12px;
+                                border-radius: 10px;
+                                border: 1px solid var(--page-border);
+                                text-decoration: none;
+                                color: var(--page-text);
+                                word-break: break-all;
+                            }}
+                            .page .choice {{ color: var(--page-text); }}
+                            .page .choice.primary {{
+                                background: linear-gradient(135deg, #00729F 0%, #532ED1 100%);
+                                color: white;
+                                border-color: transparent;
+                            }}
+                            .choice small {{ display: block; opacity: 0.85; }}
+                            .page h2 {{ font-size: 1.1em; margin: 20px 0 8px; }}
+                            .page table {{ width: 100%; border-collapse: collapse; margin-bottom: 8px; }}
+                            .page th, .page td {{
+                                text-align: left;
+                                padding: 8px 10px;
+                                border-bottom: 1px solid var(--page-border);
+                                vertical-align: top;
+                            }}
+                            .page th {{ font-size: 0.85em; color: var(--page-muted); }}
+                            .visually-hidden {{
+                                position: absolute;
+                                width: 1px;
+                                height: 1px;
+                                margin: -1px;
+                                overflow: hidden;
+                                clip: rect(0 0 0 0);
+                                white-space: nowrap;
+                                border: 0;
+                            }}
+                        </style>
+                    </head>
+                    <body>
+                        {}
+                    </body>
+                </html>"#,
+        title, THEME_COLOR, body_content
+    )
+}
+
+/// Render the "did you mean" interstitial shown when an unknown command is a
+/// near-miss of a known binding
+pub fn render_did_you_mean_html(query: &str, suggested_query: &str, fallback_url: &str) -> String {
+    let query = query.to_string();
+    let suggested_query = suggested_query.to_string();
+    let fallback_url = fallback_url.to_string();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! {
+            <DidYouMeanPage
+                query=query.clone()
+                suggested_query=suggested_query.clone()
+                fallback_url=fallback_url.clone()
+            />
+        }
+    })
+    .to_string();
+
+    render_simple_page("Did you mean", &body_content)
+}
+
+#[component]
+fn DidYouMeanPage(query: String, suggested_query: String, fallback_url: String) -> impl IntoView {
+    let suggested_href = format!(
+        "/?cmd={}",
+        crate::utils::url_encoding::encode_url(&suggested_query)
+    );
+
+    view! {
+        <main class="page">
+            <h1>"Did you mean…"</h1>
+            <p>
+                <code>{query.clone()}</code>
+                " doesn't match any command."
+            </p>
+            <a class="choice primary" href=suggested_href data-suggested-command autofocus>
+                <small>"Run command"</small>
+                {suggested_query}
+            </a>
+            <a class="choice" href=fallback_url>
+                <small>"Search the web instead"</small>
+                {query}
+            </a>
+        </main>
+    }
+}
+
+/// Render a resolved URL as a QR code so it can be opened on another device
+pub fn render_qr_page_html(query: &str, url: &str) -> String {
+    let query = query.to_string();
+    let url = url.to_string();
+    let qr_svg = qr_code_svg(&url);
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! { <QrPage query=query.clone() url=url.clone() qr_svg=qr_svg.clone() /> }
+    })
+    .to_string();
+
+    render_simple_page("QR code", &body_content)
+}
+
+/// Encode `data` as an SVG QR code, or an empty string if it is too long to fit
+fn qr_code_svg(data: &str) -> String {
+    match qrcode::QrCode::new(data.as_bytes()) {
+        Ok(code) => code
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(240, 240)
+            .quiet_zone(true)
+            .build(),
+        Err(_) => String::new(),
+    }
+}
+
+#[component]
+fn QrPage(query: String, url: String, qr_svg: String) -> impl IntoView {
+    let has_code = !qr_svg.is_empty();
+
+    view! {
+        <main class="page" style:text-align="center">
+            <h1>{query}</h1>
+            {if has_code {
+                view! { <div data-qr-code inner_html=qr_svg></div> }.into_view()
+            } else {
+                view! { <p>"This URL is too long to fit in a QR code."</p> }.into_view()
+            }}
+            <a class="choice" href=url.clone()>
+                <small>"Open here instead"</small>
+                {url.clone()}
+            </a>
+        </main>
+    }
+}
+
+/// Render an interstitial for a query that resolved to several URLs, since a
+/// single redirect can only target one of them
+pub fn render_multi_target_html(query: &str, urls: &[String]) -> String {
+    let query = query.to_string();
+    let urls = urls.to_vec();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! { <MultiTargetPage query=query.clone() urls=urls.clone() /> }
+    })
+    .to_string();
+
+    render_simple_page("Open tabs", &body_content)
+}
+
+/// Opens every target link in a new tab when "Open all" is pressed. Browsers
+/// only allow this from a click, so it can't run on page load.
+const OPEN_ALL_JS: &str = r#"document.querySelector('[data-open-all]').addEventListener('click', () => {
+    document.querySelectorAll('[data-target-url]').forEach((link) => window.open(link.href, '_blank', 'noopener'));
+});"#;
+
+#[component]
+fn MultiTargetPage(query: String, urls: Vec<String>) -> impl IntoView {
+    let count = urls.len();
+
+    view! {
+        <main class="page">
+            <h1>{format!("Open {count} tabs")}</h1>
+            <p>
+                <code>{query}</code>
+                " resolved to more than one URL. If only one tab opens, allow pop-ups for this site."
+            </p>
+            <button type="button" class="choice primary" data-open-all autofocus style:width="100%" style:cursor="pointer" style:font="inherit">
+                {format!("Open all {count}")}
+            </button>
+            {urls.into_iter().map(|url| view! {
+                <a class="choice" href=url.clone() target="_blank" rel="noopener" data-target-url>
+                    {url}
+                </a>
+            }).collect::<Vec<_>>()}
+            <script inner_html=OPEN_ALL_JS></script>
+        </main>
+    }
+}
+
+/// Render the page shown when a command rejects its arguments, with the
+/// command's usage forms so the user can see what it expected
+pub fn render_command_error_html(
+    query: &str,
+    error: &str,
+    info: Option<&BunnylolCommandInfo>,
+    fallback_url: &str,
+) -> String {
+    let query = query.to_string();
+    let error = error.to_string();
+    let info = info.cloned();
+    let fallback_url = fallback_url.to_string();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! {
+            <CommandErrorPage
+                query=query.clone()
+                error=error.clone()
+                info=info.clone()
+                fallback_url=fallback_url.clone()
+            />
+        }
+    })
+    .to_string();
+
+    render_simple_page("Invalid command", &body_content)
+}
+
+#[component]
+fn CommandErrorPage(
+    query: String,
+    error: String,
+    info: Option<BunnylolCommandInfo>,
+    fallback_url: String,
+) -> impl IntoView {
+    let usage: Vec<_> = info
+        .map(|info| info.usage.into_iter().chain(info.subcommands).collect())
+        .unwrap_or_default();
+    let has_usage = !usage.is_empty();
+
+    view! {
+        <main class="page">
+            <h1>"Can't run that command"</h1>
+            <p>
+                <code>{query.clone()}</code>
+                ": "
+                <span data-command-error>{error}</span>
+            </p>
+            {has_usage.then(|| view! {
+                <h2>"Usage"</h2>
+                <table>
+                    <tbody>
+                        {usage.into_iter().map(|usage| view! {
+                            <tr>
+                                <td><code>{usage.syntax}</code></td>
+                                <td>{usage.description}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            })}
+            <a class="choice" href=fallback_url>
+                <small>"Search the web instead"</small>
+                {query}
+            </a>
+        </main>
+    }
+}
+
+/// How many of the top commands the stats page lists
+pub const STATS_TOP_LIMIT: usize = 20;
+
+/// Render the /stats page: top commands from history plus a live feed of
+/// commands streamed from /stats/events
+pub fn render_stats_html(top_commands: &[CommandScore], history_enabled: bool) -> String {
+    let top_commands: Vec<CommandScore> =
+        top_commands.iter().take(STATS_TOP_LIMIT).cloned().collect();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! { <StatsPage top_commands=top_commands.clone() history_enabled=history_enabled /> }
+    })
+    .to_string();
+
+    render_simple_page("Stats", &body_content)
+}
+
+/// Prepends each streamed command to the live feed, keeping the newest 100
+const LIVE_FEED_JS: &str = r#"(() => {
+    const feed = document.querySelector('[data-live-feed]');
+    const status = document.querySelector('[data-live-status]');
+    const source = new EventSource('/stats/events');
+    source.addEventListener('open', () => { status.textContent = 'Connected'; });
+    source.addEventListener('error', () => { status.textContent = 'Reconnecting…'; });
+    source.addEventListener('command', (message) => {
+        const event = JSON.parse(message.data);
+        const item = document.createElement('li');
+        const time = new Date(event.timestamp * 1000).toLocaleTimeString();
+        const command = document.createElement('code');
+        command.textContent = event.command;
+        item.append(`${time} · ${event.user} · `, command, ` → ${event.url}`);
+        feed.prepend(item);
+        while (feed.children.length > 100) feed.lastElementChild.remove();
+    });
+})();"#;
+
+#[component]
+fn StatsPage(top_commands: Vec<CommandScore>, history_enabled: bool) -> impl IntoView {
+    let has_top = !top_commands.is_empty();
+
+    view! {
+        <main class="page">
+            <h1>"Stats"</h1>
+            <h2>"Live feed"</h2>
+            <p data-live-status role="status">"Connecting…"</p>
+            <ul data-live-feed aria-label="Recent commands" style:list-style="none" style:word-break="break-all"></ul>
+            <h2>"Top commands"</h2>
+            {if has_top {
+                view! {
+                    <table>
+                        <thead>
+                            <tr>
+                                <th>"Command"</th>
+                                <th>"Uses"</th>
+                                <th>"Score"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {top_commands.into_iter().map(|score| view! {
+                                <tr>
+                                    <td><code>{score.command}</code></td>
+                                    <td>{score.count}</td>
+                                    <td>{score.score}</td>
+                                </tr>
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                }.into_view()
+            } else if history_enabled {
+                view! { <p>"No commands in history yet."</p> }.into_view()
+            } else {
+                view! { <p>"History is disabled, so only the live feed is available."</p> }.into_view()
+            }}
+            <script inner_html=LIVE_FEED_JS></script>
+        </main>
+    }
+}
+
+/// How many matches the `hist` results page lists
+const HISTORY_SEARCH_LIMIT: usize = 100;
+
+/// Render the /history page for the `hist` command: the client's past commands
+/// matching `query`, most recent first, each linking back through `/?cmd=`.
+/// `available` is false when history is disabled or not kept per client.
+pub fn render_history_search_html(
+    query: &str,
+    results: &[CommandScore],
+    now: u64,
+    available: bool,
+) -> String {
+    let query = query.to_string();
+    let results: Vec<CommandScore> = results.iter().take(HISTORY_SEARCH_LIMIT).cloned().collect();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! {
+            <HistorySearchPage
+                query=query.clone()
+                results=results.clone()
+                now=now
+                available=available
+            />
+        }
+    })
+    .to_string();
+
+    render_simple_page("History", &body_content)
+}
+
+#[component]
+fn HistorySearchPage(
+    query: String,
+    results: Vec<CommandScore>,
+    now: u64,
+    available: bool,
+) -> impl IntoView {
+    let has_results = !results.is_empty();
+    let searched = !query.trim().is_empty();
+
+    view! {
+        <main class="page">
+            <h1>"History"</h1>
+            <form action="/history" method="get" role="search">
+                <input
+                    type="search"
+                    name="q"
+                    value=query.clone()
+                    placeholder="Search your past commands"
+                    aria-label="Search your past commands"
+                />
+                <button type="submit">"Search"</button>
+            </form>
+            {if has_results {
+                view! {
+                    <table>
+                        <thead>
+                            <tr>
+                                <th>"Command"</th>
+                                <th>"Uses"</th>
+                                <th>"Last used"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {results.into_iter().map(|result| {
+                                let href = format!(
+                                    "/?cmd={}",
+                                    crate::utils::url_encoding::encode_url(&result.command)
+                                );
+                                view! {
+                                    <tr data-history-result>
+                                        <td><a href=href title="Run again"><code>{result.command}</code></a></td>
+                                        <td>{result.count}</td>
+                                        <td>{format_age(now.saturating_sub(result.last_used))}</td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                }.into_view()
+            } else if !available {
+                view! { <p>"History is disabled or not kept per client, so there is nothing to search."</p> }.into_view()
+            } else if searched {
+                view! { <p>"No past commands match "<code>{query}</code>"."</p> }.into_view()
+            } else {
+                view! { <p>"No commands in history yet."</p> }.into_view()
+            }}
+        </main>
+    }
+}
+
+/// Render the help page for a single command: bindings, usage forms, and any
+/// config values or aliases that affect it
+pub fn render_help_page_html(info: &BunnylolCommandInfo, config: &BunnylolConfig) -> String {
+    let info = info.clone();
+    let settings: Vec<(String, String)> = info
+        .config_keys
+        .iter()
+        .map(|key| {
+            let value = config
+                .setting_value(key)
+                .unwrap_or_else(|| "(default)".to_string());
+            (key.clone(), value)
+        })
+        .collect();
+    let mut aliases: Vec<(String, String)> = config
+        .aliases
+        .iter()
+        .filter(|(_, target)| {
+            target
+                .split_whitespace()
+                .next()
+                .is_some_and(|command| info.bindings.iter().any(|b| b == command))
+        })
+        .map(|(alias, target)| (alias.clone(), target.clone()))
+        .collect();
+    aliases.sort();
+
+    let title = info.bindings.first().cloned().unwrap_or_default();
+    let body_content = leptos::ssr::render_to_string(move || {
+        view! {
+            <HelpPage
+                info=info.clone()
+                settings=settings.clone()
+                aliases=aliases.clone()
+            />
+        }
+    })
+    .to_string();
+
+    render_simple_page(&format!("help {title}"), &body_content)
+}
+
+#[component]
+fn HelpPage(
+    info: BunnylolCommandInfo,
+    settings: Vec<(String, String)>,
+    aliases: Vec<(String, String)>,
+) -> impl IntoView {
+    let signature = info.signature();
+    let examples: Vec<(String, String)> = info
+        .examples
+        .iter()
+        .map(|example| {
+            let href = crate::utils::url_encoding::build_search_url("/", "cmd", example);
+            (href, example.clone())
+        })
+        .collect();
+    let tags = info.tags.join(", ");
+    let has_tags = !tags.is_empty();
+    let has_usage = !info.usage.is_empty();
+    let has_subcommands = !info.subcommands.is_empty();
+    let has_settings = !settings.is_empty();
+    let has_aliases = !aliases.is_empty();
+
+    view! {
+        <main class="page">
+            <h1>{info.bindings.join(" / ")}</h1>
+            <p><code data-signature>{signature}</code></p>
+            <p>{info.description.clone()}</p>
+            <p>
+                {if examples.len() > 1 { "Examples: " } else { "Example: " }}
+                {examples.into_iter().enumerate().map(|(i, (href, example))| view! {
+                    {(i > 0).then_some(", ")}
+                    <a href=href><code>{example}</code></a>
+                }).collect::<Vec<_>>()}
+            </p>
+            {has_tags.then(|| view! { <p>"Tags: " {tags}</p> })}
+            {has_usage.then(|| view! {
+                <h2>"Usage"</h2>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>"Syntax"</th>
+                            <th>"Description"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {info.usage.iter().map(|usage| view! {
+                            <tr>
+                                <td><code>{usage.syntax.clone()}</code></td>
+                                <td>{usage.description.clone()}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            })}
+            {has_subcommands.then(|| view! {
+                <h2>"Subcommands"</h2>
+                <table>
+                    <tbody>
+                        {info.subcommands.iter().map(|usage| view! {
+                            <tr>
+                                <td><code>{usage.syntax.clone()}</code></td>
+                                <td>{usage.description.clone()}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            })}
+            {has_settings.then(|| view! {
+                <h2>"Config"</h2>
+                <table>
+                    <tbody>
+                        {settings.into_iter().map(|(key, value)| view! {
+                            <tr>
+                                <td><code>{key}</code></td>
+                                <td data-config-value>{value}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            })}
+            {has_aliases.then(|| view! {
+                <h2>"Your aliases"</h2>
+                <table>
+                    <tbody>
+                        {aliases.into_iter().map(|(alias, target)| view! {
+                            <tr>
+                                <td><code>{alias}</code></td>
+                                <td><code>{target}</code></td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            })}
+            <p><a href="/bindings">"All commands"</a></p>
+        </main>
+    }
+}
+
+/// A single autocomplete entry for the landing page command box
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuggestionData {
+    pub binding: String,
+    pub description: String,
+}
+
+/// Build autocomplete entries for every built-in binding and configured alias,
+/// highest frecency first and alphabetical otherwise
+pub fn suggestion_entries(
+    config: &BunnylolConfig,
+    binding_scores: &HashMap<String, u64>,
+) -> Vec<SuggestionData> {
+    let mut entries: Vec<SuggestionData> = BunnylolCommandRegistry::get_all_commands()
+        .iter()
+        .flat_map(|cmd| {
+            cmd.bindings
+                .iter()
+                .filter(|binding| !binding.starts_with('(') && !binding.contains('<'))
+                .map(|binding| SuggestionData {
+                    binding: binding.clone(),
+                    description: cmd.description.clone(),
+                })
+        })
+        .chain(config.aliases.iter().map(|(alias, target)| SuggestionData {
+            binding: alias.clone(),
+            description: format!("Alias for {}", target),
+        }))
+        .collect();
+    entries.sort_by_key(|entry| {
+        let score = binding_scores.get(&entry.binding).copied().unwrap_or(0);
+        (std::cmp::Reverse(score), entry.binding.to_lowercase())
+    });
+    entries
+}
+
+/// Theme color shared by the landing page and the web app manifest
+const THEME_COLOR: &str = "#532ED1";
+
+/// Icon served at /icon.svg and referenced by the web app manifest
+pub const APP_ICON_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100"><rect width="100" height="100" rx="20" fill="#532ED1"/><text x="50" y="50" dominant-baseline="central" text-anchor="middle" font-size="70">🐰</text></svg>"##;
+
+/// Minimal service worker served at /sw.js
+///
+/// It exists so browsers consider the landing page installable. Requests always
+/// go to the network: command redirects must never be served from a cache.
+pub const SERVICE_WORKER_JS: &str = r#"self.addEventListener('install', () => self.skipWaiting());
+self.addEventListener('activate', (event) => event.waitUntil(self.clients.claim()));
+self.addEventListener('fetch', (event) => event.respondWith(fetch(event.request)));
+"#;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestIcon {
+    pub src: String,
+    pub sizes: String,
+    #[serde(rename = "type")]
+    pub mime_type: String,
+    pub purpose: String,
+}
+
+/// Web app manifest served at /manifest.webmanifest
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebAppManifest {
+    pub name: String,
+    pub short_name: String,
+    pub description: String,
+    pub start_url: String,
+    pub scope: String,
+    pub display: String,
+    pub background_color: String,
+    pub theme_color: String,
+    pub icons: Vec<ManifestIcon>,
+}
+
+impl Default for WebAppManifest {
+    fn default() -> Self {
+        Self {
+            name: "Bunnylol".to_string(),
+            short_name: "Bunnylol".to_string(),
+            description: "Smart bookmarks: type a command, land on the right page".to_string(),
+            start_url: "/".to_string(),
+            scope: "/".to_string(),
+            display: "standalone".to_string(),
+            background_color: "#ffffff".to_string(),
+            theme_color: THEME_COLOR.to_string(),
+            icons: vec![ManifestIcon {
+                src: "/icon.svg".to_string(),
+                sizes: "any".to_string(),
+                mime_type: "image/svg+xml".to_string(),
+                purpose: "any maskable".to_string(),
+            }],
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct BindingData {
+    pub command: String,
+    pub description: String,
+    pub example: String,
+    /// Argument signature, e.g. "[<owner>/<repo>]"; empty when there are none
+    #[serde(default)]
+    pub args: String,
+    pub category: CommandCategory,
+    /// Daily uses across all of the command's bindings, oldest day first
+    #[serde(default)]
+    pub usage: Option<Vec<u32>>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasData {
+    pub alias: String,
+    pub target: String,
+    #[serde(default)]
+    pub usage: Option<Vec<u32>>,
+}
+
+impl From<BunnylolCommandInfo> for BindingData {
+    fn from(info: BunnylolCommandInfo) -> Self {
+        Self {
+            command: info
+                .bindings
+                .first()
+                .unwrap_or(&"(default)".to_string())
+                .clone(),
+            description: info.description,
+            example: info.example,
+            args: info.args,
+            category: info.category,
+            usage: None,
+        }
+    }
+}
+
+/// Sum the daily usage of several bindings; `None` when history isn't available
+fn combined_usage(
+    daily_usage: Option<&HashMap<String, Vec<u32>>>,
+    bindings: &[String],
+) -> Option<Vec<u32>> {
+    let daily_usage = daily_usage?;
+    let mut total = vec![0; crate::history_stats::SPARKLINE_DAYS];
+    for days in bindings
+        .iter()
+        .filter_map(|binding| daily_usage.get(binding))
+    {
+        for (sum, count) in total.iter_mut().zip(days) {
+            *sum += count;
+        }
+    }
+    Some(total)
+}
+
+/// SVG polyline points for a sparkline scaled to `width` x `height`
+fn sparkline_points(usage: &[u32], width: f32, height: f32) -> String {
+    let max = usage.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let step = width / (usage.len().max(2) - 1) as f32;
+    usage
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let y = height - 1.0 - (*count as f32 / max) * (height - 2.0);
+            format!("{:.1},{:.1}", i as f32 * step, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A small 30-day usage chart; renders nothing when history is unavailable
+#[component]
+fn Sparkline(usage: Option<Vec<u32>>) -> impl IntoView {
+    usage.map(|usage| {
+        let total: u32 = usage.iter().sum();
+        let label = match total {
+            0 => format!("Not used in the last {} days", usage.len()),
+            1 => format!("Used once in the last {} days", usage.len()),
+            n => format!("Used {} times in the last {} days", n, usage.len()),
+        };
+        let points = sparkline_points(&usage, 120.0, 24.0);
+        let stroke = if total == 0 {
+            "var(--text-light)"
+        } else {
+            "var(--accent-blue)"
+        };
+
+        view! {
+            <div
+                class="usage-sparkline"
+                data-usage-total=total
+                style:display="flex"
+                style:align-items="center"
+                style:gap="8px"
+                style:margin-top="12px"
+                style:font-size="0.8em"
+                style:color="var(--text-medium)"
+            >
+                <svg
+                    width="120"
+                    height="24"
+                    viewBox="0 0 120 24"
+                    aria-hidden="true"
+                    focusable="false"
+                >
+                    <polyline
+                        points=points
+                        fill="none"
+                        stroke=stroke
+                        stroke-width="1.5"
+                        stroke-linejoin="round"
+                    ></polyline>
+                </svg>
+                <span>{label}</span>
+            </div>
+        }
+    })
+}
+
+/// Resolves the playground query through /api/resolve as the user types and
+/// shows the result without redirecting or recording history
+const PLAYGROUND_JS: &str = r#"(() => {
+    const input = document.querySelector('[data-playground-input]');
+    const result = document.querySelector('[data-playground-result]');
+    if (!input || !result) return;
+    const field = (name) => result.querySelector(`[data-field="${name}"]`);
+    let timer;
+    let latest = 0;
+    const update = async () => {
+        const query = input.value.trim();
+        result.style.display = query ? 'grid' : 'none';
+        if (!query) return;
+        const request = ++latest;
+        const response = await fetch(`/api/resolve?cmd=${encodeURIComponent(query)}`);
+        if (!response.ok || request !== latest) return;
+        const resolution = await response.json();
+        const urls = field('urls');
+        urls.replaceChildren(...resolution.urls.map((url) => {
+            const link = document.createElement('a');
+            link.href = url;
+            link.textContent = url;
+            link.style.display = 'block';
+            return link;
+        }));
+        field('layer').textContent = resolution.command
+            ? `${resolution.layer} (${resolution.command})`
+            : resolution.layer;
+        field('chain').textContent = resolution.alias_chain.length
+            ? resolution.alias_chain.join(' → ')
+            : '(no alias)';
+        field('qr').href = `/?cmd=${encodeURIComponent(query)}&qr=1`;
+    };
+    input.addEventListener('input', () => {
+        clearTimeout(timer);
+        timer = setTimeout(update, 150);
+    });
+})();"#;
+
+/// "Try it" panel: a dry run of a query showing where it would go and why
+#[component]
+fn Playground() -> impl IntoView {
+    view! {
+        <div style:max-width="800px" style:margin="0 auto">
+            <p style:color="var(--text-medium)" style:line-height="1.7" style:margin-bottom="14px">
+                "Type a command to see exactly where it resolves. Nothing is opened or saved to history."
+            </p>
+            <input
+                type="search"
+                data-playground-input
+                autocomplete="off"
+                autocapitalize="off"
+                spellcheck="false"
+                aria-label="Command to try"
+                placeholder=example_query("gh facebook/react")
+                style:width="100%"
+                style:padding="12px"
+                style:border="1px solid var(--border-light)"
+                style:border-radius="8px"
+                style:font-family="'JetBrains Mono', monospace"
+                style:margin-bottom="16px"
+            />
+            <dl
+                data-playground-result
+                aria-live="polite"
+                style:display="none"
+                style:grid-template-columns="max-content 1fr"
+                style:gap="8px 16px"
+                style:word-break="break-all"
+            >
+                <dt>"URL"</dt>
+                <dd data-field="urls"></dd>
+                <dt>"Matched"</dt>
+                <dd data-field="layer"></dd>
+                <dt>"Alias chain"</dt>
+                <dd data-field="chain"></dd>
+                <dt>"Phone"</dt>
+                <dd><a data-field="qr" href="#">"Show QR code"</a></dd>
+            </dl>
+            <script inner_html=PLAYGROUND_JS></script>
+        </div>
+    }
+}
+
+/// An example query to show: `preferred` when its command is built in, else
+/// the first example that takes arguments, since a slim build may leave the
+/// preferred command out
+fn example_query(preferred: &str) -> String {
+    let command = crate::utils::get_command_from_query_string(preferred);
+    if BunnylolCommandRegistry::get_handler(command).is_some() {
+        return preferred.to_string();
+    }
+    BunnylolCommandRegistry::get_all_commands()
+        .iter()
+        .map(|info| info.example.as_str())
+        .find(|example| example.contains(' '))
+        .unwrap_or("bindings")
+        .to_string()
 }
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
-pub struct AliasData {
-    pub alias: String,
-    pub target: String,
+/// Number of pages needed to show `items` cards (at least one, even when empty)
+fn page_count(items: usize) -> usize {
+    items.div_ceil(BINDINGS_PAGE_SIZE).max(1)
 }
 
-impl From<BunnylolCommandInfo> for BindingData {
-    fn from(info: BunnylolCommandInfo) -> Self {
-        Self {
-            command: info
-                .bindings
-                .first()
-                .unwrap_or(&"(default)".to_string())
-                .clone(),
-            description: info.description,
-            example: info.example,
+/// Previous/next links for a paginated tab; renders nothing for a single page
+#[component]
+fn Pager(tab: &'static str, page: usize, page_count: usize) -> impl IntoView {
+    let href = move |page: usize| format!("/?tab={}&page={}", tab, page + 1);
+
+    (page_count > 1).then(|| {
+        view! {
+            <nav
+                aria-label=format!("{tab} pages")
+                data-pager=tab
+                style:display="flex"
+                style:justify-content="center"
+                style:align-items="center"
+                style:gap="16px"
+                style:margin-top="24px"
+                style:color="var(--text-medium)"
+            >
+                {(page > 0).then(|| view! { <a href=href(page - 1) rel="prev">"← Previous"</a> })}
+                <span aria-current="page">{format!("Page {} of {}", page + 1, page_count)}</span>
+                {(page + 1 < page_count).then(|| view! { <a href=href(page + 1) rel="next">"Next →"</a> })}
+            </nav>
         }
+    })
+}
+
+/// A row of one-click command links, e.g. recent or most-used commands
+#[component]
+fn CommandChips(label: &'static str, kind: &'static str, commands: Vec<String>) -> impl IntoView {
+    view! {
+        <nav
+            data-command-chips=kind
+            aria-label=label
+            style:display="flex"
+            style:flex-wrap="wrap"
+            style:justify-content="center"
+            style:align-items="center"
+            style:gap="8px"
+            style:max-width="700px"
+            style:margin="-8px auto 24px auto"
+        >
+            <span style:font-size="0.85em" style:color="var(--text-light)">{format!("{label}:")}</span>
+            {commands.into_iter().map(|command| {
+                let href = format!(
+                    "/?cmd={}",
+                    crate::utils::url_encoding::encode_url(&command)
+                );
+                view! { <a class="recent-chip" href=href>{command}</a> }
+            }).collect::<Vec<_>>()}
+        </nav>
     }
 }
 
 #[component]
 fn BindingCard(binding: BindingData) -> impl IntoView {
+    let help_href = crate::utils::url_encoding::build_path_url("/help", &binding.command);
+
     view! {
-        <div
+        <article
             class="binding-card"
             style:background="linear-gradient(135deg, var(--bg-light-gray) 0%, var(--bg-gradient-gray) 100%)"
             style:border-radius="8px"
@@ -244,19 +1621,34 @@ fn BindingCard(binding: BindingData) -> impl IntoView {
             style:transition="transform 0.2s, box-shadow 0.2s"
             style:border="2px solid var(--border-light)"
         >
-            <div
+            <h3
                 style:font-family="'JetBrains Mono', monospace"
                 style:font-size="1.4em"
                 style:font-weight="700"
-                style:color="var(--accent-blue)"
                 style:margin-bottom="10px"
                 style:background="var(--bg-white)"
                 style:padding="8px 12px"
                 style:border-radius="4px"
                 style:display="inline-block"
             >
-                {binding.command}
-            </div>
+                <a
+                    href=help_href
+                    style:color="var(--accent-blue)"
+                    style:text-decoration="none"
+                >
+                    {binding.command}
+                </a>
+            </h3>
+            {(!binding.args.is_empty()).then(|| view! {
+                <div
+                    style:font-family="'JetBrains Mono', monospace"
+                    style:font-size="0.85em"
+                    style:color="var(--text-medium)"
+                    style:margin-bottom="10px"
+                >
+                    {binding.args.clone()}
+                </div>
+            })}
             <div
                 style:color="var(--text-dark)"
                 style:margin-bottom="15px"
@@ -286,20 +1678,21 @@ fn BindingCard(binding: BindingData) -> impl IntoView {
                     {binding.example}
                 </div>
             </div>
-        </div>
+            <Sparkline usage=binding.usage />
+        </article>
     }
 }
 
 #[component]
 fn AliasCard(alias: AliasData) -> impl IntoView {
     view! {
-        <div
+        <article
             class="binding-card"
-            style:background="linear-gradient(135deg, #fff9e8 0%, #ffe8cc 100%)"
+            style:background="linear-gradient(135deg, var(--alias-bg-start) 0%, var(--alias-bg-end) 100%)"
             style:border-radius="8px"
             style:padding="20px"
             style:transition="transform 0.2s, box-shadow 0.2s"
-            style:border="2px solid #ffd8a8"
+            style:border="2px solid var(--alias-border)"
             style:position="relative"
         >
             <form
@@ -317,9 +1710,9 @@ fn AliasCard(alias: AliasData) -> impl IntoView {
                     style:width="34px"
                     style:height="34px"
                     style:border-radius="999px"
-                    style:border="1px solid #f1c3bb"
-                    style:background="rgba(255, 255, 255, 0.92)"
-                    style:color="#b44c39"
+                    style:border="1px solid var(--danger-border)"
+                    style:background="var(--bg-white)"
+                    style:color="var(--danger)"
                     style:display="flex"
                     style:align-items="center"
                     style:justify-content="center"
@@ -345,7 +1738,7 @@ fn AliasCard(alias: AliasData) -> impl IntoView {
                     </svg>
                 </button>
             </form>
-            <div
+            <h3
                 style:font-family="'JetBrains Mono', monospace"
                 style:font-size="1.3em"
                 style:font-weight="700"
@@ -354,7 +1747,7 @@ fn AliasCard(alias: AliasData) -> impl IntoView {
                 style:padding-right="40px"
             >
                 {alias.alias}
-            </div>
+            </h3>
             <div
                 style:font-size="0.85em"
                 style:color="var(--text-medium)"
@@ -369,13 +1762,14 @@ fn AliasCard(alias: AliasData) -> impl IntoView {
                 style:padding="12px"
                 style:border-radius="4px"
                 style:color="var(--text-dark)"
-                style:border="1px solid #ffd8a8"
+                style:border="1px solid var(--alias-border)"
                 style:line-height="1.5"
                 style:word-break="break-word"
             >
                 {alias.target}
             </div>
-        </div>
+            <Sparkline usage=alias.usage />
+        </article>
     }
 }
 
@@ -385,29 +1779,79 @@ pub fn LandingPage(
     aliases: std::collections::HashMap<String, String>,
     page_state: LandingPageState,
 ) -> impl IntoView {
+    let daily_usage = page_state.daily_usage.as_ref();
     let mut bindings: Vec<BindingData> = BunnylolCommandRegistry::get_all_commands()
         .iter()
-        .map(|cmd| (*cmd).clone().into())
+        .map(|cmd| BindingData {
+            usage: combined_usage(daily_usage, &cmd.bindings),
+            ..(*cmd).clone().into()
+        })
         .collect();
     let mut alias_entries: Vec<AliasData> = aliases
         .into_iter()
-        .map(|(alias, target)| AliasData { alias, target })
+        .map(|(alias, target)| AliasData {
+            usage: combined_usage(daily_usage, std::slice::from_ref(&alias)),
+            alias,
+            target,
+        })
         .collect();
 
-    // Sort bindings alphabetically by command name
-    bindings.sort_by(|a, b| a.command.to_lowercase().cmp(&b.command.to_lowercase()));
+    // Sort bindings by category, then alphabetically by command name, so each
+    // page lists its categories in display order
+    bindings.sort_by_key(|a| (a.category, a.command.to_lowercase()));
+    #[allow(clippy::unnecessary_sort_by)]
     alias_entries.sort_by(|a, b| a.alias.to_lowercase().cmp(&b.alias.to_lowercase()));
     let binding_count = bindings.len();
     let alias_count = alias_entries.len();
     let has_aliases = alias_count > 0;
+
+    // The commands tab pages through built-ins followed by aliases; the aliases
+    // tab pages through aliases alone
+    let commands_pages = page_count(binding_count + alias_count);
+    let commands_page = page_state.page.min(commands_pages - 1);
+    let start = commands_page * BINDINGS_PAGE_SIZE;
+    let end = start + BINDINGS_PAGE_SIZE;
+    let page_bindings = &bindings[start.min(binding_count)..end.min(binding_count)];
+    let page_command_aliases: Vec<AliasData> =
+        alias_entries[start.saturating_sub(binding_count).min(alias_count)
+            ..end.saturating_sub(binding_count).min(alias_count)]
+            .to_vec();
+    let has_page_command_aliases = !page_command_aliases.is_empty();
+    let page_command_aliases = store_value(page_command_aliases);
+
+    let alias_pages = page_count(alias_count);
+    let alias_page = page_state.page.min(alias_pages - 1);
+    let alias_entries: Vec<AliasData> = alias_entries
+        .into_iter()
+        .skip(alias_page * BINDINGS_PAGE_SIZE)
+        .take(BINDINGS_PAGE_SIZE)
+        .collect();
     let alias_entries = store_value(alias_entries);
+
+    let binding_groups: Vec<(CommandCategory, Vec<BindingData>)> = CommandCategory::ALL
+        .iter()
+        .map(|category| {
+            let group: Vec<BindingData> = page_bindings
+                .iter()
+                .filter(|binding| binding.category == *category)
+                .cloned()
+                .collect();
+            (*category, group)
+        })
+        .filter(|(_, group)| !group.is_empty())
+        .collect();
     let active_tab = page_state.active_tab.clone();
     let alias_notice = page_state.alias_notice.clone();
+    let recent_commands = page_state.recent_commands.clone();
+    let most_used = page_state.most_used.clone();
 
+    let example = example_query("gh facebook/react");
+    let address_bar_example = example_query("gh facebook/bunnylol.rs");
     // Clone server_display_url for use in the view
-    let example_url = format!("{}/?cmd=gh facebook/bunnylol.rs", server_display_url);
+    let example_url = format!("{}/?cmd={}", server_display_url, address_bar_example);
 
     view! {
+        <a class="skip-link" href="#command-input">"Skip to command box"</a>
         <div
             style:max-width="1200px"
             style:margin="0 auto 10px auto"
@@ -418,6 +1862,7 @@ pub fn LandingPage(
             style:font-family="'JetBrains Mono', monospace"
             style:position="relative"
         >
+            <header>
             <div
                 style:position="absolute"
                 style:top="18px"
@@ -429,6 +1874,7 @@ pub fn LandingPage(
                     type="button"
                     data-help-button
                     aria-expanded="false"
+                    aria-controls="setup-help"
                     aria-label="Show setup help"
                     title="Show setup help"
                 >
@@ -468,6 +1914,8 @@ pub fn LandingPage(
                         height="20"
                         viewBox="0 0 16 16"
                         fill="currentColor"
+                        aria-hidden="true"
+                        focusable="false"
                         style:display="inline-block"
                     >
                         <path d="M8 0C3.58 0 0 3.58 0 8c0 3.54 2.29 6.53 5.47 7.59.4.07.55-.17.55-.38 0-.19-.01-.82-.01-1.49-2.01.37-2.53-.49-2.69-.94-.09-.23-.48-.94-.82-1.13-.28-.15-.68-.52-.01-.53.63-.01 1.08.58 1.23.82.72 1.21 1.87.87 2.33.66.07-.52.28-.87.51-1.07-1.78-.2-3.64-.89-3.64-3.95 0-.87.31-1.59.82-2.15-.08-.2-.36-1.02.08-2.12 0 0 .67-.21 2.2.82.64-.18 1.32-.27 2-.27.68 0 1.36.09 2 .27 1.53-1.04 2.2-.82 2.2-.82.44 1.1.16 1.92.08 2.12.51.56.82 1.27.82 2.15 0 3.07-1.87 3.75-3.65 3.95.29.25.54.73.54 1.48 0 1.07-.01 1.93-.01 2.2 0 .21.15.46.55.38A8.013 8.013 0 0016 8c0-4.42-3.58-8-8-8z"></path>
@@ -477,12 +1925,107 @@ pub fn LandingPage(
                         <span style:color="var(--text-dark)" style:padding-left="2px" style:padding-right="2px">"/"</span>
                         <span style:color="var(--accent-blue)" style:font-weight="600">"bunnylol.rs"</span>
                     </span>
+                    <span class="visually-hidden">" (source on GitHub, opens in a new tab)"</span>
                 </a>
             </div>
+            </header>
 
-            <div
+            <form
+                action="/"
+                method="get"
+                data-command-form
+                role="search"
+                aria-label="Run a command"
+                style:display="flex"
+                style:gap="10px"
+                style:max-width="700px"
+                style:margin="0 auto 24px auto"
+            >
+                <div style:position="relative" style:flex="1">
+                    <label class="visually-hidden" for="command-input">"Command"</label>
+                    <input
+                        type="search"
+                        id="command-input"
+                        name="cmd"
+                        role="combobox"
+                        aria-describedby="command-input-hint"
+                        data-command-input
+                        autofocus
+                        autocomplete="off"
+                        autocapitalize="off"
+                        spellcheck="false"
+                        aria-autocomplete="list"
+                        aria-controls="command-suggestions"
+                        aria-expanded="false"
+                        placeholder=format!("{}   (press / to focus, ? for shortcuts)", example)
+                        style:width="100%"
+                        style:padding="14px 16px"
+                        style:border="1px solid var(--border-light)"
+                        style:border-radius="999px"
+                        style:font-family="'JetBrains Mono', monospace"
+                        style:font-size="1rem"
+                        style:background="var(--bg-white)"
+                    />
+                    <ul
+                        id="command-suggestions"
+                        class="suggestions"
+                        role="listbox"
+                        aria-label="Command suggestions"
+                        data-suggestions
+                        hidden=true
+                    ></ul>
+                    <span id="command-input-hint" class="visually-hidden">
+                        {format!(
+                            "Type a command such as {}. Press question mark outside the box for keyboard shortcuts.",
+                            example
+                        )}
+                    </span>
+                    <div class="visually-hidden" role="status" aria-live="polite" data-suggestion-status></div>
+                </div>
+                <button
+                    type="submit"
+                    style:border="none"
+                    style:border-radius="999px"
+                    style:padding="12px 22px"
+                    style:font-family="'JetBrains Mono', monospace"
+                    style:font-weight="700"
+                    style:cursor="pointer"
+                    style:background="linear-gradient(135deg, var(--accent-blue) 0%, var(--accent-purple) 100%)"
+                    style:color="white"
+                >
+                    "Go"
+                </button>
+                <button
+                    type="submit"
+                    name="qr"
+                    value="1"
+                    title="Show the resolved URL as a QR code"
+                    aria-label="Show as QR code"
+                    style:border="1px solid var(--border-light)"
+                    style:border-radius="999px"
+                    style:padding="12px 18px"
+                    style:font-family="'JetBrains Mono', monospace"
+                    style:font-weight="700"
+                    style:cursor="pointer"
+                    style:background="var(--bg-white)"
+                    style:color="var(--text-medium)"
+                >
+                    "QR"
+                </button>
+            </form>
+
+            {(!most_used.is_empty()).then(|| view! {
+                <CommandChips label="Most used" kind="most-used" commands=most_used />
+            })}
+            {(!recent_commands.is_empty()).then(|| view! {
+                <CommandChips label="Recent" kind="recent" commands=recent_commands />
+            })}
+
+            <section
+                id="setup-help"
                 data-help-panel
                 class="help-panel"
+                aria-label="Setup help"
                 hidden=true
                 style:background="var(--bg-light-gray)"
                 style:padding="20px"
@@ -539,7 +2082,7 @@ pub fn LandingPage(
                                 style:font-size="0.9em"
                                 style:white-space="nowrap"
                             >
-                                "gh facebook/bunnylol.rs"
+                                {address_bar_example}
                             </code>
                             " in your address bar to get the same result."
                         </p>
@@ -619,9 +2162,11 @@ pub fn LandingPage(
                         </div>
                     </div>
                 </div>
-            </div>
+            </section>
 
-            <div
+            <main id="main">
+            <h2
+                id="shortcuts-heading"
                 style:text-align="center"
                 style:color="var(--text-medium)"
                 style:margin-bottom="20px"
@@ -629,9 +2174,11 @@ pub fn LandingPage(
                 style:font-weight="600"
             >
                 "Available Shortcuts"
-            </div>
+            </h2>
 
             <div
+                role="tablist"
+                aria-labelledby="shortcuts-heading"
                 style:display="flex"
                 style:justify-content="center"
                 style:gap="12px"
@@ -641,6 +2188,10 @@ pub fn LandingPage(
                 <button
                     class=if active_tab == "commands" { "tab-button active" } else { "tab-button" }
                     type="button"
+                    role="tab"
+                    id="tab-commands"
+                    aria-controls="panel-commands"
+                    tabindex=if active_tab == "commands" { "0" } else { "-1" }
                     data-tab-button="commands"
                     aria-selected=if active_tab == "commands" { "true" } else { "false" }
                 >
@@ -649,43 +2200,114 @@ pub fn LandingPage(
                 <button
                     class=if active_tab == "aliases" { "tab-button active" } else { "tab-button" }
                     type="button"
+                    role="tab"
+                    id="tab-aliases"
+                    aria-controls="panel-aliases"
+                    tabindex=if active_tab == "aliases" { "0" } else { "-1" }
                     data-tab-button="aliases"
                     aria-selected=if active_tab == "aliases" { "true" } else { "false" }
                 >
                     {format!("Aliases ({})", alias_count)}
                 </button>
+                <button
+                    class=if active_tab == "playground" { "tab-button active" } else { "tab-button" }
+                    type="button"
+                    role="tab"
+                    id="tab-playground"
+                    aria-controls="panel-playground"
+                    tabindex=if active_tab == "playground" { "0" } else { "-1" }
+                    data-tab-button="playground"
+                    aria-selected=if active_tab == "playground" { "true" } else { "false" }
+                >
+                    "Try it"
+                </button>
+            </div>
+
+            <div
+                data-tab-panel="playground"
+                id="panel-playground"
+                role="tabpanel"
+                aria-labelledby="tab-playground"
+                tabindex="0"
+                class="tab-panel"
+                hidden=active_tab != "playground"
+            >
+                <Playground />
             </div>
 
             <div
                 data-tab-panel="commands"
+                id="panel-commands"
+                role="tabpanel"
+                aria-labelledby="tab-commands"
+                tabindex="0"
                 class="tab-panel"
-                style:display="grid"
-                style:grid-template-columns="repeat(auto-fill, minmax(350px, 1fr))"
-                style:gap="20px"
+                hidden=active_tab != "commands"
                 style:margin-top="30px"
             >
-                <For
-                    each=move || bindings.clone()
-                    key=|binding| binding.command.clone()
-                    children=|binding| view! { <BindingCard binding=binding /> }
-                />
+                <div
+                    data-export-links
+                    role="group"
+                    aria-label="Export command list"
+                    style:display="flex"
+                    style:justify-content="flex-end"
+                    style:gap="10px"
+                    style:font-size="0.9em"
+                    style:color="var(--text-medium)"
+                >
+                    "Export:"
+                    <a href="/bindings/export/json" download>"JSON"</a>
+                    <a href="/bindings/export/csv" download>"CSV"</a>
+                    <a href="/bindings/export/md" download>"Markdown"</a>
+                </div>
+                {binding_groups.into_iter().map(|(category, group)| view! {
+                    <details class="category-group" data-category=category.label().to_lowercase() open=true>
+                        <summary>{format!("{} ({})", category.label(), group.len())}</summary>
+                        <div class="binding-grid">
+                            <For
+                                each=move || group.clone()
+                                key=|binding| binding.command.clone()
+                                children=|binding| view! { <BindingCard binding=binding /> }
+                            />
+                        </div>
+                    </details>
+                }).collect::<Vec<_>>()}
+                {has_page_command_aliases.then(|| view! {
+                    <details class="category-group" data-category="aliases" open=true>
+                        <summary>{format!("Your aliases ({})", alias_count)}</summary>
+                        <div class="binding-grid">
+                            <For
+                                each=move || page_command_aliases.get_value()
+                                key=|alias| alias.alias.clone()
+                                children=|alias| view! { <AliasCard alias=alias /> }
+                            />
+                        </div>
+                    </details>
+                })}
+                <Pager tab="commands" page=commands_page page_count=commands_pages />
             </div>
 
             <div
                 data-tab-panel="aliases"
+                id="panel-aliases"
+                role="tabpanel"
+                aria-labelledby="tab-aliases"
+                tabindex="0"
                 class="tab-panel"
                 hidden=active_tab != "aliases"
             >
                 {alias_notice.map(|notice| {
                     let (background, border, title) = match notice.kind {
-                        AliasNoticeKind::Success => ("#eefbf3", "#8bd8a8", "Saved"),
-                        AliasNoticeKind::Deleted => ("#fff3f1", "#f2b3a8", "Deleted"),
-                        AliasNoticeKind::Error => ("#fff3f1", "#f2b3a8", "Could not save"),
+                        AliasNoticeKind::Success => ("var(--notice-success-bg)", "var(--notice-success-border)", "Saved"),
+                        AliasNoticeKind::Deleted => ("var(--notice-error-bg)", "var(--notice-error-border)", "Deleted"),
+                        AliasNoticeKind::Error => ("var(--notice-error-bg)", "var(--notice-error-border)", "Could not save"),
                     };
+                    let role = if notice.kind == AliasNoticeKind::Error { "alert" } else { "status" };
 
                     view! {
                         <div
                             data-alias-notice
+                            role=role
                             style:background=background
                             style:border={format!("1px solid {}", border)}
                             style:border-radius="10px"
@@ -708,8 +2330,8 @@ pub fn LandingPage(
                 })}
 
                 <div
-                    style:background="linear-gradient(135deg, #fff9e8 0%, #fff4d6 100%)"
-                    style:border="1px solid #ffd8a8"
+                    style:background="linear-gradient(135deg, var(--alias-bg-start) 0%, var(--alias-bg-end) 100%)"
+                    style:border="1px solid var(--alias-border)"
                     style:border-radius="10px"
                     style:padding="22px"
                     style:margin-bottom="22px"
@@ -754,7 +2376,7 @@ pub fn LandingPage(
                                 placeholder="work"
                                 style:width="100%"
                                 style:padding="12px"
-                                style:border="1px solid #e2c790"
+                                style:border="1px solid var(--alias-input-border)"
                                 style:border-radius="8px"
                                 style:font-family="'JetBrains Mono', monospace"
                                 style:background="var(--bg-white)"
@@ -774,10 +2396,10 @@ pub fn LandingPage(
                                 name="target"
                                 required
                                 autocomplete="off"
-                                placeholder="gh facebook/react"
+                                placeholder=example.clone()
                                 style:width="100%"
                                 style:padding="12px"
-                                style:border="1px solid #e2c790"
+                                style:border="1px solid var(--alias-input-border)"
                                 style:border-radius="8px"
                                 style:font-family="'JetBrains Mono', monospace"
                                 style:background="var(--bg-white)"
@@ -804,8 +2426,8 @@ pub fn LandingPage(
                     when=move || has_aliases
                     fallback=|| view! {
                         <div
-                            style:background="linear-gradient(135deg, #fff9e8 0%, #fff4d6 100%)"
-                            style:border="1px solid #ffd8a8"
+                            style:background="linear-gradient(135deg, var(--alias-bg-start) 0%, var(--alias-bg-end) 100%)"
+                            style:border="1px solid var(--alias-border)"
                             style:border-radius="10px"
                             style:padding="28px"
                             style:text-align="center"
@@ -837,8 +2459,64 @@ pub fn LandingPage(
                             children=|alias| view! { <AliasCard alias=alias /> }
                         />
                     </div>
+                    <Pager tab="aliases" page=alias_page page_count=alias_pages />
                 </Show>
             </div>
+            </main>
+        </div>
+        <Cheatsheet />
+    }
+}
+
+/// Keyboard cheatsheet overlay, opened with `?` and closed with `Esc`
+#[component]
+fn Cheatsheet() -> impl IntoView {
+    let mut commands: Vec<BunnylolCommandInfo> =
+        BunnylolCommandRegistry::get_all_commands().clone();
+    commands.sort_by_key(|cmd| cmd.bindings.first().map(|b| b.to_lowercase()));
+
+    view! {
+        <div class="cheatsheet-backdrop" data-cheatsheet hidden=true>
+            <div class="cheatsheet" role="dialog" aria-modal="true" aria-labelledby="cheatsheet-title">
+                <div
+                    style:display="flex"
+                    style:justify-content="space-between"
+                    style:align-items="center"
+                    style:margin-bottom="16px"
+                >
+                    <h2 id="cheatsheet-title" style:font-size="1.2em">"Shortcuts"</h2>
+                    <button type="button" class="help-button" data-cheatsheet-close aria-label="Close shortcuts (Esc)">
+                        "×"
+                    </button>
+                </div>
+                <div class="cheatsheet-keys">
+                    <span><kbd>"/"</kbd>" focus command box"</span>
+                    <span><kbd>"?"</kbd>" toggle this cheatsheet"</span>
+                    <span><kbd>"↑"</kbd><kbd>"↓"</kbd>" move through suggestions"</span>
+                    <span><kbd>"Enter"</kbd>" run command"</span>
+                    <span><kbd>"Esc"</kbd>" clear / close"</span>
+                </div>
+                <table class="cheatsheet-table">
+                    <caption class="visually-hidden">"All command bindings"</caption>
+                    <thead class="visually-hidden">
+                        <tr>
+                            <th scope="col">"Bindings"</th>
+                            <th scope="col">"Description"</th>
+                        </tr>
+                    </thead>
+                    {commands
+                        .into_iter()
+                        .map(|cmd| {
+                            view! {
+                                <tr>
+                                    <td><code>{cmd.bindings.join(", ")}</code></td>
+                                    <td>{cmd.description}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </table>
+            </div>
         </div>
     }
 }
@@ -862,6 +2540,7 @@ mod tests {
                     kind: AliasNoticeKind::Success,
                     message: "Alias saved.".to_string(),
                 }),
+                ..Default::default()
             },
         );
 
@@ -872,4 +2551,342 @@ mod tests {
         assert!(html.contains("/aliases/delete"));
         assert!(html.contains("Alias saved."));
     }
+
+    #[test]
+    fn render_landing_page_links_web_app_manifest() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+
+        assert!(html.contains(r#"rel="manifest" href="/manifest.webmanifest""#));
+        assert!(html.contains("serviceWorker.register('/sw.js')"));
+        assert!(html.contains("data-command-input"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "finance"))]
+    fn render_landing_page_groups_commands_by_category() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh octocat".to_string());
+
+        let html = render_landing_page_html(&config, &LandingPageState::default());
+
+        assert!(html.contains(r#"data-category="dev""#));
+        assert!(html.contains(r#"data-category="finance""#));
+        assert!(html.contains(r#"data-category="aliases""#));
+        assert!(html.contains(r#"href="/bindings/export/csv""#));
+    }
+
+    #[test]
+    fn render_landing_page_shows_recent_commands() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+        assert!(!html.contains("data-command-chips"));
+
+        let page_state =
+            LandingPageState::default().with_recent_commands(vec!["gh facebook/react".to_string()]);
+        let html = render_landing_page_html(&BunnylolConfig::default(), &page_state);
+        assert!(html.contains(r#"data-command-chips="recent""#));
+        assert!(html.contains(r#"href="/?cmd=gh%20facebook/react""#));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn suggestion_entries_rank_by_frecency() {
+        let scores = HashMap::from([("yt".to_string(), 300), ("gh".to_string(), 100)]);
+
+        let entries = suggestion_entries(&BunnylolConfig::default(), &scores);
+        assert_eq!(entries[0].binding, "yt");
+        assert_eq!(entries[1].binding, "gh");
+    }
+
+    #[test]
+    fn render_landing_page_shows_most_used_commands() {
+        let commands = vec![CommandScore {
+            command: "yt cats".to_string(),
+            count: 3,
+            last_used: 0,
+            score: 300,
+        }];
+        let page_state = LandingPageState::default().with_frecency(&commands, &[]);
+
+        let html = render_landing_page_html(&BunnylolConfig::default(), &page_state);
+        assert!(html.contains(r#"data-command-chips="most-used""#));
+        assert!(html.contains("yt cats"));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn render_landing_page_paginates_large_lists() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+        assert!(!html.contains("data-pager"));
+        assert!(html.contains(r#"data-remote="false""#));
+
+        let mut config = BunnylolConfig::default();
+        for i in 0..600 {
+            config
+                .aliases
+                .insert(format!("alias{i:03}"), "gh octocat".to_string());
+        }
+        let pages = page_count(BunnylolCommandRegistry::get_all_commands().len() + 600);
+
+        let html = render_landing_page_html(&config, &LandingPageState::default());
+        assert!(html.contains(r#"data-pager="commands""#));
+        assert!(html.contains(&format!("Page 1 of {pages}")));
+        assert!(html.contains(r#"data-category="dev""#));
+        assert!(!html.contains("alias599"));
+        // Too many entries to embed, so autocomplete asks the commands API
+        assert!(html.contains(r#"data-remote="true""#));
+
+        // Out-of-range pages clamp to the last one, which only holds aliases
+        let html = render_landing_page_html(&config, &LandingPageState::default().with_page(99));
+        assert!(html.contains(&format!("Page {pages} of {pages}")));
+        assert!(!html.contains(r#"data-category="dev""#));
+        assert!(html.contains("alias599"));
+    }
+
+    #[test]
+    fn sparkline_points_scale_to_peak() {
+        assert_eq!(
+            sparkline_points(&[0, 2, 1], 100.0, 10.0),
+            "0.0,9.0 50.0,1.0 100.0,5.0"
+        );
+        assert_eq!(sparkline_points(&[0, 0], 10.0, 10.0), "0.0,9.0 10.0,9.0");
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn render_landing_page_shows_usage_sparklines() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+        assert!(!html.contains("usage-sparkline"));
+
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh octocat".to_string());
+        let mut gh = vec![0; crate::history_stats::SPARKLINE_DAYS];
+        gh[crate::history_stats::SPARKLINE_DAYS - 1] = 2;
+        let mut github = vec![0; crate::history_stats::SPARKLINE_DAYS];
+        github[0] = 1;
+        let usage = HashMap::from([("gh".to_string(), gh), ("github".to_string(), github)]);
+
+        let html = render_landing_page_html(
+            &config,
+            &LandingPageState::default().with_daily_usage(usage),
+        );
+        // Uses of every binding count towards the command's card
+        assert!(html.contains(r#"data-usage-total="3""#));
+        assert!(html.contains("Used 3 times in the last 30 days"));
+        // The unused alias still gets a (flat) sparkline
+        assert!(html.contains("Not used in the last 30 days"));
+    }
+
+    #[test]
+    fn render_landing_page_includes_playground() {
+        let html = render_landing_page_html(
+            &BunnylolConfig::default(),
+            &LandingPageState::new(Some("playground"), None),
+        );
+
+        assert!(html.contains(r#"data-initial-tab="playground""#));
+        assert!(html.contains("data-playground-input"));
+        assert!(html.contains("/api/resolve?cmd="));
+    }
+
+    #[test]
+    fn render_landing_page_is_screen_reader_friendly() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+
+        assert!(html.contains(r##"href="#command-input""##));
+        assert!(html.contains("<header"));
+        assert!(html.contains(r#"<main id="main""#));
+        assert!(html.contains(r#"role="search""#));
+        assert!(html.contains(r#"<label for="command-input""#));
+        assert!(html.contains(r#"role="combobox""#));
+        assert!(html.contains(r#"role="tablist""#));
+        assert!(html.contains(r#"aria-controls="panel-commands""#));
+        assert!(html.contains(r#"aria-labelledby="cheatsheet-title""#));
+        assert!(html.contains("prefers-color-scheme: dark"));
+    }
+
+    #[test]
+    fn render_landing_page_includes_keyboard_cheatsheet() {
+        let html =
+            render_landing_page_html(&BunnylolConfig::default(), &LandingPageState::default());
+
+        assert!(html.contains("data-cheatsheet"));
+        assert!(html.contains(r#"role="listbox""#));
+        assert!(html.contains(r#"id="bunnylol-suggestions""#));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dev", feature = "social"))]
+    fn suggestion_entries_include_bindings_and_aliases() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("work".to_string(), "gh mycompany/repo".to_string());
+
+        let entries = suggestion_entries(&config, &HashMap::new());
+        assert!(entries.iter().any(|e| e.binding == "gh"));
+        assert!(entries.iter().any(|e| e.binding == "instagram"));
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.binding == "work" && e.description == "Alias for gh mycompany/repo")
+        );
+        // Display-only pseudo bindings are not suggested
+        assert!(!entries.iter().any(|e| e.binding == "(default)"));
+        assert!(!entries.iter().any(|e| e.binding == "$<ticker>"));
+    }
+
+    #[test]
+    fn render_qr_page_embeds_svg_and_link() {
+        let html = render_qr_page_html("gh facebook/react", "https://github.com/facebook/react");
+
+        assert!(html.contains("<svg"));
+        assert!(html.contains("data-qr-code"));
+        assert!(html.contains(r#"href="https://github.com/facebook/react""#));
+    }
+
+    #[test]
+    fn render_multi_target_lists_every_url() {
+        let urls = vec![
+            "https://github.com/rust-lang/rust".to_string(),
+            "https://www.youtube.com/results?search_query=cats".to_string(),
+        ];
+
+        let html = render_multi_target_html("gh rust-lang/rust; yt cats", &urls);
+
+        assert!(html.contains("data-open-all"));
+        assert!(html.contains(r#"href="https://github.com/rust-lang/rust""#));
+        assert!(html.contains("search_query=cats"));
+        assert_eq!(html.matches("data-target-url").count(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn render_command_error_shows_message_and_usage() {
+        let info = BunnylolCommandRegistry::get_command_info("gh").unwrap();
+        let html = render_command_error_html(
+            "gh facebook/",
+            "'facebook/' isn't a repository; expected <owner>/<repo>",
+            Some(info),
+            "https://www.google.com/search?q=gh%20facebook%2F",
+        );
+
+        assert!(html.contains("data-command-error"));
+        assert!(html.contains("a repository; expected"));
+        assert!(html.contains("Open a repository"));
+        assert!(html.contains("https://www.google.com/search?q=gh%20facebook%2F"));
+    }
+
+    #[test]
+    fn render_stats_page_lists_top_commands_and_live_feed() {
+        let top = vec![CommandScore {
+            command: "gh facebook/react".to_string(),
+            count: 4,
+            last_used: 0,
+            score: 400,
+        }];
+
+        let html = render_stats_html(&top, true);
+        assert!(html.contains("data-live-feed"));
+        assert!(html.contains("new EventSource('/stats/events')"));
+        assert!(html.contains("gh facebook&#x2F;react"));
+
+        let html = render_stats_html(&[], false);
+        assert!(html.contains("History is disabled"));
+    }
+
+    #[test]
+    fn render_history_search_page_links_to_rerun() {
+        let results = vec![CommandScore {
+            command: "gh facebook/react".to_string(),
+            count: 2,
+            last_used: 1_700_000_000 - 3 * 60 * 60,
+            score: 200,
+        }];
+
+        let html = render_history_search_html("react", &results, 1_700_000_000, true);
+        assert!(html.contains(r#"href="/?cmd=gh%20facebook/react""#));
+        assert!(html.contains("3h ago"));
+        assert!(html.contains(r#"value="react""#));
+
+        let html = render_history_search_html("vue", &[], 1_700_000_000, true);
+        assert!(html.contains("No past commands match"));
+
+        let html = render_history_search_html("", &[], 1_700_000_000, false);
+        assert!(html.contains("History is disabled"));
+    }
+
+    #[test]
+    #[cfg(feature = "finance")]
+    fn render_help_page_shows_usage_config_and_aliases() {
+        let mut config = BunnylolConfig::default();
+        config
+            .aliases
+            .insert("q".to_string(), "stock QQQ".to_string());
+        let info = BunnylolCommandRegistry::get_command_info("stock").expect("stock command");
+
+        let html = render_help_page_html(info, &config);
+
+        assert!(html.contains("<title>help stock · bunnylol</title>"));
+        assert!(html.contains("Usage"));
+        assert!(html.contains("stock_provider"));
+        assert!(html.contains("yahoo"));
+        assert!(html.contains("QQQ"));
+    }
+
+    #[test]
+    #[cfg(feature = "finance")]
+    fn render_help_page_shows_signature_examples_and_tags() {
+        let info = BunnylolCommandRegistry::get_command_info("stock").expect("stock command");
+
+        let html = render_help_page_html(info, &BunnylolConfig::default());
+
+        assert!(html.contains("stock [&lt;provider&gt;] &lt;ticker&gt;"));
+        assert!(html.contains("Examples: "));
+        assert!(html.contains(r#"href="/?cmd=stock%20finviz%20META""#));
+        assert!(html.contains(r#"href="/?cmd=$META""#));
+        assert!(html.contains("Tags: stocks, ticker"));
+    }
+
+    #[test]
+    #[cfg(feature = "dev")]
+    fn render_help_page_lists_subcommands() {
+        let info = BunnylolCommandRegistry::get_command_info("gh").expect("gh command");
+
+        let html = render_help_page_html(info, &BunnylolConfig::default());
+
+        assert!(html.contains("Subcommands"));
+        assert!(html.contains("gh token"));
+        assert!(html.contains("(also: tokens)"));
+    }
+
+    #[test]
+    fn render_did_you_mean_offers_suggestion_and_fallback() {
+        let html = render_did_you_mean_html(
+            "gihub foo",
+            "github foo",
+            "https://www.google.com/search?q=gihub%20foo",
+        );
+
+        assert!(html.contains("Did you mean"));
+        assert!(html.contains(r#"href="/?cmd=github%20foo""#));
+        assert!(html.contains("https://www.google.com/search?q=gihub%20foo"));
+    }
+
+    #[test]
+    fn web_app_manifest_defaults() {
+        let manifest = WebAppManifest::default();
+        assert_eq!(manifest.name, "Bunnylol");
+        assert_eq!(manifest.start_url, "/");
+        assert_eq!(manifest.display, "standalone");
+        assert_eq!(manifest.icons[0].src, "/icon.svg");
+    }
 }
@@ -0,0 +1,179 @@
+//! Shared argument tokenizer for Bunnylol commands
+//!
+//! Splits a command's arguments on whitespace, keeping double-quoted phrases
+//! together (`gm "coffee near me"`). Inside or outside quotes, `\"` is a
+//! literal quote and `\\` a literal backslash; any other backslash is kept
+//! as-is. An unclosed quote runs to the end of the line.
+
+/// Tokens of a command's arguments, consumed front to back
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::args::Args;
+///
+/// let mut args = Args::new(r#"finviz "BRK B" extra words"#);
+/// assert_eq!(args.next().as_deref(), Some("finviz"));
+/// assert_eq!(args.next().as_deref(), Some("BRK B"));
+/// assert_eq!(args.rest(), "extra words");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Args<'a> {
+    remaining: &'a str,
+}
+
+impl<'a> Args<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Args {
+            remaining: input.trim_start(),
+        }
+    }
+
+    /// Everything not yet consumed, verbatim (quotes included), for free-text
+    /// arguments where the target site gives quotes a meaning of its own
+    pub fn rest(&self) -> &'a str {
+        self.remaining
+    }
+
+    /// The remaining tokens joined with single spaces, quotes removed
+    pub fn rest_unquoted(self) -> String {
+        self.collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Iterator for Args<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let input = self.remaining;
+        if input.is_empty() {
+            return None;
+        }
+
+        let mut token = String::new();
+        let mut in_quotes = false;
+        let mut end = input.len();
+        let mut chars = input.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.peek() {
+                    Some(&(_, escaped @ ('"' | '\\'))) => {
+                        token.push(escaped);
+                        chars.next();
+                    }
+                    _ => token.push('\\'),
+                },
+                '"' => in_quotes = !in_quotes,
+                c if c.is_whitespace() && !in_quotes => {
+                    end = i;
+                    break;
+                }
+                c => token.push(c),
+            }
+        }
+
+        self.remaining = input[end..].trim_start();
+        Some(token)
+    }
+}
+
+/// Split `input` into tokens
+pub fn tokenize(input: &str) -> Vec<String> {
+    Args::new(input).collect()
+}
+
+/// The shape of a command's arguments, for commands that route some shapes
+/// specially (e.g. `gh 1234` to an issue)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgKind<'a> {
+    /// No arguments
+    Empty,
+    /// A single whole number, optionally written `#1234`
+    Number(u64),
+    /// Anything else, trimmed
+    Text(&'a str),
+}
+
+impl<'a> ArgKind<'a> {
+    /// Classify a command's arguments (without the command itself)
+    pub fn classify(args: &'a str) -> Self {
+        let args = args.trim();
+        if args.is_empty() {
+            return ArgKind::Empty;
+        }
+        let digits = args.strip_prefix('#').unwrap_or(args);
+        if !digits.is_empty()
+            && digits.bytes().all(|b| b.is_ascii_digit())
+            && let Ok(number) = digits.parse()
+        {
+            return ArgKind::Number(number);
+        }
+        ArgKind::Text(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_whitespace() {
+        assert_eq!(
+            tokenize("  gh   facebook/react "),
+            vec!["gh", "facebook/react"]
+        );
+        assert!(tokenize("").is_empty());
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_quoted_phrase() {
+        assert_eq!(
+            tokenize(r#"gm "coffee near me" now"#),
+            vec!["gm", "coffee near me", "now"]
+        );
+        // Quotes can open mid-token, like a shell
+        assert_eq!(
+            tokenize(r#"title:"hello world""#),
+            vec!["title:hello world"]
+        );
+        assert_eq!(tokenize(r#""" x"#), vec!["", "x"]);
+    }
+
+    #[test]
+    fn test_tokenize_escapes() {
+        assert_eq!(
+            tokenize(r#""say \"hi\"" back\\slash"#),
+            vec![r#"say "hi""#, r"back\slash"]
+        );
+        // Other backslashes are literal
+        assert_eq!(tokenize(r"C:\temp"), vec![r"C:\temp"]);
+    }
+
+    #[test]
+    fn test_tokenize_unclosed_quote() {
+        assert_eq!(tokenize(r#"a "b c"#), vec!["a", "b c"]);
+    }
+
+    #[test]
+    fn test_rest() {
+        let mut args = Args::new(r#"r/rust "async await"  tips"#);
+        assert_eq!(args.next().as_deref(), Some("r/rust"));
+        assert_eq!(args.rest(), r#""async await"  tips"#);
+        assert_eq!(args.rest_unquoted(), "async await tips");
+    }
+
+    #[test]
+    fn test_arg_kind() {
+        assert_eq!(ArgKind::classify("  "), ArgKind::Empty);
+        assert_eq!(ArgKind::classify(" 1234 "), ArgKind::Number(1234));
+        assert_eq!(ArgKind::classify("#567"), ArgKind::Number(567));
+        assert_eq!(ArgKind::classify("12 34"), ArgKind::Text("12 34"));
+        assert_eq!(ArgKind::classify("+12"), ArgKind::Text("+12"));
+        assert_eq!(ArgKind::classify("#"), ArgKind::Text("#"));
+        // Too big for a u64
+        assert_eq!(
+            ArgKind::classify("99999999999999999999"),
+            ArgKind::Text("99999999999999999999")
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Splitting a query into chained commands
+//!
+//! `gh rust-lang/rust; cal tomorrow` (or `... && cal tomorrow`) runs two
+//! commands. A `;` or `&&` only separates commands when the text after it
+//! starts with a command, so arguments like `g c++; java` stay whole. Inside
+//! double quotes neither separates, and `\;` or `\&` is always literal.
+
+/// Split `query` into the commands it chains, each trimmed
+///
+/// `is_command` says whether a token starts a command (a binding, alias, or
+/// prefix). A query without separators comes back as a single command, with
+/// any `\;` or `\&` escapes removed.
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::chain::split_chain;
+///
+/// let is_command = |token: &str| ["gh", "cal", "g"].contains(&token);
+/// assert_eq!(
+///     split_chain("gh rust-lang/rust; cal tomorrow", is_command),
+///     vec!["gh rust-lang/rust", "cal tomorrow"]
+/// );
+/// assert_eq!(split_chain("g c++; java", is_command), vec!["g c++; java"]);
+/// assert_eq!(split_chain(r"g a\; gh", is_command), vec!["g a; gh"]);
+/// ```
+pub fn split_chain(query: &str, is_command: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.peek() {
+                Some(&(_, escaped @ (';' | '&'))) => {
+                    current.push(escaped);
+                    chars.next();
+                }
+                Some(&(_, '"')) => {
+                    // Kept escaped for the command's own tokenizer
+                    current.push_str("\\\"");
+                    chars.next();
+                }
+                _ => current.push('\\'),
+            },
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ';' | '&' if !in_quotes => {
+                let separator_len = if c == ';' {
+                    1
+                } else if query[i..].starts_with("&&") {
+                    2
+                } else {
+                    current.push(c);
+                    continue;
+                };
+                let next = first_token(&query[i + separator_len..]);
+                if !next.is_empty() && is_command(next) {
+                    commands.push(current.trim().to_string());
+                    current.clear();
+                    if separator_len == 2 {
+                        chars.next();
+                    }
+                } else {
+                    current.push(c);
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    commands.push(current.trim().to_string());
+    commands.retain(|command| !command.is_empty());
+    commands
+}
+
+/// The token at the start of `text`, which ends at whitespace or another
+/// separator
+fn first_token(text: &str) -> &str {
+    let text = text.trim_start();
+    let end = text
+        .find(|c: char| c.is_whitespace() || c == ';')
+        .unwrap_or(text.len());
+    let token = &text[..end];
+    token.find("&&").map_or(token, |end| &token[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_command(token: &str) -> bool {
+        ["gh", "cal", "g", "$META"].contains(&token)
+    }
+
+    #[test]
+    fn test_split_chain_semicolon_and_ampersands() {
+        assert_eq!(
+            split_chain("gh rust-lang/rust; cal tomorrow", is_command),
+            vec!["gh rust-lang/rust", "cal tomorrow"]
+        );
+        assert_eq!(
+            split_chain("gh rust && cal&&$META", is_command),
+            vec!["gh rust", "cal", "$META"]
+        );
+    }
+
+    #[test]
+    fn test_split_chain_keeps_semicolons_in_arguments() {
+        // Nothing after the separator is a command
+        assert_eq!(
+            split_chain("g c++; java && kotlin", is_command),
+            vec!["g c++; java && kotlin"]
+        );
+        assert_eq!(split_chain("g a & b", is_command), vec!["g a & b"]);
+        assert_eq!(split_chain("g trailing;", is_command), vec!["g trailing;"]);
+    }
+
+    #[test]
+    fn test_split_chain_escapes_and_quotes() {
+        assert_eq!(split_chain(r"g a\; gh", is_command), vec!["g a; gh"]);
+        assert_eq!(split_chain(r"g a \&& gh", is_command), vec!["g a && gh"]);
+        assert_eq!(
+            split_chain(r#"g "a; gh" ; cal"#, is_command),
+            vec![r#"g "a; gh""#, "cal"]
+        );
+        assert_eq!(
+            split_chain(r#"g \"; cal"#, is_command),
+            vec![r#"g \""#, "cal"]
+        );
+        assert_eq!(split_chain(r"g C:\temp", is_command), vec![r"g C:\temp"]);
+    }
+
+    #[test]
+    fn test_split_chain_drops_empty_commands() {
+        assert_eq!(split_chain("gh;; cal", is_command), vec!["gh;", "cal"]);
+        assert!(split_chain("   ", is_command).is_empty());
+    }
+}
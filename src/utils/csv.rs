@@ -0,0 +1,55 @@
+//! Minimal CSV helpers for the export endpoints and `bunnylol import`
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::csv::csv_field;
+///
+/// assert_eq!(csv_field("gh"), "gh");
+/// assert_eq!(csv_field("a, b"), "\"a, b\"");
+/// ```
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Join fields into one CSV row (without the trailing newline)
+pub fn csv_row<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    fields
+        .into_iter()
+        .map(csv_field)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Split one CSV line into fields, unquoting quoted ones
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::csv::csv_fields;
+///
+/// assert_eq!(csv_fields("gh,\"a, \"\"b\"\"\""), ["gh", "a, \"b\""]);
+/// ```
+pub fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
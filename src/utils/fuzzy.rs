@@ -0,0 +1,376 @@
+//! Fuzzy matching helpers for command resolution
+//!
+//! Used to spot likely typos (e.g. `gihub` → `gh`) before a query silently
+//! falls through to the default search engine.
+
+/// Compute the edit distance between two strings
+///
+/// Counts insertions, deletions, substitutions, and transpositions of adjacent
+/// characters (optimal string alignment), so `wrok` is one edit from `work`.
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::fuzzy::edit_distance;
+///
+/// assert_eq!(edit_distance("gihub", "github"), 1);
+/// assert_eq!(edit_distance("wrok", "work"), 1);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    distance(a, b, true)
+}
+
+/// Levenshtein distance, which unlike `edit_distance` satisfies the triangle
+/// inequality that `FuzzyIndex` relies on; it's at most twice `edit_distance`
+fn levenshtein(a: &str, b: &str) -> usize {
+    distance(a, b, false)
+}
+
+fn distance(a: &str, b: &str, transpositions: bool) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if transpositions && i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+/// Maximum edit distance tolerated for a token of the given length
+///
+/// Short tokens only tolerate a single typo so that e.g. `go` doesn't match `gh`.
+pub fn max_typo_distance(token: &str) -> usize {
+    match token.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Find the closest candidate to `token` within the typo tolerance for its length
+///
+/// Returns `None` for exact matches, since those are not typos.
+/// Ties are broken alphabetically so results are deterministic.
+pub fn closest_match<'a, I>(token: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = max_typo_distance(token);
+    if max_distance == 0 {
+        return None;
+    }
+
+    let mut best: Option<(usize, &'a str)> = None;
+    for candidate in candidates {
+        if candidate == token {
+            return None;
+        }
+        let distance = edit_distance(token, candidate);
+        if distance > max_distance {
+            continue;
+        }
+        best = match best {
+            Some((best_distance, best_candidate))
+                if (best_distance, best_candidate) <= (distance, candidate) =>
+            {
+                Some((best_distance, best_candidate))
+            }
+            _ => Some((distance, candidate)),
+        };
+    }
+
+    best.map(|(_, candidate)| candidate)
+}
+
+/// The nearest candidate to a token, and whether it was the only one that near
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch<'a> {
+    pub candidate: &'a str,
+    pub distance: usize,
+    /// No other candidate is as near
+    pub unambiguous: bool,
+}
+
+/// Shortest token that `FuzzyIndex::best_match` completes as a prefix
+const MIN_PREFIX_LEN: usize = 3;
+
+/// An index of candidates for finding near-misses of a token without
+/// comparing it against every candidate
+///
+/// Candidates are kept in a BK-tree keyed by Levenshtein distance, so a query
+/// only visits subtrees that could hold a match, plus a sorted list for
+/// prefix lookups.
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::fuzzy::FuzzyIndex;
+///
+/// let index = FuzzyIndex::new(["github", "gitlab", "youtube"]);
+/// let best = index.best_match("gihub").unwrap();
+/// assert_eq!((best.candidate, best.distance), ("github", 1));
+/// assert_eq!(index.best_match("youtu").unwrap().candidate, "youtube");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FuzzyIndex {
+    nodes: Vec<BkNode>,
+    sorted: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct BkNode {
+    word: String,
+    /// (Levenshtein distance to `word`, index of the child in `nodes`)
+    children: Vec<(usize, usize)>,
+}
+
+impl FuzzyIndex {
+    pub fn new<I, S>(candidates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut index = FuzzyIndex::default();
+        for candidate in candidates {
+            index.insert(candidate.into());
+        }
+        index
+    }
+
+    pub fn insert(&mut self, word: String) {
+        match self.sorted.binary_search(&word) {
+            Ok(_) => return,
+            Err(position) => self.sorted.insert(position, word.clone()),
+        }
+        if self.nodes.is_empty() {
+            self.nodes.push(BkNode {
+                word,
+                children: Vec::new(),
+            });
+            return;
+        }
+
+        let mut current = 0;
+        loop {
+            let distance = levenshtein(&word, &self.nodes[current].word);
+            match self.nodes[current]
+                .children
+                .iter()
+                .find(|(d, _)| *d == distance)
+            {
+                Some(&(_, child)) => current = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(BkNode {
+                        word,
+                        children: Vec::new(),
+                    });
+                    self.nodes[current].children.push((distance, child));
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.sorted
+            .binary_search_by(|candidate| candidate.as_str().cmp(word))
+            .is_ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Every candidate within `max_distance` edits (as `edit_distance`
+    /// counts them) of `token`, nearest first, ties alphabetical
+    pub fn within(&self, token: &str, max_distance: usize) -> Vec<(usize, &str)> {
+        let mut found = Vec::new();
+        if self.nodes.is_empty() {
+            return found;
+        }
+        // A transposition is two Levenshtein edits, so search twice as wide
+        let radius = max_distance * 2;
+        let mut pending = vec![0];
+        while let Some(current) = pending.pop() {
+            let node = &self.nodes[current];
+            let distance = levenshtein(token, &node.word);
+            if distance <= radius {
+                let edits = edit_distance(token, &node.word);
+                if edits <= max_distance {
+                    found.push((edits, node.word.as_str()));
+                }
+            }
+            pending.extend(
+                node.children
+                    .iter()
+                    .filter(|(d, _)| d.abs_diff(distance) <= radius)
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort();
+        found
+    }
+
+    /// Candidates that start with `prefix`, alphabetically
+    pub fn with_prefix(&self, prefix: &str) -> &[String] {
+        let start = self.sorted.partition_point(|word| word.as_str() < prefix);
+        let len = self.sorted[start..]
+            .iter()
+            .take_while(|word| word.starts_with(prefix))
+            .count();
+        &self.sorted[start..start + len]
+    }
+
+    /// The nearest candidate to `token` within the typo tolerance for its
+    /// length, or else the only candidate it's a prefix of
+    ///
+    /// Returns `None` for exact matches, since those are not typos.
+    pub fn best_match(&self, token: &str) -> Option<FuzzyMatch<'_>> {
+        if self.contains(token) {
+            return None;
+        }
+
+        let matches = self.within(token, max_typo_distance(token));
+        if let Some(&(distance, candidate)) = matches.first() {
+            return Some(FuzzyMatch {
+                candidate,
+                distance,
+                unambiguous: matches.get(1).is_none_or(|&(next, _)| next > distance),
+            });
+        }
+
+        if token.chars().count() < MIN_PREFIX_LEN {
+            return None;
+        }
+        match self.with_prefix(token) {
+            [only] => Some(FuzzyMatch {
+                candidate: only,
+                distance: only.chars().count() - token.chars().count(),
+                unambiguous: true,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("gh", "gh"), 0);
+        assert_eq!(edit_distance("gihub", "github"), 1);
+        assert_eq!(edit_distance("yuotube", "youtube"), 1);
+        assert_eq!(edit_distance("wrok", "work"), 1);
+        assert_eq!(edit_distance("abc", ""), 3);
+    }
+
+    #[test]
+    fn test_closest_match_finds_typo() {
+        let candidates = ["github", "gitlab", "gmail"];
+        assert_eq!(closest_match("gihub", candidates), Some("github"));
+        assert_eq!(closest_match("gitlba", candidates), Some("gitlab"));
+    }
+
+    #[test]
+    fn test_closest_match_ignores_exact_and_distant() {
+        let candidates = ["github", "gitlab"];
+        assert_eq!(closest_match("github", candidates), None);
+        assert_eq!(closest_match("weather", candidates), None);
+    }
+
+    #[test]
+    fn test_closest_match_short_tokens_need_exact_match() {
+        assert_eq!(closest_match("gx", ["gh"]), None);
+    }
+
+    #[test]
+    fn test_closest_match_ties_are_alphabetical() {
+        assert_eq!(closest_match("npx", ["npm", "npx2", "npn"]), Some("npm"));
+    }
+
+    #[test]
+    fn test_fuzzy_index_within_matches_linear_scan() {
+        let words = [
+            "github", "gitlab", "gmail", "gh", "go", "gopkg", "godocs", "google", "npm", "npx",
+            "nuget", "node", "ca", "ac", "abc",
+        ];
+        let index = FuzzyIndex::new(words);
+        assert_eq!(index.len(), words.len());
+        for token in ["gihub", "gitlba", "gogle", "np", "nod", "ca", "abc", "xyz"] {
+            for max_distance in 0..=2 {
+                let mut expected: Vec<(usize, &str)> = words
+                    .iter()
+                    .map(|&word| (edit_distance(token, word), word))
+                    .filter(|&(distance, _)| distance <= max_distance)
+                    .collect();
+                expected.sort();
+                assert_eq!(index.within(token, max_distance), expected, "{}", token);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_index_best_match() {
+        let index = FuzzyIndex::new(["github", "gitlab", "npm", "npx", "youtube"]);
+        assert_eq!(
+            index.best_match("gihub"),
+            Some(FuzzyMatch {
+                candidate: "github",
+                distance: 1,
+                unambiguous: true
+            })
+        );
+        // "npn" is one edit from both npm and npx
+        assert_eq!(
+            index.best_match("npn"),
+            Some(FuzzyMatch {
+                candidate: "npm",
+                distance: 1,
+                unambiguous: false
+            })
+        );
+        assert_eq!(index.best_match("github"), None);
+        assert_eq!(index.best_match("weather"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_index_prefix_completion() {
+        let index = FuzzyIndex::new(["github", "gitlab", "youtube"]);
+        assert_eq!(
+            index.best_match("youtu"),
+            Some(FuzzyMatch {
+                candidate: "youtube",
+                distance: 2,
+                unambiguous: true
+            })
+        );
+        // Ambiguous and too-short prefixes aren't completed
+        assert_eq!(index.best_match("git"), None);
+        assert_eq!(index.best_match("yo"), None);
+        assert_eq!(index.with_prefix("git"), ["github", "gitlab"]);
+    }
+}
@@ -1,3 +1,9 @@
+pub mod args;
+pub mod chain;
+pub mod csv;
+pub mod date;
+pub mod fuzzy;
+pub mod url_builder;
 pub mod url_encoding;
 
 pub fn get_command_from_query_string(query_string: &str) -> &str {
@@ -0,0 +1,221 @@
+/// Typed URL construction for Bunnylol commands
+///
+/// Commands describe a URL as a base plus path segments, query pairs, and a
+/// fragment, and `UrlBuilder` encodes each part for where it goes, so user
+/// input containing spaces, `#`, `&`, `%`, or unicode can't break the URL.
+/// The result is parsed and serialized by the `url` crate.
+use percent_encoding::utf8_percent_encode;
+use url::{Position, Url};
+
+use crate::utils::url_encoding::{COMPONENT as QUERY, PATH_SEGMENT as SEGMENT};
+
+/// Origin used to resolve relative bases like `/help`; never part of the output
+const RELATIVE_ORIGIN: &str = "http://relative.invalid";
+
+/// Builds a URL from a base plus encoded path, query, and fragment parts
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::url_builder::UrlBuilder;
+///
+/// let url = UrlBuilder::new("https://github.com")
+///     .path("facebook/react")
+///     .segment("issues")
+///     .query("q", "is:open #1 & more")
+///     .build();
+/// assert_eq!(
+///     url,
+///     "https://github.com/facebook/react/issues?q=is:open%20%231%20%26%20more"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct UrlBuilder {
+    url: Url,
+    relative: bool,
+    path: Option<String>,
+    query: Vec<String>,
+    fragment: Option<String>,
+}
+
+impl UrlBuilder {
+    /// Start from `base`, an absolute URL or a path like `/help`
+    ///
+    /// Bases are literals in the commands, so an invalid one is a bug and panics.
+    pub fn new(base: &str) -> Self {
+        let relative = base.starts_with('/');
+        let url = if relative {
+            Url::parse(RELATIVE_ORIGIN).and_then(|origin| origin.join(base))
+        } else {
+            Url::parse(base)
+        }
+        .unwrap_or_else(|e| panic!("invalid base URL '{}': {}", base, e));
+        UrlBuilder {
+            url,
+            relative,
+            path: None,
+            query: Vec::new(),
+            fragment: None,
+        }
+    }
+
+    /// Append a `/`-separated path, encoding each segment
+    pub fn path(mut self, path: &str) -> Self {
+        for segment in path.split('/') {
+            self = self.segment(segment);
+        }
+        self
+    }
+
+    /// Append one path segment, encoding any `/` in it
+    pub fn segment(mut self, segment: &str) -> Self {
+        let path = self
+            .path
+            .get_or_insert_with(|| self.url.path().trim_end_matches('/').to_string());
+        path.push('/');
+        path.extend(utf8_percent_encode(segment, SEGMENT));
+        self
+    }
+
+    /// Append a `key=value` query pair
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push(format!(
+            "{}={}",
+            utf8_percent_encode(key, QUERY),
+            utf8_percent_encode(value, QUERY)
+        ));
+        self
+    }
+
+    /// Set the `#fragment`
+    pub fn fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(utf8_percent_encode(fragment, QUERY).to_string());
+        self
+    }
+
+    /// Set the `#fragment` to a single-page app route ending in `value`, like
+    /// `/stacks?filteringText=` and a stack name; only `value` is encoded, so
+    /// the route keeps its `?` and `=`
+    pub fn fragment_with(mut self, route: &str, value: &str) -> Self {
+        self.fragment = Some(format!("{}{}", route, utf8_percent_encode(value, QUERY)));
+        self
+    }
+
+    /// Serialize the URL
+    pub fn build(mut self) -> String {
+        if let Some(path) = &self.path {
+            self.url.set_path(path);
+        }
+        if !self.query.is_empty() {
+            let query = self
+                .url
+                .query()
+                .filter(|query| !query.is_empty())
+                .into_iter()
+                .map(str::to_string)
+                .chain(self.query)
+                .collect::<Vec<_>>()
+                .join("&");
+            self.url.set_query(Some(&query));
+        }
+        if let Some(fragment) = &self.fragment {
+            self.url.set_fragment(Some(fragment));
+        }
+        if self.relative {
+            self.url[Position::BeforePath..].to_string()
+        } else {
+            self.url.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_keeps_slashes_between_segments() {
+        assert_eq!(
+            UrlBuilder::new("https://github.com")
+                .path("facebook/react")
+                .build(),
+            "https://github.com/facebook/react"
+        );
+        assert_eq!(
+            UrlBuilder::new("https://github.com/").path("a b").build(),
+            "https://github.com/a%20b"
+        );
+    }
+
+    #[test]
+    fn test_segment_encodes_delimiters() {
+        assert_eq!(
+            UrlBuilder::new("https://example.com/wiki")
+                .segment("AC/DC? #1 100%")
+                .build(),
+            "https://example.com/wiki/AC%2FDC%3F%20%231%20100%25"
+        );
+    }
+
+    #[test]
+    fn test_query_pairs() {
+        assert_eq!(
+            UrlBuilder::new("https://google.com/search")
+                .query("q", "Peak Sports & Spine = 100% #1")
+                .build(),
+            "https://google.com/search?q=Peak%20Sports%20%26%20Spine%20%3D%20100%25%20%231"
+        );
+        assert_eq!(
+            UrlBuilder::new("https://github.com/search")
+                .query("q", "")
+                .query("type", "repositories")
+                .build(),
+            "https://github.com/search?q=&type=repositories"
+        );
+    }
+
+    #[test]
+    fn test_query_appends_to_base_query() {
+        assert_eq!(
+            UrlBuilder::new("https://example.com/?lang=en")
+                .query("q", "x")
+                .build(),
+            "https://example.com/?lang=en&q=x"
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        assert_eq!(
+            UrlBuilder::new("https://en.wikipedia.org/wiki")
+                .segment("Zürich")
+                .query("q", "東京")
+                .build(),
+            "https://en.wikipedia.org/wiki/Z%C3%BCrich?q=%E6%9D%B1%E4%BA%AC"
+        );
+    }
+
+    #[test]
+    fn test_fragment() {
+        assert_eq!(
+            UrlBuilder::new("https://doc.rust-lang.org/std/vec/struct.Vec.html")
+                .fragment("method.push")
+                .build(),
+            "https://doc.rust-lang.org/std/vec/struct.Vec.html#method.push"
+        );
+        assert_eq!(
+            UrlBuilder::new("https://console.aws.amazon.com/dynamodbv2/home")
+                .fragment_with("table?name=", "a&b=c")
+                .build(),
+            "https://console.aws.amazon.com/dynamodbv2/home#table?name=a%26b%3Dc"
+        );
+    }
+
+    #[test]
+    fn test_relative_base() {
+        assert_eq!(UrlBuilder::new("/help").segment("gh").build(), "/help/gh");
+        assert_eq!(
+            UrlBuilder::new("/").query("cmd", "gh rust").build(),
+            "/?cmd=gh%20rust"
+        );
+    }
+}
@@ -2,10 +2,18 @@
 ///
 /// This module provides common URL encoding functionality to eliminate
 /// duplication across different command implementations.
+///
+/// Every piece of user input that ends up in a URL is encoded with the sets
+/// below: spaces become `%20` (never `+`), and `+`, `%`, `&`, `=`, `#`, and
+/// non-ASCII characters are always escaped, so input is never double-encoded
+/// or left raw. Incoming `?cmd=` values are form-decoded, so a `+` there is a
+/// space and a literal plus arrives as `%2B`.
 extern crate percent_encoding;
 
 use percent_encoding::{AsciiSet, CONTROLS, NON_ALPHANUMERIC, utf8_percent_encode};
 
+use crate::utils::url_builder::UrlBuilder;
+
 /// URL fragment encoding set used for percent encoding
 /// Used as part of the percent_encoding library to safely encode URLs
 pub const FRAGMENT: &AsciiSet = &CONTROLS
@@ -19,6 +27,17 @@ pub const FRAGMENT: &AsciiSet = &CONTROLS
     .add(b'+')
     .add(b'#');
 
+/// Characters encoded in query keys and values, fragments, and template
+/// placeholders: the fragment set plus `%`, so input can't form a bogus escape
+pub const COMPONENT: &AsciiSet = &FRAGMENT.add(b'%');
+
+/// Characters encoded in a single path segment: the component set plus the
+/// characters that would end the segment or the path
+pub const PATH_SEGMENT: &AsciiSet = &COMPONENT.add(b'/').add(b'?');
+
+/// Where the value goes in a user-configured URL template
+pub const TEMPLATE_PLACEHOLDER: &str = "{}";
+
 /// Encode a string for safe use in URLs
 ///
 /// # Arguments
@@ -35,7 +54,40 @@ pub const FRAGMENT: &AsciiSet = &CONTROLS
 /// assert_eq!(encoded, "hello%20world");
 /// ```
 pub fn encode_url(input: &str) -> String {
-    utf8_percent_encode(input, FRAGMENT).to_string()
+    utf8_percent_encode(input, COMPONENT).to_string()
+}
+
+/// Fill a configured URL template's `{}` placeholders with encoded input
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::url_encoding::expand_template;
+///
+/// let url = expand_template("https://jira.example.com/browse/{}", "PROJ 1");
+/// assert_eq!(url, "https://jira.example.com/browse/PROJ%201");
+/// ```
+pub fn expand_template(template: &str, input: &str) -> String {
+    template.replace(TEMPLATE_PLACEHOLDER, &encode_url(input))
+}
+
+/// A site's base URL from the config, without a trailing slash, if it's an
+/// absolute http(s) URL that `UrlBuilder` can start from
+///
+/// # Example
+/// ```
+/// use bunnylol::utils::url_encoding::configured_base_url;
+///
+/// assert_eq!(
+///     configured_base_url("https://gitlab.example.com/"),
+///     Some("https://gitlab.example.com")
+/// );
+/// assert_eq!(configured_base_url("gitlab.example.com"), None);
+/// ```
+pub fn configured_base_url(url: &str) -> Option<&str> {
+    let url = url.trim().trim_end_matches('/');
+    url::Url::parse(url)
+        .is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https") && parsed.has_host())
+        .then_some(url)
 }
 
 /// Encode a string strictly for safe use in URLs (encodes all non-alphanumeric characters)
@@ -76,8 +128,9 @@ pub fn encode_url_special_char(input: &str) -> String {
 /// assert_eq!(url, "https://google.com/search?q=hello%20world");
 /// ```
 pub fn build_search_url(base_url: &str, query_param: &str, query_value: &str) -> String {
-    let encoded_query = encode_url(query_value);
-    format!("{}?{}={}", base_url, query_param, encoded_query)
+    UrlBuilder::new(base_url)
+        .query(query_param, query_value)
+        .build()
 }
 
 /// Build a simple path URL with proper encoding
@@ -97,8 +150,7 @@ pub fn build_search_url(base_url: &str, query_param: &str, query_value: &str) ->
 /// assert_eq!(url, "https://github.com/facebook/react");
 /// ```
 pub fn build_path_url(base_url: &str, path: &str) -> String {
-    let encoded_path = encode_url(path);
-    format!("{}/{}", base_url.trim_end_matches('/'), encoded_path)
+    UrlBuilder::new(base_url).path(path).build()
 }
 
 #[cfg(test)]
@@ -161,6 +213,25 @@ mod tests {
         assert_eq!(encode_url("a+b"), "a%2Bb");
     }
 
+    #[test]
+    fn test_encode_url_with_percent_and_unicode() {
+        assert_eq!(encode_url("100%"), "100%25");
+        assert_eq!(encode_url("%41"), "%2541");
+        assert_eq!(encode_url("café"), "caf%C3%A9");
+    }
+
+    #[test]
+    fn test_expand_template() {
+        assert_eq!(
+            expand_template("https://example.com/{}?q={}", "a+b & c"),
+            "https://example.com/a%2Bb%20%26%20c?q=a%2Bb%20%26%20c"
+        );
+        assert_eq!(
+            expand_template("https://example.com/", "x"),
+            "https://example.com/"
+        );
+    }
+
     #[test]
     fn test_build_search_url_with_ampersand() {
         let url = build_search_url(
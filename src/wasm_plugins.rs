@@ -0,0 +1,459 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Commands compiled to WebAssembly, loaded from the `.wasm` files in
+//! `[plugins] dir`
+//!
+//! Unlike shared-library plugins (`crate::plugins`), these run sandboxed: a
+//! module gets no imports, so it can't touch the filesystem, network, or
+//! clock, and each call is capped in memory and fuel (instructions). That
+//! makes them safe for third-party command packs. The directory is rescanned
+//! every few seconds, so adding, replacing, or removing a `.wasm` file takes
+//! effect without a restart.
+//!
+//! A plugin module exports:
+//!
+//! - `memory`
+//! - `bunnylol_alloc(len: i32) -> i32`: room for `len` bytes of arguments
+//! - `bunnylol_info() -> i64`: a string of four lines: name, description,
+//!   example, and space-separated bindings
+//! - `bunnylol_resolve(ptr: i32, len: i32) -> i64`: the URL for the full
+//!   query at `ptr` (e.g. "jira PROJ-123"), or 0 to reject the arguments
+//!
+//! Strings are UTF-8 and returned packed as `ptr << 32 | len`. Every call
+//! runs in a fresh instance, so plugins are stateless.
+
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use wasmtime::{Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::bunnylol_command_registry::BunnylolCommandRegistry;
+use crate::commands::bunnylol_command::{BunnylolCommandInfo, CommandCategory, CommandError};
+
+/// Most memory a plugin call may use
+pub const MAX_MEMORY_BYTES: usize = 16 << 20;
+
+/// Fuel (roughly, wasm instructions) a plugin call may use before it's stopped
+pub const FUEL_PER_CALL: u64 = 10_000_000;
+
+/// Longest string a plugin may return
+pub const MAX_STRING_BYTES: usize = 64 << 10;
+
+/// How often the plugin directory is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A command provided by a WebAssembly module
+pub struct WasmPlugin {
+    pub name: String,
+    info: BunnylolCommandInfo,
+    module: Module,
+}
+
+impl WasmPlugin {
+    /// Compile a plugin module and read its name, description, and bindings
+    pub fn new(engine: &Engine, wasm: &[u8]) -> Result<Self, String> {
+        let module = Module::new(engine, wasm).map_err(|e| e.to_string())?;
+        let info = call(&module, |store, instance| {
+            let info = instance.get_typed_func::<(), i64>(&mut *store, "bunnylol_info")?;
+            let packed = info.call(&mut *store, ())?;
+            read_string(store, instance, packed)
+        })?
+        .ok_or("bunnylol_info returned nothing")?;
+
+        let mut lines = info.lines().map(str::trim);
+        let mut line = || lines.next().unwrap_or_default();
+        let (name, description, example) = (line(), line(), line());
+        let bindings: Vec<&str> = line().split_whitespace().collect();
+        if name.is_empty() || bindings.is_empty() {
+            return Err("bunnylol_info needs a name and at least one binding".to_string());
+        }
+        Ok(WasmPlugin {
+            name: name.to_string(),
+            info: BunnylolCommandInfo::new(&bindings, description, example)
+                .with_category(CommandCategory::Other),
+            module,
+        })
+    }
+
+    pub fn bindings(&self) -> &[String] {
+        &self.info.bindings
+    }
+
+    pub fn info(&self) -> &BunnylolCommandInfo {
+        &self.info
+    }
+
+    /// Ask the plugin for the URL of a full query, e.g. "jira PROJ-123"
+    pub fn resolve(&self, args: &str) -> Result<String, CommandError> {
+        let url = call(&self.module, |store, instance| {
+            let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "bunnylol_alloc")?;
+            let resolve =
+                instance.get_typed_func::<(i32, i32), i64>(&mut *store, "bunnylol_resolve")?;
+            let len = i32::try_from(args.len())?;
+            let ptr = alloc.call(&mut *store, len)?;
+            memory(store, instance)?.write(&mut *store, ptr as u32 as usize, args.as_bytes())?;
+            let packed = resolve.call(&mut *store, (ptr, len))?;
+            read_string(store, instance, packed)
+        });
+        match url {
+            Ok(Some(url)) => Ok(url),
+            Ok(None) => Err(CommandError::InvalidArguments(format!(
+                "the {} plugin couldn't handle '{}'",
+                self.name, args
+            ))),
+            Err(e) => Err(CommandError::InvalidArguments(format!(
+                "the {} plugin failed: {}",
+                self.name, e
+            ))),
+        }
+    }
+}
+
+/// An engine that meters fuel, for `WasmPlugin::new`
+pub fn engine() -> Engine {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("fuel metering is always supported")
+}
+
+/// Run `f` against a fresh, sandboxed instance of `module`
+fn call<T>(
+    module: &Module,
+    f: impl FnOnce(&mut Store<StoreLimits>, &Instance) -> wasmtime::Result<T>,
+) -> Result<T, String> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .instances(1)
+        .build();
+    let mut store = Store::new(module.engine(), limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+    // An empty linker: a module importing anything fails to instantiate
+    let instance = Linker::new(module.engine())
+        .instantiate(&mut store, module)
+        .map_err(|e| e.to_string())?;
+    f(&mut store, &instance).map_err(|e| format!("{:#}", e))
+}
+
+fn memory(
+    store: &mut Store<StoreLimits>,
+    instance: &Instance,
+) -> wasmtime::Result<wasmtime::Memory> {
+    instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| wasmtime::Error::msg("plugin exports no memory"))
+}
+
+/// The string a plugin returned as `ptr << 32 | len`, or `None` for 0; the
+/// length is checked against the plugin's memory before anything is
+/// allocated for it
+fn read_string(
+    store: &mut Store<StoreLimits>,
+    instance: &Instance,
+    packed: i64,
+) -> wasmtime::Result<Option<String>> {
+    if packed == 0 {
+        return Ok(None);
+    }
+    let (ptr, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+    let memory = memory(store, instance)?;
+    let available = memory.data_size(&*store).saturating_sub(ptr);
+    if len > available.min(MAX_STRING_BYTES) {
+        return Err(wasmtime::Error::msg(format!(
+            "returned a {} byte string, past the end of its memory or the {} byte limit",
+            len, MAX_STRING_BYTES
+        )));
+    }
+    let mut bytes = vec![0; len];
+    memory.read(&*store, ptr, &mut bytes)?;
+    Ok(Some(String::from_utf8(bytes)?))
+}
+
+/// The plugins in a directory, reloaded when its `.wasm` files change
+pub struct WasmPlugins {
+    dir: PathBuf,
+    engine: Engine,
+    state: RwLock<State>,
+}
+
+#[derive(Default)]
+struct State {
+    checked: Option<Instant>,
+    /// Each `.wasm` file and when it was last modified, as of the last load
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    plugins: Vec<WasmPlugin>,
+}
+
+impl WasmPlugins {
+    pub fn new(dir: &Path) -> Self {
+        WasmPlugins {
+            dir: dir.to_path_buf(),
+            engine: engine(),
+            state: RwLock::new(State::default()),
+        }
+    }
+
+    /// Load the directory's plugins again if any `.wasm` file was added,
+    /// removed, or modified since the last load, returning why any plugin
+    /// was skipped
+    ///
+    /// Modules are compiled without holding the lock, so commands keep
+    /// resolving against the old plugins until the new ones are swapped in.
+    pub fn reload(&self) -> Vec<String> {
+        let files = self.scan();
+        {
+            let mut state = self.state.write().unwrap();
+            let loaded = state.checked.replace(Instant::now()).is_some();
+            if loaded && state.files == files {
+                return Vec::new();
+            }
+            // Claim this set of files so a concurrent reload doesn't compile
+            // them again
+            state.files = files.clone();
+        }
+
+        let (plugins, errors) = self.load(&files);
+        let mut state = self.state.write().unwrap();
+        // A reload that started later has already swapped in newer files
+        if state.files == files {
+            state.plugins = plugins;
+        }
+        errors
+    }
+
+    /// Compile the plugins in `files`, returning them and why any plugin was
+    /// skipped
+    fn load(&self, files: &[(PathBuf, Option<SystemTime>)]) -> (Vec<WasmPlugin>, Vec<String>) {
+        let mut plugins: Vec<WasmPlugin> = Vec::new();
+        let mut errors = Vec::new();
+        for (path, _) in files {
+            let plugin = std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|wasm| WasmPlugin::new(&self.engine, &wasm))
+                .and_then(|plugin| {
+                    let taken: Vec<&str> = BunnylolCommandRegistry::extension_bindings()
+                        .into_iter()
+                        .chain(
+                            plugins
+                                .iter()
+                                .flat_map(|p| p.bindings().iter().map(String::as_str)),
+                        )
+                        .collect();
+                    BunnylolCommandRegistry::check_plugin_bindings(
+                        &plugin.name,
+                        plugin.bindings(),
+                        &taken,
+                    )?;
+                    Ok(plugin)
+                });
+            match plugin {
+                Ok(plugin) => plugins.push(plugin),
+                Err(e) => errors.push(format!("{:?}: {}", path, e)),
+            }
+        }
+        (plugins, errors)
+    }
+
+    /// Reload if the last check was long enough ago, warning about skipped
+    /// plugins on stderr
+    fn refresh(&self) {
+        let due = self
+            .state
+            .read()
+            .unwrap()
+            .checked
+            .is_none_or(|checked| checked.elapsed() >= RELOAD_INTERVAL);
+        if due {
+            for error in self.reload() {
+                eprintln!("Warning: skipping plugin: {}", error);
+            }
+        }
+    }
+
+    /// Every `.wasm` file in the directory with its modification time
+    fn scan(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "wasm"))
+            .map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).ok();
+                (entry.path(), modified)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Run the plugin bound to `command`, if any
+    pub fn resolve(&self, command: &str, full_args: &str) -> Option<Result<String, CommandError>> {
+        self.refresh();
+        let state = self.state.read().unwrap();
+        state
+            .plugins
+            .iter()
+            .find(|plugin| plugin.bindings().iter().any(|b| b == command))
+            .map(|plugin| plugin.resolve(full_args))
+    }
+
+    pub fn has_binding(&self, command: &str) -> bool {
+        self.refresh();
+        let state = self.state.read().unwrap();
+        state
+            .plugins
+            .iter()
+            .any(|plugin| plugin.bindings().iter().any(|b| b == command))
+    }
+
+    /// The info of every plugin currently loaded
+    pub fn infos(&self) -> Vec<BunnylolCommandInfo> {
+        self.refresh();
+        let state = self.state.read().unwrap();
+        state
+            .plugins
+            .iter()
+            .map(|plugin| plugin.info().clone())
+            .collect()
+    }
+}
+
+static WASM_PLUGINS: OnceLock<WasmPlugins> = OnceLock::new();
+
+/// Load the plugins in `dir` and keep watching it, returning why any plugin
+/// was skipped; only the first call has any effect
+pub fn watch(dir: &Path) -> Vec<String> {
+    if WASM_PLUGINS.get().is_some() {
+        return Vec::new();
+    }
+    WASM_PLUGINS.get_or_init(|| WasmPlugins::new(dir)).reload()
+}
+
+/// The plugins being watched, if `watch` was called
+pub fn watched() -> Option<&'static WasmPlugins> {
+    WASM_PLUGINS.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A plugin whose URL is the query with its binding stripped
+    fn echo_plugin(binding: &str) -> Vec<u8> {
+        let info = format!(
+            "echo\nOpen the URL given\n{} https://example.com\n{}",
+            binding, binding
+        );
+        let (data, info_len) = (info.replace('\n', "\\n"), info.len());
+        let skip = binding.len() + 1;
+        wat::parse_str(format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{data}")
+                (global $next (mut i32) (i32.const 1024))
+                (func (export "bunnylol_alloc") (param $len i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (global.get $next))
+                    (global.set $next (i32.add (global.get $next) (local.get $len)))
+                    (local.get $ptr))
+                (func (export "bunnylol_info") (result i64)
+                    (i64.const {info_len}))
+                (func (export "bunnylol_resolve") (param $ptr i32) (param $len i32) (result i64)
+                    (if (result i64) (i32.le_u (local.get $len) (i32.const {skip}))
+                        (then (i64.const 0))
+                        (else (i64.or
+                            (i64.shl
+                                (i64.extend_i32_u (i32.add (local.get $ptr) (i32.const {skip})))
+                                (i64.const 32))
+                            (i64.extend_i32_u (i32.sub (local.get $len) (i32.const {skip}))))))))"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plugin_info_and_resolve() {
+        let plugin = WasmPlugin::new(&engine(), &echo_plugin("wecho")).unwrap();
+        assert_eq!(plugin.name, "echo");
+        assert_eq!(plugin.bindings(), ["wecho"]);
+        assert_eq!(plugin.info().example, "wecho https://example.com");
+        assert_eq!(
+            plugin.resolve("wecho https://example.com/a").as_deref(),
+            Ok("https://example.com/a")
+        );
+        assert!(plugin.resolve("wecho").is_err());
+    }
+
+    #[test]
+    fn test_plugin_cannot_import() {
+        let wasm = wat::parse_str(
+            r#"(module (import "wasi_snapshot_preview1" "fd_write"
+                (func (param i32 i32 i32 i32) (result i32))))"#,
+        )
+        .unwrap();
+        assert!(WasmPlugin::new(&engine(), &wasm).is_err());
+    }
+
+    #[test]
+    fn test_plugin_runs_out_of_fuel() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "bunnylol_info") (result i64)
+                    (loop $forever (br $forever))
+                    (i64.const 0)))"#,
+        )
+        .unwrap();
+        let error = WasmPlugin::new(&engine(), &wasm).err().unwrap();
+        assert!(error.contains("fuel"), "{}", error);
+    }
+
+    #[test]
+    fn test_plugin_string_must_fit_in_memory() {
+        // Claims a 4 GiB string at address 0 of a one-page memory
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "bunnylol_info") (result i64)
+                    (i64.const 0xffffffff)))"#,
+        )
+        .unwrap();
+        let error = WasmPlugin::new(&engine(), &wasm).err().unwrap();
+        assert!(error.contains("past the end of its memory"), "{}", error);
+    }
+
+    #[test]
+    fn test_reload_picks_up_changes() {
+        let dir =
+            std::env::temp_dir().join(format!("bunnylol-wasm-plugins-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.wasm"), echo_plugin("wecho")).unwrap();
+        std::fs::write(dir.join("b.wasm"), echo_plugin("gh")).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a plugin").unwrap();
+
+        let plugins = WasmPlugins::new(&dir);
+        let errors = plugins.reload();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("'gh' is already taken"), "{}", errors[0]);
+        assert!(plugins.has_binding("wecho"));
+
+        std::fs::write(dir.join("c.wasm"), echo_plugin("wecho2")).unwrap();
+        assert!(plugins.reload().len() == 1);
+        assert_eq!(
+            plugins.resolve("wecho2", "wecho2 https://example.com"),
+            Some(Ok("https://example.com".to_string()))
+        );
+
+        std::fs::remove_file(dir.join("a.wasm")).unwrap();
+        plugins.reload();
+        assert!(!plugins.has_binding("wecho"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
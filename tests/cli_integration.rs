@@ -19,7 +19,7 @@ fn test_cli_version() {
 }
 
 #[test]
-#[cfg(feature = "cli")]
+#[cfg(all(feature = "cli", feature = "dev", feature = "social"))]
 fn test_cli_list_commands_flag() {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
     cmd.arg("--list")
@@ -32,7 +32,7 @@ fn test_cli_list_commands_flag() {
 }
 
 #[test]
-#[cfg(feature = "cli")]
+#[cfg(all(feature = "cli", feature = "dev", feature = "social"))]
 fn test_cli_list_commands_as_command() {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
     cmd.arg("list")
@@ -45,7 +45,33 @@ fn test_cli_list_commands_as_command() {
 }
 
 #[test]
-#[cfg(feature = "cli")]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_picker() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["list", "--picker"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\ngh\t"))
+        .stdout(predicate::str::contains("Command").not());
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "--from-picker"])
+        .write_stdin("gh\tSearch GitHub\n")
+        .assert()
+        .success()
+        .stdout("https://github.com\n");
+
+    // Nothing picked, nothing opened
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "--from-picker"])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
 fn test_cli_dry_run_github() {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
     cmd.arg("--dry-run")
@@ -56,7 +82,7 @@ fn test_cli_dry_run_github() {
 }
 
 #[test]
-#[cfg(feature = "cli")]
+#[cfg(all(feature = "cli", feature = "social"))]
 fn test_cli_dry_run_instagram_reels() {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
     cmd.arg("--dry-run")
@@ -68,7 +94,7 @@ fn test_cli_dry_run_instagram_reels() {
 }
 
 #[test]
-#[cfg(feature = "cli")]
+#[cfg(all(feature = "cli", feature = "dev"))]
 fn test_cli_dry_run_github_repo() {
     let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
     cmd.arg("--dry-run")
@@ -78,3 +104,577 @@ fn test_cli_dry_run_github_repo() {
         .success()
         .stdout("https://github.com/facebook/react\n");
 }
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev", feature = "social"))]
+fn test_cli_dry_run_chain() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.arg("--dry-run")
+        .arg("gh facebook/react; ig reels")
+        .assert()
+        .success()
+        .stdout("https://github.com/facebook/react\nhttps://www.instagram.com/reels/\n");
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev", feature = "social"))]
+fn test_cli_resolve() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.arg("resolve")
+        .arg("gh facebook/react; ig reels")
+        .assert()
+        .success()
+        .stdout("https://github.com/facebook/react\nhttps://www.instagram.com/reels/\n");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["resolve", "--format", "json", "gh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""layer":"builtin""#))
+        .stdout(predicate::str::contains(r#""urls":["https://github.com"]"#));
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev", feature = "social"))]
+fn test_cli_resolve_reads_stdin() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.arg("resolve")
+        .write_stdin("gh\n\ngh facebook/\nig reels\n")
+        .assert()
+        .failure()
+        .stdout("https://github.com\nhttps://www.instagram.com/reels/\n")
+        .stderr(predicate::str::contains("expected <owner>/<repo>"));
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_open() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "open", "gh", "facebook/react"])
+        .assert()
+        .success()
+        .stdout("https://github.com/facebook/react\n");
+
+    // A bare address opens the site, as the open command did before
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "open", "example.com/docs"])
+        .assert()
+        .success()
+        .stdout("https://example.com/docs\n");
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_alias_rejects_builtin_names() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["alias", "add", "gh", "ddg", "rust"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already a built-in command"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["alias", "add", "my work", "gh", "mbinns"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot contain spaces"));
+}
+
+#[test]
+#[cfg(all(feature = "cli", unix))]
+fn test_cli_config_edit_refuses_invalid_config() {
+    // An editor that leaves the file untouched changes nothing
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["config", "edit"])
+        .env_remove("VISUAL")
+        .env("EDITOR", "true")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No changes"));
+
+    // One that breaks it is refused, and quitting keeps the original
+    let editor =
+        std::env::temp_dir().join(format!("bunnylol-break-config-{}.sh", std::process::id()));
+    std::fs::write(&editor, "#!/bin/sh\necho 'aliases = \"nope\"' > \"$1\"\n").unwrap();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["config", "edit"])
+        .env_remove("VISUAL")
+        .env("EDITOR", format!("sh {}", editor.display()))
+        .write_stdin("q\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Refusing to write an invalid config",
+        ))
+        .stderr(predicate::str::contains("left unchanged"));
+    std::fs::remove_file(&editor).unwrap();
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_repl() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["repl"])
+        .write_stdin("gh facebook/react\n:nope\n:quit\ngh rust\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "https://github.com/facebook/react\n  layer: builtin via gh\n",
+        ))
+        .stdout(predicate::str::contains("Unknown command ':nope'"))
+        .stdout(predicate::str::contains("q=rust").not());
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_bench_needs_a_server() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["bench", "--address", "127.0.0.1", "--port", "1"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "No bunnylol server is answering at 127.0.0.1:1",
+        ));
+}
+
+#[test]
+#[cfg(all(feature = "server", unix))]
+fn test_cli_serve_daemon() {
+    let dir = std::env::temp_dir().join(format!("bunnylol-cli-daemon-{}", std::process::id()));
+    let pid_file = dir.join("bunnylol.pid");
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+        .to_string();
+    let serve = |log: &str| {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_DATA_HOME", &dir)
+            .args([
+                "serve",
+                "--daemon",
+                "--address",
+                "127.0.0.1",
+                "--port",
+                &port,
+            ])
+            .arg("--pid-file")
+            .arg(&pid_file)
+            .arg("--log-file")
+            .arg(dir.join(log));
+        cmd
+    };
+
+    serve("first.log")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("running in the background"));
+    let pid = std::fs::read_to_string(&pid_file).unwrap();
+
+    serve("second.log")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already running"));
+
+    // Stopping it removes the pid file
+    std::process::Command::new("kill")
+        .arg(pid.trim())
+        .status()
+        .unwrap();
+    for _ in 0..100 {
+        if !pid_file.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(!pid_file.exists());
+    assert!(
+        std::fs::read_to_string(dir.join("first.log"))
+            .unwrap()
+            .contains("Server listening on 127.0.0.1")
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_doctor() {
+    // The findings depend on the machine, but every check reports
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["doctor"])
+        .assert()
+        .code(predicate::in_iter([0, 1, 2]))
+        .stdout(predicate::str::contains("config"))
+        .stdout(predicate::str::contains("history"))
+        .stdout(predicate::str::contains("templates"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["doctor", "--format", "json"])
+        .assert()
+        .code(predicate::in_iter([0, 1, 2]))
+        .stdout(predicate::str::starts_with("{"))
+        .stdout(predicate::str::contains(r#""check": "templates""#))
+        .stdout(predicate::str::contains(r#""exit_code": "#));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_export() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["export"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("["))
+        .stdout(predicate::str::contains(r#""kind": "command""#));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["export", "--format", "md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("| Command | Category |"));
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_search() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["search", "package"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("npm, npmjs"))
+        .stdout(predicate::str::contains("pypi, pip"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["search", "--format", "json", "rust", "package"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "[\n  {\n    \"kind\": \"command\"",
+        ))
+        .stdout(predicate::str::contains(r#""crates""#))
+        .stdout(predicate::str::contains(r#""npm""#).not());
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["search", "zzzznothing"])
+        .assert()
+        .success()
+        .stdout("No commands match 'zzzznothing'\n");
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_import_dry_run() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "import", "-"])
+        .write_stdin("keyword,url\nmyimportedwiki,https://wiki.example.com/?q=%s\ngh,https://github.com/search?q=%s\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already a built-in command"))
+        .stdout(predicate::str::contains("Would add 1 keyword to"))
+        .stdout(predicate::str::contains("https://wiki.example.com/?q={}"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["--dry-run", "import", "--format", "bangs", "-"])
+        .write_stdin("not json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse bang JSON"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_history_filters() {
+    // Clearing needs a filter or --all, so a bare `clear` can't wipe everything
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["history", "clear"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--all"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["history", "list", "--since", "yesterday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --since value"));
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_completions_offer_bindings() {
+    for shell in ["bash", "zsh", "fish"] {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.args(["completions", shell])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("resolve"))
+            .stdout(predicate::str::contains("github"))
+            .stdout(predicate::str::contains("(default)").not());
+    }
+
+    // The old name still works
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["completion", "bash"]).assert().success();
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_dry_run_invalid_arguments() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.arg("--dry-run")
+        .arg("gh")
+        .arg("facebook/")
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("expected <owner>/<repo>"))
+        .stderr(predicate::str::contains("Usage:"));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_history_prune() {
+    let data_home = std::env::temp_dir().join(format!("bunnylol-cli-prune-{}", std::process::id()));
+    let history_path = data_home.join("bunnylol").join("history");
+    std::fs::create_dir_all(history_path.parent().unwrap()).unwrap();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    std::fs::write(
+        &history_path,
+        format!("1000|testuser|old\n{}|testuser|gh\n", now),
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "prune", "--days", "30"])
+        .assert()
+        .success()
+        .stdout("Pruned 1 history entries\n");
+
+    let remaining = std::fs::read_to_string(&history_path).unwrap();
+    assert_eq!(remaining, format!("{}|testuser|gh\n", now));
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_history_export() {
+    let data_home =
+        std::env::temp_dir().join(format!("bunnylol-cli-export-{}", std::process::id()));
+    let history_path = data_home.join("bunnylol").join("history");
+    std::fs::create_dir_all(history_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &history_path,
+        "1000|testuser|old\n1706659200|testuser|gh a, b\n",
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "export", "--since", "2024-01-01"])
+        .assert()
+        .success()
+        .stdout("timestamp,user,command\n1706659200,testuser,\"gh a, b\"\n");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "export", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""command": "old""#));
+
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_history_purge() {
+    let data_home = std::env::temp_dir().join(format!("bunnylol-cli-purge-{}", std::process::id()));
+    let history_path = data_home.join("bunnylol").join("history");
+    std::fs::create_dir_all(history_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &history_path,
+        "1000|10.0.0.1|gh\n1001|alice|yt\n1002|10.0.0.1|ig\n",
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "purge"])
+        .assert()
+        .failure();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "purge", "--user", "10.0.0.1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("removed 2 entries"));
+    let remaining = std::fs::read_to_string(&history_path).unwrap();
+    assert_eq!(remaining, "1001|alice|yt\n");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "purge", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("removed 1 entries"));
+
+    let audit =
+        std::fs::read_to_string(data_home.join("bunnylol").join("history-audit.log")).unwrap();
+    assert!(audit.contains("|cli|user:10.0.0.1|2\n"));
+    assert!(audit.contains("|cli|all|1\n"));
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_history_unknown() {
+    let data_home =
+        std::env::temp_dir().join(format!("bunnylol-cli-unknown-{}", std::process::id()));
+
+    for args in [["zzfoo", "bar"], ["zzfoo", "baz"], ["gh", "rust"]] {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_DATA_HOME", &data_home)
+            .arg("--dry-run")
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "unknown"])
+        .assert()
+        .success()
+        .stdout("2  zzfoo\n");
+
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_stats() {
+    let data_home = std::env::temp_dir().join(format!("bunnylol-cli-stats-{}", std::process::id()));
+
+    for args in [["zzfoo", "bar"], ["gh", "rust"], ["gh", "tokio"]] {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_DATA_HOME", &data_home)
+            .arg("--dry-run")
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["stats"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Last 7 days: 3 queries"))
+        .stdout(predicate::str::contains("\n     1  zzfoo\n"));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["stats", "--days", "30", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""days": 30"#))
+        .stdout(predicate::str::contains(r#""command": "gh""#));
+
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(all(feature = "cli", feature = "dev"))]
+fn test_cli_format_json() {
+    let data_home = std::env::temp_dir().join(format!("bunnylol-cli-json-{}", std::process::id()));
+
+    for args in [["zzfoo", "bar"], ["gh", "rust"], ["gh", "tokio"]] {
+        let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+        cmd.env("XDG_DATA_HOME", &data_home)
+            .arg("--dry-run")
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    // Newest first, like the text listing
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "list", "--limit", "2", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("[\n  {\n    \"timestamp\": "))
+        .stdout(predicate::str::contains(r#""command": "gh tokio""#))
+        .stdout(predicate::str::contains("zzfoo").not());
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "top", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""command": "gh tokio""#))
+        .stdout(predicate::str::contains(r#""count": 1"#))
+        .stdout(predicate::str::contains(r#""last_used": "#));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["history", "unknown", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""command": "zzfoo""#));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.args(["alias", "list", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("["));
+
+    std::fs::remove_dir_all(&data_home).unwrap();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_migrate() {
+    let data_home =
+        std::env::temp_dir().join(format!("bunnylol-cli-migrate-{}", std::process::id()));
+    let history_path = data_home.join("bunnylol").join("history");
+    std::fs::create_dir_all(history_path.parent().unwrap()).unwrap();
+    std::fs::write(&history_path, "1000|testuser|gh\n").unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .arg("migrate")
+        .assert()
+        .success()
+        .stdout("Upgraded history (file) from schema v0 to v1\n");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .arg("migrate")
+        .assert()
+        .success()
+        .stdout("History (file) is already at schema v1\n");
+
+    // History from a newer bunnylol is never touched
+    std::fs::write(data_home.join("bunnylol").join("history-version"), "99\n").unwrap();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .arg("migrate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("schema v99"));
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("bunnylol");
+    cmd.env("XDG_DATA_HOME", &data_home)
+        .args(["--dry-run", "gh"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&history_path).unwrap(),
+        "1000|testuser|gh\n"
+    );
+
+    std::fs::remove_dir_all(&data_home).unwrap();
+}